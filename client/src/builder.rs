@@ -25,7 +25,11 @@ pub struct ClientBuilder {
     resolver: ResolverService,
     pool_capacity: usize,
     timeout_config: TimeoutConfig,
+    body_limit: Option<usize>,
     local_addr: Option<SocketAddr>,
+    happy_eyeballs: bool,
+    #[cfg(feature = "http1")]
+    h1_pipelining: bool,
     max_http_version: Version,
     service: HttpService,
 }
@@ -43,7 +47,11 @@ impl ClientBuilder {
             resolver: base_resolver(),
             pool_capacity: 2,
             timeout_config: TimeoutConfig::new(),
+            body_limit: None,
             local_addr: None,
+            happy_eyeballs: false,
+            #[cfg(feature = "http1")]
+            h1_pipelining: false,
             max_http_version: max_http_version(),
             service: base_service(),
         }
@@ -142,6 +150,7 @@ impl ClientBuilder {
     }
 
     /// Use custom DNS resolver for domain look up. custom resolver must impl [Service] trait.
+    /// See [resolver](crate::resolver) module for built in resolver types.
     ///
     /// # Example
     /// ```rust
@@ -264,6 +273,18 @@ impl ClientBuilder {
         self
     }
 
+    /// Set default size limit in bytes for collecting response body with [Response::body], [Response::string]
+    /// and `Response::json`.
+    ///
+    /// Response body exceeding the limit would produce [Error::BodyTooLarge] error. See
+    /// [Response::body_limited] for detail.
+    ///
+    /// Default to no limit where response body is collected with [Response::limit] behavior.
+    pub fn set_body_limit(mut self, limit: usize) -> Self {
+        self.body_limit = Some(limit);
+        self
+    }
+
     /// Set [TimeoutConfig] for client.
     pub fn set_timeout(mut self, timeout_config: TimeoutConfig) -> Self {
         self.timeout_config = timeout_config;
@@ -278,6 +299,43 @@ impl ClientBuilder {
         self
     }
 
+    /// Enable happy eyeballs (RFC 8305) for establishing tcp connection when dns resolver produces
+    /// multiple addresses.
+    ///
+    /// Addresses are interleaved by IPv6 and IPv4 address family and connection attempts are raced
+    /// with a 250 milliseconds delay between each other. The first established connection is used.
+    /// Addresses are resolved by dns resolver as a whole before connecting and resolving of A and
+    /// AAAA records are not raced by client.
+    ///
+    /// By default happy eyeballs is disabled and addresses are connected one after another in order.
+    pub fn happy_eyeballs(mut self, enable: bool) -> Self {
+        self.happy_eyeballs = enable;
+        self
+    }
+
+    /// Enable http/1 request pipelining for [Client::pipeline].
+    ///
+    /// When enabled multiple requests to the same remote host are written to one pooled connection
+    /// at once and their responses are read in order. This saves round trips for bulk clients that
+    /// can not use http/2.
+    ///
+    /// # Risks
+    /// - head-of-line blocking: a slow response delays all responses after it.
+    /// - many servers and proxies handle pipelined requests poorly. They may close the connection after
+    ///   the first response or answer requests out of order.
+    ///
+    /// Client detects the first case: when a remote host closes connection in the middle of a pipeline
+    /// it's marked as not supporting pipelining and the unanswered requests (and all following ones
+    /// to that host) are sent one by one instead. The mark expires after 10 minutes and at most 256
+    /// hosts are marked at the same time.
+    ///
+    /// By default pipelining is disabled and [Client::pipeline] sends requests one by one.
+    #[cfg(feature = "http1")]
+    pub fn h1_pipelining(mut self, enable: bool) -> Self {
+        self.h1_pipelining = enable;
+        self
+    }
+
     /// Set capacity of the connection pool for re-useable connection.
     ///
     /// # Note
@@ -430,8 +488,14 @@ impl ClientBuilder {
             connector: self.connector,
             resolver: self.resolver,
             timeout_config: self.timeout_config,
+            body_limit: self.body_limit,
             max_http_version: self.max_http_version,
             local_addr: self.local_addr,
+            happy_eyeballs: self.happy_eyeballs,
+            #[cfg(feature = "http1")]
+            h1_pipelining: self.h1_pipelining,
+            #[cfg(feature = "http1")]
+            pipeline_unsupported: crate::pipeline::PipelineUnsupported::new(),
             date_service: DateTimeService::new(),
             service: self.service,
             #[cfg(feature = "http3")]
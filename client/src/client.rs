@@ -1,7 +1,13 @@
-use core::{net::SocketAddr, pin::Pin};
+use core::{
+    future::{poll_fn, Future},
+    net::SocketAddr,
+    pin::Pin,
+    task::Poll,
+    time::Duration,
+};
 
 use futures_core::stream::Stream;
-use tokio::time::{Instant, Sleep};
+use tokio::time::{sleep, Instant, Sleep};
 use xitca_io::net::{TcpSocket, TcpStream};
 
 use crate::{
@@ -19,6 +25,7 @@ use crate::{
     resolver::ResolverService,
     service::HttpService,
     timeout::{Timeout, TimeoutConfig},
+    timing::TimingRecorder,
     tls::connector::Connector,
     uri::Uri,
 };
@@ -33,8 +40,16 @@ pub struct Client {
     pub(crate) connector: Connector,
     pub(crate) resolver: ResolverService,
     pub(crate) timeout_config: TimeoutConfig,
+    // only read when http protocol feature is enabled.
+    #[allow(dead_code)]
+    pub(crate) body_limit: Option<usize>,
     pub(crate) max_http_version: Version,
     pub(crate) local_addr: Option<SocketAddr>,
+    pub(crate) happy_eyeballs: bool,
+    #[cfg(feature = "http1")]
+    pub(crate) h1_pipelining: bool,
+    #[cfg(feature = "http1")]
+    pub(crate) pipeline_unsupported: crate::pipeline::PipelineUnsupported,
     pub(crate) date_service: DateTimeService,
     pub(crate) service: HttpService,
     #[cfg(feature = "http3")]
@@ -274,6 +289,8 @@ impl Client {
                     .await
                     .map_err(|_| TimeoutError::TlsHandshake)??;
 
+                connect.record(TimingRecorder::tls);
+
                 Ok((conn, version))
             }
             Uri::Unix(_) => self
@@ -288,12 +305,16 @@ impl Client {
         connect: &mut Connect<'_>,
         timer: &mut Pin<Box<Sleep>>,
     ) -> Result<ConnectionExclusive, Error> {
+        connect.record(TimingRecorder::mark);
+
         self.resolver
             .call(connect)
             .timeout(timer.as_mut())
             .await
             .map_err(|_| TimeoutError::Resolve)??;
 
+        connect.record(TimingRecorder::dns);
+
         timer
             .as_mut()
             .reset(Instant::now() + self.timeout_config.connect_timeout);
@@ -304,6 +325,8 @@ impl Client {
             .await
             .map_err(|_| TimeoutError::Connect)??;
 
+        connect.record(TimingRecorder::connect);
+
         // TODO: make nodelay configurable?
         let _ = stream.set_nodelay(true);
 
@@ -311,6 +334,10 @@ impl Client {
     }
 
     async fn make_tcp_inner(&self, connect: &Connect<'_>) -> Result<TcpStream, Error> {
+        if self.happy_eyeballs && connect.addrs().len() > 1 {
+            return self.make_tcp_happy_eyeballs(connect).await;
+        }
+
         let mut iter = connect.addrs();
 
         let mut addr = iter.next().ok_or_else(|| ResolveError::new(connect.hostname()))?;
@@ -328,6 +355,59 @@ impl Client {
         }
     }
 
+    // connect with happy eyeballs algorithm (RFC 8305). addresses are interleaved by address family
+    // and connection attempts are started one after another with a fixed delay. an attempt failing
+    // early starts the next one immediately. the first established connection is used and the
+    // others are dropped.
+    async fn make_tcp_happy_eyeballs(&self, connect: &Connect<'_>) -> Result<TcpStream, Error> {
+        // recommended connection attempt delay of RFC 8305.
+        const ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+        let mut addrs = interleave_addrs(connect.addrs()).into_iter();
+
+        let mut attempts = Vec::new();
+        let mut err = None;
+
+        let addr = addrs.next().ok_or_else(|| ResolveError::new(connect.hostname()))?;
+        attempts.push(Box::pin(self.maybe_connect_with_local_addr(addr)));
+
+        let mut delay = Box::pin(sleep(ATTEMPT_DELAY));
+
+        poll_fn(|cx| loop {
+            let mut failed = false;
+
+            let mut i = 0;
+            while i < attempts.len() {
+                match attempts[i].as_mut().poll(cx) {
+                    Poll::Ready(Ok(stream)) => return Poll::Ready(Ok(stream)),
+                    Poll::Ready(Err(e)) => {
+                        drop(attempts.swap_remove(i));
+                        err = Some(e);
+                        failed = true;
+                    }
+                    Poll::Pending => i += 1,
+                }
+            }
+
+            if failed || delay.as_mut().poll(cx).is_ready() {
+                if let Some(addr) = addrs.next() {
+                    attempts.push(Box::pin(self.maybe_connect_with_local_addr(addr)));
+                    delay.as_mut().reset(Instant::now() + ATTEMPT_DELAY);
+                    continue;
+                }
+            }
+
+            return match err.take() {
+                Some(e) if attempts.is_empty() => Poll::Ready(Err(e)),
+                e => {
+                    err = e;
+                    Poll::Pending
+                }
+            };
+        })
+        .await
+    }
+
     async fn maybe_connect_with_local_addr(&self, addr: SocketAddr) -> Result<TcpStream, Error> {
         match self.local_addr {
             Some(local_addr) => {
@@ -361,6 +441,8 @@ impl Client {
 
         #[cfg(unix)]
         {
+            _connect.record(TimingRecorder::mark);
+
             let path = format!(
                 "/{}{}",
                 _connect.uri.authority().unwrap().as_str(),
@@ -372,6 +454,8 @@ impl Client {
                 .await
                 .map_err(|_| TimeoutError::Connect)??;
 
+            _connect.record(TimingRecorder::connect);
+
             Ok(Box::new(stream))
         }
 
@@ -382,12 +466,50 @@ impl Client {
     }
 }
 
-#[cfg(feature = "compress")]
-#[cfg(feature = "openssl")]
+// interleave addresses by address family starting with the family of the first address.
+// RFC 8305 section 4.
+fn interleave_addrs(mut addrs: impl Iterator<Item = SocketAddr>) -> Vec<SocketAddr> {
+    let Some(first) = addrs.next() else {
+        return Vec::new();
+    };
+
+    let (mut preferred, mut other): (Vec<_>, Vec<_>) = addrs.partition(|addr| addr.is_ipv6() == first.is_ipv6());
+    preferred.insert(0, first);
+
+    let mut res = Vec::with_capacity(preferred.len() + other.len());
+    let mut preferred = preferred.drain(..);
+    let mut other = other.drain(..);
+
+    loop {
+        match (preferred.next(), other.next()) {
+            (None, None) => return res,
+            (a, b) => res.extend(a.into_iter().chain(b)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
     use super::*;
 
+    #[test]
+    fn interleave() {
+        let v4 = |n| SocketAddr::from((IpAddr::from(Ipv4Addr::new(127, 0, 0, n)), 80));
+        let v6 = |n| SocketAddr::from((IpAddr::from(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, n)), 80));
+
+        let addrs = interleave_addrs([v6(1), v6(2), v6(3), v4(1), v4(2)].into_iter());
+        assert_eq!(addrs, [v6(1), v4(1), v6(2), v4(2), v6(3)]);
+
+        let addrs = interleave_addrs([v4(1), v4(2), v6(1)].into_iter());
+        assert_eq!(addrs, [v4(1), v6(1), v4(2)]);
+
+        assert!(interleave_addrs(core::iter::empty()).is_empty());
+    }
+
+    #[cfg(feature = "compress")]
+    #[cfg(feature = "openssl")]
     #[tokio::test]
     async fn connect_google() {
         let res = Client::builder()
@@ -1,8 +1,8 @@
-use core::{fmt, iter, net::SocketAddr};
+use core::{fmt, iter, net::SocketAddr, time::Duration};
 
 use std::collections::vec_deque::{self, VecDeque};
 
-use crate::uri::Uri;
+use crate::{timing::TimingRecorder, uri::Uri};
 
 pub trait Address {
     /// Get hostname part.
@@ -80,6 +80,8 @@ pub struct Connect<'a> {
     pub(crate) uri: Uri<'a>,
     pub(crate) port: u16,
     pub(crate) addr: Addrs,
+    pub(crate) ttl: Option<Duration>,
+    pub(crate) timing: Option<TimingRecorder>,
 }
 
 impl<'a> Connect<'a> {
@@ -91,6 +93,8 @@ impl<'a> Connect<'a> {
             uri,
             port: port.unwrap_or(0),
             addr: Addrs::None,
+            ttl: None,
+            timing: None,
         }
     }
 
@@ -107,6 +111,18 @@ impl<'a> Connect<'a> {
         };
     }
 
+    /// Set time to live of resolved addresses. Used by [CachedResolver] for expiring cached addresses.
+    ///
+    /// [CachedResolver]: crate::resolver::CachedResolver
+    pub fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = Some(ttl);
+    }
+
+    /// Get time to live of resolved addresses.
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
     /// Get hostname.
     pub fn hostname(&self) -> &str {
         self.uri.hostname()
@@ -125,6 +141,13 @@ impl<'a> Connect<'a> {
             Addrs::Multi(ref addrs) => AddrsIter::Multi(addrs.iter()),
         }
     }
+
+    // record stage of connecting when request is sent by RequestBuilder::timed.
+    pub(crate) fn record(&self, func: fn(&TimingRecorder)) {
+        if let Some(ref timing) = self.timing {
+            func(timing)
+        }
+    }
 }
 
 impl fmt::Display for Connect<'_> {
@@ -4,7 +4,7 @@ use core::{convert::Infallible, fmt, str};
 
 use std::{error, io};
 
-use super::http::{uri, StatusCode};
+use super::http::{uri, Method, StatusCode};
 
 #[derive(Debug)]
 #[non_exhaustive]
@@ -23,6 +23,9 @@ pub enum Error {
     #[cfg(any(feature = "rustls", feature = "rustls-ring-crypto"))]
     Rustls(_rustls::RustlsError),
     Parse(ParseError),
+    /// response body exceeds size limit. carrying the limit in bytes.
+    /// see [Response::body_limited](crate::Response::body_limited) for detail.
+    BodyTooLarge(usize),
 }
 
 impl fmt::Display for Error {
@@ -285,6 +288,34 @@ impl From<ErrorResponse> for Error {
     }
 }
 
+/// error type for requests rejected by [Client::pipeline].
+///
+/// [Client::pipeline]: crate::Client::pipeline
+#[derive(Debug)]
+pub enum PipelineError {
+    /// request method is not idempotent and it's not safe to be pipelined.
+    NonIdempotent(Method),
+    /// requests are targeting different remote hosts.
+    MixedAuthority,
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonIdempotent(method) => write!(f, "{method} method is not idempotent and can not be pipelined"),
+            Self::MixedAuthority => f.write_str("pipelined requests must target the same remote host"),
+        }
+    }
+}
+
+impl error::Error for PipelineError {}
+
+impl From<PipelineError> for Error {
+    fn from(e: PipelineError) -> Self {
+        Self::Std(Box::new(e))
+    }
+}
+
 #[derive(Debug)]
 pub enum FeatureError {
     Http1NotEnabled,
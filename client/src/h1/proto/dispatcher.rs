@@ -3,7 +3,11 @@ use core::{future::poll_fn, pin::Pin};
 use std::io;
 
 use futures_core::stream::Stream;
-use xitca_http::{body::BodySize, bytes::Buf, h1::proto::codec::TransferCoding};
+use xitca_http::{
+    body::{BodySize, Once},
+    bytes::Buf,
+    h1::proto::codec::{ChunkResult, TransferCoding},
+};
 use xitca_io::io::{AsyncIo, Interest};
 
 use crate::{
@@ -31,26 +35,7 @@ where
 {
     let mut buf = BytesMut::new();
 
-    if !req.headers().contains_key(HOST) {
-        if let Some(host) = req.uri().host() {
-            buf.reserve(host.len() + 5);
-            buf.extend_from_slice(host.as_bytes());
-
-            if let Some(port) = req.uri().port() {
-                let port = port.as_str();
-                match port {
-                    "80" | "443" => {}
-                    _ => {
-                        buf.extend_from_slice(b":");
-                        buf.extend_from_slice(port.as_bytes());
-                    }
-                }
-            }
-
-            let val = HeaderValue::from_maybe_shared(buf.split().freeze()).unwrap();
-            req.headers_mut().insert(HOST, val);
-        }
-    }
+    set_host_header(req, &mut buf);
 
     let mut is_expect = req.headers().contains_key(EXPECT);
 
@@ -136,6 +121,176 @@ where
     }
 }
 
+/// outcome of [pipeline]. responses are in the same order of requests.
+pub(crate) struct Pipelined {
+    pub(crate) responses: Vec<Response<Bytes>>,
+    // connection can not be used for following requests.
+    pub(crate) is_close: bool,
+    // error happened when sending requests or reading responses. responses received before the error
+    // are still valid and the error belongs to the first unanswered request.
+    pub(crate) error: Option<crate::error::Error>,
+}
+
+/// send all requests at once and read their responses in order. response bodies are collected in
+/// full as pipelined responses share the same connection and must be read one after another.
+///
+/// when server closes the connection before all responses are received the remaining requests are
+/// left unanswered and it's up to caller to decide what to do with them.
+///
+/// response body exceeding body_limit stops the pipeline with [Error::BodyTooLarge] and connection is
+/// closed afterwards.
+///
+/// [Error::BodyTooLarge]: crate::error::Error::BodyTooLarge
+pub(crate) async fn pipeline<S>(
+    stream: &mut S,
+    date: DateTimeHandle<'_>,
+    reqs: &mut [Request<Bytes>],
+    body_limit: Option<usize>,
+) -> Pipelined
+where
+    S: AsyncIo + Unpin,
+{
+    let mut pipelined = Pipelined {
+        responses: Vec::with_capacity(reqs.len()),
+        is_close: false,
+        error: None,
+    };
+
+    let mut buf = BytesMut::new();
+    let mut host_buf = BytesMut::new();
+
+    for req in reqs.iter_mut() {
+        set_host_header(req, &mut host_buf);
+
+        // expect header would stall the whole pipeline and it's not used.
+        req.headers_mut().remove(EXPECT);
+
+        // encode_head would mutate headers so encode from a copy of request and keep the original
+        // intact in case caller has to resend it.
+        let mut head = Request::new(Once::new(req.body().clone()));
+        *head.method_mut() = req.method().clone();
+        *head.uri_mut() = req.uri().clone();
+        *head.version_mut() = req.version();
+        *head.headers_mut() = req.headers().clone();
+
+        let mut ctx = Context::<128>::new(&date);
+
+        let mut encoder = match ctx.encode_head(&mut buf, &mut head) {
+            Ok(encoder) => encoder,
+            Err(e) => {
+                pipelined.is_close = true;
+                pipelined.error = Some(Error::from(e).into());
+                return pipelined;
+            }
+        };
+
+        if !encoder.is_eof() {
+            encoder.encode(req.body().clone(), &mut buf);
+            encoder.encode_eof(&mut buf);
+        }
+    }
+
+    if let Err(e) = async {
+        write_all_buf(stream, &mut buf).await?;
+        flush(stream).await
+    }
+    .await
+    {
+        pipelined.is_close = true;
+        pipelined.error = Some(e.into());
+        return pipelined;
+    }
+
+    for req in reqs.iter() {
+        let mut ctx = Context::<128>::new(&date);
+        let is_head = *req.method() == Method::HEAD;
+        match read_full_response(stream, &mut buf, &mut ctx, is_head, body_limit).await {
+            Ok(res) => {
+                pipelined.responses.push(res);
+                if ctx.is_connection_closed() {
+                    pipelined.is_close = true;
+                    break;
+                }
+            }
+            Err(e) => {
+                pipelined.is_close = true;
+                pipelined.error = Some(e);
+                break;
+            }
+        }
+    }
+
+    pipelined
+}
+
+async fn read_full_response<S>(
+    stream: &mut S,
+    buf: &mut BytesMut,
+    ctx: &mut Context<'_, '_, 128>,
+    is_head: bool,
+    body_limit: Option<usize>,
+) -> Result<Response<Bytes>, crate::error::Error>
+where
+    S: AsyncIo,
+{
+    let (res, mut decoder) = loop {
+        match ctx.decode_head(buf).map_err(Error::from)? {
+            // informational responses are not final and skipped.
+            Some((res, _)) if res.status().is_informational() => {}
+            Some(res) => break res,
+            None => read_buf(stream, buf).await?,
+        }
+    };
+
+    if is_head {
+        decoder = TransferCoding::eof();
+    }
+
+    let mut body = BytesMut::new();
+
+    loop {
+        match decoder.decode(buf) {
+            ChunkResult::Ok(bytes) => {
+                if let Some(max) = body_limit {
+                    if body.len() + bytes.len() > max {
+                        return Err(crate::error::Error::BodyTooLarge(max));
+                    }
+                }
+                body.extend_from_slice(&bytes)
+            }
+            ChunkResult::InsufficientData => read_buf(stream, buf).await?,
+            ChunkResult::Err(e) => return Err(Error::from(e).into()),
+            ChunkResult::Corrupted => return Err(io::Error::from(io::ErrorKind::InvalidData).into()),
+            _ => break,
+        }
+    }
+
+    Ok(res.map(|_| body.freeze()))
+}
+
+fn set_host_header<B>(req: &mut Request<B>, buf: &mut BytesMut) {
+    if !req.headers().contains_key(HOST) {
+        if let Some(host) = req.uri().host() {
+            buf.reserve(host.len() + 5);
+            buf.extend_from_slice(host.as_bytes());
+
+            if let Some(port) = req.uri().port() {
+                let port = port.as_str();
+                match port {
+                    "80" | "443" => {}
+                    _ => {
+                        buf.extend_from_slice(b":");
+                        buf.extend_from_slice(port.as_bytes());
+                    }
+                }
+            }
+
+            let val = HeaderValue::from_maybe_shared(buf.split().freeze()).unwrap();
+            req.headers_mut().insert(HOST, val);
+        }
+    }
+}
+
 async fn send_body<S, B, E>(
     stream: &mut S,
     mut encoder: TransferCoding,
@@ -209,6 +364,14 @@ async fn try_read_response<S>(
     buf: &mut BytesMut,
     ctx: &mut Context<'_, '_, 128>,
 ) -> Result<Option<(Response<()>, TransferCoding)>, Error>
+where
+    S: AsyncIo,
+{
+    read_buf(stream, buf).await?;
+    ctx.decode_head(buf).map_err(Into::into)
+}
+
+async fn read_buf<S>(stream: &mut S, buf: &mut BytesMut) -> Result<(), Error>
 where
     S: AsyncIo,
 {
@@ -218,7 +381,7 @@ where
                 return if n == 0 {
                     Err(Error::from(io::Error::from(io::ErrorKind::UnexpectedEof)))
                 } else {
-                    ctx.decode_head(buf).map_err(Into::into)
+                    Ok(())
                 }
             }
             Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
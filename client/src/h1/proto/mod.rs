@@ -3,4 +3,4 @@ mod decode;
 mod dispatcher;
 mod encode;
 
-pub(crate) use dispatcher::send;
+pub(crate) use dispatcher::{pipeline, send};
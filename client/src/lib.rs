@@ -37,10 +37,10 @@ mod connection;
 mod date;
 mod pool;
 mod request;
-mod resolver;
 mod response;
 mod service;
 mod timeout;
+mod timing;
 mod tls;
 mod tunnel;
 mod uri;
@@ -48,6 +48,9 @@ mod uri;
 #[cfg(feature = "http1")]
 mod h1;
 
+#[cfg(feature = "http1")]
+mod pipeline;
+
 #[cfg(feature = "http2")]
 mod h2;
 
@@ -60,6 +63,7 @@ pub mod ws;
 pub mod error;
 pub mod http_tunnel;
 pub mod middleware;
+pub mod resolver;
 
 pub use self::builder::ClientBuilder;
 pub use self::client::Client;
@@ -68,6 +72,7 @@ pub use self::request::RequestBuilder;
 pub use self::response::Response;
 pub use self::service::{HttpService, Service, ServiceRequest};
 pub use self::timeout::TimeoutConfig;
+pub use self::timing::Timings;
 pub use self::tls::{connector::Connector, TlsStream};
 
 // re-export http crate.
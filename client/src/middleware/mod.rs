@@ -1,6 +1,7 @@
 //! middleware offer extended functionality to http client.
 
 mod redirect;
+mod trace;
 
 #[cfg(feature = "compress")]
 mod decompress;
@@ -9,3 +10,4 @@ mod decompress;
 pub use decompress::Decompress;
 
 pub use redirect::FollowRedirect;
+pub use trace::{Trace, TraceContext};
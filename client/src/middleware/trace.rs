@@ -0,0 +1,79 @@
+use tracing::{field::Empty, info_span, Instrument};
+
+use crate::{
+    error::Error,
+    response::Response,
+    service::{Service, ServiceRequest},
+};
+
+pub use xitca_http::util::trace_context::TraceContext;
+
+/// middleware propagating [W3C trace context](https://www.w3.org/TR/trace-context/) to outgoing http
+/// request and creating a [tracing] span for it.
+///
+/// Span of outgoing request is a child of current trace context ([`TraceContext::current`]) and a new
+/// trace is started when there is none. Context of the span is injected to request as `traceparent`
+/// and `tracestate` headers. The span records `trace_id`, `span_id`, `parent_span_id`,
+/// `http.request.method`, `url.full` and `http.response.status_code` fields.
+///
+/// Current trace context is set by server side middleware like xitca-web's `middleware::trace::Trace`
+/// or manually with [`TraceContext::scope`].
+pub struct Trace<S> {
+    service: S,
+}
+
+impl<S> Trace<S> {
+    /// construct a new trace middleware with given http service type.
+    pub const fn new(service: S) -> Self {
+        Self { service }
+    }
+}
+
+impl<'r, 'c, S> Service<ServiceRequest<'r, 'c>> for Trace<S>
+where
+    S: for<'r2, 'c2> Service<ServiceRequest<'r2, 'c2>, Response = Response, Error = Error> + Send + Sync,
+{
+    type Response = Response;
+    type Error = Error;
+
+    async fn call(&self, req: ServiceRequest<'r, 'c>) -> Result<Self::Response, Self::Error> {
+        let parent = TraceContext::current();
+        let trace = match parent {
+            Some(ref parent) => parent.child(),
+            None => TraceContext::new_root(),
+        };
+
+        trace.inject(req.req.headers_mut());
+
+        let span = info_span!(
+            "client_request",
+            otel.kind = "client",
+            trace_id = %format_args!("{:032x}", trace.trace_id()),
+            span_id = %format_args!("{:016x}", trace.span_id()),
+            parent_span_id = Empty,
+            http.request.method = %req.req.method(),
+            url.full = %req.req.uri(),
+            http.response.status_code = Empty,
+        );
+
+        if let Some(parent) = parent {
+            span.record("parent_span_id", format_args!("{:016x}", parent.span_id()));
+        }
+
+        let res = self.service.call(req).instrument(span.clone()).await?;
+        span.record("http.response.status_code", res.status().as_u16());
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Client;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn build_trace_mw() {
+        let _ = Client::builder().middleware(Trace::new).finish();
+    }
+}
@@ -0,0 +1,381 @@
+//! http/1 request pipelining.
+
+use core::{
+    future::{poll_fn, Future},
+    pin::Pin,
+    task::Poll,
+    time::Duration,
+};
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use futures_core::stream::Stream;
+use tokio::sync::oneshot;
+use tracing::debug;
+
+use crate::{
+    body::{BoxBody, Once, ResponseBody},
+    bytes::{Bytes, BytesMut},
+    client::Client,
+    connect::Connect,
+    connection::ConnectionKey,
+    error::{Error, PipelineError, TimeoutError},
+    http::{self, Method, Request, Version},
+    pool::exclusive,
+    response::Response,
+    timeout::Timeout,
+    uri::Uri,
+};
+
+impl Client {
+    /// Send multiple requests to the same remote host and collect their responses in order.
+    ///
+    /// When pipelining is enabled with [ClientBuilder::h1_pipelining] all requests are written to one
+    /// pooled http/1 connection at once and responses are read one after another. Otherwise requests
+    /// are sent one by one. Response bodies are collected in full as pipelined responses share the same
+    /// connection.
+    ///
+    /// Every request is passed through middlewares added by [ClientBuilder::middleware] the same way as
+    /// [RequestBuilder::send]. Requests reaching the inner http service at the same time are pipelined
+    /// together.
+    ///
+    /// Only idempotent methods (GET, HEAD, PUT, DELETE, OPTIONS and TRACE) are allowed as requests may
+    /// be resent when remote host fails in the middle of a pipeline. Requests are always sent with
+    /// http/1.1 regardless of [ClientBuilder::set_max_http_version]. `Expect` header is ignored.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use xitca_client::{bytes::Bytes, http, Client};
+    ///
+    /// # async fn pipeline() -> Result<(), xitca_client::error::Error> {
+    /// let client = Client::builder().h1_pipelining(true).finish();
+    ///
+    /// let reqs = (0..8).map(|i| {
+    ///     http::Request::get(format!("http://localhost:8080/item/{i}"))
+    ///         .body(Bytes::new())
+    ///         .unwrap()
+    /// });
+    ///
+    /// for res in client.pipeline(reqs).await? {
+    ///     println!("{}: {:?}", res.status(), res.body());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [ClientBuilder::h1_pipelining]: crate::ClientBuilder::h1_pipelining
+    /// [ClientBuilder::middleware]: crate::ClientBuilder::middleware
+    /// [ClientBuilder::set_max_http_version]: crate::ClientBuilder::set_max_http_version
+    /// [RequestBuilder::send]: crate::RequestBuilder::send
+    pub async fn pipeline<I>(&self, reqs: I) -> Result<Vec<http::Response<Bytes>>, Error>
+    where
+        I: IntoIterator<Item = http::Request<Bytes>>,
+    {
+        let mut reqs = reqs.into_iter().collect::<Vec<_>>();
+
+        let Some(first) = reqs.first() else {
+            return Ok(Vec::new());
+        };
+
+        let uri = first.uri().clone();
+
+        for req in reqs.iter_mut() {
+            if !is_idempotent(req.method()) {
+                return Err(PipelineError::NonIdempotent(req.method().clone()).into());
+            }
+
+            if req.uri().scheme() != uri.scheme() || req.uri().authority() != uri.authority() {
+                return Err(PipelineError::MixedAuthority.into());
+            }
+
+            *req.version_mut() = Version::HTTP_11;
+        }
+
+        let key = ConnectionKey::from(&Uri::try_parse(&uri)?);
+
+        let batch = (self.h1_pipelining && reqs.len() > 1 && !self.pipeline_unsupported.contains(&key))
+            .then(PipelineBatch::default);
+
+        let mut futs = reqs
+            .into_iter()
+            .map(|mut req| {
+                if let Some(ref batch) = batch {
+                    req.extensions_mut().insert(batch.clone());
+                }
+                Box::pin(self.send_collect(req))
+            })
+            .collect::<Vec<_>>();
+
+        let mut outputs = futs.iter().map(|_| None).collect::<Vec<_>>();
+
+        loop {
+            // drive all requests through middlewares. once every request still in progress is waiting
+            // in the batch the batch is sent as one pipeline.
+            let queued = poll_fn(|cx| {
+                let mut pending = 0;
+
+                for (fut, output) in futs.iter_mut().zip(outputs.iter_mut()) {
+                    if output.is_none() {
+                        match fut.as_mut().poll(cx) {
+                            Poll::Ready(res) => *output = Some(res),
+                            Poll::Pending => pending += 1,
+                        }
+                    }
+                }
+
+                if pending == 0 {
+                    return Poll::Ready(None);
+                }
+
+                match batch {
+                    Some(ref batch) => match batch.take_if_len(pending) {
+                        Some(queued) => Poll::Ready(Some(queued)),
+                        None => Poll::Pending,
+                    },
+                    None => Poll::Pending,
+                }
+            })
+            .await;
+
+            match queued {
+                Some(queued) => self.send_pipeline(&key, queued).await,
+                None => break,
+            }
+        }
+
+        outputs
+            .into_iter()
+            .map(|res| res.expect("output must be set"))
+            .collect()
+    }
+
+    async fn send_collect(&self, req: http::Request<Bytes>) -> Result<http::Response<Bytes>, Error> {
+        let mut res = self.request(req.map(Once::new)).send().await?;
+
+        let mut collected = http::Response::new(Bytes::new());
+        *collected.status_mut() = res.status();
+        *collected.version_mut() = res.version();
+        *collected.headers_mut() = core::mem::take(res.headers_mut());
+        *collected.extensions_mut() = core::mem::take(res.extensions_mut());
+        *collected.body_mut() = Bytes::from(res.body().await?);
+
+        Ok(collected)
+    }
+
+    // write queued requests to one connection and answer them with responses in order. requests not
+    // queued together with others or not answered by remote host are resent one by one.
+    async fn send_pipeline(&self, key: &ConnectionKey, queued: Vec<Queued>) {
+        // middlewares can rewrite request uri. only pipeline requests still going to the same host and
+        // dropping the rest would make them sent one by one.
+        let (mut reqs, txs) = queued
+            .into_iter()
+            .filter(|q| Uri::try_parse(q.req.uri()).is_ok_and(|uri| ConnectionKey::from(&uri) == *key))
+            .map(|q| (q.req, q.tx))
+            .unzip::<_, _, Vec<_>, Vec<_>>();
+
+        if reqs.len() < 2 {
+            return;
+        }
+
+        let Ok(uri) = Uri::try_parse(reqs[0].uri()) else {
+            return;
+        };
+
+        let mut connect = Connect::new(uri);
+
+        let mut conn = loop {
+            match self.exclusive_pool.acquire(&connect.uri).await {
+                exclusive::AcquireOutput::Conn(conn) => break conn,
+                exclusive::AcquireOutput::Spawner(spawner) => {
+                    let mut timer = Box::pin(tokio::time::sleep(self.timeout_config.resolve_timeout));
+                    let Ok((conn, _)) = self.make_exclusive(&mut connect, &mut timer, Version::HTTP_11).await else {
+                        return;
+                    };
+                    spawner.spawned(conn);
+                }
+            }
+        };
+
+        let date = self.date_service.handle();
+        let mut timer = Box::pin(tokio::time::sleep(self.timeout_config.request_timeout));
+
+        let Ok(pipelined) = crate::h1::proto::pipeline(&mut *conn, date, &mut reqs, self.body_limit)
+            .timeout(timer.as_mut())
+            .await
+        else {
+            conn.destroy_on_drop();
+            for tx in txs {
+                let _ = tx.send(Answer::Timeout);
+            }
+            return;
+        };
+
+        if pipelined.is_close {
+            conn.destroy_on_drop();
+        }
+
+        let len = pipelined.responses.len();
+
+        // dropped senders of unanswered requests notify them to be resent.
+        let mut txs = txs.into_iter();
+
+        for (res, tx) in pipelined.responses.into_iter().zip(txs.by_ref()) {
+            let _ = tx.send(Answer::Response(res));
+        }
+
+        match pipelined.error {
+            // response is too large for client and resending the request would not help.
+            Some(e @ Error::BodyTooLarge(_)) => {
+                if let Some(tx) = txs.next() {
+                    let _ = tx.send(Answer::Error(e));
+                }
+            }
+            // remote host answered part of the pipeline and gave up on the rest. it's not safe to
+            // pipeline to it anymore.
+            // when nothing is answered the connection can simply be a stale one from pool and the
+            // following one by one sending would surface the error if there is a real one.
+            e => {
+                if let Some(e) = e {
+                    debug!("pipeline is stopped after {len} responses: {e}");
+                }
+                if len > 0 && len < reqs.len() {
+                    self.pipeline_unsupported.insert(key.clone());
+                }
+            }
+        }
+    }
+}
+
+// batch of requests sent by Client::pipeline. shared with http service through request extensions.
+#[derive(Clone, Default)]
+pub(crate) struct PipelineBatch(Arc<Mutex<Vec<Queued>>>);
+
+struct Queued {
+    req: Request<Bytes>,
+    tx: oneshot::Sender<Answer>,
+}
+
+enum Answer {
+    Response(http::Response<Bytes>),
+    Timeout,
+    Error(Error),
+}
+
+impl PipelineBatch {
+    fn take_if_len(&self, len: usize) -> Option<Vec<Queued>> {
+        let mut queued = self.0.lock().unwrap();
+        (queued.len() == len).then(|| core::mem::take(&mut *queued))
+    }
+
+    // queue request in batch and wait for it's response. Ok(None) is returned when request is not
+    // answered through pipeline and it must be sent by caller.
+    pub(crate) async fn join(self, req: &mut Request<BoxBody>, client: &Client) -> Result<Option<Response>, Error> {
+        let mut body = core::mem::take(req.body_mut());
+        let mut bytes = BytesMut::new();
+        while let Some(chunk) = poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await {
+            bytes.extend_from_slice(&chunk?);
+        }
+        let bytes = bytes.freeze();
+
+        let mut queued = Request::new(bytes.clone());
+        *queued.method_mut() = req.method().clone();
+        *queued.uri_mut() = req.uri().clone();
+        *queued.version_mut() = Version::HTTP_11;
+        *queued.headers_mut() = req.headers().clone();
+
+        let (tx, rx) = oneshot::channel();
+        self.0.lock().unwrap().push(Queued { req: queued, tx });
+
+        match rx.await {
+            Ok(Answer::Response(res)) => {
+                let res = res.map(|body| ResponseBody::Unknown(Box::pin(BoxBody::new(Once::new(body)))));
+                let timer = Box::pin(tokio::time::sleep(client.timeout_config.response_timeout));
+                let timeout = client.timeout_config.response_timeout;
+                Ok(Some(Response::new(res, timer, timeout, client.body_limit)))
+            }
+            Ok(Answer::Timeout) => Err(TimeoutError::Request.into()),
+            Ok(Answer::Error(e)) => Err(e),
+            Err(_) => {
+                *req.body_mut() = BoxBody::new(Once::new(bytes));
+                Ok(None)
+            }
+        }
+    }
+}
+
+// remote hosts failed to handle pipeline. entries expire so a host upgraded or fixed later can be
+// pipelined again and the amount of tracked hosts is bounded.
+pub(crate) struct PipelineUnsupported(Mutex<HashMap<ConnectionKey, Instant>>);
+
+impl PipelineUnsupported {
+    const EXPIRE: Duration = Duration::from_secs(600);
+    const CAPACITY: usize = 256;
+
+    pub(crate) fn new() -> Self {
+        Self(Mutex::new(HashMap::new()))
+    }
+
+    fn contains(&self, key: &ConnectionKey) -> bool {
+        let mut map = self.0.lock().unwrap();
+        match map.get(key) {
+            Some(at) if at.elapsed() < Self::EXPIRE => true,
+            Some(_) => {
+                map.remove(key);
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn insert(&self, key: ConnectionKey) {
+        let mut map = self.0.lock().unwrap();
+
+        if map.len() >= Self::CAPACITY && !map.contains_key(&key) {
+            map.retain(|_, at| at.elapsed() < Self::EXPIRE);
+
+            if map.len() >= Self::CAPACITY {
+                let oldest = map.iter().min_by_key(|(_, at)| **at).map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    map.remove(&oldest);
+                }
+            }
+        }
+
+        map.insert(key, Instant::now());
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS | Method::TRACE
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unsupported_bounded() {
+        let unsupported = PipelineUnsupported::new();
+
+        let key = |i: usize| ConnectionKey::from(&Uri::try_parse(&format!("http://host{i}").parse().unwrap()).unwrap());
+
+        for i in 0..=PipelineUnsupported::CAPACITY {
+            unsupported.insert(key(i));
+        }
+
+        assert_eq!(unsupported.0.lock().unwrap().len(), PipelineUnsupported::CAPACITY);
+        assert!(unsupported.contains(&key(PipelineUnsupported::CAPACITY)));
+
+        if let Some(expired) = Instant::now().checked_sub(PipelineUnsupported::EXPIRE) {
+            unsupported.0.lock().unwrap().insert(key(1), expired);
+            assert!(!unsupported.contains(&key(1)));
+        }
+    }
+}
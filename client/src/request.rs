@@ -14,6 +14,7 @@ use crate::{
     },
     response::Response,
     service::ServiceRequest,
+    timing::{TimingRecorder, Timings},
 };
 
 /// builder type for [http::Request] with extended functionalities.
@@ -91,6 +92,33 @@ impl RequestBuilder<'_, marker::Http> {
     pub async fn send(self) -> Result<Response, Error> {
         self._send().await
     }
+
+    /// Finish request builder and send it to server. Timing breakdown of the request is returned along with
+    /// the response.
+    ///
+    /// Timings are recorded until response head is received. When request is sent multiple times (redirect
+    /// for example) stage timings are from the last attempt while [Timings::total] covers all of them.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_client::Client;
+    /// # async fn timed(client: &Client) -> Result<(), xitca_client::error::Error> {
+    /// let (res, timings) = client.get("http://localhost:8080").timed().await?;
+    /// // a connection reused from pool does not have connect timing.
+    /// if let Some(connect) = timings.connect() {
+    ///     println!("connected in {connect:?}");
+    /// }
+    /// println!("first byte in {:?}. total {:?}", timings.ttfb(), timings.total());
+    /// # drop(res);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn timed(mut self) -> Result<(Response, Timings), Error> {
+        let recorder = TimingRecorder::new();
+        self.req.extensions_mut().insert(recorder.clone());
+        let res = self._send().await?;
+        Ok((res, recorder.timings()))
+    }
 }
 
 impl<'a, M> RequestBuilder<'a, M> {
@@ -1,4 +1,10 @@
-use std::net::ToSocketAddrs;
+//! dns resolver types.
+
+use core::{net::SocketAddr, time::Duration};
+
+use std::{collections::HashMap, net::ToSocketAddrs, sync::Mutex};
+
+use tokio::time::Instant;
 
 use crate::{
     connect::Connect,
@@ -10,26 +16,157 @@ pub type ResolverService =
     Box<dyn for<'r, 'c> ServiceDyn<&'r mut Connect<'c>, Response = (), Error = Error> + Send + Sync>;
 
 pub(crate) fn base_resolver() -> ResolverService {
-    struct DefaultResolver;
+    Box::new(DefaultResolver)
+}
+
+/// default dns resolver of client. domain is resolved with [ToSocketAddrs] in blocking thread pool.
+pub struct DefaultResolver;
+
+impl<'r, 'c> Service<&'r mut Connect<'c>> for DefaultResolver {
+    type Response = ();
+    type Error = Error;
+
+    async fn call(&self, req: &'r mut Connect<'c>) -> Result<Self::Response, Self::Error> {
+        let host = req.hostname();
+        let port = req.port();
+
+        let host = host.to_string();
+        let addrs = tokio::task::spawn_blocking(move || (host, port).to_socket_addrs())
+            .await
+            .unwrap()?;
+
+        req.set_addrs(addrs);
+
+        Ok(())
+    }
+}
+
+/// resolver caching resolved addresses of it's inner resolver.
+///
+/// Cached addresses are expired after the TTL set by inner resolver through [Connect::set_ttl].
+/// When inner resolver does not provide TTL (like [DefaultResolver]) the fixed TTL given to
+/// [CachedResolver::new] is used.
+///
+/// # Examples
+/// ```rust
+/// use std::time::Duration;
+///
+/// use xitca_client::{
+///     resolver::{CachedResolver, DefaultResolver},
+///     ClientBuilder,
+/// };
+///
+/// # fn resolve() {
+/// // cache addresses resolved by default resolver for 60 seconds.
+/// let client = ClientBuilder::new()
+///     .resolver(CachedResolver::new(DefaultResolver, Duration::from_secs(60)))
+///     .finish();
+/// # }
+/// ```
+pub struct CachedResolver<R> {
+    resolver: R,
+    ttl: Duration,
+    cache: Mutex<HashMap<(String, u16), CacheEntry>>,
+}
+
+struct CacheEntry {
+    addrs: Vec<SocketAddr>,
+    expire: Instant,
+}
+
+impl<R> CachedResolver<R> {
+    /// construct a new caching resolver with inner resolver and fixed TTL used when inner resolver
+    /// does not provide one.
+    pub fn new(resolver: R, ttl: Duration) -> Self {
+        Self {
+            resolver,
+            ttl,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+}
 
-    impl<'r, 'c> Service<&'r mut Connect<'c>> for DefaultResolver {
+impl<'r, 'c, R> Service<&'r mut Connect<'c>> for CachedResolver<R>
+where
+    R: for<'r2, 'c2> Service<&'r2 mut Connect<'c2>, Response = (), Error = Error> + Sync,
+{
+    type Response = ();
+    type Error = Error;
+
+    async fn call(&self, req: &'r mut Connect<'c>) -> Result<Self::Response, Self::Error> {
+        let key = (req.hostname().to_owned(), req.port());
+
+        {
+            let cache = self.cache.lock().unwrap();
+            if let Some(entry) = cache.get(&key) {
+                if entry.expire > Instant::now() {
+                    req.set_addrs(entry.addrs.iter().copied());
+                    return Ok(());
+                }
+            }
+        }
+
+        self.resolver.call(req).await?;
+
+        let addrs = req.addrs().collect::<Vec<_>>();
+
+        // empty result is not cached so it can be retried by following request.
+        if !addrs.is_empty() {
+            let now = Instant::now();
+            let expire = now + req.ttl().unwrap_or(self.ttl);
+
+            let mut cache = self.cache.lock().unwrap();
+            cache.retain(|_, entry| entry.expire > now);
+            cache.insert(key, CacheEntry { addrs, expire });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{
+        net::{IpAddr, Ipv4Addr},
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use crate::uri::Uri;
+
+    use super::*;
+
+    struct Counting(AtomicUsize);
+
+    impl<'r, 'c> Service<&'r mut Connect<'c>> for Counting {
         type Response = ();
         type Error = Error;
 
         async fn call(&self, req: &'r mut Connect<'c>) -> Result<Self::Response, Self::Error> {
-            let host = req.hostname();
-            let port = req.port();
+            self.0.fetch_add(1, Ordering::Relaxed);
+            req.set_addrs([SocketAddr::from((IpAddr::from(Ipv4Addr::LOCALHOST), req.port()))]);
+            Ok(())
+        }
+    }
 
-            let host = host.to_string();
-            let addrs = tokio::task::spawn_blocking(move || (host, port).to_socket_addrs())
-                .await
-                .unwrap()?;
+    #[tokio::test]
+    async fn cache_ttl() {
+        let resolver = CachedResolver::new(Counting(AtomicUsize::new(0)), Duration::from_millis(100));
 
-            req.set_addrs(addrs);
+        let uri = crate::http::Uri::from_static("http://example.com");
 
-            Ok(())
+        for _ in 0..3 {
+            let mut connect = Connect::new(Uri::try_parse(&uri).unwrap());
+            Service::call(&resolver, &mut connect).await.unwrap();
+            assert_eq!(connect.addrs().next().unwrap().port(), 80);
         }
-    }
 
-    Box::new(DefaultResolver)
+        assert_eq!(resolver.resolver.0.load(Ordering::Relaxed), 1);
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        let mut connect = Connect::new(Uri::try_parse(&uri).unwrap());
+        Service::call(&resolver, &mut connect).await.unwrap();
+
+        assert_eq!(resolver.resolver.0.load(Ordering::Relaxed), 2);
+    }
 }
@@ -23,6 +23,7 @@ pub struct Response<const PAYLOAD_LIMIT: usize = DEFAULT_PAYLOAD_LIMIT> {
     pub(crate) res: http::Response<ResponseBody>,
     timer: Pin<Box<Sleep>>,
     timeout: Duration,
+    body_limit: Option<usize>,
 }
 
 impl<const PAYLOAD_LIMIT: usize> Deref for Response<PAYLOAD_LIMIT> {
@@ -47,8 +48,18 @@ impl<const PAYLOAD_LIMIT: usize> fmt::Debug for Response<PAYLOAD_LIMIT> {
 
 impl<const PAYLOAD_LIMIT: usize> Response<PAYLOAD_LIMIT> {
     #[cfg(any(feature = "http1", feature = "http2", feature = "http3"))]
-    pub(crate) fn new(res: http::Response<ResponseBody>, timer: Pin<Box<Sleep>>, timeout: Duration) -> Self {
-        Self { res, timer, timeout }
+    pub(crate) fn new(
+        res: http::Response<ResponseBody>,
+        timer: Pin<Box<Sleep>>,
+        timeout: Duration,
+        body_limit: Option<usize>,
+    ) -> Self {
+        Self {
+            res,
+            timer,
+            timeout,
+            body_limit,
+        }
     }
 
     /// Get ownership the inner response type.
@@ -69,12 +80,17 @@ impl<const PAYLOAD_LIMIT: usize> Response<PAYLOAD_LIMIT> {
     /// Set payload size limit in bytes. Payload size beyond limit would be discarded.
     ///
     /// Default to 8 Mb.
+    ///
+    /// # Note:
+    /// This limit is not in effect when client has a default body limit set by
+    /// [ClientBuilder::set_body_limit](crate::ClientBuilder::set_body_limit).
     #[inline]
     pub fn limit<const PAYLOAD_LIMIT_2: usize>(self) -> Response<PAYLOAD_LIMIT_2> {
         Response {
             res: self.res,
             timer: self.timer,
             timeout: self.timeout,
+            body_limit: self.body_limit,
         }
     }
 
@@ -88,19 +104,52 @@ impl<const PAYLOAD_LIMIT: usize> Response<PAYLOAD_LIMIT> {
             res: self.res,
             timer: self.timer,
             timeout: dur,
+            body_limit: self.body_limit,
         }
     }
 
     /// Collect response body as String. Response is consumed.
     #[inline]
     pub async fn string(self) -> Result<String, Error> {
-        self.collect().await
+        let limit = self.body_limit;
+        self.collect(limit).await
     }
 
     /// Collect response body as `Vec<u8>`. Response is consumed.
     #[inline]
     pub async fn body(self) -> Result<Vec<u8>, Error> {
-        self.collect().await
+        let limit = self.body_limit;
+        self.collect(limit).await
+    }
+
+    /// Collect response body as `Vec<u8>` with size limit in bytes. Response is consumed.
+    ///
+    /// Unlike [Response::limit] collecting is stopped and [Error::BodyTooLarge] error is returned as soon
+    /// as the collected body exceeds the limit. Connection is closed afterwards.
+    ///
+    /// The limit applies to body after it's decoded. When response is decompressed (with `middleware::Decompress`
+    /// for example) the decompressed size is checked against the limit, not the size of compressed body received
+    /// from the wire.
+    ///
+    /// For collecting body without limit the body stream can be consumed directly with
+    /// [Response::into_inner].
+    ///
+    /// # Examples:
+    /// ```rust
+    /// # use xitca_client::{error::Error, Client};
+    /// # async fn collect(client: &Client) -> Result<(), Error> {
+    /// let res = client.get("https://www.rust-lang.org/").send().await?;
+    /// match res.body_limited(1024 * 1024).await {
+    ///     Ok(body) => assert!(body.len() <= 1024 * 1024),
+    ///     Err(Error::BodyTooLarge(limit)) => assert_eq!(limit, 1024 * 1024),
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub async fn body_limited(self, max: usize) -> Result<Vec<u8>, Error> {
+        self.collect(Some(max)).await
     }
 
     #[cfg(feature = "json")]
@@ -113,11 +162,13 @@ impl<const PAYLOAD_LIMIT: usize> Response<PAYLOAD_LIMIT> {
     {
         use xitca_http::bytes::Buf;
 
-        let bytes = self.collect::<BytesMut>().await?;
+        let limit = self.body_limit;
+        let bytes = self.collect::<BytesMut>(limit).await?;
         Ok(serde_json::from_slice(bytes.chunk())?)
     }
 
-    async fn collect<B>(self) -> Result<B, Error>
+    // collect body with limit. when limit is absent PAYLOAD_LIMIT is used and body beyond it is discarded.
+    async fn collect<B>(self, max: Option<usize>) -> Result<B, Error>
     where
         B: Collectable,
     {
@@ -151,9 +202,16 @@ impl<const PAYLOAD_LIMIT: usize> Response<PAYLOAD_LIMIT> {
                         }
                     };
 
+                    if let Some(max) = max {
+                        if b.len() + buf.len() > max {
+                            body.destroy_on_drop();
+                            return Err(Error::BodyTooLarge(max));
+                        }
+                    }
+
                     b.try_extend_from_slice(&buf)?;
 
-                    if buf.len() > limit {
+                    if max.is_none() && buf.len() > limit {
                         debug!("PAYLOAD_LIMIT reached and only part of the response body is collected.");
                         body.destroy_on_drop();
                         break;
@@ -185,6 +243,8 @@ impl<const PAYLOAD_LIMIT: usize> Response<PAYLOAD_LIMIT> {
 trait Collectable {
     fn with_capacity(cap: usize) -> Self;
 
+    fn len(&self) -> usize;
+
     fn try_extend_from_slice(&mut self, slice: &[u8]) -> Result<(), Error>;
 }
 
@@ -194,6 +254,11 @@ impl Collectable for BytesMut {
         Self::with_capacity(cap)
     }
 
+    #[inline]
+    fn len(&self) -> usize {
+        Self::len(self)
+    }
+
     #[inline]
     fn try_extend_from_slice(&mut self, slice: &[u8]) -> Result<(), Error> {
         self.extend_from_slice(slice);
@@ -207,6 +272,11 @@ impl Collectable for Vec<u8> {
         Self::with_capacity(cap)
     }
 
+    #[inline]
+    fn len(&self) -> usize {
+        Self::len(self)
+    }
+
     #[inline]
     fn try_extend_from_slice(&mut self, slice: &[u8]) -> Result<(), Error> {
         self.extend_from_slice(slice);
@@ -220,6 +290,11 @@ impl Collectable for String {
         Self::with_capacity(cap)
     }
 
+    #[inline]
+    fn len(&self) -> usize {
+        Self::len(self)
+    }
+
     fn try_extend_from_slice(&mut self, slice: &[u8]) -> Result<(), Error> {
         let str = std::str::from_utf8(slice)?;
         self.push_str(str);
@@ -8,6 +8,7 @@ use crate::{
     http::{Request, Version},
     pool::{exclusive, shared},
     response::Response,
+    timing::TimingRecorder,
     uri::Uri,
 };
 
@@ -87,6 +88,15 @@ pub(crate) fn base_service() -> HttpService {
 
             let ServiceRequest { req, client, timeout } = req;
 
+            // request sent by Client::pipeline. it's removed from extensions so retrying middlewares
+            // would not queue it again.
+            #[cfg(feature = "http1")]
+            if let Some(batch) = req.extensions_mut().remove::<crate::pipeline::PipelineBatch>() {
+                if let Some(res) = batch.join(req, client).await? {
+                    return Ok(res);
+                }
+            }
+
             let uri = Uri::try_parse(req.uri())?;
 
             // temporary version to record possible version downgrade/upgrade happens when making connections.
@@ -94,7 +104,18 @@ pub(crate) fn base_service() -> HttpService {
             #[allow(unused_mut)]
             let mut version = req.version();
 
+            // timing recorder inserted by RequestBuilder::timed. connect holds a copy for recording connecting
+            // stages and the rest are recorded with the local one as connect is borrowing request.
+            let timing = req.extensions().get::<TimingRecorder>().cloned();
+            let record = |func: fn(&TimingRecorder)| {
+                if let Some(ref timing) = timing {
+                    func(timing)
+                }
+            };
+            record(TimingRecorder::reset);
+
             let mut connect = Connect::new(uri);
+            connect.timing = timing.clone();
 
             let _date = client.date_service.handle();
 
@@ -104,6 +125,7 @@ pub(crate) fn base_service() -> HttpService {
                         shared::AcquireOutput::Conn(mut _conn) => {
                             let mut _timer = Box::pin(tokio::time::sleep(timeout));
                             *req.version_mut() = version;
+                            record(TimingRecorder::mark);
                             #[allow(unreachable_code)]
                             return match _conn.conn {
                                 #[cfg(feature = "http2")]
@@ -113,8 +135,9 @@ pub(crate) fn base_service() -> HttpService {
                                         .await
                                     {
                                         Ok(Ok(res)) => {
+                                            record(TimingRecorder::response_head);
                                             let timeout = client.timeout_config.response_timeout;
-                                            Ok(Response::new(res, _timer, timeout))
+                                            Ok(Response::new(res, _timer, timeout, client.body_limit))
                                         }
                                         Ok(Err(e)) => {
                                             _conn.destroy_on_drop();
@@ -133,8 +156,9 @@ pub(crate) fn base_service() -> HttpService {
                                         .await
                                         .map_err(|_| TimeoutError::Request)??;
 
+                                    record(TimingRecorder::response_head);
                                     let timeout = client.timeout_config.response_timeout;
-                                    Ok(Response::new(res, _timer, timeout))
+                                    Ok(Response::new(res, _timer, timeout, client.body_limit))
                                 }
                             };
                         }
@@ -144,10 +168,12 @@ pub(crate) fn base_service() -> HttpService {
                                 {
                                     let mut timer = Box::pin(tokio::time::sleep(client.timeout_config.resolve_timeout));
 
+                                    connect.record(TimingRecorder::mark);
                                     Service::call(&client.resolver, &mut connect)
                                         .timeout(timer.as_mut())
                                         .await
                                         .map_err(|_| TimeoutError::Resolve)??;
+                                    connect.record(TimingRecorder::dns);
                                     timer
                                         .as_mut()
                                         .reset(tokio::time::Instant::now() + client.timeout_config.connect_timeout);
@@ -160,6 +186,7 @@ pub(crate) fn base_service() -> HttpService {
                                     .timeout(timer.as_mut())
                                     .await
                                     {
+                                        connect.record(TimingRecorder::connect);
                                         _spawner.spawned(conn.into());
                                     } else {
                                         #[cfg(feature = "http2")]
@@ -219,19 +246,21 @@ pub(crate) fn base_service() -> HttpService {
                             #[cfg(feature = "http1")]
                             {
                                 let mut timer = Box::pin(tokio::time::sleep(timeout));
+                                record(TimingRecorder::mark);
                                 let res = crate::h1::proto::send(&mut *_conn, _date, req)
                                     .timeout(timer.as_mut())
                                     .await;
 
                                 return match res {
                                     Ok(Ok((res, buf, decoder, is_close))) => {
+                                        record(TimingRecorder::response_head);
                                         if is_close {
                                             _conn.destroy_on_drop();
                                         }
                                         let body = crate::h1::body::ResponseBody::new(_conn, buf, decoder);
                                         let res = res.map(|_| crate::body::ResponseBody::H1(body));
                                         let timeout = client.timeout_config.response_timeout;
-                                        Ok(Response::new(res, timer, timeout))
+                                        Ok(Response::new(res, timer, timeout, client.body_limit))
                                     }
                                     Ok(Err(e)) => {
                                         _conn.destroy_on_drop();
@@ -0,0 +1,130 @@
+use core::time::Duration;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+/// Timing breakdown of a request. Produced by [RequestBuilder::timed].
+///
+/// Stages not happened for the request are [None]. For example a request sent through a pooled connection
+/// does not have dns, connect and tls stage.
+///
+/// [RequestBuilder::timed]: crate::RequestBuilder::timed
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Timings {
+    dns: Option<Duration>,
+    connect: Option<Duration>,
+    tls: Option<Duration>,
+    ttfb: Duration,
+    total: Duration,
+}
+
+impl Timings {
+    /// Duration of dns look up.
+    #[inline]
+    pub fn dns(&self) -> Option<Duration> {
+        self.dns
+    }
+
+    /// Duration of establishing connection. For http/3 it includes quic handshake.
+    #[inline]
+    pub fn connect(&self) -> Option<Duration> {
+        self.connect
+    }
+
+    /// Duration of tls handshake.
+    #[inline]
+    pub fn tls(&self) -> Option<Duration> {
+        self.tls
+    }
+
+    /// Duration from request starting to be sent to response head received. (time to first byte)
+    #[inline]
+    pub fn ttfb(&self) -> Duration {
+        self.ttfb
+    }
+
+    /// Duration from request being sent by [RequestBuilder::timed] to response head received. Collecting
+    /// response body is not included.
+    ///
+    /// [RequestBuilder::timed]: crate::RequestBuilder::timed
+    #[inline]
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+}
+
+// recorder shared between RequestBuilder and http service through request extensions.
+#[derive(Clone, Debug)]
+pub(crate) struct TimingRecorder(Arc<Mutex<Recording>>);
+
+#[derive(Debug)]
+struct Recording {
+    start: Instant,
+    last: Instant,
+    timings: Timings,
+}
+
+impl Recording {
+    fn lap(&mut self) -> Duration {
+        let now = Instant::now();
+        let dur = now - self.last;
+        self.last = now;
+        dur
+    }
+}
+
+impl TimingRecorder {
+    pub(crate) fn new() -> Self {
+        let now = Instant::now();
+        Self(Arc::new(Mutex::new(Recording {
+            start: now,
+            last: now,
+            timings: Timings::default(),
+        })))
+    }
+
+    pub(crate) fn timings(&self) -> Timings {
+        self.with(|rec| rec.timings)
+    }
+
+    // start recording of a new attempt. request can be sent multiple times. (redirect for example)
+    // timings of last attempt are kept while total duration is accumulated.
+    pub(crate) fn reset(&self) {
+        self.with(|rec| rec.timings = Timings::default())
+    }
+
+    // mark the start of a stage.
+    pub(crate) fn mark(&self) {
+        self.with(|rec| rec.last = Instant::now())
+    }
+
+    pub(crate) fn dns(&self) {
+        self.with(|rec| rec.timings.dns = Some(rec.lap()))
+    }
+
+    pub(crate) fn connect(&self) {
+        self.with(|rec| rec.timings.connect = Some(rec.lap()))
+    }
+
+    pub(crate) fn tls(&self) {
+        self.with(|rec| rec.timings.tls = Some(rec.lap()))
+    }
+
+    // only used when http protocol feature is enabled.
+    #[allow(dead_code)]
+    pub(crate) fn response_head(&self) {
+        self.with(|rec| {
+            rec.timings.ttfb = rec.lap();
+            rec.timings.total = rec.start.elapsed();
+        })
+    }
+
+    fn with<F, O>(&self, func: F) -> O
+    where
+        F: FnOnce(&mut Recording) -> O,
+    {
+        func(&mut self.0.lock().unwrap())
+    }
+}
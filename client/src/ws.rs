@@ -1,19 +1,22 @@
 //! websocket request/response handling.
 
-pub use http_ws::Message;
+pub use http_ws::{Item, Message, OpCode};
 
 use core::{
-    pin::Pin,
+    future::poll_fn,
+    pin::{pin, Pin},
     task::{ready, Context, Poll},
 };
 
+use std::io;
+
 use futures_core::stream::Stream;
 use futures_sink::Sink;
-use http_ws::{Codec, RequestStream, WsError};
+use http_ws::{Codec, ProtocolError, RequestStream, WsError};
 
 use super::{
     body::ResponseBody,
-    bytes::{Buf, BytesMut},
+    bytes::{Buf, Bytes, BytesMut},
     connection::ConnectionExclusive,
     error::{Error, ErrorResponse},
     http::{StatusCode, Version},
@@ -85,6 +88,97 @@ impl WebSocket {
         *recv_codec = recv_codec.set_max_size(size);
         self
     }
+
+    /// Send a message fragmented into frames with payload of each chunk yielded by given stream.
+    ///
+    /// See [WebSocketSink::send_fragmented] for detail.
+    pub async fn send_fragmented<S, E>(&mut self, opcode: OpCode, chunks: S) -> Result<(), Error>
+    where
+        S: Stream<Item = Result<Bytes, E>>,
+        Error: From<E>,
+    {
+        send_fragmented(self, opcode, chunks).await
+    }
+}
+
+impl WebSocketSink<'_> {
+    /// Send a message fragmented into frames with payload of each chunk yielded by given stream.
+    /// Opcode must be either [OpCode::Text] or [OpCode::Binary].
+    ///
+    /// Each chunk is sent as a frame and flushed before the next one is polled so a large message
+    /// can be streamed without buffering it in full. The final frame is sent with FIN bit when the
+    /// stream ends.
+    ///
+    /// Control frames ([Message::Ping], [Message::Pong] and [Message::Close]) can be sent with other
+    /// [WebSocketSink] from [WebSocket::split] while fragments are being sent and they would be
+    /// interleaved between fragments. Sending [Message::Text] or [Message::Binary] before the
+    /// fragmented message is finished is a protocol error.
+    ///
+    /// For finer control over every frame [Message::Continuation] can be sent directly.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use futures::stream;
+    /// use xitca_client::{bytes::Bytes, error::Error, ws::{OpCode, WebSocket}};
+    ///
+    /// # async fn fragment(ws: WebSocket) -> Result<(), Error> {
+    /// let (mut tx, _rx) = ws.split();
+    ///
+    /// // send a binary message in 3 frames.
+    /// let chunks = stream::iter(["9", "9", "6"].map(|c| Ok::<_, Error>(Bytes::from(c))));
+    /// tx.send_fragmented(OpCode::Binary, chunks).await
+    /// # }
+    /// ```
+    pub async fn send_fragmented<S, E>(&mut self, opcode: OpCode, chunks: S) -> Result<(), Error>
+    where
+        S: Stream<Item = Result<Bytes, E>>,
+        Error: From<E>,
+    {
+        send_fragmented(self, opcode, chunks).await
+    }
+}
+
+async fn send_fragmented<T, S, E>(sink: &mut T, opcode: OpCode, chunks: S) -> Result<(), Error>
+where
+    T: Sink<Message, Error = Error> + Unpin,
+    S: Stream<Item = Result<Bytes, E>>,
+    Error: From<E>,
+{
+    let first = match opcode {
+        OpCode::Text => Item::FirstText,
+        OpCode::Binary => Item::FirstBinary,
+        code => return Err(ProtocolError::ContinuationFragment(code).into()),
+    };
+
+    let mut chunks = pin!(chunks);
+
+    // hold one chunk back so the last one can be sent with FIN bit.
+    let mut prev = None;
+    let mut started = false;
+
+    while let Some(chunk) = poll_fn(|cx| chunks.as_mut().poll_next(cx)).await {
+        let chunk = chunk?;
+        if let Some(prev) = prev.replace(chunk) {
+            let item = if started { Item::Continue(prev) } else { first(prev) };
+            started = true;
+            send(sink, Message::Continuation(item)).await?;
+        }
+    }
+
+    if !started {
+        send(sink, Message::Continuation(first(prev.take().unwrap_or_default()))).await?;
+    }
+
+    send(sink, Message::Continuation(Item::Last(prev.unwrap_or_default()))).await
+}
+
+async fn send<T>(sink: &mut T, msg: Message) -> Result<(), Error>
+where
+    T: Sink<Message, Error = Error> + Unpin,
+{
+    poll_fn(|cx| Pin::new(&mut *sink).poll_ready(cx)).await?;
+    Pin::new(&mut *sink).start_send(msg)?;
+    poll_fn(|cx| Pin::new(&mut *sink).poll_flush(cx)).await
 }
 
 pub struct WebSocketTunnel {
@@ -184,6 +278,7 @@ impl Stream for WebSocketTunnel {
             .map_err(|e| match e {
                 WsError::Protocol(e) => Error::from(e),
                 WsError::Stream(e) => Error::Std(e),
+                WsError::Timeout => Error::Io(io::ErrorKind::TimedOut.into()),
             })
     }
 }
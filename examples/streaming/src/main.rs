@@ -107,39 +107,37 @@ async fn nightly_middleware<S, C, Res, Err>(next: &S, mut ctx: WebContext<'_, C>
 where
     S: for<'r> Service<WebContext<'r, C>, Response = Res, Error = Err>,
 {
-    let body = ctx.body_get_mut();
-
-    // take request body and move it into generator for async iterating.
-    let mut body_own = std::mem::take(body);
+    // take request body and move it into generator for async iterating. the newly generated request
+    // streaming body is assigned to context.
+    ctx.map_body(|mut body_own| {
+        let async_body = AsyncBody::from(async gen move {
+            // a counter type with drop guard
+            struct Counter(usize);
+
+            // downstream async body consumer is free to cancel body streaming at any
+            // time so print the counter on drop to coop with possible cancelation.
+            impl Drop for Counter {
+                fn drop(&mut self) {
+                    println!("request body size: {}", self.0);
+                }
+            }
 
-    let async_body = AsyncBody::from(async gen move {
-        // a counter type with drop guard
-        struct Counter(usize);
+            let mut counter = Counter(0);
 
-        // downstream async body consumer is free to cancel body streaming at any
-        // time so print the counter on drop to coop with possible cancelation.
-        impl Drop for Counter {
-            fn drop(&mut self) {
-                println!("request body size: {}", self.0);
+            // request body does not implement AsyncIterator and futures crate must be used for async yielding.
+            use futures::stream::StreamExt;
+            while let Some(res) = body_own.next().await {
+                // record every successful bytes and yield body item.
+                yield res.map(|bytes| {
+                    counter.0 += bytes.len();
+                    bytes
+                });
             }
-        }
+        });
 
-        let mut counter = Counter(0);
-
-        // request body does not implement AsyncIterator and futures crate must be used for async yielding.
-        use futures::stream::StreamExt;
-        while let Some(res) = body_own.next().await {
-            // record every successful bytes and yield body item.
-            yield res.map(|bytes| {
-                counter.0 += bytes.len();
-                bytes
-            });
-        }
+        RequestBody::from(BoxBody::new(async_body))
     });
 
-    // assign newly generate request streaming body to context.
-    *body = RequestBody::from(BoxBody::new(async_body));
-
     // call next application service.
     next.call(ctx).await
 }
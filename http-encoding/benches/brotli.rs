@@ -0,0 +1,63 @@
+//! compare compression ratio and latency of brotli presets.
+//!
+//! run with `cargo bench -p http-encoding --features br`. compression ratio of every preset is printed
+//! before it's latency is measured.
+
+use std::convert::Infallible;
+
+use bytes::Bytes;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use futures_util::{stream, StreamExt};
+use http::Response;
+use http_encoding::{encoder_with, BrotliConfig, BrotliMode, ContentEncoding};
+
+// html like text input. a typical payload compressed by compression middleware.
+fn input() -> Bytes {
+    let mut buf = String::new();
+    for i in 0..2048 {
+        buf.push_str(&format!(
+            "<li class=\"item\"><a href=\"/items/{i}\">item {i}</a><span>{}</span></li>\n",
+            i * 31 % 977
+        ));
+    }
+    Bytes::from(buf)
+}
+
+fn encode(rt: &tokio::runtime::Runtime, config: BrotliConfig, input: &Bytes) -> usize {
+    let body = stream::once(async { Ok::<_, Infallible>(input.clone()) });
+    let res = encoder_with(Response::new(body), ContentEncoding::Br, config);
+    rt.block_on(
+        res.into_body()
+            .fold(0, |len, chunk| async move { len + chunk.unwrap().len() }),
+    )
+}
+
+fn brotli(c: &mut Criterion) {
+    let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let input = input();
+
+    // max preset takes hundreds of milliseconds per iteration.
+    let mut group = c.benchmark_group("brotli");
+    group.sample_size(10);
+
+    for (name, config) in [
+        ("fast", BrotliConfig::fast()),
+        ("balanced", BrotliConfig::balanced()),
+        ("max", BrotliConfig::max()),
+        ("balanced_text", BrotliConfig::balanced().mode(BrotliMode::Text)),
+    ] {
+        let len = encode(&rt, config, &input);
+        println!(
+            "brotli {name}: {} -> {len} bytes. ratio: {:.2}",
+            input.len(),
+            input.len() as f64 / len as f64
+        );
+
+        group.bench_function(name, |b| b.iter(|| encode(&rt, black_box(config), black_box(&input))));
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, brotli);
+criterion_main!(benches);
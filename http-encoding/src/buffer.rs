@@ -0,0 +1,191 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use bytes::{Bytes, BytesMut};
+use futures_core::stream::Stream;
+use pin_project_lite::pin_project;
+use tokio::time::{sleep, Sleep};
+
+use super::coder::try_downcast_to_bytes;
+
+pin_project! {
+    /// A stream type accumulating items of input stream before passing them on. Used as input of
+    /// [Coder](crate::Coder) to reduce tiny (de)compressed frames produced from small items.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use futures_core::Stream;
+    /// # use http_encoding::{Buffered, Coder, FeaturedCode};
+    /// fn buffered<S>(body: S) -> Coder<Buffered<S>>
+    /// where
+    ///     S: Stream<Item = Result<Vec<u8>, std::io::Error>>,
+    /// {
+    ///     // accumulate 4KB of input or flush what's accumulated for 50ms.
+    ///     let body = Buffered::new(body).buffer(4096, Duration::from_millis(50));
+    ///     Coder::new(body, FeaturedCode::default())
+    /// }
+    /// ```
+    pub struct Buffered<S> {
+        #[pin]
+        body: S,
+        buf: Option<Box<Buffer>>,
+    }
+}
+
+// state of buffering mode.
+struct Buffer {
+    bytes: BytesMut,
+    threshold: usize,
+    interval: Duration,
+    timer: Option<Pin<Box<Sleep>>>,
+    eof: bool,
+}
+
+impl Buffer {
+    fn take(&mut self) -> Bytes {
+        self.timer = None;
+        self.bytes.split().freeze()
+    }
+}
+
+impl<S> Buffered<S> {
+    /// Construct a new stream that pass through items of input stream until [Buffered::buffer] is
+    /// called.
+    #[inline]
+    pub const fn new(body: S) -> Self {
+        Self { body, buf: None }
+    }
+
+    /// Enable buffering mode.
+    ///
+    /// Input is accumulated until it reaches given threshold in bytes before being passed on.
+    ///
+    /// Buffered input is also passed on when it has been held longer than given interval while
+    /// input stream is pending. This bounds latency of streaming response like SSE where input is
+    /// produced sparsely.
+    ///
+    /// Timer of buffering mode depends on tokio runtime with time driver enabled.
+    pub fn buffer(mut self, threshold: usize, interval: Duration) -> Self {
+        self.buf = Some(Box::new(Buffer {
+            bytes: BytesMut::new(),
+            threshold,
+            interval,
+            timer: None,
+            eof: false,
+        }));
+        self
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.body
+    }
+}
+
+impl<S, T, E> Stream for Buffered<S>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: AsRef<[u8]> + 'static,
+{
+    type Item = Result<Bytes, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        let Some(buf) = this.buf.as_deref_mut() else {
+            return this.body.poll_next(cx).map_ok(|item| {
+                try_downcast_to_bytes(item).unwrap_or_else(|item| Bytes::copy_from_slice(item.as_ref()))
+            });
+        };
+
+        while !buf.eof {
+            match this.body.as_mut().poll_next(cx) {
+                Poll::Ready(Some(res)) => {
+                    buf.bytes.extend_from_slice(res?.as_ref());
+                    if buf.bytes.len() >= buf.threshold {
+                        return Poll::Ready(Some(Ok(buf.take())));
+                    }
+                    if buf.timer.is_none() && !buf.bytes.is_empty() {
+                        buf.timer = Some(Box::pin(sleep(buf.interval)));
+                    }
+                }
+                Poll::Ready(None) => buf.eof = true,
+                Poll::Pending => {
+                    let Some(timer) = buf.timer.as_mut() else {
+                        return Poll::Pending;
+                    };
+                    ready!(timer.as_mut().poll(cx));
+                    return Poll::Ready(Some(Ok(buf.take())));
+                }
+            }
+        }
+
+        if buf.bytes.is_empty() {
+            Poll::Ready(None)
+        } else {
+            Poll::Ready(Some(Ok(buf.take())))
+        }
+    }
+
+    // buffering does not change the total size of input stream.
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.body.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_util::{stream, StreamExt};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn pass_through() {
+        let body = stream::iter(["ab", "cd"]).map(Ok::<_, ()>);
+        let res = Buffered::new(body).map(Result::unwrap).collect::<Vec<_>>().await;
+        assert_eq!(res, ["ab", "cd"]);
+    }
+
+    #[tokio::test]
+    async fn buffered() {
+        let body = stream::iter(["ab", "cd", "efgh", "ij"]).map(Ok::<_, ()>);
+        let res = Buffered::new(body)
+            .buffer(8, Duration::from_secs(60))
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(res, ["abcdefgh", "ij"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn buffered_flush_on_interval() {
+        // stream producing one small item and then stay pending for a long time.
+        let body = stream::unfold(0, |n| async move {
+            match n {
+                0 => Some((Ok::<_, ()>("data: 996\n\n"), 1)),
+                1 => {
+                    tokio::time::sleep(Duration::from_secs(3600)).await;
+                    Some((Ok("data: 251\n\n"), 2))
+                }
+                _ => None,
+            }
+        });
+
+        let mut body = core::pin::pin!(Buffered::new(body).buffer(1024, Duration::from_millis(100)));
+
+        let start = tokio::time::Instant::now();
+        let item = body.next().await.unwrap().unwrap();
+        assert_eq!(item, "data: 996\n\n");
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        let item = body.next().await.unwrap().unwrap();
+        assert_eq!(item, "data: 251\n\n");
+        assert!(body.next().await.is_none());
+    }
+}
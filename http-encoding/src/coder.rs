@@ -233,7 +233,7 @@ macro_rules! code_impl {
     };
 }
 
-fn try_downcast_to_bytes<T: 'static>(item: T) -> Result<Bytes, T> {
+pub(crate) fn try_downcast_to_bytes<T: 'static>(item: T) -> Result<Bytes, T> {
     use core::any::Any;
 
     let item = &mut Some(item);
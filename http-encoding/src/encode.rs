@@ -9,7 +9,22 @@ use super::{
 };
 
 /// Construct from headers and stream body. Use for encoding.
-pub fn encoder<S, T, E>(response: Response<S>, mut encoding: ContentEncoding) -> Response<Coder<S, FeaturedCode>>
+pub fn encoder<S, T, E>(response: Response<S>, encoding: ContentEncoding) -> Response<Coder<S, FeaturedCode>>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: AsRef<[u8]> + 'static,
+{
+    encoder_with(response, encoding, BrotliConfig::default())
+}
+
+/// Same as [encoder] but with given [BrotliConfig] for brotli encoding.
+/// The config is ignored when brotli is not the selected encoding.
+#[allow(unused_variables)]
+pub fn encoder_with<S, T, E>(
+    response: Response<S>,
+    mut encoding: ContentEncoding,
+    brotli: BrotliConfig,
+) -> Response<Coder<S, FeaturedCode>>
 where
     S: Stream<Item = Result<T, E>>,
     T: AsRef<[u8]> + 'static,
@@ -45,7 +60,7 @@ where
             #[cfg(feature = "br")]
             ContentEncoding::Br => {
                 update_header(&mut parts.headers, "br");
-                FeaturedCode::EncodeBr(super::brotli::Encoder::new(3))
+                FeaturedCode::EncodeBr(super::brotli::Encoder::new(&brotli))
             }
             _ => FeaturedCode::default(),
         }
@@ -61,3 +76,126 @@ fn update_header(headers: &mut header::HeaderMap, value: &'static str) {
     headers.remove(header::CONTENT_LENGTH);
     headers.insert(header::TRANSFER_ENCODING, header::HeaderValue::from_static("chunked"));
 }
+
+/// mode hinting brotli encoder about the type of input data.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BrotliMode {
+    /// no assumption about properties of input data.
+    #[default]
+    Generic,
+    /// utf-8 formatted text input.
+    Text,
+    /// WOFF 2.0 font input.
+    Font,
+}
+
+/// configuration of brotli encoder.
+///
+/// Default config equals to [BrotliConfig::fast]. Compression ratio and latency of presets can be compared with
+/// `cargo bench -p http-encoding --features br`.
+///
+/// # Examples
+/// ```rust
+/// use http_encoding::{BrotliConfig, BrotliMode};
+///
+/// // start from a preset and override individual parameters.
+/// let config = BrotliConfig::balanced().window(20).mode(BrotliMode::Text);
+/// assert_eq!(config.get_quality(), 6);
+/// assert_eq!(config.get_window(), 20);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BrotliConfig {
+    quality: u32,
+    window: u32,
+    mode: BrotliMode,
+}
+
+impl Default for BrotliConfig {
+    fn default() -> Self {
+        Self::fast()
+    }
+}
+
+impl BrotliConfig {
+    /// preset with low CPU cost and moderate compression ratio. suitable for dynamic response.
+    pub const fn fast() -> Self {
+        Self {
+            quality: 3,
+            window: 22,
+            mode: BrotliMode::Generic,
+        }
+    }
+
+    /// preset trading more CPU for better compression ratio than [BrotliConfig::fast].
+    pub const fn balanced() -> Self {
+        Self {
+            quality: 6,
+            window: 22,
+            mode: BrotliMode::Generic,
+        }
+    }
+
+    /// preset with highest compression ratio and CPU cost. suitable for static content that is
+    /// compressed once and served many times.
+    pub const fn max() -> Self {
+        Self {
+            quality: 11,
+            window: 24,
+            mode: BrotliMode::Generic,
+        }
+    }
+
+    /// set compression quality. higher quality means better compression ratio and more CPU cost.
+    /// value is clamped to range of 0 to 11.
+    pub const fn quality(mut self, quality: u32) -> Self {
+        self.quality = if quality > 11 { 11 } else { quality };
+        self
+    }
+
+    /// set base 2 logarithm of sliding window size (lgwin). larger window means better compression
+    /// ratio and more memory usage for both encoder and decoder.
+    /// value is clamped to range of 10 to 24.
+    pub const fn window(mut self, window: u32) -> Self {
+        self.window = if window < 10 {
+            10
+        } else if window > 24 {
+            24
+        } else {
+            window
+        };
+        self
+    }
+
+    /// set mode of encoder.
+    pub const fn mode(mut self, mode: BrotliMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub const fn get_quality(&self) -> u32 {
+        self.quality
+    }
+
+    pub const fn get_window(&self) -> u32 {
+        self.window
+    }
+
+    pub const fn get_mode(&self) -> BrotliMode {
+        self.mode
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn brotli_config_clamp() {
+        let config = BrotliConfig::max().quality(99).window(99);
+        assert_eq!(config.get_quality(), 11);
+        assert_eq!(config.get_window(), 24);
+
+        let config = BrotliConfig::fast().window(0);
+        assert_eq!(config.get_window(), 10);
+    }
+}
@@ -4,6 +4,8 @@
 
 pub mod error;
 
+#[cfg(feature = "buffer")]
+mod buffer;
 #[macro_use]
 mod coder;
 mod coding;
@@ -20,14 +22,26 @@ mod brotli {
     use brotli2::write::{BrotliDecoder, BrotliEncoder};
     use bytes::Bytes;
 
-    use super::{coder::Code, writer::BytesMutWriter};
+    use brotli2::{CompressMode, CompressParams};
+
+    use super::{coder::Code, encode::BrotliConfig, encode::BrotliMode, writer::BytesMutWriter};
 
     pub type Decoder = BrotliDecoder<BytesMutWriter>;
     pub struct Encoder(Option<BrotliEncoder<BytesMutWriter>>);
 
     impl Encoder {
-        pub(crate) fn new(level: u32) -> Self {
-            Self(Some(BrotliEncoder::new(BytesMutWriter::new(), level)))
+        pub(crate) fn new(config: &BrotliConfig) -> Self {
+            let mode = match config.get_mode() {
+                BrotliMode::Generic => CompressMode::Generic,
+                BrotliMode::Text => CompressMode::Text,
+                BrotliMode::Font => CompressMode::Font,
+            };
+            let mut params = CompressParams::new();
+            params
+                .quality(config.get_quality())
+                .lgwin(config.get_window())
+                .mode(mode);
+            Self(Some(BrotliEncoder::from_params(BytesMutWriter::new(), &params)))
         }
     }
 
@@ -114,7 +128,9 @@ mod deflate {
     code_impl!(DeflateEncoder);
 }
 
+#[cfg(feature = "buffer")]
+pub use self::buffer::Buffered;
 pub use self::coder::{Code, Coder, FeaturedCode};
 pub use self::coding::ContentEncoding;
 pub use self::decode::try_decoder;
-pub use self::encode::encoder;
+pub use self::encode::{encoder, encoder_with, BrotliConfig, BrotliMode};
@@ -0,0 +1,166 @@
+//! in memory cache of small files for skipping file system access of frequently served files.
+
+use core::time::Duration;
+
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Instant, SystemTime},
+};
+
+use bytes::{Bytes, BytesMut};
+
+use super::runtime::{ChunkRead, Meta};
+
+// cached file is served without touching file system for this duration after it's validated. after
+// that it's validated again against file's length and modified date.
+pub(super) const REVALIDATE: Duration = Duration::from_secs(1);
+
+struct Entry {
+    file: CachedFile,
+    validated: Instant,
+    // last access in ticks of cache. used for least recently used eviction.
+    used: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    map: HashMap<PathBuf, Entry>,
+    tick: u64,
+}
+
+/// file content and metadata served from memory.
+#[derive(Clone)]
+pub(super) struct CachedFile {
+    modified: SystemTime,
+    content: Bytes,
+}
+
+impl CachedFile {
+    pub(super) fn content(&self) -> &Bytes {
+        &self.content
+    }
+}
+
+impl Meta for CachedFile {
+    fn modified(&mut self) -> Option<SystemTime> {
+        Some(self.modified)
+    }
+
+    fn len(&self) -> u64 {
+        self.content.len() as u64
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct FileCache {
+    capacity: usize,
+    max_file_size: u64,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl FileCache {
+    pub(super) fn new(capacity: usize, max_file_size: u64) -> Self {
+        Self {
+            capacity,
+            max_file_size,
+            inner: Arc::new(Mutex::new(Inner::default())),
+        }
+    }
+
+    // get cached file of given path when it's validated recently.
+    pub(super) fn get(&self, path: &Path) -> Option<CachedFile> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.tick += 1;
+        let tick = inner.tick;
+        let entry = inner.map.get_mut(path)?;
+        if entry.validated.elapsed() > REVALIDATE {
+            return None;
+        }
+        entry.used = tick;
+        Some(entry.file.clone())
+    }
+
+    // validate cached file of given path against opened file. file content is read into cache when
+    // there is no valid cached one. opened file is handed back when it can not be cached.
+    pub(super) async fn load<F>(
+        &self,
+        path: PathBuf,
+        mut file: F,
+        chunk_size: usize,
+    ) -> io::Result<Result<CachedFile, F>>
+    where
+        F: ChunkRead + Meta,
+    {
+        let len = file.len();
+
+        // file without modified date can not be validated.
+        let Some(modified) = file.modified() else {
+            return Ok(Err(file));
+        };
+
+        if self.capacity == 0 || len > self.max_file_size {
+            return Ok(Err(file));
+        }
+
+        {
+            let mut inner = self.inner.lock().unwrap();
+            inner.tick += 1;
+            let tick = inner.tick;
+            if let Some(entry) = inner.map.get_mut(&path) {
+                if entry.file.modified == modified && entry.file.len() == len {
+                    entry.validated = Instant::now();
+                    entry.used = tick;
+                    return Ok(Ok(entry.file.clone()));
+                }
+            }
+        }
+
+        let mut content = BytesMut::with_capacity(len as usize);
+        let mut buf = BytesMut::with_capacity(chunk_size);
+        while (content.len() as u64) < len {
+            let Some((f, mut b, n)) = file.next(buf).await? else {
+                break;
+            };
+            // only len bytes of file are cached. ignore data appended after.
+            let n = core::cmp::min(n as u64, len - content.len() as u64) as usize;
+            content.extend_from_slice(&b[..n]);
+            b.clear();
+            file = f;
+            buf = b;
+        }
+
+        let file = CachedFile {
+            modified,
+            content: content.freeze(),
+        };
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.tick += 1;
+        let used = inner.tick;
+
+        if !inner.map.contains_key(&path) && inner.map.len() >= self.capacity {
+            if let Some(lru) = inner
+                .map
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(path, _)| path.clone())
+            {
+                inner.map.remove(&lru);
+            }
+        }
+
+        inner.map.insert(
+            path,
+            Entry {
+                file: file.clone(),
+                validated: Instant::now(),
+                used,
+            },
+        );
+
+        Ok(Ok(file))
+    }
+}
@@ -1,10 +1,10 @@
 use core::{
     future::Future,
     pin::Pin,
-    task::{ready, Context, Poll},
+    task::{Context, Poll},
 };
 
-use std::io;
+use std::{collections::VecDeque, io};
 
 use bytes::{Bytes, BytesMut};
 use futures_core::stream::Stream;
@@ -20,6 +20,9 @@ pin_project! {
         F: ChunkRead,
     {
         Empty,
+        Bytes {
+            bytes: Option<Bytes>
+        },
         Reader {
             #[pin]
             reader:  _ChunkReader<F>
@@ -35,11 +38,18 @@ where
         Self::Empty
     }
 
-    pub(super) fn reader(file: F, size: u64, chunk_size: usize) -> Self {
+    pub(super) fn bytes(bytes: Bytes) -> Self {
+        Self::Bytes { bytes: Some(bytes) }
+    }
+
+    pub(super) fn reader(file: F, size: u64, chunk_size: usize, read_ahead: usize) -> Self {
         Self::Reader {
             reader: _ChunkReader {
                 chunk_size,
                 size,
+                read_ahead,
+                buf: VecDeque::new(),
+                err: None,
                 on_flight: file.next(BytesMut::with_capacity(chunk_size)),
             },
         }
@@ -55,6 +65,7 @@ where
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         match self.project() {
             ChunkReaderProj::Empty => Poll::Ready(None),
+            ChunkReaderProj::Bytes { bytes } => Poll::Ready(bytes.take().map(Ok)),
             ChunkReaderProj::Reader { reader } => reader.poll_next(cx),
         }
     }
@@ -63,6 +74,10 @@ where
         match self {
             // see xitca_http::body::none_body_hint for reason. this is a library hack.
             Self::Empty => (usize::MAX, Some(0)),
+            Self::Bytes { ref bytes } => {
+                let size = bytes.as_ref().map_or(0, Bytes::len);
+                (size, Some(size))
+            }
             Self::Reader { ref reader } => reader.size_hint(),
         }
     }
@@ -75,7 +90,13 @@ pin_project! {
         F: ChunkRead,
     {
         chunk_size: usize,
+        // remaining bytes to be read from file.
         size: u64,
+        // max count of chunks read from file and buffered before they are yielded.
+        read_ahead: usize,
+        buf: VecDeque<Bytes>,
+        // error from read ahead. yielded after buffered chunks.
+        err: Option<io::Error>,
         #[pin]
         on_flight: F::Future
     }
@@ -90,40 +111,65 @@ where
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let mut this = self.project();
 
-        if *this.size == 0 {
-            return Poll::Ready(None);
-        }
-
-        Poll::Ready(ready!(this.on_flight.as_mut().poll(cx))?.map(|(file, mut bytes, n)| {
-            let mut chunk = bytes.split_to(n);
-
-            let n = n as u64;
-
-            if *this.size <= n {
-                if *this.size < n {
-                    // an unlikely case happen when someone append data to file while it's being
-                    // read.
-                    // drop the extra part. only self.size bytes of data were promised to client.
-                    chunk.truncate(*this.size as usize);
+        // keep reading until there are read_ahead count of chunks buffered besides the one about to
+        // be yielded. next read is started right after previous one finished so file io can make
+        // progress while caller consumes yielded chunk.
+        while *this.size > 0 && this.buf.len() <= *this.read_ahead {
+            let Poll::Ready(res) = this.on_flight.as_mut().poll(cx) else {
+                break;
+            };
+
+            match res {
+                Ok(Some((file, mut bytes, n))) => {
+                    let mut chunk = bytes.split_to(n);
+
+                    let n = n as u64;
+
+                    if *this.size <= n {
+                        if *this.size < n {
+                            // an unlikely case happen when someone append data to file while it's being
+                            // read.
+                            // drop the extra part. only self.size bytes of data were promised to client.
+                            chunk.truncate(*this.size as usize);
+                        }
+                        *this.size = 0;
+                    } else {
+                        *this.size -= n;
+
+                        // TODO: better handling additional memory alloc?
+                        // the goal should be linear growth targeting page size.
+                        bytes.reserve(*this.chunk_size);
+                        this.on_flight.set(file.next(bytes));
+                    }
+
+                    this.buf.push_back(chunk.freeze());
+                }
+                Ok(None) => *this.size = 0,
+                Err(e) => {
+                    *this.size = 0;
+                    *this.err = Some(e);
                 }
-                *this.size = 0;
-                return Ok(chunk.freeze());
             }
+        }
 
-            *this.size -= n;
+        if let Some(chunk) = this.buf.pop_front() {
+            return Poll::Ready(Some(Ok(chunk)));
+        }
 
-            // TODO: better handling additional memory alloc?
-            // the goal should be linear growth targeting page size.
-            bytes.reserve(*this.chunk_size);
-            this.on_flight.set(file.next(bytes));
+        if let Some(e) = this.err.take() {
+            return Poll::Ready(Some(Err(e)));
+        }
 
-            Ok(chunk.freeze())
-        }))
+        if *this.size == 0 {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let size = self.size as usize;
+        let size = self.size as usize + self.buf.iter().map(Bytes::len).sum::<usize>();
         (size, Some(size))
     }
 }
@@ -0,0 +1,214 @@
+//! response digest for integrity check of served file. (RFC 9530)
+
+use std::{
+    collections::HashMap,
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bytes::BytesMut;
+use http::header::{HeaderName, HeaderValue};
+use sha2::{Digest, Sha256, Sha512};
+
+use super::runtime::{AsyncFs, ChunkRead};
+
+pub(super) const REPR_DIGEST: HeaderName = HeaderName::from_static("repr-digest");
+pub(super) const CONTENT_DIGEST: HeaderName = HeaderName::from_static("content-digest");
+
+/// hash algorithm used for producing `Repr-Digest` and `Content-Digest` header.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Algorithm {
+    /// sha-256 algorithm.
+    Sha256,
+    /// sha-512 algorithm.
+    Sha512,
+}
+
+impl Algorithm {
+    fn token(&self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha-256",
+            Self::Sha512 => "sha-512",
+        }
+    }
+}
+
+enum Hasher {
+    Sha256(Sha256),
+    Sha512(Sha512),
+}
+
+impl Hasher {
+    fn new(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Sha256 => Self::Sha256(Sha256::new()),
+            Algorithm::Sha512 => Self::Sha512(Sha512::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(h) => h.update(data),
+            Self::Sha512(h) => h.update(data),
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        match self {
+            Self::Sha256(h) => h.finalize().to_vec(),
+            Self::Sha512(h) => h.finalize().to_vec(),
+        }
+    }
+}
+
+// digest header value of file. it's valid as long as file size and modified time stay the same.
+struct Cached {
+    size: u64,
+    // compared in full precision of file system instead of the second precision of http date so
+    // modification within the same second is observed.
+    modified: SystemTime,
+    value: HeaderValue,
+    // last access in ticks of cache. used for least recently used eviction.
+    used: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    map: HashMap<PathBuf, Cached>,
+    tick: u64,
+}
+
+#[derive(Clone)]
+pub(super) struct DigestConfig {
+    algorithm: Algorithm,
+    capacity: usize,
+    max_size: u64,
+    cache: Arc<Mutex<Inner>>,
+}
+
+impl DigestConfig {
+    pub(super) fn new(algorithm: Algorithm, capacity: usize, max_size: u64) -> Self {
+        Self {
+            algorithm,
+            capacity,
+            max_size,
+            cache: Arc::new(Mutex::new(Inner::default())),
+        }
+    }
+
+    // produce digest header value of file at given path. return None when file is too large.
+    pub(super) async fn header_value<FS: AsyncFs>(
+        &self,
+        fs: &FS,
+        path: PathBuf,
+        size: u64,
+        modified: Option<SystemTime>,
+        chunk_size: usize,
+    ) -> io::Result<Option<HeaderValue>> {
+        if size > self.max_size {
+            return Ok(None);
+        }
+
+        // file without modified time can not be validated against cached value.
+        if let Some(modified) = modified {
+            let mut inner = self.cache.lock().unwrap();
+            inner.tick += 1;
+            let tick = inner.tick;
+            if let Some(cached) = inner.map.get_mut(&path) {
+                if cached.size == size && cached.modified == modified {
+                    cached.used = tick;
+                    return Ok(Some(cached.value.clone()));
+                }
+            }
+        }
+
+        // file is opened separately to keep the read position of the one being served.
+        let mut file = fs.open(path.clone()).await?;
+        let mut buf = BytesMut::with_capacity(chunk_size);
+        let mut hasher = Hasher::new(self.algorithm);
+        let mut remaining = size;
+
+        while remaining > 0 {
+            let Some((f, mut b, n)) = file.next(buf).await? else {
+                break;
+            };
+            // only size bytes of file are served. ignore data appended after.
+            let n = core::cmp::min(n as u64, remaining) as usize;
+            hasher.update(&b[..n]);
+            remaining -= n as u64;
+            b.clear();
+            file = f;
+            buf = b;
+        }
+
+        // dictionary structured field with byte sequence value.
+        let value = format!("{}=:{}:", self.algorithm.token(), STANDARD.encode(hasher.finish()));
+        let value = HeaderValue::try_from(value).expect("base64 encoded digest must be valid header value");
+
+        if let Some(modified) = modified.filter(|_| self.capacity > 0) {
+            let mut inner = self.cache.lock().unwrap();
+            inner.tick += 1;
+            let used = inner.tick;
+
+            if !inner.map.contains_key(&path) && inner.map.len() >= self.capacity {
+                if let Some(lru) = inner
+                    .map
+                    .iter()
+                    .min_by_key(|(_, cached)| cached.used)
+                    .map(|(path, _)| path.clone())
+                {
+                    inner.map.remove(&lru);
+                }
+            }
+
+            inner.map.insert(
+                path,
+                Cached {
+                    size,
+                    modified,
+                    value: value.clone(),
+                    used,
+                },
+            );
+        }
+
+        Ok(Some(value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::runtime::TokioFs;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn cache() {
+        let config = DigestConfig::new(Algorithm::Sha256, 1, 1024);
+        let modified = SystemTime::now();
+
+        let value = |path: &str, modified| config.header_value(&TokioFs, PathBuf::from(path), 13, Some(modified), 64);
+
+        let digest = value("sample/test.txt", modified).await.unwrap().unwrap();
+
+        // digest is recalculated when modified time changes within the same second.
+        config.cache.lock().unwrap().map.values_mut().for_each(|cached| {
+            cached.value = HeaderValue::from_static("stale");
+        });
+        let modified2 = modified + Duration::from_nanos(1);
+        assert_eq!(value("sample/test.txt", modified2).await.unwrap().unwrap(), digest);
+        assert_eq!(value("sample/test.txt", modified2).await.unwrap().unwrap(), digest);
+
+        // least recently served digest is evicted when cache is full.
+        value("./sample/test.txt", modified).await.unwrap().unwrap();
+        let inner = config.cache.lock().unwrap();
+        assert_eq!(inner.map.len(), 1);
+        assert!(inner.map.contains_key(&PathBuf::from("./sample/test.txt")));
+    }
+}
@@ -3,7 +3,7 @@ use core::fmt;
 use std::{error, io};
 
 use http::{
-    header::{ALLOW, CONTENT_RANGE},
+    header::{ALLOW, CONTENT_RANGE, CONTENT_TYPE},
     request::Parts,
     HeaderValue, Request, Response, StatusCode,
 };
@@ -11,10 +11,12 @@ use http::{
 use super::buf::buf_write_header;
 
 /// high level error types for serving file.
-/// see [into_response_from] and [into_response] for way of converting error to [Response] type.
+/// see [into_response_from], [into_response] and [into_response_with] for way of converting error to
+/// [Response] type.
 ///
 /// [into_response_from]: ServeError::into_response_from
 /// [into_response]: ServeError::into_response
+/// [into_response_with]: ServeError::into_response_with
 #[derive(Debug)]
 pub enum ServeError {
     /// request method is not allowed. only GET/HEAD methods are allowed.
@@ -75,6 +77,37 @@ impl ServeError {
         self._into_response(Response::new(()))
     }
 
+    /// produce a response from error with custom body. given closure is called with reference of
+    /// error and returns body and optional value of `Content-Type` header of response.
+    ///
+    /// Status code and headers of response are the same as [ServeError::into_response]. Response of
+    /// [ServeError::NotModified] must not have body and closure should return an empty one for it.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use http::{header::{HeaderValue, CONTENT_TYPE}, StatusCode};
+    /// # use http_file::ServeError;
+    /// let res = ServeError::NotFound.into_response_with(|e| match e {
+    ///     ServeError::NotFound => (Some(HeaderValue::from_static("text/html")), "<h1>404 Not Found</h1>"),
+    ///     _ => (None, ""),
+    /// });
+    ///
+    /// assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    /// assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "text/html");
+    /// assert_eq!(*res.body(), "<h1>404 Not Found</h1>");
+    /// ```
+    pub fn into_response_with<F, B>(self, func: F) -> Response<B>
+    where
+        F: FnOnce(&Self) -> (Option<HeaderValue>, B),
+    {
+        let (content_type, body) = func(&self);
+        let mut res = self.into_response();
+        if let Some(content_type) = content_type {
+            res.headers_mut().insert(CONTENT_TYPE, content_type);
+        }
+        res.map(|_| body)
+    }
+
     fn _into_response(self, mut res: Response<()>) -> Response<()> {
         match self {
             Self::MethodNotAllowed => {
@@ -135,6 +168,19 @@ mod test {
                 .remove(CONTENT_RANGE)
                 .unwrap(),
             HeaderValue::from_static("bytes */128")
-        )
+        );
+
+        let res = ServeError::MethodNotAllowed.into_response();
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(res.headers().get(ALLOW).unwrap(), "GET,HEAD");
+
+        let res = ServeError::InvalidPath.into_response_with(|_| (Some(HeaderValue::from_static("text/plain")), "bad"));
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "text/plain");
+        assert_eq!(*res.body(), "bad");
+
+        let res = ServeError::NotModified.into_response_with(|_| (None, ""));
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert!(!res.headers().contains_key(CONTENT_TYPE));
     }
 }
@@ -2,24 +2,32 @@
 
 #![feature(impl_trait_in_assoc_type)]
 
+pub mod preconditions;
 pub mod runtime;
 
+#[cfg(feature = "digest")]
+pub mod digest;
+
 mod buf;
+mod cache;
 mod chunk;
 mod date;
 mod error;
 
 pub use self::{chunk::ChunkReader, error::ServeError};
 
+use core::{cmp, ops::RangeInclusive};
+
 use std::{
     io::SeekFrom,
     path::{Component, Path, PathBuf},
 };
 
 use http::{
-    header::{HeaderValue, ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, LAST_MODIFIED, RANGE},
+    header::{HeaderValue, ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, ETAG, LAST_MODIFIED, RANGE},
     Method, Request, Response, StatusCode,
 };
+use http_range_header::{ParsedRanges, StartPosition};
 use mime_guess::mime;
 
 use self::{
@@ -31,16 +39,26 @@ use self::{
 #[derive(Clone)]
 pub struct ServeDir<FS: AsyncFs = runtime::TokioFs> {
     chunk_size: usize,
+    read_ahead: usize,
+    accept_ranges: bool,
     base_path: PathBuf,
     async_fs: FS,
+    cache: Option<cache::FileCache>,
+    #[cfg(feature = "digest")]
+    digest: Option<digest::DigestConfig>,
 }
 
 #[cfg(not(feature = "tokio"))]
 #[derive(Clone)]
 pub struct ServeDir<FS: AsyncFs> {
     chunk_size: usize,
+    read_ahead: usize,
+    accept_ranges: bool,
     base_path: PathBuf,
     async_fs: FS,
+    cache: Option<cache::FileCache>,
+    #[cfg(feature = "digest")]
+    digest: Option<digest::DigestConfig>,
 }
 
 #[cfg(feature = "default")]
@@ -65,8 +83,13 @@ impl<FS: AsyncFs> ServeDir<FS> {
     pub fn with_fs(path: impl Into<PathBuf>, async_fs: FS) -> Self {
         Self {
             chunk_size: 4096,
+            read_ahead: 0,
+            accept_ranges: true,
             base_path: path.into(),
             async_fs,
+            cache: None,
+            #[cfg(feature = "digest")]
+            digest: None,
         }
     }
 
@@ -78,6 +101,64 @@ impl<FS: AsyncFs> ServeDir<FS> {
         self
     }
 
+    /// count of chunks read ahead of async file streaming. default to 0.
+    ///
+    /// when set to non zero reading from file does not stop after a chunk is read. The next read is
+    /// started whenever the streaming is polled and up to n chunks are buffered in memory before they
+    /// are consumed. This helps throughput of serving large file from high latency storage (network
+    /// file system for example) where waiting for each chunk to be sent before the next read
+    /// serializes io.
+    ///
+    /// reads are sequential: [ChunkRead](runtime::ChunkRead) takes the file by value so at most one read is in flight at
+    /// any time. read ahead overlaps file io with sending chunks to client. it does not issue n reads
+    /// concurrently.
+    ///
+    /// memory usage of each file streaming grows up to `n * chunk_size` bytes. See [ServeDir::chunk_size].
+    pub fn read_ahead(&mut self, n: usize) -> &mut Self {
+        self.read_ahead = n;
+        self
+    }
+
+    /// enable or disable byte range request. default to enabled.
+    ///
+    /// when disabled response advertises `Accept-Ranges: none` and `Range` header of request is
+    /// ignored. Full file content is always served with 200 status code instead of 206. This is useful
+    /// when served content is transformed afterwards (for example by compression) and byte offsets of
+    /// file do not correspond to the response body anymore.
+    pub fn accept_ranges(&mut self, accept: bool) -> &mut Self {
+        self.accept_ranges = accept;
+        self
+    }
+
+    /// cache content of file with size no larger than given max_file_size in memory. at most capacity
+    /// count of files are cached and the least recently served one is evicted when cache is full.
+    ///
+    /// cached file is served from memory without opening it from file system. It's validated against
+    /// it's size and modified date by opening it again when it's been more than 1 second since last
+    /// validation and it's read into memory again when it's modified. File without modified date is
+    /// not cached. Cache is shared by all clones of [ServeDir].
+    ///
+    /// memory usage of cache grows up to `capacity * max_file_size` bytes.
+    pub fn cache(&mut self, capacity: usize, max_file_size: u64) -> &mut Self {
+        self.cache = Some(cache::FileCache::new(capacity, max_file_size));
+        self
+    }
+
+    /// add `Repr-Digest` header (RFC 9530) to response of file with size no larger than given
+    /// max_size. Response with full file content has `Content-Digest` header of the same value.
+    ///
+    /// digest is calculated from full content of file with given [digest::Algorithm] when it's served
+    /// and cached in memory alongside file size and modified time. Cached digest is calculated again
+    /// when file is modified. File without modified time has it's digest calculated for every request.
+    /// Range request does not affect `Repr-Digest` as it always represents the full file. at most
+    /// capacity count of digests are cached and the least recently served one is evicted when cache is
+    /// full.
+    #[cfg(feature = "digest")]
+    pub fn digest(&mut self, algorithm: digest::Algorithm, capacity: usize, max_size: u64) -> &mut Self {
+        self.digest = Some(digest::DigestConfig::new(algorithm, capacity, max_size));
+        self
+    }
+
     /// try to find a matching file from given input request and generate http response with stream
     /// reader of matched file.
     ///
@@ -97,8 +178,10 @@ impl<FS: AsyncFs> ServeDir<FS> {
 
         let path = self.path_check(req.uri().path())?;
 
+        let cached = self.cache.as_ref().and_then(|cache| cache.get(&path));
+
         // TODO: enable nest dir serving?
-        if path.is_dir() {
+        if cached.is_none() && path.is_dir() {
             return Err(ServeError::InvalidPath);
         }
 
@@ -106,28 +189,61 @@ impl<FS: AsyncFs> ServeDir<FS> {
             .first_raw()
             .unwrap_or_else(|| mime::APPLICATION_OCTET_STREAM.as_ref());
 
-        let mut file = self.async_fs.open(path).await?;
+        #[cfg(feature = "digest")]
+        let digest_path = self.digest.as_ref().map(|_| path.clone());
+
+        let mut file = match cached {
+            Some(cached) => Source::Cached(cached),
+            None => {
+                let file = self.async_fs.open(path.clone()).await?;
+                match self.cache {
+                    Some(ref cache) => match cache.load(path, file, self.chunk_size).await? {
+                        Ok(cached) => Source::Cached(cached),
+                        Err(file) => Source::File(file),
+                    },
+                    None => Source::File(file),
+                }
+            }
+        };
 
-        let modified = date::mod_date_check(req, &mut file)?;
+        let action = preconditions::evaluate(&mut file, req.headers());
+        match action {
+            preconditions::Action::NotModified => return Err(ServeError::NotModified),
+            preconditions::Action::PreconditionFailed => return Err(ServeError::PreconditionFailed),
+            preconditions::Action::Full | preconditions::Action::Range => {}
+        }
+
+        let modified = file.modified();
+        let etag = preconditions::etag(&mut file);
 
         let mut res = Response::new(());
 
         let mut size = file.len();
+        // start position of served content.
+        let mut offset = 0;
+
+        #[cfg(feature = "digest")]
+        if let (Some(digest), Some(path)) = (self.digest.as_ref(), digest_path) {
+            if let Some(val) = digest
+                .header_value(&self.async_fs, path, size, modified, self.chunk_size)
+                .await?
+            {
+                res.headers_mut().insert(digest::REPR_DIGEST, val);
+            }
+        }
 
-        if let Some(range) = req
-            .headers()
-            .get(RANGE)
+        if let Some(range) = (self.accept_ranges && action == preconditions::Action::Range)
+            .then(|| req.headers())
+            .and_then(|headers| headers.get(RANGE))
             .and_then(|h| h.to_str().ok())
             .and_then(|range| http_range_header::parse_range_header(range).ok())
-            .map(|range| range.validate(size))
         {
-            let (start, end) = range
-                .map_err(|_| ServeError::RangeNotSatisfied(size))?
-                .pop()
-                .expect("http_range_header produced empty range")
-                .into_inner();
+            let (start, end) = validate_range(range, size)?;
 
-            file.seek(SeekFrom::Start(start)).await?;
+            if let Source::File(ref mut file) = file {
+                file.seek(SeekFrom::Start(start)).await?;
+            }
+            offset = start;
 
             *res.status_mut() = StatusCode::PARTIAL_CONTENT;
             let val = buf_write_header!(0, "bytes {start}-{end}/{size}");
@@ -136,59 +252,150 @@ impl<FS: AsyncFs> ServeDir<FS> {
             size = end - start + 1;
         }
 
+        // content of full response is the same as representation.
+        #[cfg(feature = "digest")]
+        if res.status() == StatusCode::OK {
+            if let Some(val) = res.headers().get(digest::REPR_DIGEST).cloned() {
+                res.headers_mut().insert(digest::CONTENT_DIGEST, val);
+            }
+        }
+
         res.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static(ct));
         res.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from(size));
+        let accept_ranges = if self.accept_ranges { "bytes" } else { "none" };
         res.headers_mut()
-            .insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+            .insert(ACCEPT_RANGES, HeaderValue::from_static(accept_ranges));
 
-        if let Some(modified) = modified {
+        if let Some(modified) = modified.map(httpdate::HttpDate::from) {
             let val = date::date_to_header(modified);
             res.headers_mut().insert(LAST_MODIFIED, val);
         }
 
-        let stream = if matches!(*req.method(), Method::HEAD) {
-            ChunkReader::empty()
-        } else {
-            ChunkReader::reader(file, size, self.chunk_size)
+        if let Some(etag) = etag {
+            res.headers_mut().insert(ETAG, etag);
+        }
+
+        let stream = match file {
+            _ if matches!(*req.method(), Method::HEAD) => ChunkReader::empty(),
+            Source::File(file) => ChunkReader::reader(file, size, self.chunk_size, self.read_ahead),
+            Source::Cached(cached) => {
+                let start = offset as usize;
+                ChunkReader::bytes(cached.content().slice(start..start + size as usize))
+            }
         };
 
         Ok(res.map(|_| stream))
     }
 }
 
+// file being served. it's either opened from file system or cached in memory.
+enum Source<F> {
+    File(F),
+    Cached(cache::CachedFile),
+}
+
+impl<F> Meta for Source<F>
+where
+    F: Meta,
+{
+    fn modified(&mut self) -> Option<std::time::SystemTime> {
+        match self {
+            Self::File(file) => file.modified(),
+            Self::Cached(cached) => cached.modified(),
+        }
+    }
+
+    fn len(&self) -> u64 {
+        match self {
+            Self::File(file) => file.len(),
+            Self::Cached(cached) => cached.len(),
+        }
+    }
+}
+
+// resolve parsed range header to inclusive start and end position of file with given size.
+fn validate_range(mut range: ParsedRanges, size: u64) -> Result<(u64, u64), ServeError> {
+    // no byte range is satisfiable for empty file.
+    if size == 0 {
+        return Err(ServeError::RangeNotSatisfied(size));
+    }
+
+    // suffix range longer than file selects the whole file. (RFC 9110 section 14.1.2)
+    for range in range.ranges.iter_mut() {
+        if let StartPosition::FromLast(ref mut len) = range.start {
+            *len = cmp::min(*len, size);
+        }
+    }
+
+    range
+        .validate(size)
+        .map_err(|_| ServeError::RangeNotSatisfied(size))?
+        .pop()
+        .map(RangeInclusive::into_inner)
+        .ok_or(ServeError::RangeNotSatisfied(size))
+}
+
 impl<FS: AsyncFs> ServeDir<FS> {
     fn path_check(&self, path: &str) -> Result<PathBuf, ServeError> {
-        let path = path.trim_start_matches('/').as_bytes();
-
-        let path_decoded = percent_encoding::percent_decode(path)
-            .decode_utf8()
-            .map_err(|_| ServeError::InvalidPath)?;
-        let path_decoded = Path::new(&*path_decoded);
-
-        let mut path = self.base_path.clone();
-
-        for component in path_decoded.components() {
-            match component {
-                Component::Normal(comp) => {
-                    if Path::new(&comp)
-                        .components()
-                        .any(|c| !matches!(c, Component::Normal(_)))
-                    {
-                        return Err(ServeError::InvalidPath);
-                    }
-                    path.push(comp)
-                }
-                Component::CurDir => {}
-                Component::Prefix(_) | Component::RootDir | Component::ParentDir => {
-                    return Err(ServeError::InvalidPath)
-                }
+        let mut path_buf = self.base_path.clone();
+
+        // split raw path before percent decoding so encoded separator can not produce extra segment.
+        for segment in path.split('/') {
+            match segment {
+                "" | "." => continue,
+                ".." => return Err(ServeError::InvalidPath),
+                _ => {}
             }
+
+            let segment = percent_encoding::percent_decode(segment.as_bytes())
+                .decode_utf8()
+                .map_err(|_| ServeError::InvalidPath)?;
+
+            // dot segment, path separator and null byte are not allowed in decoded segment.
+            if matches!(&*segment, "." | "..")
+                || segment.contains(['/', '\\', '\0'])
+                || (cfg!(windows) && is_windows_reserved(&segment))
+            {
+                return Err(ServeError::InvalidPath);
+            }
+
+            // segment must be a plain file name. this rejects prefix like windows drive letter.
+            if Path::new(&*segment)
+                .components()
+                .any(|c| !matches!(c, Component::Normal(_)))
+            {
+                return Err(ServeError::InvalidPath);
+            }
+
+            path_buf.push(&*segment);
         }
 
-        Ok(path)
+        Ok(path_buf)
     }
 }
 
+// file name that resolves to device or a different file on windows.
+fn is_windows_reserved(name: &str) -> bool {
+    // alternate data stream and trailing dot/space that are silently trimmed by windows.
+    if name.contains(':') || name.ends_with(['.', ' ']) {
+        return true;
+    }
+
+    // device names are reserved regardless of extension.
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    matches!(
+        stem.to_ascii_uppercase().as_bytes(),
+        b"CON"
+            | b"PRN"
+            | b"AUX"
+            | b"NUL"
+            | b"CONIN$"
+            | b"CONOUT$"
+            | [b'C', b'O', b'M', b'1'..=b'9']
+            | [b'L', b'P', b'T', b'1'..=b'9']
+    )
+}
+
 #[cfg(test)]
 mod test {
     use core::future::poll_fn;
@@ -254,8 +461,62 @@ mod test {
     #[tokio::test]
     async fn invalid_path() {
         let dir = ServeDir::new("sample");
-        let req = Request::builder().uri("/../test.txt").body(()).unwrap();
-        assert!(matches!(dir.serve(&req).await.err(), Some(ServeError::InvalidPath)));
+
+        for uri in [
+            "/../test.txt",
+            "/%2e%2e/test.txt",
+            "/%2e%2e%2ftest.txt",
+            "/%2E%2E%2Fsrc/lib.rs",
+            "/..%2ftest.txt",
+            "/%2e%2e%5ctest.txt",
+            "/sample%2ftest.txt",
+            "/%2e/test.txt",
+            "/test.txt%00",
+            "/%ff.txt",
+        ] {
+            let req = Request::builder().uri(uri).body(()).unwrap();
+            assert!(
+                matches!(dir.serve(&req).await.err(), Some(ServeError::InvalidPath)),
+                "{uri} must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn valid_path() {
+        let dir = ServeDir::new("sample");
+
+        for path in [
+            "/test.txt",
+            "/./test.txt",
+            "//test.txt",
+            "/%74est.txt",
+            "/%E4%BD%A0%E5%A5%BD.txt",
+        ] {
+            let path_buf = dir.path_check(path).unwrap();
+            assert!(path_buf.starts_with("sample"));
+            assert_eq!(path_buf.components().count(), 2);
+        }
+    }
+
+    #[test]
+    fn windows_reserved() {
+        for name in [
+            "CON",
+            "con.txt",
+            "Aux.tar.gz",
+            "COM1",
+            "lpt9.log",
+            "test.txt.",
+            "test.txt ",
+            "a.txt::$DATA",
+        ] {
+            assert!(is_windows_reserved(name), "{name} is reserved");
+        }
+
+        for name in ["test.txt", "console.txt", "COM10", "nul_file"] {
+            assert!(!is_windows_reserved(name), "{name} is not reserved");
+        }
     }
 
     #[tokio::test]
@@ -275,6 +536,15 @@ mod test {
             res.headers().get(CONTENT_LENGTH).unwrap(),
             HeaderValue::from("hello, world!".len())
         );
+
+        // entity tag of response can be used for conditional request.
+        let etag = res.headers().get(ETAG).unwrap().clone();
+        let req = Request::builder()
+            .uri("/test.txt")
+            .header(http::header::IF_NONE_MATCH, etag)
+            .body(())
+            .unwrap();
+        assert!(matches!(dir.serve(&req).await, Err(ServeError::NotModified)));
     }
 
     #[tokio::test]
@@ -289,6 +559,69 @@ mod test {
         assert_eq!(lower, "hello, world!".len());
     }
 
+    #[tokio::test]
+    async fn read_ahead() {
+        let mut dir = ServeDir::new("sample");
+        dir.chunk_size(2).read_ahead(3);
+
+        let req = Request::builder().uri("/test.txt").body(()).unwrap();
+
+        let mut stream = Box::pin(dir.serve(&req).await.unwrap().into_body());
+
+        let mut res = String::new();
+
+        while let Some(Ok(bytes)) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+            res.push_str(std::str::from_utf8(bytes.as_ref()).unwrap());
+
+            // buffered chunks are counted in size hint.
+            let (low, high) = stream.size_hint();
+            assert_eq!(low, high.unwrap());
+            assert_eq!(low, "hello, world!".len() - res.len());
+        }
+
+        assert_eq!("hello, world!", res);
+    }
+
+    #[cfg(feature = "digest")]
+    #[tokio::test]
+    async fn digest() {
+        const SHA256: &str = "sha-256=:aOZWslHmfoNYvvhIOrDVHGYZ8+ehqfDnWDjUH/No9yg=:";
+
+        let mut dir = ServeDir::new("sample");
+        dir.digest(digest::Algorithm::Sha256, 8, 1024);
+
+        // second request is served from cache. range request carries digest of full file.
+        for range in [None, None, Some("bytes=2-12")] {
+            let mut req = Request::builder().uri("/test.txt");
+            if let Some(range) = range {
+                req = req.header(RANGE, range);
+            }
+            let res = dir.serve(&req.body(()).unwrap()).await.unwrap();
+            assert_eq!(res.headers().get(digest::REPR_DIGEST).unwrap(), SHA256);
+            // content of range response is not the full representation.
+            match range {
+                Some(_) => assert!(res.headers().get(digest::CONTENT_DIGEST).is_none()),
+                None => assert_eq!(res.headers().get(digest::CONTENT_DIGEST).unwrap(), SHA256),
+            }
+        }
+
+        let mut dir = ServeDir::new("sample");
+        dir.digest(digest::Algorithm::Sha512, 8, 1024);
+        let req = Request::builder().uri("/test.txt").body(()).unwrap();
+        let res = dir.serve(&req).await.unwrap();
+        assert_eq!(
+            res.headers().get(digest::REPR_DIGEST).unwrap(),
+            "sha-512=:bCYYNY2gfIMLiMWvjDU1CA6OYDyIuJECiiWczbmsgC0PwBcMmdWK/88AeGzhiPxddT6MZiivIHHDJw1QRFxLHA==:"
+        );
+
+        // file larger than max size has no digest.
+        let mut dir = ServeDir::new("sample");
+        dir.digest(digest::Algorithm::Sha256, 8, 8);
+        let req = Request::builder().uri("/test.txt").body(()).unwrap();
+        let res = dir.serve(&req).await.unwrap();
+        assert!(res.headers().get(digest::REPR_DIGEST).is_none());
+    }
+
     async fn _basic<FS: AsyncFs>(dir: ServeDir<FS>) {
         let req = Request::builder().uri("/test.txt").body(()).unwrap();
 
@@ -320,45 +653,189 @@ mod test {
     }
 
     async fn test_range<FS: AsyncFs>(dir: ServeDir<FS>) {
+        // sample file content is "hello, world!" with 13 bytes.
+        for (range, content_range, body) in [
+            ("bytes=2-12", "bytes 2-12/13", "llo, world!"),
+            // open ended range.
+            ("bytes=7-", "bytes 7-12/13", "world!"),
+            // suffix range.
+            ("bytes=-6", "bytes 7-12/13", "world!"),
+            // suffix range longer than file.
+            ("bytes=-500", "bytes 0-12/13", "hello, world!"),
+            // range ends exactly at EOF.
+            ("bytes=0-12", "bytes 0-12/13", "hello, world!"),
+            // range ends beyond EOF.
+            ("bytes=7-100", "bytes 7-12/13", "world!"),
+            // last byte.
+            ("bytes=12-12", "bytes 12-12/13", "!"),
+        ] {
+            let req = Request::builder()
+                .uri("/test.txt")
+                .header("range", range)
+                .body(())
+                .unwrap();
+            let res = dir.serve(&req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+            assert_eq!(
+                res.headers().get(CONTENT_TYPE).unwrap(),
+                HeaderValue::from_static("text/plain")
+            );
+            assert_eq!(
+                res.headers().get(CONTENT_RANGE).unwrap(),
+                HeaderValue::from_static(content_range)
+            );
+            assert_eq!(
+                res.headers().get(CONTENT_LENGTH).unwrap(),
+                HeaderValue::from(body.len())
+            );
+
+            let mut stream = Box::pin(res.into_body());
+
+            let mut res = String::new();
+
+            while let Some(Ok(bytes)) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+                res.push_str(std::str::from_utf8(bytes.as_ref()).unwrap());
+            }
+
+            assert_eq!(body, res);
+        }
+
+        for range in ["bytes=13-", "bytes=13-20", "bytes=5-2"] {
+            let req = Request::builder()
+                .uri("/test.txt")
+                .header("range", range)
+                .body(())
+                .unwrap();
+            match dir.serve(&req).await {
+                Err(ServeError::RangeNotSatisfied(13)) => {}
+                _ => panic!("range {range} must not be satisfied"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn ranged() {
+        test_range(ServeDir::new("sample")).await;
+    }
+
+    #[cfg(all(target_os = "linux", feature = "tokio-uring"))]
+    #[test]
+    fn ranged_tokio_uring() {
+        tokio_uring::start(test_range(ServeDir::new_tokio_uring("sample")))
+    }
+
+    #[tokio::test]
+    async fn range_disabled() {
+        let mut dir = ServeDir::new("sample");
+        dir.accept_ranges(false);
+
         let req = Request::builder()
             .uri("/test.txt")
             .header("range", "bytes=2-12")
             .body(())
             .unwrap();
         let res = dir.serve(&req).await.unwrap();
-        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(res.status(), StatusCode::OK);
         assert_eq!(
-            res.headers().get(CONTENT_TYPE).unwrap(),
-            HeaderValue::from_static("text/plain")
+            res.headers().get(ACCEPT_RANGES).unwrap(),
+            HeaderValue::from_static("none")
         );
+        assert!(res.headers().get(CONTENT_RANGE).is_none());
         assert_eq!(
-            res.headers().get(CONTENT_RANGE).unwrap(),
-            HeaderValue::from_static("bytes 2-12/13")
+            res.headers().get(CONTENT_LENGTH).unwrap(),
+            HeaderValue::from("hello, world!".len())
         );
+    }
+
+    #[tokio::test]
+    async fn cache() {
+        async fn serve(dir: &ServeDir, range: Option<&str>) -> (StatusCode, String) {
+            let mut req = Request::builder().uri("/test.txt");
+            if let Some(range) = range {
+                req = req.header(RANGE, range);
+            }
+            let res = dir.serve(&req.body(()).unwrap()).await.unwrap();
+            let status = res.status();
+            let mut stream = Box::pin(res.into_body());
+            let mut body = String::new();
+            while let Some(chunk) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+                body.push_str(std::str::from_utf8(&chunk.unwrap()).unwrap());
+            }
+            (status, body)
+        }
+
+        let base = std::env::temp_dir().join("http-file-cache");
+        std::fs::create_dir_all(&base).unwrap();
+        let path = base.join("test.txt");
+        std::fs::write(&path, "hello, world!").unwrap();
+
+        let mut dir = ServeDir::new(&base);
+        dir.cache(8, 1024);
+
+        assert_eq!(serve(&dir, None).await, (StatusCode::OK, "hello, world!".into()));
+
+        // removed file is still served from cache before it's validated again.
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(serve(&dir, None).await, (StatusCode::OK, "hello, world!".into()));
         assert_eq!(
-            res.headers().get(CONTENT_LENGTH).unwrap(),
-            HeaderValue::from("llo, world!".len())
+            serve(&dir, Some("bytes=7-11")).await,
+            (StatusCode::PARTIAL_CONTENT, "world".into())
         );
 
-        let mut stream = Box::pin(res.into_body());
+        // modified file is read again after validation.
+        std::fs::write(&path, "hello, xitca!!").unwrap();
+        std::thread::sleep(cache::REVALIDATE + core::time::Duration::from_millis(100));
+        assert_eq!(serve(&dir, None).await, (StatusCode::OK, "hello, xitca!!".into()));
 
-        let mut res = String::new();
+        // file larger than max size is not cached.
+        let mut dir = ServeDir::new(&base);
+        dir.cache(8, 4);
+        assert_eq!(serve(&dir, None).await, (StatusCode::OK, "hello, xitca!!".into()));
+        std::fs::remove_file(&path).unwrap();
+        let req = Request::builder().uri("/test.txt").body(()).unwrap();
+        assert!(matches!(dir.serve(&req).await, Err(ServeError::NotFound)));
+    }
 
-        while let Some(Ok(bytes)) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
-            res.push_str(std::str::from_utf8(bytes.as_ref()).unwrap());
+    #[test]
+    fn range_empty_file() {
+        for range in ["bytes=0-", "bytes=0-0", "bytes=-1"] {
+            let range = http_range_header::parse_range_header(range).unwrap();
+            assert!(matches!(
+                validate_range(range, 0),
+                Err(ServeError::RangeNotSatisfied(0))
+            ));
         }
+    }
 
-        assert_eq!("llo, world!", res);
+    async fn test_write<FS: runtime::AsyncFsWrite>(fs: FS, name: &str) {
+        use bytes::Bytes;
+
+        use runtime::ChunkWrite;
+
+        let path = std::env::temp_dir().join(name);
+
+        let file = fs.create(path.clone()).await.unwrap();
+        let file = file.write(Bytes::from_static(b"hello, ")).await.unwrap();
+        file.close().await.unwrap();
+
+        let file = fs.append(path.clone()).await.unwrap();
+        let file = file.write(Bytes::from_static(b"world!")).await.unwrap();
+        file.close().await.unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"hello, world!");
+
+        fs.remove(path.clone()).await.unwrap();
+        assert!(!path.exists());
     }
 
     #[tokio::test]
-    async fn ranged() {
-        test_range(ServeDir::new("sample")).await;
+    async fn write() {
+        test_write(runtime::TokioFs, "http_file_write_tokio.txt").await;
     }
 
     #[cfg(all(target_os = "linux", feature = "tokio-uring"))]
     #[test]
-    fn ranged_tokio_uring() {
-        tokio_uring::start(test_range(ServeDir::new_tokio_uring("sample")))
+    fn write_tokio_uring() {
+        tokio_uring::start(test_write(runtime::TokioUringFs, "http_file_write_tokio_uring.txt"))
     }
 }
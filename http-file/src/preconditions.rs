@@ -0,0 +1,281 @@
+//! evaluation of conditional request headers. (RFC 9110 section 13)
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use http::header::{
+    HeaderMap, HeaderValue, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_RANGE, IF_UNMODIFIED_SINCE, RANGE,
+};
+use httpdate::HttpDate;
+
+use super::{buf::buf_write_header, date::to_http_date, runtime::Meta};
+
+/// action of serving file decided by conditional headers of request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// serve full content of file.
+    Full,
+    /// serve byte range of file requested by `Range` header.
+    Range,
+    /// respond with `304 Not Modified`.
+    NotModified,
+    /// respond with `412 Precondition Failed`.
+    PreconditionFailed,
+}
+
+/// evaluate conditional headers of `GET` or `HEAD` request against file with given [Meta].
+///
+/// Headers are evaluated in the order of RFC 9110 section 13.2.2:
+/// 1. `If-Match` or `If-Unmodified-Since` when `If-Match` is absent. fails with
+///    [Action::PreconditionFailed].
+/// 2. `If-None-Match` or `If-Modified-Since` when `If-None-Match` is absent. fails with
+///    [Action::NotModified].
+/// 3. `If-Range` when `Range` header is present. [Action::Full] is returned when it does not match and
+///    range request is ignored.
+///
+/// Validators of file are entity tag produced by [etag] and it's modified date. Malformed date headers
+/// are ignored. `Range` header itself is not validated and caller is responsible for parsing it.
+///
+/// # Examples
+/// ```rust
+/// # use std::time::SystemTime;
+/// # use http::header::{HeaderMap, IF_NONE_MATCH};
+/// # use http_file::{preconditions::{etag, evaluate, Action}, runtime::Meta};
+/// struct File;
+///
+/// impl Meta for File {
+///     fn modified(&mut self) -> Option<SystemTime> {
+///         Some(SystemTime::UNIX_EPOCH)
+///     }
+///
+///     fn len(&self) -> u64 {
+///         13
+///     }
+/// }
+///
+/// let mut headers = HeaderMap::new();
+/// assert_eq!(evaluate(&mut File, &headers), Action::Full);
+///
+/// headers.insert(IF_NONE_MATCH, etag(&mut File).unwrap());
+/// assert_eq!(evaluate(&mut File, &headers), Action::NotModified);
+/// ```
+pub fn evaluate<M>(meta: &mut M, headers: &HeaderMap) -> Action
+where
+    M: Meta,
+{
+    let modified = meta.modified();
+    let etag = modified.map(|modified| etag_value(meta.len(), modified));
+    let mod_date = modified.map(HttpDate::from);
+
+    match headers.get(IF_MATCH) {
+        Some(value) => {
+            if !etag_match(value, etag.as_ref(), true) {
+                return Action::PreconditionFailed;
+            }
+        }
+        None => {
+            if let Some(date) = to_http_date(headers.get(IF_UNMODIFIED_SINCE)) {
+                // file without modified date can not be proven unmodified.
+                if mod_date.is_none_or(|mod_date| mod_date > date) {
+                    return Action::PreconditionFailed;
+                }
+            }
+        }
+    }
+
+    match headers.get(IF_NONE_MATCH) {
+        Some(value) => {
+            if etag_match(value, etag.as_ref(), false) {
+                return Action::NotModified;
+            }
+        }
+        None => {
+            if let (Some(date), Some(mod_date)) = (to_http_date(headers.get(IF_MODIFIED_SINCE)), mod_date) {
+                if mod_date <= date {
+                    return Action::NotModified;
+                }
+            }
+        }
+    }
+
+    if !headers.contains_key(RANGE) {
+        return Action::Full;
+    }
+
+    match headers.get(IF_RANGE) {
+        None => Action::Range,
+        Some(value) if value.as_bytes().starts_with(b"\"") || value.as_bytes().starts_with(b"W/") => {
+            match etag_match(value, etag.as_ref(), true) {
+                true => Action::Range,
+                false => Action::Full,
+            }
+        }
+        Some(value) => match (to_http_date(Some(value)), mod_date) {
+            (Some(date), Some(mod_date)) if date == mod_date => Action::Range,
+            _ => Action::Full,
+        },
+    }
+}
+
+/// entity tag of file with given [Meta]. it's made of file length and modified date and file without
+/// modified date does not have one.
+pub fn etag<M>(meta: &mut M) -> Option<HeaderValue>
+where
+    M: Meta,
+{
+    meta.modified().map(|modified| etag_value(meta.len(), modified))
+}
+
+fn etag_value(len: u64, modified: SystemTime) -> HeaderValue {
+    let nanos = modified.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+    buf_write_header!(0, "\"{nanos:x}-{len:x}\"")
+}
+
+// match entity tag against list of entity tags in header value. strong comparison does not match weak
+// entity tags.
+fn etag_match(value: &HeaderValue, etag: Option<&HeaderValue>, strong: bool) -> bool {
+    let Ok(value) = value.to_str() else {
+        return false;
+    };
+
+    // wildcard matches any existing file.
+    if value.trim() == "*" {
+        return true;
+    }
+
+    let Some(etag) = etag.and_then(|etag| etag.to_str().ok()) else {
+        return false;
+    };
+
+    value.split(',').map(str::trim).any(|tag| match tag.strip_prefix("W/") {
+        Some(tag) => !strong && tag == etag,
+        None => tag == etag,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use core::time::Duration;
+
+    use http::header::HeaderName;
+
+    use super::*;
+
+    struct File(Option<SystemTime>);
+
+    impl Meta for File {
+        fn modified(&mut self) -> Option<SystemTime> {
+            self.0
+        }
+
+        fn len(&self) -> u64 {
+            13
+        }
+    }
+
+    fn eval(file: &mut File, headers: &[(HeaderName, &str)]) -> Action {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.append(name, HeaderValue::from_str(value).unwrap());
+        }
+        evaluate(file, &map)
+    }
+
+    #[test]
+    fn evaluate_headers() {
+        let modified = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let mut file = File(Some(modified));
+
+        let etag = etag(&mut file).unwrap();
+        let etag = etag.to_str().unwrap();
+        let weak = format!("W/{etag}");
+        let date = HttpDate::from(modified).to_string();
+        let before = HttpDate::from(modified - Duration::from_secs(1)).to_string();
+        let after = HttpDate::from(modified + Duration::from_secs(1)).to_string();
+
+        assert_eq!(eval(&mut file, &[]), Action::Full);
+
+        // If-Match
+        assert_eq!(eval(&mut file, &[(IF_MATCH, etag)]), Action::Full);
+        assert_eq!(eval(&mut file, &[(IF_MATCH, "*")]), Action::Full);
+        assert_eq!(eval(&mut file, &[(IF_MATCH, &format!("\"a\", {etag}"))]), Action::Full);
+        assert_eq!(eval(&mut file, &[(IF_MATCH, "\"a\"")]), Action::PreconditionFailed);
+        assert_eq!(eval(&mut file, &[(IF_MATCH, &weak)]), Action::PreconditionFailed);
+        // If-Match takes precedence over If-Unmodified-Since.
+        assert_eq!(
+            eval(&mut file, &[(IF_MATCH, etag), (IF_UNMODIFIED_SINCE, &before)]),
+            Action::Full
+        );
+
+        // If-Unmodified-Since
+        assert_eq!(eval(&mut file, &[(IF_UNMODIFIED_SINCE, &date)]), Action::Full);
+        assert_eq!(
+            eval(&mut file, &[(IF_UNMODIFIED_SINCE, &before)]),
+            Action::PreconditionFailed
+        );
+        assert_eq!(eval(&mut file, &[(IF_UNMODIFIED_SINCE, "bogus")]), Action::Full);
+
+        // If-None-Match
+        assert_eq!(eval(&mut file, &[(IF_NONE_MATCH, etag)]), Action::NotModified);
+        assert_eq!(eval(&mut file, &[(IF_NONE_MATCH, &weak)]), Action::NotModified);
+        assert_eq!(eval(&mut file, &[(IF_NONE_MATCH, "*")]), Action::NotModified);
+        assert_eq!(eval(&mut file, &[(IF_NONE_MATCH, "\"a\"")]), Action::Full);
+        // If-None-Match takes precedence over If-Modified-Since.
+        assert_eq!(
+            eval(&mut file, &[(IF_NONE_MATCH, "\"a\""), (IF_MODIFIED_SINCE, &after)]),
+            Action::Full
+        );
+        // failed If-Match takes precedence over If-None-Match.
+        assert_eq!(
+            eval(&mut file, &[(IF_MATCH, "\"a\""), (IF_NONE_MATCH, etag)]),
+            Action::PreconditionFailed
+        );
+
+        // If-Modified-Since
+        assert_eq!(eval(&mut file, &[(IF_MODIFIED_SINCE, &date)]), Action::NotModified);
+        assert_eq!(eval(&mut file, &[(IF_MODIFIED_SINCE, &after)]), Action::NotModified);
+        assert_eq!(eval(&mut file, &[(IF_MODIFIED_SINCE, &before)]), Action::Full);
+
+        // Range and If-Range
+        assert_eq!(eval(&mut file, &[(RANGE, "bytes=0-1")]), Action::Range);
+        assert_eq!(
+            eval(&mut file, &[(RANGE, "bytes=0-1"), (IF_RANGE, etag)]),
+            Action::Range
+        );
+        assert_eq!(
+            eval(&mut file, &[(RANGE, "bytes=0-1"), (IF_RANGE, &weak)]),
+            Action::Full
+        );
+        assert_eq!(
+            eval(&mut file, &[(RANGE, "bytes=0-1"), (IF_RANGE, "\"a\"")]),
+            Action::Full
+        );
+        assert_eq!(
+            eval(&mut file, &[(RANGE, "bytes=0-1"), (IF_RANGE, &date)]),
+            Action::Range
+        );
+        assert_eq!(
+            eval(&mut file, &[(RANGE, "bytes=0-1"), (IF_RANGE, &after)]),
+            Action::Full
+        );
+        assert_eq!(eval(&mut file, &[(IF_RANGE, etag)]), Action::Full);
+    }
+
+    #[test]
+    fn evaluate_without_modified() {
+        let mut file = File(None);
+        let date = HttpDate::from(SystemTime::now()).to_string();
+
+        assert!(etag(&mut file).is_none());
+        assert_eq!(
+            eval(&mut file, &[(IF_UNMODIFIED_SINCE, &date)]),
+            Action::PreconditionFailed
+        );
+        assert_eq!(eval(&mut file, &[(IF_MATCH, "\"a\"")]), Action::PreconditionFailed);
+        assert_eq!(eval(&mut file, &[(IF_MATCH, "*")]), Action::Full);
+        assert_eq!(eval(&mut file, &[(IF_MODIFIED_SINCE, &date)]), Action::Full);
+        assert_eq!(
+            eval(&mut file, &[(RANGE, "bytes=0-1"), (IF_RANGE, &date)]),
+            Action::Full
+        );
+    }
+}
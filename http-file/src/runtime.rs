@@ -8,7 +8,7 @@ use std::{
     time::SystemTime,
 };
 
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 
 /// trait for generic over async file systems.
 pub trait AsyncFs {
@@ -19,6 +19,24 @@ pub trait AsyncFs {
     fn open(&self, path: PathBuf) -> Self::OpenFuture;
 }
 
+/// trait for generic over async file systems with write capability.
+///
+/// separated from [AsyncFs] so read only file serving does not require write capability.
+pub trait AsyncFsWrite {
+    type File: ChunkWrite;
+    type OpenFuture: Future<Output = io::Result<Self::File>>;
+    type RemoveFuture: Future<Output = io::Result<()>>;
+
+    /// create a file at given path for writing. existing file would be truncated.
+    fn create(&self, path: PathBuf) -> Self::OpenFuture;
+
+    /// open a file at given path for appending. file would be created if it does not exist.
+    fn append(&self, path: PathBuf) -> Self::OpenFuture;
+
+    /// remove a file at given path.
+    fn remove(&self, path: PathBuf) -> Self::RemoveFuture;
+}
+
 /// trait for generic over file metadata.
 pub trait Meta {
     /// the last time when file is modified. optional
@@ -53,14 +71,29 @@ pub trait ChunkRead: Sized {
     fn next(self, buf: BytesMut) -> Self::Future;
 }
 
+/// trait for async chunk write to file.
+pub trait ChunkWrite: Sized {
+    type Future: Future<Output = io::Result<Self>>;
+
+    type CloseFuture: Future<Output = io::Result<()>>;
+
+    /// async write of all bytes from given [Bytes] into Self.
+    /// return Ok(Self) after all bytes are written.
+    /// return Err(io::Error) when write error occur.
+    fn write(self, buf: Bytes) -> Self::Future;
+
+    /// flush written bytes to file system and close Self.
+    fn close(self) -> Self::CloseFuture;
+}
+
 #[cfg(feature = "tokio")]
-pub(crate) use tokio_impl::TokioFs;
+pub use tokio_impl::{TokioFile, TokioFs, TokioWriteFile};
 
 #[cfg(feature = "tokio")]
 mod tokio_impl {
     use tokio::{
-        fs::File,
-        io::{AsyncReadExt, AsyncSeekExt},
+        fs::{File, OpenOptions},
+        io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
     };
 
     use super::*;
@@ -91,6 +124,7 @@ mod tokio_impl {
         }
     }
 
+    /// file opened by [TokioFs] for reading.
     pub struct TokioFile {
         file: File,
         modified_time: Option<SystemTime>,
@@ -130,14 +164,65 @@ mod tokio_impl {
             }
         }
     }
+
+    impl AsyncFsWrite for TokioFs {
+        type File = TokioWriteFile;
+        type OpenFuture = impl Future<Output = io::Result<Self::File>> + Send;
+        type RemoveFuture = impl Future<Output = io::Result<()>> + Send;
+
+        fn create(&self, path: PathBuf) -> Self::OpenFuture {
+            open_write(path, false)
+        }
+
+        fn append(&self, path: PathBuf) -> Self::OpenFuture {
+            open_write(path, true)
+        }
+
+        fn remove(&self, path: PathBuf) -> Self::RemoveFuture {
+            tokio::fs::remove_file(path)
+        }
+    }
+
+    async fn open_write(path: PathBuf, append: bool) -> io::Result<TokioWriteFile> {
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)
+            .await
+            .map(|file| TokioWriteFile { file })
+    }
+
+    /// file opened by [TokioFs] for writing.
+    pub struct TokioWriteFile {
+        file: File,
+    }
+
+    impl ChunkWrite for TokioWriteFile {
+        type Future = impl Future<Output = io::Result<Self>> + Send;
+        type CloseFuture = impl Future<Output = io::Result<()>> + Send;
+
+        fn write(mut self, buf: Bytes) -> Self::Future {
+            async move {
+                self.file.write_all(&buf).await?;
+                Ok(self)
+            }
+        }
+
+        fn close(mut self) -> Self::CloseFuture {
+            // tokio file write is done in blocking thread pool. flush to wait for the completion.
+            async move { self.file.flush().await }
+        }
+    }
 }
 
 #[cfg(feature = "tokio-uring")]
-pub(crate) use tokio_uring_impl::TokioUringFs;
+pub use tokio_uring_impl::{TokioUringFile, TokioUringFs, TokioUringWriteFile};
 
 #[cfg(feature = "tokio-uring")]
 mod tokio_uring_impl {
-    use tokio_uring::fs::File;
+    use tokio_uring::fs::{File, OpenOptions};
 
     use super::*;
 
@@ -177,6 +262,7 @@ mod tokio_uring_impl {
         }
     }
 
+    /// file opened by [TokioUringFs] for reading.
     pub struct TokioUringFile {
         file: File,
         pos: u64,
@@ -223,4 +309,59 @@ mod tokio_uring_impl {
             }
         }
     }
+
+    impl AsyncFsWrite for TokioUringFs {
+        type File = TokioUringWriteFile;
+        type OpenFuture = impl Future<Output = io::Result<Self::File>>;
+        type RemoveFuture = impl Future<Output = io::Result<()>>;
+
+        fn create(&self, path: PathBuf) -> Self::OpenFuture {
+            open_write(path, false)
+        }
+
+        fn append(&self, path: PathBuf) -> Self::OpenFuture {
+            open_write(path, true)
+        }
+
+        fn remove(&self, path: PathBuf) -> Self::RemoveFuture {
+            tokio_uring::fs::remove_file(path)
+        }
+    }
+
+    async fn open_write(path: PathBuf, append: bool) -> io::Result<TokioUringWriteFile> {
+        // file opened with append flag ignores write position and always write to the end of file.
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)
+            .await
+            .map(|file| TokioUringWriteFile { file, pos: 0 })
+    }
+
+    /// file opened by [TokioUringFs] for writing.
+    pub struct TokioUringWriteFile {
+        file: File,
+        pos: u64,
+    }
+
+    impl ChunkWrite for TokioUringWriteFile {
+        type Future = impl Future<Output = io::Result<Self>>;
+        type CloseFuture = impl Future<Output = io::Result<()>>;
+
+        fn write(mut self, buf: Bytes) -> Self::Future {
+            async {
+                let len = buf.len() as u64;
+                let (res, _) = self.file.write_all_at(buf, self.pos).await;
+                res?;
+                self.pos += len;
+                Ok(self)
+            }
+        }
+
+        fn close(self) -> Self::CloseFuture {
+            self.file.close()
+        }
+    }
 }
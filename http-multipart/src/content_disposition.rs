@@ -27,17 +27,24 @@ impl ContentDisposition {
         Ok(Self::from_slice(header))
     }
 
+    // part of nested multipart can have arbitrary disposition type or no header at all.
+    pub(super) fn from_header_opt(headers: &HeaderMap) -> Self {
+        match headers.get(&CONTENT_DISPOSITION) {
+            Some(header) => Self::from_slice(header.as_bytes()),
+            None => Self {
+                name_indice: None,
+                filename_indice: None,
+            },
+        }
+    }
+
     pub(super) fn name_from_headers<'h>(&self, headers: &'h HeaderMap) -> Option<&'h [u8]> {
-        let header = headers
-            .get(&CONTENT_DISPOSITION)
-            .expect("ContentDisposition::try_from_header must make sure header exists");
+        let header = headers.get(&CONTENT_DISPOSITION)?;
         self.name(header.as_bytes())
     }
 
     pub(super) fn filename_from_headers<'h>(&self, headers: &'h HeaderMap) -> Option<&'h [u8]> {
-        let header = headers
-            .get(&CONTENT_DISPOSITION)
-            .expect("ContentDisposition::try_from_header must make sure header exists");
+        let header = headers.get(&CONTENT_DISPOSITION)?;
         self.filename(header.as_bytes())
     }
 
@@ -12,8 +12,11 @@ pub enum MultipartError {
     ParseContentType,
     /// Multipart boundary is not found
     Boundary,
-    /// Nested multipart is not supported
+    /// Nested multipart exceeds [Config::max_depth](crate::Config::max_depth)
     Nested,
+    /// Field size does not match it's Content-Length header.
+    /// Only produced when [Config::enforce_content_length](crate::Config::enforce_content_length) is enabled.
+    ContentLength,
     /// Multipart stream is incomplete
     UnexpectedEof,
     /// Multipart parsing internal buffer overflown
@@ -34,7 +37,8 @@ impl fmt::Display for MultipartError {
             Self::NoContentType => f.write_str("No Content-Type header found"),
             Self::ParseContentType => f.write_str("Can not parse Content-Type header"),
             Self::Boundary => f.write_str("Multipart boundary is not found"),
-            Self::Nested => f.write_str("Nested multipart is not supported"),
+            Self::Nested => f.write_str("Nested multipart exceeds max depth"),
+            Self::ContentLength => f.write_str("Field size does not match Content-Length header"),
             Self::UnexpectedEof => f.write_str("Multipart stream ended early than expected."),
             Self::BufferOverflow => f.write_str("Multipart parsing internal buffer overflown"),
             Self::Header(ref e) => fmt::Display::fmt(e, f),
@@ -1,4 +1,9 @@
-use core::{cmp, pin::Pin};
+use core::{
+    cmp,
+    future::poll_fn,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
 
 use bytes::{Bytes, BytesMut};
 use futures_core::stream::Stream;
@@ -8,11 +13,19 @@ use memchr::memmem;
 use super::{
     content_disposition::ContentDisposition,
     error::{MultipartError, PayloadError},
-    Multipart,
+    header, Multipart,
 };
 
+/// A single field of [Multipart].
+///
+/// Dropping a field before it's fully consumed is allowed. Remaining data of it would be read and discarded
+/// by the following [Multipart::try_next] call in order to find the next boundary. See [Field::skip] for
+/// explicit alternative.
 pub struct Field<'a, S> {
     decoder: FieldDecoder,
+    length: Option<u64>,
+    // declared Content-Length and the count of bytes read when it's enforced.
+    enforce_length: Option<(u64, u64)>,
     cp: ContentDisposition,
     multipart: Pin<&'a mut Multipart<S>>,
 }
@@ -25,12 +38,20 @@ impl<S> Drop for Field<'_, S> {
 
 impl<'a, S> Field<'a, S> {
     pub(super) fn new(length: Option<u64>, cp: ContentDisposition, multipart: Pin<&'a mut Multipart<S>>) -> Self {
+        let mut enforce_length = None;
         let typ = match length {
+            // enforced length is checked against field size found by boundary.
+            Some(len) if multipart.config.enforce_content_length => {
+                enforce_length = Some((len, 0));
+                FieldDecoder::StreamBegin
+            }
             Some(len) => FieldDecoder::Fixed(len),
             None => FieldDecoder::StreamBegin,
         };
         Self {
             decoder: typ,
+            length,
+            enforce_length,
             cp,
             multipart,
         }
@@ -46,7 +67,7 @@ pub(super) enum FieldDecoder {
     StreamEnd,
 }
 
-impl<S, T, E> Field<'_, S>
+impl<'a, S, T, E> Field<'a, S>
 where
     S: Stream<Item = Result<T, E>>,
     T: AsRef<[u8]> + 'static,
@@ -70,7 +91,116 @@ where
         &self.multipart.headers
     }
 
+    /// The declared size of field found in [http::header::CONTENT_LENGTH] header.
+    pub fn content_length(&self) -> Option<u64> {
+        self.length
+    }
+
+    /// Check if the field is a nested multipart with `multipart/*` Content-Type header.
+    /// Nested multipart field can be converted with [Field::into_nested] to iterate through it's parts.
+    pub fn is_nested(&self) -> bool {
+        header::is_multipart(self.headers())
+    }
+
+    /// Convert nested multipart field into [Multipart] operating on the field's data with boundary
+    /// found in field's Content-Type header.
+    ///
+    /// Parts of nested multipart are not required to carry `form-data` Content-Disposition header.
+    /// [Field::name] and [Field::file_name] of them are [None] when the header is absent.
+    ///
+    /// # Errors
+    /// - [MultipartError::Nested] when the field is not nested multipart.
+    /// - [MultipartError::Boundary] when boundary can't be found from field's Content-Type header.
+    pub fn into_nested(self) -> Result<Multipart<NestedBody<'a, S>>, MultipartError> {
+        if !self.is_nested() {
+            return Err(MultipartError::Nested);
+        }
+
+        let boundary = header::boundary(self.headers())?.into();
+        let depth = self.multipart.depth + 1;
+        let config = self.multipart.config;
+
+        Ok(Multipart {
+            stream: NestedBody {
+                field: self,
+                eof: false,
+            },
+            buf: BytesMut::new(),
+            boundary,
+            headers: HeaderMap::new(),
+            pending_field: false,
+            depth,
+            config,
+        })
+    }
+
     pub async fn try_next(&mut self) -> Result<Option<Bytes>, MultipartError> {
+        poll_fn(|cx| self.poll_try_next(cx)).await
+    }
+
+    /// Consume and discard remaining data of the field until next boundary. Return the count of bytes skipped.
+    ///
+    /// It's equivalent to dropping the field but the cost of reading through it is paid explicitly. It's
+    /// useful for early rejecting unwanted field (e.g. field name not allowed) while keep iterating through
+    /// following fields.
+    ///
+    /// When field has Content-Length header and [Config::enforce_content_length](crate::Config::enforce_content_length)
+    /// is disabled it's skipped by the declared length without looking for boundary.
+    ///
+    /// # Errors
+    /// Same as [Field::try_next]. [MultipartError::ContentLength] is returned when declared length is
+    /// enforced and it does not match the count of bytes skipped.
+    ///
+    /// # Examples:
+    /// ```rust
+    /// # use std::{convert::Infallible, pin::Pin};
+    /// # use futures_core::stream::Stream;
+    /// # use http_multipart::{Multipart, MultipartError};
+    /// async fn handle<B>(mut multipart: Pin<&mut Multipart<B>>) -> Result<(), MultipartError>
+    /// where
+    ///     B: Stream<Item = Result<Vec<u8>, Infallible>>
+    /// {
+    ///     while let Some(mut field) = multipart.try_next().await? {
+    ///         if field.name() != Some("allowed") {
+    ///             field.skip().await?;
+    ///             continue;
+    ///         }
+    ///         while let Some(chunk) = field.try_next().await? {
+    ///             // handle bytes data.
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn skip(mut self) -> Result<u64, MultipartError> {
+        let mut skipped = 0;
+        while let Some(bytes) = self.try_next().await? {
+            skipped += bytes.len() as u64;
+        }
+        Ok(skipped)
+    }
+
+    fn poll_try_next(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Bytes>, MultipartError>> {
+        let res = ready!(self.poll_decode(cx));
+
+        if let (Ok(res), Some((declared, read))) = (&res, self.enforce_length.as_mut()) {
+            let declared = *declared;
+            match res {
+                Some(bytes) => {
+                    *read += bytes.len() as u64;
+                    if *read > declared {
+                        return Poll::Ready(Err(MultipartError::ContentLength));
+                    }
+                }
+                None if *read != declared => return Poll::Ready(Err(MultipartError::ContentLength)),
+                None => {}
+            }
+        }
+
+        Poll::Ready(res)
+    }
+
+    fn poll_decode(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Bytes>, MultipartError>> {
         loop {
             let multipart = self.multipart.as_mut().project();
             let buf = multipart.buf;
@@ -80,24 +210,24 @@ where
                 match self.decoder {
                     FieldDecoder::Fixed(0) | FieldDecoder::StreamEnd => {
                         *multipart.pending_field = false;
-                        return Ok(None);
+                        return Poll::Ready(Ok(None));
                     }
                     FieldDecoder::Fixed(ref mut len) => {
                         let at = cmp::min(*len, buf.len() as u64);
                         *len -= at;
                         let chunk = buf.split_to(at as usize).freeze();
-                        return Ok(Some(chunk));
+                        return Poll::Ready(Ok(Some(chunk)));
                     }
                     FieldDecoder::StreamBegin | FieldDecoder::StreamPossibleEnd => {
                         if let Some(at) = self.decoder.try_find_split_idx(buf, multipart.boundary)? {
-                            return Ok(Some(buf.split_to(at).freeze()));
+                            return Poll::Ready(Ok(Some(buf.split_to(at).freeze())));
                         }
                     }
                 }
             }
 
             // multipart buffer is empty. read more from stream.
-            let item = self.multipart.as_mut().try_read_stream().await?;
+            let item = ready!(self.multipart.as_mut().poll_read_stream(cx))?;
 
             let multipart = self.multipart.as_mut().project();
             let buf = multipart.buf;
@@ -107,19 +237,19 @@ where
                 FieldDecoder::Fixed(0) => {
                     buf.extend_from_slice(item.as_ref());
                     *multipart.pending_field = false;
-                    return Ok(None);
+                    return Poll::Ready(Ok(None));
                 }
                 FieldDecoder::Fixed(ref mut len) => {
                     let chunk = item.as_ref();
                     let at = cmp::min(*len, chunk.len() as u64);
                     *len -= at;
                     let bytes = split_bytes(item, at as usize, buf);
-                    return Ok(Some(bytes));
+                    return Poll::Ready(Ok(Some(bytes)));
                 }
                 FieldDecoder::StreamBegin => match self.decoder.try_find_split_idx(&item, multipart.boundary)? {
                     Some(at) => {
                         let bytes = split_bytes(item, at, buf);
-                        return Ok(Some(bytes));
+                        return Poll::Ready(Ok(Some(bytes)));
                     }
                     None => buf.extend_from_slice(item.as_ref()),
                 },
@@ -127,13 +257,61 @@ where
                 FieldDecoder::StreamPossibleEnd => buf.extend_from_slice(item.as_ref()),
                 FieldDecoder::StreamEnd => {
                     *multipart.pending_field = false;
-                    return Ok(None);
+                    return Poll::Ready(Ok(None));
                 }
             }
         }
     }
 }
 
+impl<S, T, E> Stream for Field<'_, S>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: AsRef<[u8]> + 'static,
+    E: Into<PayloadError>,
+{
+    type Item = Result<Bytes, MultipartError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_try_next(cx).map(Result::transpose)
+    }
+}
+
+/// Body stream of nested multipart. See [Field::into_nested] for detail.
+pub struct NestedBody<'a, S> {
+    field: Field<'a, S>,
+    eof: bool,
+}
+
+impl<S, T, E> Stream for NestedBody<'_, S>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: AsRef<[u8]> + 'static,
+    E: Into<PayloadError>,
+{
+    type Item = Result<Bytes, MultipartError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.eof {
+            return Poll::Ready(None);
+        }
+        match ready!(this.field.poll_try_next(cx)) {
+            Ok(Some(bytes)) => Poll::Ready(Some(Ok(bytes))),
+            // CRLF preceding the boundary of parent multipart belongs to it and the closing boundary
+            // of nested multipart is not terminated with line feed. append one so it can be parsed.
+            Ok(None) => {
+                this.eof = true;
+                Poll::Ready(Some(Ok(Bytes::from_static(b"\r\n"))))
+            }
+            Err(e) => {
+                this.eof = true;
+                Poll::Ready(Some(Err(e)))
+            }
+        }
+    }
+}
+
 impl FieldDecoder {
     pub(super) fn try_find_split_idx<T>(&mut self, item: &T, boundary: &[u8]) -> Result<Option<usize>, MultipartError>
     where
@@ -148,9 +326,10 @@ impl FieldDecoder {
 
                 let slice = &item[start..end];
 
-                // not boundary so split till end offset.
+                // not boundary so split till the double hyphen. bytes after it can be the start of a
+                // real boundary. (e.g. `--\r\n--boundary`)
                 if !boundary.starts_with(slice) {
-                    return Ok(Some(end));
+                    return Ok(Some(start));
                 }
 
                 // possible boundary but no full view yet.
@@ -205,6 +384,20 @@ fn try_downcast_to_bytes<T: 'static>(item: T) -> Result<Bytes, T> {
 mod test {
     use super::*;
 
+    #[test]
+    fn split_idx_double_hyphen_before_boundary() {
+        let mut decoder = FieldDecoder::default();
+        let boundary = b"AaB03x";
+        let item = b"data--\r\n--AaB03x\r\n";
+
+        // double hyphen not followed by boundary is split without consuming bytes after it.
+        assert_eq!(decoder.try_find_split_idx(item, boundary).unwrap(), Some(6));
+        assert!(matches!(decoder, FieldDecoder::StreamBegin));
+
+        assert_eq!(decoder.try_find_split_idx(&&item[6..], boundary).unwrap(), Some(0));
+        assert!(matches!(decoder, FieldDecoder::StreamEnd));
+    }
+
     #[test]
     fn downcast_bytes() {
         let bytes = Bytes::new();
@@ -39,21 +39,29 @@ pub(super) fn parse_headers(headers: &mut HeaderMap, slice: &[u8]) -> Result<(),
     }
 }
 
-pub(super) fn check_headers(headers: &HeaderMap) -> Result<(), MultipartError> {
+pub(super) fn check_headers(headers: &HeaderMap, allow_nested: bool) -> Result<(), MultipartError> {
     let ct = headers
         .get(&CONTENT_TYPE)
         .and_then(|ct| ct.to_str().ok())
         .and_then(|ct| ct.parse().ok())
         .unwrap_or(mime::APPLICATION_OCTET_STREAM);
 
-    // nested multipart stream is not supported
-    if ct.type_() == mime::MULTIPART {
+    // nested multipart stream exceeding max depth is not supported
+    if ct.type_() == mime::MULTIPART && !allow_nested {
         return Err(MultipartError::Nested);
     }
 
     Ok(())
 }
 
+pub(super) fn is_multipart(headers: &HeaderMap) -> bool {
+    headers
+        .get(&CONTENT_TYPE)
+        .and_then(|ct| ct.to_str().ok())
+        .and_then(|ct| ct.parse::<mime::Mime>().ok())
+        .is_some_and(|ct| ct.type_() == mime::MULTIPART)
+}
+
 pub(super) fn content_length_opt(headers: &HeaderMap) -> Result<Option<u64>, MultipartError> {
     match headers.get(&CONTENT_LENGTH) {
         Some(len) => {
@@ -4,10 +4,19 @@ mod content_disposition;
 mod error;
 mod field;
 mod header;
+mod writer;
 
-pub use self::{error::MultipartError, field::Field};
+pub use self::{
+    error::MultipartError,
+    field::{Field, NestedBody},
+    writer::{BoxStream, MultipartWriter, Part},
+};
 
-use core::{future::poll_fn, pin::Pin};
+use core::{
+    future::poll_fn,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
 
 use bytes::{Buf, BytesMut};
 use field::FieldDecoder;
@@ -87,6 +96,7 @@ where
         boundary: boundary.into(),
         headers: HeaderMap::new(),
         pending_field: false,
+        depth: 0,
         config,
     })
 }
@@ -98,11 +108,26 @@ pub struct Config {
     /// internal buffer is used to cache overlapped chunks around boundary and filed headers.
     /// Default to 1MB
     pub buf_limit: usize,
+    /// limit the max depth of nested multipart.
+    /// field with `multipart/*` Content-Type header nested deeper than the limit would produce
+    /// [MultipartError::Nested] error. 0 disables nested multipart.
+    /// Default to 1 which allows `multipart/mixed` inside `multipart/form-data` field.
+    pub max_depth: usize,
+    /// validate field size against it's Content-Length header.
+    /// When enabled field with Content-Length header is read until boundary and produce
+    /// [MultipartError::ContentLength] error when it's size does not match the declared length.
+    /// When disabled the declared length is trusted and field is read by it without looking for boundary.
+    /// Default to false.
+    pub enforce_content_length: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
-        Self { buf_limit: 1024 * 1024 }
+        Self {
+            buf_limit: 1024 * 1024,
+            max_depth: 1,
+            enforce_content_length: false,
+        }
     }
 }
 
@@ -114,6 +139,7 @@ pin_project! {
         boundary: Box<[u8]>,
         headers: HeaderMap,
         pending_field: bool,
+        depth: usize,
         config: Config
     }
 }
@@ -196,9 +222,14 @@ where
                 header::parse_headers(this.headers, slice)?;
                 this.buf.advance(slice.len());
 
-                let cp = ContentDisposition::try_from_header(this.headers)?;
+                // nested multipart part is not required to be form-data.
+                let cp = if *this.depth == 0 {
+                    ContentDisposition::try_from_header(this.headers)?
+                } else {
+                    ContentDisposition::from_header_opt(this.headers)
+                };
 
-                header::check_headers(this.headers)?;
+                header::check_headers(this.headers, *this.depth < this.config.max_depth)?;
 
                 let length = header::content_length_opt(this.headers)?;
 
@@ -240,10 +271,14 @@ where
     }
 
     async fn try_read_stream(mut self: Pin<&mut Self>) -> Result<T, MultipartError> {
-        match poll_fn(move |cx| self.as_mut().project().stream.poll_next(cx)).await {
-            Some(Ok(bytes)) => Ok(bytes),
-            Some(Err(e)) => Err(MultipartError::Payload(e.into())),
-            None => Err(MultipartError::UnexpectedEof),
+        poll_fn(move |cx| self.as_mut().poll_read_stream(cx)).await
+    }
+
+    pub(crate) fn poll_read_stream(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<T, MultipartError>> {
+        match ready!(self.project().stream.poll_next(cx)) {
+            Some(Ok(bytes)) => Poll::Ready(Ok(bytes)),
+            Some(Err(e)) => Poll::Ready(Err(MultipartError::Payload(e.into()))),
+            None => Poll::Ready(Err(MultipartError::UnexpectedEof)),
         }
     }
 
@@ -385,6 +420,257 @@ mod test {
         assert!(multipart.try_next().now_or_never().unwrap().unwrap().is_none());
     }
 
+    #[test]
+    fn nested() {
+        let body = b"\
+            --AaB03x\r\n\
+            Content-Disposition: form-data; name=\"submit-name\"\r\n\r\n\
+            Larry\r\n\
+            --AaB03x\r\n\
+            Content-Disposition: form-data; name=\"files\"\r\n\
+            Content-Type: multipart/mixed; boundary=BbC04y\r\n\r\n\
+            --BbC04y\r\n\
+            Content-Disposition: file; filename=\"file1.txt\"\r\n\
+            Content-Type: text/plain\r\n\r\n\
+            file1\r\n\
+            --BbC04y\r\n\
+            Content-Type: text/plain\r\n\r\n\
+            file2\r\n\
+            --BbC04y--\r\n\
+            --AaB03x\r\n\
+            Content-Disposition: form-data; name=\"last\"\r\n\r\n\
+            end\r\n\
+            --AaB03x--\r\n\
+            ";
+
+        let mut req = Request::new(());
+        *req.method_mut() = Method::POST;
+        req.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("multipart/form-data; boundary=AaB03x"),
+        );
+
+        let multipart = multipart(&req, once_body(Bytes::copy_from_slice(body))).unwrap();
+        let mut multipart = pin!(multipart);
+
+        {
+            let mut field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+            assert_eq!(field.name().unwrap(), "submit-name");
+            assert!(!field.is_nested());
+            assert_eq!(
+                field.try_next().now_or_never().unwrap().unwrap().unwrap().chunk(),
+                b"Larry"
+            );
+        }
+
+        {
+            let field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+            assert_eq!(field.name().unwrap(), "files");
+            assert!(field.is_nested());
+
+            let nested = field.into_nested().unwrap();
+            let mut nested = pin!(nested);
+
+            let mut field = nested.try_next().now_or_never().unwrap().unwrap().unwrap();
+            assert!(field.name().is_none());
+            assert_eq!(field.file_name().unwrap(), "file1.txt");
+            assert_eq!(
+                field.try_next().now_or_never().unwrap().unwrap().unwrap().chunk(),
+                b"file1"
+            );
+            assert!(field.try_next().now_or_never().unwrap().unwrap().is_none());
+            drop(field);
+
+            let mut field = nested.try_next().now_or_never().unwrap().unwrap().unwrap();
+            assert!(field.file_name().is_none());
+            assert_eq!(
+                field.try_next().now_or_never().unwrap().unwrap().unwrap().chunk(),
+                b"file2"
+            );
+            drop(field);
+
+            assert!(nested.try_next().now_or_never().unwrap().unwrap().is_none());
+        }
+
+        {
+            let mut field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+            assert_eq!(field.name().unwrap(), "last");
+            assert_eq!(
+                field.try_next().now_or_never().unwrap().unwrap().unwrap().chunk(),
+                b"end"
+            );
+        }
+
+        assert!(multipart.try_next().now_or_never().unwrap().unwrap().is_none());
+    }
+
+    #[test]
+    fn nested_max_depth() {
+        let body = b"\
+            --AaB03x\r\n\
+            Content-Disposition: form-data; name=\"files\"\r\n\
+            Content-Type: multipart/mixed; boundary=BbC04y\r\n\r\n\
+            --BbC04y\r\n\
+            Content-Type: multipart/mixed; boundary=CcD05z\r\n\r\n\
+            --CcD05z--\r\n\
+            --BbC04y--\r\n\
+            --AaB03x--\r\n\
+            ";
+
+        let mut req = Request::new(());
+        *req.method_mut() = Method::POST;
+        req.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("multipart/form-data; boundary=AaB03x"),
+        );
+
+        let config = Config {
+            max_depth: 0,
+            ..Default::default()
+        };
+        let multipart = multipart_with_config(&req, once_body(Bytes::copy_from_slice(body)), config).unwrap();
+        let mut multipart = pin!(multipart);
+        assert!(matches!(
+            multipart.try_next().now_or_never().unwrap().err().unwrap(),
+            MultipartError::Nested
+        ));
+
+        let multipart =
+            multipart_with_config(&req, once_body(Bytes::copy_from_slice(body)), Config::default()).unwrap();
+        let mut multipart = pin!(multipart);
+        let field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+        let nested = field.into_nested().unwrap();
+        let mut nested = pin!(nested);
+        assert!(matches!(
+            nested.try_next().now_or_never().unwrap().err().unwrap(),
+            MultipartError::Nested
+        ));
+    }
+
+    #[test]
+    fn enforce_content_length() {
+        let body = b"\
+            --12345\r\n\
+            Content-Disposition: form-data; name=\"exact\"\r\nContent-Length: 4\r\n\r\n\
+            test\r\n\
+            --12345\r\n\
+            Content-Disposition: form-data; name=\"longer\"\r\nContent-Length: 4\r\n\r\n\
+            test1\r\n\
+            --12345\r\n\
+            Content-Disposition: form-data; name=\"shorter\"\r\nContent-Length: 9\r\n\r\n\
+            test\r\n\
+            --12345--\r\n\
+            ";
+
+        let mut req = Request::new(());
+        *req.method_mut() = Method::POST;
+        req.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("multipart/form-data; boundary=12345"),
+        );
+
+        let config = Config {
+            enforce_content_length: true,
+            ..Default::default()
+        };
+        let multipart = multipart_with_config(&req, once_body(Bytes::copy_from_slice(body)), config).unwrap();
+        let mut multipart = pin!(multipart);
+
+        {
+            let mut field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+            assert_eq!(
+                field.try_next().now_or_never().unwrap().unwrap().unwrap().chunk(),
+                b"test"
+            );
+            assert!(field.try_next().now_or_never().unwrap().unwrap().is_none());
+        }
+
+        {
+            let mut field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+            assert!(matches!(
+                field.try_next().now_or_never().unwrap().err().unwrap(),
+                MultipartError::ContentLength
+            ));
+        }
+
+        {
+            let mut field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+            assert_eq!(
+                field.try_next().now_or_never().unwrap().unwrap().unwrap().chunk(),
+                b"test"
+            );
+            assert!(matches!(
+                field.try_next().now_or_never().unwrap().err().unwrap(),
+                MultipartError::ContentLength
+            ));
+        }
+
+        assert!(multipart.try_next().now_or_never().unwrap().unwrap().is_none());
+    }
+
+    #[test]
+    fn skip() {
+        let body = b"\
+            --12345\r\n\
+            Content-Disposition: form-data; name=\"fixed\"\r\nContent-Length: 9\r\n\r\n\
+            testdata1\r\n\
+            --12345\r\n\
+            Content-Disposition: form-data; name=\"stream\"\r\n\r\n\
+            testdata--2\r\n\
+            --12345\r\n\
+            Content-Disposition: form-data; name=\"partial\"\r\n\r\n\
+            testdata3\r\n\
+            --12345\r\n\
+            Content-Disposition: form-data; name=\"last\"\r\n\r\n\
+            end\r\n\
+            --12345--\r\n\
+            ";
+
+        let mut req = Request::new(());
+        *req.method_mut() = Method::POST;
+        req.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("multipart/form-data; boundary=12345"),
+        );
+
+        // split body into small chunks so field spans across multiple reads.
+        let body = futures_util::stream::iter(
+            body.split_inclusive(|b| *b == b'a')
+                .map(|chunk| Ok::<_, Infallible>(Bytes::copy_from_slice(chunk)))
+                .collect::<Vec<_>>(),
+        );
+
+        let multipart = multipart(&req, body).unwrap();
+        let mut multipart = pin!(multipart);
+
+        let field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(field.name().unwrap(), "fixed");
+        assert_eq!(field.content_length(), Some(9));
+        assert_eq!(field.skip().now_or_never().unwrap().unwrap(), 9);
+
+        let field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(field.name().unwrap(), "stream");
+        assert!(field.content_length().is_none());
+        assert_eq!(field.skip().now_or_never().unwrap().unwrap(), 11);
+
+        // skip partially consumed field.
+        let mut field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(field.name().unwrap(), "partial");
+        let read = field.try_next().now_or_never().unwrap().unwrap().unwrap().len() as u64;
+        assert_eq!(read + field.skip().now_or_never().unwrap().unwrap(), 9);
+
+        let mut field = multipart.try_next().now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(field.name().unwrap(), "last");
+        let mut data = Vec::new();
+        while let Some(chunk) = field.try_next().now_or_never().unwrap().unwrap() {
+            data.extend_from_slice(&chunk);
+        }
+        assert_eq!(data, b"end");
+        drop(field);
+
+        assert!(multipart.try_next().now_or_never().unwrap().unwrap().is_none());
+    }
+
     #[test]
     fn field_header_overflow() {
         let body = b"\
@@ -402,7 +688,15 @@ mod test {
         let body = once_body(Bytes::copy_from_slice(body));
 
         // limit is set to 7 so the first boundary can be parsed.
-        let multipart = multipart_with_config(&req, body, Config { buf_limit: 7 }).unwrap();
+        let multipart = multipart_with_config(
+            &req,
+            body,
+            Config {
+                buf_limit: 7,
+                ..Default::default()
+            },
+        )
+        .unwrap();
 
         let mut multipart = pin!(multipart);
 
@@ -426,7 +720,15 @@ mod test {
         let body = once_body(Bytes::copy_from_slice(body));
 
         // limit is set to 7 so the first boundary can not be parsed.
-        let multipart = multipart_with_config(&req, body, Config { buf_limit: 7 }).unwrap();
+        let multipart = multipart_with_config(
+            &req,
+            body,
+            Config {
+                buf_limit: 7,
+                ..Default::default()
+            },
+        )
+        .unwrap();
 
         let mut multipart = pin!(multipart);
 
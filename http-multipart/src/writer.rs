@@ -0,0 +1,420 @@
+use core::{
+    mem,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use std::{
+    collections::{hash_map::RandomState, VecDeque},
+    hash::{BuildHasher, Hasher},
+};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use futures_core::stream::Stream;
+use http::header::HeaderValue;
+
+use super::error::{MultipartError, PayloadError};
+
+/// type erased byte stream. default stream type of [MultipartWriter] and [Part] where parts with different
+/// stream types can be mixed together.
+pub type BoxStream = Pin<Box<dyn Stream<Item = Result<Bytes, PayloadError>> + Send>>;
+
+/// Multipart body encoder. The counterpart of [multipart](crate::multipart) that produce a well formed
+/// `multipart/form-data` body as [Stream] of bytes.
+///
+/// # Examples:
+/// ```rust
+/// use http::{header::CONTENT_TYPE, HeaderValue, Method, Request};
+/// use http_multipart::{MultipartWriter, Part};
+///
+/// let mut writer = MultipartWriter::new();
+///
+/// writer
+///     .add_field("name", "foo")
+///     .add_part(
+///         Part::bytes("file", "file content")
+///             .file_name("foo.txt")
+///             .content_type(HeaderValue::from_static("text/plain")),
+///     );
+///
+/// // Content-Type header containing boundary of the body.
+/// let mut req = Request::new(());
+/// *req.method_mut() = Method::POST;
+/// req.headers_mut().insert(CONTENT_TYPE, writer.content_type());
+///
+/// // writer is a Stream of bytes. it can be used as request body directly.
+/// let (parts, _) = req.into_parts();
+/// let req = Request::from_parts(parts, writer);
+/// ```
+pub struct MultipartWriter<S = BoxStream> {
+    boundary: Box<str>,
+    parts: VecDeque<Part<S>>,
+    state: State<S>,
+    started: bool,
+}
+
+enum State<S> {
+    Next,
+    Bytes(Bytes),
+    Stream(S),
+    Done,
+}
+
+impl MultipartWriter {
+    /// construct a new writer with random generated boundary.
+    ///
+    /// parts with stream body would be type erased with [BoxStream]. use [MultipartWriter::default] for
+    /// writer with concrete stream type.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S> Default for MultipartWriter<S> {
+    fn default() -> Self {
+        Self::_new(random_boundary())
+    }
+}
+
+impl<S> MultipartWriter<S> {
+    /// construct a new writer with given boundary.
+    ///
+    /// boundary must be 1 to 70 characters long and only contain characters allowed by RFC 2046. it must
+    /// not appear in any part's body.
+    ///
+    /// # Errors
+    /// When boundary is not valid [MultipartError::Boundary] is returned.
+    pub fn with_boundary(boundary: &str) -> Result<Self, MultipartError> {
+        let valid = (1..=70).contains(&boundary.len())
+            && boundary
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b"'()+_,-./:=?".contains(&b));
+
+        if !valid {
+            return Err(MultipartError::Boundary);
+        }
+
+        Ok(Self::_new(boundary.into()))
+    }
+
+    fn _new(boundary: Box<str>) -> Self {
+        Self {
+            boundary,
+            parts: VecDeque::new(),
+            state: State::Next,
+            started: false,
+        }
+    }
+
+    /// boundary of the multipart body.
+    #[inline]
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// value of Content-Type header for the multipart body. in form of `multipart/form-data; boundary=<boundary>`.
+    pub fn content_type(&self) -> HeaderValue {
+        let value = format!("multipart/form-data; boundary={}", self.boundary);
+        HeaderValue::try_from(value).expect("boundary is checked to be valid header value")
+    }
+
+    /// append a text field with given name and value.
+    pub fn add_field(&mut self, name: impl Into<Box<str>>, value: impl Into<Bytes>) -> &mut Self {
+        self.add_part(Part::bytes(name, value))
+    }
+
+    /// append a [Part] to the multipart body. parts are written in the order they are added.
+    pub fn add_part(&mut self, part: Part<S>) -> &mut Self {
+        self.parts.push_back(part);
+        self
+    }
+}
+
+/// A single part of [MultipartWriter].
+pub struct Part<S = BoxStream> {
+    name: Box<str>,
+    file_name: Option<Box<str>>,
+    content_type: Option<HeaderValue>,
+    body: State<S>,
+}
+
+impl<S> Part<S> {
+    /// construct a part with in memory bytes as body.
+    pub fn bytes(name: impl Into<Box<str>>, bytes: impl Into<Bytes>) -> Self {
+        Self::_new(name.into(), State::Bytes(bytes.into()))
+    }
+
+    /// construct a part with a stream of bytes as body.
+    pub fn stream(name: impl Into<Box<str>>, stream: S) -> Self {
+        Self::_new(name.into(), State::Stream(stream))
+    }
+
+    fn _new(name: Box<str>, body: State<S>) -> Self {
+        Self {
+            name,
+            file_name: None,
+            content_type: None,
+            body,
+        }
+    }
+
+    /// set filename of Content-Disposition header of the part.
+    pub fn file_name(mut self, file_name: impl Into<Box<str>>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// set Content-Type header of the part.
+    pub fn content_type(mut self, content_type: HeaderValue) -> Self {
+        self.content_type = Some(content_type);
+        self
+    }
+
+    fn encode_head(&self, buf: &mut BytesMut) {
+        buf.put_slice(b"Content-Disposition: form-data; name=\"");
+        escape_quoted(&self.name, buf);
+        buf.put_u8(b'"');
+        if let Some(ref file_name) = self.file_name {
+            buf.put_slice(b"; filename=\"");
+            escape_quoted(file_name, buf);
+            buf.put_u8(b'"');
+        }
+        buf.put_slice(b"\r\n");
+        if let Some(ref ct) = self.content_type {
+            buf.put_slice(b"Content-Type: ");
+            buf.put_slice(ct.as_bytes());
+            buf.put_slice(b"\r\n");
+        }
+        buf.put_slice(b"\r\n");
+    }
+}
+
+impl<S, T, E> Stream for MultipartWriter<S>
+where
+    S: Stream<Item = Result<T, E>> + Unpin,
+    T: Into<Bytes>,
+{
+    type Item = Result<Bytes, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match this.state {
+                State::Next => {
+                    let mut buf = BytesMut::new();
+                    // CRLF ending previous part's body is part of the delimiter.
+                    if mem::replace(&mut this.started, true) {
+                        buf.put_slice(b"\r\n");
+                    }
+                    buf.put_slice(b"--");
+                    buf.put_slice(this.boundary.as_bytes());
+                    match this.parts.pop_front() {
+                        Some(part) => {
+                            buf.put_slice(b"\r\n");
+                            part.encode_head(&mut buf);
+                            this.state = part.body;
+                        }
+                        None => {
+                            buf.put_slice(b"--\r\n");
+                            this.state = State::Done;
+                        }
+                    }
+                    return Poll::Ready(Some(Ok(buf.freeze())));
+                }
+                State::Bytes(ref mut bytes) => {
+                    let bytes = mem::take(bytes);
+                    this.state = State::Next;
+                    if !bytes.is_empty() {
+                        return Poll::Ready(Some(Ok(bytes)));
+                    }
+                }
+                State::Stream(ref mut stream) => match ready!(Pin::new(stream).poll_next(cx)) {
+                    Some(Ok(bytes)) => return Poll::Ready(Some(Ok(bytes.into()))),
+                    Some(Err(e)) => {
+                        this.state = State::Done;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    None => this.state = State::Next,
+                },
+                State::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+// percent encode characters that can not appear in quoted string of Content-Disposition header.
+fn escape_quoted(value: &str, buf: &mut BytesMut) {
+    for b in value.bytes() {
+        match b {
+            b'"' => buf.put_slice(b"%22"),
+            b'\r' => buf.put_slice(b"%0D"),
+            b'\n' => buf.put_slice(b"%0A"),
+            b => buf.put_u8(b),
+        }
+    }
+}
+
+fn random_boundary() -> Box<str> {
+    // RandomState is randomly seeded and it's keys are changed for every new instance.
+    let state = RandomState::new();
+    let mut hasher = state.build_hasher();
+    let a = hasher.finish();
+    hasher.write_u64(a);
+    let b = hasher.finish();
+    format!("{a:016x}{b:016x}").into()
+}
+
+#[cfg(test)]
+mod test {
+    use core::convert::Infallible;
+
+    use std::pin::pin;
+
+    use futures_util::{stream, FutureExt, StreamExt};
+    use http::{header::CONTENT_TYPE, Method, Request};
+
+    use crate::multipart;
+
+    use super::*;
+
+    fn collect<S>(mut writer: S) -> Vec<S::Item>
+    where
+        S: Stream + Unpin,
+    {
+        let mut chunks = Vec::new();
+        while let Some(chunk) = writer.next().now_or_never().unwrap() {
+            chunks.push(chunk);
+        }
+        chunks
+    }
+
+    fn request(content_type: HeaderValue) -> Request<()> {
+        let mut req = Request::new(());
+        *req.method_mut() = Method::POST;
+        req.headers_mut().insert(CONTENT_TYPE, content_type);
+        req
+    }
+
+    #[test]
+    fn boundary() {
+        assert!(MultipartWriter::<BoxStream>::with_boundary("").is_err());
+        assert!(MultipartWriter::<BoxStream>::with_boundary(&"a".repeat(71)).is_err());
+        assert!(MultipartWriter::<BoxStream>::with_boundary("ab\r\ncd").is_err());
+
+        let writer = MultipartWriter::<BoxStream>::with_boundary("AaB03x").unwrap();
+        assert_eq!(writer.boundary(), "AaB03x");
+        assert_eq!(writer.content_type(), "multipart/form-data; boundary=AaB03x");
+
+        assert_ne!(MultipartWriter::new().boundary(), MultipartWriter::new().boundary());
+    }
+
+    #[test]
+    fn encode() {
+        let mut writer = MultipartWriter::<BoxStream>::with_boundary("AaB03x").unwrap();
+        writer.add_field("name", "Larry").add_part(
+            Part::bytes("file", "file1")
+                .file_name("a \"b\".txt")
+                .content_type(HeaderValue::from_static("text/plain")),
+        );
+
+        let body = collect(writer)
+            .into_iter()
+            .map(Result::unwrap)
+            .fold(Vec::new(), |mut body, chunk| {
+                body.extend_from_slice(&chunk);
+                body
+            });
+
+        assert_eq!(
+            body,
+            b"\
+            --AaB03x\r\n\
+            Content-Disposition: form-data; name=\"name\"\r\n\r\n\
+            Larry\r\n\
+            --AaB03x\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"a %22b%22.txt\"\r\n\
+            Content-Type: text/plain\r\n\r\n\
+            file1\r\n\
+            --AaB03x--\r\n\
+            "
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let chunks = ["stream", "ed ", "data"].map(|s| Ok::<_, Infallible>(Bytes::from_static(s.as_bytes())));
+
+        let mut writer = MultipartWriter::default();
+        writer
+            .add_field("text", "value")
+            .add_field("empty", Bytes::new())
+            .add_part(
+                Part::bytes("file", "file content")
+                    .file_name("foo.txt")
+                    .content_type(HeaderValue::from_static("text/plain; charset=utf-8")),
+            )
+            .add_part(Part::stream("stream", stream::iter(chunks)).file_name("bar.bin"));
+
+        let req = request(writer.content_type());
+        let body = stream::iter(collect(writer));
+
+        let multipart = multipart(&req, body).unwrap();
+        let mut multipart = pin!(multipart);
+
+        let mut fields = Vec::new();
+        while let Some(mut field) = multipart.try_next().now_or_never().unwrap().unwrap() {
+            let name = field.name().unwrap().to_owned();
+            let file_name = field.file_name().map(ToOwned::to_owned);
+            let content_type = field.headers().get(CONTENT_TYPE).cloned();
+            let mut data = Vec::new();
+            while let Some(chunk) = field.try_next().now_or_never().unwrap().unwrap() {
+                data.extend_from_slice(&chunk);
+            }
+            fields.push((name, file_name, content_type, data));
+        }
+
+        assert_eq!(fields.len(), 4);
+
+        assert_eq!(fields[0].0, "text");
+        assert!(fields[0].1.is_none());
+        assert!(fields[0].2.is_none());
+        assert_eq!(fields[0].3, b"value");
+
+        assert_eq!(fields[1].0, "empty");
+        assert!(fields[1].3.is_empty());
+
+        assert_eq!(fields[2].0, "file");
+        assert_eq!(fields[2].1.as_deref(), Some("foo.txt"));
+        assert_eq!(fields[2].2.as_ref().unwrap(), "text/plain; charset=utf-8");
+        assert_eq!(fields[2].3, b"file content");
+
+        assert_eq!(fields[3].0, "stream");
+        assert_eq!(fields[3].1.as_deref(), Some("bar.bin"));
+        assert_eq!(fields[3].3, b"streamed data");
+    }
+
+    #[test]
+    fn round_trip_empty() {
+        let writer = MultipartWriter::new();
+        let req = request(writer.content_type());
+        let body = stream::iter(collect(writer));
+
+        let multipart = multipart(&req, body).unwrap();
+        let mut multipart = pin!(multipart);
+        assert!(multipart.try_next().now_or_never().unwrap().unwrap().is_none());
+    }
+
+    #[test]
+    fn stream_error() {
+        let chunks = [Ok(Bytes::from_static(b"data")), Err(())];
+
+        let mut writer = MultipartWriter::default();
+        writer.add_part(Part::stream("stream", stream::iter(chunks)));
+
+        let mut chunks = collect(writer).into_iter();
+        assert!(chunks.next().unwrap().is_ok());
+        assert_eq!(chunks.next().unwrap().unwrap(), "data");
+        assert!(chunks.next().unwrap().is_err());
+        assert!(chunks.next().is_none());
+    }
+}
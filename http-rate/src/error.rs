@@ -60,6 +60,34 @@ impl fmt::Display for InsufficientCapacity {
 
 impl error::Error for InsufficientCapacity {}
 
+/// Error happen when parsing [Quota](crate::Quota) from string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseQuotaError {
+    /// string is not in `<cells>/<unit>[;burst=<cells>]` form.
+    Format,
+    /// number of cells is not a positive integer fitting in u32.
+    Cells,
+    /// time unit is not one of `s`, `min` or `hour` and their aliases.
+    Unit,
+    /// burst size is not a positive integer fitting in u32.
+    Burst,
+}
+
+impl fmt::Display for ParseQuotaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            Self::Format => "quota must be in form of <cells>/<unit>[;burst=<cells>]",
+            Self::Cells => "quota cells must be positive integer",
+            Self::Unit => "quota unit must be one of s, min, hour or day",
+            Self::Burst => "quota burst must be positive integer",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl error::Error for ParseQuotaError {}
+
 #[cfg(test)]
 mod test {
     use super::InsufficientCapacity;
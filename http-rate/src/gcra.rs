@@ -2,10 +2,8 @@ use core::{cmp, fmt, time::Duration};
 
 use crate::{nanos::Nanos, quota::Quota, snapshot::RateSnapshot, state::StateStore, timer::Reference};
 
-#[cfg(test)]
 use core::num::NonZeroU32;
 
-#[cfg(test)]
 use crate::error::InsufficientCapacity;
 
 /// A negative rate-limiting outcome.
@@ -114,7 +112,6 @@ impl Gcra {
         })
     }
 
-    #[cfg(test)]
     /// Tests whether all `n` cells could be accommodated and updates the rate limiter state, if so.
     pub(crate) fn test_n_all_and_update<K, P, S>(
         &self,
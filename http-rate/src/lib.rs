@@ -9,11 +9,15 @@ mod snapshot;
 mod state;
 mod timer;
 
-pub use error::TooManyRequests;
+pub use error::{InsufficientCapacity, ParseQuotaError, TooManyRequests};
 pub use quota::Quota;
 pub use snapshot::RateSnapshot;
 
-use core::net::{IpAddr, SocketAddr};
+use core::{
+    net::{IpAddr, SocketAddr},
+    num::NonZeroU32,
+    time::Duration,
+};
 
 use std::sync::Arc;
 
@@ -41,12 +45,53 @@ impl RateLimit {
     ///
     /// [Request]: http::Request
     pub fn rate_limit(&self, headers: &HeaderMap, addr: &SocketAddr) -> Result<RateSnapshot, TooManyRequests> {
-        let addr = maybe_x_forwarded_for(headers)
-            .or_else(|| maybe_x_real_ip(headers))
-            .or_else(|| maybe_forwarded(headers))
-            .unwrap_or_else(|| addr.ip());
+        let addr = client_addr(headers, addr);
         self.limit.check_key(&addr).map_err(TooManyRequests::from)
     }
+
+    /// Rate limit [Request] with given cost where cost is the number of cells consumed from client's
+    /// bucket. Client address is determined the same way as [RateLimit::rate_limit].
+    ///
+    /// Return `Err(InsufficientCapacity)` when cost exceeds the burst size of quota and the request can
+    /// never be allowed. Return `Ok(Err(TooManyRequests))` when client's bucket does not have enough
+    /// cells at the current time.
+    ///
+    /// [Request]: http::Request
+    pub fn rate_limit_cost(
+        &self,
+        headers: &HeaderMap,
+        addr: &SocketAddr,
+        cost: NonZeroU32,
+    ) -> Result<Result<RateSnapshot, TooManyRequests>, InsufficientCapacity> {
+        let addr = client_addr(headers, addr);
+        self.limit
+            .check_key_n(&addr, cost)
+            .map(|res| res.map_err(TooManyRequests::from))
+    }
+
+    /// Export rate limit state of clients for persisting across restarts or sharing between instances.
+    ///
+    /// State of each client is expressed as the time remaining until its bucket becomes fully
+    /// replenished, relative to the time of export. Clients with fully replenished bucket are skipped.
+    pub fn export_state(&self) -> Vec<(IpAddr, Duration)> {
+        self.limit.export_state()
+    }
+
+    /// Import rate limit state of clients produced by [RateLimit::export_state].
+    ///
+    /// Remaining time of each client is re-based on the time of import. Existing state of the same
+    /// client is replaced. Importing state exported from a RateLimit with different [Quota] would
+    /// produce unspecified rate limit result.
+    pub fn import_state(&self, state: impl IntoIterator<Item = (IpAddr, Duration)>) {
+        self.limit.import_state(state)
+    }
+}
+
+fn client_addr(headers: &HeaderMap, addr: &SocketAddr) -> IpAddr {
+    maybe_x_forwarded_for(headers)
+        .or_else(|| maybe_x_real_ip(headers))
+        .or_else(|| maybe_forwarded(headers))
+        .unwrap_or_else(|| addr.ip())
 }
 
 const X_REAL_IP: HeaderName = HeaderName::from_static("x-real-ip");
@@ -138,6 +183,68 @@ mod test {
         assert!(lb.check().is_err(), "{lb:?}");
     }
 
+    #[test]
+    fn rate_limit_cost() {
+        let limit = RateLimit::new(Quota::per_minute(10));
+        let headers = HeaderMap::new();
+        let addr = SocketAddr::from(([127, 0, 0, 1], 8080));
+        let cost = NonZeroU32::new(4).unwrap();
+
+        assert!(limit.rate_limit_cost(&headers, &addr, cost).unwrap().is_ok());
+        assert!(limit.rate_limit_cost(&headers, &addr, cost).unwrap().is_ok());
+        assert!(limit.rate_limit_cost(&headers, &addr, cost).unwrap().is_err());
+        assert!(limit.rate_limit(&headers, &addr).is_ok());
+
+        let cost = NonZeroU32::new(11).unwrap();
+        assert_eq!(
+            limit.rate_limit_cost(&headers, &addr, cost).err(),
+            Some(InsufficientCapacity(10))
+        );
+    }
+
+    #[test]
+    fn export_import_state() {
+        let limit = RateLimit::new(Quota::per_minute(2));
+        let headers = HeaderMap::new();
+        let addr = SocketAddr::from(([127, 0, 0, 1], 8080));
+
+        assert!(limit.rate_limit(&headers, &addr).is_ok());
+        assert!(limit.rate_limit(&headers, &addr).is_ok());
+        assert!(limit.rate_limit(&headers, &addr).is_err());
+
+        let state = limit.export_state();
+        assert_eq!(state.len(), 1);
+        assert_eq!(state[0].0, addr.ip());
+        assert_gt!(state[0].1, Duration::from_secs(30));
+
+        let limit = RateLimit::new(Quota::per_minute(2));
+        limit.import_state(state);
+        assert!(limit.rate_limit(&headers, &addr).is_err());
+
+        let addr2 = SocketAddr::from(([127, 0, 0, 2], 8080));
+        assert!(limit.rate_limit(&headers, &addr2).is_ok());
+    }
+
+    #[test]
+    fn burst_independent_of_rate() {
+        let clock = FakeRelativeClock::default();
+        let lb = RateLimiter::direct_with_clock(Quota::with_burst(1, 10), &clock);
+        let ms = Duration::from_millis(1);
+
+        // burst of 10 is allowed immediately:
+        for _ in 0..10 {
+            assert!(lb.check().is_ok(), "Now: {:?}", clock.now());
+        }
+        assert!(lb.check().is_err(), "Now: {:?}", clock.now());
+
+        // replenished at the rate of 1 per second:
+        clock.advance(ms * 500);
+        assert!(lb.check().is_err(), "Now: {:?}", clock.now());
+        clock.advance(ms * 500);
+        assert!(lb.check().is_ok(), "Now: {:?}", clock.now());
+        assert!(lb.check().is_err(), "Now: {:?}", clock.now());
+    }
+
     #[test]
     fn all_1_identical_to_1() {
         let clock = FakeRelativeClock::default();
@@ -1,6 +1,6 @@
-use core::{fmt, num::NonZeroU32, time::Duration};
+use core::{fmt, num::NonZeroU32, str::FromStr, time::Duration};
 
-use crate::nanos::Nanos;
+use crate::{error::ParseQuotaError, nanos::Nanos};
 
 /// A rate-limiting quota.
 ///
@@ -24,6 +24,24 @@ use crate::nanos::Nanos;
 ///
 /// In other words, the burst size is the maximum number of cells that the rate limiter will ever
 /// allow through without replenishing them.
+///
+/// Burst size can be configured independently of replenish rate with [`Quota::with_burst`] or
+/// [`Quota::allow_burst`]. In terms of GCRA the replenish interval of a single cell is the emission
+/// interval `t` and the burst size multiplied by `t` is the delay tolerance `tau`. The `per_second`,
+/// `per_minute` and `per_hour` constructors derive `t` from the period divided by given cells and
+/// set `tau` to the whole period.
+///
+/// # Parsing
+/// Quota can be parsed from string in form of `<cells>/<unit>[;burst=<cells>]` where unit is one of
+/// `s`, `min`, `hour` and `day`(and their aliases like `sec`, `m`, `h`). The period of unit is divided
+/// by cells to derive the replenish interval and the optional burst parameter is equivalent to
+/// [`Quota::allow_burst`].
+/// ```rust
+/// # use http_rate::Quota;
+/// assert_eq!("100/min".parse::<Quota>().unwrap(), Quota::per_minute(100));
+/// assert_eq!("10/s;burst=20".parse::<Quota>().unwrap(), Quota::per_second(10).allow_burst(20));
+/// assert!(Quota::try_from("0/hour").is_err());
+/// ```
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct Quota {
     pub(crate) max_burst: NonZeroU32,
@@ -76,6 +94,25 @@ impl Quota {
         Self::new(max_burst, replenish_interval_ns)
     }
 
+    /// Construct a quota that replenishes `rate` cells per second with a maximum burst size of
+    /// `burst` cells.
+    ///
+    /// This is equivalent to `Quota::per_second(rate).allow_burst(burst)`. e.g: a quota of
+    /// `Quota::with_burst(1, 10)` allows 10 cells immediately after which one cell is replenished
+    /// per second.
+    ///
+    /// # Panics
+    /// - When rate or burst is zero.
+    pub fn with_burst<R, B>(rate: R, burst: B) -> Self
+    where
+        R: TryInto<NonZeroU32>,
+        R::Error: fmt::Debug,
+        B: TryInto<NonZeroU32>,
+        B::Error: fmt::Debug,
+    {
+        Self::per_second(rate).allow_burst(burst)
+    }
+
     /// Construct a quota that replenishes one cell in a given
     /// interval.
     ///
@@ -110,6 +147,65 @@ impl Quota {
     }
 }
 
+impl FromStr for Quota {
+    type Err = ParseQuotaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut params = s.split(';');
+
+        let (cells, unit) = params
+            .next()
+            .and_then(|rate| rate.split_once('/'))
+            .ok_or(ParseQuotaError::Format)?;
+
+        let cells = cells.trim().parse::<NonZeroU32>().map_err(|_| ParseQuotaError::Cells)?;
+
+        let unit = unit.trim();
+        let secs = if ["s", "sec", "second", "seconds"]
+            .iter()
+            .any(|u| u.eq_ignore_ascii_case(unit))
+        {
+            1
+        } else if ["m", "min", "minute", "minutes"]
+            .iter()
+            .any(|u| u.eq_ignore_ascii_case(unit))
+        {
+            60
+        } else if ["h", "hr", "hour", "hours"]
+            .iter()
+            .any(|u| u.eq_ignore_ascii_case(unit))
+        {
+            60 * 60
+        } else if ["d", "day", "days"].iter().any(|u| u.eq_ignore_ascii_case(unit)) {
+            60 * 60 * 24
+        } else {
+            return Err(ParseQuotaError::Unit);
+        };
+
+        let replenish_interval_ns = Duration::from_secs(secs).as_nanos() / (cells.get() as u128);
+        let mut quota = Self::new(cells, replenish_interval_ns);
+
+        for param in params {
+            let (name, value) = param.split_once('=').ok_or(ParseQuotaError::Format)?;
+            if !name.trim().eq_ignore_ascii_case("burst") {
+                return Err(ParseQuotaError::Format);
+            }
+            quota.max_burst = value.trim().parse::<NonZeroU32>().map_err(|_| ParseQuotaError::Burst)?;
+        }
+
+        Ok(quota)
+    }
+}
+
+impl TryFrom<&str> for Quota {
+    type Error = ParseQuotaError;
+
+    #[inline]
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
 impl Quota {
     fn new(max_burst: NonZeroU32, dur_ns: u128) -> Self {
         Self {
@@ -163,6 +259,28 @@ mod test {
         assert_eq!(minutely.replenish_interval() / 60, secondly.replenish_interval());
     }
 
+    #[test]
+    fn parse() {
+        assert_eq!("100/min".parse::<Quota>().unwrap(), Quota::per_minute(100));
+        assert_eq!(" 10 / S ".parse::<Quota>().unwrap(), Quota::per_second(10));
+        assert_eq!(Quota::try_from("1000/hour").unwrap(), Quota::per_hour(1000));
+        assert_eq!(
+            "100/min;burst=20".parse::<Quota>().unwrap(),
+            Quota::per_minute(100).allow_burst(20)
+        );
+        assert_eq!(
+            "1/day".parse::<Quota>().unwrap().replenish_interval(),
+            Duration::from_secs(60 * 60 * 24)
+        );
+
+        assert_eq!("100".parse::<Quota>(), Err(ParseQuotaError::Format));
+        assert_eq!("0/s".parse::<Quota>(), Err(ParseQuotaError::Cells));
+        assert_eq!("-1/s".parse::<Quota>(), Err(ParseQuotaError::Cells));
+        assert_eq!("1/week".parse::<Quota>(), Err(ParseQuotaError::Unit));
+        assert_eq!("1/s;burst=0".parse::<Quota>(), Err(ParseQuotaError::Burst));
+        assert_eq!("1/s;cost=2".parse::<Quota>(), Err(ParseQuotaError::Format));
+    }
+
     #[test]
     fn period_error_cases() {
         assert!(Quota::with_period(Duration::from_secs(0)).is_none());
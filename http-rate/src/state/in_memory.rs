@@ -21,6 +21,15 @@ use std::time::Duration;
 pub(crate) struct InMemoryState(AtomicU64);
 
 impl InMemoryState {
+    pub(crate) fn new(tat: Nanos) -> Self {
+        Self(AtomicU64::new(tat.into()))
+    }
+
+    // the theoretical arrival time. None when state is never measured.
+    pub(crate) fn tat(&self) -> Option<Nanos> {
+        NonZeroU64::new(self.0.load(Ordering::Acquire)).map(|n| n.get().into())
+    }
+
     pub(crate) fn measure_and_replace_one<T, F, E>(&self, mut f: F) -> Result<T, E>
     where
         F: FnMut(Option<Nanos>) -> Result<(T, Nanos), E>,
@@ -1,12 +1,12 @@
-use core::hash::Hash;
+use core::{hash::Hash, num::NonZeroU32};
 
-use crate::{gcra::NotUntil, quota::Quota, snapshot::RateSnapshot, state::RateLimiter, state::StateStore, timer};
+use crate::{
+    error::InsufficientCapacity, gcra::NotUntil, quota::Quota, snapshot::RateSnapshot, state::RateLimiter,
+    state::StateStore, timer,
+};
 
 #[cfg(test)]
-use core::num::NonZeroU32;
-
-#[cfg(test)]
-use crate::{error::InsufficientCapacity, timer::Reference};
+use crate::timer::Reference;
 
 // A trait for state stores with one rate limiting state per key.
 //
@@ -60,7 +60,6 @@ where
             .test_and_update::<K, C::Instant, S>(self.start, key, &self.state, self.clock.now())
     }
 
-    #[cfg(test)]
     /// Allow *only all* `n` cells through the rate limiter for the given key.
     ///
     /// This method can succeed in only one way and fail in two ways:
@@ -1,14 +1,15 @@
-use core::hash::Hash;
+use core::{hash::Hash, time::Duration};
 
 use std::{collections::HashMap, sync::Mutex};
 
 use crate::{
     nanos::Nanos,
-    state::{InMemoryState, StateStore},
+    state::{InMemoryState, RateLimiter, StateStore},
+    timer::{Reference, Timer},
 };
 
 #[cfg(test)]
-use crate::{quota::Quota, state::RateLimiter, timer::Timer};
+use crate::quota::Quota;
 
 /// A thread-safe (but not very performant) implementation of a keyed rate limiter state
 /// store using [`HashMap`].
@@ -75,3 +76,32 @@ where
         RateLimiter::new(quota, state, clock)
     }
 }
+
+impl<K, C> RateLimiter<K, HashMapStateStore<K>, C>
+where
+    K: Hash + Eq + Clone,
+    C: Timer,
+{
+    // export keys with their theoretical arrival time relative to current time of clock.
+    // keys that are indistinguishable from a fresh state are skipped.
+    pub(crate) fn export_state(&self) -> Vec<(K, Duration)> {
+        let now = self.clock.now().duration_since(self.start);
+        let map = self.state.lock().unwrap();
+        map.iter()
+            .filter_map(|(key, state)| {
+                let tat = state.tat()?;
+                (tat > now).then(|| (key.clone(), tat.duration_since(now).into()))
+            })
+            .collect()
+    }
+
+    // import keys with their theoretical arrival time relative to current time of clock.
+    // existing states of the same keys are replaced.
+    pub(crate) fn import_state(&self, state: impl IntoIterator<Item = (K, Duration)>) {
+        let now = self.clock.now().duration_since(self.start);
+        let mut map = self.state.lock().unwrap();
+        for (key, remain) in state {
+            map.insert(key, InMemoryState::new(now + Nanos::from(remain)));
+        }
+    }
+}
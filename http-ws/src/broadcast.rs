@@ -0,0 +1,174 @@
+//! fan-out helper for sending message to multiple websocket connections.
+
+use alloc::sync::{Arc, Weak};
+
+use std::{collections::HashMap, sync::Mutex};
+
+use super::{
+    codec::Message,
+    error::ProtocolError,
+    stream::{ResponseSender, ResponseWeakSender},
+};
+
+/// Registry of websocket connections for broadcasting message to all of them.
+///
+/// Connections are tracked with [ResponseWeakSender] so a registered connection is not kept alive
+/// by broadcaster. Dead connections are pruned when broadcasting or when [Registration] returned from
+/// [Broadcaster::register] is dropped.
+///
+/// Broadcasting awaits on every connection in sequence and a slow client can block the others.
+/// Use [ResponseSender::set_send_policy] with dropping policy to avoid it.
+///
+/// [ResponseSender::set_send_policy]: crate::ResponseSender::set_send_policy
+///
+/// # Examples
+/// ```rust
+/// # use http_ws::{Broadcaster, Message, ResponseSender};
+/// async fn chat(broadcaster: &Broadcaster, tx: &ResponseSender) {
+///     // connection is removed from broadcaster when registration is dropped.
+///     let _registration = broadcaster.register(tx);
+///     broadcaster.broadcast(Message::Text("someone joined".into())).await;
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Broadcaster {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    next_id: u64,
+    senders: HashMap<u64, ResponseWeakSender>,
+}
+
+impl Broadcaster {
+    /// Construct an empty broadcaster.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a connection to broadcaster. The connection is removed from broadcaster when returned
+    /// [Registration] is dropped.
+    pub fn register(&self, tx: &ResponseSender) -> Registration {
+        let mut inner = self.inner.lock().unwrap();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.senders.insert(id, tx.downgrade());
+        Registration {
+            id,
+            inner: Arc::downgrade(&self.inner),
+        }
+    }
+
+    /// Number of registered connections. Dead connections not yet pruned are counted.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().senders.len()
+    }
+
+    /// Return true when no connection is registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Send message to all live connections and return the number of connections it's sent to.
+    ///
+    /// Connections already dropped or closed are pruned from broadcaster.
+    pub async fn broadcast(&self, msg: Message) -> usize {
+        let senders = {
+            let mut inner = self.inner.lock().unwrap();
+            let mut senders = Vec::with_capacity(inner.senders.len());
+            inner.senders.retain(|id, tx| match tx.upgrade() {
+                Some(tx) => {
+                    senders.push((*id, tx));
+                    true
+                }
+                None => false,
+            });
+            senders
+        };
+
+        let mut sent = 0;
+        let mut closed = Vec::new();
+
+        for (id, tx) in senders {
+            match tx.send(msg.clone()).await {
+                Ok(_) => sent += 1,
+                Err(ProtocolError::Closed) => closed.push(id),
+                Err(_) => {}
+            }
+        }
+
+        if !closed.is_empty() {
+            let mut inner = self.inner.lock().unwrap();
+            for id in closed {
+                inner.senders.remove(&id);
+            }
+        }
+
+        sent
+    }
+}
+
+/// Guard type of connection registered to [Broadcaster]. Connection is removed from broadcaster when
+/// it's dropped.
+#[derive(Debug)]
+pub struct Registration {
+    id: u64,
+    inner: Weak<Mutex<Inner>>,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.upgrade() {
+            inner.lock().unwrap().senders.remove(&self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::{Bytes, BytesMut};
+    use futures_core::Stream;
+    use futures_util::{stream, FutureExt};
+
+    use crate::{codec::Codec, stream::RequestStream};
+
+    use super::*;
+
+    #[test]
+    fn broadcast() {
+        let req = RequestStream::new(stream::empty::<Result<Bytes, ()>>());
+
+        let broadcaster = Broadcaster::new();
+
+        let (mut res1, tx1) = req.response_stream();
+        let (res2, tx2) = req.response_stream();
+        let (_res3, tx3) = req.response_stream();
+
+        let _reg1 = broadcaster.register(&tx1);
+        let _reg2 = broadcaster.register(&tx2);
+        let reg3 = broadcaster.register(&tx3);
+        assert_eq!(broadcaster.len(), 3);
+
+        // registration drop removes connection.
+        drop(reg3);
+        assert_eq!(broadcaster.len(), 2);
+
+        let msg = Message::Text(Bytes::from_static(b"996"));
+        assert_eq!(broadcaster.broadcast(msg.clone()).now_or_never().unwrap(), 2);
+
+        let item = core::future::poll_fn(|cx| core::pin::Pin::new(&mut res1).poll_next(cx))
+            .now_or_never()
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let mut buf = BytesMut::from(item.as_ref());
+        assert_eq!(Codec::new().client_mode().decode(&mut buf).unwrap().unwrap(), msg);
+
+        // dropped sender and closed response stream are pruned.
+        drop(tx1);
+        drop(res2);
+        assert_eq!(broadcaster.broadcast(msg).now_or_never().unwrap(), 0);
+        assert!(broadcaster.is_empty());
+    }
+}
@@ -8,7 +8,7 @@ use super::{
 };
 
 /// A WebSocket message.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Message {
     /// Text message.
     Text(Bytes),
@@ -27,7 +27,7 @@ pub enum Message {
 }
 
 /// A WebSocket continuation item.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Item {
     FirstText(Bytes),
     FirstBinary(Bytes),
@@ -125,6 +125,10 @@ impl Codec {
 
         let mask = !self.flags.contains(Flags::SERVER);
         match item {
+            // data frames can not be interleaved with fragmented message. only control frames are allowed.
+            Message::Text(_) | Message::Binary(_) if self.flags.contains(Flags::CONTINUATION) => {
+                return Err(ProtocolError::ContinuationStarted)
+            }
             Message::Text(bytes) => Parser::write_message(dst, bytes, OpCode::Text, true, mask),
             Message::Binary(bytes) => Parser::write_message(dst, bytes, OpCode::Binary, true, mask),
             Message::Ping(bytes) => Parser::write_message(dst, bytes, OpCode::Ping, true, mask),
@@ -164,12 +168,14 @@ impl Codec {
                     Err(ProtocolError::ContinuationNotStarted)
                 }
                 OpCode::Continue => {
-                    if finished {
+                    let payload = payload.unwrap_or_else(Bytes::new);
+                    let item = if finished {
                         self.flags.remove(Flags::CONTINUATION);
-                    }
-                    Ok(Some(Message::Continuation(Item::Continue(
-                        payload.unwrap_or_else(Bytes::new),
-                    ))))
+                        Item::Last(payload)
+                    } else {
+                        Item::Continue(payload)
+                    };
+                    Ok(Some(Message::Continuation(item)))
                 }
                 OpCode::Binary if !finished => {
                     self.try_start_continue()?;
@@ -229,4 +235,34 @@ mod test {
         assert!(flags.contains(Flags::CONTINUATION));
         assert!(!flags.contains(Flags::SERVER));
     }
+
+    #[test]
+    fn encode_interleave() {
+        let mut codec = Codec::new().client_mode();
+        let mut buf = BytesMut::new();
+
+        codec
+            .encode(
+                Message::Continuation(Item::FirstBinary(Bytes::from_static(b"9"))),
+                &mut buf,
+            )
+            .unwrap();
+
+        // control frame can be interleaved with fragmented message.
+        codec.encode(Message::Ping(Bytes::new()), &mut buf).unwrap();
+
+        // data frame can not be interleaved with fragmented message.
+        assert!(matches!(
+            codec.encode(Message::Text(Bytes::from_static(b"6")), &mut buf),
+            Err(ProtocolError::ContinuationStarted)
+        ));
+
+        codec
+            .encode(Message::Continuation(Item::Last(Bytes::from_static(b"6"))), &mut buf)
+            .unwrap();
+
+        codec
+            .encode(Message::Text(Bytes::from_static(b"996")), &mut buf)
+            .unwrap();
+    }
 }
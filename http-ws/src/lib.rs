@@ -215,11 +215,16 @@ fn ws_version_check(headers: &HeaderMap) -> Result<(), HandshakeError> {
     }
 }
 
+#[cfg(feature = "stream")]
+pub mod broadcast;
 #[cfg(feature = "stream")]
 pub mod stream;
 
 #[cfg(feature = "stream")]
-pub use self::stream::{RequestStream, ResponseSender, ResponseStream, ResponseWeakSender, WsError};
+pub use self::broadcast::{Broadcaster, Registration};
+
+#[cfg(feature = "stream")]
+pub use self::stream::{RequestStream, ResponseSender, ResponseStream, ResponseWeakSender, SendPolicy, WsError};
 
 #[cfg(feature = "stream")]
 pub type WsOutput<B> = (RequestStream<B>, Response<ResponseStream>, ResponseSender);
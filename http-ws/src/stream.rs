@@ -1,18 +1,25 @@
 use core::{
     fmt,
     future::Future,
-    pin::Pin,
-    task::{ready, Context, Poll},
+    pin::{pin, Pin},
+    task::{ready, Context, Poll, Waker},
+    time::Duration,
 };
 
-use alloc::sync::{Arc, Weak};
+use alloc::{
+    collections::VecDeque,
+    sync::{Arc, Weak},
+};
 
 use std::{error, io, sync::Mutex};
 
 use bytes::{Bytes, BytesMut};
 use futures_core::stream::Stream;
 use pin_project_lite::pin_project;
-use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::{
+    sync::Notify,
+    time::{sleep, Instant, Sleep},
+};
 
 use super::{
     codec::{Codec, Message},
@@ -29,6 +36,9 @@ pin_project! {
         stream: S,
         buf: BytesMut,
         codec: Codec,
+        close_reason: Option<CloseReason>,
+        idle_timeout: Option<Duration>,
+        timer: Option<Pin<Box<Sleep>>>,
     }
 }
 
@@ -46,9 +56,24 @@ where
             stream,
             buf: BytesMut::new(),
             codec,
+            close_reason: None,
+            idle_timeout: None,
+            timer: None,
         }
     }
 
+    /// Set idle timeout of reading from inner stream. When no data is received from inner stream in
+    /// the given duration [WsError::Timeout] would be yielded and the connection should be torn down.
+    ///
+    /// The timer is reset whenever data is received (including ping/pong frames) and it can be used
+    /// together with ping interval for detecting half open connections.
+    ///
+    /// Default to no timeout.
+    pub fn set_idle_timeout(mut self, dur: Duration) -> Self {
+        self.idle_timeout = Some(dur);
+        self
+    }
+
     #[inline]
     pub fn inner_mut(&mut self) -> &mut S {
         &mut self.stream
@@ -59,20 +84,33 @@ where
         &mut self.codec
     }
 
+    /// Reason of close frame received from peer.
+    ///
+    /// [Message::Close] is yielded by Self when peer sends close frame and this method can be used to
+    /// retrieve its reason after stream termination. Return None when no close frame is received or the
+    /// close frame does not carry a reason.
+    ///
+    /// When server initiates close by sending [Message::Close] through [ResponseSender] the reason
+    /// returned here is from peer's close frame replied to it.
+    #[inline]
+    pub fn close_reason(&self) -> Option<&CloseReason> {
+        self.close_reason.as_ref()
+    }
+
     /// Make a [ResponseStream] from current DecodeStream.
     ///
     /// This API is to share the same codec for both decode and encode stream.
     pub fn response_stream(&self) -> (ResponseStream, ResponseSender) {
-        let codec = self.codec.duplicate();
-        let cap = codec.capacity();
-        let (tx, rx) = channel(cap);
-        (ResponseStream(rx), ResponseSender::new(tx, codec))
+        let chan = Arc::new(Channel::new(self.codec.duplicate()));
+        (ResponseStream(chan.clone()), ResponseSender::new(chan))
     }
 }
 
 pub enum WsError<E> {
     Protocol(ProtocolError),
     Stream(E),
+    /// no data received from inner stream in the duration of [RequestStream::set_idle_timeout].
+    Timeout,
 }
 
 impl<E> fmt::Debug for WsError<E> {
@@ -80,6 +118,7 @@ impl<E> fmt::Debug for WsError<E> {
         match *self {
             Self::Protocol(ref e) => fmt::Debug::fmt(e, f),
             Self::Stream(..) => f.write_str("Input Stream error"),
+            Self::Timeout => f.write_str("Input Stream idle timeout"),
         }
     }
 }
@@ -89,6 +128,7 @@ impl<E> fmt::Display for WsError<E> {
         match *self {
             Self::Protocol(ref e) => fmt::Debug::fmt(e, f),
             Self::Stream(..) => f.write_str("Input Stream error"),
+            Self::Timeout => f.write_str("Input Stream idle timeout"),
         }
     }
 }
@@ -113,29 +153,85 @@ where
 
         loop {
             if let Some(msg) = this.codec.decode(this.buf)? {
+                if let Message::Close(ref reason) = msg {
+                    this.close_reason.clone_from(reason);
+                }
                 return Poll::Ready(Some(Ok(msg)));
             }
-            match ready!(this.stream.as_mut().poll_next(cx)) {
-                Some(res) => {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(res)) => {
                     let item = res.map_err(WsError::Stream)?;
-                    this.buf.extend_from_slice(item.as_ref())
+                    this.buf.extend_from_slice(item.as_ref());
+                    if let (Some(dur), Some(timer)) = (*this.idle_timeout, this.timer.as_mut()) {
+                        timer.as_mut().reset(Instant::now() + dur);
+                    }
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => {
+                    if let Some(dur) = *this.idle_timeout {
+                        let timer = this.timer.get_or_insert_with(|| Box::pin(sleep(dur)));
+                        ready!(timer.as_mut().poll(cx));
+                        return Poll::Ready(Some(Err(WsError::Timeout)));
+                    }
+                    return Poll::Pending;
                 }
-                None => return Poll::Ready(None),
             }
         }
     }
 }
 
-pub struct ResponseStream(Receiver<Item>);
+/// Policy of [ResponseSender] when it's queue of outgoing messages is full. A full queue usually means
+/// the client is not reading messages fast enough.
+///
+/// Capacity of the queue is set with [Codec::set_capacity]. Only [Message::Text], [Message::Binary] and
+/// [Message::Continuation] count toward the capacity. Control messages and errors sent with
+/// [ResponseSender::send_error] are always queued.
+///
+/// Dropping fragment of continuation message would leave the client with incomplete message and it's
+/// caller's responsibility to avoid mixing continuation message with dropping policies.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SendPolicy {
+    /// wait until queue has free space. This is the default policy.
+    #[default]
+    Wait,
+    /// drop the oldest queued message to make room for new message.
+    DropOldest,
+    /// drop the new message and keep queued messages.
+    DropNewest,
+    /// drop all queued messages and add an [io::Error] to [ResponseStream] as a signal to close the
+    /// connection. The sending returns [ProtocolError::Closed] and no more message can be sent.
+    CloseConnection,
+}
+
+pub struct ResponseStream(Arc<Channel>);
 
 type Item = io::Result<Bytes>;
 
 impl Stream for ResponseStream {
     type Item = Item;
 
-    #[inline]
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.get_mut().0.poll_recv(cx)
+        let chan = &self.get_mut().0;
+        let mut inner = chan.inner.lock().unwrap();
+        match inner.queue.pop_front() {
+            Some((item, _)) => {
+                drop(inner);
+                chan.notify.notify_waiters();
+                Poll::Ready(Some(item))
+            }
+            None if inner.closed || inner.sender_dropped => Poll::Ready(None),
+            None => {
+                inner.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for ResponseStream {
+    fn drop(&mut self) {
+        self.0.inner.lock().unwrap().closed = true;
+        self.0.notify.notify_waiters();
     }
 }
 
@@ -146,18 +242,20 @@ pub struct ResponseSender {
 }
 
 impl ResponseSender {
-    fn new(tx: Sender<Item>, codec: Codec) -> Self {
+    fn new(chan: Arc<Channel>) -> Self {
         Self {
-            inner: Arc::new(_ResponseSender {
-                encoder: Mutex::new(Encoder {
-                    codec,
-                    buf: BytesMut::with_capacity(codec.max_size()),
-                }),
-                tx,
-            }),
+            inner: Arc::new(_ResponseSender { chan }),
         }
     }
 
+    /// Set policy of sending message when queue of outgoing messages is full. The policy is shared by
+    /// all senders of the same [ResponseStream].
+    ///
+    /// Default to [SendPolicy::Wait].
+    pub fn set_send_policy(&self, policy: SendPolicy) {
+        self.inner.chan.inner.lock().unwrap().policy = policy;
+    }
+
     /// downgrade Self to a weak sender.
     pub fn downgrade(&self) -> ResponseWeakSender {
         ResponseWeakSender {
@@ -264,29 +362,61 @@ impl ResponseWeakSender {
 
 #[derive(Debug)]
 struct _ResponseSender {
-    encoder: Mutex<Encoder>,
-    tx: Sender<Item>,
+    chan: Arc<Channel>,
 }
 
-#[derive(Debug)]
-struct Encoder {
-    codec: Codec,
-    buf: BytesMut,
+impl Drop for _ResponseSender {
+    fn drop(&mut self) {
+        let mut inner = self.chan.inner.lock().unwrap();
+        inner.sender_dropped = true;
+        if let Some(waker) = inner.waker.take() {
+            waker.wake();
+        }
+    }
 }
 
 impl _ResponseSender {
     // send message to response stream. it would produce Ok(bytes) when succeed where
     // the bytes is encoded binary websocket message ready to be sent to client.
     async fn send(&self, msg: Message) -> Result<(), ProtocolError> {
-        let permit = self.tx.reserve().await.map_err(|_| ProtocolError::Closed)?;
-        let buf = {
-            let mut encoder = self.encoder.lock().unwrap();
-            let Encoder { codec, buf } = &mut *encoder;
-            codec.encode(msg, buf)?;
-            buf.split().freeze()
-        };
-        permit.send(Ok(buf));
-        Ok(())
+        let bounded = matches!(msg, Message::Text(_) | Message::Binary(_) | Message::Continuation(_));
+        let mut msg = Some(msg);
+        loop {
+            // register interest before checking queue so wake up from receiver is not lost.
+            let mut notified = pin!(self.chan.notify.notified());
+            notified.as_mut().enable();
+
+            {
+                let mut inner = self.chan.inner.lock().unwrap();
+
+                if inner.closed {
+                    return Err(ProtocolError::Closed);
+                }
+
+                if bounded && inner.queue.len() >= inner.codec.capacity() {
+                    match inner.policy {
+                        SendPolicy::Wait => {}
+                        SendPolicy::DropNewest => return Ok(()),
+                        SendPolicy::DropOldest => {
+                            if let Some(idx) = inner.queue.iter().position(|(_, bounded)| *bounded) {
+                                inner.queue.remove(idx);
+                            }
+                            return inner.encode(msg.take().unwrap(), bounded);
+                        }
+                        SendPolicy::CloseConnection => {
+                            inner.queue.clear();
+                            inner.push(Err(io::Error::other("websocket send queue overflow")), false);
+                            inner.closed = true;
+                            return Err(ProtocolError::Closed);
+                        }
+                    }
+                } else {
+                    return inner.encode(msg.take().unwrap(), bounded);
+                }
+            }
+
+            notified.await;
+        }
     }
 
     // send error to response stream. it would produce Err(io::Error) when succeed where
@@ -294,6 +424,185 @@ impl _ResponseSender {
     // the consumer observing the error should close the stream and the tcp connection
     // the stream belongs to.
     async fn send_error(&self, err: io::Error) -> Result<(), ProtocolError> {
-        self.tx.send(Err(err)).await.map_err(|_| ProtocolError::Closed)
+        let mut inner = self.chan.inner.lock().unwrap();
+        if inner.closed {
+            return Err(ProtocolError::Closed);
+        }
+        inner.push(Err(err), false);
+        Ok(())
+    }
+}
+
+// shared queue between ResponseSender and ResponseStream.
+struct Channel {
+    inner: Mutex<ChannelInner>,
+    // notify senders waiting for free space of queue.
+    notify: Notify,
+}
+
+impl fmt::Debug for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Channel").finish_non_exhaustive()
+    }
+}
+
+impl Channel {
+    fn new(codec: Codec) -> Self {
+        Self {
+            inner: Mutex::new(ChannelInner {
+                codec,
+                buf: BytesMut::with_capacity(codec.max_size()),
+                queue: VecDeque::new(),
+                policy: SendPolicy::Wait,
+                closed: false,
+                sender_dropped: false,
+                waker: None,
+            }),
+            notify: Notify::new(),
+        }
+    }
+}
+
+struct ChannelInner {
+    codec: Codec,
+    buf: BytesMut,
+    // queued items and if they count toward capacity of queue.
+    queue: VecDeque<(Item, bool)>,
+    policy: SendPolicy,
+    // no more item can be sent.
+    closed: bool,
+    // all ResponseSender are dropped.
+    sender_dropped: bool,
+    waker: Option<Waker>,
+}
+
+impl ChannelInner {
+    fn encode(&mut self, msg: Message, bounded: bool) -> Result<(), ProtocolError> {
+        self.codec.encode(msg, &mut self.buf)?;
+        let bytes = self.buf.split().freeze();
+        self.push(Ok(bytes), bounded);
+        Ok(())
+    }
+
+    fn push(&mut self, item: Item, bounded: bool) {
+        self.queue.push_back((item, bounded));
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::future::poll_fn;
+
+    use futures_util::{stream, FutureExt};
+
+    use crate::proto::CloseCode;
+
+    use super::*;
+
+    #[test]
+    fn close_reason() {
+        let mut buf = BytesMut::new();
+        let reason = CloseReason::from((CloseCode::Away, "bye"));
+        Codec::new()
+            .client_mode()
+            .encode(Message::Close(Some(reason.clone())), &mut buf)
+            .unwrap();
+
+        let stream = RequestStream::new(stream::once(async { Ok::<_, ()>(buf.freeze()) }));
+        let mut stream = core::pin::pin!(stream);
+
+        assert!(stream.close_reason().is_none());
+
+        let msg = poll_fn(|cx| stream.as_mut().poll_next(cx))
+            .now_or_never()
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(matches!(msg, Message::Close(Some(ref r)) if *r == reason));
+        let next = poll_fn(|cx| stream.as_mut().poll_next(cx)).now_or_never().unwrap();
+        assert!(next.is_none());
+
+        assert_eq!(stream.close_reason(), Some(&reason));
+    }
+
+    #[test]
+    fn idle_timeout() {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(async {
+                let stream = stream::pending::<Result<Bytes, ()>>();
+                let stream = RequestStream::new(stream).set_idle_timeout(Duration::from_millis(50));
+                let mut stream = core::pin::pin!(stream);
+
+                let res = poll_fn(|cx| stream.as_mut().poll_next(cx)).await.unwrap();
+                assert!(matches!(res, Err(WsError::Timeout)));
+            });
+    }
+
+    #[test]
+    fn send_policy() {
+        fn next(stream: &mut ResponseStream) -> Option<Item> {
+            poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx))
+                .now_or_never()
+                .unwrap()
+        }
+
+        fn decode(item: Option<Item>) -> Message {
+            let mut buf = BytesMut::from(item.unwrap().unwrap().as_ref());
+            Codec::new().client_mode().decode(&mut buf).unwrap().unwrap()
+        }
+
+        let codec = Codec::new().set_capacity(1);
+        let req = RequestStream::with_codec(stream::empty::<Result<Bytes, ()>>(), codec);
+
+        let (mut res, tx) = req.response_stream();
+        // wait policy blocks sending until queue has free space.
+        tx.text("1").now_or_never().unwrap().unwrap();
+        let mut send = core::pin::pin!(tx.text("2"));
+        assert!(send.as_mut().now_or_never().is_none());
+        assert_eq!(decode(next(&mut res)), Message::Text(Bytes::from("1")));
+        send.now_or_never().unwrap().unwrap();
+        assert_eq!(decode(next(&mut res)), Message::Text(Bytes::from("2")));
+
+        let (mut res, tx) = req.response_stream();
+        tx.set_send_policy(SendPolicy::DropNewest);
+        tx.text("1").now_or_never().unwrap().unwrap();
+        tx.text("2").now_or_never().unwrap().unwrap();
+        // control message is not bounded by capacity.
+        tx.send(Message::Ping(Bytes::new())).now_or_never().unwrap().unwrap();
+        assert_eq!(decode(next(&mut res)), Message::Text(Bytes::from("1")));
+        assert_eq!(decode(next(&mut res)), Message::Ping(Bytes::new()));
+
+        let (mut res, tx) = req.response_stream();
+        tx.set_send_policy(SendPolicy::DropOldest);
+        tx.text("1").now_or_never().unwrap().unwrap();
+        tx.text("2").now_or_never().unwrap().unwrap();
+        assert_eq!(decode(next(&mut res)), Message::Text(Bytes::from("2")));
+
+        let (mut res, tx) = req.response_stream();
+        tx.set_send_policy(SendPolicy::CloseConnection);
+        tx.text("1").now_or_never().unwrap().unwrap();
+        assert!(matches!(
+            tx.text("2").now_or_never().unwrap(),
+            Err(ProtocolError::Closed)
+        ));
+        assert!(next(&mut res).unwrap().is_err());
+        assert!(next(&mut res).is_none());
+        assert!(matches!(
+            tx.text("3").now_or_never().unwrap(),
+            Err(ProtocolError::Closed)
+        ));
+
+        // stream ends when all senders are dropped.
+        let (mut res, tx) = req.response_stream();
+        tx.text("1").now_or_never().unwrap().unwrap();
+        drop(tx);
+        assert!(next(&mut res).is_some());
+        assert!(next(&mut res).is_none());
     }
 }
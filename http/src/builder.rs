@@ -1,4 +1,6 @@
-use core::{fmt, marker::PhantomData};
+use core::{fmt, marker::PhantomData, sync::atomic::AtomicBool};
+
+use std::sync::Arc;
 
 use xitca_io::net;
 use xitca_service::Service;
@@ -37,6 +39,7 @@ pub struct HttpServiceBuilder<
 > {
     pub(crate) tls_factory: FA,
     pub(crate) config: HttpServiceConfig<HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>,
+    pub(crate) draining: Option<Arc<AtomicBool>>,
     pub(crate) _body: PhantomData<fn(V, St)>,
 }
 
@@ -69,6 +72,7 @@ impl
         HttpServiceBuilder {
             tls_factory: tls::NoOpTlsAcceptorBuilder,
             config,
+            draining: None,
             _body: PhantomData,
         }
     }
@@ -86,6 +90,7 @@ impl
         HttpServiceBuilder {
             tls_factory: tls::NoOpTlsAcceptorBuilder,
             config: HttpServiceConfig::default(),
+            draining: None,
             _body: PhantomData,
         }
     }
@@ -103,6 +108,7 @@ impl
         HttpServiceBuilder {
             tls_factory: tls::NoOpTlsAcceptorBuilder,
             config: HttpServiceConfig::default(),
+            draining: None,
             _body: PhantomData,
         }
     }
@@ -125,6 +131,7 @@ impl<V, St, FA, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WR
         HttpServiceBuilder {
             tls_factory: self.tls_factory,
             config,
+            draining: self.draining,
             _body: PhantomData,
         }
     }
@@ -137,10 +144,37 @@ impl<V, St, FA, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WR
         HttpServiceBuilder {
             tls_factory,
             config: self.config,
+            draining: self.draining,
+            _body: PhantomData,
+        }
+    }
+
+    /// replace io type of incoming connection service expects. io type must impl [AsyncIo] trait
+    /// for Http/1 and Http/2 protocols.
+    ///
+    /// useful for wrapping connection with custom io type. (instrumented or fault injected stream
+    /// for example)
+    ///
+    /// [AsyncIo]: xitca_io::io::AsyncIo
+    pub fn with_stream<St2>(self) -> HttpServiceBuilder<V, St2, FA, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT> {
+        HttpServiceBuilder {
+            tls_factory: self.tls_factory,
+            config: self.config,
+            draining: self.draining,
             _body: PhantomData,
         }
     }
 
+    /// observe a shared draining state. when the state is set to true connections stop keeping alive
+    /// while in flight requests are finished normally.
+    ///
+    /// Http/1 connection would respond with `Connection: close` header and Http/2 connection would
+    /// send GOAWAY frame and refuse new streams.
+    pub fn draining_state(mut self, state: Arc<AtomicBool>) -> Self {
+        self.draining = Some(state);
+        self
+    }
+
     #[cfg(feature = "openssl")]
     /// use openssl as tls service. tls service is used for Http/1 and Http/2 protocols.
     pub fn openssl(
@@ -198,6 +232,6 @@ where
     async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
         let service = res.map_err(|e| Box::new(e) as Error)?;
         let tls_acceptor = self.tls_factory.call(()).await.map_err(|e| Box::new(e) as Error)?;
-        Ok(HttpService::new(self.config, service, tls_acceptor))
+        Ok(HttpService::new(self.config, service, tls_acceptor).draining(self.draining.clone()))
     }
 }
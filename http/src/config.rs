@@ -19,6 +19,10 @@ pub const DEFAULT_WRITE_BUF_LIMIT: usize = 8192 + 4096 * 100;
 /// 64 chosen for no particular reason.
 pub const DEFAULT_HEADER_LIMIT: usize = 64;
 
+/// The default maximum length of request uri in bytes. Request with uri longer than it is
+/// rejected with `414 URI Too Long` response.
+pub const DEFAULT_MAX_URI_LENGTH: usize = 8 * 1024;
+
 #[derive(Copy, Clone)]
 pub struct HttpServiceConfig<
     const HEADER_LIMIT: usize = DEFAULT_HEADER_LIMIT,
@@ -30,6 +34,11 @@ pub struct HttpServiceConfig<
     pub(crate) request_head_timeout: Duration,
     pub(crate) tls_accept_timeout: Duration,
     pub(crate) peek_protocol: bool,
+    pub(crate) title_case_headers: bool,
+    pub(crate) max_uri_length: usize,
+    pub(crate) stream_idle_timeout: Option<Duration>,
+    pub(crate) max_half_open_streams: usize,
+    pub(crate) request_body_timeout: Option<Duration>,
 }
 
 impl Default for HttpServiceConfig {
@@ -46,6 +55,11 @@ impl HttpServiceConfig {
             request_head_timeout: Duration::from_secs(5),
             tls_accept_timeout: Duration::from_secs(3),
             peek_protocol: false,
+            title_case_headers: false,
+            max_uri_length: DEFAULT_MAX_URI_LENGTH,
+            stream_idle_timeout: None,
+            max_half_open_streams: usize::MAX,
+            request_body_timeout: None,
         }
     }
 }
@@ -123,11 +137,92 @@ impl<const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE_BUF_LIM
     ///
     /// This API is used to bypass alpn setting from tls and enable Http/2 protocol over
     /// plain Tcp connection.
+    ///
+    /// Connection starting with Http/2 connection preface is served as Http/2 with prior knowledge
+    /// and others are served as Http/1. Connection with `h2` negotiated by alpn is not peeked.
+    /// It has no effect when `http2` feature is not enabled.
+    ///
+    /// When `http1` feature is enabled the first request of Http/1 connection can upgrade it to
+    /// Http/2 with `Upgrade: h2c` header. Only request without body can be upgraded and it's served
+    /// as stream 1 of Http/2 connection. Values of `HTTP2-Settings` header are ignored and settings
+    /// from client's SETTINGS frame are used.
     pub fn peek_protocol(mut self) -> Self {
         self.peek_protocol = true;
         self
     }
 
+    /// Write header names of Http/1 response in title case. (`Content-Type` instead of
+    /// `content-type` for example)
+    ///
+    /// Well known headers with irregular case are written in their canonical form. (`ETag` and
+    /// `WWW-Authenticate` for example)
+    ///
+    /// Header names are case insensitive by Http specification and this API is only useful for
+    /// interop with clients and proxies not following it. It comes with extra cost of encoding
+    /// response head.
+    pub fn title_case_headers(mut self) -> Self {
+        self.title_case_headers = true;
+        self
+    }
+
+    /// Define max length of request uri in bytes.
+    ///
+    /// For Http/1 it's the request target of request line and it's enforced while request head is
+    /// still being received. For Http/2 it's the `:path` pseudo-header. Request exceeding the limit
+    /// is rejected with `414 URI Too Long` response.
+    ///
+    /// See [DEFAULT_MAX_URI_LENGTH] for default value.
+    pub fn max_uri_length(mut self, len: usize) -> Self {
+        self.max_uri_length = len;
+        self
+    }
+
+    /// Define duration of how long a Http/2 stream can stay idle while it's request body is still
+    /// being received.
+    ///
+    /// Stream that does not receive any DATA frame after duration would be reset with RST_STREAM
+    /// frame and it's service future would be dropped. DATA frames received while service is not
+    /// reading request body keep the stream active. Other streams of the connection are not affected.
+    /// Stream with request body finished (END_STREAM flag received) is not subject to this timeout.
+    ///
+    /// Http/2 with io-uring is not covered.
+    ///
+    /// Default to no timeout.
+    pub fn stream_idle_timeout(mut self, dur: Duration) -> Self {
+        self.stream_idle_timeout = Some(dur);
+        self
+    }
+
+    /// Define max number of half open streams of a Http/2 connection. Half open stream is a stream
+    /// with it's request body still being received.
+    ///
+    /// New stream with request body exceeding the limit would be refused with RST_STREAM frame
+    /// (`REFUSED_STREAM` error code) and client is free to retry it. Stream without request body is
+    /// not affected.
+    ///
+    /// Http/2 with io-uring is not covered.
+    ///
+    /// Default to no limit.
+    pub fn max_half_open_streams(mut self, num: usize) -> Self {
+        self.max_half_open_streams = num;
+        self
+    }
+
+    /// Define duration of how long request body can stay idle between it's chunks before a response
+    /// is produced.
+    ///
+    /// Timer starts when consumer of request body is waiting for more data and it's reset every time
+    /// data is received. When it's expired the service future is dropped and request is aborted with
+    /// `408 Request Timeout` response. Http/1 connection is closed afterwards and Http/2 stream is
+    /// reset. It does not limit the total time of receiving request body and it's not applied after
+    /// response is produced. Http/1 and Http/2 with io-uring are not covered.
+    ///
+    /// Default to no timeout.
+    pub fn request_body_timeout(mut self, dur: Duration) -> Self {
+        self.request_body_timeout = Some(dur);
+        self
+    }
+
     #[doc(hidden)]
     /// A shortcut for mutating const generic params.
     pub fn mutate_const_generic<
@@ -143,6 +238,11 @@ impl<const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE_BUF_LIM
             request_head_timeout: self.request_head_timeout,
             tls_accept_timeout: self.tls_accept_timeout,
             peek_protocol: self.peek_protocol,
+            title_case_headers: self.title_case_headers,
+            max_uri_length: self.max_uri_length,
+            stream_idle_timeout: self.stream_idle_timeout,
+            max_half_open_streams: self.max_half_open_streams,
+            request_body_timeout: self.request_body_timeout,
         }
     }
 }
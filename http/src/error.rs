@@ -70,6 +70,8 @@ pub enum TimeoutError {
     TlsAccept,
     #[cfg(feature = "http2")]
     H2Handshake,
+    #[cfg(feature = "http2")]
+    PeekProtocol,
 }
 
 impl<S, B> From<()> for HttpServiceError<S, B> {
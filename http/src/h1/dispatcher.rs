@@ -4,10 +4,11 @@ use core::{
     marker::PhantomData,
     net::SocketAddr,
     pin::{pin, Pin},
+    sync::atomic::{AtomicBool, Ordering},
     time::Duration,
 };
 
-use std::io;
+use std::{io, sync::Arc};
 
 use futures_core::stream::Stream;
 use tracing::trace;
@@ -26,10 +27,10 @@ use crate::{
     },
     http::{
         response::{Parts, Response},
-        StatusCode,
+        StatusCode, TlsInfo,
     },
     util::{
-        buffered::{BufferedIo, ListWriteBuf, ReadBuf, WriteBuf},
+        buffered::{BufInterest, BufferedIo, ListWriteBuf, ReadBuf, WriteBuf},
         timer::{KeepAlive, Timeout},
     },
 };
@@ -45,6 +46,7 @@ use super::proto::{
 type ExtRequest<B> = crate::http::Request<crate::http::RequestExt<B>>;
 
 /// function to generic over different writer buffer types dispatcher.
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn run<
     'a,
     St,
@@ -59,10 +61,13 @@ pub(crate) async fn run<
 >(
     io: &'a mut St,
     addr: SocketAddr,
+    local_addr: Option<SocketAddr>,
+    tls_info: Option<Arc<TlsInfo>>,
     timer: Pin<&'a mut KeepAlive>,
     config: HttpServiceConfig<HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>,
     service: &'a S,
     date: &'a D,
+    draining: Option<&'a AtomicBool>,
 ) -> Result<(), Error<S::Error, BE>>
 where
     S: Service<ExtRequest<ReqB>, Response = Response<ResB>>,
@@ -77,9 +82,11 @@ where
         EitherBuf::Right(WriteBuf::<WRITE_BUF_LIMIT>::default())
     };
 
-    Dispatcher::new(io, addr, timer, config, service, date, write_buf)
-        .run()
-        .await
+    Dispatcher::new(
+        io, addr, local_addr, tls_info, timer, config, service, date, draining, write_buf,
+    )
+    .run()
+    .await
 }
 
 /// Http/1 dispatcher
@@ -88,6 +95,8 @@ struct Dispatcher<'a, St, S, ReqB, W, D, const HEADER_LIMIT: usize, const READ_B
     timer: Timer<'a>,
     ctx: Context<'a, D, HEADER_LIMIT>,
     service: &'a S,
+    draining: Option<&'a AtomicBool>,
+    body_timeout: Option<Duration>,
     _phantom: PhantomData<ReqB>,
 }
 
@@ -166,20 +175,34 @@ where
     W: H1BufWrite,
     D: DateTime,
 {
+    #[allow(clippy::too_many_arguments)]
     fn new<const WRITE_BUF_LIMIT: usize>(
         io: &'a mut St,
         addr: SocketAddr,
+        local_addr: Option<SocketAddr>,
+        tls_info: Option<Arc<TlsInfo>>,
         timer: Pin<&'a mut KeepAlive>,
         config: HttpServiceConfig<HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>,
         service: &'a S,
         date: &'a D,
+        draining: Option<&'a AtomicBool>,
         write_buf: W,
     ) -> Self {
+        let mut ctx = Context::with_addr(addr, date);
+        ctx.set_local_addr(local_addr);
+        ctx.set_tls_info(tls_info);
+        if config.title_case_headers {
+            ctx.set_title_case_headers();
+        }
+        ctx.set_max_uri_length(config.max_uri_length);
+
         Self {
             io: BufferedIo::new(io, write_buf),
             timer: Timer::new(timer, config.keep_alive_timeout, config.request_head_timeout),
-            ctx: Context::with_addr(addr, date),
+            ctx,
             service,
+            draining,
+            body_timeout: config.request_body_timeout,
             _phantom: PhantomData,
         }
     }
@@ -193,9 +216,16 @@ where
                     return Ok(());
                 }
                 Err(Error::RequestTimeout) => self.request_error(|| status_only(StatusCode::REQUEST_TIMEOUT)),
+                Err(Error::RequestBodyTimeout) => {
+                    trace!(target: "h1_dispatcher", "Request body timed out. Aborting request");
+                    self.request_error(|| status_only(StatusCode::REQUEST_TIMEOUT))
+                }
                 Err(Error::Proto(ProtoError::HeaderTooLarge)) => {
                     self.request_error(|| status_only(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE))
                 }
+                Err(Error::Proto(ProtoError::UriTooLong)) => {
+                    self.request_error(|| status_only(StatusCode::URI_TOO_LONG))
+                }
                 Err(Error::Proto(_)) => self.request_error(|| status_only(StatusCode::BAD_REQUEST)),
                 Err(e) => return Err(e),
             }
@@ -274,6 +304,10 @@ where
     }
 
     fn encode_head(&mut self, parts: Parts, body: &impl Stream) -> Result<TransferCoding, ProtoError> {
+        // stop keeping connection alive when server is draining.
+        if self.draining.is_some_and(|draining| draining.load(Ordering::Relaxed)) {
+            self.ctx.set_close();
+        }
         self.ctx.encode_head(parts, body, &mut self.io.write_buf)
     }
 
@@ -290,7 +324,25 @@ where
         }
 
         loop {
-            body_reader.ready(&mut self.io.read_buf).await;
+            if body_reader.decoder.is_eof() {
+                // request body is fully received. keep reading io to detect peer disconnect while service future
+                // is running. io error (including peer closed connection) would drop service future early.
+                // read is stopped when read buffer is full of pipelined requests.
+                if !self.io.read_buf.want_write_buf() {
+                    pending::<()>().await;
+                }
+            } else {
+                body_reader.ready(&mut self.io.read_buf).await;
+
+                // consumer of request body is waiting for more data.
+                if let Some(dur) = self.body_timeout {
+                    match self.io.read().select(tokio::time::sleep(dur)).await {
+                        SelectOutput::A(res) => res?,
+                        SelectOutput::B(_) => return Err(Error::RequestBodyTimeout),
+                    }
+                    continue;
+                }
+            }
             self.io.read().await?;
         }
     }
@@ -356,7 +408,10 @@ impl BodyReader {
                     // service future drop RequestBody so marker decoder to corrupted.
                     Err(_) => self.decoder.set_corrupted(),
                 },
-                ChunkResult::OnEof => self.tx.feed_eof(),
+                ChunkResult::OnEof => {
+                    self.tx.feed_eof();
+                    return;
+                }
                 ChunkResult::AlreadyEof | ChunkResult::Corrupted => pending().await,
                 ChunkResult::Err(e) => self.feed_error(e),
             }
@@ -7,6 +7,7 @@ use core::{
     net::SocketAddr,
     ops::{Deref, DerefMut},
     pin::{pin, Pin},
+    sync::atomic::{AtomicBool, Ordering},
     task::{self, ready, Poll, Waker},
 };
 
@@ -50,6 +51,7 @@ pub(super) struct Dispatcher<'a, Io, S, ReqB, D, const H_LIMIT: usize, const R_L
     timer: Timer<'a>,
     ctx: Context<'a, D, H_LIMIT>,
     service: &'a S,
+    draining: Option<&'a AtomicBool>,
     read_buf: BufOwned,
     write_buf: BufOwned,
     notify: Notify<BufOwned>,
@@ -118,12 +120,20 @@ where
         config: HttpServiceConfig<H_LIMIT, R_LIMIT, W_LIMIT>,
         service: &'a S,
         date: &'a D,
+        draining: Option<&'a AtomicBool>,
     ) -> Self {
+        let mut ctx = Context::<_, H_LIMIT>::with_addr(addr, date);
+        if config.title_case_headers {
+            ctx.set_title_case_headers();
+        }
+        ctx.set_max_uri_length(config.max_uri_length);
+
         Self {
             io: Rc::new(io),
             timer: Timer::new(timer, config.keep_alive_timeout, config.request_head_timeout),
-            ctx: Context::<_, H_LIMIT>::with_addr(addr, date),
+            ctx,
             service,
+            draining,
             read_buf: BufOwned::new(),
             write_buf: BufOwned::new(),
             notify: Notify::new(),
@@ -143,6 +153,9 @@ where
                 Err(Error::Proto(ProtoError::HeaderTooLarge)) => {
                     self.request_error(|| status_only(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE))
                 }
+                Err(Error::Proto(ProtoError::UriTooLong)) => {
+                    self.request_error(|| status_only(StatusCode::URI_TOO_LONG))
+                }
                 Err(Error::Proto(_)) => self.request_error(|| status_only(StatusCode::BAD_REQUEST)),
                 Err(e) => return Err(e),
             }
@@ -192,6 +205,11 @@ where
 
             let (parts, body) = self.service.call(req).await.map_err(Error::Service)?.into_parts();
 
+            // stop keeping connection alive when server is draining.
+            if self.draining.is_some_and(|draining| draining.load(Ordering::Relaxed)) {
+                self.ctx.set_close();
+            }
+
             let mut encoder = self.ctx.encode_head(parts, &body, &mut *self.write_buf)?;
 
             // this block is necessary. ResB has to be dropped asap as it may hold ownership of
@@ -11,6 +11,8 @@ pub enum Error<S, B> {
     KeepAliveExpire,
     /// socket fail to receive a complete request head in given time window.
     RequestTimeout,
+    /// socket fail to receive request body data in given time window.
+    RequestBodyTimeout,
     Closed,
     /// service error. terminate connection right away.
     Service(S),
@@ -32,6 +34,7 @@ where
         match *self {
             Self::KeepAliveExpire => f.write_str("Keep-Alive time expired"),
             Self::RequestTimeout => f.write_str("request head time out"),
+            Self::RequestBodyTimeout => f.write_str("request body time out"),
             Self::Closed => f.write_str("closed"),
             Self::Service(ref e) => fmt::Debug::fmt(e, f),
             Self::Body(ref e) => fmt::Debug::fmt(e, f),
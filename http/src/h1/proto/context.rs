@@ -1,16 +1,26 @@
 use core::{mem, net::SocketAddr};
 
-use crate::http::{header::HeaderMap, Extensions};
+use std::sync::Arc;
+
+use crate::http::{header::HeaderMap, Extensions, TlsInfo};
 
 /// Context is connection specific struct contain states for processing.
 pub struct Context<'a, D, const HEADER_LIMIT: usize> {
     addr: SocketAddr,
+    local_addr: Option<SocketAddr>,
+    tls_info: Option<Arc<TlsInfo>>,
+    // count of requests decoded from connection.
+    request_count: usize,
     state: ContextState,
     // header map reused by next request.
     header: Option<HeaderMap>,
     // http extensions reused by next request.
     exts: Extensions,
     date: &'a D,
+    // write response header names in title case.
+    title_case_headers: bool,
+    // max length of request uri in bytes.
+    max_uri_length: usize,
 }
 
 // A set of state for current request that are used after request's ownership is passed
@@ -60,10 +70,15 @@ impl<'a, D, const HEADER_LIMIT: usize> Context<'a, D, HEADER_LIMIT> {
     pub fn with_addr(addr: SocketAddr, date: &'a D) -> Self {
         Self {
             addr,
+            local_addr: None,
+            tls_info: None,
+            request_count: 0,
             state: ContextState::new(),
             header: None,
             exts: Extensions::new(),
             date,
+            title_case_headers: false,
+            max_uri_length: crate::config::DEFAULT_MAX_URI_LENGTH,
         }
     }
 
@@ -137,6 +152,30 @@ impl<'a, D, const HEADER_LIMIT: usize> Context<'a, D, HEADER_LIMIT> {
         self.state.remove(ContextState::CLOSE)
     }
 
+    /// Set Context to write response header names in title case.
+    #[inline]
+    pub fn set_title_case_headers(&mut self) {
+        self.title_case_headers = true;
+    }
+
+    /// Get title case header names state.
+    #[inline]
+    pub const fn is_title_case_headers(&self) -> bool {
+        self.title_case_headers
+    }
+
+    /// Set max length of request uri in bytes.
+    #[inline]
+    pub fn set_max_uri_length(&mut self, len: usize) {
+        self.max_uri_length = len;
+    }
+
+    /// Get max length of request uri in bytes.
+    #[inline]
+    pub const fn max_uri_length(&self) -> usize {
+        self.max_uri_length
+    }
+
     /// Get expect header state.
     #[inline]
     pub const fn is_expect_header(&self) -> bool {
@@ -166,4 +205,41 @@ impl<'a, D, const HEADER_LIMIT: usize> Context<'a, D, HEADER_LIMIT> {
     pub fn socket_addr(&self) -> &SocketAddr {
         &self.addr
     }
+
+    /// Set local socket address of connection context associated with.
+    #[inline]
+    pub fn set_local_addr(&mut self, local_addr: Option<SocketAddr>) {
+        self.local_addr = local_addr;
+    }
+
+    /// Get local socket address of connection context associated with.
+    #[inline]
+    pub fn local_addr(&self) -> Option<&SocketAddr> {
+        self.local_addr.as_ref()
+    }
+
+    /// Set tls information of connection context associated with.
+    #[inline]
+    pub fn set_tls_info(&mut self, tls_info: Option<Arc<TlsInfo>>) {
+        self.tls_info = tls_info;
+    }
+
+    /// Get tls information of connection context associated with.
+    #[inline]
+    pub fn tls_info(&self) -> Option<&Arc<TlsInfo>> {
+        self.tls_info.as_ref()
+    }
+
+    // count a newly decoded request and return count of requests decoded from connection.
+    pub(super) fn incr_request_count(&mut self) -> usize {
+        self.request_count += 1;
+        self.request_count
+    }
+
+    // unset early data flag of tls information for following requests.
+    pub(super) fn clear_early_data(&mut self) {
+        if let Some(info) = self.tls_info.as_mut().filter(|info| info.early_data) {
+            Arc::make_mut(info).early_data = false;
+        }
+    }
 }
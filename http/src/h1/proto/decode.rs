@@ -6,7 +6,7 @@ use crate::{
     bytes::{Buf, Bytes, BytesMut},
     http::{
         header::{HeaderMap, HeaderName, HeaderValue, CONNECTION, CONTENT_LENGTH, EXPECT, TRANSFER_ENCODING, UPGRADE},
-        Extension, Method, Request, RequestExt, Uri, Version,
+        ConnInfo, Extension, Method, Request, RequestExt, Uri, Version,
     },
 };
 
@@ -19,6 +19,23 @@ use super::{
 
 type Decoded = (Request<RequestExt<()>>, TransferCoding);
 
+// length of request target from a partial request line. bytes of target are counted until the
+// end of request line is received.
+fn partial_uri_len(buf: &[u8]) -> usize {
+    let line = match buf.iter().position(|b| *b == b'\n') {
+        Some(end) => &buf[..end],
+        None => buf,
+    };
+
+    match line.iter().position(|b| *b == b' ') {
+        Some(start) => {
+            let target = &line[start + 1..];
+            target.iter().position(|b| *b == b' ').unwrap_or(target.len())
+        }
+        None => 0,
+    }
+}
+
 impl<D, const MAX_HEADERS: usize> Context<'_, D, MAX_HEADERS> {
     // decode head and generate request and body decoder.
     pub fn decode_head<const READ_BUF_LIMIT: usize>(
@@ -68,6 +85,10 @@ impl<D, const MAX_HEADERS: usize> Context<'_, D, MAX_HEADERS> {
                 let path_head = path.as_ptr() as usize - buf.as_ptr() as usize;
                 let path_len = path.len();
 
+                if path_len > self.max_uri_length() {
+                    return Err(ProtoError::UriTooLong);
+                }
+
                 // split the headers from buffer.
                 let slice = buf.split_to(len).freeze();
 
@@ -82,7 +103,22 @@ impl<D, const MAX_HEADERS: usize> Context<'_, D, MAX_HEADERS> {
                     self.try_write_header(&mut headers, &mut decoder, idx, &slice, version)?;
                 }
 
-                let ext = Extension::new(*self.socket_addr());
+                let request_count = self.incr_request_count();
+                let conn_info = ConnInfo::new(*self.socket_addr())
+                    .with_version(version)
+                    .with_local_addr(self.local_addr().copied())
+                    .with_request_count(request_count)
+                    .with_keep_alive(!self.is_connection_closed());
+
+                let ext = Extension::new(*self.socket_addr())
+                    .with_tls_info(self.tls_info().cloned())
+                    .with_conn_info(conn_info);
+
+                // tls early data is read into buffer before any other data. when buffer is drained
+                // all following requests are received after handshake.
+                if buf.is_empty() {
+                    self.clear_early_data();
+                }
                 let mut req = Request::new(RequestExt::from_parts((), ext));
 
                 let extensions = self.take_extensions();
@@ -97,7 +133,10 @@ impl<D, const MAX_HEADERS: usize> Context<'_, D, MAX_HEADERS> {
             }
 
             Status::Partial => {
-                if buf.remaining() >= READ_BUF_LIMIT {
+                // reject long uri before the whole request head is received.
+                if partial_uri_len(buf) > self.max_uri_length() {
+                    Err(ProtoError::UriTooLong)
+                } else if buf.remaining() >= READ_BUF_LIMIT {
                     Err(ProtoError::HeaderTooLarge)
                 } else {
                     Ok(None)
@@ -311,4 +350,77 @@ mod test {
             "transfer coding is not decoded to chunked"
         );
     }
+
+    #[test]
+    fn uri_too_long() {
+        let mut ctx = Context::<_, 4>::new(&());
+        ctx.set_max_uri_length(6);
+
+        let mut buf = BytesMut::from(&b"GET /996/251 HTTP/1.1\r\n\r\n"[..]);
+        assert!(matches!(ctx.decode_head::<128>(&mut buf), Err(ProtoError::UriTooLong)));
+
+        // long uri is rejected before request line is finished.
+        let mut buf = BytesMut::from(&b"GET /996/25"[..]);
+        assert!(matches!(ctx.decode_head::<128>(&mut buf), Err(ProtoError::UriTooLong)));
+
+        let mut buf = BytesMut::from(&b"GET /996/2"[..]);
+        assert!(ctx.decode_head::<128>(&mut buf).unwrap().is_none());
+
+        let mut buf = BytesMut::from(&b"GET /996/2 HTTP/1.1\r\n\r\n"[..]);
+        assert!(ctx.decode_head::<128>(&mut buf).unwrap().is_some());
+    }
+    #[test]
+    fn early_data() {
+        use std::sync::Arc;
+
+        use crate::http::TlsInfo;
+
+        let mut ctx = Context::<_, 4>::new(&());
+        let info = TlsInfo {
+            early_data: true,
+            ..Default::default()
+        };
+        ctx.set_tls_info(Some(Arc::new(info)));
+
+        let is_early_data = |req: &Request<RequestExt<()>>| req.body().tls_info().unwrap().early_data;
+
+        // pipelined requests received as early data.
+        let mut buf = BytesMut::from(&b"GET / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n"[..]);
+        let (req, _) = ctx.decode_head::<128>(&mut buf).unwrap().unwrap();
+        assert!(is_early_data(&req));
+        let (req, _) = ctx.decode_head::<128>(&mut buf).unwrap().unwrap();
+        assert!(is_early_data(&req));
+
+        let mut buf = BytesMut::from(&b"GET / HTTP/1.1\r\n\r\n"[..]);
+        let (req, _) = ctx.decode_head::<128>(&mut buf).unwrap().unwrap();
+        assert!(!is_early_data(&req));
+    }
+    #[test]
+    fn conn_info() {
+        let addr = "127.0.0.1:8080".parse().unwrap();
+
+        let mut ctx = Context::<_, 4>::new(&());
+        ctx.set_local_addr(Some(addr));
+
+        let mut buf = BytesMut::from(&b"GET / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\nConnection: close\r\n\r\n"[..]);
+
+        let (req, _) = ctx.decode_head::<128>(&mut buf).unwrap().unwrap();
+        let info = req.body().conn_info();
+        assert_eq!(info.version(), Version::HTTP_11);
+        assert_eq!(info.local_addr(), Some(&addr));
+        assert_eq!(info.request_count(), 1);
+        assert!(info.is_keep_alive());
+
+        let (req, _) = ctx.decode_head::<128>(&mut buf).unwrap().unwrap();
+        let info = req.body().conn_info();
+        assert_eq!(info.request_count(), 2);
+        assert!(!info.is_keep_alive());
+
+        let mut buf = BytesMut::from(&b"GET / HTTP/1.0\r\n\r\n"[..]);
+        let (req, _) = ctx.decode_head::<128>(&mut buf).unwrap().unwrap();
+        let info = req.body().conn_info();
+        assert_eq!(info.version(), Version::HTTP_10);
+        assert_eq!(info.request_count(), 3);
+        assert!(!info.is_keep_alive());
+    }
 }
@@ -3,7 +3,7 @@ use tracing::{debug, error, warn};
 
 use crate::{
     body::BodySize,
-    bytes::{Bytes, BytesMut},
+    bytes::{BufMut, Bytes, BytesMut},
     date::DateTime,
     http::{
         header::{HeaderMap, CONNECTION, CONTENT_LENGTH, DATE, SET_COOKIE, TE, TRANSFER_ENCODING, UPGRADE},
@@ -115,6 +115,8 @@ where
     ) -> Result<TransferCoding, ProtoError> {
         let mut skip_date = false;
 
+        let title_case = self.is_title_case_headers();
+
         // use the shortest header name as default
         let mut name = TE;
 
@@ -171,7 +173,11 @@ where
                 let name = name.as_str().as_bytes();
                 buf.reserve(name.len() + value.len() + 4);
                 buf.extend_from_slice(b"\r\n");
-                buf.extend_from_slice(name);
+                if title_case {
+                    write_title_case(buf, name);
+                } else {
+                    buf.extend_from_slice(name);
+                }
                 buf.extend_from_slice(b": ");
                 buf.extend_from_slice(value);
             }
@@ -179,7 +185,7 @@ where
 
         // special handling for head method request by removing potential unwanted response body.
         if self.is_head_method() {
-            try_remove_body(buf, skip_ct_te, size, &mut encoding);
+            try_remove_body(buf, skip_ct_te, size, &mut encoding, title_case);
         // encode transfer-encoding or content-length if header map didn't provide them.
         } else if !skip_ct_te {
             match size {
@@ -187,24 +193,28 @@ where
                     encoding = TransferCoding::eof();
                 }
                 BodySize::Stream => {
-                    buf.extend_from_slice(CHUNKED_HEADER);
+                    buf.extend_from_slice(chunked_header(title_case));
                     encoding = TransferCoding::encode_chunked();
                 }
                 BodySize::Sized(size) => {
-                    write_length_header(buf, size);
+                    write_length_header_with(buf, size, title_case);
                     encoding = TransferCoding::length(size as u64);
                 }
             }
         }
 
         if self.is_connection_closed() {
-            buf.extend_from_slice(CLOSE_HEADER);
+            buf.extend_from_slice(if title_case {
+                b"\r\nConnection: close"
+            } else {
+                CLOSE_HEADER
+            });
         }
 
         // set date header if there is not any.
         if !skip_date {
             buf.reserve(D::DATE_VALUE_LENGTH + 12);
-            buf.extend_from_slice(b"\r\ndate: ");
+            buf.extend_from_slice(if title_case { b"\r\nDate: " } else { b"\r\ndate: " });
             self.date().with_date(|slice| buf.extend_from_slice(slice));
         }
 
@@ -217,18 +227,59 @@ where
 const CHUNKED_HEADER: &[u8; 28] = b"\r\ntransfer-encoding: chunked";
 const CLOSE_HEADER: &[u8; 19] = b"\r\nconnection: close";
 
+fn chunked_header(title_case: bool) -> &'static [u8] {
+    if title_case {
+        b"\r\nTransfer-Encoding: chunked"
+    } else {
+        CHUNKED_HEADER
+    }
+}
+
+// write lower case header name in title case. well known headers with irregular case are written
+// in their canonical form.
+fn write_title_case(buf: &mut BytesMut, name: &[u8]) {
+    let canonical: &[u8] = match name {
+        b"etag" => b"ETag",
+        b"te" => b"TE",
+        b"dnt" => b"DNT",
+        b"www-authenticate" => b"WWW-Authenticate",
+        b"content-md5" => b"Content-MD5",
+        b"x-xss-protection" => b"X-XSS-Protection",
+        b"sec-websocket-accept" => b"Sec-WebSocket-Accept",
+        b"sec-websocket-extensions" => b"Sec-WebSocket-Extensions",
+        b"sec-websocket-key" => b"Sec-WebSocket-Key",
+        b"sec-websocket-protocol" => b"Sec-WebSocket-Protocol",
+        b"sec-websocket-version" => b"Sec-WebSocket-Version",
+        _ => {
+            let mut upper = true;
+            for &b in name {
+                buf.put_u8(if upper { b.to_ascii_uppercase() } else { b });
+                upper = b == b'-';
+            }
+            return;
+        }
+    };
+    buf.extend_from_slice(canonical);
+}
+
 #[cold]
 #[inline(never)]
-fn try_remove_body(buf: &mut BytesMut, skip_ct_te: bool, size: BodySize, encoding: &mut TransferCoding) {
+fn try_remove_body(
+    buf: &mut BytesMut,
+    skip_ct_te: bool,
+    size: BodySize,
+    encoding: &mut TransferCoding,
+    title_case: bool,
+) {
     *encoding = TransferCoding::eof();
 
     match size {
         BodySize::None => return,
         BodySize::Stream if !skip_ct_te => {
-            buf.extend_from_slice(CHUNKED_HEADER);
+            buf.extend_from_slice(chunked_header(title_case));
         }
         BodySize::Sized(size) if !skip_ct_te => {
-            write_length_header(buf, size);
+            write_length_header_with(buf, size, title_case);
         }
         _ => {}
     }
@@ -237,11 +288,19 @@ fn try_remove_body(buf: &mut BytesMut, skip_ct_te: bool, size: BodySize, encodin
 }
 
 pub(crate) fn write_length_header(buf: &mut BytesMut, size: usize) {
+    write_length_header_with(buf, size, false)
+}
+
+fn write_length_header_with(buf: &mut BytesMut, size: usize, title_case: bool) {
     let mut buffer = itoa::Buffer::new();
     let buffer = buffer.format(size).as_bytes();
 
     buf.reserve(buffer.len() + 18);
-    buf.extend_from_slice(b"\r\ncontent-length: ");
+    buf.extend_from_slice(if title_case {
+        b"\r\nContent-Length: "
+    } else {
+        b"\r\ncontent-length: "
+    });
     buf.extend_from_slice(buffer);
 }
 
@@ -250,7 +309,10 @@ mod test {
     use crate::{
         body::{BoxBody, Once},
         date::SystemTimeDateTimeHandler,
-        http::{HeaderValue, Response},
+        http::{
+            header::{CONTENT_TYPE, ETAG},
+            HeaderValue, Response,
+        },
     };
 
     use super::*;
@@ -313,4 +375,36 @@ mod test {
         assert_eq!(header[1].name, "set-cookie");
         assert_eq!(header[1].value, b"bar=bar");
     }
+
+    #[test]
+    fn title_case_header() {
+        let mut ctx = Context::<_, 64>::new(&SystemTimeDateTimeHandler);
+        ctx.set_title_case_headers();
+        ctx.set_close();
+
+        let mut res = Response::new(BoxBody::new(Once::new(Bytes::from_static(b"996"))));
+
+        res.headers_mut().insert(ETAG, HeaderValue::from_static("\"996\""));
+        res.headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+        res.headers_mut()
+            .insert("x-custom-header", HeaderValue::from_static("foo"));
+
+        let (parts, body) = res.into_parts();
+
+        let mut buf = BytesMut::new();
+        ctx.encode_head(parts, &body, &mut buf).unwrap();
+
+        let head = core::str::from_utf8(&buf).unwrap();
+        for name in [
+            "ETag",
+            "Content-Type",
+            "X-Custom-Header",
+            "Content-Length",
+            "Connection",
+            "Date",
+        ] {
+            assert!(head.contains(&format!("\r\n{name}: ")), "{name} not found in {head}");
+        }
+    }
 }
@@ -7,6 +7,7 @@ pub enum ProtoError {
     HeaderTooLarge,
     Method,
     Uri,
+    UriTooLong,
     NewLine,
     Status,
     Token,
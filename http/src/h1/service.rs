@@ -41,14 +41,26 @@ where
             .await
             .map_err(|_| HttpServiceError::Timeout(TimeoutError::TlsAccept))??;
 
-        super::dispatcher::run(&mut io, addr, timer, self.config, &self.service, self.date.get())
-            .await
-            .map_err(Into::into)
+        super::dispatcher::run(
+            &mut io,
+            addr,
+            None,
+            None,
+            timer,
+            self.config,
+            &self.service,
+            self.date.get(),
+            self.draining.as_deref(),
+        )
+        .await
+        .map_err(Into::into)
     }
 }
 
 #[cfg(feature = "io-uring")]
 use {
+    core::sync::atomic::AtomicBool,
+    std::sync::Arc,
     xitca_io::{
         io_uring::{AsyncBufRead, AsyncBufWrite},
         net::io_uring::TcpStream,
@@ -69,6 +81,7 @@ pub struct H1UringService<S, A, const HEADER_LIMIT: usize, const READ_BUF_LIMIT:
     pub(crate) date: DateTimeService,
     pub(crate) service: S,
     pub(crate) tls_acceptor: A,
+    pub(crate) draining: Option<Arc<AtomicBool>>,
 }
 
 #[cfg(feature = "io-uring")]
@@ -85,8 +98,14 @@ impl<S, A, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE_B
             date: DateTimeService::new(),
             service,
             tls_acceptor,
+            draining: None,
         }
     }
+
+    pub(super) fn draining(mut self, draining: Option<Arc<AtomicBool>>) -> Self {
+        self.draining = draining;
+        self
+    }
 }
 
 #[cfg(feature = "io-uring")]
@@ -113,10 +132,18 @@ where
             .await
             .map_err(|_| HttpServiceError::Timeout(TimeoutError::TlsAccept))??;
 
-        super::dispatcher_uring::Dispatcher::new(io, addr, timer, self.config, &self.service, self.date.get())
-            .run()
-            .await
-            .map_err(Into::into)
+        super::dispatcher_uring::Dispatcher::new(
+            io,
+            addr,
+            timer,
+            self.config,
+            &self.service,
+            self.date.get(),
+            self.draining.as_deref(),
+        )
+        .run()
+        .await
+        .map_err(Into::into)
     }
 }
 
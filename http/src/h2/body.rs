@@ -1,10 +1,16 @@
 use core::{
+    cmp,
     pin::Pin,
-    task::{ready, Context, Poll},
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    task::{Context, Poll},
+    time::Duration,
 };
 
-use futures_core::stream::Stream;
-use h2::RecvStream;
+use std::sync::{Arc, OnceLock};
+
+use futures_core::{stream::Stream, task::__internal::AtomicWaker};
+use h2::{FlowControl, RecvStream};
+use tokio::time::Instant;
 
 use crate::{bytes::Bytes, error::BodyError};
 
@@ -12,6 +18,128 @@ use crate::{bytes::Bytes, error::BodyError};
 pub struct RequestBody {
     end_stream: bool,
     stream: RecvStream,
+    activity: Option<Arc<StreamActivity>>,
+}
+
+impl RequestBody {
+    pub(crate) fn with_activity(mut self, activity: Arc<StreamActivity>) -> Self {
+        let _ = activity.flow.set(self.stream.flow_control().clone());
+        self.activity = Some(activity);
+        self
+    }
+
+    fn close_activity(&mut self) {
+        if let Some(activity) = self.activity.take() {
+            activity.close();
+        }
+    }
+}
+
+impl Drop for RequestBody {
+    fn drop(&mut self) {
+        self.close_activity();
+    }
+}
+
+/// activity of a half open stream shared between it's request body and dispatcher. it's closed when
+/// request body is finished or dropped.
+pub(crate) struct StreamActivity {
+    start: Instant,
+    // milliseconds from start when last DATA frame is received.
+    last: AtomicU64,
+    closed: AtomicBool,
+    // count of half open streams of the connection.
+    half_open: Arc<AtomicUsize>,
+    // flow control of stream. used for observing DATA frames received but not consumed yet.
+    flow: OnceLock<FlowControl>,
+    // milliseconds from start plus one when consumer of request body started waiting for data. zero
+    // when consumer is not waiting.
+    waiting: AtomicU64,
+    // woken when consumer of request body starts waiting for data.
+    waiting_waker: AtomicWaker,
+}
+
+impl StreamActivity {
+    pub(crate) fn new(half_open: Arc<AtomicUsize>) -> Self {
+        half_open.fetch_add(1, Ordering::Relaxed);
+        Self {
+            start: Instant::now(),
+            last: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+            half_open,
+            flow: OnceLock::new(),
+            waiting: AtomicU64::new(0),
+            waiting_waker: AtomicWaker::new(),
+        }
+    }
+
+    fn elapsed_millis(&self) -> u64 {
+        u64::try_from(self.start.elapsed().as_millis()).unwrap_or(u64::MAX)
+    }
+
+    fn touch(&self) {
+        self.last.store(self.elapsed_millis(), Ordering::Relaxed);
+    }
+
+    fn start_waiting(&self) {
+        if self.waiting.load(Ordering::Relaxed) == 0 {
+            let now = self.elapsed_millis().saturating_add(1);
+            self.waiting.store(now, Ordering::Relaxed);
+            self.waiting_waker.wake();
+        }
+    }
+
+    fn stop_waiting(&self) {
+        self.waiting.store(0, Ordering::Relaxed);
+    }
+
+    fn close(&self) {
+        if !self.closed.swap(true, Ordering::Relaxed) {
+            self.half_open.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    // DATA frames are received and buffered while consumer of request body is not polling it. they
+    // count as used capacity of stream until consumer releases them. stream is touched when there is
+    // unconsumed data so it's not considered idle when peer is sending data to a slow consumer.
+    pub(crate) fn touch_on_received(&self) -> bool {
+        let received = self.flow.get().is_some_and(|flow| flow.used_capacity() > 0);
+        if received {
+            self.touch();
+        }
+        received
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    // deadline of stream being idle for given duration.
+    pub(crate) fn idle_deadline(&self, dur: Duration) -> Instant {
+        self.start + Duration::from_millis(self.last.load(Ordering::Relaxed)) + dur
+    }
+
+    // deadline of consumer of request body waiting for data for given duration. DATA frame received
+    // while waiting pushes the deadline back. None when consumer is not waiting.
+    pub(crate) fn waiting_deadline(&self, dur: Duration) -> Option<Instant> {
+        match self.waiting.load(Ordering::Relaxed) {
+            0 => None,
+            waiting => {
+                let since = cmp::max(waiting - 1, self.last.load(Ordering::Relaxed));
+                Some(self.start + Duration::from_millis(since) + dur)
+            }
+        }
+    }
+
+    // resolve when consumer of request body is waiting for data or stream is closed.
+    pub(crate) fn poll_waiting(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.waiting_waker.register(cx.waker());
+        if self.waiting.load(Ordering::Relaxed) != 0 || self.is_closed() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
 }
 
 impl Stream for RequestBody {
@@ -24,10 +152,29 @@ impl Stream for RequestBody {
             return Poll::Ready(None);
         }
 
-        let res = ready!(this.stream.poll_data(cx)?);
+        let res = match this.stream.poll_data(cx) {
+            Poll::Ready(res) => {
+                if let Some(activity) = this.activity.as_ref() {
+                    activity.stop_waiting();
+                }
+                res.transpose()?
+            }
+            Poll::Pending => {
+                if let Some(activity) = this.activity.as_ref() {
+                    activity.start_waiting();
+                }
+                return Poll::Pending;
+            }
+        };
 
         this.end_stream = this.stream.is_end_stream();
 
+        if this.end_stream {
+            this.close_activity();
+        } else if let Some(activity) = this.activity.as_ref() {
+            activity.touch();
+        }
+
         match res {
             Some(bytes) if bytes.is_empty() => self.poll_next(cx),
             Some(bytes) => {
@@ -53,6 +200,7 @@ impl From<RecvStream> for RequestBody {
         RequestBody {
             end_stream: false,
             stream,
+            activity: None,
         }
     }
 }
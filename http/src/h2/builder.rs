@@ -29,6 +29,7 @@ impl<St, FA, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE
         HttpServiceBuilder {
             tls_factory: self.tls_factory,
             config: self.config,
+            draining: self.draining,
             _body: std::marker::PhantomData,
         }
     }
@@ -47,7 +48,7 @@ where
     async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
         let service = res.map_err(|e| Box::new(e) as Error)?;
         let tls_acceptor = self.tls_factory.call(()).await.map_err(|e| Box::new(e) as Error)?;
-        Ok(H2Service::new(self.config, service, tls_acceptor))
+        Ok(H2Service::new(self.config, service, tls_acceptor).draining(self.draining.clone()))
     }
 }
 
@@ -6,8 +6,9 @@ mod proto;
 mod service;
 
 pub mod body;
+pub mod tunnel;
 
-pub(crate) use self::proto::Dispatcher;
+pub(crate) use self::proto::{max_header_list_size, Dispatcher};
 
 pub use self::body::RequestBody;
 pub use self::error::Error;
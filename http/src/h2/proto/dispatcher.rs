@@ -4,13 +4,16 @@ use core::{
     marker::PhantomData,
     net::SocketAddr,
     pin::{pin, Pin},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
     task::{ready, Context, Poll},
     time::Duration,
 };
 
+use std::sync::Arc;
+
 use ::h2::{
     server::{Connection, SendResponse},
-    Ping, PingPong,
+    Ping, PingPong, Reason,
 };
 use futures_core::stream::Stream;
 use tracing::trace;
@@ -23,10 +26,13 @@ use crate::{
     bytes::Bytes,
     date::{DateTime, DateTimeHandle},
     error::HttpServiceError,
-    h2::{body::RequestBody, error::Error},
+    h2::{
+        body::{RequestBody, StreamActivity},
+        error::Error,
+    },
     http::{
         header::{HeaderMap, HeaderName, HeaderValue, CONNECTION, CONTENT_LENGTH, DATE, TRAILER},
-        Extension, Request, RequestExt, Response, Version,
+        ConnInfo, Extension, Request, RequestExt, Response, StatusCode, TlsInfo, Version,
     },
     util::{futures::Queue, timer::KeepAlive},
 };
@@ -35,10 +41,18 @@ use crate::{
 pub(crate) struct Dispatcher<'a, TlsSt, S, ReqB> {
     io: &'a mut Connection<TlsSt, Bytes>,
     addr: SocketAddr,
+    local_addr: Option<SocketAddr>,
+    tls_info: Option<Arc<TlsInfo>>,
     keep_alive: Pin<&'a mut KeepAlive>,
     ka_dur: Duration,
+    header_limit: usize,
+    max_uri_length: usize,
+    stream_idle_timeout: Option<Duration>,
+    max_half_open_streams: usize,
+    request_body_timeout: Option<Duration>,
     service: &'a S,
     date: &'a DateTimeHandle,
+    draining: Option<&'a AtomicBool>,
     _req_body: PhantomData<ReqB>,
 }
 
@@ -53,21 +67,38 @@ where
     TlsSt: AsyncRead + AsyncWrite + Unpin,
     ReqB: From<RequestBody>,
 {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         io: &'a mut Connection<TlsSt, Bytes>,
         addr: SocketAddr,
+        local_addr: Option<SocketAddr>,
+        tls_info: Option<Arc<TlsInfo>>,
         keep_alive: Pin<&'a mut KeepAlive>,
         ka_dur: Duration,
+        header_limit: usize,
+        max_uri_length: usize,
+        stream_idle_timeout: Option<Duration>,
+        max_half_open_streams: usize,
+        request_body_timeout: Option<Duration>,
         service: &'a S,
         date: &'a DateTimeHandle,
+        draining: Option<&'a AtomicBool>,
     ) -> Self {
         Self {
             io,
             addr,
+            local_addr,
+            tls_info,
             keep_alive,
             ka_dur,
+            header_limit,
+            max_uri_length,
+            stream_idle_timeout,
+            max_half_open_streams,
+            request_body_timeout,
             service,
             date,
+            draining,
             _req_body: PhantomData,
         }
     }
@@ -76,10 +107,18 @@ where
         let Self {
             io,
             addr,
+            local_addr,
+            tls_info,
             mut keep_alive,
             ka_dur,
+            header_limit,
+            max_uri_length,
+            stream_idle_timeout,
+            max_half_open_streams,
+            request_body_timeout,
             service,
             date,
+            draining,
             ..
         } = self;
 
@@ -99,20 +138,87 @@ where
         };
 
         let mut queue = Queue::new();
+        let half_open = Arc::new(AtomicUsize::new(0));
+        let mut is_draining = false;
+        // count of requests received from connection.
+        let mut request_count = 0;
+        let mut wait_draining = pin!(wait_for_draining(draining));
 
         loop {
-            match io.accept().select(try_poll_queue(&mut queue, &mut ping_pong)).await {
-                SelectOutput::A(Some(Ok((req, tx)))) => {
+            // send GOAWAY and refuse new streams when server is draining. h2 would send GOAWAY with
+            // the last processed stream id after a ping round trip and in flight streams are finished
+            // before connection is closed.
+            if !is_draining && draining.is_some_and(|draining| draining.load(Ordering::Relaxed)) {
+                trace!("Server is draining. Shutting down gracefully");
+                is_draining = true;
+                io.graceful_shutdown();
+            }
+
+            // idle connection still has to observe draining state in time.
+            let drain_check = async {
+                if is_draining {
+                    core::future::pending().await
+                } else {
+                    wait_draining.as_mut().await
+                }
+            };
+
+            let accept = io.accept().select(drain_check);
+
+            match accept.select(try_poll_queue(&mut queue, &mut ping_pong)).await {
+                SelectOutput::A(SelectOutput::B(_)) => continue,
+                SelectOutput::A(SelectOutput::A(Some(Ok((req, mut tx))))) => {
+                    // reject stream with too many headers. other streams of the connection are not affected.
+                    if req.headers().len() > header_limit {
+                        reject_stream(
+                            tx,
+                            req.body().is_end_stream(),
+                            StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+                        );
+                        continue;
+                    }
+
+                    // reject stream with :path pseudo-header too long.
+                    if req.uri().path_and_query().map_or(0, |p| p.as_str().len()) > max_uri_length {
+                        reject_stream(tx, req.body().is_end_stream(), StatusCode::URI_TOO_LONG);
+                        continue;
+                    }
+
+                    // track stream with request body still being received.
+                    let activity = if req.body().is_end_stream() {
+                        None
+                    } else {
+                        // refuse stream when too many streams are half open. client can retry it later.
+                        if half_open.load(Ordering::Relaxed) >= max_half_open_streams {
+                            trace!("Too many half open streams. Refusing stream");
+                            tx.send_reset(Reason::REFUSED_STREAM);
+                            continue;
+                        }
+                        Some(Arc::new(StreamActivity::new(half_open.clone())))
+                    };
+
                     // Convert http::Request body type to crate::h2::Body
                     // and reconstruct as HttpRequest.
                     let req = req.map(|body| {
-                        let body = ReqB::from(RequestBody::from(body));
-                        RequestExt::from_parts(body, Extension::new(addr))
+                        let mut body = RequestBody::from(body);
+                        if let Some(activity) = activity.as_ref() {
+                            body = body.with_activity(activity.clone());
+                        }
+                        request_count += 1;
+                        let conn_info = ConnInfo::new(addr)
+                            .with_version(Version::HTTP_2)
+                            .with_local_addr(local_addr)
+                            .with_request_count(request_count)
+                            .with_keep_alive(true);
+                        let ext = Extension::new(addr)
+                            .with_tls_info(tls_info.clone())
+                            .with_conn_info(conn_info);
+                        RequestExt::from_parts(ReqB::from(body), ext)
                     });
 
                     queue.push(async move {
                         let fut = service.call(req);
-                        h2_handler(fut, tx, date).await
+                        h2_handler(fut, tx, date, activity, stream_idle_timeout, request_body_timeout).await
                     });
                 }
                 SelectOutput::B(SelectOutput::A(_)) => io.graceful_shutdown(),
@@ -120,11 +226,13 @@ where
                     trace!("Connection keep-alive timeout. Shutting down");
                     return Ok(());
                 }
-                SelectOutput::A(None) => {
+                SelectOutput::A(SelectOutput::A(None)) => {
                     trace!("Connection closed by remote. Shutting down");
                     break;
                 }
-                SelectOutput::A(Some(Err(e))) | SelectOutput::B(SelectOutput::B(Err(e))) => return Err(From::from(e)),
+                SelectOutput::A(SelectOutput::A(Some(Err(e)))) | SelectOutput::B(SelectOutput::B(Err(e))) => {
+                    return Err(From::from(e))
+                }
             }
         }
 
@@ -134,6 +242,21 @@ where
     }
 }
 
+// interval of checking server draining state.
+const DRAIN_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+// resolve when server starts draining. never resolve when draining state is not observed.
+async fn wait_for_draining(draining: Option<&AtomicBool>) {
+    match draining {
+        Some(draining) => {
+            while !draining.load(Ordering::Relaxed) {
+                tokio::time::sleep(DRAIN_CHECK_INTERVAL).await;
+            }
+        }
+        None => core::future::pending().await,
+    }
+}
+
 async fn try_poll_queue<F, E, S, B>(
     queue: &mut Queue<F>,
     ping_ping: &mut H2PingPong<'_>,
@@ -208,24 +331,154 @@ impl Future for H2PingPong<'_> {
     }
 }
 
+/// max size of decoded header block of a single stream. stream exceeding it is rejected with 431 response
+/// and RST_STREAM frame while the connection and other streams of it are not affected.
+pub(crate) fn max_header_list_size(read_buf_limit: usize) -> u32 {
+    u32::try_from(read_buf_limit).unwrap_or(u32::MAX)
+}
+
+// send a response without body and reset the stream if remote has not finished sending request.
+fn reject_stream(mut tx: SendResponse<Bytes>, is_end_stream: bool, status: StatusCode) {
+    let mut res = Response::new(());
+    *res.status_mut() = status;
+    *res.version_mut() = Version::HTTP_2;
+
+    if let Err(e) = tx.send_response(res, true) {
+        trace!("Failed to send response for rejected stream: {e:?}");
+        return;
+    }
+
+    if !is_end_stream {
+        tx.send_reset(Reason::NO_ERROR);
+    }
+}
+
 enum ConnectionState {
     KeepAlive,
     Close,
 }
 
 // handle request/response and return if connection should go into graceful shutdown.
+// stream is reset when it's request body is idle for longer than given timeout.
+// request is aborted with 408 response when it's request body is idle for longer than given body timeout
+// before response is produced.
 async fn h2_handler<Fut, B, SE, BE>(
     fut: Fut,
     mut tx: SendResponse<Bytes>,
     date: &DateTimeHandle,
+    activity: Option<Arc<StreamActivity>>,
+    idle_timeout: Option<Duration>,
+    body_timeout: Option<Duration>,
 ) -> Result<ConnectionState, Error<SE, BE>>
 where
     Fut: Future<Output = Result<Response<B>, SE>>,
     B: Stream<Item = Result<Bytes, BE>>,
     BE: fmt::Debug,
 {
+    let Some(activity) = activity else {
+        return h2_handler_inner(fut, &mut tx, date, core::future::pending()).await;
+    };
+
+    let body_timeout = async {
+        match body_timeout {
+            Some(dur) => wait_for_body(&activity, dur).await,
+            None => core::future::pending().await,
+        }
+    };
+
+    let Some(dur) = idle_timeout else {
+        return h2_handler_inner(fut, &mut tx, date, body_timeout).await;
+    };
+
+    match h2_handler_inner(fut, &mut tx, date, body_timeout)
+        .select(wait_for_idle(&activity, dur))
+        .await
+    {
+        SelectOutput::A(res) => res,
+        SelectOutput::B(_) => {
+            trace!("Stream request body idle timeout. Resetting stream");
+            tx.send_reset(Reason::CANCEL);
+            Ok(ConnectionState::KeepAlive)
+        }
+    }
+}
+
+// resolve when stream is idle for given duration. never resolve after stream is closed.
+async fn wait_for_idle(activity: &StreamActivity, dur: Duration) {
+    loop {
+        if activity.is_closed() {
+            return core::future::pending().await;
+        }
+
+        let deadline = activity.idle_deadline(dur);
+        if deadline <= tokio::time::Instant::now() {
+            // DATA frames received without being consumed make the stream active again.
+            if activity.touch_on_received() {
+                continue;
+            }
+            return;
+        }
+
+        tokio::time::sleep_until(deadline).await;
+    }
+}
+
+// resolve when consumer of request body is waiting for data for given duration. timer is not running
+// while consumer is not polling request body. never resolve after stream is closed.
+async fn wait_for_body(activity: &StreamActivity, dur: Duration) {
+    loop {
+        if activity.is_closed() {
+            return core::future::pending().await;
+        }
+
+        match activity.waiting_deadline(dur) {
+            Some(deadline) if deadline <= tokio::time::Instant::now() => {
+                // DATA frames received and not consumed yet push the deadline back.
+                if activity.touch_on_received() {
+                    continue;
+                }
+                return;
+            }
+            Some(deadline) => tokio::time::sleep_until(deadline).await,
+            None => poll_fn(|cx| activity.poll_waiting(cx)).await,
+        }
+    }
+}
+
+// body_timeout future resolves when request body is timed out.
+async fn h2_handler_inner<Fut, B, SE, BE>(
+    fut: Fut,
+    tx: &mut SendResponse<Bytes>,
+    date: &DateTimeHandle,
+    body_timeout: impl Future<Output = ()>,
+) -> Result<ConnectionState, Error<SE, BE>>
+where
+    Fut: Future<Output = Result<Response<B>, SE>>,
+    B: Stream<Item = Result<Bytes, BE>>,
+    BE: fmt::Debug,
+{
+    // service future is dropped early when client reset the stream or request body is timed out before
+    // response is produced.
+    let res = match fut.select(poll_fn(|cx| tx.poll_reset(cx)).select(body_timeout)).await {
+        SelectOutput::A(res) => res.map_err(Error::Service)?,
+        SelectOutput::B(SelectOutput::A(reason)) => {
+            trace!("Stream reset by remote before response produced: {reason:?}");
+            return Ok(ConnectionState::KeepAlive);
+        }
+        SelectOutput::B(SelectOutput::B(_)) => {
+            trace!("Stream request body timeout. Aborting request");
+            // request body is dropped with service future and stream is reset with NO_ERROR code after response
+            // is sent. explicit reset would discard the response.
+            let mut res = Response::new(());
+            *res.status_mut() = StatusCode::REQUEST_TIMEOUT;
+            *res.version_mut() = Version::HTTP_2;
+            tx.send_response(res, true)?;
+            return Ok(ConnectionState::KeepAlive);
+        }
+    };
+
     // split response to header and body.
-    let (res, body) = fut.await.map_err(Error::Service)?.into_parts();
+    let (res, body) = res.into_parts();
     let mut res = Response::from_parts(res, ());
 
     // set response version.
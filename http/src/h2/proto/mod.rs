@@ -14,7 +14,7 @@ mod settings;
 mod stream_id;
 mod window_update;
 
-pub(crate) use dispatcher::Dispatcher;
+pub(crate) use dispatcher::{max_header_list_size, Dispatcher};
 
 const HEADER_LEN: usize = 9;
 
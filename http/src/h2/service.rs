@@ -12,7 +12,10 @@ use crate::{
     util::timer::Timeout,
 };
 
-use super::{body::RequestBody, proto::Dispatcher};
+use super::{
+    body::RequestBody,
+    proto::{max_header_list_size, Dispatcher},
+};
 
 pub type H2Service<St, S, A, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE_BUF_LIMIT: usize> =
     HttpService<St, S, RequestBody, A, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>;
@@ -61,6 +64,7 @@ where
 
         let mut conn = ::h2::server::Builder::new()
             .enable_connect_protocol()
+            .max_header_list_size(max_header_list_size(READ_BUF_LIMIT))
             .handshake(PollIoAdapter(tls_stream))
             .timeout(timer.as_mut())
             .await
@@ -69,10 +73,18 @@ where
         let dispatcher = Dispatcher::new(
             &mut conn,
             addr,
+            None,
+            None,
             timer,
             self.config.keep_alive_timeout,
+            HEADER_LIMIT,
+            self.config.max_uri_length,
+            self.config.stream_idle_timeout,
+            self.config.max_half_open_streams,
+            self.config.request_body_timeout,
             &self.service,
             self.date.get(),
+            self.draining.as_deref(),
         );
 
         dispatcher.run().await?;
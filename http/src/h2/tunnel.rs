@@ -0,0 +1,57 @@
+//! tunneling over http/2 CONNECT stream.
+//!
+//! Http/2 does not have connection upgrade like Http/1. A tunnel is established on a single stream
+//! with `CONNECT` method instead. Request with plain `CONNECT` method targets an authority for
+//! proxying tcp traffic and request with extended `CONNECT` method ([RFC 8441]) carries additional
+//! `:protocol` pseudo-header naming the protocol running inside the tunnel (`websocket` for example).
+//! Extended `CONNECT` is enabled by default by Http/2 dispatcher and it's `:protocol` pseudo-header
+//! can be obtained with [protocol] function.
+//!
+//! Tunnel data is carried by DATA frames of the stream: request body is the data sent by client and
+//! response body is the data sent to client. Service accepting a tunnel responds with `200 OK` status
+//! and a streaming response body and from this point on the stream is in tunnel mode. [tunnel] function
+//! bridges the request body and response body into a single bidirectional byte stream implementing
+//! [AsyncRead] and [AsyncWrite] traits.
+//!
+//! # Examples
+//! ```rust
+//! use xitca_http::{
+//!     h2::{tunnel, RequestBody},
+//!     http::{Method, Request, RequestExt, Response, StatusCode},
+//!     ResponseBody,
+//! };
+//!
+//! async fn service(req: Request<RequestExt<RequestBody>>) -> Response<ResponseBody> {
+//!     // accept extended CONNECT request with echo protocol.
+//!     if req.method() != Method::CONNECT || tunnel::protocol(&req) != Some("echo") {
+//!         let mut res = Response::new(ResponseBody::empty());
+//!         *res.status_mut() = StatusCode::BAD_REQUEST;
+//!         return res;
+//!     }
+//!
+//!     let (tunnel, body) = tunnel::tunnel(req.into_body());
+//!
+//!     // tunnel must be handled in separate task as response has to be returned to client first.
+//!     tokio::spawn(async move {
+//!         let (mut read, mut write) = tokio::io::split(tunnel);
+//!         let _ = tokio::io::copy(&mut read, &mut write).await;
+//!     });
+//!
+//!     // response with 200 status switches stream into tunnel mode.
+//!     Response::new(ResponseBody::box_stream(body))
+//! }
+//! ```
+//!
+//! [RFC 8441]: https://datatracker.ietf.org/doc/html/rfc8441
+//! [AsyncRead]: xitca_io::io::AsyncRead
+//! [AsyncWrite]: xitca_io::io::AsyncWrite
+
+use crate::http::Request;
+
+pub use crate::util::tunnel::{tunnel, Tunnel, TunnelBody};
+
+/// obtain the value of `:protocol` pseudo-header of extended `CONNECT` request.
+/// return None when request is not extended `CONNECT`.
+pub fn protocol<B>(req: &Request<B>) -> Option<&str> {
+    req.extensions().get::<::h2::ext::Protocol>().map(|p| p.as_str())
+}
@@ -0,0 +1,324 @@
+//! Http/2 over plain text connection. (h2c)
+
+use std::io::{self, ErrorKind};
+
+use xitca_io::io::{AsyncIo, Interest};
+
+use crate::{bytes::BytesMut, http::Version, util::rewind::Rewind};
+
+const PREFACE: &[u8; 24] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+/// read from io until it's known if connection starts with http/2 connection preface. bytes read
+/// are yielded by returned io before reading from io again.
+///
+/// When `http1` feature is enabled and first request of http/1 connection asks for upgrading to
+/// h2c the upgrade is accepted and connection is served as http/2 with the request as stream 1.
+pub(crate) async fn peek_version<Io, const READ_BUF_LIMIT: usize>(mut io: Io) -> io::Result<(Version, Rewind<Io>)>
+where
+    Io: AsyncIo,
+{
+    let mut buf = BytesMut::new();
+
+    // stop reading when received bytes diverge from preface or connection is closed.
+    while buf.len() < PREFACE.len() && PREFACE.starts_with(&buf) {
+        let max = PREFACE.len() - buf.len();
+        if read(&mut io, &mut buf, max).await? == 0 {
+            break;
+        }
+    }
+
+    if buf.starts_with(PREFACE) {
+        return Ok((Version::HTTP_2, Rewind::new(buf.freeze(), io)));
+    }
+
+    #[cfg(feature = "http1")]
+    if let Some(buf) = upgrade::try_upgrade::<_, READ_BUF_LIMIT>(&mut io, &mut buf).await? {
+        return Ok((Version::HTTP_2, Rewind::new(buf, io)));
+    }
+
+    Ok((Version::HTTP_11, Rewind::new(buf.freeze(), io)))
+}
+
+// read at most max bytes from io and append them to buf.
+async fn read<Io>(io: &mut Io, buf: &mut BytesMut, max: usize) -> io::Result<usize>
+where
+    Io: AsyncIo,
+{
+    let mut chunk = [0; 4096];
+    let max = max.min(chunk.len());
+    loop {
+        match io.read(&mut chunk[..max]) {
+            Ok(n) => {
+                buf.extend_from_slice(&chunk[..n]);
+                return Ok(n);
+            }
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                io.ready(Interest::READABLE).await?;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(feature = "http1")]
+mod upgrade {
+    use httparse::{Request, Status, EMPTY_HEADER};
+
+    use crate::bytes::{BufMut, Bytes};
+
+    use super::*;
+
+    const MAX_HEADERS: usize = 64;
+
+    const SWITCHING_PROTOCOLS: &[u8] =
+        b"HTTP/1.1 101 Switching Protocols\r\nconnection: upgrade\r\nupgrade: h2c\r\n\r\n";
+
+    // http/2 frame header size.
+    const FRAME_HEADER_LEN: usize = 9;
+    // default SETTINGS_MAX_FRAME_SIZE of http/2.
+    const MAX_FRAME_SIZE: usize = 16_384;
+
+    const FRAME_HEADERS: u8 = 0x1;
+    const FLAG_END_STREAM: u8 = 0x1;
+    const FLAG_END_HEADERS: u8 = 0x4;
+
+    /// read first request head of http/1 connection and try to upgrade connection to h2c with it.
+    /// On success returns bytes to be yielded to http/2 connection where upgrade request is
+    /// encoded as HEADERS frame of stream 1.
+    ///
+    /// Only request without body can be upgraded. Other requests are left untouched in buf and
+    /// served as http/1.
+    pub(super) async fn try_upgrade<Io, const READ_BUF_LIMIT: usize>(
+        io: &mut Io,
+        buf: &mut BytesMut,
+    ) -> io::Result<Option<Bytes>>
+    where
+        Io: AsyncIo,
+    {
+        let (len, block) = loop {
+            let mut headers = [EMPTY_HEADER; MAX_HEADERS];
+            let mut req = Request::new(&mut headers);
+            match req.parse(buf) {
+                Ok(Status::Complete(len)) => match header_block(&req) {
+                    Some(block) => break (len, block),
+                    None => return Ok(None),
+                },
+                Ok(Status::Partial) if buf.len() < READ_BUF_LIMIT => {
+                    let max = READ_BUF_LIMIT - buf.len();
+                    if read(io, buf, max).await? == 0 {
+                        return Ok(None);
+                    }
+                }
+                // let http/1 dispatcher handle malformed and too large request.
+                _ => return Ok(None),
+            }
+        };
+
+        // header block of stream 1 must fit in a single HEADERS frame.
+        if block.len() > MAX_FRAME_SIZE {
+            return Ok(None);
+        }
+
+        write_all(io, SWITCHING_PROTOCOLS).await?;
+
+        // after 101 response client sends connection preface followed by SETTINGS frame. HEADERS
+        // frame of upgrade request is injected after them.
+        let _ = buf.split_to(len);
+
+        let settings_end = loop {
+            if buf.len() >= PREFACE.len() {
+                if !buf.starts_with(PREFACE) {
+                    return Err(io::Error::new(ErrorKind::InvalidData, "invalid h2c connection preface"));
+                }
+                if buf.len() >= PREFACE.len() + FRAME_HEADER_LEN {
+                    let header = &buf[PREFACE.len()..];
+                    let payload_len = u32::from_be_bytes([0, header[0], header[1], header[2]]) as usize;
+                    let end = PREFACE.len() + FRAME_HEADER_LEN + payload_len;
+                    if buf.len() >= end {
+                        break end;
+                    }
+                }
+            }
+            let max = READ_BUF_LIMIT.saturating_sub(buf.len());
+            if max == 0 || read(io, buf, max).await? == 0 {
+                return Err(ErrorKind::UnexpectedEof.into());
+            }
+        };
+
+        let mut bytes = BytesMut::with_capacity(buf.len() + FRAME_HEADER_LEN + block.len());
+        bytes.extend_from_slice(&buf[..settings_end]);
+        bytes.put_uint(block.len() as u64, 3);
+        bytes.put_u8(FRAME_HEADERS);
+        bytes.put_u8(FLAG_END_HEADERS | FLAG_END_STREAM);
+        bytes.put_u32(1);
+        bytes.extend_from_slice(&block);
+        bytes.extend_from_slice(&buf[settings_end..]);
+
+        Ok(Some(bytes.freeze()))
+    }
+
+    async fn write_all<Io>(io: &mut Io, mut bytes: &[u8]) -> io::Result<()>
+    where
+        Io: AsyncIo,
+    {
+        while !bytes.is_empty() {
+            match io.write(bytes) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(n) => bytes = &bytes[n..],
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                    io.ready(Interest::WRITABLE).await?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        loop {
+            match io.flush() {
+                Ok(_) => return Ok(()),
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                    io.ready(Interest::WRITABLE).await?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // encode request as hpack header block when it's a h2c upgrade request without body.
+    fn header_block(req: &Request<'_, '_>) -> Option<BytesMut> {
+        if req.version != Some(1) {
+            return None;
+        }
+
+        let mut upgrade = false;
+        let mut settings = false;
+        let mut connection = false;
+        let mut authority = None;
+
+        for header in req.headers.iter() {
+            let name = header.name;
+            if name.eq_ignore_ascii_case("upgrade") {
+                upgrade |= has_token(header.value, "h2c");
+            } else if name.eq_ignore_ascii_case("http2-settings") {
+                settings = true;
+            } else if name.eq_ignore_ascii_case("connection") {
+                connection |= has_token(header.value, "upgrade");
+            } else if name.eq_ignore_ascii_case("host") {
+                authority = Some(header.value);
+            } else if name.eq_ignore_ascii_case("transfer-encoding")
+                || (name.eq_ignore_ascii_case("content-length") && header.value != b"0")
+            {
+                // request with body is not upgraded.
+                return None;
+            }
+        }
+
+        if !(upgrade && settings && connection) {
+            return None;
+        }
+
+        let mut block = BytesMut::new();
+        encode(&mut block, b":method", req.method?.as_bytes());
+        encode(&mut block, b":scheme", b"http");
+        encode(&mut block, b":path", req.path?.as_bytes());
+        if let Some(authority) = authority {
+            encode(&mut block, b":authority", authority);
+        }
+
+        for header in req.headers.iter() {
+            let name = header.name;
+            // connection specific headers are not allowed in http/2.
+            if [
+                "host",
+                "connection",
+                "upgrade",
+                "http2-settings",
+                "keep-alive",
+                "proxy-connection",
+                "te",
+            ]
+            .iter()
+            .any(|n| name.eq_ignore_ascii_case(n))
+            {
+                continue;
+            }
+            encode(&mut block, name.to_ascii_lowercase().as_bytes(), header.value);
+        }
+
+        Some(block)
+    }
+
+    fn has_token(value: &[u8], token: &str) -> bool {
+        value
+            .split(|b| *b == b',')
+            .any(|v| v.trim_ascii().eq_ignore_ascii_case(token.as_bytes()))
+    }
+
+    // hpack literal header field without indexing with new name. strings are not huffman encoded.
+    fn encode(block: &mut BytesMut, name: &[u8], value: &[u8]) {
+        block.put_u8(0);
+        encode_str(block, name);
+        encode_str(block, value);
+    }
+
+    fn encode_str(block: &mut BytesMut, str: &[u8]) {
+        // 7 bit prefix integer.
+        const MAX: usize = 127;
+        let mut len = str.len();
+        if len < MAX {
+            block.put_u8(len as u8);
+        } else {
+            block.put_u8(MAX as u8);
+            len -= MAX;
+            while len >= 128 {
+                block.put_u8((len % 128 + 128) as u8);
+                len /= 128;
+            }
+            block.put_u8(len as u8);
+        }
+        block.extend_from_slice(str);
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn upgrade_header_block() {
+            let parse = |head: &[u8]| {
+                let mut headers = [EMPTY_HEADER; MAX_HEADERS];
+                let mut req = Request::new(&mut headers);
+                req.parse(head).unwrap();
+                header_block(&req)
+            };
+
+            let block = parse(
+                b"GET /foo HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade, HTTP2-Settings\r\nUpgrade: h2c\r\nHTTP2-Settings: AAMAAABkAAQAAP__\r\nAccept: */*\r\n\r\n",
+            )
+            .unwrap();
+
+            let mut expected = BytesMut::new();
+            encode(&mut expected, b":method", b"GET");
+            encode(&mut expected, b":scheme", b"http");
+            encode(&mut expected, b":path", b"/foo");
+            encode(&mut expected, b":authority", b"localhost");
+            encode(&mut expected, b"accept", b"*/*");
+            assert_eq!(block, expected);
+
+            // no upgrade header.
+            assert!(parse(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").is_none());
+            // request with body.
+            assert!(parse(
+                b"POST / HTTP/1.1\r\nConnection: Upgrade, HTTP2-Settings\r\nUpgrade: h2c\r\nHTTP2-Settings: \r\nContent-Length: 3\r\n\r\n"
+            )
+            .is_none());
+        }
+
+        #[test]
+        fn integer_encode() {
+            let mut block = BytesMut::new();
+            encode_str(&mut block, &[b'a'; 1337]);
+            // example from RFC 7541 C.1.3 with 7 bit prefix.
+            assert_eq!(&block[..3], &[127, 186, 9]);
+        }
+    }
+}
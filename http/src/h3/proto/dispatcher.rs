@@ -19,7 +19,7 @@ use crate::{
     bytes::Bytes,
     error::HttpServiceError,
     h3::{body::RequestBody, error::Error},
-    http::{Extension, Request, RequestExt, Response},
+    http::{ConnInfo, Extension, Request, RequestExt, Response, Version},
     util::futures::Queue,
 };
 
@@ -59,6 +59,8 @@ where
         let mut conn = server::Connection::new(conn).await?;
 
         let mut queue = Queue::new();
+        // count of requests received from connection.
+        let mut request_count = 0;
 
         // accept loop
         loop {
@@ -69,7 +71,12 @@ where
                     // Reconstruct Request to attach crate body type.
                     let req = req.map(|_| {
                         let body = ReqB::from(RequestBody(rx));
-                        RequestExt::from_parts(body, Extension::new(self.addr))
+                        request_count += 1;
+                        let conn_info = ConnInfo::new(self.addr)
+                            .with_version(Version::HTTP_3)
+                            .with_request_count(request_count)
+                            .with_keep_alive(true);
+                        RequestExt::from_parts(body, Extension::new(self.addr).with_conn_info(conn_info))
                     });
 
                     queue.push(async move {
@@ -10,6 +10,8 @@ use core::{
     task::{Context, Poll},
 };
 
+use std::sync::Arc;
+
 use futures_core::stream::Stream;
 use pin_project_lite::pin_project;
 
@@ -149,15 +151,30 @@ impl Extension {
     pub(crate) fn new(addr: SocketAddr) -> Self {
         Self(Box::new(_Extension {
             addr,
+            tls_info: None,
+            conn_info: ConnInfo::new(addr),
             #[cfg(feature = "router")]
             params: Default::default(),
         }))
     }
+
+    #[cfg(any(feature = "http1", feature = "http2"))]
+    pub(crate) fn with_tls_info(mut self, tls_info: Option<Arc<TlsInfo>>) -> Self {
+        self.0.tls_info = tls_info;
+        self
+    }
+
+    pub(crate) fn with_conn_info(mut self, conn_info: ConnInfo) -> Self {
+        self.0.conn_info = conn_info;
+        self
+    }
 }
 
 #[derive(Clone, Debug)]
 struct _Extension {
     addr: SocketAddr,
+    tls_info: Option<Arc<TlsInfo>>,
+    conn_info: ConnInfo,
     #[cfg(feature = "router")]
     params: Params,
 }
@@ -182,6 +199,33 @@ impl<B> RequestExt<B> {
         &mut self.ext.0.addr
     }
 
+    /// retrieve information of tls connection request is received from.
+    ///
+    /// # Default
+    /// [None] is used for plain text connection and connection where tls information can't be obtained.
+    #[inline]
+    pub fn tls_info(&self) -> Option<&TlsInfo> {
+        self.ext.0.tls_info.as_deref()
+    }
+
+    /// exclusive version of [RequestExt::tls_info]
+    #[inline]
+    pub fn tls_info_mut(&mut self) -> &mut Option<Arc<TlsInfo>> {
+        &mut self.ext.0.tls_info
+    }
+
+    /// retrieve information of connection request is received from.
+    #[inline]
+    pub fn conn_info(&self) -> &ConnInfo {
+        &self.ext.0.conn_info
+    }
+
+    /// exclusive version of [RequestExt::conn_info]
+    #[inline]
+    pub fn conn_info_mut(&mut self) -> &mut ConnInfo {
+        &mut self.ext.0.conn_info
+    }
+
     /// map body type of self to another type with given function closure.
     #[inline]
     pub fn map_body<F, B1>(self, func: F) -> RequestExt<B1>
@@ -204,6 +248,117 @@ impl<B> RequestExt<B> {
     }
 }
 
+/// information of tls connection negotiated during handshake.
+///
+/// Every field is optional as not all tls backends are able to provide them. Textual values are
+/// formatted by the tls backend in use.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct TlsInfo {
+    /// application protocol negotiated with ALPN extension. e.g. `h2` or `http/1.1`.
+    pub alpn_protocol: Option<Box<[u8]>>,
+    /// tls protocol version. e.g. `TLSv1.3`.
+    pub protocol_version: Option<&'static str>,
+    /// name of negotiated cipher suite.
+    pub cipher_suite: Option<&'static str>,
+    /// server name requested by client with SNI extension.
+    pub server_name: Option<Box<str>>,
+    /// true when request may be carried by tls 1.3 0-RTT early data. Early data can be replayed by
+    /// attacker and request with it set should be handled with care.
+    ///
+    /// For http/1 it's set for requests received before the early data is fully consumed. For http/2
+    /// it's set for all requests of connection accepted early data.
+    pub early_data: bool,
+}
+
+/// information of connection request is received from.
+#[derive(Clone, Copy, Debug)]
+pub struct ConnInfo {
+    version: Version,
+    remote_addr: SocketAddr,
+    local_addr: Option<SocketAddr>,
+    request_count: usize,
+    keep_alive: bool,
+}
+
+impl ConnInfo {
+    /// construct connection information with given remote address.
+    ///
+    /// # Default
+    /// Connection is Http/1.1 without local address and it's not keep-alive. Request count is 1.
+    pub const fn new(remote_addr: SocketAddr) -> Self {
+        Self {
+            version: Version::HTTP_11,
+            remote_addr,
+            local_addr: None,
+            request_count: 1,
+            keep_alive: false,
+        }
+    }
+
+    /// set http version of connection.
+    pub const fn with_version(mut self, version: Version) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// set local address of connection.
+    pub const fn with_local_addr(mut self, local_addr: Option<SocketAddr>) -> Self {
+        self.local_addr = local_addr;
+        self
+    }
+
+    /// set count of requests received from connection.
+    pub const fn with_request_count(mut self, request_count: usize) -> Self {
+        self.request_count = request_count;
+        self
+    }
+
+    /// set if connection is keep-alive.
+    pub const fn with_keep_alive(mut self, keep_alive: bool) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// http version negotiated for connection.
+    #[inline]
+    pub const fn version(&self) -> Version {
+        self.version
+    }
+
+    /// remote peer's socket address of connection. unlike [RequestExt::socket_addr] it's not meant to
+    /// be modified after connection is accepted.
+    #[inline]
+    pub const fn remote_addr(&self) -> &SocketAddr {
+        &self.remote_addr
+    }
+
+    /// local socket address of connection.
+    ///
+    /// # Default
+    /// [None] is used for connection where local address can't be obtained. (unix socket, Http/3 for
+    /// example)
+    #[inline]
+    pub const fn local_addr(&self) -> Option<&SocketAddr> {
+        self.local_addr.as_ref()
+    }
+
+    /// count of requests received from connection including current one.
+    #[inline]
+    pub const fn request_count(&self) -> usize {
+        self.request_count
+    }
+
+    /// true when connection is supposed to be kept alive after response of current request is sent.
+    ///
+    /// Http/2 and Http/3 connections are always keep-alive. Http/1 connection can still be closed
+    /// afterwards by service or server shutdown.
+    #[inline]
+    pub const fn is_keep_alive(&self) -> bool {
+        self.keep_alive
+    }
+}
+
 impl<B> Default for RequestExt<B>
 where
     B: Default,
@@ -40,6 +40,8 @@ mod tls;
 
 #[cfg(feature = "runtime")]
 mod builder;
+#[cfg(feature = "http2")]
+mod h2c;
 #[cfg(feature = "runtime")]
 mod service;
 #[cfg(feature = "runtime")]
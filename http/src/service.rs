@@ -1,4 +1,12 @@
-use core::{fmt, marker::PhantomData, pin::pin};
+use core::{
+    fmt,
+    marker::PhantomData,
+    net::SocketAddr,
+    pin::{pin, Pin},
+    sync::atomic::AtomicBool,
+};
+
+use std::sync::Arc;
 
 use futures_core::Stream;
 use xitca_io::{
@@ -13,7 +21,7 @@ use super::{
     config::HttpServiceConfig,
     date::{DateTime, DateTimeService},
     error::{HttpServiceError, TimeoutError},
-    http::{Request, RequestExt, Response},
+    http::{Request, RequestExt, Response, TlsInfo, Version},
     util::timer::{KeepAlive, Timeout},
     version::AsVersion,
 };
@@ -31,6 +39,7 @@ pub struct HttpService<
     pub(crate) date: DateTimeService,
     pub(crate) service: S,
     pub(crate) tls_acceptor: A,
+    pub(crate) draining: Option<Arc<AtomicBool>>,
     _body: PhantomData<(St, ReqB)>,
 }
 
@@ -47,10 +56,16 @@ impl<St, S, ReqB, A, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, con
             date: DateTimeService::new(),
             service,
             tls_acceptor,
+            draining: None,
             _body: PhantomData,
         }
     }
 
+    pub(crate) fn draining(mut self, draining: Option<Arc<AtomicBool>>) -> Self {
+        self.draining = draining;
+        self
+    }
+
     #[cfg(feature = "http2")]
     pub(crate) fn update_first_request_deadline(&self, timer: core::pin::Pin<&mut KeepAlive>) {
         let request_dur = self.config.request_head_timeout;
@@ -94,61 +109,41 @@ where
                 .run()
                 .await
                 .map_err(From::from),
+            // raw datagram does not carry http protocol.
+            #[cfg(not(target_family = "wasm"))]
+            ServerStream::Datagram(..) => Err(HttpServiceError::Ignored),
             ServerStream::Tcp(io, _addr) => {
+                let local_addr = io.local_addr().ok();
                 let io = TcpStream::from_std(io).expect("TODO: handle io error");
-                let mut _tls_stream = self
+                let _tls_stream = self
                     .tls_acceptor
                     .call(io)
                     .timeout(timer.as_mut())
                     .await
                     .map_err(|_| HttpServiceError::Timeout(TimeoutError::TlsAccept))??;
 
-                let version = if self.config.peek_protocol {
+                let tls_info = _tls_stream.tls_info().map(Arc::new);
+                let version = _tls_stream.as_version();
+
+                #[cfg(feature = "http2")]
+                if self.config.peek_protocol && version != super::http::Version::HTTP_2 {
+                    // update timer to first request timeout.
+                    self.update_first_request_deadline(timer.as_mut());
+
                     // peek version from connection to figure out the real protocol used
                     // regardless of AsVersion's outcome.
-                    todo!("peek version is not implemented yet!")
-                } else {
-                    _tls_stream.as_version()
-                };
-
-                match version {
-                    #[cfg(feature = "http1")]
-                    super::http::Version::HTTP_11 | super::http::Version::HTTP_10 => super::h1::dispatcher::run(
-                        &mut _tls_stream,
-                        _addr,
-                        timer.as_mut(),
-                        self.config,
-                        &self.service,
-                        self.date.get(),
-                    )
-                    .await
-                    .map_err(From::from),
-                    #[cfg(feature = "http2")]
-                    super::http::Version::HTTP_2 => {
-                        // update timer to first request timeout.
-                        self.update_first_request_deadline(timer.as_mut());
-
-                        let mut conn = ::h2::server::Builder::new()
-                            .enable_connect_protocol()
-                            .handshake(xitca_io::io::PollIoAdapter(_tls_stream))
-                            .timeout(timer.as_mut())
-                            .await
-                            .map_err(|_| HttpServiceError::Timeout(TimeoutError::H2Handshake))??;
-
-                        super::h2::Dispatcher::new(
-                            &mut conn,
-                            _addr,
-                            timer.as_mut(),
-                            self.config.keep_alive_timeout,
-                            &self.service,
-                            self.date.get(),
-                        )
-                        .run()
+                    let (version, io) = super::h2c::peek_version::<_, READ_BUF_LIMIT>(_tls_stream)
+                        .timeout(timer.as_mut())
                         .await
-                        .map_err(Into::into)
-                    }
-                    version => Err(HttpServiceError::UnSupportedVersion(version)),
+                        .map_err(|_| HttpServiceError::Timeout(TimeoutError::PeekProtocol))?
+                        // io error before any request is received is treated as closed connection.
+                        .map_err(|_| HttpServiceError::Ignored)?;
+
+                    return self.dispatch(io, _addr, local_addr, tls_info, version, timer).await;
                 }
+
+                self.dispatch(_tls_stream, _addr, local_addr, tls_info, version, timer)
+                    .await
             }
             #[cfg(unix)]
             ServerStream::Unix(_io, _) => {
@@ -164,10 +159,13 @@ where
                     super::h1::dispatcher::run(
                         &mut io,
                         crate::unspecified_socket_addr(),
+                        None,
+                        None,
                         timer.as_mut(),
                         self.config,
                         &self.service,
                         self.date.get(),
+                        self.draining.as_deref(),
                     )
                     .await
                     .map_err(From::from)
@@ -177,6 +175,80 @@ where
     }
 }
 
+impl<S, ResB, BE, A, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE_BUF_LIMIT: usize>
+    HttpService<ServerStream, S, RequestBody, A, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>
+where
+    S: Service<Request<RequestExt<RequestBody>>, Response = Response<ResB>>,
+    S::Error: fmt::Debug,
+    ResB: Stream<Item = Result<Bytes, BE>>,
+    BE: fmt::Debug,
+{
+    // dispatch connection to http/1 or http/2 dispatcher according to given version.
+    async fn dispatch<Io>(
+        &self,
+        mut _io: Io,
+        _addr: SocketAddr,
+        _local_addr: Option<SocketAddr>,
+        _tls_info: Option<Arc<TlsInfo>>,
+        version: Version,
+        mut _timer: Pin<&mut KeepAlive>,
+    ) -> Result<(), HttpServiceError<S::Error, BE>>
+    where
+        Io: AsyncIo,
+    {
+        match version {
+            #[cfg(feature = "http1")]
+            Version::HTTP_11 | Version::HTTP_10 => super::h1::dispatcher::run(
+                &mut _io,
+                _addr,
+                _local_addr,
+                _tls_info,
+                _timer.as_mut(),
+                self.config,
+                &self.service,
+                self.date.get(),
+                self.draining.as_deref(),
+            )
+            .await
+            .map_err(From::from),
+            #[cfg(feature = "http2")]
+            Version::HTTP_2 => {
+                // update timer to first request timeout.
+                self.update_first_request_deadline(_timer.as_mut());
+
+                let mut conn = ::h2::server::Builder::new()
+                    .enable_connect_protocol()
+                    .max_header_list_size(super::h2::max_header_list_size(READ_BUF_LIMIT))
+                    .handshake(xitca_io::io::PollIoAdapter(_io))
+                    .timeout(_timer.as_mut())
+                    .await
+                    .map_err(|_| HttpServiceError::Timeout(TimeoutError::H2Handshake))??;
+
+                super::h2::Dispatcher::new(
+                    &mut conn,
+                    _addr,
+                    _local_addr,
+                    _tls_info,
+                    _timer.as_mut(),
+                    self.config.keep_alive_timeout,
+                    HEADER_LIMIT,
+                    self.config.max_uri_length,
+                    self.config.stream_idle_timeout,
+                    self.config.max_half_open_streams,
+                    self.config.request_body_timeout,
+                    &self.service,
+                    self.date.get(),
+                    self.draining.as_deref(),
+                )
+                .run()
+                .await
+                .map_err(Into::into)
+            }
+            version => Err(HttpServiceError::UnSupportedVersion(version)),
+        }
+    }
+}
+
 impl<St, S, ReqB, A, const HEADER_LIMIT: usize, const READ_BUF_LIMIT: usize, const WRITE_BUF_LIMIT: usize> ReadyService
     for HttpService<St, S, ReqB, A, HEADER_LIMIT, READ_BUF_LIMIT, WRITE_BUF_LIMIT>
 where
@@ -14,7 +14,10 @@ use native_tls::{Error, HandshakeError};
 use xitca_io::io::{AsyncIo, Interest, Ready};
 use xitca_service::Service;
 
-use crate::{http::Version, version::AsVersion};
+use crate::{
+    http::{TlsInfo, Version},
+    version::AsVersion,
+};
 
 use super::error::TlsError;
 
@@ -34,6 +37,14 @@ impl<Io: AsyncIo> AsVersion for TlsStream<Io> {
             .map(Self::from_alpn)
             .unwrap_or(Version::HTTP_11)
     }
+
+    // native-tls only exposes negotiated application protocol.
+    fn tls_info(&self) -> Option<TlsInfo> {
+        Some(TlsInfo {
+            alpn_protocol: self.io.negotiated_alpn().ok().flatten().map(Into::into),
+            ..Default::default()
+        })
+    }
 }
 
 #[derive(Clone)]
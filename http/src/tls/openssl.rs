@@ -6,7 +6,10 @@ use xitca_io::io::AsyncIo;
 use xitca_service::Service;
 use xitca_tls::openssl::ssl;
 
-use crate::{http::Version, version::AsVersion};
+use crate::{
+    http::{TlsInfo, Version},
+    version::AsVersion,
+};
 
 use super::error::TlsError;
 
@@ -22,6 +25,18 @@ where
             .map(Self::from_alpn)
             .unwrap_or(Version::HTTP_11)
     }
+
+    fn tls_info(&self) -> Option<TlsInfo> {
+        let session = self.session();
+        Some(TlsInfo {
+            alpn_protocol: session.selected_alpn_protocol().map(Into::into),
+            protocol_version: Some(session.version_str()),
+            cipher_suite: session.current_cipher().map(|cipher| cipher.name()),
+            server_name: session.servername(ssl::NameType::HOST_NAME).map(Into::into),
+            // early data is not read by openssl acceptor.
+            early_data: false,
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -4,9 +4,12 @@ use std::{error, io, sync::Arc};
 
 use xitca_io::io::AsyncIo;
 use xitca_service::Service;
-use xitca_tls::rustls::{Error, ServerConfig, ServerConnection, TlsStream as _TlsStream};
+use xitca_tls::rustls::{Error, ProtocolVersion, ServerConfig, ServerConnection, TlsStream as _TlsStream};
 
-use crate::{http::Version, version::AsVersion};
+use crate::{
+    http::{TlsInfo, Version},
+    version::AsVersion,
+};
 
 use super::error::TlsError;
 
@@ -25,6 +28,24 @@ where
             .map(Self::from_alpn)
             .unwrap_or(Version::HTTP_11)
     }
+
+    fn tls_info(&self) -> Option<TlsInfo> {
+        let session = self.session();
+        Some(TlsInfo {
+            alpn_protocol: session.alpn_protocol().map(Into::into),
+            // align version format with openssl.
+            protocol_version: session.protocol_version().and_then(|version| match version {
+                ProtocolVersion::TLSv1_3 => Some("TLSv1.3"),
+                ProtocolVersion::TLSv1_2 => Some("TLSv1.2"),
+                version => version.as_str(),
+            }),
+            cipher_suite: session
+                .negotiated_cipher_suite()
+                .and_then(|suite| suite.suite().as_str()),
+            server_name: session.server_name().map(Into::into),
+            early_data: self.is_early_data_accepted(),
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -61,7 +82,7 @@ impl<Io: AsyncIo> Service<Io> for TlsAcceptorService {
 
     async fn call(&self, io: Io) -> Result<Self::Response, Self::Error> {
         let conn = ServerConnection::new(self.acceptor.clone())?;
-        _TlsStream::handshake(io, conn).await.map_err(Into::into)
+        _TlsStream::accept(io, conn).await.map_err(Into::into)
     }
 }
 
@@ -0,0 +1,169 @@
+//! middleware for deciding on request with `Expect: 100-continue` header.
+//!
+//! Http/1 dispatcher sends `100 Continue` lazily when service starts reading request body. A service
+//! producing response without reading body would reject the request implicitly and body is not
+//! received from client. [ExpectContinue] makes this decision explicit by inspecting request head
+//! before it reaches inner service.
+
+use xitca_service::{ready::ReadyService, Service};
+
+use crate::http::{
+    header::{HeaderValue, CONTENT_LENGTH, EXPECT},
+    Request, Response, StatusCode,
+};
+
+/// decision on request with `Expect: 100-continue` header.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Expectation {
+    /// pass request to inner service. `100 Continue` is sent when inner service starts reading
+    /// request body. Inner service can still defer the decision and reject request by responding
+    /// without reading body.
+    Continue,
+    /// respond with given status code without passing request to inner service. `100 Continue` is
+    /// not sent and request body is not received from client.
+    /// (Typically `417 Expectation Failed` or `413 Payload Too Large`)
+    Reject(StatusCode),
+}
+
+/// builder for middleware calling given function on request with `Expect: 100-continue` header.
+///
+/// # Examples
+/// ```rust
+/// use xitca_http::{
+///     http::{Request, Response, StatusCode},
+///     util::middleware::expect::{content_length, ExpectContinue, Expectation},
+/// };
+/// use xitca_service::{fn_service, ServiceExt};
+///
+/// # fn _main() {
+/// let service = fn_service(|_: Request<()>| async { Ok::<_, ()>(Response::new(String::new())) })
+///     // reject upload larger than 1MB before receiving it's body.
+///     .enclosed(ExpectContinue::new(|req: &Request<()>| {
+///         match content_length(req) {
+///             Some(len) if len > 1024 * 1024 => Expectation::Reject(StatusCode::PAYLOAD_TOO_LARGE),
+///             _ => Expectation::Continue,
+///         }
+///     }));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ExpectContinue<F> {
+    func: F,
+}
+
+impl<F> ExpectContinue<F> {
+    pub fn new<Ext>(func: F) -> Self
+    where
+        F: Fn(&Request<Ext>) -> Expectation + Clone,
+    {
+        Self { func }
+    }
+}
+
+impl<S, E, F> Service<Result<S, E>> for ExpectContinue<F>
+where
+    F: Clone,
+{
+    type Response = ExpectContinueService<S, F>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| ExpectContinueService {
+            service,
+            func: self.func.clone(),
+        })
+    }
+}
+
+pub struct ExpectContinueService<S, F> {
+    service: S,
+    func: F,
+}
+
+impl<S, F, Ext, ResB> Service<Request<Ext>> for ExpectContinueService<S, F>
+where
+    S: Service<Request<Ext>, Response = Response<ResB>>,
+    F: Fn(&Request<Ext>) -> Expectation,
+    ResB: Default,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    async fn call(&self, req: Request<Ext>) -> Result<Self::Response, Self::Error> {
+        if req.headers().get(EXPECT).is_some_and(is_continue) {
+            if let Expectation::Reject(status) = (self.func)(&req) {
+                let mut res = Response::new(ResB::default());
+                *res.status_mut() = status;
+                return Ok(res);
+            }
+        }
+        self.service.call(req).await
+    }
+}
+
+impl<S, F> ReadyService for ExpectContinueService<S, F>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+/// utility function for parsing `Content-Length` header of request.
+pub fn content_length<Ext>(req: &Request<Ext>) -> Option<u64> {
+    req.headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+}
+
+fn is_continue(value: &HeaderValue) -> bool {
+    value.as_bytes().eq_ignore_ascii_case(b"100-continue")
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_service::{fn_service, ServiceExt};
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use super::*;
+
+    #[test]
+    fn reject_large_body() {
+        let service = fn_service(|_: Request<()>| async { Ok::<_, ()>(Response::new(String::from("996"))) })
+            .enclosed(ExpectContinue::new(|req: &Request<()>| match content_length(req) {
+                Some(len) if len > 64 => Expectation::Reject(StatusCode::PAYLOAD_TOO_LARGE),
+                _ => Expectation::Continue,
+            }))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let req = |expect: Option<&'static str>, len: u64| {
+            let mut req = Request::new(());
+            req.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from(len));
+            if let Some(expect) = expect {
+                req.headers_mut().insert(EXPECT, HeaderValue::from_static(expect));
+            }
+            req
+        };
+
+        let res = service.call(req(Some("100-continue"), 128)).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(res.body().is_empty());
+
+        let res = service.call(req(Some("100-Continue"), 128)).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let res = service.call(req(Some("100-continue"), 32)).now_or_panic().unwrap();
+        assert_eq!(res.body(), "996");
+
+        // request without expect header is not inspected.
+        let res = service.call(req(None, 128)).now_or_panic().unwrap();
+        assert_eq!(res.body(), "996");
+    }
+}
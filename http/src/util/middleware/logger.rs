@@ -1,9 +1,25 @@
-use tracing::Level;
+use core::{
+    fmt,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use futures_core::stream::Stream;
+use pin_project_lite::pin_project;
+use tracing::{event, Level, Span};
 use xitca_service::Service;
 
+use crate::{
+    bytes::Bytes,
+    http::{Response, StatusCode},
+};
+
 /// a builder for logger service.
+///
+/// By default response is logged when it's produced by the enclosed service. [Logger::body_size] can be used
+/// for logging it after it's body is fully sent, with the count of body bytes in the log record.
 #[derive(Clone)]
-pub struct Logger {
+pub struct Logger<const BODY_SIZE: bool = false> {
     level: Level,
 }
 
@@ -23,10 +39,20 @@ impl Logger {
     pub fn with_level(level: Level) -> Self {
         Self { level }
     }
+
+    /// defer logging of response until it's body stream is finished and log the count of body bytes
+    /// sent with it. body stream ending with error or dropped before finished is logged as well. empty body
+    /// is logged as sent when it's dropped as it may never be polled.
+    ///
+    /// # Note:
+    /// response body is wrapped by [LoggerBody] type.
+    pub fn body_size(self) -> Logger<true> {
+        Logger { level: self.level }
+    }
 }
 
-impl<S, E> Service<Result<S, E>> for Logger {
-    type Response = service::LoggerService<S>;
+impl<S, E, const BODY_SIZE: bool> Service<Result<S, E>> for Logger<BODY_SIZE> {
+    type Response = service::LoggerService<S, BODY_SIZE>;
     type Error = E;
 
     async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
@@ -37,53 +63,189 @@ impl<S, E> Service<Result<S, E>> for Logger {
     }
 }
 
+pin_project! {
+    /// response body type counting bytes sent through it. produced by [Logger::body_size].
+    pub struct LoggerBody<B> {
+        #[pin]
+        body: B,
+        span: Span,
+        status: StatusCode,
+        size: u64,
+        finished: bool,
+        // empty body can be dropped without being polled. (h2 ends stream along with response head)
+        empty: bool,
+    }
+
+    impl<B> PinnedDrop for LoggerBody<B> {
+        fn drop(this: Pin<&mut Self>) {
+            if this.finished {
+                return;
+            }
+
+            this.span.in_scope(|| {
+                if this.empty {
+                    event!(
+                        target: "on_response",
+                        Level::INFO,
+                        status = this.status.as_u16(),
+                        bytes = this.size,
+                        "response sent"
+                    )
+                } else {
+                    event!(
+                        target: "on_response",
+                        Level::WARN,
+                        status = this.status.as_u16(),
+                        bytes = this.size,
+                        "response body dropped before finished"
+                    )
+                }
+            });
+        }
+    }
+}
+
+impl<B, E> Stream for LoggerBody<B>
+where
+    B: Stream<Item = Result<Bytes, E>>,
+    E: fmt::Display,
+{
+    type Item = Result<Bytes, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+
+        if *this.finished {
+            return Poll::Ready(None);
+        }
+
+        let res = ready!(this.body.poll_next(cx));
+
+        match res {
+            Some(Ok(ref bytes)) => *this.size += bytes.len() as u64,
+            Some(Err(ref e)) => {
+                *this.finished = true;
+                this.span.in_scope(|| {
+                    event!(
+                        target: "on_error",
+                        Level::WARN,
+                        status = this.status.as_u16(),
+                        bytes = *this.size,
+                        "response body error: {}",
+                        e
+                    )
+                });
+            }
+            None => {
+                *this.finished = true;
+                this.span.in_scope(|| {
+                    event!(
+                        target: "on_response",
+                        Level::INFO,
+                        status = this.status.as_u16(),
+                        bytes = *this.size,
+                        "response sent"
+                    )
+                });
+            }
+        }
+
+        Poll::Ready(res)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.body.size_hint()
+    }
+}
+
 mod service {
     use std::error;
 
-    use tracing::{event, span, Instrument};
+    use tracing::{span, Instrument};
     use xitca_service::ready::ReadyService;
 
     use crate::http::{header::HeaderMap, BorrowReq, Method, Uri};
 
     use super::*;
 
-    pub struct LoggerService<S> {
+    pub struct LoggerService<S, const BODY_SIZE: bool = false> {
         pub(super) service: S,
         pub(super) level: Level,
     }
 
-    impl<S, Req> Service<Req> for LoggerService<S>
+    macro_rules! span2 {
+        ($lvl:expr, $name:expr, $($fields:tt)*) => {
+            match $lvl {
+                Level::TRACE => span!(Level::TRACE, $name, $($fields)*),
+                Level::DEBUG => span!(Level::DEBUG, $name, $($fields)*),
+                Level::INFO => span!(Level::INFO, $name, $($fields)*),
+                Level::WARN => span!(Level::WARN, $name, $($fields)*),
+                Level::ERROR => span!(Level::ERROR, $name, $($fields)*),
+            }
+        }
+    }
+
+    fn request_span<Req>(level: Level, req: &Req) -> Span
     where
-        S: Service<Req>,
+        Req: BorrowReq<Method> + BorrowReq<Uri>,
+    {
+        let method: &Method = req.borrow();
+        let uri: &Uri = req.borrow();
+        span2!(level, "request", method = %method, uri = %uri)
+    }
+
+    impl<S, Req, ResB> Service<Req> for LoggerService<S, true>
+    where
+        S: Service<Req, Response = Response<ResB>>,
         Req: BorrowReq<Method> + BorrowReq<Uri> + BorrowReq<HeaderMap>,
         S::Error: error::Error,
+        ResB: Stream,
     {
-        type Response = S::Response;
+        type Response = Response<LoggerBody<ResB>>;
         type Error = S::Error;
 
         #[inline]
         async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
-            let method: &Method = req.borrow();
-            let uri: &Uri = req.borrow();
-
-            macro_rules! span2 {
-                ($lvl:expr, $name:expr, $($fields:tt)*) => {
-                    match $lvl {
-                        Level::TRACE => span!(Level::TRACE, $name, $($fields)*),
-                        Level::DEBUG => span!(Level::DEBUG, $name, $($fields)*),
-                        Level::INFO => span!(Level::INFO, $name, $($fields)*),
-                        Level::WARN => span!(Level::WARN, $name, $($fields)*),
-                        Level::ERROR => span!(Level::ERROR, $name, $($fields)*),
+            let span = request_span(self.level, &req);
+
+            async {
+                event!(target: "on_request", Level::INFO, "serving request");
+                match self.service.call(req).await {
+                    Ok(res) => {
+                        let status = res.status();
+                        Ok(res.map(|body| LoggerBody {
+                            empty: body.size_hint().1 == Some(0),
+                            body,
+                            span: Span::current(),
+                            status,
+                            size: 0,
+                            finished: false,
+                        }))
+                    }
+                    Err(e) => {
+                        event!(target: "on_error", Level::WARN, "{}", e);
+                        Err(e)
                     }
                 }
             }
+            .instrument(span)
+            .await
+        }
+    }
 
-            let span = span2!(
-                self.level,
-                "request",
-                method = %method,
-                uri = %uri
-            );
+    impl<S, Req> Service<Req> for LoggerService<S>
+    where
+        S: Service<Req>,
+        Req: BorrowReq<Method> + BorrowReq<Uri> + BorrowReq<HeaderMap>,
+        S::Error: error::Error,
+    {
+        type Response = S::Response;
+        type Error = S::Error;
+
+        #[inline]
+        async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+            let span = request_span(self.level, &req);
 
             async {
                 event!(target: "on_request", Level::INFO, "serving request");
@@ -103,7 +265,7 @@ mod service {
         }
     }
 
-    impl<S> ReadyService for LoggerService<S>
+    impl<S, const BODY_SIZE: bool> ReadyService for LoggerService<S, BODY_SIZE>
     where
         S: ReadyService,
     {
@@ -115,3 +277,45 @@ mod service {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use core::{convert::Infallible, future::poll_fn, pin::pin};
+
+    use xitca_service::{fn_service, ServiceExt};
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::{BoxBody, ResponseBody},
+        http::Request,
+    };
+
+    use super::*;
+
+    #[test]
+    fn body_size() {
+        let service = fn_service(|_: Request<()>| async {
+            Ok::<_, Infallible>(Response::new(ResponseBody::<BoxBody>::bytes("996")))
+        })
+        .enclosed(Logger::new().body_size())
+        .call(())
+        .now_or_panic()
+        .unwrap();
+
+        let res = service.call(Request::new(())).now_or_panic().unwrap();
+
+        let mut body = pin!(res.into_body());
+        assert_eq!(body.size_hint(), (3, Some(3)));
+        assert_eq!(body.size, 0);
+
+        let chunk = poll_fn(|cx| body.as_mut().poll_next(cx))
+            .now_or_panic()
+            .unwrap()
+            .unwrap();
+        assert_eq!(chunk, "996");
+        assert_eq!(body.size, 3);
+
+        assert!(poll_fn(|cx| body.as_mut().poll_next(cx)).now_or_panic().is_none());
+        assert!(body.finished);
+    }
+}
@@ -7,8 +7,12 @@ mod socket_config;
 
 pub mod catch_unwind;
 pub mod context;
+pub mod expect;
 
-pub use self::{extension::Extension, logger::Logger};
+pub use self::{
+    extension::Extension,
+    logger::{Logger, LoggerBody},
+};
 
 #[cfg(not(target_family = "wasm"))]
 #[cfg(feature = "runtime")]
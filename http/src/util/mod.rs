@@ -3,9 +3,14 @@
 pub mod middleware;
 
 pub mod service;
+pub mod trace_context;
+#[cfg(feature = "runtime")]
+pub mod tunnel;
 
 #[cfg(any(feature = "http1", feature = "http2"))]
 pub mod buffered;
 pub(crate) mod futures;
+#[cfg(all(feature = "runtime", feature = "http2"))]
+pub(crate) mod rewind;
 #[cfg(feature = "runtime")]
 pub(crate) mod timer;
@@ -0,0 +1,89 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use std::io;
+
+use xitca_io::io::{AsyncIo, Interest, Ready};
+
+use crate::bytes::{Buf, Bytes};
+
+/// io type yielding bytes already read from inner io before reading from it again.
+pub(crate) struct Rewind<Io> {
+    buf: Bytes,
+    io: Io,
+}
+
+impl<Io> Rewind<Io> {
+    pub(crate) fn new(buf: Bytes, io: Io) -> Self {
+        Self { buf, io }
+    }
+}
+
+impl<Io> AsyncIo for Rewind<Io>
+where
+    Io: AsyncIo,
+{
+    fn ready(&mut self, interest: Interest) -> impl Future<Output = io::Result<Ready>> + Send {
+        let buffered = !self.buf.is_empty() && interest.is_readable();
+        let ready = self.io.ready(interest);
+        async move {
+            if buffered {
+                return Ok(Ready::READABLE);
+            }
+            ready.await
+        }
+    }
+
+    fn poll_ready(&mut self, interest: Interest, cx: &mut Context<'_>) -> Poll<io::Result<Ready>> {
+        if !self.buf.is_empty() && interest.is_readable() {
+            return Poll::Ready(Ok(Ready::READABLE));
+        }
+        self.io.poll_ready(interest, cx)
+    }
+
+    #[inline]
+    fn is_vectored_write(&self) -> bool {
+        self.io.is_vectored_write()
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        AsyncIo::poll_shutdown(Pin::new(&mut self.get_mut().io), cx)
+    }
+}
+
+impl<Io> io::Read for Rewind<Io>
+where
+    Io: io::Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.buf.is_empty() {
+            return self.io.read(buf);
+        }
+        let len = buf.len().min(self.buf.len());
+        self.buf.copy_to_slice(&mut buf[..len]);
+        Ok(len)
+    }
+}
+
+impl<Io> io::Write for Rewind<Io>
+where
+    Io: io::Write,
+{
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.io.write(buf)
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        self.io.write_vectored(bufs)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.io.flush()
+    }
+}
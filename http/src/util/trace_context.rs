@@ -0,0 +1,303 @@
+//! [W3C trace context](https://www.w3.org/TR/trace-context/) propagation.
+//!
+//! [`TraceContext`] is parsed from and injected to `traceparent` and `tracestate` headers. It can be
+//! made the current context of a future with [`TraceContext::scope`] where it's observable through
+//! [`TraceContext::current`]. This is how trace context is propagated from server middleware to http
+//! client used in request handler without passing it around explicitly.
+
+use core::{
+    cell::RefCell,
+    fmt,
+    future::Future,
+    hash::{BuildHasher, Hasher},
+    mem,
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll},
+};
+
+use std::collections::hash_map::RandomState;
+
+use pin_project_lite::pin_project;
+
+use crate::http::header::{HeaderMap, HeaderName, HeaderValue};
+
+/// `traceparent` header name.
+pub const TRACEPARENT: HeaderName = HeaderName::from_static("traceparent");
+
+/// `tracestate` header name.
+pub const TRACESTATE: HeaderName = HeaderName::from_static("tracestate");
+
+// sampled bit of trace flags.
+const FLAG_SAMPLED: u8 = 0x01;
+
+thread_local! {
+    static CURRENT: RefCell<Option<TraceContext>> = const { RefCell::new(None) };
+}
+
+/// trace context of a span. identified by it's trace id shared by all spans of a trace and span id
+/// unique to itself.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    trace_id: u128,
+    span_id: u64,
+    flags: u8,
+    state: Option<HeaderValue>,
+}
+
+impl TraceContext {
+    /// construct a sampled root context starting a new trace.
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: (u128::from(random_id()) << 64) | u128::from(random_id()),
+            span_id: random_id(),
+            flags: FLAG_SAMPLED,
+            state: None,
+        }
+    }
+
+    /// parse trace context from `traceparent` and `tracestate` headers.
+    ///
+    /// Return None when `traceparent` header is missing or malformed. In which case `tracestate`
+    /// header is ignored as well.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let mut ctx = Self::parse(headers.get(TRACEPARENT)?.as_bytes())?;
+        ctx.state = headers.get(TRACESTATE).cloned();
+        Some(ctx)
+    }
+
+    /// construct a child context in the same trace with a new span id. trace flags and trace state
+    /// are inherited.
+    pub fn child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id,
+            span_id: random_id(),
+            flags: self.flags,
+            state: self.state.clone(),
+        }
+    }
+
+    /// trace id shared by all spans of the trace.
+    pub fn trace_id(&self) -> u128 {
+        self.trace_id
+    }
+
+    /// span id of the context.
+    pub fn span_id(&self) -> u64 {
+        self.span_id
+    }
+
+    /// check if the trace is sampled by caller.
+    pub fn is_sampled(&self) -> bool {
+        self.flags & FLAG_SAMPLED != 0
+    }
+
+    /// vendor specific trace state of `tracestate` header.
+    pub fn state(&self) -> Option<&HeaderValue> {
+        self.state.as_ref()
+    }
+
+    /// encode context as value of `traceparent` header.
+    pub fn traceparent(&self) -> HeaderValue {
+        let value = format!("00-{:032x}-{:016x}-{:02x}", self.trace_id, self.span_id, self.flags);
+        HeaderValue::try_from(value).expect("traceparent must be valid header value")
+    }
+
+    /// insert `traceparent` and `tracestate` headers of context to given header map. existing
+    /// headers are replaced.
+    pub fn inject(&self, headers: &mut HeaderMap) {
+        headers.insert(TRACEPARENT, self.traceparent());
+        match self.state {
+            Some(ref state) => {
+                headers.insert(TRACESTATE, state.clone());
+            }
+            None => {
+                headers.remove(TRACESTATE);
+            }
+        }
+    }
+
+    /// current context set by [`TraceContext::scope`] of the future being polled.
+    pub fn current() -> Option<Self> {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// make context the current context when given future is polled.
+    pub fn scope<F>(self, fut: F) -> Scope<F>
+    where
+        F: Future,
+    {
+        Scope { ctx: Some(self), fut }
+    }
+
+    // traceparent = version "-" trace-id "-" parent-id "-" trace-flags
+    fn parse(value: &[u8]) -> Option<Self> {
+        const LEN: usize = 55;
+
+        if value.len() < LEN {
+            return None;
+        }
+
+        let version = hex(&value[..2])?;
+        match version {
+            // invalid version.
+            0xff => return None,
+            // version 00 has fixed length.
+            0x00 if value.len() != LEN => return None,
+            // future version can have extra fields after trace flags.
+            _ if value.len() > LEN && value[LEN] != b'-' => return None,
+            _ => {}
+        }
+
+        if value[2] != b'-' || value[35] != b'-' || value[52] != b'-' {
+            return None;
+        }
+
+        let trace_id = hex(&value[3..35])?;
+        let span_id = hex(&value[36..52])?;
+        let flags = hex(&value[53..55])?;
+
+        // all zero trace id and span id are invalid.
+        if trace_id == 0 || span_id == 0 {
+            return None;
+        }
+
+        Some(Self {
+            trace_id,
+            span_id: span_id as u64,
+            flags: flags as u8,
+            state: None,
+        })
+    }
+}
+
+impl fmt::Debug for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TraceContext")
+            .field("trace_id", &format_args!("{:032x}", self.trace_id))
+            .field("span_id", &format_args!("{:016x}", self.span_id))
+            .field("flags", &format_args!("{:02x}", self.flags))
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+pin_project! {
+    /// future with [`TraceContext`] set as current context when it's polled.
+    pub struct Scope<F> {
+        ctx: Option<TraceContext>,
+        #[pin]
+        fut: F,
+    }
+}
+
+impl<F> Future for Scope<F>
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+
+        // swap context in and restore the previous one after polling. it has to be done even when
+        // polling panics so the context does not leak to other futures on the same thread.
+        struct Guard<'a>(&'a mut Option<TraceContext>);
+
+        impl Drop for Guard<'_> {
+            fn drop(&mut self) {
+                CURRENT.with(|current| mem::swap(&mut *current.borrow_mut(), self.0));
+            }
+        }
+
+        CURRENT.with(|current| mem::swap(&mut *current.borrow_mut(), this.ctx));
+        let _guard = Guard(this.ctx);
+
+        this.fut.poll(cx)
+    }
+}
+
+// lowercase hex only.
+fn hex(bytes: &[u8]) -> Option<u128> {
+    bytes.iter().try_fold(0u128, |n, b| {
+        let d = match b {
+            b'0'..=b'9' => b - b'0',
+            b'a'..=b'f' => b - b'a' + 10,
+            _ => return None,
+        };
+        Some((n << 4) | u128::from(d))
+    })
+}
+
+// non zero random id. it's not cryptographically secure and only good for identifying spans.
+fn random_id() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    loop {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+        let id = hasher.finish();
+        if id != 0 {
+            return id;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use super::*;
+
+    const TRACEPARENT_VALUE: &str = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+
+    #[test]
+    fn parse() {
+        let mut headers = HeaderMap::new();
+        headers.insert(TRACEPARENT, HeaderValue::from_static(TRACEPARENT_VALUE));
+        headers.insert(TRACESTATE, HeaderValue::from_static("congo=t61rcWkgMzE"));
+
+        let ctx = TraceContext::from_headers(&headers).unwrap();
+        assert_eq!(ctx.trace_id(), 0x0af7651916cd43dd8448eb211c80319c);
+        assert_eq!(ctx.span_id(), 0xb7ad6b7169203331);
+        assert!(ctx.is_sampled());
+        assert_eq!(ctx.state().unwrap(), "congo=t61rcWkgMzE");
+        assert_eq!(ctx.traceparent(), TRACEPARENT_VALUE);
+
+        let child = ctx.child();
+        assert_eq!(child.trace_id(), ctx.trace_id());
+        assert_ne!(child.span_id(), ctx.span_id());
+
+        let mut headers = HeaderMap::new();
+        child.inject(&mut headers);
+        assert_eq!(TraceContext::from_headers(&headers).unwrap(), child);
+
+        let parse = |value: &str| TraceContext::parse(value.as_bytes());
+
+        // future version with extra field.
+        assert!(parse("01-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01-what").is_some());
+        // invalid version.
+        assert!(parse("ff-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01").is_none());
+        // version 00 with extra field.
+        assert!(parse("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01-what").is_none());
+        // uppercase hex.
+        assert!(parse("00-0AF7651916CD43DD8448EB211C80319C-b7ad6b7169203331-01").is_none());
+        // all zero trace id.
+        assert!(parse("00-00000000000000000000000000000000-b7ad6b7169203331-01").is_none());
+        // all zero span id.
+        assert!(parse("00-0af7651916cd43dd8448eb211c80319c-0000000000000000-01").is_none());
+        // bad delimiter.
+        assert!(parse("00_0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01").is_none());
+    }
+
+    #[test]
+    fn scope() {
+        let ctx = TraceContext::new_root();
+        assert!(TraceContext::current().is_none());
+
+        let current = ctx.clone().scope(async { TraceContext::current() }).now_or_panic();
+        assert_eq!(current.unwrap(), ctx);
+
+        assert!(TraceContext::current().is_none());
+    }
+}
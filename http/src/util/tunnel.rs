@@ -0,0 +1,251 @@
+//! bidirectional byte stream bridging a request body and a response body.
+//!
+//! Connection upgrade of Http/1 and tunnel of Http/2 `CONNECT` stream both carry raw bytes through
+//! request body (data sent by client) and response body (data sent to client). [tunnel] function
+//! bridges them into a single [Tunnel] type implementing [AsyncRead] and [AsyncWrite] traits.
+
+use core::{
+    convert::Infallible,
+    pin::Pin,
+    task::{ready, Context, Poll, Waker},
+};
+
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use futures_core::stream::Stream;
+use pin_project_lite::pin_project;
+use xitca_io::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{
+    bytes::{Buf, Bytes, BytesMut},
+    error::BodyError,
+};
+
+// max size of bytes buffered by tunnel before they are sent by dispatcher.
+const MAX_BUF_SIZE: usize = 64 * 1024;
+
+/// split request body into a bidirectional [Tunnel] and it's paired [TunnelBody] which must be used as
+/// response body.
+pub fn tunnel<B>(body: B) -> (Tunnel<B>, TunnelBody) {
+    let shared = Arc::new(Mutex::new(Shared {
+        buf: BytesMut::new(),
+        closed: false,
+        body_dropped: false,
+        read_waker: None,
+        write_waker: None,
+    }));
+
+    let tunnel = Tunnel {
+        body,
+        read_buf: Bytes::new(),
+        shared: shared.clone(),
+    };
+
+    (tunnel, TunnelBody { shared })
+}
+
+struct Shared {
+    buf: BytesMut,
+    closed: bool,
+    body_dropped: bool,
+    // waker of TunnelBody waiting for data written to tunnel.
+    read_waker: Option<Waker>,
+    // waker of Tunnel waiting for buffered data being sent.
+    write_waker: Option<Waker>,
+}
+
+impl Shared {
+    fn wake_read(&mut self) {
+        if let Some(waker) = self.read_waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn wake_write(&mut self) {
+        if let Some(waker) = self.write_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+pin_project! {
+    /// bidirectional byte stream of a tunnel. reading from it yields data sent by client and writing
+    /// to it sends data to client through paired [TunnelBody].
+    ///
+    /// Dropping or shutting down the tunnel ends the response body and the stream.
+    pub struct Tunnel<B> {
+        #[pin]
+        body: B,
+        read_buf: Bytes,
+        shared: Arc<Mutex<Shared>>,
+    }
+
+    impl<B> PinnedDrop for Tunnel<B> {
+        fn drop(this: Pin<&mut Self>) {
+            let mut shared = this.shared.lock().unwrap();
+            shared.closed = true;
+            shared.wake_read();
+        }
+    }
+}
+
+impl<B, E> AsyncRead for Tunnel<B>
+where
+    B: Stream<Item = Result<Bytes, E>>,
+    E: Into<BodyError>,
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let mut this = self.project();
+
+        while this.read_buf.is_empty() {
+            match ready!(this.body.as_mut().poll_next(cx)) {
+                Some(res) => *this.read_buf = res.map_err(|e| io::Error::other(e.into()))?,
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+
+        let len = core::cmp::min(this.read_buf.len(), buf.remaining());
+        buf.put_slice(&this.read_buf[..len]);
+        this.read_buf.advance(len);
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<B> AsyncWrite for Tunnel<B> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let mut shared = self.shared.lock().unwrap();
+
+        if shared.body_dropped {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+
+        if shared.closed {
+            return Poll::Ready(Err(io::ErrorKind::NotConnected.into()));
+        }
+
+        let remaining = MAX_BUF_SIZE - shared.buf.len();
+        if remaining == 0 {
+            shared.write_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        let len = core::cmp::min(remaining, buf.len());
+        shared.buf.extend_from_slice(&buf[..len]);
+        shared.wake_read();
+
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut shared = self.shared.lock().unwrap();
+
+        if shared.body_dropped {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+
+        if !shared.buf.is_empty() {
+            shared.write_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut shared = self.shared.lock().unwrap();
+        shared.closed = true;
+        shared.wake_read();
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// response body paired with [Tunnel]. yields data written to tunnel and ends when tunnel is shutdown
+/// or dropped.
+pub struct TunnelBody {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Stream for TunnelBody {
+    type Item = Result<Bytes, Infallible>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut shared = self.shared.lock().unwrap();
+
+        if !shared.buf.is_empty() {
+            let bytes = shared.buf.split().freeze();
+            shared.wake_write();
+            return Poll::Ready(Some(Ok(bytes)));
+        }
+
+        if shared.closed {
+            return Poll::Ready(None);
+        }
+
+        shared.read_waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for TunnelBody {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.body_dropped = true;
+        shared.wake_write();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::future::poll_fn;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use super::*;
+
+    struct Once(Option<Bytes>);
+
+    impl Stream for Once {
+        type Item = Result<Bytes, BodyError>;
+
+        fn poll_next(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Ready(self.get_mut().0.take().map(Ok))
+        }
+    }
+
+    #[test]
+    fn tunnel_read_write() {
+        let (tunnel, mut body) = tunnel(Once(Some(Bytes::from_static(b"996"))));
+        let mut tunnel = core::pin::pin!(tunnel);
+
+        let mut buf = [0; 8];
+        let mut read_buf = ReadBuf::new(&mut buf);
+        poll_fn(|cx| tunnel.as_mut().poll_read(cx, &mut read_buf))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(read_buf.filled(), b"996");
+
+        // request body is finished.
+        let mut read_buf = ReadBuf::new(&mut buf);
+        poll_fn(|cx| tunnel.as_mut().poll_read(cx, &mut read_buf))
+            .now_or_panic()
+            .unwrap();
+        assert!(read_buf.filled().is_empty());
+
+        let n = poll_fn(|cx| tunnel.as_mut().poll_write(cx, b"251"))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(n, 3);
+
+        let chunk = poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).now_or_panic();
+        assert_eq!(chunk.unwrap().unwrap(), "251");
+
+        poll_fn(|cx| tunnel.as_mut().poll_flush(cx)).now_or_panic().unwrap();
+        poll_fn(|cx| tunnel.as_mut().poll_shutdown(cx)).now_or_panic().unwrap();
+
+        assert!(poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).now_or_panic().is_none());
+    }
+}
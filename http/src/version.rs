@@ -1,9 +1,14 @@
-use crate::http::Version;
+use crate::http::{TlsInfo, Version};
 
 /// A helper trait for get a protocol from certain types.
 pub trait AsVersion {
     fn as_version(&self) -> Version;
 
+    /// information of tls connection. [None] for plain text connection.
+    fn tls_info(&self) -> Option<TlsInfo> {
+        None
+    }
+
     fn from_alpn<B: AsRef<[u8]>>(proto: B) -> Version {
         if proto.as_ref().windows(2).any(|window| window == b"h2") {
             Version::HTTP_2
@@ -22,6 +27,9 @@ impl AsVersion for xitca_io::net::Stream {
             Self::Unix(..) => Version::HTTP_11,
             #[cfg(feature = "http3")]
             Self::Udp(..) => Version::HTTP_3,
+            // raw datagram does not carry http protocol. fallback to default version.
+            #[cfg(not(target_family = "wasm"))]
+            Self::Datagram(..) => Version::default(),
         }
     }
 }
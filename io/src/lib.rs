@@ -1,6 +1,6 @@
 //! Async traits and types used for Io operations.
 
-#![forbid(unsafe_code)]
+#![deny(unsafe_code)]
 
 pub mod bytes;
 #[cfg(feature = "runtime")]
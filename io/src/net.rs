@@ -6,6 +6,8 @@ pub mod io_uring;
 #[cfg(feature = "quic")]
 mod quic;
 mod tcp;
+#[cfg(not(target_family = "wasm"))]
+mod udp;
 #[cfg(unix)]
 mod unix;
 
@@ -14,6 +16,8 @@ pub use quic::*;
 #[cfg(not(target_family = "wasm"))]
 pub use tcp::TcpSocket;
 pub use tcp::{TcpListener, TcpStream};
+#[cfg(not(target_family = "wasm"))]
+pub use udp::{Datagram, UdpListener};
 #[cfg(unix)]
 pub use unix::{UnixListener, UnixStream};
 
@@ -113,6 +117,8 @@ pub enum Listener {
     Tcp(TcpListener),
     #[cfg(feature = "quic")]
     Udp(QuicListener),
+    #[cfg(not(target_family = "wasm"))]
+    Datagram(UdpListener),
     #[cfg(unix)]
     Unix(UnixListener),
 }
@@ -131,6 +137,12 @@ impl Listener {
                 let addr = stream.peer_addr();
                 Ok(Stream::Udp(stream, addr))
             }
+            #[cfg(not(target_family = "wasm"))]
+            Self::Datagram(ref udp) => {
+                let datagram = udp.accept().await?;
+                let addr = datagram.peer_addr();
+                Ok(Stream::Datagram(datagram, addr))
+            }
             #[cfg(unix)]
             Self::Unix(ref unix) => {
                 let (stream, _) = unix.accept().await?;
@@ -147,6 +159,8 @@ pub enum Stream {
     Tcp(std::net::TcpStream, SocketAddr),
     #[cfg(feature = "quic")]
     Udp(QuicStream, SocketAddr),
+    #[cfg(not(target_family = "wasm"))]
+    Datagram(Datagram, SocketAddr),
     #[cfg(unix)]
     Unix(std::os::unix::net::UnixStream, std::os::unix::net::SocketAddr),
 }
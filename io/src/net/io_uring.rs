@@ -1,3 +1,9 @@
+#[cfg(feature = "runtime-uring-multishot")]
+mod multishot;
+
+#[cfg(feature = "runtime-uring-multishot")]
+pub use multishot::{Accept, Ring};
+
 use core::net::SocketAddr;
 
 use std::{io, net::Shutdown};
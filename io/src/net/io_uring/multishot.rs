@@ -0,0 +1,423 @@
+//! multishot accept on a dedicated io_uring instance.
+//!
+//! tokio-uring only offers single-shot operations with caller owned buffers. [Ring] is a small per thread
+//! io_uring driven by tokio's reactor and it runs next to tokio-uring runtime (or plain tokio runtime).
+//!
+//! Accepted connections are read and written through tokio-uring streams with caller owned buffers. Provided
+//! buffer ring reads are not offered here as they can't be plugged into that read path.
+
+#![allow(unsafe_code)]
+
+use core::{
+    cell::{Cell, RefCell},
+    future::poll_fn,
+    mem,
+    net::SocketAddr,
+    ptr,
+    task::{Context, Poll, Waker},
+};
+
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    net::TcpStream,
+    os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+    rc::{Rc, Weak},
+};
+
+use io_uring::{cqueue, opcode, squeue, types, IoUring};
+use tokio::io::{unix::AsyncFd, Interest};
+
+const RING_ENTRIES: u32 = 256;
+
+// user data of cancel operations. their completions are ignored.
+const CANCEL_USER_DATA: u64 = u64::MAX;
+
+// accepted socket is non blocking like the ones accepted by tokio listener.
+const ACCEPT_FLAGS: i32 = libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC;
+
+thread_local! {
+    static CURRENT: RefCell<Weak<RingInner>> = const { RefCell::new(Weak::new()) };
+}
+
+/// Per thread io_uring instance for multishot accept.
+#[derive(Clone)]
+pub struct Ring(Rc<RingInner>);
+
+impl Ring {
+    /// Get io_uring instance of current thread. The instance is lazily created on first call and shared by
+    /// all callers on the same thread.
+    ///
+    /// # Errors
+    /// When io_uring is not available. (old kernel or sandbox blocking io_uring syscalls for example)
+    ///
+    /// # Panics
+    /// When called outside of [tokio::task::LocalSet]. tokio-uring runtime and xitca-server worker thread run
+    /// inside it.
+    pub fn current() -> io::Result<Self> {
+        CURRENT.with(|current| {
+            if let Some(inner) = current.borrow().upgrade().filter(|inner| inner.driving.get()) {
+                return Ok(Ring(inner));
+            }
+
+            let uring = IoUring::new(RING_ENTRIES)?;
+            // fd is owned by uring and it's not closed before AsyncFd is dropped. see RingInner.
+            #[allow(deprecated)]
+            let fd = AsyncFd::with_interest(uring.as_raw_fd(), Interest::READABLE)?;
+
+            let inner = Rc::new(RingInner {
+                fd,
+                uring: RefCell::new(uring),
+                ops: RefCell::new(HashMap::new()),
+                completions: Cell::new(Vec::new()),
+                next_id: Cell::new(0),
+                driving: Cell::new(true),
+            });
+
+            tokio::task::spawn_local(drive(inner.clone()));
+
+            *current.borrow_mut() = Rc::downgrade(&inner);
+
+            Ok(Ring(inner))
+        })
+    }
+
+    /// Accept connections from given listener with multishot accept where one submission yields connections
+    /// until it's cancelled. Kernel without multishot accept support (before linux 5.19) is detected at runtime
+    /// and [Accept] fallback to single-shot accept.
+    pub fn accept<'a, L>(&self, listener: &'a L) -> Accept<'a>
+    where
+        L: AsFd,
+    {
+        Accept {
+            ring: self.0.clone(),
+            fd: listener.as_fd(),
+            multishot: true,
+            op: None,
+        }
+    }
+}
+
+/// Connection acceptor created by [Ring::accept].
+pub struct Accept<'a> {
+    ring: Rc<RingInner>,
+    fd: BorrowedFd<'a>,
+    multishot: bool,
+    op: Option<Op>,
+}
+
+impl Accept<'_> {
+    /// Accept a new connection. Accepted stream is in non blocking mode.
+    pub async fn accept(&mut self) -> io::Result<(TcpStream, SocketAddr)> {
+        loop {
+            if self.op.is_none() {
+                let fd = types::Fd(self.fd.as_raw_fd());
+                let entry = if self.multishot {
+                    opcode::AcceptMulti::new(fd).flags(ACCEPT_FLAGS).build()
+                } else {
+                    opcode::Accept::new(fd, ptr::null_mut(), ptr::null_mut())
+                        .flags(ACCEPT_FLAGS)
+                        .build()
+                };
+                self.op = Some(self.ring.push(entry, OpKind::Accept)?);
+            }
+
+            let op = self.op.as_ref().unwrap();
+            let Some((res, flags)) = poll_fn(|cx| op.poll_next(cx)).await else {
+                self.op = None;
+                continue;
+            };
+
+            // operation is finished and must be submitted again on next accept.
+            if !cqueue::more(flags) {
+                self.op = None;
+            }
+
+            if res == -libc::EINVAL && self.multishot {
+                self.multishot = false;
+                continue;
+            }
+
+            if res < 0 {
+                return Err(io::Error::from_raw_os_error(-res));
+            }
+
+            // SAFETY: successful accept completion carries a newly created socket fd.
+            let stream = unsafe { TcpStream::from_raw_fd(res) };
+            // connection can be reset before it's addr is resolved. move on to the next one.
+            if let Ok(addr) = stream.peer_addr() {
+                return Ok((stream, addr));
+            }
+        }
+    }
+
+    /// Check if multishot accept is used. It's false after falling back to single-shot accept.
+    pub fn is_multishot(&self) -> bool {
+        self.multishot
+    }
+}
+
+struct RingInner {
+    // must be dropped before uring where the fd is closed.
+    fd: AsyncFd<RawFd>,
+    uring: RefCell<IoUring>,
+    ops: RefCell<HashMap<u64, OpState>>,
+    // reusable storage for draining completion queue.
+    completions: Cell<Vec<(u64, i32, u32)>>,
+    next_id: Cell<u64>,
+    driving: Cell<bool>,
+}
+
+impl RingInner {
+    fn push(self: &Rc<Self>, entry: squeue::Entry, kind: OpKind) -> io::Result<Op> {
+        let id = self.next_id.get();
+        self.next_id.set(id.wrapping_add(1) % CANCEL_USER_DATA);
+
+        self.ops.borrow_mut().insert(id, OpState::new(kind));
+
+        match self.submit(entry.user_data(id)) {
+            Ok(_) => Ok(Op { ring: self.clone(), id }),
+            Err(e) => {
+                self.ops.borrow_mut().remove(&id);
+                Err(e)
+            }
+        }
+    }
+
+    fn submit(&self, entry: squeue::Entry) -> io::Result<()> {
+        let mut uring = self.uring.borrow_mut();
+        // SAFETY: operations submitted by this module do not reference memory owned by caller.
+        while unsafe { uring.submission().push(&entry) }.is_err() {
+            uring.submit()?;
+        }
+        uring.submit().map(|_| ())
+    }
+
+    fn drain(&self) {
+        let mut completions = self.completions.take();
+
+        completions.extend(
+            self.uring
+                .borrow_mut()
+                .completion()
+                .map(|cqe| (cqe.user_data(), cqe.result(), cqe.flags())),
+        );
+
+        let mut wakers = Vec::new();
+
+        {
+            let mut ops = self.ops.borrow_mut();
+            for (id, res, flags) in completions.drain(..) {
+                let Some(state) = ops.get_mut(&id) else {
+                    continue;
+                };
+
+                let last = !cqueue::more(flags);
+
+                if state.orphan {
+                    state.kind.discard(res);
+                    if last {
+                        ops.remove(&id);
+                    }
+                    continue;
+                }
+
+                state.completions.push_back((res, flags));
+                state.finished = last;
+                wakers.extend(state.waker.take());
+            }
+        }
+
+        self.completions.set(completions);
+
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl Drop for RingInner {
+    fn drop(&mut self) {
+        // collect completions of orphan operations so accepted sockets are closed.
+        self.drain();
+    }
+}
+
+async fn drive(ring: Rc<RingInner>) {
+    struct Guard<'a>(&'a RingInner);
+
+    impl Drop for Guard<'_> {
+        fn drop(&mut self) {
+            self.0.driving.set(false);
+        }
+    }
+
+    let _guard = Guard(&ring);
+
+    while let Ok(mut guard) = ring.fd.readable().await {
+        // clear readiness before draining so completion arriving in between wakes up driver again.
+        guard.clear_ready();
+        ring.drain();
+    }
+}
+
+struct Op {
+    ring: Rc<RingInner>,
+    id: u64,
+}
+
+impl Op {
+    fn poll_next(&self, cx: &mut Context<'_>) -> Poll<Option<(i32, u32)>> {
+        let mut ops = self.ring.ops.borrow_mut();
+        let state = ops.get_mut(&self.id).expect("operation state must outlive it's handle");
+        match state.completions.pop_front() {
+            Some(completion) => Poll::Ready(Some(completion)),
+            None if state.finished => Poll::Ready(None),
+            None => {
+                match state.waker {
+                    Some(ref mut waker) => waker.clone_from(cx.waker()),
+                    None => state.waker = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for Op {
+    fn drop(&mut self) {
+        let mut ops = self.ring.ops.borrow_mut();
+        let state = ops.get_mut(&self.id).expect("operation state must outlive it's handle");
+
+        let completions = mem::take(&mut state.completions);
+        for (res, _) in completions {
+            state.kind.discard(res);
+        }
+
+        if state.finished {
+            ops.remove(&self.id);
+            return;
+        }
+
+        // operation is still in flight. keep it's state for discarding completions and cancel it.
+        state.orphan = true;
+        state.waker = None;
+        drop(ops);
+
+        let entry = opcode::AsyncCancel::new(self.id).build().user_data(CANCEL_USER_DATA);
+        let _ = self.ring.submit(entry);
+    }
+}
+
+struct OpState {
+    kind: OpKind,
+    completions: VecDeque<(i32, u32)>,
+    waker: Option<Waker>,
+    finished: bool,
+    orphan: bool,
+}
+
+impl OpState {
+    fn new(kind: OpKind) -> Self {
+        Self {
+            kind,
+            completions: VecDeque::new(),
+            waker: None,
+            finished: false,
+            orphan: false,
+        }
+    }
+}
+
+enum OpKind {
+    Accept,
+}
+
+impl OpKind {
+    // release resource carried by completion no one is waiting for.
+    fn discard(&self, res: i32) {
+        match *self {
+            Self::Accept => {
+                if res >= 0 {
+                    // SAFETY: successful accept completion carries a newly created socket fd.
+                    drop(unsafe { OwnedFd::from_raw_fd(res) });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::future::Future;
+
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    async fn run<F: Future>(fut: F) -> F::Output {
+        tokio::task::LocalSet::new().run_until(fut).await
+    }
+
+    #[tokio::test]
+    async fn accept() {
+        run(async {
+            let ring = Ring::current().unwrap();
+
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.set_nonblocking(true).unwrap();
+            let addr = listener.local_addr().unwrap();
+
+            let mut accept = ring.accept(&listener);
+
+            for _ in 0..3 {
+                let mut client = std::net::TcpStream::connect(addr).unwrap();
+                let (stream, peer) = accept.accept().await.unwrap();
+                assert_eq!(peer, client.local_addr().unwrap());
+
+                (&stream).write_all(b"hello").unwrap();
+                let mut res = [0; 5];
+                client.read_exact(&mut res).unwrap();
+                assert_eq!(&res, b"hello");
+            }
+
+            assert!(accept.is_multishot());
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn drop_pending() {
+        run(async {
+            let ring = Ring::current().unwrap();
+
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.set_nonblocking(true).unwrap();
+            let addr = listener.local_addr().unwrap();
+
+            {
+                // pending accept is cancelled on drop.
+                let mut accept = ring.accept(&listener);
+                let accept = accept.accept();
+                let mut accept = core::pin::pin!(accept);
+                assert!(poll_fn(|cx| Poll::Ready(accept.as_mut().poll(cx).is_pending())).await);
+            }
+
+            // cancellation is completed asynchronously.
+            for _ in 0..100 {
+                if ring.0.ops.borrow().is_empty() {
+                    break;
+                }
+                tokio::task::yield_now().await;
+            }
+            assert!(ring.0.ops.borrow().is_empty());
+
+            let mut accept = ring.accept(&listener);
+            let client = std::net::TcpStream::connect(addr).unwrap();
+            let (_, peer) = accept.accept().await.unwrap();
+            assert_eq!(peer, client.local_addr().unwrap());
+        })
+        .await
+    }
+}
@@ -0,0 +1,149 @@
+use core::net::SocketAddr;
+
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use bytes::{Bytes, BytesMut};
+use tokio::net::UdpSocket;
+
+use super::Stream;
+
+// maximum payload size of udp datagram.
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+// receive buffer is allocated in chunks that fit multiple maximum sized datagrams.
+const BUF_SIZE: usize = MAX_DATAGRAM_SIZE * 4;
+
+/// Listener type for connectionless udp socket.
+///
+/// Unlike connection oriented listener types every accepted [Datagram] is a single packet received
+/// from socket. The socket is shared between all accepted datagrams for replying to peer.
+#[derive(Debug)]
+pub struct UdpListener {
+    socket: Arc<UdpSocket>,
+    buf: Mutex<BytesMut>,
+}
+
+impl UdpListener {
+    pub fn from_std(socket: std::net::UdpSocket) -> io::Result<Self> {
+        UdpSocket::from_std(socket).map(|socket| Self {
+            socket: Arc::new(socket),
+            buf: Mutex::new(BytesMut::new()),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Receive a [Datagram] from socket.
+    ///
+    /// Payload of datagram is split off from a receive buffer shared by the listener. The buffer is reused
+    /// when all payloads split from it are dropped.
+    pub async fn accept(&self) -> io::Result<Datagram> {
+        loop {
+            self.socket.readable().await?;
+
+            let mut buf = self.buf.lock().unwrap();
+
+            if buf.capacity() < MAX_DATAGRAM_SIZE {
+                buf.reserve(BUF_SIZE);
+            }
+
+            match self.socket.try_recv_buf_from(&mut *buf) {
+                Ok((_, peer)) => {
+                    return Ok(Datagram {
+                        data: buf.split().freeze(),
+                        peer,
+                        socket: self.socket.clone(),
+                    })
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// A single packet received by [UdpListener].
+pub struct Datagram {
+    data: Bytes,
+    peer: SocketAddr,
+    socket: Arc<UdpSocket>,
+}
+
+impl Datagram {
+    /// Payload of datagram.
+    pub fn data(&self) -> &Bytes {
+        &self.data
+    }
+
+    /// Take ownership of payload of datagram.
+    pub fn into_data(self) -> Bytes {
+        self.data
+    }
+
+    /// Get remote [`SocketAddr`] datagram is sent from.
+    pub fn peer_addr(&self) -> SocketAddr {
+        self.peer
+    }
+
+    /// Send datagram to the peer through the socket it's received from.
+    pub async fn reply(&self, buf: &[u8]) -> io::Result<usize> {
+        self.socket.send_to(buf, self.peer).await
+    }
+
+    /// Reference of the socket datagram is received from. Can be used to send datagram to arbitrary peer.
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+}
+
+impl TryFrom<Stream> for Datagram {
+    type Error = io::Error;
+
+    fn try_from(stream: Stream) -> Result<Self, Self::Error> {
+        <(Datagram, SocketAddr)>::try_from(stream).map(|(datagram, _)| datagram)
+    }
+}
+
+impl TryFrom<Stream> for (Datagram, SocketAddr) {
+    type Error = io::Error;
+
+    fn try_from(stream: Stream) -> Result<Self, Self::Error> {
+        match stream {
+            Stream::Datagram(datagram, addr) => Ok((datagram, addr)),
+            _ => unreachable!("Can not be casted to Datagram"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn accept_split_buf() {
+        let listener = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let listener = UdpListener::from_std(listener).unwrap();
+
+        let client = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        let mut datagrams = Vec::new();
+        for msg in [&b"996"[..], b"251", &[7; MAX_DATAGRAM_SIZE]] {
+            client.send_to(msg, addr).unwrap();
+            let datagram = listener.accept().await.unwrap();
+            assert_eq!(datagram.peer_addr(), client.local_addr().unwrap());
+            datagrams.push(datagram);
+        }
+
+        // payloads split from the same buffer do not overlap.
+        assert_eq!(datagrams[0].data(), &b"996"[..]);
+        assert_eq!(datagrams[1].data(), &b"251"[..]);
+        assert_eq!(datagrams[2].data(), &[7; MAX_DATAGRAM_SIZE][..]);
+    }
+}
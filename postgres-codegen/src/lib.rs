@@ -7,7 +7,7 @@ use syn::{
     parse::{Parse, ParseStream},
     spanned::Spanned,
     token::Comma,
-    Expr, ExprReference, Lit, LitStr,
+    Data, DeriveInput, Expr, ExprReference, Fields, Lit, LitStr,
 };
 
 #[proc_macro]
@@ -21,6 +21,62 @@ pub fn sql(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     .into()
 }
 
+/// derive `xitca_postgres::row::FromRow` trait for struct with named fields.
+///
+/// every field is matched to the column with the same name and the row must contain the exact amount of columns
+/// as the struct's fields. nullable column can be mapped to `Option<T>` typed field.
+#[proc_macro_derive(FromRow)]
+pub fn from_row(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => {
+                return syn::Error::new(
+                    input.ident.span(),
+                    "FromRow can only be derived for struct with named fields",
+                )
+                .to_compile_error()
+                .into()
+            }
+        },
+        _ => {
+            return syn::Error::new(input.ident.span(), "FromRow can only be derived for struct")
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let ident = &input.ident;
+    let (impl_gen, ty_gen, where_clause) = input.generics.split_for_impl();
+    let len = fields.len();
+    let fields = fields.iter().map(|field| {
+        let ident = field.ident.as_ref().unwrap();
+        let name = ident.to_string();
+        let name = name.strip_prefix("r#").unwrap_or(&name);
+        quote! { #ident: row.try_get(#name)? }
+    });
+
+    quote! {
+        impl #impl_gen ::xitca_postgres::row::FromRow for #ident #ty_gen #where_clause {
+            fn from_row(row: &::xitca_postgres::row::Row<'_>) -> Result<Self, ::xitca_postgres::error::Error> {
+                if row.len() != #len {
+                    return Err(::xitca_postgres::error::InvalidColumnCount {
+                        expected: #len,
+                        columns: row.len(),
+                    }
+                    .into());
+                }
+                Ok(Self {
+                    #(#fields),*
+                })
+            }
+        }
+    }
+    .into()
+}
+
 struct Query {
     sql: LitStr,
     exprs: Vec<ExprReference>,
@@ -4,7 +4,14 @@ use std::future::IntoFuture;
 
 use xitca_postgres::{iter::AsyncLendingIterator, Execute, Postgres};
 // an example proc macro
-use xitca_postgres_codegen::sql;
+use xitca_postgres_codegen::{sql, FromRow};
+
+// derive macro for mapping row to struct by column names. nullable column can be mapped to Option type.
+#[derive(FromRow)]
+struct Person {
+    id: i32,
+    name: Option<String>,
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -28,5 +35,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     assert_eq!(row.get::<&str>("name"), "alice");
 
+    drop(stream);
+
+    // map rows of stream to type implementing FromRow trait.
+    let mut stream = sql!("SELECT id, name FROM foo WHERE id = $1", &2i32)
+        .query(&cli)
+        .await?
+        .map_rows::<Person>();
+
+    let person = stream.try_next().await?.ok_or("row not found")?;
+
+    assert_eq!(person.id, 2);
+    assert_eq!(person.name.as_deref(), Some("bob"));
+
     Ok(())
 }
@@ -80,6 +80,10 @@ impl Prepare for PoolConnection {
     fn _get_type_blocking(&self, oid: Oid) -> Result<Type, Error> {
         self.conn._get_type_blocking(oid)
     }
+
+    fn _query_observed(&self) -> bool {
+        self.conn._query_observed()
+    }
 }
 
 // trait for how a query is sent.
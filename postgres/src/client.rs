@@ -18,6 +18,7 @@ use super::{
         DriverTx,
     },
     error::Error,
+    observe::QueryObserver,
     prepare::Prepare,
     query::Query,
     session::Session,
@@ -103,6 +104,7 @@ pub struct Client {
 
 pub(crate) struct ClientCache {
     session: Session,
+    query_observer: Option<QueryObserver>,
     type_info: Mutex<CachedTypeInfo>,
 }
 
@@ -131,6 +133,18 @@ impl Client {
         Transaction::<Self>::builder().begin(self)
     }
 
+    /// run given async function in a transaction and retry it on serialization failure or deadlock error.
+    ///
+    /// see [`TransactionBuilder::retry`] for detail and requirement of idempotent function.
+    ///
+    /// [`TransactionBuilder::retry`]: crate::transaction::TransactionBuilder::retry
+    pub async fn transaction_retry<F, T>(&mut self, max_attempts: usize, func: F) -> Result<T, Error>
+    where
+        F: for<'t, 'c> FnMut(&'t mut Transaction<'c, Self>) -> crate::BoxedFuture<'t, Result<T, Error>>,
+    {
+        Transaction::<Self>::builder().retry(self, max_attempts, func).await
+    }
+
     /// Executes a `COPY FROM STDIN` statement, returning a sink used to write the copy data.
     ///
     /// PostgreSQL does not support parameters in `COPY` statements, so this method does not take any. The copy *must*
@@ -210,6 +224,10 @@ impl Client {
         self.cache.type_info.lock().unwrap().types.insert(oid, type_.clone());
     }
 
+    pub(crate) fn query_observed(&self) -> bool {
+        self.cache.query_observer.is_some()
+    }
+
     /// Clears the client's type information cache.
     ///
     /// When user-defined types are used in a query, the client loads their definitions from the database and caches
@@ -219,11 +237,12 @@ impl Client {
         self.cache.type_info.lock().unwrap().types.clear();
     }
 
-    pub(crate) fn new(tx: DriverTx, session: Session) -> Self {
+    pub(crate) fn new(tx: DriverTx, session: Session, query_observer: Option<QueryObserver>) -> Self {
         Self {
             tx,
             cache: Box::new(ClientCache {
                 session,
+                query_observer,
                 type_info: Mutex::new(CachedTypeInfo {
                     typeinfo: None,
                     typeinfo_composite: None,
@@ -252,6 +271,11 @@ impl Prepare for Arc<Client> {
     fn _get_type_blocking(&self, oid: Oid) -> Result<Type, Error> {
         Client::_get_type_blocking(self, oid)
     }
+
+    #[inline]
+    fn _query_observed(&self) -> bool {
+        Client::_query_observed(self)
+    }
 }
 
 impl Query for Arc<Client> {
@@ -270,7 +294,15 @@ impl Query for Client {
     where
         S: Encode,
     {
-        encode::send_encode_query(&self.tx, stmt)
+        let observing = self.cache.query_observer.as_ref().and_then(|observer| {
+            stmt.observe_hint()
+                .map(|(sql, params)| observer.observe(sql, params, stmt.count_hint()))
+        });
+        let (output, mut res) = encode::send_encode_query(&self.tx, stmt)?;
+        if let Some(observing) = observing {
+            res.observe(observing);
+        }
+        Ok((output, res))
     }
 }
 
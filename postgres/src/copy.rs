@@ -1,5 +1,11 @@
 use core::future::Future;
 
+#[cfg(feature = "compat")]
+use core::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
 use postgres_protocol::message::{backend, frontend};
 use xitca_io::bytes::{Buf, Bytes, BytesMut};
 
@@ -95,6 +101,17 @@ where
     }
 }
 
+/// async iterator of raw data produced by `COPY ... TO STDOUT` statement.
+///
+/// with `compat` crate feature enabled [`CopyOut`] implements [`Stream`] trait where it can be piped into other
+/// types expecting a stream of [`Bytes`]. (http response body, file writer, etc)
+///
+/// *. data is received from database by [`Driver`] and buffered until [`CopyOut`] consumes it. slow consumer
+/// does not throttle the reading of database connection and the buffered data can grow when the consumer can't
+/// keep up with the database.
+///
+/// [`Stream`]: futures_core::stream::Stream
+/// [`Driver`]: crate::driver::Driver
 pub struct CopyOut {
     res: Response,
 }
@@ -136,3 +153,16 @@ impl AsyncLendingIterator for CopyOut {
         }
     }
 }
+
+#[cfg(feature = "compat")]
+impl futures_core::stream::Stream for CopyOut {
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match ready!(self.get_mut().res.poll_recv(cx))? {
+            backend::Message::CopyData(body) => Poll::Ready(Some(Ok(body.into_bytes()))),
+            backend::Message::CopyDone => Poll::Ready(None),
+            _ => Poll::Ready(Some(Err(Error::unexpected()))),
+        }
+    }
+}
@@ -57,7 +57,7 @@ pub(super) async fn connect(cfg: &mut Config) -> Result<(Client, Driver), Error>
     let hosts = cfg.get_hosts().to_vec();
     for host in hosts {
         match self::connect::connect_host(host, cfg).await {
-            Ok((tx, session, drv)) => return Ok((Client::new(tx, session), drv)),
+            Ok((tx, session, drv)) => return Ok((Client::new(tx, session, cfg.query_observer.clone()), drv)),
             Err(e) => err = Some(e),
         }
     }
@@ -70,7 +70,10 @@ where
     Io: AsyncIo + Send + 'static,
 {
     let (tx, session, drv) = prepare_driver(ConnectInfo::default(), Box::new(io) as _, cfg).await?;
-    Ok((Client::new(tx, session), Driver::Dynamic(drv)))
+    Ok((
+        Client::new(tx, session, cfg.query_observer.clone()),
+        Driver::Dynamic(drv),
+    ))
 }
 
 pub(super) async fn connect_info(info: ConnectInfo) -> Result<(DriverTx, Driver), Error> {
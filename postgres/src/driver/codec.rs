@@ -2,6 +2,7 @@ pub(crate) mod encode;
 pub(crate) mod response;
 
 use core::{
+    fmt,
     future::{poll_fn, Future},
     task::{ready, Context, Poll},
 };
@@ -12,6 +13,7 @@ use xitca_io::bytes::BytesMut;
 
 use crate::{
     error::{Completed, DriverDownReceiving, Error},
+    observe::Observing,
     types::BorrowToSql,
 };
 
@@ -25,18 +27,41 @@ pub(super) fn request_pair(msg_count: usize) -> (ResponseSender, Response) {
             rx,
             buf: BytesMut::new(),
             complete: false,
+            observing: None,
         },
     )
 }
 
-#[derive(Debug)]
 pub struct Response {
     rx: ResponseReceiver,
     buf: BytesMut,
     complete: bool,
+    observing: Option<Box<Observing>>,
+}
+
+impl fmt::Debug for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response")
+            .field("rx", &self.rx)
+            .field("buf", &self.buf)
+            .field("complete", &self.complete)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Response {
+    fn drop(&mut self) {
+        if let Some(observing) = self.observing.take() {
+            observing.finish(None);
+        }
+    }
 }
 
 impl Response {
+    pub(crate) fn observe(&mut self, observing: Observing) {
+        self.observing = Some(Box::new(observing));
+    }
+
     pub(crate) fn blocking_recv(&mut self) -> Result<backend::Message, Error> {
         if self.buf.is_empty() {
             let res = self.rx.blocking_recv();
@@ -125,8 +150,21 @@ impl Response {
 
     fn parse_message(&mut self) -> Result<backend::Message, Error> {
         match backend::Message::parse(&mut self.buf)?.expect("must not parse message from empty buffer.") {
-            backend::Message::ErrorResponse(body) => Err(Error::db(body.fields())),
-            msg => Ok(msg),
+            backend::Message::ErrorResponse(body) => {
+                let e = Error::db(body.fields());
+                if let Some(observing) = self.observing.take() {
+                    observing.finish(Some(&e));
+                }
+                Err(e)
+            }
+            msg => {
+                if let Some(ref mut observing) = self.observing {
+                    if observing.on_message(&msg) {
+                        self.observing.take().unwrap().finish(None);
+                    }
+                }
+                Ok(msg)
+            }
         }
     }
 }
@@ -42,6 +42,15 @@ pub trait Encode: sealed::Sealed + Sized {
     fn count_hint(&self) -> usize {
         1
     }
+
+    #[doc(hidden)]
+    /// sql text and parameter count of query for [`Config::query_observer`]. query is not observed when it's None.
+    ///
+    /// [`Config::query_observer`]: crate::Config::query_observer
+    #[inline(always)]
+    fn observe_hint(&self) -> Option<(&str, usize)> {
+        None
+    }
 }
 
 impl sealed::Sealed for &str {}
@@ -54,6 +63,11 @@ impl Encode for &str {
         frontend::query(self, buf)?;
         Ok(Vec::new())
     }
+
+    #[inline]
+    fn observe_hint(&self) -> Option<(&str, usize)> {
+        Some((*self, 0))
+    }
 }
 
 impl sealed::Sealed for &Statement {}
@@ -70,6 +84,11 @@ impl<'s> Encode for &'s Statement {
         }
         Ok(self.columns())
     }
+
+    #[inline]
+    fn observe_hint(&self) -> Option<(&str, usize)> {
+        self.sql().map(|sql| (sql, 0))
+    }
 }
 
 impl<C> sealed::Sealed for StatementCreate<'_, '_, C> {}
@@ -83,7 +102,16 @@ where
     #[inline]
     fn encode<const SYNC_MODE: bool>(self, buf: &mut BytesMut) -> Result<Self::Output, Error> {
         let Self { name, stmt, types, cli } = self;
-        encode_statement_create(&name, stmt, types, buf).map(|_| StatementCreateResponse { name, cli })
+        encode_statement_create(&name, stmt, types, buf).map(|_| StatementCreateResponse {
+            name,
+            sql: cli._query_observed().then(|| Box::from(stmt)),
+            cli,
+        })
+    }
+
+    #[inline]
+    fn observe_hint(&self) -> Option<(&str, usize)> {
+        Some((self.stmt, self.types.len()))
     }
 }
 
@@ -98,7 +126,16 @@ where
     #[inline]
     fn encode<const SYNC_MODE: bool>(self, buf: &mut BytesMut) -> Result<Self::Output, Error> {
         let Self { name, stmt, types, cli } = self;
-        encode_statement_create(&name, stmt, types, buf).map(|_| StatementCreateResponseBlocking { name, cli })
+        encode_statement_create(&name, stmt, types, buf).map(|_| StatementCreateResponseBlocking {
+            name,
+            sql: cli._query_observed().then(|| Box::from(stmt)),
+            cli,
+        })
+    }
+
+    #[inline]
+    fn observe_hint(&self) -> Option<(&str, usize)> {
+        Some((self.stmt, self.types.len()))
     }
 }
 
@@ -145,6 +182,11 @@ where
         }
         Ok(stmt.columns())
     }
+
+    #[inline]
+    fn observe_hint(&self) -> Option<(&str, usize)> {
+        self.stmt.sql().map(|sql| (sql, self.stmt.params().len()))
+    }
 }
 
 impl<C, P> sealed::Sealed for StatementUnnamedQuery<'_, '_, P, C> {}
@@ -173,6 +215,11 @@ where
         }
         Ok(IntoRowStreamGuard(cli))
     }
+
+    #[inline]
+    fn observe_hint(&self) -> Option<(&str, usize)> {
+        Some((self.stmt, self.types.len()))
+    }
 }
 
 pub(crate) struct PortalCreate<'a, P> {
@@ -85,6 +85,7 @@ impl IntoResponse for NoOpIntoRowStream {
 
 pub struct StatementCreateResponse<'a, C> {
     pub(super) name: String,
+    pub(super) sql: Option<Box<str>>,
     pub(super) cli: &'a C,
 }
 
@@ -98,7 +99,7 @@ where
 
     fn into_response(self, mut res: Response) -> Self::Response {
         Box::pin(async move {
-            let Self { name, cli } = self;
+            let Self { name, sql, cli } = self;
 
             match res.recv().await? {
                 backend::Message::ParseComplete => {}
@@ -133,13 +134,14 @@ where
                 }
             }
 
-            Ok(Statement::new(name, params, columns))
+            Ok(Statement::new(name, sql, params, columns))
         })
     }
 }
 
 pub struct StatementCreateResponseBlocking<'a, C> {
     pub(super) name: String,
+    pub(super) sql: Option<Box<str>>,
     pub(super) cli: &'a C,
 }
 
@@ -152,7 +154,7 @@ where
     type Response = Result<Statement, Error>;
 
     fn into_response(self, mut res: Response) -> Self::Response {
-        let Self { name, cli } = self;
+        let Self { name, sql, cli } = self;
 
         match res.blocking_recv()? {
             backend::Message::ParseComplete => {}
@@ -187,6 +189,6 @@ where
             }
         }
 
-        Ok(Statement::new(name, params, columns))
+        Ok(Statement::new(name, sql, params, columns))
     }
 }
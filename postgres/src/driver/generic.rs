@@ -313,6 +313,9 @@ where
 
     #[cold]
     fn on_read_err(&mut self, e: io::Error) {
+        // database would not send response after read side is closed. close shared state so driver tx can observe
+        // the shutdown and driver can finish after pending writes instead of waiting for tx to be dropped.
+        self.shared_state.guarded.lock().unwrap().closed = true;
         let reason = (e.kind() != io::ErrorKind::UnexpectedEof).then_some(e);
         self.read_state = ReadState::Closed(reason);
     }
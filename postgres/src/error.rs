@@ -42,6 +42,23 @@ impl Error {
         self.0.is::<DriverDown>() || self.0.is::<DriverDownReceiving>()
     }
 
+    /// check if error is [`DbError`] produced by a statement canceled by database because it exceeds the
+    /// `statement_timeout` setting.
+    ///
+    /// see [`TransactionBuilder::statement_timeout`] for setting per query statement timeout.
+    ///
+    /// *. the detection is based on database's error message and only works when database server is using english
+    /// `lc_messages` setting. for other settings consider check [`DbError::code`] against
+    /// [`SqlState::QUERY_CANCELED`] where statement canceled by [`Client::cancel_token`] shares the same code.
+    ///
+    /// [`TransactionBuilder::statement_timeout`]: crate::transaction::TransactionBuilder::statement_timeout
+    /// [`Client::cancel_token`]: crate::Client::cancel_token
+    pub fn is_statement_timeout(&self) -> bool {
+        self.0
+            .downcast_ref::<DbError>()
+            .is_some_and(|e| e.code() == &SqlState::QUERY_CANCELED && e.message() == STATEMENT_TIMEOUT_MESSAGE)
+    }
+
     pub(crate) fn todo() -> Self {
         Self(Box::new(ToDo {
             back_trace: Backtrace::capture(),
@@ -281,6 +298,52 @@ impl error::Error for InvalidParamCount {}
 
 from_impl!(InvalidParamCount);
 
+/// error happens when [`FromRow`] impl expecting a different number of columns from the row it's mapping.
+///
+/// [`FromRow`]: crate::row::FromRow
+#[derive(Debug)]
+pub struct InvalidColumnCount {
+    pub expected: usize,
+    pub columns: usize,
+}
+
+impl fmt::Display for InvalidColumnCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected row with {} columns but got {}.\r\n",
+            self.expected, self.columns
+        )?;
+        f.write_str("note: consider check the selected columns of query match the fields of mapped type")
+    }
+}
+
+impl error::Error for InvalidColumnCount {}
+
+from_impl!(InvalidColumnCount);
+
+/// error happens when query expecting exactly one row but database returns zero or more than one rows.
+#[derive(Debug)]
+pub enum InvalidRowCount {
+    /// query returns no row.
+    Zero,
+    /// query returns more than one row.
+    Multiple,
+}
+
+impl fmt::Display for InvalidRowCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Zero => f.write_str("expected query returns exactly one row but got none"),
+            Self::Multiple => f.write_str("expected query returns exactly one row but got more than one"),
+        }
+    }
+}
+
+impl error::Error for InvalidRowCount {}
+
+from_impl!(InvalidRowCount);
+
 impl From<Infallible> for Error {
     fn from(e: Infallible) -> Self {
         match e {}
@@ -408,6 +471,8 @@ pub(crate) fn unexpected_eof_err() -> io::Error {
 
 from_impl!(WrongType);
 
+const STATEMENT_TIMEOUT_MESSAGE: &str = "canceling statement due to statement timeout";
+
 /// A Postgres error or notice.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DbError {
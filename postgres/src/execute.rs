@@ -1,6 +1,16 @@
 mod async_impl;
 mod sync_impl;
 
+use core::future::Future;
+
+use postgres_types::FromSql;
+
+use crate::{
+    error::{Error, InvalidRowCount},
+    query::RowStreamOne,
+    row::RowOwned,
+};
+
 /// Defining how a query is executed. can be used for customizing encoding, executing and database
 /// data decoding.
 ///
@@ -40,6 +50,70 @@ where
 
     /// define how a statement is queried with repeated response
     fn query(self, cli: C) -> Self::QueryOutput;
+
+    /// query a statement that is expected to produce exactly one row.
+    ///
+    /// [`InvalidRowCount::Zero`] error is returned when query produce no row and [`InvalidRowCount::Multiple`]
+    /// error is returned when query produce more than one row.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_postgres::{Client, Error, Execute, Statement};
+    /// # async fn query_one(cli: Client) -> Result<(), Error> {
+    /// let stmt = Statement::named("SELECT id, name FROM users WHERE id = $1", &[]).execute(&cli).await?;
+    /// let row = stmt.bind([1i32]).query_one(&cli).await?;
+    /// let name: &str = row.get("name");
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn query_one(self, cli: C) -> impl Future<Output = Result<RowOwned, Error>> + Send
+    where
+        Self::QueryOutput: Future<Output: RowStreamOne> + Send,
+    {
+        let fut = self.query(cli);
+        async { fut.await.try_one().await?.ok_or_else(|| InvalidRowCount::Zero.into()) }
+    }
+
+    /// query a statement that is expected to produce exactly one row and decode the first column of it to type `T`.
+    ///
+    /// error condition is the same as [`Execute::query_one`]. consider use [`Execute::query_opt_scalar`] when the
+    /// query can produce no row.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_postgres::{Client, Error, Execute, Statement};
+    /// # async fn query_scalar(cli: Client) -> Result<(), Error> {
+    /// let stmt = Statement::named("SELECT count(*) FROM users", &[]).execute(&cli).await?;
+    /// let count = stmt.query_scalar::<i64>(&cli).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn query_scalar<T>(self, cli: C) -> impl Future<Output = Result<T, Error>> + Send
+    where
+        Self::QueryOutput: Future<Output: RowStreamOne> + Send,
+        T: for<'r> FromSql<'r>,
+    {
+        let fut = self.query_one(cli);
+        async { fut.await?.try_get(0) }
+    }
+
+    /// query a statement that is expected to produce at most one row and decode the first column of it to type `T`.
+    ///
+    /// `Ok(None)` is returned when query produce no row and [`InvalidRowCount::Multiple`] error is returned when
+    /// query produce more than one row.
+    fn query_opt_scalar<T>(self, cli: C) -> impl Future<Output = Result<Option<T>, Error>> + Send
+    where
+        Self::QueryOutput: Future<Output: RowStreamOne> + Send,
+        T: for<'r> FromSql<'r>,
+    {
+        let fut = self.query(cli);
+        async {
+            match fut.await.try_one().await? {
+                Some(row) => row.try_get(0).map(Some),
+                None => Ok(None),
+            }
+        }
+    }
 }
 
 /// blocking version of [`Execute`] for synchronous environment
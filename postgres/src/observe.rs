@@ -0,0 +1,140 @@
+//! observability of queries sent by [Client].
+//!
+//! see [`Config::query_observer`] for usage.
+//!
+//! [Client]: crate::Client
+//! [`Config::query_observer`]: crate::Config::query_observer
+
+use core::{fmt, time::Duration};
+
+use std::{sync::Arc, time::Instant};
+
+use postgres_protocol::message::backend;
+
+use super::{driver::codec::body_to_affected_rows, error::Error};
+
+/// information of a finished query. passed to observer function registered with [`Config::query_observer`].
+///
+/// [`Config::query_observer`]: crate::Config::query_observer
+pub struct QueryEvent<'a> {
+    sql: &'a str,
+    params: usize,
+    rows: u64,
+    duration: Duration,
+    error: Option<&'a Error>,
+}
+
+impl QueryEvent<'_> {
+    /// sql text of query.
+    #[inline]
+    pub fn sql(&self) -> &str {
+        self.sql
+    }
+
+    /// count of parameters bound to query.
+    #[inline]
+    pub fn params(&self) -> usize {
+        self.params
+    }
+
+    /// count of rows returned or affected by query. it's the count observed before query is finished or dropped.
+    #[inline]
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    /// duration from query being sent to it's response being fully received, failed or dropped.
+    #[inline]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// error returned by database when query failed.
+    #[inline]
+    pub fn error(&self) -> Option<&Error> {
+        self.error
+    }
+}
+
+impl fmt::Debug for QueryEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryEvent")
+            .field("sql", &self.sql)
+            .field("params", &self.params)
+            .field("rows", &self.rows)
+            .field("duration", &self.duration)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct QueryObserver(Arc<dyn Fn(&QueryEvent<'_>) + Send + Sync>);
+
+impl QueryObserver {
+    pub(crate) fn new<F>(func: F) -> Self
+    where
+        F: Fn(&QueryEvent<'_>) + Send + Sync + 'static,
+    {
+        Self(Arc::new(func))
+    }
+
+    pub(crate) fn observe(&self, sql: &str, params: usize, count: usize) -> Observing {
+        Observing {
+            observer: self.clone(),
+            sql: Box::from(sql),
+            params,
+            rows: 0,
+            remaining: count,
+            start: Instant::now(),
+        }
+    }
+}
+
+// config equality is not affected by observer. it's compared by identity.
+impl PartialEq for QueryObserver {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for QueryObserver {}
+
+/// state of a query being observed. it's attached to query's response and observer is called when response is
+/// finished, failed or dropped.
+pub(crate) struct Observing {
+    observer: QueryObserver,
+    sql: Box<str>,
+    params: usize,
+    rows: u64,
+    // count of ReadyForQuery messages expected before query is finished.
+    remaining: usize,
+    start: Instant,
+}
+
+impl Observing {
+    // return true when query is finished and observer should be called.
+    pub(crate) fn on_message(&mut self, msg: &backend::Message) -> bool {
+        match msg {
+            backend::Message::CommandComplete(body) => {
+                self.rows += body_to_affected_rows(body).unwrap_or(0);
+                false
+            }
+            backend::Message::ReadyForQuery(_) => {
+                self.remaining = self.remaining.saturating_sub(1);
+                self.remaining == 0
+            }
+            _ => false,
+        }
+    }
+
+    pub(crate) fn finish(self, error: Option<&Error>) {
+        (self.observer.0)(&QueryEvent {
+            sql: &self.sql,
+            params: self.params,
+            rows: self.rows,
+            duration: self.start.elapsed(),
+            error,
+        })
+    }
+}
@@ -18,6 +18,14 @@ pub trait Prepare: Query + Sync {
 
     // blocking version of [`Prepare::_get_type`].
     fn _get_type_blocking(&self, oid: Oid) -> Result<Type, Error>;
+
+    // return true when queries are observed by [`Config::query_observer`]. sql text of prepared statement is
+    // only kept when it's true.
+    //
+    // [`Config::query_observer`]: crate::Config::query_observer
+    fn _query_observed(&self) -> bool {
+        false
+    }
 }
 
 impl Prepare for Client {
@@ -112,6 +120,10 @@ impl Prepare for Client {
 
         Ok(type_)
     }
+
+    fn _query_observed(&self) -> bool {
+        self.query_observed()
+    }
 }
 
 const TYPEINFO_QUERY: StatementNamed = Statement::named(
@@ -3,7 +3,10 @@ mod stream;
 #[cfg(feature = "compat")]
 pub(crate) mod compat;
 
-pub use stream::{RowAffected, RowSimpleStream, RowSimpleStreamOwned, RowStream, RowStreamGuarded, RowStreamOwned};
+pub use stream::{
+    MapRows, RowAffected, RowSimpleStream, RowSimpleStreamOwned, RowStream, RowStreamGuarded, RowStreamOne,
+    RowStreamOwned, SimpleQueryMessage, SimpleQueryStream,
+};
 
 use super::{
     driver::codec::{encode::Encode, response::IntoResponse, Response},
@@ -6,9 +6,13 @@ use core::{
 use futures_core::Stream;
 use postgres_protocol::message::backend;
 
-use crate::{error::Error, row::RowOwned};
+use crate::{
+    column::Column,
+    error::Error,
+    row::{marker, FromRow, Row, RowOwned},
+};
 
-use super::RowStreamOwned;
+use super::{stream::GenericRowStream, MapRows, RowStreamOwned};
 
 impl Stream for RowStreamOwned {
     type Item = Result<RowOwned, Error>;
@@ -30,3 +34,30 @@ impl Stream for RowStreamOwned {
         }
     }
 }
+
+impl<C, T> Stream for MapRows<GenericRowStream<C, marker::Typed>, T>
+where
+    C: AsRef<[Column]> + Unpin,
+    T: FromRow,
+{
+    type Item = Result<T, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let stream = &mut self.get_mut().stream;
+        loop {
+            match ready!(stream.res.poll_recv(cx))? {
+                backend::Message::DataRow(body) => {
+                    let res =
+                        Row::try_new(stream.col.as_ref(), body, &mut stream.ranges).and_then(|row| T::from_row(&row));
+                    return Poll::Ready(Some(res));
+                }
+                backend::Message::BindComplete
+                | backend::Message::EmptyQueryResponse
+                | backend::Message::CommandComplete(_)
+                | backend::Message::PortalSuspended => {}
+                backend::Message::ReadyForQuery(_) => return Poll::Ready(None),
+                _ => return Poll::Ready(Some(Err(Error::unexpected()))),
+            }
+        }
+    }
+}
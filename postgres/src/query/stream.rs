@@ -13,11 +13,11 @@ use postgres_protocol::message::backend;
 
 use crate::{
     column::Column,
-    driver::codec::Response,
-    error::Error,
+    driver::codec::{body_to_affected_rows, Response},
+    error::{Error, InvalidRowCount},
     iter::AsyncLendingIterator,
     prepare::Prepare,
-    row::{marker, Row, RowOwned, RowSimple, RowSimpleOwned},
+    row::{marker, FromRow, Row, RowOwned, RowSimple, RowSimpleOwned},
     types::Type,
 };
 
@@ -132,6 +132,116 @@ impl AsyncLendingIterator for RowStreamOwned {
     }
 }
 
+impl<C> GenericRowStream<C, marker::Typed> {
+    /// map rows of stream to type implementing [`FromRow`] trait.
+    ///
+    /// # Examples
+    /// ```
+    /// # use xitca_postgres::{iter::AsyncLendingIterator, row::{FromRow, Row}, Client, Error, Execute, Statement};
+    /// struct User {
+    ///     id: i32,
+    ///     name: Option<String>,
+    /// }
+    ///
+    /// impl FromRow for User {
+    ///     fn from_row(row: &Row<'_>) -> Result<Self, Error> {
+    ///         Ok(Self { id: row.try_get("id")?, name: row.try_get("name")? })
+    ///     }
+    /// }
+    ///
+    /// # async fn map(cli: Client) -> Result<(), Error> {
+    /// let stmt = Statement::named("SELECT id, name FROM users", &[]).execute(&cli).await?;
+    /// let mut users = stmt.query(&cli).await?.map_rows::<User>();
+    /// while let Some(user) = users.try_next().await? {
+    ///     println!("user id: {}, name: {:?}", user.id, user.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn map_rows<T>(self) -> MapRows<Self, T>
+    where
+        T: FromRow,
+    {
+        MapRows {
+            stream: self,
+            _type: PhantomData,
+        }
+    }
+}
+
+/// async iterator produced by `RowStream::map_rows`. yields type implementing [`FromRow`] trait.
+pub struct MapRows<S, T> {
+    pub(super) stream: S,
+    pub(super) _type: PhantomData<fn() -> T>,
+}
+
+impl<C, T> AsyncLendingIterator for MapRows<GenericRowStream<C, marker::Typed>, T>
+where
+    C: AsRef<[Column]> + Send,
+    T: FromRow + Send,
+{
+    type Ok<'i>
+        = T
+    where
+        Self: 'i;
+    type Err = Error;
+
+    async fn try_next(&mut self) -> Result<Option<Self::Ok<'_>>, Self::Err> {
+        let stream = &mut self.stream;
+        match try_next(&mut stream.res, stream.col.as_ref(), &mut stream.ranges).await? {
+            Some(row) => T::from_row(&row).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// typed row stream that can be consumed into a single row. see [`Execute::query_one`] for detail.
+///
+/// [`Execute::query_one`]: crate::Execute::query_one
+pub trait RowStreamOne: Sized {
+    /// consume stream and return it's only row. `Ok(None)` is returned when stream is empty and
+    /// [`InvalidRowCount::Multiple`] error is returned when stream yields more than one row.
+    fn try_one(self) -> impl Future<Output = Result<Option<RowOwned>, Error>> + Send;
+}
+
+impl<S> RowStreamOne for Result<S, Error>
+where
+    S: RowStreamOne + Send,
+{
+    async fn try_one(self) -> Result<Option<RowOwned>, Error> {
+        self?.try_one().await
+    }
+}
+
+impl RowStreamOne for RowStream<'_> {
+    #[inline]
+    fn try_one(self) -> impl Future<Output = Result<Option<RowOwned>, Error>> + Send {
+        RowStreamOwned::from(self).try_one()
+    }
+}
+
+impl RowStreamOne for RowStreamOwned {
+    async fn try_one(mut self) -> Result<Option<RowOwned>, Error> {
+        let mut row = None;
+        loop {
+            match self.res.recv().await? {
+                backend::Message::DataRow(body) => {
+                    if row.is_some() {
+                        return Err(InvalidRowCount::Multiple.into());
+                    }
+                    row = Some(RowOwned::try_new(self.col.clone(), body, Vec::new())?);
+                }
+                backend::Message::BindComplete
+                | backend::Message::EmptyQueryResponse
+                | backend::Message::CommandComplete(_)
+                | backend::Message::PortalSuspended => {}
+                backend::Message::ReadyForQuery(_) => return Ok(row),
+                _ => return Err(Error::unexpected()),
+            }
+        }
+    }
+}
+
 impl IntoIterator for RowStream<'_> {
     type Item = Result<RowOwned, Error>;
     type IntoIter = RowStreamOwned;
@@ -198,6 +308,81 @@ impl AsyncLendingIterator for RowSimpleStream {
     }
 }
 
+impl RowSimpleStream {
+    /// convert stream to async iterator yielding every row and command completion of simple query.
+    ///
+    /// simple query can contain multiple statements separated by semicolon and each statement produces
+    /// it's own result set followed by a command completion. [`RowSimpleStream`] only yields rows and
+    /// discard the boundary between statements. [`SimpleQueryStream`] yields them in order they are
+    /// received from database.
+    ///
+    /// # Examples
+    /// ```
+    /// # use xitca_postgres::{iter::AsyncLendingIterator, Client, Error, Execute, SimpleQueryMessage};
+    /// # async fn messages(cli: Client) -> Result<(), Error> {
+    /// let mut stream = "SELECT 1; UPDATE users SET name = 'foo'".query(&cli).await?.into_messages();
+    /// while let Some(msg) = stream.try_next().await? {
+    ///     match msg {
+    ///         SimpleQueryMessage::Row(row) => println!("row: {:?}", row.get(0)),
+    ///         SimpleQueryMessage::CommandComplete(rows) => println!("rows affected: {rows}"),
+    ///         _ => {}
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_messages(self) -> SimpleQueryStream {
+        SimpleQueryStream { stream: self }
+    }
+}
+
+/// message yielded by [`SimpleQueryStream`].
+#[non_exhaustive]
+pub enum SimpleQueryMessage<'r> {
+    /// a row of current statement's result set.
+    Row(RowSimple<'r>),
+    /// end of current statement with the number of rows affected by it.
+    CommandComplete(u64),
+}
+
+/// async iterator produced by `RowSimpleStream::into_messages`. yields [`SimpleQueryMessage`].
+pub struct SimpleQueryStream {
+    stream: RowSimpleStream,
+}
+
+impl AsyncLendingIterator for SimpleQueryStream {
+    type Ok<'i>
+        = SimpleQueryMessage<'i>
+    where
+        Self: 'i;
+    type Err = Error;
+
+    async fn try_next(&mut self) -> Result<Option<Self::Ok<'_>>, Self::Err> {
+        let stream = &mut self.stream;
+        loop {
+            match stream.res.recv().await? {
+                backend::Message::RowDescription(body) => {
+                    stream.col = body
+                        .fields()
+                        .map(|f| Ok(Column::new(f.name(), Type::TEXT)))
+                        .collect::<Vec<_>>()?;
+                }
+                backend::Message::DataRow(body) => {
+                    return RowSimple::try_new(&stream.col, body, &mut stream.ranges)
+                        .map(|row| Some(SimpleQueryMessage::Row(row)));
+                }
+                backend::Message::CommandComplete(body) => {
+                    let rows = body_to_affected_rows(&body)?;
+                    return Ok(Some(SimpleQueryMessage::CommandComplete(rows)));
+                }
+                backend::Message::EmptyQueryResponse => {}
+                backend::Message::ReadyForQuery(_) => return Ok(None),
+                _ => return Err(Error::unexpected()),
+            }
+        }
+    }
+}
+
 /// [`RowSimpleStreamOwned`] with static lifetime
 pub type RowSimpleStreamOwned = GenericRowStream<Arc<[Column]>, marker::NoTyped>;
 
@@ -310,6 +495,76 @@ pub struct RowStreamGuardedOwned<'a, C> {
     cli: &'a C,
 }
 
+impl<C> RowStreamGuarded<'_, C> {
+    /// map rows of stream to type implementing [`FromRow`] trait.
+    ///
+    /// see [`RowStream::map_rows`] for detail.
+    pub fn map_rows<T>(self) -> MapRows<Self, T>
+    where
+        T: FromRow,
+    {
+        MapRows {
+            stream: self,
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<C, T> AsyncLendingIterator for MapRows<RowStreamGuarded<'_, C>, T>
+where
+    C: Prepare + Sync,
+    T: FromRow + Send,
+{
+    type Ok<'i>
+        = T
+    where
+        Self: 'i;
+    type Err = Error;
+
+    async fn try_next(&mut self) -> Result<Option<Self::Ok<'_>>, Self::Err> {
+        match self.stream.try_next().await? {
+            Some(row) => T::from_row(&row).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<C> RowStreamOne for RowStreamGuarded<'_, C>
+where
+    C: Prepare + Sync,
+{
+    async fn try_one(mut self) -> Result<Option<RowOwned>, Error> {
+        let mut row = None;
+        loop {
+            match self.res.recv().await? {
+                backend::Message::RowDescription(body) => {
+                    let mut it = body.fields();
+                    while let Some(field) = it.next()? {
+                        let ty = self.cli._get_type(field.type_oid()).await?;
+                        self.col.push(Column::new(field.name(), ty));
+                    }
+                }
+                backend::Message::DataRow(body) => {
+                    if row.is_some() {
+                        return Err(InvalidRowCount::Multiple.into());
+                    }
+                    let col = Arc::from(core::mem::take(&mut self.col));
+                    row = Some(RowOwned::try_new(col, body, Vec::new())?);
+                }
+                backend::Message::ParseComplete
+                | backend::Message::BindComplete
+                | backend::Message::ParameterDescription(_)
+                | backend::Message::EmptyQueryResponse
+                | backend::Message::CommandComplete(_)
+                | backend::Message::PortalSuspended
+                | backend::Message::NoData => {}
+                backend::Message::ReadyForQuery(_) => return Ok(row),
+                _ => return Err(Error::unexpected()),
+            }
+        }
+    }
+}
+
 impl<'a, C> From<RowStreamGuarded<'a, C>> for RowStreamGuardedOwned<'a, C> {
     fn from(stream: RowStreamGuarded<'a, C>) -> Self {
         Self {
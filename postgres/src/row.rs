@@ -5,6 +5,40 @@ mod types;
 
 pub use types::{Row, RowOwned, RowSimple, RowSimpleOwned};
 
+use crate::error::Error;
+
+/// trait for constructing a type from a typed [`Row`].
+///
+/// it's used by `RowStream::map_rows` and can be derived with `#[derive(FromRow)]` from `xitca-postgres-codegen`
+/// crate. the derived impl matches struct fields to columns by name and returns [`InvalidColumnCount`] error when
+/// the number of columns in row is not equal to the number of fields.
+///
+/// # Examples
+/// ```rust
+/// use xitca_postgres::{error::Error, row::{FromRow, Row}};
+///
+/// struct User {
+///     id: i32,
+///     // nullable column can be mapped to Option type.
+///     name: Option<String>,
+/// }
+///
+/// // manual impl of FromRow. equivalent to #[derive(FromRow)] on User type.
+/// impl FromRow for User {
+///     fn from_row(row: &Row<'_>) -> Result<Self, Error> {
+///         Ok(Self {
+///             id: row.try_get("id")?,
+///             name: row.try_get("name")?,
+///         })
+///     }
+/// }
+/// ```
+///
+/// [`InvalidColumnCount`]: crate::error::InvalidColumnCount
+pub trait FromRow: Sized {
+    fn from_row(row: &Row<'_>) -> Result<Self, Error>;
+}
+
 // Marker types for specialized impl on row types
 pub(crate) mod marker {
     #[derive(Debug)]
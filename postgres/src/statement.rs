@@ -80,14 +80,16 @@ where
 #[derive(Default)]
 pub struct Statement {
     name: Box<str>,
+    sql: Option<Box<str>>,
     params: Box<[Type]>,
     columns: Box<[Column]>,
 }
 
 impl Statement {
-    pub(crate) fn new(name: String, params: Vec<Type>, columns: Vec<Column>) -> Self {
+    pub(crate) fn new(name: String, sql: Option<Box<str>>, params: Vec<Type>, columns: Vec<Column>) -> Self {
         Self {
             name: name.into_boxed_str(),
+            sql,
             params: params.into_boxed_slice(),
             columns: columns.into_boxed_slice(),
         }
@@ -96,6 +98,7 @@ impl Statement {
     pub(crate) fn duplicate(&self) -> Self {
         Self {
             name: self.name.clone(),
+            sql: self.sql.clone(),
             params: self.params.clone(),
             columns: self.columns.clone(),
         }
@@ -160,6 +163,39 @@ impl Statement {
         self.bind(params.iter().cloned())
     }
 
+    /// [Statement::bind] for owned dynamic typed parameters
+    ///
+    /// parameters are moved into returned type so it can be constructed from values computed at runtime
+    /// without keeping them alive elsewhere.
+    ///
+    /// # Examples
+    /// ```
+    /// # use xitca_postgres::{types::ToSql, statement::Statement};
+    /// # fn bind_owned(statement: Statement, name: Option<String>) {
+    /// let params: Vec<Box<dyn ToSql + Send + Sync>> = vec![
+    ///     Box::new(9527i32),
+    ///     Box::new(name.unwrap_or_else(|| String::from("nobody"))),
+    /// ];
+    /// let bind = statement.bind_owned(params);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn bind_owned(
+        &self,
+        params: Vec<Box<dyn ToSql + Send + Sync>>,
+    ) -> StatementQuery<'_, Vec<Box<dyn ToSql + Send + Sync>>> {
+        self.bind(params)
+    }
+
+    /// Returns the sql text the statement is prepared from. It's only kept when statement is prepared by
+    /// client with [`Config::query_observer`] set.
+    ///
+    /// [`Config::query_observer`]: crate::Config::query_observer
+    #[inline]
+    pub fn sql(&self) -> Option<&str> {
+        self.sql.as_deref()
+    }
+
     /// Returns the expected types of the statement's parameters.
     #[inline]
     pub fn params(&self) -> &[Type] {
@@ -258,6 +294,44 @@ impl<'a> StatementUnnamed<'a> {
     ) -> StatementUnnamedBind<'a, impl ExactSizeIterator<Item = &'t (dyn ToSql + Sync)> + 'p> {
         self.bind(params.iter().cloned())
     }
+
+    /// function the same as [`Statement::bind_owned`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use xitca_postgres::{types::{ToSql, Type}, Client, Error, Execute, Statement};
+    /// # async fn bind_owned(cli: Client, name: Option<&str>, min_age: Option<i32>) -> Result<(), Error> {
+    /// // build WHERE clause from optional filters where sql, parameter types and values grow together.
+    /// let mut sql = String::from("SELECT id, name, age FROM users WHERE true");
+    /// let mut types = Vec::new();
+    /// let mut params = Vec::<Box<dyn ToSql + Send + Sync>>::new();
+    ///
+    /// if let Some(name) = name {
+    ///     params.push(Box::new(name.to_string()));
+    ///     types.push(Type::TEXT);
+    ///     sql.push_str(&format!(" AND name = ${}", params.len()));
+    /// }
+    ///
+    /// if let Some(min_age) = min_age {
+    ///     params.push(Box::new(min_age));
+    ///     types.push(Type::INT4);
+    ///     sql.push_str(&format!(" AND age >= ${}", params.len()));
+    /// }
+    ///
+    /// let row_stream = Statement::unnamed(&sql, &types)
+    ///     .bind_owned(params)
+    ///     .query(&cli)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn bind_owned(
+        self,
+        params: Vec<Box<dyn ToSql + Send + Sync>>,
+    ) -> StatementUnnamedBind<'a, Vec<Box<dyn ToSql + Send + Sync>>> {
+        self.bind(params)
+    }
 }
 
 /// a named statement with it's query params
@@ -1,6 +1,8 @@
 mod builder;
 mod portal;
 
+use core::time::Duration;
+
 use std::borrow::Cow;
 
 use super::{
@@ -15,7 +17,7 @@ use super::{
     BoxedFuture,
 };
 
-pub use builder::TransactionBuilder;
+pub use builder::{IsolationLevel, TransactionBuilder};
 pub use portal::Portal;
 
 pub struct Transaction<'a, C>
@@ -134,6 +136,23 @@ where
         self._save_point(Some(name.into())).await
     }
 
+    /// Sets the statement timeout for the remaining statements executed within the transaction.
+    ///
+    /// see [`TransactionBuilder::statement_timeout`] for detail.
+    pub async fn set_statement_timeout(&self, timeout: Duration) -> Result<(), Error> {
+        // zero value disables statement timeout. round non zero sub millisecond timeout up to 1 millisecond.
+        let millis = if timeout.is_zero() {
+            0
+        } else {
+            timeout.as_millis().max(1)
+        };
+        format!("SET LOCAL statement_timeout = {millis}")
+            .as_str()
+            .execute(self)
+            .await
+            .map(|_| ())
+    }
+
     /// Consumes the transaction, committing all changes made within it.
     pub async fn commit(mut self) -> Result<(), Error> {
         self.state = State::Finish;
@@ -187,6 +206,11 @@ where
     fn _get_type_blocking(&self, oid: Oid) -> Result<Type, Error> {
         self.client._get_type_blocking(oid)
     }
+
+    #[inline]
+    fn _query_observed(&self) -> bool {
+        self.client._query_observed()
+    }
 }
 
 impl<C> Query for Transaction<'_, C>
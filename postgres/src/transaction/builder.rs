@@ -1,4 +1,13 @@
-use crate::{client::ClientBorrowMut, error::Error, execute::Execute, prepare::Prepare, query::Query};
+use core::time::Duration;
+
+use crate::{
+    client::ClientBorrowMut,
+    error::{DbError, Error, SqlState},
+    execute::Execute,
+    prepare::Prepare,
+    query::Query,
+    BoxedFuture,
+};
 
 use super::Transaction;
 
@@ -38,10 +47,12 @@ impl IsolationLevel {
 }
 
 /// A builder for database transactions.
+#[derive(Clone)]
 pub struct TransactionBuilder {
     isolation_level: Option<IsolationLevel>,
     read_only: Option<bool>,
     deferrable: Option<bool>,
+    statement_timeout: Option<Duration>,
 }
 
 impl TransactionBuilder {
@@ -50,6 +61,7 @@ impl TransactionBuilder {
             isolation_level: None,
             read_only: None,
             deferrable: None,
+            statement_timeout: None,
         }
     }
 
@@ -75,6 +87,25 @@ impl TransactionBuilder {
         self
     }
 
+    /// Sets the statement timeout of the transaction.
+    ///
+    /// `SET LOCAL statement_timeout` is issued after the transaction began and every statement executed within the
+    /// transaction is canceled by database when it runs longer than the timeout. A canceled statement produces
+    /// [`DbError`] error where [`Error::is_statement_timeout`] can be used for detecting it. The transaction is
+    /// aborted after statement canceled where only rollback is allowed afterwards.
+    ///
+    /// The setting is scoped to the transaction and reverted when it's committed or rolled back. nested transaction
+    /// (savepoint) shares the same setting with it's parent transaction.
+    ///
+    /// [`Duration::ZERO`] disables statement timeout as it does in postgres. Non zero timeout shorter than one
+    /// millisecond is rounded up to one millisecond.
+    ///
+    /// [`DbError`]: crate::error::DbError
+    pub fn statement_timeout(mut self, timeout: Duration) -> Self {
+        self.statement_timeout = Some(timeout);
+        self
+    }
+
     /// Begins the transaction.
     ///
     /// The transaction will roll back by default - use the `commit` method to commit it.
@@ -91,6 +122,7 @@ impl TransactionBuilder {
             isolation_level,
             read_only,
             deferrable,
+            statement_timeout,
         } = self;
 
         if let Some(isolation_level) = isolation_level {
@@ -111,6 +143,82 @@ impl TransactionBuilder {
             query.pop();
         }
 
-        query.as_str().execute(cli).await.map(|_| Transaction::new(cli))
+        query.as_str().execute(cli).await?;
+
+        let tx = Transaction::new(cli);
+
+        if let Some(timeout) = statement_timeout {
+            tx.set_statement_timeout(timeout).await?;
+        }
+
+        Ok(tx)
     }
+
+    /// Runs given async function in transaction and commits it when function returns successfully.
+    ///
+    /// When the function or commit fails with serialization failure (`40001`) or deadlock (`40P01`) error the
+    /// transaction is rolled back and a new transaction is started for calling the function again after a backoff
+    /// duration. The backoff starts from 10 milliseconds and doubles on every retry with an upper bound of 1 second.
+    /// Up to `max_attempts` attempts are made(at least one) and error of the last attempt is returned when all of them
+    /// fail. Other errors are returned immediately.
+    ///
+    /// # Idempotency
+    /// The function can be called multiple times. Database side effects of failed attempts are rolled back but side
+    /// effects outside of database(sending messages, mutating captured states, etc) are not. They must be idempotent
+    /// or be deferred until this method returns successfully.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_postgres::{transaction::{IsolationLevel, Transaction}, Client, Error, Execute};
+    /// # async fn retry(mut cli: Client) -> Result<(), Error> {
+    /// Transaction::<Client>::builder()
+    ///     .isolation_level(IsolationLevel::Serializable)
+    ///     .retry(&mut cli, 3, |tx| {
+    ///         Box::pin(async move {
+    ///             "UPDATE accounts SET balance = balance - 10 WHERE id = 1".execute(&*tx).await?;
+    ///             "UPDATE accounts SET balance = balance + 10 WHERE id = 2".execute(&*tx).await?;
+    ///             Ok(())
+    ///         })
+    ///     })
+    ///     .await
+    /// # }
+    /// ```
+    pub async fn retry<C, F, T>(self, cli: &mut C, max_attempts: usize, mut func: F) -> Result<T, Error>
+    where
+        C: Prepare + Query + ClientBorrowMut,
+        F: for<'t, 'c> FnMut(&'t mut Transaction<'c, C>) -> BoxedFuture<'t, Result<T, Error>>,
+    {
+        let mut attempt = 1;
+        let mut backoff = Duration::from_millis(10);
+
+        loop {
+            let mut tx = self.clone().begin(cli).await?;
+
+            let res = match func(&mut tx).await {
+                // failed commit is rolled back by database.
+                Ok(t) => tx.commit().await.map(|_| t),
+                Err(e) => {
+                    if attempt < max_attempts && is_retriable(&e) {
+                        tx.rollback().await?;
+                    }
+                    Err(e)
+                }
+            };
+
+            match res {
+                Err(e) if attempt < max_attempts && is_retriable(&e) => {
+                    tokio::time::sleep(backoff).await;
+                    backoff = core::cmp::min(backoff * 2, Duration::from_secs(1));
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+fn is_retriable(e: &Error) -> bool {
+    e.downcast_ref::<DbError>().is_some_and(|e| {
+        e.code() == &SqlState::T_R_SERIALIZATION_FAILURE || e.code() == &SqlState::T_R_DEADLOCK_DETECTED
+    })
 }
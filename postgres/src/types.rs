@@ -1,6 +1,38 @@
 //! Types.
 //!
 //! This module is a reexport of the `postgres_types` crate.
+//!
+//! # Custom types
+//! user defined postgres types (enum, composite and domain) are looked up from database's catalog when preparing
+//! statement and cached by client afterwards. rust type can be mapped to them with [`ToSql`] and [`FromSql`] traits.
+//!
+//! with `derive` crate feature enabled the traits can be derived. the derived code refers to `postgres_types` path
+//! and it's needed to alias this module to it:
+//! ```ignore
+//! use xitca_postgres::types::{self as postgres_types, FromSql, ToSql};
+//!
+//! // map to postgres enum: CREATE TYPE mood AS ENUM ('sad', 'ok', 'happy')
+//! // variants are matched by label.
+//! #[derive(Debug, ToSql, FromSql)]
+//! #[postgres(name = "mood")]
+//! enum Mood {
+//!     #[postgres(name = "sad")]
+//!     Sad,
+//!     #[postgres(name = "ok")]
+//!     Ok,
+//!     #[postgres(name = "happy")]
+//!     Happy,
+//! }
+//!
+//! // map to postgres composite type: CREATE TYPE worker AS (id INT, wage INT)
+//! // fields are matched by name.
+//! #[derive(Debug, ToSql, FromSql)]
+//! #[postgres(name = "worker")]
+//! struct Worker {
+//!     id: i32,
+//!     wage: i32,
+//! }
+//! ```
 
 #[doc(inline)]
 pub use postgres_types::*;
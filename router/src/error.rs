@@ -19,6 +19,14 @@ pub enum InsertError {
     UnnamedParam,
     /// Catch-all parameters are only allowed at the end of a path.
     InvalidCatchAll,
+    /// Route has more parameters than the max count allowed by [Router]. Regardless of the max count a route can not
+    /// have more than 26 named parameters.
+    ///
+    /// [Router]: crate::Router
+    TooManyRouteParams {
+        /// The max count of parameters allowed.
+        max: usize,
+    },
 }
 
 impl fmt::Display for InsertError {
@@ -34,6 +42,7 @@ impl fmt::Display for InsertError {
             Self::TooManyParams => f.write_str("only one parameter is allowed per path segment"),
             Self::UnnamedParam => f.write_str("parameters must be registered with a name"),
             Self::InvalidCatchAll => f.write_str("catch-all parameters are only allowed at the end of a route"),
+            Self::TooManyRouteParams { max } => write!(f, "route can not have more than {max} parameters"),
         }
     }
 }
@@ -21,6 +21,10 @@ impl Param {
 
 /// A list of parameters returned by a route match.
 ///
+/// Parameters are stored in the order they appear in route path. A route can capture the same key more than once
+/// (e.g. `/:id/posts/:id`) and all the values are kept: [Params::get] returns the first one, [Params::get_all]
+/// returns all of them and [Params::iter] yields every key and value pair in order.
+///
 /// ```rust
 /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// # let mut router = xitca_router::Router::new();
@@ -41,6 +45,18 @@ impl Param {
 #[derive(Clone, Debug)]
 pub struct Params {
     inner: Vec<Param>,
+    remaining: Remaining,
+}
+
+// remaining path matched by catch-all parameter.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Remaining {
+    // route does not end with catch-all parameter.
+    None,
+    // relaxed catch-all matched empty path.
+    Empty,
+    // value of last parameter.
+    Last,
 }
 
 impl Default for Params {
@@ -71,6 +87,28 @@ impl Params {
             .map(Param::value_str)
     }
 
+    /// Returns the values of all parameters registered under the given key in the order they appear in route path.
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let mut router = xitca_router::Router::new();
+    /// router.insert("/:id/posts/:id", true)?;
+    /// let matched = router.at("/1/posts/2")?;
+    ///
+    /// assert_eq!(matched.params.get("id"), Some("1"));
+    /// assert_eq!(matched.params.get_all("id").collect::<Vec<_>>(), ["1", "2"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.inner
+            .iter()
+            .filter(move |param| param.key_str() == key)
+            .map(Param::value_str)
+    }
+
+    /// Returns an iterator over key and value pairs of parameters in the order they appear in route path.
     #[inline]
     pub fn iter(&self) -> Iter<'_> {
         Iter {
@@ -81,7 +119,32 @@ impl Params {
 
 impl Params {
     pub(super) const fn new() -> Self {
-        Self { inner: Vec::new() }
+        Self {
+            inner: Vec::new(),
+            remaining: Remaining::None,
+        }
+    }
+
+    // path matched by catch-all parameter.
+    pub(super) fn remaining(&self) -> Option<&str> {
+        match self.remaining {
+            Remaining::None => None,
+            Remaining::Empty => Some(""),
+            Remaining::Last => self.inner.last().map(Param::value_str),
+        }
+    }
+
+    pub(super) fn set_remaining(&mut self, remaining: Remaining) {
+        self.remaining = remaining;
+    }
+
+    // merge params of sub match into self. catch-all parameter of self is removed as it's consumed by sub match.
+    pub(super) fn merge(&mut self, other: Self) {
+        if let Remaining::Last = self.remaining {
+            self.inner.pop();
+        }
+        self.inner.extend(other.inner);
+        self.remaining = other.remaining;
     }
 
     pub(super) fn truncate(&mut self, n: usize) {
@@ -117,6 +180,17 @@ impl IntoIterator for Params {
     }
 }
 
+impl<'a> IntoIterator for &'a Params {
+    type Item = (&'a str, &'a str);
+    type IntoIter = Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct Iter<'a> {
     inner: slice::Iter<'a, Param>,
 }
@@ -135,6 +209,9 @@ impl<'a> Iterator for Iter<'a> {
     }
 }
 
+impl ExactSizeIterator for Iter<'_> {}
+
+#[derive(Debug)]
 pub struct IntoIter {
     inner: std::vec::IntoIter<Param>,
 }
@@ -153,6 +230,8 @@ impl Iterator for IntoIter {
     }
 }
 
+impl ExactSizeIterator for IntoIter {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,10 +2,14 @@ use super::{params::Params, tree::Node, InsertError, MatchError};
 
 /// A URL router.
 ///
+/// Const generic `MAX_PARAMS` is the max count of parameters(named and catch-all) a route can have. Inserting a route
+/// exceeding it results in [InsertError::TooManyRouteParams]. It's unbounded by default and [Router::with_max_params]
+/// can be used to construct a router with bounded count.
+///
 /// See [the crate documentation](crate) for details.
 #[derive(Clone)]
 #[cfg_attr(test, derive(Debug))]
-pub struct Router<T> {
+pub struct Router<T, const MAX_PARAMS: usize = { usize::MAX }> {
     root: Node<T>,
 }
 
@@ -14,6 +18,28 @@ impl<T> Router<T> {
     pub const fn new() -> Self {
         Self { root: Node::new() }
     }
+}
+
+impl<T, const MAX_PARAMS: usize> Router<T, MAX_PARAMS> {
+    /// Construct a new router where route can have up to `MAX_PARAMS` count of parameters.
+    ///
+    /// Captured parameters are stored in [Params] with one heap allocation that grows with parameter count. Each key
+    /// and value of parameter is stored inline when it's short. Bounding parameter count makes sure a pathological
+    /// route can not cause excessive allocation on every match.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xitca_router::{InsertError, Router};
+    /// let mut router = Router::<_, 2>::with_max_params();
+    /// router.insert("/users/:id/posts/:post", true).unwrap();
+    ///
+    /// let err = router.insert("/:org/:repo/issues/:id", true).unwrap_err();
+    /// assert_eq!(err, InsertError::TooManyRouteParams { max: 2 });
+    /// ```
+    pub const fn with_max_params() -> Self {
+        Self { root: Node::new() }
+    }
 
     /// Insert a route.
     ///
@@ -29,7 +55,7 @@ impl<T> Router<T> {
     /// # }
     /// ```
     pub fn insert(&mut self, route: impl Into<String>, value: T) -> Result<(), InsertError> {
-        self.root.insert(route, value)
+        self.root.insert(route, value, MAX_PARAMS)
     }
 
     /// Tries to find a value in the router matching the given path.
@@ -67,3 +93,81 @@ pub struct Match<V> {
     /// The route parameters. See [parameters](crate#parameters) for more details.
     pub params: Params,
 }
+
+impl<V> Match<V> {
+    /// Returns the remaining path matched by catch-all parameter at the end of route. The value is the same as
+    /// catch-all parameter which does not include it's leading `/`.
+    ///
+    /// Returns `None` when route does not end with catch-all parameter.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xitca_router::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut router = Router::new();
+    /// router.insert("/api/*rest", true)?;
+    /// router.insert("/users/*", true)?;
+    /// router.insert("/home", true)?;
+    ///
+    /// assert_eq!(router.at("/api/users/1")?.remaining(), Some("users/1"));
+    /// assert_eq!(router.at("/users/")?.remaining(), Some(""));
+    /// assert_eq!(router.at("/home")?.remaining(), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn remaining(&self) -> Option<&str> {
+        self.params.remaining()
+    }
+
+    /// Match the remaining path of catch-all parameter against given [Router]. The remaining path is matched as
+    /// relative path with leading `/`. (empty remaining path is matched as `/`)
+    ///
+    /// Params of returned match starts with params of self and followed by params of sub match. The catch-all
+    /// parameter of self is not included as it's consumed by sub match. When parent and sub match share the same
+    /// parameter key [Params::get] returns value of parent and [Params::iter] yields both.
+    ///
+    /// Returns [MatchError] when self does not end with catch-all parameter or sub match failed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use xitca_router::Router;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut users = Router::new();
+    /// users.insert("/", "list")?;
+    /// users.insert("/:id", "user")?;
+    ///
+    /// let mut router = Router::new();
+    /// router.insert("/:version/users/*rest", users)?;
+    ///
+    /// let matched = router.at("/v1/users/996")?;
+    /// let users = matched.value;
+    /// let matched = matched.at_remaining(users)?;
+    ///
+    /// assert_eq!(*matched.value, "user");
+    /// assert_eq!(matched.params.get("version"), Some("v1"));
+    /// assert_eq!(matched.params.get("id"), Some("996"));
+    /// assert_eq!(matched.params.get("rest"), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn at_remaining<T, const MAX_PARAMS: usize>(
+        self,
+        router: &Router<T, MAX_PARAMS>,
+    ) -> Result<Match<&T>, MatchError> {
+        let remaining = self.remaining().ok_or(MatchError)?;
+
+        let mut path = String::with_capacity(remaining.len() + 1);
+        path.push('/');
+        path.push_str(remaining);
+
+        let Match { value, params } = router.at(&path)?;
+
+        let mut merged = self.params;
+        merged.merge(params);
+
+        Ok(Match { value, params: merged })
+    }
+}
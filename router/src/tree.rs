@@ -1,6 +1,9 @@
 use core::{cmp::min, mem, str::from_utf8};
 
-use super::{params::Params, InsertError, MatchError};
+use super::{
+    params::{Params, Remaining},
+    InsertError, MatchError,
+};
 
 /// The types of nodes the tree can hold
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone)]
@@ -50,9 +53,9 @@ impl<T> Node<T> {
         }
     }
 
-    pub fn insert(&mut self, route: impl Into<String>, val: T) -> Result<(), InsertError> {
+    pub fn insert(&mut self, route: impl Into<String>, val: T, max_params: usize) -> Result<(), InsertError> {
         let route = route.into().into_bytes();
-        let (route, param_remapping) = normalize_params(route)?;
+        let (route, param_remapping) = normalize_params(route, max_params)?;
         let mut prefix = route.as_ref();
 
         self.priority += 1;
@@ -427,6 +430,7 @@ impl<T> Node<T> {
 
                                 // store the final catch-all parameter
                                 params.push(&current.prefix[1..], path);
+                                params.set_remaining(Remaining::Last);
 
                                 return Ok((value, params));
                             }
@@ -457,6 +461,7 @@ impl<T> Node<T> {
             if let Some(val) = current.children.first() {
                 if val.prefix == "*" {
                     if let Some(ref val) = val.value {
+                        params.set_remaining(Remaining::Empty);
                         return Ok((val, params));
                     }
                 }
@@ -488,11 +493,15 @@ impl<T> Node<T> {
 /// An ordered list of route parameters keys for a specific route, stored at leaf nodes.
 type ParamRemapping = Vec<Box<str>>;
 
+/// Max count of named parameters of a route. Named parameters are normalized to single alphabetic character.
+const MAX_NAMED_PARAMS: usize = 26;
+
 /// Returns `path` with normalized route parameters, and a parameter remapping
 /// to store at the leaf node for this route.
-fn normalize_params(mut path: Vec<u8>) -> Result<(Vec<u8>, ParamRemapping), InsertError> {
+fn normalize_params(mut path: Vec<u8>, max_params: usize) -> Result<(Vec<u8>, ParamRemapping), InsertError> {
     let mut start = 0;
     let mut original = ParamRemapping::new();
+    let mut count = 0;
 
     // parameter names are normalized alphabetically
     let mut next = b'a';
@@ -508,12 +517,21 @@ fn normalize_params(mut path: Vec<u8>) -> Result<(Vec<u8>, ParamRemapping), Inse
             return Err(InsertError::UnnamedParam);
         }
 
+        count += 1;
+        if count > max_params {
+            return Err(InsertError::TooManyRouteParams { max: max_params });
+        }
+
         // don't need to normalize catch-all parameters
         if wildcard[0] == b'*' {
             start += wildcard_index + wildcard.len();
             continue;
         }
 
+        if next > b'z' {
+            return Err(InsertError::TooManyRouteParams { max: MAX_NAMED_PARAMS });
+        }
+
         wildcard_index += start;
 
         // normalize the parameter
@@ -525,9 +543,6 @@ fn normalize_params(mut path: Vec<u8>) -> Result<(Vec<u8>, ParamRemapping), Inse
 
         // get the next key
         next += 1;
-        if next > b'z' {
-            panic!("too many route parameters");
-        }
 
         start = wildcard_index + 2;
     }
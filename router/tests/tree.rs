@@ -35,6 +35,122 @@ fn issue_31() {
     assert_eq!(router.at("/path/foo/myarg/bar/baz").map(|m| *m.value), Ok("wildcard"));
 }
 
+#[test]
+fn max_params() {
+    let mut router = Router::<_, 3>::with_max_params();
+    router.insert("/:org/:repo/issues/:id", 1).unwrap();
+    router.insert("/:org/:repo/*path", 2).unwrap();
+    assert_eq!(
+        router.insert("/:org/:repo/pulls/:id/*path", 3),
+        Err(InsertError::TooManyRouteParams { max: 3 })
+    );
+    assert_eq!(
+        router.insert("/:org/:repo/:branch/:dir/*", 4),
+        Err(InsertError::TooManyRouteParams { max: 3 })
+    );
+    assert_eq!(router.at("/xitca/web/issues/1").map(|m| *m.value), Ok(1));
+    assert_eq!(router.at("/xitca/web/pulls/1/files").map(|m| *m.value), Ok(2));
+
+    // remaining path can be matched against router with max params.
+    let mut parent = Router::new();
+    parent.insert("/repos/*rest", &router).unwrap();
+    let m = parent.at("/repos/xitca/web/issues/1").unwrap();
+    let sub = *m.value;
+    let m = m.at_remaining(sub).unwrap();
+    assert_eq!(*m.value, 1);
+    assert_eq!(m.params.get("id"), Some("1"));
+
+    let route = |count: u8| (0..count).map(|i| format!("/:p{i}")).collect::<String>();
+
+    // named parameters are bounded to 26 regardless of max params.
+    let mut router = Router::new();
+    router.insert(route(26), 1).unwrap();
+    assert_eq!(
+        router.insert(format!("/static{}", route(27)), 2),
+        Err(InsertError::TooManyRouteParams { max: 26 })
+    );
+
+    let path = (0..26).map(|i| format!("/{i}")).collect::<String>();
+    let m = router.at(&path).unwrap();
+    assert_eq!(m.params.len(), 26);
+    assert_eq!(m.params.get("p25"), Some("25"));
+}
+
+#[test]
+fn params_order() {
+    let mut router = Router::new();
+    router.insert("/:org/:repo/issues/:id", 1).unwrap();
+    router.insert("/:id/posts/:id/*id", 2).unwrap();
+
+    let m = router.at("/xitca/web/issues/1").unwrap();
+    assert_eq!(m.params.len(), 3);
+    assert_eq!(
+        m.params.iter().collect::<Vec<_>>(),
+        [("org", "xitca"), ("repo", "web"), ("id", "1")]
+    );
+    assert_eq!((&m.params).into_iter().len(), 3);
+
+    let m = router.at("/1/posts/2/3/4").unwrap();
+    assert_eq!(m.params.len(), 3);
+    assert_eq!(m.params.get("id"), Some("1"));
+    assert_eq!(m.params.get_all("id").collect::<Vec<_>>(), ["1", "2", "3/4"]);
+    assert_eq!(m.params.get_all("org").count(), 0);
+    assert_eq!(
+        m.params.into_iter().collect::<Vec<_>>(),
+        [
+            (SmallBoxedStr::from("id"), SmallBoxedStr::from("1")),
+            (SmallBoxedStr::from("id"), SmallBoxedStr::from("2")),
+            (SmallBoxedStr::from("id"), SmallBoxedStr::from("3/4"))
+        ]
+    );
+}
+
+#[test]
+fn at_remaining() {
+    let mut posts = Router::new();
+    posts.insert("/", "posts").unwrap();
+    posts.insert("/:id", "post").unwrap();
+
+    let mut users = Router::new();
+    users.insert("/", "users").unwrap();
+    users.insert("/:id", "user").unwrap();
+    users.insert("/:id/posts/*", "").unwrap();
+
+    let mut router = Router::new();
+    router.insert("/api/:version/users/*rest", &users).unwrap();
+    router.insert("/users/*", &users).unwrap();
+    router.insert("/home", &users).unwrap();
+
+    let m = router.at("/api/v1/users/7").unwrap();
+    assert_eq!(m.remaining(), Some("7"));
+    let users = *m.value;
+    let m = m.at_remaining(users).unwrap();
+    assert_eq!(*m.value, "user");
+    assert_eq!(m.remaining(), None);
+    assert_eq!(m.params.iter().collect::<Vec<_>>(), [("version", "v1"), ("id", "7")]);
+
+    let m = router.at("/users/").unwrap();
+    assert_eq!(m.remaining(), Some(""));
+    assert_eq!(*m.at_remaining(users).unwrap().value, "users");
+
+    let m = router.at("/users/7/posts/8").unwrap();
+    assert_eq!(m.remaining(), Some("7/posts/8"));
+    let m = m.at_remaining(users).unwrap();
+    assert_eq!(m.remaining(), Some("8"));
+    let m = m.at_remaining(&posts).unwrap();
+    assert_eq!(*m.value, "post");
+    assert_eq!(m.params.iter().collect::<Vec<_>>(), [("id", "7"), ("id", "8")]);
+    // parent param takes precedence on key conflict.
+    assert_eq!(m.params.get("id"), Some("7"));
+
+    let m = router.at("/api/v1/users/7/unknown").unwrap();
+    assert_eq!(m.at_remaining(users).unwrap_err(), MatchError);
+
+    let m = router.at("/home").unwrap();
+    assert_eq!(m.remaining(), None);
+    assert_eq!(m.at_remaining(users).unwrap_err(), MatchError);
+}
+
 match_tests! {
     basic {
         routes = [
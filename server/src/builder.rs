@@ -1,4 +1,10 @@
-use std::{collections::HashMap, future::Future, pin::Pin, time::Duration};
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{atomic::AtomicBool, Arc},
+    time::Duration,
+};
 
 #[cfg(not(target_family = "wasm"))]
 use std::{io, net};
@@ -7,18 +13,39 @@ use xitca_io::net::Stream;
 
 use crate::{
     net::AsListener,
-    server::{IntoServiceObj, Server, ServerFuture, ServiceObj},
+    server::{IntoServiceObj, Server, ServerFuture, ServiceObj, ShutdownController},
 };
 
+/// threading model of server. See [Builder::runtime] for detail.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RuntimeModel {
+    /// every worker accepts connections from listeners by itself. (default)
+    #[default]
+    PerWorker,
+    /// connections are accepted on the server's shared multi-threaded runtime and dispatched to the
+    /// worker with least active connections.
+    SharedMultiThread,
+}
+
 pub struct Builder {
     pub(crate) server_threads: usize,
     pub(crate) worker_threads: usize,
+    pub(crate) runtime: RuntimeModel,
     pub(crate) worker_max_blocking_threads: usize,
     pub(crate) listeners: HashMap<String, Vec<Box<dyn AsListener>>>,
     pub(crate) factories: HashMap<String, ServiceObj>,
     pub(crate) enable_signal: bool,
     pub(crate) shutdown_timeout: Duration,
     pub(crate) on_worker_start: Box<dyn Fn() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>,
+    pub(crate) draining: Arc<AtomicBool>,
+    pub(crate) shutdown_controller: Option<ShutdownController>,
+    pub(crate) proxy_protocol: bool,
+    pub(crate) tcp_nodelay: bool,
+    pub(crate) tcp_keepalive: Option<Duration>,
+    recv_buffer_size: Option<usize>,
+    send_buffer_size: Option<usize>,
+    #[cfg(feature = "io-uring")]
+    pub(crate) io_uring_fallback: bool,
     backlog: u32,
 }
 
@@ -34,12 +61,22 @@ impl Builder {
         Self {
             server_threads: 1,
             worker_threads: std::thread::available_parallelism().map(|size| size.get()).unwrap_or(1),
+            runtime: RuntimeModel::PerWorker,
             worker_max_blocking_threads: 512,
             listeners: HashMap::new(),
             factories: HashMap::new(),
             enable_signal: true,
             shutdown_timeout: Duration::from_secs(30),
             on_worker_start: Box::new(|| Box::pin(async {})),
+            draining: Arc::new(AtomicBool::new(false)),
+            shutdown_controller: None,
+            proxy_protocol: false,
+            tcp_nodelay: false,
+            tcp_keepalive: None,
+            recv_buffer_size: None,
+            send_buffer_size: None,
+            #[cfg(feature = "io-uring")]
+            io_uring_fallback: false,
             backlog: 2048,
         }
     }
@@ -60,6 +97,17 @@ impl Builder {
     ///
     /// Default set to available logical cpu as workers count.
     ///
+    /// # Threading model
+    /// Every worker runs on its own thread with a single threaded async runtime (thread-per-core
+    /// style). Services are constructed once per worker and connections handled by a worker stay on
+    /// the same thread until they are closed. This keeps service state and connection buffers local to
+    /// one cpu core and removes the need of synchronization.
+    ///
+    /// How connections are distributed among workers is configured with [Builder::runtime].
+    ///
+    /// Blocking or cpu heavy work should be offloaded with [tokio::task::spawn_blocking] or a
+    /// dedicated thread pool to avoid stalling a worker.
+    ///
     /// # Panics:
     /// When received 0 as number of worker thread.
     pub fn worker_threads(mut self, num: usize) -> Self {
@@ -69,6 +117,34 @@ impl Builder {
         self
     }
 
+    /// Set threading model of server. Default to [RuntimeModel::PerWorker].
+    ///
+    /// # Tradeoffs
+    /// - [RuntimeModel::PerWorker]: workers compete on accepting connections from the same listeners.
+    ///   Accepting happens on the worker thread so a connection never crosses threads and cache
+    ///   locality is best. A busy worker naturally accepts less new connections but the balance is
+    ///   loose: bursts of connections can land on a few workers.
+    /// - [RuntimeModel::SharedMultiThread]: tcp and unix listeners are accepted on the server's shared
+    ///   multi-threaded runtime (sized by [Builder::server_threads]) and every connection is
+    ///   dispatched to the worker with least active connections. Load is balanced more evenly at the
+    ///   cost of an extra thread hop for every new connection. Udp based listeners are still accepted
+    ///   by workers.
+    ///
+    /// In both models a connection is served by one worker for its whole lifetime. Services are not
+    /// required to be thread safe (`Send`) so requests can not be stolen by other workers. Long lived
+    /// connections with uneven amount of work can still leave some workers busier than others.
+    /// Lowering keep-alive duration of http services helps redistributing such connections.
+    ///
+    /// # Examples
+    /// ```
+    /// # use xitca_server::{Builder, RuntimeModel};
+    /// let builder = Builder::new().runtime(RuntimeModel::SharedMultiThread);
+    /// ```
+    pub fn runtime(mut self, model: RuntimeModel) -> Self {
+        self.runtime = model;
+        self
+    }
+
     /// Set max number of threads for each worker's blocking task thread pool.
     ///
     /// One thread pool is set up **per worker**; not shared across workers.
@@ -107,11 +183,99 @@ impl Builder {
         self
     }
 
+    /// Set the maximum number of pending connections of listeners created by bind methods.
+    ///
+    /// Default set to 2048.
     pub fn backlog(mut self, num: u32) -> Self {
         self.backlog = num;
         self
     }
 
+    /// Set `TCP_NODELAY` option on every accepted Tcp connection. Disabling Nagle's algorithm reduces
+    /// latency of small writes.
+    ///
+    /// Default set to false.
+    pub fn tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp_nodelay = nodelay;
+        self
+    }
+
+    /// Enable `SO_KEEPALIVE` option with given idle time before probing on every accepted Tcp connection.
+    /// `None` leaves the option untouched.
+    ///
+    /// Default set to `None`.
+    pub fn tcp_keepalive(mut self, time: Option<Duration>) -> Self {
+        self.tcp_keepalive = time;
+        self
+    }
+
+    /// Set `SO_RCVBUF` option on sockets created by bind methods. Accepted Tcp connections inherit the
+    /// option from listener.
+    ///
+    /// Default to OS setting.
+    pub fn recv_buffer_size(mut self, size: usize) -> Self {
+        self.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Set `SO_SNDBUF` option on sockets created by bind methods. Accepted Tcp connections inherit the
+    /// option from listener.
+    ///
+    /// Default to OS setting.
+    pub fn send_buffer_size(mut self, size: usize) -> Self {
+        self.send_buffer_size = Some(size);
+        self
+    }
+
+    /// Expect PROXY protocol (v1 or v2) header at the start of every accepted Tcp connection.
+    ///
+    /// When enabled the header is read and removed from connection before passing it to service.
+    /// The client address carried by header replaces the peer address of connection so downstream
+    /// services observe the real client behind L4 proxy. (AWS NLB or HAProxy for example)
+    ///
+    /// Connection with missing or malformed header is rejected. Header not received in 5 seconds is
+    /// treated as malformed.
+    ///
+    /// Default to false.
+    pub fn proxy_protocol(mut self, enable: bool) -> Self {
+        self.proxy_protocol = enable;
+        self
+    }
+
+    #[cfg(feature = "io-uring")]
+    /// Fallback to tokio runtime for worker threads when io_uring is not available at runtime.
+    /// (old kernel or sandbox blocking io_uring syscalls for example)
+    ///
+    /// Without fallback server would fail to start with error when io_uring is not available.
+    ///
+    /// # Note:
+    /// Fallback is only transparent for services operating on tokio io types. Services requiring
+    /// io_uring runtime (io_uring flavor of http service or io_uring file serving for example)
+    /// would still fail when the fallback happens.
+    pub fn io_uring_with_fallback(mut self) -> Self {
+        self.io_uring_fallback = true;
+        self
+    }
+
+    /// Get a shared reference of server's draining state.
+    ///
+    /// The state would be set to true when [ServerHandle::start_draining](crate::ServerHandle::start_draining)
+    /// is called. Services can observe it and stop keeping connections alive while in flight requests are
+    /// finished normally.
+    pub fn draining_state(&self) -> Arc<AtomicBool> {
+        self.draining.clone()
+    }
+
+    /// Register server with given [ShutdownController] so it can be stopped together with other servers
+    /// registered with the same controller.
+    ///
+    /// Registered server can still be stopped individually through OS signal or
+    /// [ServerHandle](crate::ServerHandle).
+    pub fn shutdown_controller(mut self, controller: &ShutdownController) -> Self {
+        self.shutdown_controller = Some(controller.clone());
+        self
+    }
+
     #[doc(hidden)]
     /// Async callback called when worker thread is spawned.
     ///
@@ -185,10 +349,79 @@ impl Builder {
 
         let socket = socket2::SockRef::from(&listener);
         socket.set_reuse_address(true)?;
+        self.apply_buffer_size(&socket)?;
         socket.listen(self.backlog as _)?;
 
         Ok(self.listen(name, listener, service))
     }
+
+    /// Bind to udp socket of given address for handling connectionless datagram.
+    ///
+    /// Every received datagram is passed to service as [Datagram](xitca_io::net::Datagram) type which
+    /// can be used to reply to it's peer. The socket is shared by all worker threads and they compete
+    /// on receiving datagrams from it. On unix system the socket is bound with `SO_REUSEPORT` so
+    /// multiple server instances can bind to the same address and kernel would distribute datagrams
+    /// between them.
+    ///
+    /// # Graceful shutdown
+    /// Connectionless socket has no connection to be drained. On graceful shutdown worker threads stop
+    /// receiving datagrams from socket and in flight service calls are given
+    /// [Builder::shutdown_timeout] to finish. Datagrams queued in socket buffer afterwards are dropped.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::convert::Infallible;
+    ///
+    /// use xitca_io::net::Datagram;
+    /// use xitca_server::Builder;
+    /// use xitca_service::fn_service;
+    ///
+    /// // an echo server reply received datagram to it's peer.
+    /// async fn echo(datagram: Datagram) -> Result<(), Infallible> {
+    ///     let _ = datagram.reply(datagram.data()).await;
+    ///     Ok(())
+    /// }
+    ///
+    /// # fn _main() -> std::io::Result<()> {
+    /// let server = Builder::new().bind_udp("echo", "127.0.0.1:8053", fn_service(echo))?.build();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bind_udp<N, A, F, St>(self, name: N, addr: A, service: F) -> io::Result<Self>
+    where
+        N: AsRef<str>,
+        A: net::ToSocketAddrs,
+        F: IntoServiceObj<St>,
+        St: TryFrom<Stream> + 'static,
+    {
+        let addr = addr
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::AddrNotAvailable, "Can not parse SocketAddr"))?;
+
+        let socket = socket2::Socket::new(
+            socket2::Domain::for_address(addr),
+            socket2::Type::DGRAM,
+            Some(socket2::Protocol::UDP),
+        )?;
+        socket.set_reuse_address(true)?;
+        #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+        socket.set_reuse_port(true)?;
+        self.apply_buffer_size(&socket)?;
+        socket.bind(&addr.into())?;
+
+        Ok(self.listen(name, net::UdpSocket::from(socket), service))
+    }
+
+    fn apply_buffer_size(&self, socket: &socket2::Socket) -> io::Result<()> {
+        if let Some(size) = self.recv_buffer_size {
+            socket.set_recv_buffer_size(size)?;
+        }
+        if let Some(size) = self.send_buffer_size {
+            socket.set_send_buffer_size(size)?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(unix)]
@@ -7,10 +7,11 @@ mod server;
 mod signals;
 mod worker;
 
+pub mod metrics;
 pub mod net;
 
-pub use builder::Builder;
-pub use server::{ServerFuture, ServerHandle};
+pub use builder::{Builder, RuntimeModel};
+pub use server::{ServerFuture, ServerHandle, ShutdownController};
 
 #[cfg(all(not(target_os = "linux"), feature = "io-uring"))]
 compile_error!("io_uring can only be used on linux system");
@@ -27,4 +28,305 @@ mod test {
             .listen("test", listener, fn_service(|_: TcpStream| async { Ok::<_, ()>(()) }))
             .build();
     }
+
+    #[tokio::test]
+    async fn udp_echo() {
+        use std::{net::UdpSocket, time::Duration};
+
+        use xitca_io::net::Datagram;
+
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+
+        let mut server = crate::builder::Builder::new()
+            .worker_threads(1)
+            .disable_signal()
+            .listen(
+                "udp",
+                socket,
+                fn_service(|datagram: Datagram| async move { datagram.reply(datagram.data()).await.map(|_| ()) }),
+            )
+            .build();
+
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client.set_read_timeout(Some(Duration::from_secs(3))).unwrap();
+
+        for msg in [&b"996"[..], b"251"] {
+            client.send_to(msg, addr).unwrap();
+            let mut buf = [0; 8];
+            let (n, peer) = client.recv_from(&mut buf).unwrap();
+            assert_eq!(peer, addr);
+            assert_eq!(&buf[..n], msg);
+        }
+
+        server.handle().unwrap().stop(true);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn proxy_protocol() {
+        use std::{
+            io::{Read, Write},
+            net::{SocketAddr, TcpListener},
+            sync::mpsc,
+            time::Duration,
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (tx, rx) = mpsc::channel();
+
+        let mut server = crate::builder::Builder::new()
+            .worker_threads(1)
+            .disable_signal()
+            .proxy_protocol(true)
+            .listen(
+                "proxy",
+                listener,
+                fn_service(move |(_, addr): (TcpStream, SocketAddr)| {
+                    let tx = tx.clone();
+                    async move { tx.send(addr).map_err(|_| ()) }
+                }),
+            )
+            .build();
+
+        let mut v2 = b"\r\n\r\n\0\r\nQUIT\n\x21\x11\x00\x0c".to_vec();
+        v2.extend_from_slice(&[10, 0, 0, 2, 127, 0, 0, 1, 0x1f, 0x90, 0x00, 0x50]);
+
+        for (header, expect) in [
+            (&b"PROXY TCP4 10.0.0.1 127.0.0.1 996 80\r\n"[..], "10.0.0.1:996"),
+            (&v2, "10.0.0.2:8080"),
+        ] {
+            let mut stream = std::net::TcpStream::connect(addr).unwrap();
+            stream.write_all(header).unwrap();
+            let peer = rx.recv_timeout(Duration::from_secs(3)).unwrap();
+            assert_eq!(peer, expect.parse().unwrap());
+        }
+
+        // malformed header must be rejected without reaching service.
+        let mut stream = std::net::TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(3))).unwrap();
+        stream.write_all(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+        // unread bytes in socket buffer may cause connection reset instead of graceful close.
+        match stream.read(&mut [0; 1]) {
+            Ok(n) => assert_eq!(n, 0),
+            Err(e) => assert_eq!(e.kind(), std::io::ErrorKind::ConnectionReset),
+        }
+        assert!(rx.try_recv().is_err());
+
+        server.handle().unwrap().stop(true);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn tcp_socket_option() {
+        use std::{
+            net::{SocketAddr, TcpListener},
+            sync::mpsc,
+            time::Duration,
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (tx, rx) = mpsc::channel();
+
+        let mut server = crate::builder::Builder::new()
+            .worker_threads(1)
+            .disable_signal()
+            .tcp_nodelay(true)
+            .tcp_keepalive(Some(Duration::from_secs(60)))
+            .listen(
+                "tcp",
+                listener,
+                fn_service(move |(stream, _): (TcpStream, SocketAddr)| {
+                    let tx = tx.clone();
+                    async move {
+                        let stream = stream.into_std().unwrap();
+                        let socket = socket2::SockRef::from(&stream);
+                        let res = (stream.nodelay().unwrap(), socket.keepalive().unwrap());
+                        tx.send(res).map_err(|_| ())
+                    }
+                }),
+            )
+            .build();
+
+        let _stream = std::net::TcpStream::connect(addr).unwrap();
+        let (nodelay, keepalive) = rx.recv_timeout(Duration::from_secs(3)).unwrap();
+        assert!(nodelay);
+        assert!(keepalive);
+
+        server.handle().unwrap().stop(true);
+        server.await.unwrap();
+    }
+
+    #[cfg(feature = "io-uring-multishot")]
+    #[tokio::test]
+    async fn io_uring_multishot_accept() {
+        use std::{
+            net::{SocketAddr, TcpListener},
+            sync::mpsc,
+            time::Duration,
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (tx, rx) = mpsc::channel();
+
+        let mut server = crate::builder::Builder::new()
+            .worker_threads(1)
+            .disable_signal()
+            .listen(
+                "tcp",
+                listener,
+                fn_service(move |(_, addr): (TcpStream, SocketAddr)| {
+                    let tx = tx.clone();
+                    async move { tx.send(addr).map_err(|_| ()) }
+                }),
+            )
+            .build();
+
+        for _ in 0..3 {
+            let stream = std::net::TcpStream::connect(addr).unwrap();
+            let peer = rx.recv_timeout(Duration::from_secs(3)).unwrap();
+            assert_eq!(peer, stream.local_addr().unwrap());
+        }
+
+        server.handle().unwrap().stop(true);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn shutdown_controller() {
+        use std::net::TcpListener;
+
+        use crate::ShutdownController;
+
+        let controller = ShutdownController::new();
+
+        let server = || {
+            crate::builder::Builder::new()
+                .worker_threads(1)
+                .disable_signal()
+                .shutdown_controller(&controller)
+                .listen(
+                    "tcp",
+                    TcpListener::bind("127.0.0.1:0").unwrap(),
+                    fn_service(|_: TcpStream| async { Ok::<_, ()>(()) }),
+                )
+                .build()
+        };
+
+        let public = tokio::spawn(server());
+        let admin = tokio::spawn(server());
+
+        controller.shutdown_graceful().await;
+
+        public.await.unwrap().unwrap();
+        admin.await.unwrap().unwrap();
+
+        // server registered after shutdown is stopped right away.
+        server().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn metrics() {
+        use std::{net::TcpListener, sync::mpsc, time::Duration};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (tx, rx) = mpsc::channel();
+
+        let mut server = crate::builder::Builder::new()
+            .worker_threads(2)
+            .disable_signal()
+            .listen(
+                "metrics",
+                listener,
+                fn_service(move |_: TcpStream| {
+                    let tx = tx.clone();
+                    async move { tx.send(()).map_err(|_| ()) }
+                }),
+            )
+            .build();
+
+        let handle = server.handle().unwrap();
+
+        for _ in 0..2 {
+            let _stream = std::net::TcpStream::connect(addr).unwrap();
+            rx.recv_timeout(Duration::from_secs(3)).unwrap();
+        }
+
+        // connection stays active until it's task is finished.
+        let mut total = handle.metrics().total();
+        for _ in 0..30 {
+            if total.active == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            total = handle.metrics().total();
+        }
+
+        assert_eq!(handle.metrics().workers().len(), 2);
+        assert_eq!(total, crate::metrics::WorkerMetrics { accepted: 2, active: 0 });
+
+        handle.stop(true);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn shared_runtime_dispatch() {
+        use std::{net::TcpListener, sync::mpsc, time::Duration};
+
+        use crate::RuntimeModel;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (tx, rx) = mpsc::channel();
+
+        let mut server = crate::builder::Builder::new()
+            .worker_threads(2)
+            .runtime(RuntimeModel::SharedMultiThread)
+            .disable_signal()
+            .listen(
+                "shared",
+                listener,
+                fn_service(move |_: TcpStream| {
+                    let tx = tx.clone();
+                    async move {
+                        tx.send(std::thread::current().name().map(String::from))
+                            .map_err(|_| ())?;
+                        // hold connection so it stays active.
+                        tokio::time::sleep(Duration::from_secs(3600)).await;
+                        Ok::<_, ()>(())
+                    }
+                }),
+            )
+            .build();
+
+        let handle = server.handle().unwrap();
+
+        let mut streams = Vec::new();
+        let mut names = Vec::new();
+        for _ in 0..4 {
+            streams.push(std::net::TcpStream::connect(addr).unwrap());
+            names.push(rx.recv_timeout(Duration::from_secs(3)).unwrap().unwrap());
+        }
+
+        // connections are dispatched to worker with least active connections.
+        let workers = handle.metrics().workers().to_vec();
+        assert_eq!(workers.len(), 2);
+        assert!(workers.iter().all(|w| w.active == 2 && w.accepted == 2));
+
+        names.sort();
+        names.dedup();
+        assert_eq!(names, ["xitca-server-worker-0", "xitca-server-worker-1"]);
+
+        handle.stop(false);
+        server.await.unwrap();
+    }
 }
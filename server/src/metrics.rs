@@ -0,0 +1,113 @@
+//! live metrics of server and it's worker threads.
+//!
+//! Only connection counters are provided. Accepted streams are handed to services as raw sockets and
+//! protocols are implemented outside of server so bytes transferred and requests served are not observed
+//! by it. They should be recorded by service (or it's middleware) when needed.
+
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use std::sync::Arc;
+
+/// atomic counters shared between worker thread and [ServerHandle](crate::ServerHandle).
+#[derive(Default)]
+pub(crate) struct Counters {
+    accepted: AtomicU64,
+    active: AtomicU64,
+}
+
+impl Counters {
+    pub(crate) fn active(&self) -> u64 {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    fn snapshot(&self) -> WorkerMetrics {
+        WorkerMetrics {
+            accepted: self.accepted.load(Ordering::Relaxed),
+            active: self.active.load(Ordering::Relaxed),
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<MetricsRecorder>> = const { RefCell::new(None) };
+}
+
+// recorder of metrics for the worker thread it's obtained from.
+#[derive(Clone)]
+pub(crate) struct MetricsRecorder(Arc<Counters>);
+
+impl MetricsRecorder {
+    // obtain recorder of current worker thread. return None when not called from server worker thread.
+    pub(crate) fn current() -> Option<Self> {
+        CURRENT.with(|c| c.borrow().clone())
+    }
+
+    pub(crate) fn set_current(counters: Arc<Counters>) {
+        CURRENT.with(|c| *c.borrow_mut() = Some(Self(counters)));
+    }
+
+    // track an accepted connection. connection is counted as active until the guard is dropped.
+    pub(crate) fn connection(&self) -> ConnectionGuard {
+        self.0.accepted.fetch_add(1, Ordering::Relaxed);
+        self.0.active.fetch_add(1, Ordering::Relaxed);
+        ConnectionGuard(self.0.clone())
+    }
+}
+
+impl From<Arc<Counters>> for MetricsRecorder {
+    fn from(counters: Arc<Counters>) -> Self {
+        Self(counters)
+    }
+}
+
+pub(crate) struct ConnectionGuard(Arc<Counters>);
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// snapshot of metrics of a single worker thread.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WorkerMetrics {
+    /// total number of accepted connections.
+    pub accepted: u64,
+    /// number of connections currently being served.
+    pub active: u64,
+}
+
+/// snapshot of metrics of server. obtained from [ServerHandle::metrics](crate::ServerHandle::metrics).
+///
+/// counters are loaded individually with relaxed ordering and snapshot is not guaranteed to be
+/// consistent between counters.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ServerMetrics {
+    workers: Vec<WorkerMetrics>,
+}
+
+impl ServerMetrics {
+    pub(crate) fn new(counters: &[Arc<Counters>]) -> Self {
+        Self {
+            workers: counters.iter().map(|c| c.snapshot()).collect(),
+        }
+    }
+
+    /// metrics of every worker thread. indexed by worker id.
+    pub fn workers(&self) -> &[WorkerMetrics] {
+        &self.workers
+    }
+
+    /// aggregated metrics of all worker threads.
+    pub fn total(&self) -> WorkerMetrics {
+        self.workers
+            .iter()
+            .fold(WorkerMetrics::default(), |acc, w| WorkerMetrics {
+                accepted: acc.accepted + w.accepted,
+                active: acc.active + w.active,
+            })
+    }
+}
@@ -2,6 +2,8 @@ use std::{io, net};
 
 #[cfg(feature = "quic")]
 use xitca_io::net::QuicListenerBuilder;
+#[cfg(not(target_family = "wasm"))]
+use xitca_io::net::UdpListener;
 #[cfg(unix)]
 use xitca_io::net::UnixListener;
 use xitca_io::net::{Listener, TcpListener};
@@ -28,6 +30,20 @@ impl AsListener for Option<net::TcpListener> {
     }
 }
 
+#[cfg(not(target_family = "wasm"))]
+impl AsListener for Option<net::UdpSocket> {
+    fn as_listener(&mut self) -> io::Result<Listener> {
+        let this = self.take().unwrap();
+        this.set_nonblocking(true)?;
+
+        let udp = UdpListener::from_std(this)?;
+
+        info!("Started Udp datagram listening on: {:?}", udp.local_addr().ok());
+
+        Ok(Listener::Datagram(udp))
+    }
+}
+
 #[cfg(unix)]
 impl AsListener for Option<std::os::unix::net::UnixListener> {
     fn as_listener(&mut self) -> io::Result<Listener> {
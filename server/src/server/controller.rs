@@ -0,0 +1,104 @@
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::oneshot;
+
+use super::handle::ServerHandle;
+
+/// A shared controller for stopping multiple servers together.
+///
+/// Servers built from [Builder](crate::Builder) registered with the same controller through
+/// [Builder::shutdown_controller](crate::Builder::shutdown_controller) are stopped by one
+/// [ShutdownController::shutdown] call and it resolves when all of them are finished.
+///
+/// # Examples:
+/// ```rust
+/// # use xitca_io::net::TcpStream;
+/// # use xitca_server::{Builder, ShutdownController};
+/// # use xitca_service::fn_service;
+/// # #[tokio::main]
+/// # async fn main() {
+/// let controller = ShutdownController::new();
+///
+/// let public = Builder::new()
+///     .shutdown_controller(&controller)
+///     .bind("public", "127.0.0.1:0", fn_service(|_io: TcpStream| async { Ok::<_, ()>(()) }))
+///     .unwrap()
+///     .build();
+///
+/// let admin = Builder::new()
+///     .shutdown_controller(&controller)
+///     .bind("admin", "127.0.0.1:0", fn_service(|_io: TcpStream| async { Ok::<_, ()>(()) }))
+///     .unwrap()
+///     .build();
+///
+/// tokio::spawn(public);
+/// tokio::spawn(admin);
+///
+/// // drain and stop both servers.
+/// controller.shutdown_graceful().await;
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct ShutdownController {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    servers: Vec<(ServerHandle, oneshot::Receiver<()>)>,
+    // graceful flag of shutdown that already happened.
+    shutdown: Option<bool>,
+}
+
+impl ShutdownController {
+    /// Create new controller with no server registered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop all registered servers with graceful flag and wait for them to finish.
+    ///
+    /// See [ServerHandle::stop] for the meaning of graceful flag. Server registered after this call is
+    /// stopped right away with the same flag.
+    ///
+    /// # Note:
+    /// Server is stopped when it's [ServerFuture](crate::ServerFuture) is polled. It must be spawned as
+    /// task or consumed with [ServerFuture::wait](crate::ServerFuture::wait) for the returned future to
+    /// resolve.
+    pub async fn shutdown(&self, graceful: bool) {
+        let servers = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.shutdown = Some(graceful);
+            std::mem::take(&mut inner.servers)
+        };
+
+        let rxs = servers
+            .into_iter()
+            .map(|(handle, rx)| {
+                handle.stop(graceful);
+                rx
+            })
+            .collect::<Vec<_>>();
+
+        // sender is dropped or notified when server is stopped or failed to start.
+        for rx in rxs {
+            let _ = rx.await;
+        }
+    }
+
+    /// Graceful stop all registered servers and wait for them to finish. Equivalent to
+    /// [ShutdownController::shutdown] with graceful flag set to true.
+    pub async fn shutdown_graceful(&self) {
+        self.shutdown(true).await
+    }
+
+    pub(crate) fn register(&self, handle: ServerHandle) -> oneshot::Sender<()> {
+        let (tx, rx) = oneshot::channel();
+        let mut inner = self.inner.lock().unwrap();
+        match inner.shutdown {
+            Some(graceful) => handle.stop(graceful),
+            None => inner.servers.push((handle, rx)),
+        }
+        tx
+    }
+}
@@ -47,9 +47,13 @@ impl ServerFuture {
         match *self {
             Self::Init { ref server, .. } => Ok(ServerHandle {
                 tx: server.tx_cmd.clone(),
+                is_draining: server.is_draining.clone(),
+                metrics: server.metrics.clone(),
             }),
             Self::Running(ref inner) => Ok(ServerHandle {
                 tx: inner.server.tx_cmd.clone(),
+                is_draining: inner.server.is_draining.clone(),
+                metrics: inner.server.metrics.clone(),
             }),
             Self::Error(_) => match mem::take(self) {
                 Self::Error(e) => Err(e),
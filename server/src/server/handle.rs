@@ -1,14 +1,26 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
 use tokio::sync::mpsc::UnboundedSender;
 
+use crate::metrics::{Counters, ServerMetrics};
+
 use super::Command;
 
 #[derive(Clone)]
 pub struct ServerHandle {
     pub(super) tx: UnboundedSender<Command>,
+    pub(super) is_draining: Arc<AtomicBool>,
+    pub(super) metrics: Vec<Arc<Counters>>,
 }
 
 impl ServerHandle {
     /// Stop xitca-server with graceful flag.
+    ///
+    /// Graceful stop puts server in draining state (see [ServerHandle::start_draining]) and waits for
+    /// connections to finish until shutdown timeout.
     pub fn stop(&self, graceful: bool) {
         let cmd = if graceful {
             Command::GracefulStop
@@ -18,4 +30,29 @@ impl ServerHandle {
 
         let _ = self.tx.send(cmd);
     }
+
+    /// Start draining xitca-server without stopping it.
+    ///
+    /// Server would keep accepting connections and finish in flight requests. Services observing
+    /// [Builder::draining_state](crate::Builder::draining_state) would stop keeping connections alive.
+    /// (For example: http/1 responses would carry `Connection: close` header and http/2 connections
+    /// would be sent with GOAWAY frame).
+    ///
+    /// Unlike [ServerHandle::stop] server process would keep running until it's stopped explicitly.
+    /// This is useful for removing server from load balancer before shutting it down.
+    pub fn start_draining(&self) {
+        if !self.is_draining.swap(true, Ordering::SeqCst) {
+            tracing::info!("Server start draining");
+        }
+    }
+
+    /// Check if server is in draining state.
+    pub fn is_draining(&self) -> bool {
+        self.is_draining.load(Ordering::SeqCst)
+    }
+
+    /// Take a snapshot of server metrics.
+    pub fn metrics(&self) -> ServerMetrics {
+        ServerMetrics::new(&self.metrics)
+    }
 }
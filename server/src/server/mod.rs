@@ -1,8 +1,9 @@
+mod controller;
 mod future;
 mod handle;
 mod service;
 
-pub use self::{future::ServerFuture, handle::ServerHandle};
+pub use self::{controller::ShutdownController, future::ServerFuture, handle::ServerHandle};
 
 pub(crate) use self::service::{IntoServiceObj, ServiceObj};
 
@@ -17,17 +18,28 @@ use std::{
 
 use tokio::{
     runtime::Runtime,
-    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+    sync::{
+        mpsc::{UnboundedReceiver, UnboundedSender},
+        oneshot,
+    },
 };
 
-use crate::{builder::Builder, worker};
+use crate::{
+    builder::{Builder, RuntimeModel},
+    metrics::{Counters, MetricsRecorder},
+    worker::{self, AcceptConfig, Incoming},
+};
 
 pub struct Server {
     is_graceful_shutdown: Arc<AtomicBool>,
+    is_draining: Arc<AtomicBool>,
     tx_cmd: UnboundedSender<Command>,
     rx_cmd: UnboundedReceiver<Command>,
     rt: Option<Runtime>,
+    metrics: Vec<Arc<Counters>>,
     worker_join_handles: Vec<thread::JoinHandle<io::Result<()>>>,
+    // notify registered ShutdownController when server is stopped.
+    on_stop: Option<oneshot::Sender<()>>,
 }
 
 impl Server {
@@ -38,9 +50,18 @@ impl Server {
             factories,
             shutdown_timeout,
             on_worker_start,
+            proxy_protocol,
+            tcp_nodelay,
+            tcp_keepalive,
             ..
         } = builder;
 
+        let accept_config = AcceptConfig {
+            proxy_protocol,
+            tcp_nodelay,
+            tcp_keepalive,
+        };
+
         let rt = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
 
         let fut = async {
@@ -55,7 +76,11 @@ impl Server {
                 .collect::<Result<Vec<_>, io::Error>>()
         };
 
-        let listeners = rt.block_on(fut)?;
+        let listeners = rt
+            .block_on(fut)?
+            .into_iter()
+            .map(|(name, listener)| (name, Incoming::Listener(listener)))
+            .collect::<Vec<_>>();
 
         let is_graceful_shutdown = Arc::new(AtomicBool::new(false));
 
@@ -69,7 +94,7 @@ impl Server {
 
             for (name, factory) in factories.iter() {
                 let (h, s) = factory
-                    .call((name, &listeners))
+                    .call((name, &listeners, accept_config))
                     .await
                     .map_err(|_| io::Error::from(io::ErrorKind::Other))?;
                 handles.extend(h);
@@ -92,13 +117,49 @@ impl Server {
             server_threads,
             worker_threads,
             worker_max_blocking_threads,
+            runtime,
             listeners,
             factories,
             shutdown_timeout,
             on_worker_start,
+            proxy_protocol,
+            tcp_nodelay,
+            tcp_keepalive,
+            draining,
+            shutdown_controller,
+            #[cfg(feature = "io-uring")]
+            io_uring_fallback,
             ..
         } = builder;
 
+        #[cfg(feature = "io-uring")]
+        let use_io_uring = {
+            // probe io_uring in a spawned thread. dropping runtime inside another runtime's context panics.
+            // *. Server::new is most likely already inside a tokio runtime.
+            let probe = thread::scope(|s| {
+                s.spawn(|| tokio_uring::Runtime::new(&tokio_uring::builder()).map(drop))
+                    .join()
+            })
+            .unwrap();
+
+            match probe {
+                Ok(_) => true,
+                Err(e) if io_uring_fallback => {
+                    tracing::warn!("io_uring is not available: {e}. Fallback to tokio runtime for worker threads");
+                    false
+                }
+                Err(e) => return Err(e),
+            }
+        };
+
+        let accept_config = AcceptConfig {
+            proxy_protocol,
+            tcp_nodelay,
+            tcp_keepalive,
+            #[cfg(feature = "io-uring-multishot")]
+            multishot: use_io_uring,
+        };
+
         let rt = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
             // This worker threads is only used for accepting connections.
@@ -125,25 +186,58 @@ impl Server {
         let is_graceful_shutdown = Arc::new(AtomicBool::new(false));
         let is_graceful_shutdown2 = is_graceful_shutdown.clone();
 
+        let metrics = (0..worker_threads)
+            .map(|_| Arc::default())
+            .collect::<Vec<Arc<Counters>>>();
+        let metrics2 = metrics.clone();
+
+        // connection sources of every worker.
+        let mut incoming = metrics.iter().map(|_| Vec::new()).collect::<Vec<_>>();
+
+        for (name, listener) in listeners {
+            if runtime == RuntimeModel::SharedMultiThread && worker::dispatchable(&listener) {
+                let mut workers = Vec::with_capacity(metrics.len());
+                for (incoming, counters) in incoming.iter_mut().zip(metrics.iter()) {
+                    let (tx, rx) = Incoming::dispatch();
+                    incoming.push((name.clone(), rx));
+                    workers.push((tx, counters.clone()));
+                }
+                rt.spawn(worker::dispatch(listener, workers));
+            } else {
+                for incoming in incoming.iter_mut() {
+                    incoming.push((name.clone(), Incoming::Listener(listener.clone())));
+                }
+            }
+        }
+
         let worker_handles = thread::Builder::new()
             .name(String::from("xitca-server-worker-shared-scope"))
             .spawn(move || {
                 let is_graceful_shutdown = is_graceful_shutdown2;
+                let metrics = metrics2;
+
+                let factories = &factories;
+                let on_worker_start = &on_worker_start;
+                let is_graceful_shutdown = &is_graceful_shutdown;
 
                 // TODO: wait for startup error(including panic) and return as io::Error on call site.
                 // currently the error only show when shared scope thread is joined with handle.
                 thread::scope(|scope| {
-                    for idx in 0..worker_threads {
+                    for (idx, (counters, incoming)) in metrics.iter().zip(incoming).enumerate() {
                         let thread = thread::Builder::new().name(format!("xitca-server-worker-{idx}"));
 
-                        let task = || async {
+                        let counters = counters.clone();
+
+                        let task = move || async move {
+                            MetricsRecorder::set_current(counters);
+
                             on_worker_start().await;
 
                             let mut handles = Vec::new();
                             let mut services = Vec::new();
 
                             for (name, factory) in factories.iter() {
-                                match factory.call((name, &listeners)).await {
+                                match factory.call((name, &incoming, accept_config)).await {
                                     Ok((h, s)) => {
                                         handles.extend(h);
                                         services.push(s);
@@ -152,28 +246,23 @@ impl Server {
                                 }
                             }
 
-                            worker::wait_for_stop(handles, services, shutdown_timeout, &is_graceful_shutdown).await;
+                            worker::wait_for_stop(handles, services, shutdown_timeout, is_graceful_shutdown).await;
                         };
 
-                        #[cfg(not(feature = "io-uring"))]
-                        {
-                            let rt = tokio::runtime::Builder::new_current_thread()
-                                .enable_all()
-                                .max_blocking_threads(worker_max_blocking_threads)
-                                .build()?;
-
-                            thread.spawn_scoped(scope, move || {
-                                rt.block_on(tokio::task::LocalSet::new().run_until(task()))
-                            })?;
-                        }
-
                         #[cfg(feature = "io-uring")]
-                        {
-                            thread.spawn_scoped(scope, move || {
-                                let _ = worker_max_blocking_threads;
-                                tokio_uring::start(task())
-                            })?;
+                        if use_io_uring {
+                            thread.spawn_scoped(scope, move || tokio_uring::start(task()))?;
+                            continue;
                         }
+
+                        let rt = tokio::runtime::Builder::new_current_thread()
+                            .enable_all()
+                            .max_blocking_threads(worker_max_blocking_threads)
+                            .build()?;
+
+                        thread.spawn_scoped(scope, move || {
+                            rt.block_on(tokio::task::LocalSet::new().run_until(task()))
+                        })?;
                     }
 
                     Ok(())
@@ -182,22 +271,41 @@ impl Server {
 
         let (tx_cmd, rx_cmd) = tokio::sync::mpsc::unbounded_channel();
 
+        let on_stop = shutdown_controller.map(|controller| {
+            controller.register(ServerHandle {
+                tx: tx_cmd.clone(),
+                is_draining: draining.clone(),
+                metrics: metrics.clone(),
+            })
+        });
+
         Ok(Self {
             is_graceful_shutdown,
+            is_draining: draining,
             tx_cmd,
             rx_cmd,
             rt: Some(rt),
+            metrics,
             worker_join_handles: vec![worker_handles],
+            on_stop,
         })
     }
 
     pub(crate) fn stop(&mut self, graceful: bool) {
         if let Some(rt) = self.rt.take() {
             self.is_graceful_shutdown.store(graceful, Ordering::SeqCst);
+            // notify services observing draining state to finish in flight requests and close
+            // connections.
+            if graceful {
+                self.is_draining.store(true, Ordering::SeqCst);
+            }
             rt.shutdown_background();
             mem::take(&mut self.worker_join_handles).into_iter().for_each(|handle| {
                 let _ = handle.join().unwrap();
             });
+            if let Some(tx) = self.on_stop.take() {
+                let _ = tx.send(());
+            }
         }
     }
 }
@@ -1,41 +1,180 @@
+mod proxy_protocol;
 mod shutdown;
 
 use core::{any::Any, sync::atomic::AtomicBool, time::Duration};
 
-use std::{io, rc::Rc, sync::Arc, thread};
+#[cfg(feature = "io-uring-multishot")]
+use core::{
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+};
 
-use tokio::{task::JoinHandle, time::sleep};
-use tracing::{error, info};
+use std::{
+    io,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use tokio::{
+    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+    task::JoinHandle,
+    time::sleep,
+};
+use tracing::{debug, error, info, warn};
 use xitca_io::net::{Listener, Stream};
 use xitca_service::{ready::ReadyService, Service};
 
+use crate::metrics::{ConnectionGuard, Counters, MetricsRecorder};
+
 use self::shutdown::ShutdownHandle;
 
 // erase Rc<S: ReadyService<_>> type and only use it for counting the reference counter of Rc.
 pub(crate) type ServiceAny = Rc<dyn Any>;
 
-pub(crate) fn start<S, Req>(listener: &Arc<Listener>, service: &Rc<S>) -> JoinHandle<()>
+/// configuration applied to accepted connections by worker.
+#[derive(Clone, Copy, Default)]
+pub struct AcceptConfig {
+    pub(crate) proxy_protocol: bool,
+    pub(crate) tcp_nodelay: bool,
+    pub(crate) tcp_keepalive: Option<Duration>,
+    // accept tcp connection with io_uring multishot accept. only enabled on io_uring worker threads.
+    #[cfg(feature = "io-uring-multishot")]
+    pub(crate) multishot: bool,
+}
+
+impl AcceptConfig {
+    fn apply(&self, stream: &Stream) {
+        #[cfg_attr(target_family = "wasm", allow(irrefutable_let_patterns))]
+        let Stream::Tcp(ref tcp, _) = *stream
+        else {
+            return;
+        };
+
+        if self.tcp_nodelay {
+            if let Err(e) = tcp.set_nodelay(true) {
+                warn!("Failed to set TCP_NODELAY on connection: {e}");
+            }
+        }
+
+        #[cfg(not(target_family = "wasm"))]
+        if let Some(time) = self.tcp_keepalive {
+            let ka = socket2::TcpKeepalive::new().with_time(time);
+            if let Err(e) = socket2::SockRef::from(tcp).set_tcp_keepalive(&ka) {
+                warn!("Failed to set SO_KEEPALIVE on connection: {e}");
+            }
+        }
+    }
+}
+
+/// source of connections of a worker.
+pub enum Incoming {
+    /// worker accepts connections from listener by itself.
+    Listener(Arc<Listener>),
+    /// connections are accepted by server and dispatched to worker. receiver is taken by the worker
+    /// when it starts.
+    Dispatch(Mutex<Option<UnboundedReceiver<Dispatched>>>),
+}
+
+impl Incoming {
+    /// construct a channel for dispatching connections to a worker.
+    #[cfg(not(target_family = "wasm"))]
+    pub(crate) fn dispatch() -> (UnboundedSender<Dispatched>, Self) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        (tx, Self::Dispatch(Mutex::new(Some(rx))))
+    }
+}
+
+/// connection accepted by server and dispatched to a worker.
+pub struct Dispatched {
+    stream: Stream,
+    // connection is counted as active for the worker it's dispatched to from the moment it's
+    // dispatched. so following dispatch decisions observe it.
+    guard: ConnectionGuard,
+}
+
+// accept connections from listener and dispatch them to the worker with least active connections.
+// used with shared runtime model where accepting runs on the server's multi-threaded runtime.
+#[cfg(not(target_family = "wasm"))]
+pub(crate) async fn dispatch(listener: Arc<Listener>, workers: Vec<(UnboundedSender<Dispatched>, Arc<Counters>)>) {
+    loop {
+        match listener.accept().await {
+            Ok(stream) => {
+                let Some((tx, counters)) = workers.iter().min_by_key(|(_, counters)| counters.active()) else {
+                    return;
+                };
+                let guard = MetricsRecorder::from(counters.clone()).connection();
+                if tx.send(Dispatched { stream, guard }).is_err() {
+                    return;
+                }
+            }
+            Err(ref e) if connection_error(e) => continue,
+            Err(ref e) if fatal_error(e) => return,
+            Err(ref e) if os_error(e) => {
+                error!("Error accepting connection: {e}");
+                sleep(Duration::from_secs(1)).await;
+            }
+            Err(_) => return,
+        }
+    }
+}
+
+// connection accepted from listener can be handed to other thread.
+#[cfg(not(target_family = "wasm"))]
+pub(crate) fn dispatchable(listener: &Listener) -> bool {
+    #[cfg(unix)]
+    if let Listener::Unix(_) = *listener {
+        return true;
+    }
+    matches!(*listener, Listener::Tcp(_))
+}
+
+pub(crate) fn start<S, Req>(incoming: &Incoming, service: &Rc<S>, config: AcceptConfig) -> JoinHandle<()>
 where
     S: ReadyService + Service<Req> + 'static,
     S::Ready: 'static,
     Req: TryFrom<Stream> + 'static,
 {
-    let listener = listener.clone();
+    let mut source = match *incoming {
+        Incoming::Listener(ref listener) => Source::Listener(listener.clone()),
+        Incoming::Dispatch(ref rx) => Source::Dispatch(rx.lock().unwrap().take()),
+    };
     let service = service.clone();
+    let metrics = MetricsRecorder::current();
 
     tokio::task::spawn_local(async move {
+        let mut accept = Accept::new(&mut source, config);
+
         loop {
             let ready = service.ready().await;
 
-            match listener.accept().await {
-                Ok(stream) => {
-                    if let Ok(req) = TryFrom::try_from(stream) {
-                        let service = service.clone();
-                        tokio::task::spawn_local(async move {
+            match accept.accept().await {
+                Ok((stream, guard)) => {
+                    let service = service.clone();
+                    // connection is counted as active until it's task is finished.
+                    let guard = guard.or_else(|| metrics.as_ref().map(MetricsRecorder::connection));
+                    tokio::task::spawn_local(async move {
+                        config.apply(&stream);
+
+                        let stream = if config.proxy_protocol {
+                            match proxy_protocol::resolve(stream).await {
+                                Ok(stream) => stream,
+                                Err(e) => {
+                                    debug!("Connection rejected on PROXY protocol error: {e}");
+                                    return;
+                                }
+                            }
+                        } else {
+                            stream
+                        };
+
+                        if let Ok(req) = TryFrom::try_from(stream) {
                             let _ = service.call(req).await;
-                            drop(ready);
-                        });
-                    }
+                        }
+                        drop(ready);
+                        drop(guard);
+                    });
                 }
                 Err(ref e) if connection_error(e) => continue,
                 Err(ref e) if fatal_error(e) => return,
@@ -50,6 +189,70 @@ where
     })
 }
 
+enum Source {
+    Listener(Arc<Listener>),
+    // receiver can only be taken once. None when the same dispatch source is started again.
+    Dispatch(Option<UnboundedReceiver<Dispatched>>),
+}
+
+enum Accept<'a> {
+    Listener(&'a Listener),
+    #[cfg(feature = "io-uring-multishot")]
+    Multishot(&'a Listener, xitca_io::net::io_uring::Accept<'a>),
+    Dispatch(Option<&'a mut UnboundedReceiver<Dispatched>>),
+}
+
+impl<'a> Accept<'a> {
+    #[cfg_attr(not(feature = "io-uring-multishot"), allow(unused_variables))]
+    fn new(source: &'a mut Source, config: AcceptConfig) -> Self {
+        let listener = match *source {
+            Source::Listener(ref listener) => listener,
+            Source::Dispatch(ref mut rx) => return Self::Dispatch(rx.as_mut()),
+        };
+
+        #[cfg(feature = "io-uring-multishot")]
+        if let (true, Listener::Tcp(ref tcp)) = (config.multishot, &**listener) {
+            match xitca_io::net::io_uring::Ring::current() {
+                Ok(ring) => return Self::Multishot(listener, ring.accept(tcp)),
+                Err(e) => warn!("io_uring multishot accept is not available: {e}. Fallback to tokio listener"),
+            }
+        }
+
+        Self::Listener(listener)
+    }
+
+    // accepted connection and it's guard when it's already counted by dispatcher.
+    async fn accept(&mut self) -> io::Result<(Stream, Option<ConnectionGuard>)> {
+        match *self {
+            Self::Listener(listener) => listener.accept().await.map(|stream| (stream, None)),
+            // dispatcher is dropped when server is shutting down.
+            Self::Dispatch(ref mut rx) => match rx {
+                Some(rx) => rx
+                    .recv()
+                    .await
+                    .map(|d| (d.stream, Some(d.guard)))
+                    .ok_or_else(|| io::ErrorKind::BrokenPipe.into()),
+                None => Err(io::ErrorKind::BrokenPipe.into()),
+            },
+            #[cfg(feature = "io-uring-multishot")]
+            Self::Multishot(listener, ref mut accept) => {
+                // listener is polled together to observe server shutdown where it's reactor is dropped and
+                // accept returns with error. connection accepted by either of them is used.
+                let mut multishot = pin!(accept.accept());
+                let mut listener = pin!(listener.accept());
+                poll_fn(|cx| {
+                    if let Poll::Ready(res) = multishot.as_mut().poll(cx) {
+                        return Poll::Ready(res.map(|(stream, addr)| Stream::Tcp(stream, addr)));
+                    }
+                    listener.as_mut().poll(cx)
+                })
+                .await
+                .map(|stream| (stream, None))
+            }
+        }
+    }
+}
+
 pub(crate) async fn wait_for_stop(
     handles: Vec<JoinHandle<()>>,
     services: Vec<ServiceAny>,
@@ -0,0 +1,200 @@
+//! PROXY protocol v1/v2 header parsing.
+//!
+//! See <https://www.haproxy.org/download/2.9/doc/proxy-protocol.txt> for the specification.
+
+use core::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use std::io;
+
+use tokio::{io::AsyncReadExt, net::TcpStream, time::timeout};
+use xitca_io::net::Stream;
+
+// peer must send the complete header in time. prevent slow peer from holding the connection.
+const HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+
+const V1_PREFIX: &[u8] = b"PROXY ";
+// "PROXY UNKNOWN\r\n" is the shortest valid v1 header.
+const V1_MIN_LEN: usize = 15;
+const V1_MAX_LEN: usize = 107;
+
+const V2_SIGNATURE: &[u8; 12] = b"\r\n\r\n\0\r\nQUIT\n";
+
+/// read PROXY protocol header from tcp stream and replace it's peer address with the address
+/// carried by header. streams other than tcp are returned as is.
+pub(super) async fn resolve(stream: Stream) -> io::Result<Stream> {
+    match stream {
+        Stream::Tcp(tcp, addr) => {
+            let mut tcp = TcpStream::from_std(tcp)?;
+            let peer = timeout(HEADER_TIMEOUT, read_header(&mut tcp))
+                .await
+                .map_err(|_| io::Error::from(io::ErrorKind::TimedOut))??;
+            Ok(Stream::Tcp(tcp.into_std()?, peer.unwrap_or(addr)))
+        }
+        stream => Ok(stream),
+    }
+}
+
+// read exactly the bytes of header so following bytes are left in stream for service.
+// return None when header does not carry peer address. (v1 UNKNOWN or v2 LOCAL command for example)
+async fn read_header(tcp: &mut TcpStream) -> io::Result<Option<SocketAddr>> {
+    let mut buf = [0; V1_MAX_LEN];
+
+    tcp.read_exact(&mut buf[..V2_SIGNATURE.len()]).await?;
+
+    if &buf[..V2_SIGNATURE.len()] == V2_SIGNATURE {
+        let mut head = [0; 4];
+        tcp.read_exact(&mut head).await?;
+        let len = u16::from_be_bytes([head[2], head[3]]) as usize;
+        let mut body = vec![0; len];
+        tcp.read_exact(&mut body).await?;
+        return parse_v2(head[0], head[1], &body);
+    }
+
+    if !buf.starts_with(V1_PREFIX) {
+        return Err(invalid("PROXY protocol header not found"));
+    }
+
+    let mut len = V2_SIGNATURE.len();
+    tcp.read_exact(&mut buf[len..V1_MIN_LEN]).await?;
+    len = V1_MIN_LEN;
+
+    // v1 header is short and one time per connection. read byte by byte to avoid consuming bytes
+    // beyond the header.
+    while !buf[..len].ends_with(b"\r\n") {
+        if len == V1_MAX_LEN {
+            return Err(invalid("PROXY protocol v1 header too long"));
+        }
+        tcp.read_exact(&mut buf[len..len + 1]).await?;
+        len += 1;
+    }
+
+    parse_v1(&buf[..len - 2])
+}
+
+fn parse_v1(line: &[u8]) -> io::Result<Option<SocketAddr>> {
+    let line = core::str::from_utf8(line).map_err(|_| invalid("PROXY protocol v1 header is not utf-8"))?;
+
+    let mut parts = line.split(' ');
+
+    if parts.next() != Some("PROXY") {
+        return Err(invalid("PROXY protocol v1 header not found"));
+    }
+
+    let is_v4 = match parts.next() {
+        Some("TCP4") => true,
+        Some("TCP6") => false,
+        Some("UNKNOWN") => return Ok(None),
+        _ => return Err(invalid("PROXY protocol v1 header has unknown protocol")),
+    };
+
+    let mut next = || {
+        parts
+            .next()
+            .ok_or_else(|| invalid("PROXY protocol v1 header is incomplete"))
+    };
+
+    let src = next()?
+        .parse::<IpAddr>()
+        .map_err(|_| invalid("PROXY protocol v1 header has invalid source address"))?;
+    let _dst = next()?
+        .parse::<IpAddr>()
+        .map_err(|_| invalid("PROXY protocol v1 header has invalid destination address"))?;
+    let port = next()?
+        .parse::<u16>()
+        .map_err(|_| invalid("PROXY protocol v1 header has invalid source port"))?;
+    let _dst_port = next()?
+        .parse::<u16>()
+        .map_err(|_| invalid("PROXY protocol v1 header has invalid destination port"))?;
+
+    if parts.next().is_some() || src.is_ipv4() != is_v4 {
+        return Err(invalid("PROXY protocol v1 header is malformed"));
+    }
+
+    Ok(Some(SocketAddr::new(src, port)))
+}
+
+fn parse_v2(ver_cmd: u8, family: u8, body: &[u8]) -> io::Result<Option<SocketAddr>> {
+    if ver_cmd >> 4 != 2 {
+        return Err(invalid("PROXY protocol v2 header has unknown version"));
+    }
+
+    match ver_cmd & 0x0f {
+        // LOCAL command. connection is established by proxy itself.
+        0 => return Ok(None),
+        1 => {}
+        _ => return Err(invalid("PROXY protocol v2 header has unknown command")),
+    }
+
+    match family >> 4 {
+        // AF_INET
+        1 => {
+            let body = body
+                .get(..12)
+                .ok_or_else(|| invalid("PROXY protocol v2 header address is too short"))?;
+            let ip = Ipv4Addr::new(body[0], body[1], body[2], body[3]);
+            let port = u16::from_be_bytes([body[8], body[9]]);
+            Ok(Some(SocketAddr::new(IpAddr::V4(ip), port)))
+        }
+        // AF_INET6
+        2 => {
+            let body = body
+                .get(..36)
+                .ok_or_else(|| invalid("PROXY protocol v2 header address is too short"))?;
+            let mut ip = [0; 16];
+            ip.copy_from_slice(&body[..16]);
+            let port = u16::from_be_bytes([body[32], body[33]]);
+            Ok(Some(SocketAddr::new(IpAddr::V6(Ipv6Addr::from(ip)), port)))
+        }
+        // AF_UNSPEC and AF_UNIX carry no socket address.
+        0 | 3 => Ok(None),
+        _ => Err(invalid("PROXY protocol v2 header has unknown address family")),
+    }
+}
+
+fn invalid(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn v1() {
+        assert_eq!(
+            parse_v1(b"PROXY TCP4 192.168.0.1 192.168.0.11 56324 443").unwrap(),
+            Some("192.168.0.1:56324".parse().unwrap())
+        );
+        assert_eq!(
+            parse_v1(b"PROXY TCP6 ::1 ::1 8080 443").unwrap(),
+            Some("[::1]:8080".parse().unwrap())
+        );
+        assert_eq!(parse_v1(b"PROXY UNKNOWN").unwrap(), None);
+        assert_eq!(parse_v1(b"PROXY UNKNOWN ::1 ::1 8080 443").unwrap(), None);
+
+        assert!(parse_v1(b"PROXY TCP4 ::1 ::1 8080 443").is_err());
+        assert!(parse_v1(b"PROXY TCP4 192.168.0.1 192.168.0.11 56324").is_err());
+        assert!(parse_v1(b"PROXY TCP4 192.168.0.1 192.168.0.11 56324 443 1").is_err());
+        assert!(parse_v1(b"PROXY UDP4 192.168.0.1 192.168.0.11 56324 443").is_err());
+        assert!(parse_v1(b"PROXY TCP4 192.168.0.1 192.168.0.11 99999 443").is_err());
+    }
+
+    #[test]
+    fn v2() {
+        let body = [127, 0, 0, 2, 127, 0, 0, 1, 0x1f, 0x90, 0x01, 0xbb];
+        assert_eq!(
+            parse_v2(0x21, 0x11, &body).unwrap(),
+            Some("127.0.0.2:8080".parse().unwrap())
+        );
+        assert_eq!(parse_v2(0x20, 0x11, &body).unwrap(), None);
+        assert_eq!(parse_v2(0x21, 0x00, &[]).unwrap(), None);
+
+        assert!(parse_v2(0x11, 0x11, &body).is_err());
+        assert!(parse_v2(0x22, 0x11, &body).is_err());
+        assert!(parse_v2(0x21, 0x11, &body[..11]).is_err());
+        assert!(parse_v2(0x21, 0x21, &body).is_err());
+    }
+}
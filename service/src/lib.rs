@@ -47,7 +47,7 @@ pub mod ready;
 pub use self::{
     async_fn::AsyncFn,
     pipeline::{EnclosedBuilder, EnclosedFnBuilder, MapBuilder, MapErrorBuilder},
-    service::{fn_build, fn_service, FnService, Service, ServiceExt},
+    service::{fn_build, fn_service, FnService, Reborrow, Service, ServiceExt},
 };
 
 #[cfg(feature = "alloc")]
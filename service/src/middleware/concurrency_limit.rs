@@ -0,0 +1,370 @@
+use core::{
+    cell::{Cell, RefCell},
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use alloc::{collections::VecDeque, rc::Rc};
+
+use crate::{ready::ReadyService, service::Service};
+
+/// A middleware limiting number of concurrent calls to inner service.
+///
+/// A permit is acquired before calling inner service and released when the call is finished. When all
+/// permits are in use the call would wait in a first in first out queue by default.
+/// [ConcurrencyLimit::load_shed] can be used to reject the call with [Overloaded] error immediately.
+///
+/// Inner service's [ReadyService::ready] would wait in the same queue until a permit is available before
+/// forwarding it. The permit is not reserved by ready state and it's still acquired by the call itself.
+/// With load shedding enabled ready state is forwarded without waiting and rejection is left to the call.
+///
+/// The limit is not shared between services built by the middleware. For a multi-threaded server
+/// every thread has it's own limit.
+///
+/// # Examples
+/// ```rust
+/// # use core::convert::Infallible;
+/// # use xitca_service::{fn_service, middleware::{ConcurrencyLimit, Overloaded}, Service, ServiceExt};
+/// # async fn limit() {
+/// // at most 64 concurrent calls and the 65th call would wait for a permit.
+/// let service = fn_service(|_: ()| async { Ok::<_, Infallible>(()) })
+///     .enclosed(ConcurrencyLimit::new(64))
+///     .call(())
+///     .await
+///     .unwrap();
+///
+/// // at most 64 concurrent calls and the 65th call would be rejected.
+/// let service = fn_service(|_: ()| async { Ok::<_, Overloaded>(()) })
+///     .enclosed(ConcurrencyLimit::new(64).load_shed())
+///     .call(())
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct ConcurrencyLimit<const SHED: bool = false> {
+    limit: usize,
+}
+
+impl ConcurrencyLimit {
+    /// construct a new middleware with given max number of concurrent calls.
+    ///
+    /// # Panics
+    /// When limit is 0.
+    pub const fn new(limit: usize) -> Self {
+        assert!(limit > 0, "concurrency limit must be greater than 0");
+        Self { limit }
+    }
+
+    /// reject call with [Overloaded] error immediately when all permits are in use. Error type of inner
+    /// service must be able to be converted from [Overloaded].
+    pub const fn load_shed(self) -> ConcurrencyLimit<true> {
+        ConcurrencyLimit { limit: self.limit }
+    }
+}
+
+impl<S, E, const SHED: bool> Service<Result<S, E>> for ConcurrencyLimit<SHED> {
+    type Response = ConcurrencyLimitService<S, SHED>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| ConcurrencyLimitService {
+            service,
+            semaphore: Semaphore::new(self.limit),
+        })
+    }
+}
+
+/// error type for call rejected by [ConcurrencyLimit] with load shedding enabled.
+#[derive(Debug)]
+pub struct Overloaded;
+
+impl fmt::Display for Overloaded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("service is overloaded")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Overloaded {}
+
+/// service built by [ConcurrencyLimit] middleware.
+pub struct ConcurrencyLimitService<S, const SHED: bool> {
+    service: S,
+    semaphore: Semaphore,
+}
+
+impl<S, Req> Service<Req> for ConcurrencyLimitService<S, false>
+where
+    S: Service<Req>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+        let _permit = self.semaphore.acquire().await;
+        self.service.call(req).await
+    }
+}
+
+impl<S, Req> Service<Req> for ConcurrencyLimitService<S, true>
+where
+    S: Service<Req>,
+    S::Error: From<Overloaded>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+        let _permit = self.semaphore.try_acquire().ok_or(Overloaded)?;
+        self.service.call(req).await
+    }
+}
+
+impl<S, const SHED: bool> ReadyService for ConcurrencyLimitService<S, SHED>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    async fn ready(&self) -> Self::Ready {
+        // load shedding service must stay ready and reject excess calls instead of applying back pressure.
+        if !SHED {
+            self.semaphore.available().await;
+        }
+        self.service.ready().await
+    }
+}
+
+// single threaded semaphore. every waiting acquirer owns a slot in a fifo queue and a released permit
+// is handed to the first slot directly so only one waiter is woken up per permit. a slot is removed
+// from queue when its acquirer is dropped and a permit handed to dropped acquirer is passed on.
+struct Semaphore {
+    permits: Cell<usize>,
+    waiters: RefCell<VecDeque<Rc<Waiter>>>,
+}
+
+#[derive(Default)]
+struct Waiter {
+    granted: Cell<bool>,
+    waker: Cell<Option<Waker>>,
+}
+
+impl Semaphore {
+    const fn new(permits: usize) -> Self {
+        Self {
+            permits: Cell::new(permits),
+            waiters: RefCell::new(VecDeque::new()),
+        }
+    }
+
+    // permits are only counted when there is no waiter in queue so acquiring from count does not
+    // jump the queue.
+    fn try_acquire(&self) -> Option<Permit<'_>> {
+        let permits = self.permits.get();
+        (permits > 0).then(|| {
+            self.permits.set(permits - 1);
+            Permit(self)
+        })
+    }
+
+    fn acquire(&self) -> Acquire<'_> {
+        Acquire {
+            semaphore: self,
+            waiter: None,
+        }
+    }
+
+    // wait for a permit in queue and release it immediately.
+    async fn available(&self) {
+        drop(self.acquire().await);
+    }
+
+    fn release(&self) {
+        let waiter = self.waiters.borrow_mut().pop_front();
+        match waiter {
+            Some(waiter) => {
+                waiter.granted.set(true);
+                if let Some(waker) = waiter.waker.take() {
+                    waker.wake();
+                }
+            }
+            None => self.permits.set(self.permits.get() + 1),
+        }
+    }
+}
+
+struct Acquire<'a> {
+    semaphore: &'a Semaphore,
+    waiter: Option<Rc<Waiter>>,
+}
+
+impl<'a> Future for Acquire<'a> {
+    type Output = Permit<'a>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.waiter {
+            Some(ref waiter) if waiter.granted.get() => {
+                this.waiter = None;
+                Poll::Ready(Permit(this.semaphore))
+            }
+            Some(ref waiter) => {
+                waiter.waker.set(Some(cx.waker().clone()));
+                Poll::Pending
+            }
+            None => match this.semaphore.try_acquire() {
+                Some(permit) => Poll::Ready(permit),
+                None => {
+                    let waiter = Rc::new(Waiter::default());
+                    waiter.waker.set(Some(cx.waker().clone()));
+                    this.semaphore.waiters.borrow_mut().push_back(waiter.clone());
+                    this.waiter = Some(waiter);
+                    Poll::Pending
+                }
+            },
+        }
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        if let Some(waiter) = self.waiter.take() {
+            if waiter.granted.get() {
+                self.semaphore.release();
+            } else {
+                let mut waiters = self.semaphore.waiters.borrow_mut();
+                if let Some(idx) = waiters.iter().position(|w| Rc::ptr_eq(w, &waiter)) {
+                    waiters.remove(idx);
+                }
+            }
+        }
+    }
+}
+
+struct Permit<'a>(&'a Semaphore);
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{
+        future::poll_fn,
+        pin::pin,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use alloc::{boxed::Box, sync::Arc, task::Wake};
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{fn_service, ServiceExt};
+
+    use super::*;
+
+    #[test]
+    fn queue() {
+        let service = fn_service(|_: ()| async {
+            // yield once to simulate pending work.
+            let mut yielded = false;
+            poll_fn(|cx| {
+                if yielded {
+                    Poll::Ready(Ok::<_, ()>(()))
+                } else {
+                    yielded = true;
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            })
+            .await
+        })
+        .enclosed(ConcurrencyLimit::new(1))
+        .call(())
+        .now_or_panic()
+        .unwrap();
+
+        let mut cx = Context::from_waker(Waker::noop());
+
+        let mut first = pin!(service.call(()));
+        let mut second = pin!(service.call(()));
+        assert!(first.as_mut().poll(&mut cx).is_pending());
+        // second call waits for permit.
+        assert!(second.as_mut().poll(&mut cx).is_pending());
+        assert!(pin!(service.ready()).poll(&mut cx).is_pending());
+
+        assert!(first.as_mut().poll(&mut cx).is_ready());
+        assert!(second.as_mut().poll(&mut cx).is_pending());
+        assert!(second.as_mut().poll(&mut cx).is_ready());
+        service.ready().now_or_panic();
+    }
+
+    #[test]
+    fn load_shed() {
+        let service = fn_service(|_: ()| async { Ok::<_, Overloaded>(()) })
+            .enclosed(ConcurrencyLimit::new(1).load_shed())
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let permit = service.semaphore.try_acquire().unwrap();
+        // ready state does not wait for permit when load shedding.
+        service.ready().now_or_panic();
+        assert!(service.call(()).now_or_panic().is_err());
+        drop(permit);
+        assert!(service.call(()).now_or_panic().is_ok());
+    }
+
+    #[derive(Default)]
+    struct WakeCount(AtomicUsize);
+
+    impl Wake for WakeCount {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn fifo() {
+        let semaphore = Semaphore::new(1);
+        let permit = semaphore.try_acquire().unwrap();
+
+        let counts = [(); 3].map(|_| Arc::new(WakeCount::default()));
+        let wakers = counts.clone().map(Waker::from);
+
+        let mut first = Box::pin(semaphore.acquire());
+        let mut second = Box::pin(semaphore.acquire());
+        let mut third = Box::pin(semaphore.acquire());
+        assert!(first.as_mut().poll(&mut Context::from_waker(&wakers[0])).is_pending());
+        assert!(second.as_mut().poll(&mut Context::from_waker(&wakers[1])).is_pending());
+        assert!(third.as_mut().poll(&mut Context::from_waker(&wakers[2])).is_pending());
+
+        // released permit goes to first waiter and only it is woken up.
+        drop(permit);
+        assert_eq!(counts[0].0.load(Ordering::Relaxed), 1);
+        assert_eq!(counts[1].0.load(Ordering::Relaxed), 0);
+        assert_eq!(counts[2].0.load(Ordering::Relaxed), 0);
+        assert!(semaphore.try_acquire().is_none());
+
+        // cancelled waiter gives up its slot.
+        drop(second);
+
+        let permit = match first.as_mut().poll(&mut Context::from_waker(&wakers[0])) {
+            Poll::Ready(permit) => permit,
+            Poll::Pending => panic!("first waiter must be granted"),
+        };
+        drop(permit);
+        assert_eq!(counts[1].0.load(Ordering::Relaxed), 0);
+        assert_eq!(counts[2].0.load(Ordering::Relaxed), 1);
+
+        // permit granted to cancelled waiter is returned.
+        drop(third);
+        assert!(semaphore.try_acquire().is_some());
+        assert!(semaphore.waiters.borrow().is_empty());
+    }
+}
@@ -0,0 +1,339 @@
+use crate::{ready::ReadyService, service::Service};
+
+/// Receiver of call metrics produced by [Metered] middleware.
+///
+/// [Collector::start] is called before inner service is called and the returned value is passed to
+/// [Collector::finish] after call is finished. Collector can use it to carry state like start time of
+/// call for measuring latency.
+///
+/// Every started call is finished exactly once. A call dropped before completion is finished with
+/// [Outcome::Cancelled].
+///
+/// `()` is a no-op collector where both methods are empty and get optimized away.
+pub trait Collector {
+    /// state carried from start to finish of a call.
+    type Start;
+
+    /// called before inner service is called with name given to [Metered::new].
+    fn start(&self, name: &'static str) -> Self::Start;
+
+    /// called after inner service call is finished or cancelled.
+    fn finish(&self, name: &'static str, start: Self::Start, outcome: Outcome);
+}
+
+/// outcome of a call observed by [Collector::finish].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    /// inner service returned response.
+    Ok,
+    /// inner service returned error.
+    Err,
+    /// call future was dropped before inner service returned.
+    Cancelled,
+}
+
+impl Collector for () {
+    type Start = ();
+
+    #[inline(always)]
+    fn start(&self, _: &'static str) -> Self::Start {}
+
+    #[inline(always)]
+    fn finish(&self, _: &'static str, _: Self::Start, _: Outcome) {}
+}
+
+impl<C> Collector for &C
+where
+    C: Collector + ?Sized,
+{
+    type Start = C::Start;
+
+    #[inline]
+    fn start(&self, name: &'static str) -> Self::Start {
+        (**self).start(name)
+    }
+
+    #[inline]
+    fn finish(&self, name: &'static str, start: Self::Start, outcome: Outcome) {
+        (**self).finish(name, start, outcome)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<C> Collector for alloc::sync::Arc<C>
+where
+    C: Collector + ?Sized,
+{
+    type Start = C::Start;
+
+    #[inline]
+    fn start(&self, name: &'static str) -> Self::Start {
+        (**self).start(name)
+    }
+
+    #[inline]
+    fn finish(&self, name: &'static str, start: Self::Start, outcome: Outcome) {
+        (**self).finish(name, start, outcome)
+    }
+}
+
+/// A middleware reporting call count, outcome and timing of inner service to a [Collector].
+///
+/// Collector is cloned for every service built by the middleware. Use shared type like `&'static`
+/// reference or `Arc`(with `alloc` feature) of collector when metrics need to be aggregated.
+///
+/// # Examples
+/// ```rust
+/// # use core::convert::Infallible;
+/// # use std::{sync::atomic::{AtomicUsize, Ordering}, time::Instant};
+/// # use xitca_service::{fn_service, middleware::{Collector, Metered, Outcome}, Service, ServiceExt};
+/// struct Stats {
+///     ok: AtomicUsize,
+///     err: AtomicUsize,
+///     cancelled: AtomicUsize,
+/// }
+///
+/// impl Collector for Stats {
+///     type Start = Instant;
+///
+///     fn start(&self, _: &'static str) -> Self::Start {
+///         Instant::now()
+///     }
+///
+///     fn finish(&self, name: &'static str, start: Self::Start, outcome: Outcome) {
+///         println!("{name} finished with {outcome:?} in {:?}", start.elapsed());
+///         let count = match outcome {
+///             Outcome::Ok => &self.ok,
+///             Outcome::Err => &self.err,
+///             Outcome::Cancelled => &self.cancelled,
+///         };
+///         count.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// // a simple passthrough middleware function.
+/// async fn mw<S, Req>(svc: &S, req: Req) -> Result<S::Response, S::Error>
+/// where
+///     S: Service<Req>
+/// {
+///     svc.call(req).await
+/// }
+///
+/// static STATS: Stats = Stats {
+///     ok: AtomicUsize::new(0),
+///     err: AtomicUsize::new(0),
+///     cancelled: AtomicUsize::new(0),
+/// };
+///
+/// # async fn metered() {
+/// let service = fn_service(|_: ()| async { Ok::<_, Infallible>(()) })
+///     // metered middleware only observes the services it encloses. in this case the fn_service.
+///     .enclosed(Metered::new("index", &STATS))
+///     // middleware enclosed after is not measured.
+///     .enclosed_fn(mw)
+///     .call(())
+///     .await
+///     .unwrap();
+///
+/// service.call(()).await.unwrap();
+/// assert_eq!(STATS.ok.load(Ordering::Relaxed), 1);
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct Metered<C = ()> {
+    name: &'static str,
+    collector: C,
+}
+
+impl<C> Metered<C> {
+    /// construct middleware with given name and collector. name is passed to collector and can be
+    /// used for distinguishing services sharing the same collector.
+    pub const fn new(name: &'static str, collector: C) -> Self {
+        Self { name, collector }
+    }
+}
+
+impl<S, E, C> Service<Result<S, E>> for Metered<C>
+where
+    C: Clone,
+{
+    type Response = MeteredService<S, C>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| MeteredService {
+            service,
+            name: self.name,
+            collector: self.collector.clone(),
+        })
+    }
+}
+
+/// service built by [Metered] middleware.
+pub struct MeteredService<S, C> {
+    service: S,
+    name: &'static str,
+    collector: C,
+}
+
+impl<S, C, Req> Service<Req> for MeteredService<S, C>
+where
+    S: Service<Req>,
+    C: Collector,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    #[inline]
+    async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
+        let mut guard = FinishGuard {
+            collector: &self.collector,
+            name: self.name,
+            start: Some(self.collector.start(self.name)),
+        };
+        let res = self.service.call(req).await;
+        guard.finish(if res.is_ok() { Outcome::Ok } else { Outcome::Err });
+        res
+    }
+}
+
+// finish call with Outcome::Cancelled when call future is dropped before inner service returns.
+struct FinishGuard<'a, C>
+where
+    C: Collector,
+{
+    collector: &'a C,
+    name: &'static str,
+    start: Option<C::Start>,
+}
+
+impl<C> FinishGuard<'_, C>
+where
+    C: Collector,
+{
+    fn finish(&mut self, outcome: Outcome) {
+        if let Some(start) = self.start.take() {
+            self.collector.finish(self.name, start, outcome);
+        }
+    }
+}
+
+impl<C> Drop for FinishGuard<'_, C>
+where
+    C: Collector,
+{
+    fn drop(&mut self) {
+        self.finish(Outcome::Cancelled);
+    }
+}
+
+impl<S, C> ReadyService for MeteredService<S, C>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{
+        cell::Cell,
+        future::{pending, Future},
+        pin::pin,
+        task::{Context, Waker},
+    };
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{fn_service, ServiceExt};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Counter {
+        start: Cell<usize>,
+        ok: Cell<usize>,
+        err: Cell<usize>,
+        cancelled: Cell<usize>,
+    }
+
+    impl Collector for Counter {
+        type Start = usize;
+
+        fn start(&self, name: &'static str) -> Self::Start {
+            assert_eq!(name, "test");
+            self.start.set(self.start.get() + 1);
+            self.start.get()
+        }
+
+        fn finish(&self, _: &'static str, start: Self::Start, outcome: Outcome) {
+            assert_eq!(start, self.start.get());
+            let count = match outcome {
+                Outcome::Ok => &self.ok,
+                Outcome::Err => &self.err,
+                Outcome::Cancelled => &self.cancelled,
+            };
+            count.set(count.get() + 1);
+        }
+    }
+
+    #[test]
+    fn metered() {
+        let counter = Counter::default();
+
+        let service = fn_service(|ok: bool| async move {
+            if ok {
+                Ok(())
+            } else {
+                Err(())
+            }
+        })
+        .enclosed(Metered::new("test", &counter))
+        .call(())
+        .now_or_panic()
+        .unwrap();
+
+        service.call(true).now_or_panic().unwrap();
+        service.call(false).now_or_panic().unwrap_err();
+        service.call(true).now_or_panic().unwrap();
+
+        assert_eq!(counter.start.get(), 3);
+        assert_eq!(counter.ok.get(), 2);
+        assert_eq!(counter.err.get(), 1);
+        assert_eq!(counter.cancelled.get(), 0);
+
+        let service = fn_service(|_: ()| async { Ok::<_, ()>(()) })
+            .enclosed(Metered::new("noop", ()))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+        service.call(()).now_or_panic().unwrap();
+    }
+
+    #[test]
+    fn cancelled() {
+        let counter = Counter::default();
+
+        let service = fn_service(|_: ()| pending::<Result<(), ()>>())
+            .enclosed(Metered::new("test", &counter))
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        {
+            let mut fut = pin!(service.call(()));
+            assert!(fut.as_mut().poll(&mut Context::from_waker(Waker::noop())).is_pending());
+            assert_eq!(counter.start.get(), 1);
+            assert_eq!(counter.cancelled.get(), 0);
+        }
+
+        assert_eq!(counter.cancelled.get(), 1);
+        assert_eq!(counter.ok.get(), 0);
+        assert_eq!(counter.err.get(), 0);
+    }
+}
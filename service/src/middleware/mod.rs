@@ -3,9 +3,15 @@
 //! [ServiceExt::enclosed]: crate::service::ServiceExt::enclosed
 
 mod async_fn;
+#[cfg(feature = "alloc")]
+mod concurrency_limit;
 mod group;
+mod metered;
 mod unchecked_ready;
 
 pub use async_fn::AsyncFn;
+#[cfg(feature = "alloc")]
+pub use concurrency_limit::{ConcurrencyLimit, ConcurrencyLimitService, Overloaded};
 pub use group::Group;
+pub use metered::{Collector, Metered, MeteredService, Outcome};
 pub use unchecked_ready::UncheckedReady;
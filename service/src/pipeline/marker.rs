@@ -6,5 +6,7 @@ pub struct BuildMapErr;
 pub struct MapErr;
 pub struct BuildAndThen;
 pub struct AndThen;
+pub struct BuildOrElse;
+pub struct OrElse;
 pub struct BuildEnclosed;
 pub struct AsyncFn;
@@ -5,6 +5,7 @@ mod enclosed_fn;
 mod function;
 mod map;
 mod map_err;
+mod or_else;
 
 use core::{future::Future, ops::Deref, pin::Pin};
 
@@ -0,0 +1,17 @@
+use crate::pipeline::{marker::OrElse, PipelineT};
+
+use super::ReadyService;
+
+impl<S, S1, P> ReadyService for PipelineT<S, (S1, P), OrElse>
+where
+    S: ReadyService,
+    S1: ReadyService,
+{
+    type Ready = PipelineT<S::Ready, S1::Ready>;
+
+    async fn ready(&self) -> Self::Ready {
+        let first = self.first.ready().await;
+        let second = self.second.0.ready().await;
+        PipelineT::new(first, second)
+    }
+}
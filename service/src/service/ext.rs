@@ -26,6 +26,17 @@ pub trait ServiceExt<Arg>: Service<Arg> {
         self.enclosed(middleware::AsyncFn(func))
     }
 
+    /// Enclose Self with [middleware::ConcurrencyLimit] limiting number of concurrent calls to given
+    /// limit. Exceeding calls wait in queue for a permit. See [middleware::ConcurrencyLimit::load_shed]
+    /// for rejecting them instead.
+    #[cfg(feature = "alloc")]
+    fn concurrency_limit(self, limit: usize) -> PipelineT<Self, middleware::ConcurrencyLimit, marker::BuildEnclosed>
+    where
+        Self: Sized,
+    {
+        self.enclosed(middleware::ConcurrencyLimit::new(limit))
+    }
+
     /// Mutate `<<Self::Response as Service<Req>>::Future as Future>::Output` type with given
     /// closure.
     fn map<F, Res, ResMap>(self, mapper: F) -> PipelineT<Self, F, marker::BuildMap>
@@ -54,6 +65,43 @@ pub trait ServiceExt<Arg>: Service<Arg> {
     {
         PipelineT::new(self, factory)
     }
+
+    /// Chain another service factory who's service would be called with the same request when `Self`'s
+    /// service returns error and given predicate returns true for it. Otherwise the output of `Self`'s
+    /// service is returned as is.
+    ///
+    /// Both services must share the same `Service::Response` and `Service::Error` types and the request
+    /// type must implement [Reborrow] trait. Request type implementing [Clone] is supported by default.
+    ///
+    /// [Reborrow]: crate::Reborrow
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_service::{fn_service, Service, ServiceExt};
+    /// # async fn or_else() {
+    /// let service = fn_service(|req: &'static str| async move {
+    ///     match req {
+    ///         "/" => Ok("index"),
+    ///         _ => Err("not found"),
+    ///     }
+    /// })
+    /// // fall through to the second service when first one can't find a match.
+    /// .or_else(fn_service(|_: &'static str| async { Ok("fallback") }), |e: &&str| *e == "not found")
+    /// .call(())
+    /// .await
+    /// .unwrap();
+    ///
+    /// assert_eq!(service.call("/").await, Ok("index"));
+    /// assert_eq!(service.call("/foo").await, Ok("fallback"));
+    /// # }
+    /// ```
+    fn or_else<F, P>(self, factory: F, predicate: P) -> PipelineT<Self, (F, P), marker::BuildOrElse>
+    where
+        F: Service<Arg>,
+        Self: Sized,
+    {
+        PipelineT::new(self, (factory, predicate))
+    }
 }
 
 impl<S, Arg> ServiceExt<Arg> for S where S: Service<Arg> {}
@@ -153,6 +201,27 @@ mod test {
         assert_eq!(res, "251");
     }
 
+    #[test]
+    fn or_else() {
+        let service = fn_service(|req: &'static str| async move {
+            match req {
+                "996" => Ok("996"),
+                "251" => Err(251),
+                _ => Err(404),
+            }
+        })
+        .or_else(fn_service(|_: &'static str| async { Ok("fallback") }), |e: &u16| {
+            *e == 404
+        })
+        .call(())
+        .now_or_panic()
+        .unwrap();
+
+        assert_eq!(service.call("996").now_or_panic(), Ok("996"));
+        assert_eq!(service.call("251").now_or_panic(), Err(251));
+        assert_eq!(service.call("007").now_or_panic(), Ok("fallback"));
+    }
+
     #[cfg(feature = "alloc")]
     #[test]
     fn enclosed_opt() {
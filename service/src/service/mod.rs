@@ -5,10 +5,12 @@ mod function;
 mod map;
 mod map_err;
 mod opt;
+mod or_else;
 
 pub use self::{
     ext::ServiceExt,
     function::{fn_build, fn_service, FnService},
+    or_else::Reborrow,
 };
 
 use core::{future::Future, ops::Deref, pin::Pin};
@@ -0,0 +1,77 @@
+use core::future::Future;
+
+use crate::pipeline::{
+    marker::{BuildOrElse, OrElse},
+    PipelineT,
+};
+
+use super::Service;
+
+/// Trait for request type that can be passed to the first service of [ServiceExt::or_else] combinator
+/// without losing it's ownership. The request is passed to the second service afterwards when the first
+/// one returned matching error.
+///
+/// Request type implementing [Clone] is cloned. Request type referencing other data (like web context
+/// with lifetime) can implement it by reborrowing itself.
+///
+/// [ServiceExt::or_else]: crate::ServiceExt::or_else
+pub trait Reborrow<S>: Sized {
+    /// The Ok part of output future of first service.
+    type Response;
+
+    /// The Err part of output future of first service.
+    type Error;
+
+    /// call given service with reborrowed request.
+    fn call_reborrow(&mut self, service: &S) -> impl Future<Output = Result<Self::Response, Self::Error>>;
+}
+
+impl<S, Req> Reborrow<S> for Req
+where
+    S: Service<Req>,
+    Req: Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    #[inline]
+    fn call_reborrow(&mut self, service: &S) -> impl Future<Output = Result<Self::Response, Self::Error>> {
+        service.call(self.clone())
+    }
+}
+
+impl<SF, Arg, SF1, P> Service<Arg> for PipelineT<SF, (SF1, P), BuildOrElse>
+where
+    SF: Service<Arg>,
+    Arg: Clone,
+    SF1: Service<Arg>,
+    SF1::Error: From<SF::Error>,
+    P: Clone,
+{
+    type Response = PipelineT<SF::Response, (SF1::Response, P), OrElse>;
+    type Error = SF1::Error;
+
+    async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
+        let first = self.first.call(arg.clone()).await?;
+        let second = self.second.0.call(arg).await?;
+        Ok(PipelineT::new(first, (second, self.second.1.clone())))
+    }
+}
+
+impl<S, Req, S1, P> Service<Req> for PipelineT<S, (S1, P), OrElse>
+where
+    Req: Reborrow<S>,
+    S1: Service<Req, Response = Req::Response, Error = Req::Error>,
+    P: Fn(&Req::Error) -> bool,
+{
+    type Response = Req::Response;
+    type Error = Req::Error;
+
+    #[inline]
+    async fn call(&self, mut req: Req) -> Result<Self::Response, Self::Error> {
+        match req.call_reborrow(&self.first).await {
+            Err(ref e) if (self.second.1)(e) => self.second.0.call(req).await,
+            res => res,
+        }
+    }
+}
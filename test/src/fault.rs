@@ -0,0 +1,460 @@
+//! fault injection io types for testing timeout, retry and error handling code paths.
+//!
+//! [FaultyStream] wraps any [AsyncIo] type and inject delay, partial write, connection reset and
+//! stall according to [Fault] configuration. [pipe] offers an in memory [AsyncIo] pair so faulty
+//! stream can be tested without real socket.
+
+use std::{
+    future::{poll_fn, Future},
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use tokio::time::{sleep, Sleep};
+use xitca_io::{
+    bytes::{Buf, BytesMut},
+    io::{AsyncIo, Interest, Ready},
+};
+use xitca_service::{ready::ReadyService, Service};
+
+/// configuration of injected faults. by default no fault is injected.
+#[derive(Clone, Debug, Default)]
+pub struct Fault {
+    read_delay: Option<Duration>,
+    write_delay: Option<Duration>,
+    max_write_size: Option<usize>,
+    reset_read_after: Option<usize>,
+    reset_write_after: Option<usize>,
+    stall_read_after: Option<usize>,
+}
+
+impl Fault {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// delay every read readiness by given duration. simulate slow peer.
+    pub fn read_delay(mut self, dur: Duration) -> Self {
+        self.read_delay = Some(dur);
+        self
+    }
+
+    /// delay every write readiness by given duration. simulate congested peer.
+    pub fn write_delay(mut self, dur: Duration) -> Self {
+        self.write_delay = Some(dur);
+        self
+    }
+
+    /// cap the bytes accepted by single write. simulate partial write.
+    ///
+    /// # Panics
+    /// when size is 0.
+    pub fn max_write_size(mut self, size: usize) -> Self {
+        assert_ne!(size, 0, "max_write_size must be non zero");
+        self.max_write_size = Some(size);
+        self
+    }
+
+    /// fail read with [io::ErrorKind::ConnectionReset] after given amount of bytes are read.
+    pub fn reset_read_after(mut self, bytes: usize) -> Self {
+        self.reset_read_after = Some(bytes);
+        self
+    }
+
+    /// fail write with [io::ErrorKind::ConnectionReset] after given amount of bytes are written.
+    pub fn reset_write_after(mut self, bytes: usize) -> Self {
+        self.reset_write_after = Some(bytes);
+        self
+    }
+
+    /// never become readable again after given amount of bytes are read.
+    ///
+    /// stall at 0 bytes simulate a peer stuck before sending anything. (tls handshake or request
+    /// head stall for example)
+    pub fn stall_read_after(mut self, bytes: usize) -> Self {
+        self.stall_read_after = Some(bytes);
+        self
+    }
+}
+
+/// [AsyncIo] wrapper injecting faults described by [Fault].
+pub struct FaultyStream<Io> {
+    io: Io,
+    fault: Fault,
+    read: usize,
+    written: usize,
+    read_delay: Delay,
+    write_delay: Delay,
+}
+
+impl<Io> FaultyStream<Io> {
+    pub fn new(io: Io, fault: Fault) -> Self {
+        Self {
+            io,
+            fault,
+            read: 0,
+            written: 0,
+            read_delay: Delay::default(),
+            write_delay: Delay::default(),
+        }
+    }
+
+    /// total bytes read from inner io.
+    pub fn read_bytes(&self) -> usize {
+        self.read
+    }
+
+    /// total bytes written to inner io.
+    pub fn written_bytes(&self) -> usize {
+        self.written
+    }
+
+    pub fn into_inner(self) -> Io {
+        self.io
+    }
+
+    fn read_stalled(&self) -> bool {
+        self.fault.stall_read_after.is_some_and(|n| self.read >= n)
+    }
+
+    // remaining bytes allowed to be read before a fault kicks in.
+    fn read_budget(&self) -> Option<usize> {
+        let reset = self.fault.reset_read_after.map(|n| n.saturating_sub(self.read));
+        let stall = self.fault.stall_read_after.map(|n| n.saturating_sub(self.read));
+        match (reset, stall) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Delay {
+    sleep: Option<Pin<Box<Sleep>>>,
+    done: bool,
+}
+
+impl Delay {
+    // poll delay timer. return true when io operation can proceed.
+    fn poll(&mut self, dur: Option<Duration>, cx: &mut Context<'_>) -> bool {
+        let Some(dur) = dur else { return true };
+
+        if self.done {
+            return true;
+        }
+
+        let sleep = self.sleep.get_or_insert_with(|| Box::pin(sleep(dur)));
+
+        if sleep.as_mut().poll(cx).is_ready() {
+            self.sleep = None;
+            self.done = true;
+        }
+
+        self.done
+    }
+
+    // rearm delay after io operation made progress.
+    fn reset(&mut self) {
+        self.done = false;
+    }
+}
+
+impl<Io> io::Read for FaultyStream<Io>
+where
+    Io: io::Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.fault.reset_read_after.is_some_and(|n| self.read >= n) {
+            return Err(io::ErrorKind::ConnectionReset.into());
+        }
+
+        if self.read_stalled() || (self.fault.read_delay.is_some() && !self.read_delay.done) {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+
+        let len = self.read_budget().map_or(buf.len(), |n| n.min(buf.len()));
+        let n = self.io.read(&mut buf[..len])?;
+        self.read += n;
+        self.read_delay.reset();
+        Ok(n)
+    }
+}
+
+impl<Io> io::Write for FaultyStream<Io>
+where
+    Io: io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.fault.reset_write_after.is_some_and(|n| self.written >= n) {
+            return Err(io::ErrorKind::ConnectionReset.into());
+        }
+
+        if self.fault.write_delay.is_some() && !self.write_delay.done {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+
+        let mut len = self.fault.max_write_size.map_or(buf.len(), |n| n.min(buf.len()));
+        if let Some(n) = self.fault.reset_write_after {
+            len = len.min(n - self.written);
+        }
+
+        let n = self.io.write(&buf[..len])?;
+        self.written += n;
+        self.write_delay.reset();
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.io.flush()
+    }
+}
+
+impl<Io> AsyncIo for FaultyStream<Io>
+where
+    Io: AsyncIo + Send,
+{
+    async fn ready(&mut self, interest: Interest) -> io::Result<Ready> {
+        poll_fn(|cx| self.poll_ready(interest, cx)).await
+    }
+
+    fn poll_ready(&mut self, interest: Interest, cx: &mut Context<'_>) -> Poll<io::Result<Ready>> {
+        let mut ready = Ready::EMPTY;
+        let mut inner = None;
+
+        if interest.is_readable() {
+            // reset fault is exposed through io::Read so report readiness eagerly.
+            if self.fault.reset_read_after.is_some_and(|n| self.read >= n) {
+                ready |= Ready::READABLE;
+            } else if !self.read_stalled() && self.read_delay.poll(self.fault.read_delay, cx) {
+                inner = Some(Interest::READABLE);
+            }
+        }
+
+        if interest.is_writable() {
+            if self.fault.reset_write_after.is_some_and(|n| self.written >= n) {
+                ready |= Ready::WRITABLE;
+            } else if self.write_delay.poll(self.fault.write_delay, cx) {
+                inner = Some(inner.map_or(Interest::WRITABLE, |i| i | Interest::WRITABLE));
+            }
+        }
+
+        if let Some(interest) = inner {
+            match self.io.poll_ready(interest, cx) {
+                Poll::Ready(Ok(r)) => ready |= r,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => {}
+            }
+        }
+
+        if ready.is_empty() {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(ready))
+        }
+    }
+
+    fn is_vectored_write(&self) -> bool {
+        // partial write and reset are applied in io::Write::write which vectored write falls back to.
+        false
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_shutdown(cx)
+    }
+}
+
+/// middleware wrapping io type of incoming connection with [FaultyStream].
+///
+/// # Examples
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_http::HttpServiceBuilder;
+/// # use xitca_service::{fn_service, ServiceExt};
+/// # use xitca_io::net::TcpStream;
+/// # use xitca_test::fault::{Fault, FaultyStream, InjectFault};
+/// # use xitca_http::{body::ResponseBody, h1::RequestBody, http::{Request, RequestExt, Response}};
+/// async fn handle(_: Request<RequestExt<RequestBody>>) -> Result<Response<ResponseBody>, xitca_test::Error> {
+///     Ok(Response::new(ResponseBody::empty()))
+/// }
+///
+/// // every accepted connection would delay it's read by 100 milliseconds.
+/// let service = fn_service(handle)
+///     .enclosed(HttpServiceBuilder::h1().with_stream::<FaultyStream<TcpStream>>())
+///     .enclosed(InjectFault::new(Fault::new().read_delay(Duration::from_millis(100))));
+/// ```
+#[derive(Clone)]
+pub struct InjectFault {
+    fault: Fault,
+}
+
+impl InjectFault {
+    pub fn new(fault: Fault) -> Self {
+        Self { fault }
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for InjectFault {
+    type Response = InjectFaultService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| InjectFaultService {
+            service,
+            fault: self.fault.clone(),
+        })
+    }
+}
+
+pub struct InjectFaultService<S> {
+    service: S,
+    fault: Fault,
+}
+
+impl<S, Io> Service<(Io, SocketAddr)> for InjectFaultService<S>
+where
+    S: Service<(FaultyStream<Io>, SocketAddr)>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    async fn call(&self, (io, addr): (Io, SocketAddr)) -> Result<Self::Response, Self::Error> {
+        self.service
+            .call((FaultyStream::new(io, self.fault.clone()), addr))
+            .await
+    }
+}
+
+impl<S> ReadyService for InjectFaultService<S>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
+
+/// construct a pair of connected in memory [AsyncIo] types. bytes written to one end can be read
+/// from the other end.
+pub fn pipe() -> (PipeStream, PipeStream) {
+    let a = Arc::new(Mutex::new(Pipe::default()));
+    let b = Arc::new(Mutex::new(Pipe::default()));
+    (
+        PipeStream {
+            read: a.clone(),
+            write: b.clone(),
+        },
+        PipeStream { read: b, write: a },
+    )
+}
+
+#[derive(Default)]
+struct Pipe {
+    buf: BytesMut,
+    waker: Option<Waker>,
+    closed: bool,
+}
+
+impl Pipe {
+    fn close(&mut self) {
+        self.closed = true;
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// one end of in memory pipe. see [pipe] for detail.
+pub struct PipeStream {
+    read: Arc<Mutex<Pipe>>,
+    write: Arc<Mutex<Pipe>>,
+}
+
+impl io::Read for PipeStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut pipe = self.read.lock().unwrap();
+        if pipe.buf.is_empty() {
+            return if pipe.closed {
+                Ok(0)
+            } else {
+                Err(io::ErrorKind::WouldBlock.into())
+            };
+        }
+        let len = pipe.buf.len().min(buf.len());
+        buf[..len].copy_from_slice(&pipe.buf[..len]);
+        pipe.buf.advance(len);
+        Ok(len)
+    }
+}
+
+impl io::Write for PipeStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut pipe = self.write.lock().unwrap();
+        if pipe.closed {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        pipe.buf.extend_from_slice(buf);
+        if let Some(waker) = pipe.waker.take() {
+            waker.wake();
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl AsyncIo for PipeStream {
+    async fn ready(&mut self, interest: Interest) -> io::Result<Ready> {
+        poll_fn(|cx| self.poll_ready(interest, cx)).await
+    }
+
+    fn poll_ready(&mut self, interest: Interest, cx: &mut Context<'_>) -> Poll<io::Result<Ready>> {
+        let mut ready = Ready::EMPTY;
+
+        if interest.is_readable() {
+            let mut pipe = self.read.lock().unwrap();
+            if pipe.closed {
+                ready |= Ready::READABLE | Ready::READ_CLOSED;
+            } else if !pipe.buf.is_empty() {
+                ready |= Ready::READABLE;
+            } else {
+                pipe.waker = Some(cx.waker().clone());
+            }
+        }
+
+        // pipe buffer is unbounded and always writable.
+        if interest.is_writable() {
+            ready |= Ready::WRITABLE;
+        }
+
+        if ready.is_empty() {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(ready))
+        }
+    }
+
+    fn is_vectored_write(&self) -> bool {
+        false
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.write.lock().unwrap().close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Drop for PipeStream {
+    fn drop(&mut self) {
+        self.read.lock().unwrap().close();
+        self.write.lock().unwrap().close();
+    }
+}
@@ -1,3 +1,5 @@
+pub mod fault;
+
 use std::{
     error, fmt, fs,
     future::Future,
@@ -10,11 +12,12 @@ use std::{
 };
 
 use futures_util::Stream;
+use xitca_client::{Client, RequestBuilder};
 use xitca_http::{
     body::ResponseBody,
     config::HttpServiceConfig,
     h1, h2, h3,
-    http::{Request, RequestExt, Response},
+    http::{header::AsHeaderName, Request, RequestExt, Response, StatusCode},
     HttpServiceBuilder,
 };
 use xitca_io::{
@@ -31,6 +34,15 @@ type HResponse<B> = Response<ResponseBody<B>>;
 /// A general test server for any given service type that accept the connection from
 /// xitca-server
 pub fn test_server<T, Req>(service: T) -> Result<TestServerHandle, Error>
+where
+    T: Service + Send + Sync + 'static,
+    T::Response: ReadyService + Service<Req>,
+    Req: TryFrom<NetStream> + 'static,
+{
+    test_server_with(Builder::new(), service)
+}
+
+fn test_server_with<T, Req>(builder: Builder, service: T) -> Result<TestServerHandle, Error>
 where
     T: Service + Send + Sync + 'static,
     T::Response: ReadyService + Service<Req>,
@@ -40,7 +52,7 @@ where
 
     let addr = lst.local_addr()?;
 
-    let handle = Builder::new()
+    let handle = builder
         .worker_threads(1)
         .server_threads(1)
         .disable_signal()
@@ -60,19 +72,52 @@ where
     B: Stream<Item = Result<Bytes, E>> + 'static,
     E: fmt::Debug + 'static,
 {
+    let builder = Builder::new();
+    let draining = builder.draining_state();
+
     #[cfg(not(feature = "io-uring"))]
     {
-        test_server::<_, (TcpStream, SocketAddr)>(service.enclosed(HttpServiceBuilder::h1()))
+        test_server_with::<_, (TcpStream, SocketAddr)>(
+            builder,
+            service.enclosed(HttpServiceBuilder::h1().draining_state(draining)),
+        )
     }
 
     #[cfg(feature = "io-uring")]
     {
-        test_server::<_, (xitca_io::net::io_uring::TcpStream, SocketAddr)>(
-            service.enclosed(HttpServiceBuilder::h1().io_uring()),
+        test_server_with::<_, (xitca_io::net::io_uring::TcpStream, SocketAddr)>(
+            builder,
+            service.enclosed(HttpServiceBuilder::h1().draining_state(draining).io_uring()),
         )
     }
 }
 
+/// A specialized http/1 server on top of [test_server] where every accepted connection is wrapped
+/// by [fault::FaultyStream] with given [fault::Fault] configuration.
+pub fn test_h1_server_with_fault<T, B, E>(service: T, fault: fault::Fault) -> Result<TestServerHandle, Error>
+where
+    T: Service + Send + Sync + 'static,
+    T::Response: ReadyService + Service<Request<RequestExt<h1::RequestBody>>, Response = HResponse<B>> + 'static,
+    <T::Response as Service<Request<RequestExt<h1::RequestBody>>>>::Error: fmt::Debug,
+    T::Error: error::Error + 'static,
+    B: Stream<Item = Result<Bytes, E>> + 'static,
+    E: fmt::Debug + 'static,
+{
+    let builder = Builder::new();
+    let draining = builder.draining_state();
+
+    test_server_with::<_, (TcpStream, SocketAddr)>(
+        builder,
+        service
+            .enclosed(
+                HttpServiceBuilder::h1()
+                    .with_stream::<fault::FaultyStream<TcpStream>>()
+                    .draining_state(draining),
+            )
+            .enclosed(fault::InjectFault::new(fault)),
+    )
+}
+
 /// A specialized http/2 server on top of [test_server]
 pub fn test_h2_server<T, B, E>(service: T) -> Result<TestServerHandle, Error>
 where
@@ -83,14 +128,20 @@ where
     B: Stream<Item = Result<Bytes, E>> + 'static,
     E: fmt::Debug + 'static,
 {
-    test_server::<_, (TcpStream, SocketAddr)>(
+    let builder = Builder::new();
+    let draining = builder.draining_state();
+
+    test_server_with::<_, (TcpStream, SocketAddr)>(
+        builder,
         service.enclosed(
-            HttpServiceBuilder::h2().config(
-                HttpServiceConfig::new()
-                    .request_head_timeout(Duration::from_millis(500))
-                    .tls_accept_timeout(Duration::from_millis(500))
-                    .keep_alive_timeout(Duration::from_millis(500)),
-            ),
+            HttpServiceBuilder::h2()
+                .config(
+                    HttpServiceConfig::new()
+                        .request_head_timeout(Duration::from_millis(500))
+                        .tls_accept_timeout(Duration::from_millis(500))
+                        .keep_alive_timeout(Duration::from_millis(500)),
+                )
+                .draining_state(draining),
         ),
     )
 }
@@ -107,6 +158,22 @@ where
 {
     let addr = std::net::UdpSocket::bind("127.0.0.1:0")?.local_addr()?;
 
+    let config = test_quic_config()?;
+
+    let listener = xitca_io::net::QuicListenerBuilder::new(addr, config);
+
+    let handle = Builder::new()
+        .worker_threads(1)
+        .server_threads(1)
+        .disable_signal()
+        .listen("test_server", listener, service.enclosed(HttpServiceBuilder::h3()))
+        .build();
+
+    Ok(TestServerHandle { addr, handle })
+}
+
+/// quic server config with self signed certificate for testing http/3 server.
+pub fn test_quic_config() -> Result<xitca_io::net::QuicConfig, Error> {
     let key = fs::read("../examples/cert/key.pem")?;
     let cert = fs::read("../examples/cert/cert.pem")?;
 
@@ -123,18 +190,7 @@ where
 
     let config = h3_quinn::quinn::crypto::rustls::QuicServerConfig::try_from(config).unwrap();
 
-    let config = h3_quinn::quinn::ServerConfig::with_crypto(std::sync::Arc::new(config));
-
-    let listener = xitca_io::net::QuicListenerBuilder::new(addr, config);
-
-    let handle = Builder::new()
-        .worker_threads(1)
-        .server_threads(1)
-        .disable_signal()
-        .listen("test_server", listener, service.enclosed(HttpServiceBuilder::h3()))
-        .build();
-
-    Ok(TestServerHandle { addr, handle })
+    Ok(h3_quinn::quinn::ServerConfig::with_crypto(std::sync::Arc::new(config)))
 }
 
 pub struct TestServerHandle {
@@ -154,6 +210,94 @@ impl TestServerHandle {
     pub fn try_handle(&mut self) -> io::Result<ServerHandle> {
         self.handle.handle()
     }
+
+    /// construct http url targeting the bound address with given path.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{path}", self.ip_port_string())
+    }
+
+    /// construct a [TestClient] targeting the bound address.
+    pub fn client(&self) -> TestClient {
+        TestClient {
+            client: Client::new(),
+            base: self.url(""),
+        }
+    }
+}
+
+/// A thin http client targeting the address of a [TestServerHandle].
+///
+/// Request methods take path instead of full url and return [RequestBuilder] from xitca-client
+/// for further customization. (http version, headers, body etc)
+pub struct TestClient {
+    client: Client,
+    base: String,
+}
+
+macro_rules! method {
+    ($method: tt) => {
+        pub fn $method(&self, path: &str) -> RequestBuilder<'_> {
+            self.client.$method(format!("{}{path}", self.base))
+        }
+    };
+}
+
+impl TestClient {
+    method!(get);
+    method!(post);
+    method!(put);
+    method!(patch);
+    method!(delete);
+    method!(head);
+
+    /// reference to inner client for constructing request with full url.
+    pub fn inner(&self) -> &Client {
+        &self.client
+    }
+}
+
+/// Assertion and body collecting helpers for [xitca_client::Response].
+pub trait ResponseExt: Sized {
+    /// assert response status code equals to given one.
+    ///
+    /// # Panics
+    /// when status code is not matching.
+    #[track_caller]
+    fn assert_status(self, status: StatusCode) -> Self;
+
+    /// get response header value as str by name. return None when header is absent or not visible ascii.
+    fn header(&self, name: impl AsHeaderName) -> Option<&str>;
+
+    /// collect response body as String.
+    fn text(self) -> impl Future<Output = Result<String, Error>>;
+
+    /// collect response body and deserialize it as json.
+    fn json<T>(self) -> impl Future<Output = Result<T, Error>>
+    where
+        T: serde::de::DeserializeOwned;
+}
+
+impl<const PAYLOAD_LIMIT: usize> ResponseExt for xitca_client::Response<PAYLOAD_LIMIT> {
+    #[track_caller]
+    fn assert_status(self, status: StatusCode) -> Self {
+        assert_eq!(self.status(), status, "unexpected response status code");
+        self
+    }
+
+    fn header(&self, name: impl AsHeaderName) -> Option<&str> {
+        self.headers().get(name).and_then(|v| v.to_str().ok())
+    }
+
+    async fn text(self) -> Result<String, Error> {
+        self.string().await.map_err(Into::into)
+    }
+
+    async fn json<T>(self) -> Result<T, Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        xitca_client::Response::json(self).await.map_err(Into::into)
+    }
 }
 
 impl Future for TestServerHandle {
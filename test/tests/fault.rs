@@ -0,0 +1,149 @@
+use std::{
+    future::poll_fn,
+    io::{Read, Write},
+    net::SocketAddr,
+    time::Duration,
+};
+
+use tokio::task::LocalSet;
+use xitca_client::Client;
+use xitca_http::{
+    body::ResponseBody,
+    bytes::Bytes,
+    config::HttpServiceConfig,
+    h1,
+    http::{Request, RequestExt, Response, StatusCode, Version},
+    HttpServiceBuilder,
+};
+use xitca_io::io::{AsyncIo, Interest};
+use xitca_service::{fn_service, Service, ServiceExt};
+use xitca_test::{
+    fault::{pipe, Fault, FaultyStream, PipeStream},
+    test_h1_server_with_fault, Error,
+};
+
+const ADDR: SocketAddr = SocketAddr::V4(std::net::SocketAddrV4::new(std::net::Ipv4Addr::LOCALHOST, 8080));
+
+#[tokio::test]
+async fn h1_partial_write() -> Result<(), Error> {
+    // h1 service spawns local task for date header.
+    LocalSet::new()
+        .run_until(async {
+            let service = fn_service(handle)
+                .enclosed(HttpServiceBuilder::h1().with_stream::<FaultyStream<PipeStream>>())
+                .call(())
+                .await
+                .unwrap();
+
+            let (server, mut client) = pipe();
+            let server = FaultyStream::new(server, Fault::new().max_write_size(1));
+
+            client.write_all(b"GET / HTTP/1.1\r\nconnection: close\r\n\r\n")?;
+
+            let (res, buf) = tokio::join!(service.call((server, ADDR)), read_to_end(&mut client));
+            assert!(res.is_ok());
+
+            let buf = String::from_utf8(buf?)?;
+            assert!(buf.starts_with("HTTP/1.1 200 OK"));
+            assert!(buf.ends_with("GET Response"));
+
+            Ok(())
+        })
+        .await
+}
+
+#[tokio::test]
+async fn h1_stall_request_head() -> Result<(), Error> {
+    // h1 service spawns local task for date header.
+    LocalSet::new()
+        .run_until(async {
+            let service = fn_service(handle)
+                .enclosed(
+                    HttpServiceBuilder::h1()
+                        .with_stream::<FaultyStream<PipeStream>>()
+                        .config(
+                            HttpServiceConfig::new()
+                                .tls_accept_timeout(Duration::from_millis(100))
+                                .request_head_timeout(Duration::from_millis(100)),
+                        ),
+                )
+                .call(())
+                .await
+                .unwrap();
+
+            let (server, mut client) = pipe();
+            let server = FaultyStream::new(server, Fault::new().stall_read_after(4));
+
+            client.write_all(b"GET / HTTP/1.1\r\n\r\n")?;
+
+            // server only observes partial request head and must give up after timeout.
+            let res = tokio::time::timeout(Duration::from_secs(1), service.call((server, ADDR))).await;
+            assert!(res.is_ok());
+
+            Ok(())
+        })
+        .await
+}
+
+#[tokio::test]
+async fn h1_read_reset() -> Result<(), Error> {
+    // h1 service spawns local task for date header.
+    LocalSet::new()
+        .run_until(async {
+            let service = fn_service(handle)
+                .enclosed(HttpServiceBuilder::h1().with_stream::<FaultyStream<PipeStream>>())
+                .call(())
+                .await
+                .unwrap();
+
+            let (server, mut client) = pipe();
+            let server = FaultyStream::new(server, Fault::new().reset_read_after(4));
+
+            client.write_all(b"GET / HTTP/1.1\r\n\r\n")?;
+
+            assert!(service.call((server, ADDR)).await.is_err());
+
+            Ok(())
+        })
+        .await
+}
+
+#[tokio::test]
+async fn h1_slow_server() -> Result<(), Error> {
+    let fault = Fault::new()
+        .read_delay(Duration::from_millis(10))
+        .write_delay(Duration::from_millis(10))
+        .max_write_size(7);
+
+    let mut handle = test_h1_server_with_fault(fn_service(handle), fault)?;
+
+    let c = Client::new();
+
+    let res = c.get(&handle.url("/")).version(Version::HTTP_11).send().await?;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.string().await?, "GET Response");
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+async fn read_to_end(io: &mut PipeStream) -> std::io::Result<Vec<u8>> {
+    let mut res = Vec::new();
+    let mut buf = [0; 64];
+    loop {
+        poll_fn(|cx| io.poll_ready(Interest::READABLE, cx)).await?;
+        match io.read(&mut buf) {
+            Ok(0) => return Ok(res),
+            Ok(n) => res.extend_from_slice(&buf[..n]),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+async fn handle(_: Request<RequestExt<h1::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
+    Ok(Response::new(Bytes::from("GET Response").into()))
+}
@@ -9,14 +9,16 @@ use xitca_client::Client;
 use xitca_http::{
     body::{BoxBody, ResponseBody},
     bytes::{Bytes, BytesMut},
+    config::HttpServiceConfig,
     h1,
     http::{
         header::{self, HeaderValue, CONNECTION},
-        Method, Request, RequestExt, Response, Version,
+        Method, Request, RequestExt, Response, StatusCode, Version,
     },
+    HttpServiceBuilder,
 };
-use xitca_service::fn_service;
-use xitca_test::{test_h1_server, Error};
+use xitca_service::{fn_service, ServiceExt};
+use xitca_test::{test_h1_server, test_server, Error, ResponseExt};
 
 #[tokio::test]
 async fn h1_get() -> Result<(), Error> {
@@ -41,6 +43,82 @@ async fn h1_get() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn h1_happy_eyeballs() -> Result<(), Error> {
+    use std::net::{SocketAddr, TcpListener};
+
+    use xitca_client::{Connect, Service};
+
+    // resolver producing a closed address before the address of test server.
+    struct Resolver(SocketAddr, SocketAddr);
+
+    impl<'r, 'c> Service<&'r mut Connect<'c>> for Resolver {
+        type Response = ();
+        type Error = xitca_client::error::Error;
+
+        async fn call(&self, connect: &'r mut Connect<'c>) -> Result<Self::Response, Self::Error> {
+            connect.set_addrs([self.0, self.1]);
+            Ok(())
+        }
+    }
+
+    let closed = TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let c = Client::builder()
+        .resolver(Resolver(closed, handle.addr()))
+        .happy_eyeballs(true)
+        .finish();
+
+    let res = c
+        .get(format!("http://localhost:{}/", handle.addr().port()))
+        .version(Version::HTTP_11)
+        .send()
+        .await?;
+    assert_eq!(res.status().as_u16(), 200);
+    assert_eq!("GET Response", res.string().await?);
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_test_client() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let c = handle.client();
+
+    let body = c
+        .get("/")
+        .version(Version::HTTP_11)
+        .send()
+        .await?
+        .assert_status(StatusCode::OK)
+        .text()
+        .await?;
+    assert_eq!("GET Response", body);
+
+    let res = c
+        .post("/")
+        .version(Version::HTTP_11)
+        .json("996")
+        .send()
+        .await?
+        .assert_status(StatusCode::OK);
+    assert_eq!(res.header(header::CONTENT_TYPE), Some("application/json"));
+    assert_eq!("996", res.json::<String>().await?);
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn h1_head() -> Result<(), Error> {
     let mut handle = test_h1_server(fn_service(handle))?;
@@ -93,6 +171,80 @@ async fn h1_post() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn h1_body_limit() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let server_url = format!("http://{}/", handle.ip_port_string());
+
+    let body = Bytes::from(b"Hello,World!".repeat(1024));
+    let body_len = body.len();
+
+    let c = Client::new();
+
+    let res = c
+        .post(&server_url)
+        .version(Version::HTTP_11)
+        .text(body.clone())
+        .send()
+        .await?;
+    let collected = res.body_limited(body_len).await?;
+    assert_eq!(collected.len(), body_len);
+
+    let res = c
+        .post(&server_url)
+        .version(Version::HTTP_11)
+        .text(body.clone())
+        .send()
+        .await?;
+    let err = res.body_limited(body_len - 1).await.unwrap_err();
+    assert!(matches!(err, xitca_client::error::Error::BodyTooLarge(limit) if limit == body_len - 1));
+
+    // client level default limit.
+    let c = Client::builder().set_body_limit(1024).finish();
+
+    let res = c.post(&server_url).version(Version::HTTP_11).text(body).send().await?;
+    let err = res.string().await.unwrap_err();
+    assert!(matches!(err, xitca_client::error::Error::BodyTooLarge(1024)));
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_timed() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let server_url = format!("http://{}/", handle.ip_port_string());
+
+    let c = Client::new();
+
+    let (res, timings) = c.get(&server_url).version(Version::HTTP_11).timed().await?;
+    assert_eq!(res.status().as_u16(), 200);
+    assert_eq!("GET Response", res.string().await?);
+    assert!(timings.dns().is_some());
+    assert!(timings.connect().is_some());
+    assert!(timings.tls().is_none());
+    assert!(timings.ttfb() > Duration::ZERO);
+    assert!(timings.total() >= timings.ttfb());
+
+    // connection is reused from pool.
+    let (res, timings) = c.get(&server_url).version(Version::HTTP_11).timed().await?;
+    assert_eq!("GET Response", res.string().await?);
+    assert!(timings.dns().is_none());
+    assert!(timings.connect().is_none());
+    assert!(timings.ttfb() > Duration::ZERO);
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn h1_drop_body_read() -> Result<(), Error> {
     let mut handle = test_h1_server(fn_service(handle))?;
@@ -164,6 +316,38 @@ async fn h1_close_connection() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn h1_draining() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let server_url = format!("http://{}/", handle.ip_port_string());
+
+    let c = Client::new();
+
+    let mut res = c.get(&server_url).version(Version::HTTP_11).send().await?;
+    assert_eq!(res.status().as_u16(), 200);
+    assert!(!res.can_close_connection());
+    let _ = res.string().await?;
+
+    let server_handle = handle.try_handle()?;
+    server_handle.start_draining();
+    assert!(server_handle.is_draining());
+
+    for _ in 0..2 {
+        let mut res = c.get(&server_url).version(Version::HTTP_11).send().await?;
+        assert_eq!(res.status().as_u16(), 200);
+        assert!(res.can_close_connection());
+        let body = res.string().await?;
+        assert_eq!("GET Response", body);
+    }
+
+    server_handle.stop(true);
+
+    handle.await?;
+
+    Ok(())
+}
+
 // Request head size is limited by ReadBuf's max size which is 1MB by default.
 // If the default setting changed this test must be chagned to reflex it.
 #[tokio::test]
@@ -201,6 +385,111 @@ async fn h1_request_too_large() -> Result<(), Error> {
     Ok(())
 }
 
+#[cfg(not(feature = "io-uring"))]
+#[tokio::test]
+async fn h1_client_disconnect() -> Result<(), Error> {
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    static DROPPED: AtomicBool = AtomicBool::new(false);
+
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            DROPPED.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let mut handle = test_h1_server(fn_service(|_: Request<RequestExt<h1::RequestBody>>| async {
+        let _guard = Guard;
+        core::future::pending::<Result<Response<ResponseBody>, Error>>().await
+    }))?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+    stream.write_all(SIMPLE_GET_REQ)?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!DROPPED.load(Ordering::SeqCst));
+
+    // client disconnect must drop the pending service future.
+    drop(stream);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(DROPPED.load(Ordering::SeqCst));
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_uri_too_long() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let server_url = format!("http://{}/", handle.ip_port_string());
+
+    let c = Client::new();
+
+    let res = c
+        .get(format!("{server_url}?q={}", "a".repeat(4096)))
+        .version(Version::HTTP_11)
+        .send()
+        .await?;
+    assert_eq!(res.status().as_u16(), 200);
+    let _ = res.body().await;
+
+    let mut res = c
+        .get(format!("{server_url}?q={}", "a".repeat(8192)))
+        .version(Version::HTTP_11)
+        .send()
+        .await?;
+    assert_eq!(res.status().as_u16(), 414);
+    assert!(res.can_close_connection());
+
+    handle.try_handle()?.stop(true);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_request_body_timeout() -> Result<(), Error> {
+    let config = HttpServiceConfig::new().request_body_timeout(Duration::from_millis(300));
+    let mut handle = test_server::<_, (xitca_io::net::TcpStream, std::net::SocketAddr)>(
+        fn_service(handle).enclosed(HttpServiceBuilder::h1().config(config)),
+    )?;
+
+    let mut buf = [0; 128];
+
+    // request body trickling in time is not timed out.
+    let mut stream = TcpStream::connect(handle.addr())?;
+    stream.write_all(b"POST /collect HTTP/1.1\r\ncontent-length: 9\r\n\r\n")?;
+    for _ in 0..3 {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        stream.write_all(b"996")?;
+    }
+    let n = stream.read(&mut buf)?;
+    assert!(buf[..n].starts_with(b"HTTP/1.1 200 OK"));
+
+    // request body stalled is aborted with 408 response and connection is closed.
+    let mut stream = TcpStream::connect(handle.addr())?;
+    stream.write_all(b"POST /collect HTTP/1.1\r\ncontent-length: 9\r\n\r\n996")?;
+    let n = stream.read(&mut buf)?;
+    assert!(buf[..n].starts_with(b"HTTP/1.1 408 Request Timeout"));
+    loop {
+        if stream.read(&mut buf)? == 0 {
+            break;
+        }
+    }
+
+    handle.try_handle()?.stop(true);
+
+    handle.await?;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn h1_keepalive() -> Result<(), Error> {
     let mut handle = test_h1_server(fn_service(handle))?;
@@ -243,9 +532,348 @@ async fn h1_keepalive() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn h1_pipelining() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let mut stream = TcpStream::connect(handle.addr())?;
+    stream.set_read_timeout(Some(Duration::from_secs(3)))?;
+
+    // slow request is sent first. it's response must be returned before the following ones.
+    let mut req = b"GET /slow HTTP/1.1\r\ncontent-length: 0\r\n\r\n".to_vec();
+    req.extend_from_slice(b"POST / HTTP/1.1\r\ncontent-length: 4\r\ncontent-type: text/plain\r\n\r\n996!");
+    req.extend_from_slice(SIMPLE_GET_REQ);
+    stream.write_all(&req)?;
+
+    let mut res = Vec::new();
+    let mut buf = [0; 128];
+    while !res.ends_with(b"GET Response") {
+        let n = stream.read(&mut buf)?;
+        assert_ne!(n, 0, "connection closed before all responses are received");
+        res.extend_from_slice(&buf[..n]);
+    }
+
+    let res = String::from_utf8(res)?;
+    assert_eq!(res.matches("HTTP/1.1 200 OK").count(), 3);
+
+    let slow = res.find("Slow Response").unwrap();
+    let post = res.find("996!").unwrap();
+    let get = res.find("GET Response").unwrap();
+    assert!(slow < post && post < get);
+
+    drop(stream);
+
+    handle.try_handle()?.stop(true);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_client_pipeline() -> Result<(), Error> {
+    let mut handle = test_h1_server(fn_service(handle))?;
+
+    let server_url = format!("http://{}", handle.ip_port_string());
+
+    let c = Client::builder().h1_pipelining(true).finish();
+
+    let req = |method: Method, path: &str| {
+        let mut req = Request::new(Bytes::new());
+        *req.method_mut() = method;
+        *req.uri_mut() = format!("{server_url}{path}").parse().unwrap();
+        req
+    };
+
+    // non idempotent method can not be pipelined.
+    assert!(c.pipeline([req(Method::POST, "/")]).await.is_err());
+
+    let res = c
+        .pipeline([
+            req(Method::GET, "/slow"),
+            req(Method::GET, "/"),
+            req(Method::HEAD, "/"),
+            // server closes connection in the middle of pipeline and the last request is resent.
+            req(Method::GET, "/close_connection"),
+            req(Method::GET, "/"),
+        ])
+        .await?;
+
+    assert_eq!(res.len(), 5);
+    assert!(res.iter().all(|res| res.status() == StatusCode::OK));
+    assert_eq!(res[0].body(), "Slow Response");
+    assert_eq!(res[1].body(), "GET Response");
+    assert!(res[2].body().is_empty());
+    assert!(res[3].body().is_empty());
+    assert_eq!(res[4].body(), "GET Response");
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_client_pipeline_middleware() -> Result<(), Error> {
+    use std::{
+        net::TcpListener,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use xitca_client::{error::Error as ClientError, HttpService, Service, ServiceRequest};
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Tag(HttpService);
+
+    impl<'r, 'c> Service<ServiceRequest<'r, 'c>> for Tag {
+        type Response = xitca_client::Response;
+        type Error = ClientError;
+
+        async fn call(&self, req: ServiceRequest<'r, 'c>) -> Result<Self::Response, Self::Error> {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            req.req.headers_mut().insert("x-tag", HeaderValue::from_static("996"));
+            self.0.call(req).await
+        }
+    }
+
+    // server only answers after receiving all requests. it would stall when requests are not pipelined.
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut req = Vec::new();
+        let mut buf = [0; 1024];
+        while String::from_utf8_lossy(&req).matches("\r\n\r\n").count() < 3 {
+            let n = stream.read(&mut buf).unwrap();
+            assert_ne!(n, 0);
+            req.extend_from_slice(&buf[..n]);
+        }
+        for _ in 0..3 {
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 12\r\n\r\nGET Response")
+                .unwrap();
+        }
+        String::from_utf8(req).unwrap()
+    });
+
+    let c = Client::builder().h1_pipelining(true).middleware(Tag).finish();
+
+    let reqs = (0..3).map(|_| {
+        let mut req = Request::new(Bytes::new());
+        *req.uri_mut() = format!("http://{addr}/").parse().unwrap();
+        req
+    });
+
+    let res = tokio::time::timeout(Duration::from_secs(5), c.pipeline(reqs)).await??;
+
+    assert_eq!(res.len(), 3);
+    assert!(res.iter().all(|res| res.body() == "GET Response"));
+    assert_eq!(CALLS.load(Ordering::SeqCst), 3);
+
+    let req = server.join().unwrap();
+    assert_eq!(req.matches("x-tag: 996").count(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_client_pipeline_body_limit() -> Result<(), Error> {
+    use std::net::TcpListener;
+
+    use xitca_client::error::Error as ClientError;
+
+    // server announces a large body and only sends part of it. client must stop reading as soon as the
+    // limit is crossed instead of waiting for the whole body.
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            std::thread::spawn(move || {
+                let mut buf = [0; 1024];
+                let _ = stream.read(&mut buf);
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 1048576\r\n\r\n");
+                let _ = stream.write_all(&[b'a'; 128]);
+                // hold connection until client closes it.
+                while matches!(stream.read(&mut buf), Ok(n) if n > 0) {}
+            });
+        }
+    });
+
+    let c = Client::builder().h1_pipelining(true).set_body_limit(64).finish();
+
+    let reqs = (0..2).map(|_| {
+        let mut req = Request::new(Bytes::new());
+        *req.uri_mut() = format!("http://{addr}/").parse().unwrap();
+        req
+    });
+
+    let res = tokio::time::timeout(Duration::from_secs(5), c.pipeline(reqs)).await?;
+    assert!(matches!(res, Err(ClientError::BodyTooLarge(64))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h1_rustls_early_data() -> Result<(), Error> {
+    use std::{
+        io,
+        net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+        sync::Arc,
+    };
+
+    use tokio::task::LocalSet;
+    use xitca_io::io::{AsyncIo, Interest};
+    use xitca_service::Service;
+    use xitca_test::fault::{pipe, PipeStream};
+    use xitca_tls::rustls::{
+        self,
+        client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        crypto::{ring, verify_tls12_signature, verify_tls13_signature, CryptoProvider},
+        pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime},
+        ClientConfig, ClientConnection, DigitallySignedStruct, ServerConfig, SignatureScheme,
+    };
+
+    #[derive(Debug)]
+    struct SkipServerVerification(Arc<CryptoProvider>);
+
+    impl ServerCertVerifier for SkipServerVerification {
+        fn verify_server_cert(
+            &self,
+            _: &CertificateDer<'_>,
+            _: &[CertificateDer<'_>],
+            _: &ServerName<'_>,
+            _: &[u8],
+            _: UnixTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            Ok(ServerCertVerified::assertion())
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            self.0.signature_verification_algorithms.supported_schemes()
+        }
+    }
+
+    // drive client side tls over pipe until server closes connection. plain text received is returned.
+    async fn client_io(mut conn: ClientConnection, mut io: PipeStream) -> io::Result<(ClientConnection, Vec<u8>)> {
+        let mut res = Vec::new();
+        loop {
+            while conn.wants_write() {
+                conn.write_tls(&mut io)?;
+            }
+            io.ready(Interest::READABLE).await?;
+            if conn.read_tls(&mut io)? == 0 {
+                return Ok((conn, res));
+            }
+            conn.process_new_packets().map_err(io::Error::other)?;
+            // reader yields WouldBlock error when all received plain text is read.
+            let _ = conn.reader().read_to_end(&mut res);
+        }
+    }
+
+    const ADDR: SocketAddr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8080));
+    const REQ: &[u8] = b"GET / HTTP/1.1\r\nconnection: close\r\n\r\n";
+
+    let provider = Arc::new(ring::default_provider());
+
+    let key = std::fs::read("../examples/cert/key.pem")?;
+    let cert = std::fs::read("../examples/cert/cert.pem")?;
+    let key = rustls_pemfile::pkcs8_private_keys(&mut &*key).next().unwrap()?;
+    let cert = rustls_pemfile::certs(&mut &*cert).collect::<Result<_, _>>()?;
+
+    let mut server_config = ServerConfig::builder_with_provider(provider.clone())
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .with_no_client_auth()
+        .with_single_cert(cert, PrivateKeyDer::from(key))?;
+    server_config.max_early_data_size = 1024;
+
+    let mut client_config = ClientConfig::builder_with_provider(provider.clone())
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(SkipServerVerification(provider)))
+        .with_no_client_auth();
+    client_config.enable_early_data = true;
+    let client_config = Arc::new(client_config);
+
+    let server_name = ServerName::try_from("localhost")?;
+
+    // h1 service spawns local task for date header.
+    LocalSet::new()
+        .run_until(async {
+            // in memory pipe is used so io readiness is not affected by bytes already consumed by tls
+            // handshake.
+            let service = fn_service(handle)
+                .enclosed(
+                    HttpServiceBuilder::h1()
+                        .with_stream::<PipeStream>()
+                        .rustls(Arc::new(server_config)),
+                )
+                .call(())
+                .await
+                .unwrap();
+
+            // first connection does full handshake and receives session ticket for resumption.
+            let (server, client) = pipe();
+            let mut conn = ClientConnection::new(client_config.clone(), server_name.clone())?;
+            conn.writer().write_all(REQ)?;
+            let (res, client) = tokio::join!(service.call((server, ADDR)), client_io(conn, client));
+            assert!(res.is_ok());
+            let (conn, buf) = client?;
+            assert!(!conn.is_early_data_accepted());
+            let buf = String::from_utf8(buf)?;
+            assert!(buf.starts_with("HTTP/1.1 200 OK"));
+            assert!(buf.ends_with("GET Response"));
+
+            // second connection sends request as 0-RTT early data and nothing after handshake. dispatcher
+            // must read early data buffered by tls stream without waiting for io readiness.
+            let (server, client) = pipe();
+            let mut conn = ClientConnection::new(client_config, server_name)?;
+            conn.early_data()
+                .expect("session ticket must allow early data")
+                .write_all(REQ)?;
+            let (res, client) = tokio::time::timeout(Duration::from_secs(5), async {
+                tokio::join!(service.call((server, ADDR)), client_io(conn, client))
+            })
+            .await?;
+            assert!(res.is_ok());
+            let (conn, buf) = client?;
+            assert!(conn.is_early_data_accepted());
+            let buf = String::from_utf8(buf)?;
+            assert!(buf.starts_with("HTTP/1.1 200 OK"));
+            assert!(buf.ends_with("GET Response"));
+
+            Ok(())
+        })
+        .await
+}
+
 async fn handle(req: Request<RequestExt<h1::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
     match (req.method(), req.uri().path()) {
         (&Method::GET, "/") | (&Method::HEAD, "/") => Ok(Response::new(Bytes::from("GET Response").into())),
+        (&Method::GET, "/slow") => {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            Ok(Response::new(Bytes::from("Slow Response").into()))
+        }
         (&Method::POST, "/") => {
             let length = req.headers().get(header::CONTENT_LENGTH).unwrap().clone();
             let ty = req.headers().get(header::CONTENT_TYPE).unwrap().clone();
@@ -258,6 +886,15 @@ async fn handle(req: Request<RequestExt<h1::RequestBody>>) -> Result<Response<Re
 
             Ok(res)
         }
+        // collect request body before producing response.
+        (&Method::POST, "/collect") => {
+            let mut body = req.into_body();
+            let mut buf = BytesMut::new();
+            while let Some(bytes) = body.next().await {
+                buf.extend_from_slice(&bytes?);
+            }
+            Ok(Response::new(buf.freeze().into()))
+        }
         // drop request body. server should close connection afterwards.
         (&Method::POST, "/drop_body") => Ok(Response::new(Bytes::new().into())),
         // partial read request body. server should close connection afterwards.
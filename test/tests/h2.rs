@@ -1,15 +1,31 @@
-use std::time::{Duration, Instant};
+use std::{
+    convert::Infallible,
+    io,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
 use futures_util::StreamExt;
 use xitca_client::Client;
 use xitca_http::{
-    body::ResponseBody,
+    body::{RequestBody, ResponseBody},
     bytes::{Bytes, BytesMut},
+    config::HttpServiceConfig,
     h2,
     http::{header, Method, Request, RequestExt, Response, Version},
+    util::middleware::Logger,
+    HttpServiceBuilder,
+};
+use xitca_io::{io::PollIoAdapter, net::Stream as NetStream};
+use xitca_service::{fn_service, Service, ServiceExt};
+use xitca_test::{
+    fault::{pipe, PipeStream},
+    test_h2_server, test_server, Error, TestServerHandle,
 };
-use xitca_service::fn_service;
-use xitca_test::{test_h2_server, Error};
 
 #[tokio::test]
 async fn h2_get() -> Result<(), Error> {
@@ -86,6 +102,120 @@ async fn h2_post() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn h2_too_many_headers() -> Result<(), Error> {
+    let mut handle = test_h2_server(fn_service(handle))?;
+
+    let server_url = format!("https://{}/", handle.ip_port_string());
+
+    let c = Client::new();
+
+    // establish connection.
+    let res = c.get(&server_url).version(Version::HTTP_2).send().await?;
+    assert_eq!(res.status().as_u16(), 200);
+    let _ = res.body().await;
+
+    let bad = async {
+        let mut req = c.get(&server_url).version(Version::HTTP_2);
+        for i in 0..128 {
+            let name = header::HeaderName::try_from(format!("x-header-{i}")).unwrap();
+            req.headers_mut()
+                .insert(name, header::HeaderValue::from_static("value"));
+        }
+        req.send().await
+    };
+
+    let good = async { c.get(&server_url).version(Version::HTTP_2).send().await };
+
+    // stream with too many headers is rejected while sibling stream on the same connection is not affected.
+    let (bad, good) = tokio::join!(bad, good);
+
+    assert_eq!(bad?.status().as_u16(), 431);
+
+    let mut res = good?;
+    assert_eq!(res.status().as_u16(), 200);
+    assert!(!res.can_close_connection());
+    assert_eq!("GET Response", res.string().await?);
+
+    let res = c.get(&server_url).version(Version::HTTP_2).send().await?;
+    assert_eq!(res.status().as_u16(), 200);
+    assert_eq!("GET Response", res.string().await?);
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h2_uri_too_long() -> Result<(), Error> {
+    let mut handle = test_h2_server(fn_service(handle))?;
+
+    let server_url = format!("https://{}/", handle.ip_port_string());
+
+    let c = Client::new();
+
+    let res = c
+        .get(format!("{server_url}?q={}", "a".repeat(8192)))
+        .version(Version::HTTP_2)
+        .send()
+        .await?;
+    assert_eq!(res.status().as_u16(), 414);
+
+    // connection is not affected by rejected stream.
+    let mut res = c.get(&server_url).version(Version::HTTP_2).send().await?;
+    assert_eq!(res.status().as_u16(), 200);
+    assert!(!res.can_close_connection());
+    assert_eq!("GET Response", res.string().await?);
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h2_connect_tunnel() -> Result<(), Error> {
+    let mut handle = test_h2_server(fn_service(handle))?;
+
+    let server_url = format!("https://{}/tunnel", handle.ip_port_string());
+
+    let c = Client::new();
+
+    let mut tunnel = c
+        .connect(&server_url)
+        .version(Version::HTTP_2)
+        .send()
+        .await?
+        .into_inner();
+
+    use xitca_io::io::{AsyncIo, Interest};
+
+    use std::io::{Read, Write};
+
+    tunnel.ready(Interest::WRITABLE).await?;
+
+    tunnel.write_all(b"996")?;
+
+    let mut buf = [0; 8];
+
+    tunnel.ready(Interest::READABLE).await?;
+
+    let n = tunnel.read(&mut buf)?;
+
+    assert_eq!(b"996", &buf[..n]);
+
+    core::future::poll_fn(|cx| core::pin::Pin::new(&mut tunnel).poll_shutdown(cx)).await?;
+
+    handle.try_handle()?.stop(false);
+
+    handle.await?;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn h2_connect() -> Result<(), Error> {
     let mut handle = test_h2_server(fn_service(handle))?;
@@ -173,6 +303,432 @@ async fn h2_keepalive() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn h2_draining() -> Result<(), Error> {
+    let mut handle = test_h2_server(fn_service(handle))?;
+
+    let stream = tokio::net::TcpStream::connect(handle.addr()).await?;
+    let (client, conn) = ::h2::client::handshake(stream).await?;
+    tokio::spawn(async move {
+        let _ = conn.await;
+    });
+
+    let url = format!("http://{}/slow", handle.ip_port_string());
+    let req = || Request::get(&url).body(()).unwrap();
+
+    let mut client = client.ready().await?;
+    let (in_flight, _) = client.send_request(req(), true)?;
+
+    // let server accept the stream before draining.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let server_handle = handle.try_handle()?;
+    server_handle.start_draining();
+
+    // wait for GOAWAY frame of draining connection.
+    tokio::time::sleep(Duration::from_millis(700)).await;
+
+    // new stream is refused.
+    let refused = async { client.ready().await?.send_request(req(), true)?.0.await };
+    assert!(refused.await.is_err());
+
+    // in flight stream is finished.
+    let res = in_flight.await?;
+    assert_eq!(res.status().as_u16(), 200);
+    let mut body = res.into_body();
+    let mut buf = BytesMut::new();
+    while let Some(bytes) = body.data().await {
+        buf.extend_from_slice(&bytes?);
+    }
+    assert_eq!(&buf[..], b"GET Response");
+
+    server_handle.stop(true);
+
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn h2_draining_state() -> Result<(), Error> {
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            let draining = Arc::new(AtomicBool::new(false));
+            let service = fn_service(echo)
+                .enclosed(
+                    HttpServiceBuilder::h2()
+                        .with_stream::<PipeStream>()
+                        .draining_state(draining.clone()),
+                )
+                .call(())
+                .await
+                .map_err(|_| "failed to construct h2 service")?;
+            let client = h2_pipe_client(service).await?;
+            let req = |path: &str| Request::post(format!("http://localhost{path}")).body(()).unwrap();
+
+            let (in_flight, mut tx) = client.clone().ready().await?.send_request(req("/slow_read"), false)?;
+
+            // let server accept the stream before draining.
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            draining.store(true, Ordering::Relaxed);
+
+            // let connection observe draining state and send GOAWAY.
+            tokio::time::sleep(Duration::from_millis(600)).await;
+
+            // new stream is refused.
+            let refused = async { client.clone().ready().await?.send_request(req("/"), true)?.0.await };
+            assert!(refused.await.is_err());
+
+            // in flight stream is finished.
+            tx.send_data(Bytes::from_static(b"996"), true)?;
+            let res = in_flight.await?;
+            assert_eq!(res.status().as_u16(), 200);
+            assert_eq!(collect_h2_body(res.into_body()).await?, "996");
+
+            Ok(())
+        })
+        .await
+}
+
+#[tokio::test(start_paused = true)]
+async fn h2_stream_idle_timeout() -> Result<(), Error> {
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            let config = HttpServiceConfig::new().stream_idle_timeout(Duration::from_millis(300));
+            let client = h2_local_client(config).await?;
+            let req = |path: &str| Request::post(format!("http://localhost{path}")).body(()).unwrap();
+
+            // stream keeps sending request body in time.
+            let (res, mut tx) = client.clone().ready().await?.send_request(req("/"), false)?;
+            for _ in 0..3 {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                tx.send_data(Bytes::from_static(b"996"), false)?;
+            }
+            tx.send_data(Bytes::new(), true)?;
+            let res = res.await?;
+            assert_eq!(res.status().as_u16(), 200);
+            assert_eq!(collect_h2_body(res.into_body()).await?, "996996996");
+
+            // stream keeps sending request body while service is not reading it.
+            let (res, mut tx) = client.clone().ready().await?.send_request(req("/slow_read"), false)?;
+            for _ in 0..5 {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                tx.send_data(Bytes::from_static(b"251"), false)?;
+            }
+            tx.send_data(Bytes::new(), true)?;
+            let res = res.await?;
+            assert_eq!(res.status().as_u16(), 200);
+            assert_eq!(collect_h2_body(res.into_body()).await?, "251251251251251");
+
+            // stream stops sending request body is reset.
+            let (res, mut tx) = client.clone().ready().await?.send_request(req("/"), false)?;
+            tx.send_data(Bytes::from_static(b"996"), false)?;
+            let err = res.await.err().unwrap();
+            assert_eq!(err.reason(), Some(::h2::Reason::CANCEL));
+
+            // connection is not affected.
+            let (res, _) = client.clone().ready().await?.send_request(req("/"), true)?;
+            assert_eq!(res.await?.status().as_u16(), 200);
+
+            Ok(())
+        })
+        .await
+}
+
+#[tokio::test(start_paused = true)]
+async fn h2_request_body_timeout() -> Result<(), Error> {
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            let config = HttpServiceConfig::new().request_body_timeout(Duration::from_millis(300));
+            let client = h2_local_client(config).await?;
+            let req = |path: &str| Request::post(format!("http://localhost{path}")).body(()).unwrap();
+
+            // request body trickling in time is not timed out.
+            let (res, mut tx) = client.clone().ready().await?.send_request(req("/"), false)?;
+            for _ in 0..3 {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                tx.send_data(Bytes::from_static(b"996"), false)?;
+            }
+            tx.send_data(Bytes::new(), true)?;
+            let res = res.await?;
+            assert_eq!(res.status().as_u16(), 200);
+            assert_eq!(collect_h2_body(res.into_body()).await?, "996996996");
+
+            // timer does not run while service is not reading request body.
+            let (res, mut tx) = client.clone().ready().await?.send_request(req("/slow_read"), false)?;
+            tokio::time::sleep(Duration::from_millis(1100)).await;
+            tx.send_data(Bytes::from_static(b"251"), true)?;
+            let res = res.await?;
+            assert_eq!(res.status().as_u16(), 200);
+            assert_eq!(collect_h2_body(res.into_body()).await?, "251");
+
+            // request body stalled is aborted with 408 response.
+            let (res, mut tx) = client.clone().ready().await?.send_request(req("/"), false)?;
+            tx.send_data(Bytes::from_static(b"996"), false)?;
+            assert_eq!(res.await?.status().as_u16(), 408);
+
+            // connection is not affected.
+            let (res, _) = client.clone().ready().await?.send_request(req("/"), true)?;
+            assert_eq!(res.await?.status().as_u16(), 200);
+
+            Ok(())
+        })
+        .await
+}
+
+#[tokio::test(start_paused = true)]
+async fn h2_max_half_open_streams() -> Result<(), Error> {
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            let config = HttpServiceConfig::new().max_half_open_streams(1);
+            let client = h2_local_client(config).await?;
+            let req = || Request::post("http://localhost/").body(()).unwrap();
+
+            let (res1, mut tx1) = client.clone().ready().await?.send_request(req(), false)?;
+
+            // let server accept the first stream.
+            tokio::time::sleep(Duration::from_millis(100)).await;
+
+            // second half open stream is refused.
+            let (res2, _tx2) = client.clone().ready().await?.send_request(req(), false)?;
+            let err = res2.await.err().unwrap();
+            assert_eq!(err.reason(), Some(::h2::Reason::REFUSED_STREAM));
+
+            // stream without request body is not affected.
+            let (res, _) = client.clone().ready().await?.send_request(req(), true)?;
+            assert_eq!(res.await?.status().as_u16(), 200);
+
+            // finish first stream and half open stream is accepted again.
+            tx1.send_data(Bytes::from_static(b"996"), true)?;
+            assert_eq!(collect_h2_body(res1.await?.into_body()).await?, "996");
+
+            let (res, mut tx) = client.clone().ready().await?.send_request(req(), false)?;
+            tx.send_data(Bytes::from_static(b"251"), true)?;
+            assert_eq!(collect_h2_body(res.await?.into_body()).await?, "251");
+
+            Ok(())
+        })
+        .await
+}
+
+#[tokio::test]
+async fn h2_logger_empty_body() -> Result<(), Error> {
+    #[derive(Clone, Default)]
+    struct LogWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for LogWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn empty(req: Request<RequestExt<h2::RequestBody>>) -> Result<Response<ResponseBody>, Infallible> {
+        let body = match req.uri().path() {
+            "/none" => ResponseBody::none(),
+            _ => Bytes::new().into(),
+        };
+        Ok(Response::new(body))
+    }
+
+    let logs = LogWriter::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_ansi(false)
+        .with_writer({
+            let logs = logs.clone();
+            move || logs.clone()
+        })
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            let service = fn_service(empty)
+                .enclosed(Logger::new().body_size())
+                .enclosed(HttpServiceBuilder::h2().with_stream::<PipeStream>())
+                .call(())
+                .await
+                .map_err(|_| "failed to construct h2 service")?;
+            let client = h2_pipe_client(service).await?;
+
+            // empty response body is sent along with response head and never polled.
+            for path in ["/", "/none"] {
+                let req = Request::get(format!("http://localhost{path}")).body(()).unwrap();
+                let (res, _) = client.clone().ready().await?.send_request(req, true)?;
+                let res = res.await?;
+                assert_eq!(res.status().as_u16(), 200);
+                assert!(res.body().is_end_stream());
+            }
+
+            Ok::<_, Error>(())
+        })
+        .await?;
+
+    let logs = String::from_utf8(logs.0.lock().unwrap().clone())?;
+    assert_eq!(logs.matches("response sent").count(), 2);
+    assert!(!logs.contains("dropped before finished"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h2c_prior_knowledge() -> Result<(), Error> {
+    let mut handle = test_h2c_server()?;
+
+    // plain text http/2 with prior knowledge.
+    let client = h2_client(&handle).await?;
+    let req = Request::get(format!("http://{}/", handle.ip_port_string())).body(())?;
+    let (res, _) = client.ready().await?.send_request(req, true)?;
+    assert_eq!(collect_h2_body(res.await?.into_body()).await?, "HTTP/2.0");
+
+    // plain text http/1 on the same port.
+    let res = Client::new()
+        .get(&format!("http://{}/", handle.ip_port_string()))
+        .version(Version::HTTP_11)
+        .send()
+        .await?;
+    assert_eq!(res.string().await?, "HTTP/1.1");
+
+    handle.try_handle()?.stop(false);
+    handle.await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn h2c_upgrade() -> Result<(), Error> {
+    use std::io::{Read, Write};
+
+    let mut handle = test_h2c_server()?;
+
+    let mut stream = std::net::TcpStream::connect(handle.addr())?;
+    stream.write_all(
+        b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade, HTTP2-Settings\r\nUpgrade: h2c\r\nHTTP2-Settings: AAMAAABkAAQAAP__\r\n\r\n",
+    )?;
+
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        let mut byte = [0];
+        stream.read_exact(&mut byte)?;
+        head.push(byte[0]);
+    }
+    assert!(head.starts_with(b"HTTP/1.1 101 Switching Protocols\r\n"));
+
+    // connection preface and empty SETTINGS frame.
+    stream.write_all(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n")?;
+    stream.write_all(&[0, 0, 0, 4, 0, 0, 0, 0, 0])?;
+
+    // response of upgrade request is sent on stream 1.
+    let mut body = Vec::new();
+    loop {
+        let mut header = [0; 9];
+        stream.read_exact(&mut header)?;
+        let len = u32::from_be_bytes([0, header[0], header[1], header[2]]) as usize;
+        let (ty, flags) = (header[3], header[4]);
+        let stream_id = u32::from_be_bytes([header[5], header[6], header[7], header[8]]);
+
+        let mut payload = vec![0; len];
+        stream.read_exact(&mut payload)?;
+
+        match ty {
+            // acknowledge SETTINGS frame from server.
+            4 if flags & 1 == 0 => stream.write_all(&[0, 0, 0, 4, 1, 0, 0, 0, 0])?,
+            // DATA frame of stream 1.
+            0 if stream_id == 1 => body.extend_from_slice(&payload),
+            _ => {}
+        }
+
+        // END_STREAM flag of stream 1.
+        if stream_id == 1 && flags & 1 == 1 {
+            break;
+        }
+    }
+    assert_eq!(body, b"HTTP/2.0");
+
+    drop(stream);
+
+    handle.try_handle()?.stop(false);
+    handle.await?;
+
+    Ok(())
+}
+
+// server responding with http version of request and detect h2c connection.
+fn test_h2c_server() -> Result<TestServerHandle, Error> {
+    async fn version(req: Request<RequestExt<RequestBody>>) -> Result<Response<ResponseBody>, Error> {
+        Ok(Response::new(format!("{:?}", req.version()).into()))
+    }
+
+    let config = HttpServiceConfig::new().peek_protocol();
+    test_server::<_, NetStream>(fn_service(version).enclosed(HttpServiceBuilder::with_config(config)))
+}
+
+// run h2 service over in memory pipe on current thread so test can use paused time without racing
+// with network io. must be called inside LocalSet.
+async fn h2_local_client(config: HttpServiceConfig) -> Result<::h2::client::SendRequest<Bytes>, Error> {
+    let service = fn_service(echo)
+        .enclosed(HttpServiceBuilder::h2().with_stream::<PipeStream>().config(config))
+        .call(())
+        .await
+        .map_err(|_| "failed to construct h2 service")?;
+    h2_pipe_client(service).await
+}
+
+// connect h2 client to given service over in memory pipe. must be called inside LocalSet.
+async fn h2_pipe_client<S>(service: S) -> Result<::h2::client::SendRequest<Bytes>, Error>
+where
+    S: Service<(PipeStream, SocketAddr)> + 'static,
+{
+    let (server, client) = pipe();
+    let addr = "127.0.0.1:8080".parse()?;
+
+    tokio::task::spawn_local(async move {
+        let _ = service.call((server, addr)).await;
+    });
+
+    let (client, conn) = ::h2::client::handshake(PollIoAdapter(client)).await?;
+    tokio::task::spawn_local(async move {
+        let _ = conn.await;
+    });
+    Ok(client)
+}
+
+async fn h2_client(handle: &TestServerHandle) -> Result<::h2::client::SendRequest<Bytes>, Error> {
+    let stream = tokio::net::TcpStream::connect(handle.addr()).await?;
+    let (client, conn) = ::h2::client::handshake(stream).await?;
+    tokio::spawn(async move {
+        let _ = conn.await;
+    });
+    Ok(client)
+}
+
+async fn collect_h2_body(mut body: ::h2::RecvStream) -> Result<String, Error> {
+    let mut buf = BytesMut::new();
+    while let Some(bytes) = body.data().await {
+        let bytes = bytes?;
+        let _ = body.flow_control().release_capacity(bytes.len());
+        buf.extend_from_slice(&bytes);
+    }
+    Ok(String::from_utf8(buf.to_vec())?)
+}
+
+// collect request body and echo it back. request to /slow_read path waits before reading body.
+async fn echo(req: Request<RequestExt<h2::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
+    if req.uri().path() == "/slow_read" {
+        tokio::time::sleep(Duration::from_millis(1000)).await;
+    }
+    let mut body = req.into_body();
+    let mut buf = BytesMut::new();
+    while let Some(bytes) = body.next().await {
+        buf.extend_from_slice(&bytes?);
+    }
+    Ok(Response::new(buf.freeze().into()))
+}
+
 async fn handle(req: Request<RequestExt<h2::RequestBody>>) -> Result<Response<ResponseBody>, Error> {
     // Some yield for testing h2 dispatcher's concurrent future handling.
     tokio::task::yield_now().await;
@@ -181,6 +737,10 @@ async fn handle(req: Request<RequestExt<h2::RequestBody>>) -> Result<Response<Re
 
     match (req.method(), req.uri().path()) {
         (&Method::GET, "/") => Ok(Response::new(Bytes::from("GET Response").into())),
+        (&Method::GET, "/slow") => {
+            tokio::time::sleep(Duration::from_millis(1500)).await;
+            Ok(Response::new(Bytes::from("GET Response").into()))
+        }
         (&Method::GET, "/host") => Ok(Response::new(
             Bytes::from(
                 req.headers()
@@ -190,6 +750,19 @@ async fn handle(req: Request<RequestExt<h2::RequestBody>>) -> Result<Response<Re
             )
             .into(),
         )),
+        (&Method::CONNECT, "/tunnel") => {
+            // client sends extended CONNECT with connect-ip protocol by default.
+            assert_eq!(h2::tunnel::protocol(&req), Some("connect-ip"));
+
+            let (tunnel, body) = h2::tunnel::tunnel(req.into_body());
+
+            tokio::spawn(async move {
+                let (mut read, mut write) = tokio::io::split(tunnel);
+                tokio::io::copy(&mut read, &mut write).await.unwrap();
+            });
+
+            Ok(Response::new(ResponseBody::box_stream(body)))
+        }
         (&Method::CONNECT, "/") => {
             let (_, mut body) = req.into_parts();
             Ok(Response::new(ResponseBody::box_stream(async_stream::stream! {
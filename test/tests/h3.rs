@@ -7,7 +7,7 @@ use xitca_http::{
     http::{header, Method, Request, RequestExt, Response, Version},
 };
 use xitca_service::fn_service;
-use xitca_test::{test_h3_server, Error};
+use xitca_test::{test_h3_server, test_quic_config, Error};
 
 #[tokio::test]
 async fn h3_get() -> Result<(), Error> {
@@ -31,6 +31,38 @@ async fn h3_get() -> Result<(), Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn h3_web_server() -> Result<(), Error> {
+    use xitca_web::{handler::handler_service, route::get, App, HttpServer};
+
+    let addr = std::net::UdpSocket::bind("127.0.0.1:0")?.local_addr()?;
+
+    let mut server = HttpServer::serve(
+        App::new()
+            .at("/", get(handler_service(|| async { "GET Response" })))
+            .finish(),
+    )
+    .worker_threads(1)
+    .server_threads(1)
+    .disable_signal()
+    .bind_h3(addr, test_quic_config()?)?
+    .run();
+
+    let c = Client::new();
+    let server_url = format!("https://localhost:{}/", addr.port());
+
+    let res = c.get(&server_url).version(Version::HTTP_3).send().await?;
+    assert_eq!(res.status().as_u16(), 200);
+    assert_eq!(res.version(), Version::HTTP_3);
+    assert_eq!("GET Response", res.string().await?);
+
+    server.handle()?.stop(false);
+
+    server.await?;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn h3_no_host_header() -> Result<(), Error> {
     let mut handle = test_h3_server(fn_service(handle))?;
@@ -0,0 +1,63 @@
+use xitca_client::{
+    middleware::{Trace as ClientTrace, TraceContext},
+    Client,
+};
+use xitca_test::Error;
+use xitca_web::{
+    handler::handler_service,
+    http::{HeaderMap, Version},
+    middleware::trace::{Trace, TRACEPARENT},
+    route::get,
+    App, HttpServer, WebContext,
+};
+
+#[tokio::test]
+async fn trace_propagation() -> Result<(), Error> {
+    // respond with trace id of server span and traceparent header sent by client.
+    async fn handler(ctx: &WebContext<'_>) -> String {
+        let trace = ctx.req().extensions().get::<TraceContext>().unwrap();
+        let traceparent = ctx.req().headers().get(TRACEPARENT).unwrap();
+        format!("{:032x} {}", trace.trace_id(), traceparent.to_str().unwrap())
+    }
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+
+    let mut server = HttpServer::serve(
+        App::new()
+            .at("/", get(handler_service(handler)))
+            .enclosed(Trace::new())
+            .finish(),
+    )
+    .worker_threads(1)
+    .server_threads(1)
+    .disable_signal()
+    .listen(listener)?
+    .run();
+
+    let client = Client::builder().middleware(ClientTrace::new).finish();
+    let url = format!("http://{addr}/");
+
+    // client request made in the scope of a trace context is part of the same trace.
+    let root = TraceContext::new_root();
+    let body = root
+        .clone()
+        .scope(async { client.get(&url).version(Version::HTTP_11).send().await?.string().await })
+        .await?;
+
+    let (server_trace_id, traceparent) = body.split_once(' ').unwrap();
+    assert_eq!(server_trace_id, format!("{:032x}", root.trace_id()));
+
+    // client sends it's own span id as parent of server span.
+    let mut headers = HeaderMap::new();
+    headers.insert(TRACEPARENT, traceparent.parse()?);
+    let client_ctx = TraceContext::from_headers(&headers).unwrap();
+    assert_eq!(client_ctx.trace_id(), root.trace_id());
+    assert_ne!(client_ctx.span_id(), root.span_id());
+
+    server.handle()?.stop(false);
+
+    server.await?;
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use futures_util::{SinkExt, Stream, StreamExt};
-use http_ws::{ws, Message};
-use xitca_client::Client;
+use http_ws::{ws, Item, Message, OpCode};
+use xitca_client::{ws::WebSocket, Client};
 use xitca_http::{body::ResponseBody, http::Response, Request};
 use xitca_io::bytes::Bytes;
 use xitca_service::fn_service;
@@ -37,6 +37,59 @@ async fn message() -> Result<(), Error> {
     handle.await.map_err(Into::into)
 }
 
+#[tokio::test]
+async fn message_fragmented() -> Result<(), Error> {
+    let mut handle = xitca_test::test_h1_server(fn_service(handler))?;
+
+    let c = Client::new();
+
+    let ws = c.ws(&format!("ws://{}", handle.ip_port_string())).send().await?;
+
+    // inherent split method yields sinks with fragmented sending support.
+    let (mut tx, mut rx) = WebSocket::split(&ws);
+
+    let chunks = futures_util::stream::iter(["9", "9", "6"].map(|c| Ok::<_, Error>(Bytes::from(c))));
+    tx.send_fragmented(OpCode::Binary, chunks).await?;
+
+    assert_eq!(
+        rx.next().await.unwrap()?,
+        Message::Continuation(Item::FirstBinary(Bytes::from("9")))
+    );
+    assert_eq!(
+        rx.next().await.unwrap()?,
+        Message::Continuation(Item::Continue(Bytes::from("9")))
+    );
+    assert_eq!(
+        rx.next().await.unwrap()?,
+        Message::Continuation(Item::Last(Bytes::from("6")))
+    );
+
+    // control frame interleaved with fragmented message.
+    tx.send(Message::Continuation(Item::FirstText(Bytes::from("hello,"))))
+        .await?;
+    tx.send(Message::Ping(Bytes::from("pingpong"))).await?;
+    assert!(tx.send(Message::Text(Bytes::from("996"))).await.is_err());
+    tx.send(Message::Continuation(Item::Last(Bytes::from("world!"))))
+        .await?;
+
+    assert_eq!(
+        rx.next().await.unwrap()?,
+        Message::Continuation(Item::FirstText(Bytes::from("hello,")))
+    );
+    assert_eq!(rx.next().await.unwrap()?, Message::Pong(Bytes::from("pingpong")));
+    assert_eq!(
+        rx.next().await.unwrap()?,
+        Message::Continuation(Item::Last(Bytes::from("world!")))
+    );
+
+    tx.send(Message::Close(None)).await?;
+    let msg = rx.next().await.unwrap()?;
+    assert_eq!(msg, Message::Close(None));
+
+    handle.try_handle()?.stop(true);
+    handle.await.map_err(Into::into)
+}
+
 #[tokio::test]
 async fn message_h2() -> Result<(), Error> {
     let mut handle = test_h2_server(fn_service(handler))?;
@@ -88,6 +141,9 @@ where
                 Message::Text(bytes) => {
                     tx.send(Message::Text(bytes)).await.unwrap();
                 }
+                Message::Continuation(item) => {
+                    tx.send(Message::Continuation(item)).await.unwrap();
+                }
                 Message::Ping(bytes) => {
                     tx.send(Message::Pong(bytes)).await.unwrap();
                 }
@@ -15,6 +15,9 @@ use xitca_io::io::{AsyncIo, Interest, Ready};
 pub struct TlsStream<C, Io> {
     conn: C,
     io: Io,
+    // tls 1.3 0-RTT early data received during server side handshake. yielded before other data.
+    early_data: Vec<u8>,
+    early_data_accepted: bool,
 }
 
 impl<C, S, Io> TlsStream<C, Io>
@@ -44,6 +47,13 @@ where
     fn read_tls(&mut self) -> io::Result<usize> {
         self.conn.read_tls(&mut self.io)
     }
+
+    // early data and plain text decrypted from bytes already read from io can be read without waiting
+    // for io readiness. rustls does not want read when it has buffered plain text or received close
+    // notify from peer.
+    fn is_read_buffered(&self) -> bool {
+        !self.early_data.is_empty() || !self.conn.wants_read()
+    }
 }
 
 impl<C, S, Io> TlsStream<C, Io>
@@ -87,7 +97,38 @@ where
             io.ready(interest).await?;
         }
 
-        Ok(TlsStream { io, conn })
+        Ok(TlsStream {
+            io,
+            conn,
+            early_data: Vec::new(),
+            early_data_accepted: false,
+        })
+    }
+}
+
+impl<Io> TlsStream<ServerConnection, Io>
+where
+    Io: AsyncIo,
+{
+    /// finish server side handshake with given io and connection type.
+    ///
+    /// Unlike [TlsStream::handshake] tls 1.3 0-RTT early data accepted during handshake is kept and
+    /// yielded by [io::Read] before any data received after handshake. Early data is only accepted
+    /// when [ServerConfig::max_early_data_size] is not zero. It's zero by default.
+    pub async fn accept(io: Io, conn: ServerConnection) -> io::Result<Self> {
+        let mut stream = Self::handshake(io, conn).await?;
+        if let Some(mut early_data) = stream.conn.early_data() {
+            io::Read::read_to_end(&mut early_data, &mut stream.early_data)?;
+            stream.early_data_accepted = !stream.early_data.is_empty();
+        }
+        Ok(stream)
+    }
+
+    /// returns true when stream received tls 1.3 0-RTT early data during handshake.
+    ///
+    /// Early data can be replayed by attacker and it should be handled with care.
+    pub fn is_early_data_accepted(&self) -> bool {
+        self.early_data_accepted
     }
 }
 
@@ -97,13 +138,21 @@ where
     S: SideData,
     Io: AsyncIo,
 {
-    #[inline]
     fn ready(&mut self, interest: Interest) -> impl Future<Output = io::Result<Ready>> + Send {
-        self.io.ready(interest)
+        let buffered = self.is_read_buffered() && interest.is_readable();
+        let ready = self.io.ready(interest);
+        async move {
+            if buffered {
+                return Ok(Ready::READABLE);
+            }
+            ready.await
+        }
     }
 
-    #[inline]
     fn poll_ready(&mut self, interest: Interest, cx: &mut Context<'_>) -> Poll<io::Result<Ready>> {
+        if self.is_read_buffered() && interest.is_readable() {
+            return Poll::Ready(Ok(Ready::READABLE));
+        }
         self.io.poll_ready(interest, cx)
     }
 
@@ -123,6 +172,13 @@ where
     Io: AsyncIo,
 {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.early_data.is_empty() {
+            let len = buf.len().min(self.early_data.len());
+            buf[..len].copy_from_slice(&self.early_data[..len]);
+            self.early_data.drain(..len);
+            return Ok(len);
+        }
+
         while self.conn.wants_read() {
             let n = self.read_tls()?;
 
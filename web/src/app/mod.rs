@@ -1,5 +1,6 @@
 mod object;
 mod router;
+mod scope;
 
 use core::{
     convert::Infallible,
@@ -8,7 +9,7 @@ use core::{
     pin::Pin,
 };
 
-use std::error;
+use std::{cell::OnceCell, error, sync::Arc};
 
 use futures_core::stream::Stream;
 use xitca_http::util::{
@@ -21,11 +22,17 @@ use crate::{
     bytes::Bytes,
     context::WebContext,
     error::{Error, RouterError},
-    http::{WebRequest, WebResponse},
-    middleware::eraser::TypeEraser,
+    http::{header::HeaderMap, WebRequest, WebResponse},
+    middleware::{
+        default_headers::DefaultHeaders,
+        eraser::TypeEraser,
+        map_error::{MapError, MapErrorOne},
+    },
     service::{ready::ReadyService, AsyncFn, EnclosedBuilder, EnclosedFnBuilder, MapBuilder, Service, ServiceExt},
 };
 
+pub use self::scope::Scope;
+
 use self::{object::WebObject, router::AppRouter};
 
 /// composed application type with router, stateful context and default middlewares.
@@ -36,6 +43,7 @@ pub struct App<R = (), CF = ()> {
 
 type BoxFuture<C> = Pin<Box<dyn Future<Output = Result<C, Box<dyn fmt::Debug>>>>>;
 type CtxBuilder<C> = Box<dyn Fn() -> BoxFuture<C> + Send + Sync>;
+type InheritCtxBuilder<P, C> = Arc<dyn Fn(&P) -> C + Send + Sync>;
 type DefaultWebObject<C> = WebObject<C, RequestBody, WebResponse, RouterError<Error>>;
 type DefaultAppRouter<C> = AppRouter<RouteObject<(), DefaultWebObject<C>, Infallible>>;
 
@@ -393,6 +401,79 @@ impl<R, CF> App<R, CF> {
             ctx_builder,
         }
     }
+
+    /// Construct nested App with state derived from the state of App it's nested in.
+    ///
+    /// State inheritance of nested App follows these rules:
+    /// - nested App without state shares the same state with parent App.
+    /// - nested App constructed with [App::with_state] or [App::with_async_state] replaces parent's
+    ///   state and the types of parent state are not accessible from it.
+    /// - nested App constructed with this method has it's state produced by given closure with
+    ///   reference of parent's state. The closure is called once per worker thread when nested App
+    ///   handles it's first request.
+    ///
+    /// [StateRef](crate::handler::state::StateRef) extracts from the produced state type only. Types
+    /// from parent state must be forwarded through [BorrowState](crate::handler::state::BorrowState)
+    /// impl of produced state type and when parent and nested App share the same typed state it's up
+    /// to the impl deciding which one is borrowed. Middlewares enclosing parent App observe parent's
+    /// state and middlewares enclosing nested App observe the produced state.
+    ///
+    /// Nested App constructed with this method can only be used as route service of another App.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use xitca_web::{
+    /// #   handler::{handler_service, state::{BorrowState, StateRef}},
+    /// #   route::get,
+    /// #   App, WebContext
+    /// # };
+    /// // state of parent App.
+    /// #[derive(Clone)]
+    /// struct Db;
+    ///
+    /// // state of nested App carrying a copy of parent state and it's own typed state.
+    /// struct ApiState {
+    ///     db: Db,
+    ///     version: usize,
+    /// }
+    ///
+    /// impl BorrowState<Db> for ApiState {
+    ///     fn borrow(&self) -> &Db {
+    ///         &self.db
+    ///     }
+    /// }
+    ///
+    /// impl BorrowState<usize> for ApiState {
+    ///     fn borrow(&self) -> &usize {
+    ///         &self.version
+    ///     }
+    /// }
+    ///
+    /// // handler of nested App has access to both parent state and it's own state.
+    /// async fn handler(StateRef(_db): StateRef<'_, Db>, StateRef(version): StateRef<'_, usize>) -> String {
+    ///     version.to_string()
+    /// }
+    ///
+    /// App::new()
+    ///     .with_state(Db)
+    ///     .at(
+    ///         "/api",
+    ///         App::new()
+    ///             .with_inherited_state(|db: &Db| ApiState { db: db.clone(), version: 2 })
+    ///             .at("/version", get(handler_service(handler))),
+    ///     )
+    ///     # .at("/nah", handler_service(|_: &WebContext<'_, Db>| async { "used for infer type" }))
+    ///     .finish();
+    /// ```
+    pub fn with_inherited_state<P, C, F>(self, builder: F) -> App<R, InheritCtxBuilder<P, C>>
+    where
+        F: Fn(&P) -> C + Send + Sync + 'static,
+    {
+        App {
+            router: self.router,
+            ctx_builder: Arc::new(builder),
+        }
+    }
 }
 
 impl<R, CF> App<R, CF>
@@ -424,6 +505,30 @@ where
         }
     }
 
+    /// Register an async function producing response from error type `E`. When application service
+    /// produces [Error](crate::error::Error) that can be downcasted to `E` the function is called
+    /// with reference of the error and [WebContext] of the request.
+    ///
+    /// Each call encloses App with a [MapError] middleware. Multiple error types can be registered
+    /// to single middleware with [MapError::on] method.
+    /// See [map_error](crate::middleware::map_error) for more.
+    pub fn map_error<E, F>(self, func: F) -> App<EnclosedBuilder<R, MapErrorOne<E, F>>, CF>
+    where
+        E: error::Error + 'static,
+        F: Clone,
+    {
+        self.enclosed(MapError::new().on::<E, F>(func))
+    }
+
+    /// Add given headers to every response of App when response does not contain header with the same
+    /// name. Error produced by application service is converted to response before headers are added.
+    ///
+    /// Encloses App with a [DefaultHeaders] middleware. See [default_headers](crate::middleware::default_headers)
+    /// for overriding headers and security header preset.
+    pub fn default_headers(self, headers: HeaderMap) -> App<EnclosedBuilder<R, DefaultHeaders>, CF> {
+        self.enclosed(DefaultHeaders::from(headers))
+    }
+
     /// Mutate `<<Self::Response as Service<Req>>::Future as Future>::Output` type with given
     /// closure.
     pub fn map<T, Res, ResMap>(self, mapper: T) -> App<MapBuilder<R, T>, CF>
@@ -597,6 +702,52 @@ where
     }
 }
 
+impl<R, Arg, P, C> Service<Arg> for App<R, InheritCtxBuilder<P, C>>
+where
+    R: Service<Arg>,
+{
+    type Response = InheritAppService<P, C, R::Response>;
+    type Error = R::Error;
+
+    async fn call(&self, req: Arg) -> Result<Self::Response, Self::Error> {
+        let service = self.router.call(req).await?;
+
+        Ok(InheritAppService {
+            ctx_builder: self.ctx_builder.clone(),
+            ctx: OnceCell::new(),
+            service,
+        })
+    }
+}
+
+/// service built by [App] with state inherited from parent [App]. See [App::with_inherited_state] for
+/// detail.
+pub struct InheritAppService<P, C, S> {
+    ctx_builder: InheritCtxBuilder<P, C>,
+    ctx: OnceCell<C>,
+    service: S,
+}
+
+impl<'r, P, C, B, S, ResB, SE> Service<WebContext<'r, P, B>> for InheritAppService<P, C, S>
+where
+    S: for<'r1> Service<WebContext<'r1, C, B>, Response = WebResponse<ResB>, Error = SE>,
+    SE: Into<Error>,
+{
+    type Response = WebResponse<ResB>;
+    type Error = Error;
+
+    async fn call(&self, ctx: WebContext<'r, P, B>) -> Result<Self::Response, Self::Error> {
+        let WebContext { req, body, ctx } = ctx;
+
+        let ctx = self.ctx.get_or_init(|| (self.ctx_builder)(ctx));
+
+        self.service
+            .call(WebContext { req, body, ctx })
+            .await
+            .map_err(Into::into)
+    }
+}
+
 /// object safe [App] instance. used for case where naming [App]'s type is needed.
 pub type AppObject<S> =
     Box<dyn xitca_service::object::ServiceObject<(), Response = S, Error = Box<dyn fmt::Debug>> + Send + Sync>;
@@ -607,8 +758,8 @@ mod test {
 
     use crate::{
         handler::{
-            extension::ExtensionRef, extension::ExtensionsRef, handler_service, path::PathRef, state::StateRef,
-            uri::UriRef,
+            extension::ExtensionRef, extension::ExtensionsRef, handler_service, path::PathRef, state::BorrowState,
+            state::StateRef, uri::UriRef,
         },
         http::{const_header_value::TEXT_UTF8, header::CONTENT_TYPE, request, Method},
         middleware::UncheckedReady,
@@ -725,6 +876,99 @@ mod test {
         assert_eq!(res.status().as_u16(), 405);
     }
 
+    #[test]
+    fn app_nest_inherit_state() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Nest {
+            parent: String,
+            state: usize,
+        }
+
+        impl BorrowState<String> for Nest {
+            fn borrow(&self) -> &String {
+                &self.parent
+            }
+        }
+
+        impl BorrowState<usize> for Nest {
+            fn borrow(&self) -> &usize {
+                &self.state
+            }
+        }
+
+        async fn handler(StateRef(parent): StateRef<'_, String>, StateRef(state): StateRef<'_, usize>) -> String {
+            assert_eq!("state", parent);
+            assert_eq!(996, *state);
+            state.to_string()
+        }
+
+        let built = Arc::new(AtomicUsize::new(0));
+        let built2 = built.clone();
+
+        let service = App::new()
+            .with_state(String::from("state"))
+            .at(
+                "/scope",
+                App::new()
+                    .with_inherited_state(move |parent: &String| {
+                        built2.fetch_add(1, Ordering::Relaxed);
+                        Nest {
+                            parent: parent.clone(),
+                            state: 996,
+                        }
+                    })
+                    .at("/nest", get(handler_service(handler))),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .ok()
+            .unwrap();
+
+        for _ in 0..2 {
+            let req = request::Builder::default()
+                .uri("/scope/nest")
+                .body(Default::default())
+                .unwrap();
+            let res = service.call(req).now_or_panic().unwrap();
+            assert_eq!(res.status().as_u16(), 200);
+        }
+
+        // state is produced once per service.
+        assert_eq!(built.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn app_nest_inherit_state_custom_body() {
+        // request body type other than default RequestBody.
+        #[derive(Default)]
+        struct CustomBody;
+
+        async fn handler(ctx: WebContext<'_, usize, CustomBody>) -> Result<WebResponse, Infallible> {
+            let _: CustomBody = ctx.take_body_ref();
+            assert_eq!(*ctx.state(), 5);
+            Ok(WebResponse::new(ResponseBody::none()))
+        }
+
+        let service = App::new()
+            .with_inherited_state(|parent: &String| parent.len())
+            .at("/", xitca_service::fn_service(handler))
+            .call(())
+            .now_or_panic()
+            .ok()
+            .unwrap();
+
+        let mut req = WebRequest::default();
+        let mut body = core::cell::RefCell::new(CustomBody);
+        let state = String::from("state");
+        let res = service
+            .call(WebContext::new(&mut req, &mut body, &state))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status().as_u16(), 200);
+    }
+
     #[derive(Clone)]
     struct Foo;
 
@@ -0,0 +1,250 @@
+use xitca_http::util::service::router::{IntoObject, RouteGen, TypedRoute};
+
+use crate::{
+    context::WebContext,
+    service::{middleware, EnclosedBuilder, Service},
+};
+
+use super::{router::AppRouter, App};
+
+/// group of routes sharing the same path prefix and middlewares. constructed with [App::scope] and
+/// registered to application with [App::at_scope].
+///
+/// Middlewares enclosed by scope apply to all routes of it regardless of the order [Scope::enclosed]
+/// and [Scope::at] are called. Between middlewares the one enclosed later is the outer one and it
+/// observes request first, the same as [App::enclosed].
+///
+/// # Example
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::handler_service,
+/// #   route::get,
+/// #   service::Service,
+/// #   App, WebContext
+/// # };
+/// // a passthrough middleware function.
+/// async fn auth<S, C, B, Res, Err>(service: &S, ctx: WebContext<'_, C, B>) -> Result<Res, Err>
+/// where
+///     S: for<'r> Service<WebContext<'r, C, B>, Response = Res, Error = Err>,
+/// {
+///     service.call(ctx).await
+/// }
+///
+/// App::new()
+///     .at("/", get(handler_service(|| async { "index" })))
+///     // "/api/v1/users" and "/api/v1/posts" are enclosed by auth middleware while "/" is not.
+///     .at_scope(
+///         App::scope("/api/v1")
+///             .enclosed_fn(auth)
+///             .at("/users", get(handler_service(|| async { "users" })))
+///             .at("/posts", get(handler_service(|| async { "posts" }))),
+///     )
+///     .finish();
+/// ```
+pub struct Scope<R, M = ()> {
+    prefix: &'static str,
+    router: R,
+    middleware: M,
+}
+
+impl App {
+    /// Construct a new [Scope] with given path prefix.
+    pub fn scope<Obj>(prefix: &'static str) -> Scope<AppRouter<Obj>> {
+        Scope {
+            prefix,
+            router: AppRouter::new(),
+            middleware: (),
+        }
+    }
+}
+
+impl<Obj, CF> App<AppRouter<Obj>, CF> {
+    /// insert [Scope] to application. routes of scope are registered with scope's path prefix.
+    pub fn at_scope<M, F, C, B>(self, scope: Scope<AppRouter<Obj>, M>) -> Self
+    where
+        M: ScopeMiddleware<AppRouter<Obj>, Output = F>,
+        F: RouteGen + Service + Send + Sync,
+        F::Response: for<'r> Service<WebContext<'r, C, B>>,
+        for<'r> WebContext<'r, C, B>: IntoObject<F::Route<F>, (), Object = Obj>,
+    {
+        let (prefix, router) = scope.into_parts();
+        self.at(prefix, router)
+    }
+}
+
+impl<Obj, M> Scope<AppRouter<Obj>, M> {
+    /// insert routed service with given path to scope. path is prefixed with scope's prefix.
+    /// See [App::at] for routing rules.
+    pub fn at<F, C, B>(mut self, path: &'static str, builder: F) -> Self
+    where
+        F: RouteGen + Service + Send + Sync,
+        F::Response: for<'r> Service<WebContext<'r, C, B>>,
+        for<'r> WebContext<'r, C, B>: IntoObject<F::Route<F>, (), Object = Obj>,
+    {
+        self.router = self.router.insert(path, builder);
+        self
+    }
+
+    /// insert typed route service with given path to scope.
+    pub fn at_typed<T, C>(mut self, typed: T) -> Self
+    where
+        T: TypedRoute<C, Route = Obj>,
+    {
+        self.router = self.router.insert_typed(typed);
+        self
+    }
+
+    /// insert nested [Scope]. path prefix of nested scope is appended to current one.
+    pub fn at_scope<M1, F, C, B>(self, scope: Scope<AppRouter<Obj>, M1>) -> Self
+    where
+        M1: ScopeMiddleware<AppRouter<Obj>, Output = F>,
+        F: RouteGen + Service + Send + Sync,
+        F::Response: for<'r> Service<WebContext<'r, C, B>>,
+        for<'r> WebContext<'r, C, B>: IntoObject<F::Route<F>, (), Object = Obj>,
+    {
+        let (prefix, router) = scope.into_parts();
+        self.at(prefix, router)
+    }
+
+    /// Enclose all routes of scope with middleware type. Middleware must impl [Service] trait.
+    /// See [middleware](crate::middleware) for more.
+    pub fn enclosed<T>(self, transform: T) -> Scope<AppRouter<Obj>, (M, T)> {
+        Scope {
+            prefix: self.prefix,
+            router: self.router,
+            middleware: (self.middleware, transform),
+        }
+    }
+
+    /// Enclose all routes of scope with function as middleware type.
+    /// See [middleware](crate::middleware) for more.
+    pub fn enclosed_fn<T>(self, transform: T) -> Scope<AppRouter<Obj>, (M, middleware::AsyncFn<T>)> {
+        self.enclosed(middleware::AsyncFn(transform))
+    }
+}
+
+impl<R, M> Scope<R, M>
+where
+    M: ScopeMiddleware<R>,
+{
+    fn into_parts(self) -> (&'static str, M::Output) {
+        (self.prefix, self.middleware.apply(self.router))
+    }
+}
+
+// helper trait for applying middlewares enclosed by Scope to it's router in enclosing order.
+pub trait ScopeMiddleware<R> {
+    type Output;
+
+    fn apply(self, router: R) -> Self::Output;
+}
+
+impl<R> ScopeMiddleware<R> for () {
+    type Output = R;
+
+    fn apply(self, router: R) -> Self::Output {
+        router
+    }
+}
+
+impl<R, M, T> ScopeMiddleware<R> for (M, T)
+where
+    M: ScopeMiddleware<R>,
+{
+    type Output = EnclosedBuilder<M::Output, T>;
+
+    fn apply(self, router: R) -> Self::Output {
+        EnclosedBuilder::new(self.0.apply(router), self.1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::{handler_service, path::PathRef},
+        http::{header::HeaderValue, request, StatusCode, WebResponse},
+        route::get,
+    };
+
+    use super::*;
+
+    // middleware appending given tag to x-tag response header.
+    async fn tag<S, C, B, Err>(service: &S, ctx: WebContext<'_, C, B>, tag: &'static str) -> Result<WebResponse, Err>
+    where
+        S: for<'r> Service<WebContext<'r, C, B>, Response = WebResponse, Error = Err>,
+    {
+        let mut res = service.call(ctx).await?;
+        res.headers_mut().append("x-tag", HeaderValue::from_static(tag));
+        Ok(res)
+    }
+
+    macro_rules! tag_fn {
+        ($name: ident) => {
+            async fn $name<S, C, B, Err>(service: &S, ctx: WebContext<'_, C, B>) -> Result<WebResponse, Err>
+            where
+                S: for<'r> Service<WebContext<'r, C, B>, Response = WebResponse, Error = Err>,
+            {
+                tag(service, ctx, stringify!($name)).await
+            }
+        };
+    }
+
+    tag_fn!(inner);
+    tag_fn!(outer);
+    tag_fn!(nest);
+
+    #[test]
+    fn scope() {
+        async fn handler(PathRef(path): PathRef<'_>) -> String {
+            path.to_string()
+        }
+
+        let service = App::new()
+            .at("/", get(handler_service(handler)))
+            .at_scope(
+                App::scope("/api")
+                    .enclosed_fn(inner)
+                    .at("/users", get(handler_service(handler)))
+                    .enclosed_fn(outer)
+                    .at_scope(
+                        App::scope("/v1")
+                            .at("/posts", get(handler_service(handler)))
+                            .enclosed_fn(nest),
+                    ),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |path: &'static str| {
+            let req = request::Builder::default().uri(path).body(Default::default()).unwrap();
+            service.call(req).now_or_panic().unwrap()
+        };
+
+        let tags = |res: &WebResponse<_>| {
+            res.headers()
+                .get_all("x-tag")
+                .iter()
+                .map(|v| v.to_str().unwrap().to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let res = call("/");
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(tags(&res).is_empty());
+
+        let res = call("/api/users");
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(tags(&res), ["inner", "outer"]);
+
+        let res = call("/api/v1/posts");
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(tags(&res), ["nest", "inner", "outer"]);
+
+        let res = call("/users");
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,12 +1,17 @@
 //! http body types and traits.
 
+use core::{future::poll_fn, pin::Pin};
+
 use futures_core::stream::Stream;
 
 pub use xitca_http::body::{none_body_hint, BoxBody, RequestBody, ResponseBody, NONE_BODY_HINT};
 
 pub(crate) use xitca_http::body::Either;
 
-use crate::error::BodyError;
+use crate::{
+    bytes::{Bytes, BytesMut},
+    error::{BodyError, BodyIncomplete, BodyOverFlow, Error},
+};
 
 /// an extended trait for [Stream] that specify additional type info of the [Stream::Item] type.
 pub trait BodyStream: Stream<Item = Result<Self::Chunk, Self::Error>> {
@@ -24,6 +29,151 @@ where
     type Error = E;
 }
 
+/// buffered reader over [BodyStream] for reading request body in frames of custom wire protocol.
+///
+/// Chunks of body stream are buffered internally and only the requested bytes are split from buffer
+/// and returned. When body size limit is set pulling more bytes than limit from body stream produces
+/// [BodyOverFlow] error which would be converted to `413 Payload Too Large` response.
+///
+/// BodyReader can be used as type extractor where it takes the request body and applies the limit of
+/// [PayloadConfig](crate::handler::body::PayloadConfig).
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{body::BodyReader, error::Error, handler::handler_service, route::post, App};
+/// // a handler reading length prefixed frames from request body.
+/// async fn handler(mut reader: BodyReader) -> Result<String, Error> {
+///     let mut frames = 0;
+///     while let Some(len) = reader.read_exact(4).await? {
+///         let len = u32::from_be_bytes(len[..].try_into().unwrap()) as usize;
+///         let Some(_frame) = reader.read_exact(len).await? else {
+///             break;
+///         };
+///         frames += 1;
+///     }
+///     Ok(format!("received {frames} frames"))
+/// }
+///
+/// App::new().at("/", post(handler_service(handler))).finish();
+/// ```
+pub struct BodyReader<B = RequestBody> {
+    body: B,
+    buf: BytesMut,
+    // total number of bytes pulled from body stream.
+    read: usize,
+    limit: usize,
+    length: Option<usize>,
+    eof: bool,
+}
+
+impl<B> BodyReader<B> {
+    /// construct a new reader over given body stream with no size limit.
+    pub fn new(body: B) -> Self {
+        Self {
+            body,
+            buf: BytesMut::new(),
+            read: 0,
+            limit: 0,
+            length: None,
+            eof: false,
+        }
+    }
+
+    /// set limit of total bytes pulled from body stream. 0 means unlimited.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// set expected total length of body stream. usually from `Content-Length` header. it's used for
+    /// [BodyReader::remaining_hint] only.
+    pub fn length(mut self, length: usize) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// bounds on the remaining bytes of body. lower bound is the number of buffered bytes and upper
+    /// bound is derived from the length given to [BodyReader::length]. when length is known the
+    /// remaining bytes are assumed to be exact.
+    pub fn remaining_hint(&self) -> (usize, Option<usize>) {
+        match self.length {
+            Some(len) => {
+                let remaining = len.saturating_sub(self.read) + self.buf.len();
+                (remaining, Some(remaining))
+            }
+            None if self.eof => (self.buf.len(), Some(self.buf.len())),
+            None => (self.buf.len(), None),
+        }
+    }
+
+    /// consume the reader and return bytes that are buffered but not read yet and the body stream.
+    pub fn into_parts(self) -> (Bytes, B) {
+        (self.buf.freeze(), self.body)
+    }
+}
+
+impl<B> BodyReader<B>
+where
+    B: BodyStream + Unpin,
+{
+    /// read exactly n bytes from body.
+    ///
+    /// Return `Ok(None)` when body ends with no byte left. Return [BodyIncomplete] error when body ends
+    /// with less than n bytes left.
+    pub async fn read_exact(&mut self, n: usize) -> Result<Option<Bytes>, Error> {
+        while self.buf.len() < n {
+            if !self.fill().await? {
+                return match self.buf.is_empty() {
+                    true => Ok(None),
+                    false => Err(Error::from(BodyIncomplete)),
+                };
+            }
+        }
+        Ok(Some(self.buf.split_to(n).freeze()))
+    }
+
+    /// read bytes until given delimiter byte is found. returned bytes include the delimiter.
+    ///
+    /// Return `Ok(None)` when body ends with no byte left. When body ends before delimiter is found the
+    /// remaining bytes are returned without delimiter.
+    pub async fn read_until(&mut self, byte: u8) -> Result<Option<Bytes>, Error> {
+        let mut searched = 0;
+        loop {
+            if let Some(idx) = self.buf[searched..].iter().position(|b| *b == byte) {
+                return Ok(Some(self.buf.split_to(searched + idx + 1).freeze()));
+            }
+            searched = self.buf.len();
+            if !self.fill().await? {
+                return Ok((!self.buf.is_empty()).then(|| self.buf.split().freeze()));
+            }
+        }
+    }
+
+    // pull a chunk from body stream into buffer. return false when body stream is ended.
+    async fn fill(&mut self) -> Result<bool, Error> {
+        if self.eof {
+            return Ok(false);
+        }
+
+        match poll_fn(|cx| Pin::new(&mut self.body).poll_next(cx)).await {
+            Some(chunk) => {
+                let chunk = chunk.map_err(|e| Error::from(e.into()))?;
+                let chunk = chunk.as_ref();
+                self.read += chunk.len();
+                if self.limit > 0 && self.read > self.limit {
+                    return Err(Error::from(BodyOverFlow { limit: self.limit }));
+                }
+                self.buf.extend_from_slice(chunk);
+                Ok(true)
+            }
+            None => {
+                self.eof = true;
+                Ok(false)
+            }
+        }
+    }
+}
+
 #[cfg(feature = "nightly")]
 pub use nightly::AsyncBody;
 
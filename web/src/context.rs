@@ -2,6 +2,7 @@
 
 use core::{
     cell::{Ref, RefCell, RefMut},
+    future::Future,
     mem,
 };
 
@@ -9,6 +10,7 @@ use super::{
     body::{RequestBody, ResponseBody},
     handler::FromRequest,
     http::{BorrowReq, BorrowReqMut, IntoResponse, Request, RequestExt, WebRequest, WebResponse},
+    service::{Reborrow, Service},
 };
 
 /// web context type focus on stateful and side effect based request data access.
@@ -117,12 +119,44 @@ impl<'a, C, B> WebContext<'a, C, B> {
         self.body.get_mut()
     }
 
+    /// Take ownership of [RequestBody] and leave a default one in it's place.
+    ///
+    /// Like [WebContext::body_get_mut] this API bypass runtime borrow checker.
+    #[inline]
+    pub fn take_body(&mut self) -> B
+    where
+        B: Default,
+    {
+        mem::take(self.body_get_mut())
+    }
+
+    /// Replace [RequestBody] with the output of given function. The function receives ownership of
+    /// current body and it's useful for wrapping body with other types. (counting, decompressing,
+    /// limiting for example)
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_web::{body::{BoxBody, RequestBody}, WebContext};
+    /// fn wrap(ctx: &mut WebContext<'_>) {
+    ///     // box current body and wrap it with a new request body type.
+    ///     ctx.map_body(|body| RequestBody::from(BoxBody::new(body)));
+    /// }
+    /// ```
+    pub fn map_body<F>(&mut self, func: F)
+    where
+        B: Default,
+        F: FnOnce(B) -> B,
+    {
+        let body = self.take_body();
+        *self.body_get_mut() = func(body);
+    }
+
     pub fn take_request(&mut self) -> WebRequest<B>
     where
         B: Default,
     {
         let head = mem::take(self.req_mut());
-        let body = self.take_body_mut();
+        let body = self.take_body();
         head.map(|ext| ext.map_body(|_| body))
     }
 
@@ -148,13 +182,6 @@ impl<'a, C, B> WebContext<'a, C, B> {
     {
         mem::take(&mut *self.body_borrow_mut())
     }
-
-    pub(crate) fn take_body_mut(&mut self) -> B
-    where
-        B: Default,
-    {
-        mem::take(self.body_get_mut())
-    }
 }
 
 impl<C, B, T> BorrowReq<T> for WebContext<'_, C, B>
@@ -175,6 +202,20 @@ where
     }
 }
 
+// enable WebContext to be used with ServiceExt::or_else combinator.
+impl<C, B, S, Res, Err> Reborrow<S> for WebContext<'_, C, B>
+where
+    S: for<'r> Service<WebContext<'r, C, B>, Response = Res, Error = Err>,
+{
+    type Response = Res;
+    type Error = Err;
+
+    #[inline]
+    fn call_reborrow(&mut self, service: &S) -> impl Future<Output = Result<Self::Response, Self::Error>> {
+        service.call(self.reborrow())
+    }
+}
+
 #[cfg(test)]
 pub(crate) struct TestWebContext<C> {
     pub(crate) req: Request<RequestExt<()>>,
@@ -227,4 +268,54 @@ mod test {
 
         assert_eq!(path, "/foo");
     }
+
+    #[test]
+    fn map_body() {
+        use crate::bytes::Bytes;
+
+        let mut ctx = WebContext::new_test(());
+        let mut ctx = ctx.as_web_ctx();
+
+        ctx.map_body(|body| {
+            assert!(matches!(body, RequestBody::None));
+            RequestBody::from(Bytes::from_static(b"996"))
+        });
+        assert!(matches!(*ctx.body(), RequestBody::Unknown(_)));
+
+        assert!(matches!(ctx.take_body(), RequestBody::Unknown(_)));
+        assert!(matches!(*ctx.body(), RequestBody::None));
+    }
+
+    #[test]
+    fn or_else() {
+        use crate::service::{fn_service, ServiceExt};
+
+        async fn index(ctx: WebContext<'_, usize>) -> Result<usize, &'static str> {
+            match ctx.req().uri().path() {
+                "/" => Ok(*ctx.state()),
+                _ => Err("not found"),
+            }
+        }
+
+        async fn fallback(mut ctx: WebContext<'_, usize>) -> Result<usize, &'static str> {
+            // request is still owned by fallback service after it's reborrowed by first service.
+            *ctx.req_mut().uri_mut() = crate::http::Uri::from_static("/fallback");
+            Ok(*ctx.state() + 1)
+        }
+
+        let service = fn_service(index)
+            .or_else(fn_service(fallback), |e: &&str| *e == "not found")
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let mut ctx = WebContext::new_test(996usize);
+        let mut ctx = ctx.as_web_ctx();
+
+        assert_eq!(service.call(ctx.reborrow()).now_or_panic(), Ok(996));
+
+        *ctx.req_mut().uri_mut() = crate::http::Uri::from_static("/foo");
+        assert_eq!(service.call(ctx.reborrow()).now_or_panic(), Ok(997));
+        assert_eq!(ctx.req().uri().path(), "/fallback");
+    }
 }
@@ -2,6 +2,8 @@ use core::fmt;
 
 use std::error;
 
+use crate::{http::WebResponse, service::Service, WebContext};
+
 use super::{error_from_service, forward_blank_bad_request};
 
 pub use xitca_http::error::BodyError;
@@ -20,4 +22,27 @@ impl fmt::Display for BodyOverFlow {
 impl error::Error for BodyOverFlow {}
 
 error_from_service!(BodyOverFlow);
-forward_blank_bad_request!(BodyOverFlow);
+
+impl<'r, C, B> Service<WebContext<'r, C, B>> for BodyOverFlow {
+    type Response = WebResponse;
+    type Error = core::convert::Infallible;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        crate::http::StatusCode::PAYLOAD_TOO_LARGE.call(ctx).await
+    }
+}
+
+/// error type for request body ended before expected bytes are received.
+#[derive(Debug, Clone)]
+pub struct BodyIncomplete;
+
+impl fmt::Display for BodyIncomplete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("body ended before expected bytes are received")
+    }
+}
+
+impl error::Error for BodyIncomplete {}
+
+error_from_service!(BodyIncomplete);
+forward_blank_bad_request!(BodyIncomplete);
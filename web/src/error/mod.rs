@@ -73,12 +73,16 @@
 mod body;
 mod extension;
 mod header;
+#[cfg(feature = "json")]
+mod problem;
 mod router;
 mod status;
 
 pub use body::*;
 pub use extension::*;
 pub use header::*;
+#[cfg(feature = "json")]
+pub use problem::ProblemDetails;
 pub use router::*;
 pub use status::*;
 
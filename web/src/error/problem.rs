@@ -0,0 +1,220 @@
+use core::{convert::Infallible, fmt};
+
+use std::{borrow::Cow, error};
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::{
+    body::ResponseBody,
+    bytes::{BufMutWriter, BytesMut},
+    http::{
+        header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE},
+        StatusCode, WebResponse,
+    },
+    service::Service,
+    WebContext,
+};
+
+use super::{error_from_service, Error};
+
+const PROBLEM_JSON: HeaderValue = HeaderValue::from_static("application/problem+json");
+
+/// error type rendered as [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807) `application/problem+json`
+/// response.
+///
+/// `type` member defaults to `about:blank` and `title` member defaults to canonical reason of
+/// status code. `detail` and `instance` members are omitted from response when not set. Headers added
+/// with [ProblemDetails::header] are appended to response.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{error::ProblemDetails, handler::handler_service, http::StatusCode, App};
+/// async fn handler() -> Result<&'static str, ProblemDetails> {
+///     Err(ProblemDetails::new(StatusCode::FORBIDDEN)
+///         .type_uri("https://example.com/probs/out-of-credit")
+///         .title("You do not have enough credit.")
+///         .detail("Your current balance is 30, but that costs 50.")
+///         .instance("/account/12345/msgs/abc"))
+/// }
+///
+/// App::new().at("/", handler_service(handler)).finish();
+/// ```
+pub struct ProblemDetails {
+    type_uri: Cow<'static, str>,
+    title: Cow<'static, str>,
+    status: StatusCode,
+    detail: Option<Cow<'static, str>>,
+    instance: Option<Cow<'static, str>>,
+    headers: HeaderMap,
+    source: Option<Error>,
+}
+
+impl ProblemDetails {
+    /// construct problem details with given status code.
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            type_uri: Cow::Borrowed("about:blank"),
+            title: Cow::Borrowed(status.canonical_reason().unwrap_or("")),
+            status,
+            detail: None,
+            instance: None,
+            headers: HeaderMap::new(),
+            source: None,
+        }
+    }
+
+    /// set URI reference identifying the problem type.
+    pub fn type_uri(mut self, type_uri: impl Into<Cow<'static, str>>) -> Self {
+        self.type_uri = type_uri.into();
+        self
+    }
+
+    /// set short human readable summary of the problem type.
+    pub fn title(mut self, title: impl Into<Cow<'static, str>>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// set human readable explanation specific to this occurrence of the problem.
+    pub fn detail(mut self, detail: impl Into<Cow<'static, str>>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// set URI reference identifying the specific occurrence of the problem.
+    pub fn instance(mut self, instance: impl Into<Cow<'static, str>>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// append header to response. e.g. `Allow` header for `405 Method Not Allowed` problem.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// mutable reference of headers appended to response.
+    pub fn headers_mut(&mut self) -> &mut HeaderMap {
+        &mut self.headers
+    }
+
+    /// set the error causing the problem. it's not rendered to response and can be accessed through
+    /// [error::Error::source].
+    pub fn source(mut self, source: Error) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// status code of the problem.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+}
+
+impl fmt::Debug for ProblemDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProblemDetails")
+            .field("type", &self.type_uri)
+            .field("title", &self.title)
+            .field("status", &self.status)
+            .field("detail", &self.detail)
+            .field("instance", &self.instance)
+            .field("headers", &self.headers)
+            .finish()
+    }
+}
+
+impl fmt::Display for ProblemDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.detail {
+            Some(ref detail) => write!(f, "{}: {detail}", self.title),
+            None => f.write_str(&self.title),
+        }
+    }
+}
+
+impl error::Error for ProblemDetails {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.source.as_ref().map(|e| e.upcast())
+    }
+}
+
+impl Serialize for ProblemDetails {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let len = 3 + usize::from(self.detail.is_some()) + usize::from(self.instance.is_some());
+        let mut state = serializer.serialize_struct("ProblemDetails", len)?;
+        state.serialize_field("type", &self.type_uri)?;
+        state.serialize_field("title", &self.title)?;
+        state.serialize_field("status", &self.status.as_u16())?;
+        if let Some(ref detail) = self.detail {
+            state.serialize_field("detail", detail)?;
+        }
+        if let Some(ref instance) = self.instance {
+            state.serialize_field("instance", instance)?;
+        }
+        state.end()
+    }
+}
+
+error_from_service!(ProblemDetails);
+
+impl<'r, C, B> Service<WebContext<'r, C, B>> for ProblemDetails {
+    type Response = WebResponse;
+    type Error = Infallible;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let mut bytes = BytesMut::new();
+        // serializing string and integer fields to json can not fail.
+        let _ = serde_json::to_writer(BufMutWriter(&mut bytes), self);
+
+        let mut res = ctx.into_response(ResponseBody::from(bytes.freeze()));
+        *res.status_mut() = self.status;
+        res.headers_mut().extend(self.headers.clone());
+        res.headers_mut().insert(CONTENT_TYPE, PROBLEM_JSON);
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::test::collect_string_body;
+
+    use super::*;
+
+    #[test]
+    fn problem_details() {
+        let mut ctx = WebContext::new_test(());
+
+        let res = ProblemDetails::new(StatusCode::NOT_FOUND)
+            .header(CONTENT_TYPE, HeaderValue::from_static("text/plain"))
+            .header(crate::http::header::RETRY_AFTER, HeaderValue::from_static("5"))
+            .call(ctx.as_web_ctx())
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), PROBLEM_JSON);
+        assert_eq!(res.headers().get(crate::http::header::RETRY_AFTER).unwrap(), "5");
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(body, r#"{"type":"about:blank","title":"Not Found","status":404}"#);
+
+        let err = Error::from(
+            ProblemDetails::new(StatusCode::FORBIDDEN)
+                .type_uri("https://example.com/probs/out-of-credit")
+                .title("You do not have enough credit.")
+                .detail("Your current balance is 30, but that costs 50.")
+                .instance("/account/12345/msgs/abc"),
+        );
+        let res = err.call(ctx.as_web_ctx()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert_eq!(
+            body,
+            r#"{"type":"https://example.com/probs/out-of-credit","title":"You do not have enough credit.","status":403,"detail":"Your current balance is 30, but that costs 50.","instance":"/account/12345/msgs/abc"}"#
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! type extractor for claims of authenticated request.
+
+use core::ops::Deref;
+
+use crate::{
+    context::WebContext,
+    error::{Error, ExtensionNotFound},
+    handler::FromRequest,
+};
+
+/// Extract claims of bearer token validated by [`Jwt`] middleware.
+///
+/// Extracting fails with [`ExtensionNotFound`] error when the middleware is not applied or it's
+/// configured with different claims type.
+///
+/// [`Jwt`]: crate::middleware::jwt::Jwt
+#[derive(Clone, Debug)]
+pub struct Claims<T>(pub T);
+
+impl<T> Deref for Claims<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a, 'r, C, B, T> FromRequest<'a, WebContext<'r, C, B>> for Claims<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    type Type<'b> = Claims<T>;
+    type Error = Error;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        ctx.req()
+            .extensions()
+            .get::<Claims<T>>()
+            .cloned()
+            .ok_or_else(|| Error::from_service(ExtensionNotFound::from_type::<Claims<T>>()))
+    }
+}
@@ -3,7 +3,7 @@
 use core::{cmp, convert::Infallible, future::poll_fn, pin::pin};
 
 use crate::{
-    body::{BodyStream, BoxBody, ResponseBody},
+    body::{BodyReader, BodyStream, BoxBody, ResponseBody},
     bytes::{Bytes, BytesMut},
     context::WebContext,
     error::{BodyOverFlow, Error},
@@ -28,13 +28,82 @@ where
     }
 }
 
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for BodyReader<B>
+where
+    B: BodyStream + Default,
+{
+    type Type<'b> = BodyReader<B>;
+    type Error = Error;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        let reader = BodyReader::new(ctx.take_body_ref()).limit(PayloadConfig::from_ctx(ctx));
+        Ok(match content_length(ctx).await {
+            Some(len) => reader.length(len),
+            None => reader,
+        })
+    }
+}
+
 /// helper type for limiting body size.
 /// when LIMIT > 0 body size is limited to LIMIT in bytes.
 /// when LIMIT == 0 body size is unlimited.
+///
+/// Explicit limit takes priority over [PayloadConfig].
 pub struct Limit<const LIMIT: usize>;
 
+/// default body size limit of [PayloadConfig] in bytes.
+pub const DEFAULT_LIMIT: usize = 1024 * 1024;
+
+/// configuration of body size limit for [Bytes], [BytesMut], `Vec<u8>` and [String] type extractors.
+///
+/// Extractors look up the configuration from request's [Extensions] and fall back to [DEFAULT_LIMIT]
+/// when it's absent. Request body exceeding the limit produces [BodyOverFlow] error which would be
+/// converted to `413 Payload Too Large` response.
+///
+/// Use `(T, Limit<LIMIT>)` for per handler limit which bypasses the configuration.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::{body::PayloadConfig, handler_service},
+/// #   middleware::Extension,
+/// #   route::post,
+/// #   App, WebContext,
+/// # };
+/// App::new()
+///     .at("/", post(handler_service(|body: String| async move { body })))
+///     # .at("/infer", handler_service(|_: &WebContext<'_>| async { "" }))
+///     // limit body to 64KB. 0 means unlimited.
+///     .enclosed(Extension::new(PayloadConfig::limit(64 * 1024)))
+///     .finish();
+/// ```
+///
+/// [Extensions]: crate::http::Extensions
+#[derive(Clone, Copy, Debug)]
+pub struct PayloadConfig {
+    limit: usize,
+}
+
+impl Default for PayloadConfig {
+    fn default() -> Self {
+        Self::limit(DEFAULT_LIMIT)
+    }
+}
+
+impl PayloadConfig {
+    /// construct configuration with given limit in bytes. 0 means unlimited.
+    pub const fn limit(limit: usize) -> Self {
+        Self { limit }
+    }
+
+    fn from_ctx<C, B>(ctx: &WebContext<'_, C, B>) -> usize {
+        ctx.req().extensions().get::<Self>().copied().unwrap_or_default().limit
+    }
+}
+
 macro_rules! from_bytes_impl {
-    ($type: ty, $original: tt) => {
+    ($type: ty) => {
         impl<'a, 'r, C, B, const LIMIT: usize> FromRequest<'a, WebContext<'r, C, B>> for ($type, Limit<LIMIT>)
         where
             B: BodyStream + Default,
@@ -42,37 +111,18 @@ macro_rules! from_bytes_impl {
             type Type<'b> = ($type, Limit<LIMIT>);
             type Error = Error;
 
+            #[inline]
             async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
-                let limit = HeaderRef::<'a, { header::CONTENT_LENGTH }>::from_request(ctx)
-                    .await
-                    .ok()
-                    .and_then(|header| header.to_str().ok().and_then(|s| s.parse().ok()))
-                    // when content length is 0 the http library should be producing an immediate
-                    // yielding streaming body which result in an empty body collection type.
-                    .map(|len| cmp::min(len, LIMIT))
-                    .unwrap_or_else(|| LIMIT);
-
-                let body = ctx.take_body_ref();
-
-                let mut body = pin!(body);
-
                 let mut buf = <$type>::new();
-
-                while let Some(chunk) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
-                    let chunk = chunk.map_err(Into::into)?;
-                    buf.extend_from_slice(chunk.as_ref());
-                    if limit > 0 && buf.len() > limit {
-                        return Err(Error::from(BodyOverFlow { limit }));
-                    }
-                }
-
+                collect(ctx, LIMIT, |chunk| {
+                    buf.extend_from_slice(chunk);
+                    buf.len()
+                })
+                .await?;
                 Ok((buf, Limit))
             }
         }
 
-        from_bytes_impl!($type);
-    };
-    ($type: ty) => {
         impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for $type
         where
             B: BodyStream + Default,
@@ -82,16 +132,58 @@ macro_rules! from_bytes_impl {
 
             #[inline]
             async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
-                <($type, Limit<0>)>::from_request(ctx)
-                    .await
-                    .map(|(bytes, _)| bytes)
+                let limit = PayloadConfig::from_ctx(ctx);
+                let mut buf = <$type>::new();
+                collect(ctx, limit, |chunk| {
+                    buf.extend_from_slice(chunk);
+                    buf.len()
+                })
+                .await?;
+                Ok(buf)
             }
         }
     };
 }
 
-from_bytes_impl!(BytesMut, _);
-from_bytes_impl!(Vec<u8>, _);
+pub(super) async fn collect<C, B>(
+    ctx: &WebContext<'_, C, B>,
+    limit: usize,
+    mut extend: impl FnMut(&[u8]) -> usize,
+) -> Result<(), Error>
+where
+    B: BodyStream + Default,
+{
+    let limit = content_length(ctx)
+        .await
+        // when content length is 0 the http library should be producing an immediate
+        // yielding streaming body which result in an empty body collection type.
+        .map(|len| cmp::min(len, limit))
+        .unwrap_or(limit);
+
+    let body = ctx.take_body_ref();
+
+    let mut body = pin!(body);
+
+    while let Some(chunk) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+        let chunk = chunk.map_err(Into::into)?;
+        let len = extend(chunk.as_ref());
+        if limit > 0 && len > limit {
+            return Err(Error::from(BodyOverFlow { limit }));
+        }
+    }
+
+    Ok(())
+}
+
+async fn content_length<C, B>(ctx: &WebContext<'_, C, B>) -> Option<usize> {
+    HeaderRef::<'_, { header::CONTENT_LENGTH }>::from_request(ctx)
+        .await
+        .ok()
+        .and_then(|header| header.to_str().ok().and_then(|s| s.parse().ok()))
+}
+
+from_bytes_impl!(BytesMut);
+from_bytes_impl!(Vec<u8>);
 
 impl<'a, 'r, C, B, const LIMIT: usize> FromRequest<'a, WebContext<'r, C, B>> for (Bytes, Limit<LIMIT>)
 where
@@ -108,7 +200,18 @@ where
     }
 }
 
-from_bytes_impl!(Bytes);
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for Bytes
+where
+    B: BodyStream + Default,
+{
+    type Type<'b> = Bytes;
+    type Error = Error;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        BytesMut::from_request(ctx).await.map(Into::into)
+    }
+}
 
 macro_rules! responder_impl {
     ($type: ty) => {
@@ -162,3 +265,108 @@ impl<'r, C, B> Responder<WebContext<'r, C, B>> for BoxBody {
         Responder::<WebContext<'r, C, B>>::map(ResponseBody::stream(self), res)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        http::{Extensions, StatusCode},
+        service::Service,
+    };
+
+    use super::*;
+
+    #[test]
+    fn payload_config() {
+        let mut ctx = WebContext::new_test(());
+        let mut ctx = ctx.as_web_ctx();
+
+        *ctx.body_borrow_mut() = vec![0; DEFAULT_LIMIT + 1].into();
+        let err = Bytes::from_request(&ctx).now_or_panic().unwrap_err();
+        let res = err.call(ctx.reborrow()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let mut ext = Extensions::new();
+        ext.insert(PayloadConfig::limit(4));
+        *ctx.req_mut().extensions_mut() = ext;
+
+        *ctx.body_borrow_mut() = b"996".as_slice().into();
+        assert_eq!(String::from_request(&ctx).now_or_panic().unwrap(), "996");
+
+        *ctx.body_borrow_mut() = b"99610".as_slice().into();
+        assert!(Vec::<u8>::from_request(&ctx).now_or_panic().is_err());
+
+        // explicit limit bypasses configuration.
+        *ctx.body_borrow_mut() = b"99610".as_slice().into();
+        let (bytes, _) = <(BytesMut, Limit<0>)>::from_request(&ctx).now_or_panic().unwrap();
+        assert_eq!(bytes.as_ref(), b"99610");
+
+        ctx.req_mut().extensions_mut().insert(PayloadConfig::limit(0));
+        *ctx.body_borrow_mut() = vec![0; DEFAULT_LIMIT + 1].into();
+        assert_eq!(
+            BytesMut::from_request(&ctx).now_or_panic().unwrap().len(),
+            DEFAULT_LIMIT + 1
+        );
+    }
+
+    #[test]
+    fn body_reader() {
+        use core::{
+            pin::Pin,
+            task::{Context, Poll},
+        };
+
+        use futures_core::Stream;
+
+        struct Chunks(Vec<&'static [u8]>);
+
+        impl Stream for Chunks {
+            type Item = Result<Bytes, Infallible>;
+
+            fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+                Poll::Ready((!self.0.is_empty()).then(|| Ok(Bytes::from_static(self.0.remove(0)))))
+            }
+        }
+
+        let mut reader = BodyReader::new(Chunks(vec![b"\x00\x03ab", b"c\x00", b"\x05line\n", b"rest"])).length(14);
+        assert_eq!(reader.remaining_hint(), (14, Some(14)));
+        assert_eq!(
+            reader.read_exact(2).now_or_panic().unwrap().unwrap().as_ref(),
+            b"\x00\x03"
+        );
+        assert_eq!(reader.read_exact(3).now_or_panic().unwrap().unwrap().as_ref(), b"abc");
+        assert_eq!(reader.remaining_hint(), (9, Some(9)));
+        assert_eq!(
+            reader.read_exact(2).now_or_panic().unwrap().unwrap().as_ref(),
+            b"\x00\x05"
+        );
+        assert_eq!(
+            reader.read_until(b'\n').now_or_panic().unwrap().unwrap().as_ref(),
+            b"line\n"
+        );
+        assert_eq!(
+            reader.read_until(b'\n').now_or_panic().unwrap().unwrap().as_ref(),
+            b"rest"
+        );
+        assert!(reader.read_until(b'\n').now_or_panic().unwrap().is_none());
+        assert!(reader.read_exact(1).now_or_panic().unwrap().is_none());
+        assert_eq!(reader.remaining_hint(), (0, Some(0)));
+
+        let mut reader = BodyReader::new(Chunks(vec![b"ab"]));
+        let err = reader.read_exact(3).now_or_panic().unwrap_err();
+        assert!(err.upcast().downcast_ref::<crate::error::BodyIncomplete>().is_some());
+
+        let mut reader = BodyReader::new(Chunks(vec![b"ab", b"cd"])).limit(3);
+        assert_eq!(reader.read_exact(1).now_or_panic().unwrap().unwrap().as_ref(), b"a");
+        let err = reader.read_exact(3).now_or_panic().unwrap_err();
+        assert!(err.upcast().downcast_ref::<BodyOverFlow>().is_some());
+
+        let mut ctx = WebContext::new_test(());
+        let mut ctx = ctx.as_web_ctx();
+        ctx.req_mut().extensions_mut().insert(PayloadConfig::limit(2));
+        *ctx.body_borrow_mut() = b"996".as_slice().into();
+        let mut reader = BodyReader::from_request(&ctx).now_or_panic().unwrap();
+        assert!(reader.read_exact(3).now_or_panic().is_err());
+    }
+}
@@ -0,0 +1,103 @@
+//! type extractor for observing cancellation of request handling.
+//!
+//! # Cancellation semantics
+//! when client closes its connection(http/1) or reset its stream(http/2) before response is produced, the
+//! handler future is dropped at its next await point and no response would be written. this applies to
+//! handlers awaiting on request body streaming too: the body stream yields io error when connection is broken
+//! mid-stream and handler future is dropped afterward.
+//!
+//! once response head is produced the handler future is resolved and response body streaming is subject
+//! to write error of connection instead.
+//!
+//! work not owned by handler future (spawned tasks, blocking threads etc) does not observe the drop. use
+//! [Cancellation] extractor and move [CancellationToken] into them for bailing early.
+//!
+//! note: http/1 connection driven by io-uring does not detect client disconnect before response is produced.
+
+use core::{
+    convert::Infallible,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use std::sync::Arc;
+
+use crate::{context::WebContext, handler::FromRequest};
+
+/// extractor for cancellation state of current request handling.
+///
+/// the extracted value is owned by handler future and [CancellationToken] produced from it would be marked as
+/// cancelled when handler future is dropped. either by client disconnect or by handler finishing its work.
+///
+/// # Example
+/// ```rust
+/// # use xitca_web::handler::cancel::Cancellation;
+/// async fn handler(cancel: Cancellation) -> String {
+///     let token = cancel.token();
+///     // blocking task is not aborted when handler future is dropped. observe the token to bail early.
+///     tokio::task::spawn_blocking(move || {
+///         let mut n = 0u64;
+///         while n < 1_000_000 && !token.is_cancelled() {
+///             n += 1;
+///         }
+///         n.to_string()
+///     })
+///     .await
+///     .unwrap()
+/// }
+/// ```
+#[derive(Default)]
+pub struct Cancellation(CancellationToken);
+
+impl Cancellation {
+    /// construct a new cancellation token tied to the lifetime of self.
+    pub fn token(&self) -> CancellationToken {
+        self.0.clone()
+    }
+}
+
+impl Drop for Cancellation {
+    fn drop(&mut self) {
+        self.0 .0.store(true, Ordering::Release);
+    }
+}
+
+/// token for observing cancellation state of [Cancellation].
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// check if associated [Cancellation] is dropped.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for Cancellation {
+    type Type<'b> = Cancellation;
+    type Error = Infallible;
+
+    #[inline]
+    async fn from_request(_: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        Ok(Cancellation::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use super::*;
+
+    #[test]
+    fn cancel_on_drop() {
+        let mut ctx = WebContext::new_test(());
+        let ctx = ctx.as_web_ctx();
+
+        let cancel = Cancellation::from_request(&ctx).now_or_panic().unwrap();
+        let token = cancel.token();
+        assert!(!token.is_cancelled());
+        drop(cancel);
+        assert!(token.is_cancelled());
+    }
+}
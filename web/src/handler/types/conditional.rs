@@ -0,0 +1,246 @@
+//! type responder for conditional http response.
+
+use core::str::FromStr;
+
+use std::time::SystemTime;
+
+use httpdate::HttpDate;
+
+use crate::{
+    body::ResponseBody,
+    context::WebContext,
+    error::Error,
+    handler::Responder,
+    http::{
+        header::{HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+        Method, StatusCode, WebResponse,
+    },
+};
+
+/// conditional response generator.
+///
+/// wrap a lazily produced response with validators(ETag and/or Last-Modified). when request's
+/// `If-None-Match` or `If-Modified-Since` header matches the validators the body producing closure
+/// would not be called and "304 Not Modified" response would be returned instead.
+///
+/// # Example
+/// ```rust
+/// # use xitca_web::handler::{conditional::Conditional, html::Html};
+/// async fn handler() -> Conditional<impl FnOnce() -> Html<String>> {
+///     Conditional::etag("\"996\"", || Html(String::from("expensive body")))
+/// }
+/// ```
+pub struct Conditional<F> {
+    etag: Option<Result<HeaderValue, StatusCode>>,
+    last_modified: Option<HttpDate>,
+    func: F,
+}
+
+impl<F> Conditional<F> {
+    /// construct a new conditional response with entity tag validator.
+    ///
+    /// the tag must be a valid entity-tag including it's double quotes. e.g: `"\"xyz\""` or `"W/\"xyz\""`.
+    pub fn etag(tag: impl TryInto<HeaderValue>, func: F) -> Self {
+        Self {
+            etag: Some(try_into_header(tag)),
+            last_modified: None,
+            func,
+        }
+    }
+
+    /// construct a new conditional response with last modified date validator.
+    pub fn last_modified(modified: SystemTime, func: F) -> Self {
+        Self {
+            etag: None,
+            last_modified: Some(HttpDate::from(modified)),
+            func,
+        }
+    }
+
+    /// add entity tag validator. see [Conditional::etag] for detail.
+    pub fn with_etag(mut self, tag: impl TryInto<HeaderValue>) -> Self {
+        self.etag = Some(try_into_header(tag));
+        self
+    }
+
+    /// add last modified date validator.
+    pub fn with_last_modified(mut self, modified: SystemTime) -> Self {
+        self.last_modified = Some(HttpDate::from(modified));
+        self
+    }
+
+    // evaluate request preconditions. return Some(status) when response body should be skipped.
+    fn evaluate(&self, method: &Method, headers: &HeaderMap) -> Option<StatusCode> {
+        let is_safe = matches!(*method, Method::GET | Method::HEAD);
+
+        if headers.contains_key(IF_NONE_MATCH) {
+            let Some(Ok(ref etag)) = self.etag else {
+                return None;
+            };
+            let status = if is_safe {
+                StatusCode::NOT_MODIFIED
+            } else {
+                StatusCode::PRECONDITION_FAILED
+            };
+            return if_none_match(headers, etag).then_some(status);
+        }
+
+        match (is_safe, self.last_modified, headers.get(IF_MODIFIED_SINCE)) {
+            (true, Some(modified), Some(since)) => {
+                let since = since.to_str().ok().and_then(|s| HttpDate::from_str(s).ok())?;
+                (since >= modified).then_some(StatusCode::NOT_MODIFIED)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'r, C, B, F, R> Responder<WebContext<'r, C, B>> for Conditional<F>
+where
+    F: FnOnce() -> R,
+    R: Responder<WebContext<'r, C, B>, Response = WebResponse>,
+    Error: From<R::Error>,
+{
+    type Response = WebResponse;
+    type Error = Error;
+
+    async fn respond(self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let status = self.evaluate(ctx.req().method(), ctx.req().headers());
+        let Self {
+            etag,
+            last_modified,
+            func,
+        } = self;
+        let mut res = match status {
+            Some(status) => {
+                let mut res = ctx.into_response(ResponseBody::empty());
+                *res.status_mut() = status;
+                res
+            }
+            None => func().respond(ctx).await?,
+        };
+        validator_headers(etag, last_modified, res.headers_mut())?;
+        Ok(res)
+    }
+
+    fn map(self, res: Self::Response) -> Result<Self::Response, Self::Error> {
+        let Self {
+            etag,
+            last_modified,
+            func,
+        } = self;
+        let mut res = func().map(res)?;
+        validator_headers(etag, last_modified, res.headers_mut())?;
+        Ok(res)
+    }
+}
+
+fn validator_headers(
+    etag: Option<Result<HeaderValue, StatusCode>>,
+    last_modified: Option<HttpDate>,
+    headers: &mut HeaderMap,
+) -> Result<(), Error> {
+    if let Some(etag) = etag {
+        headers.insert(ETAG, etag?);
+    }
+    if let Some(modified) = last_modified {
+        headers.insert(LAST_MODIFIED, try_into_header(modified.to_string())?);
+    }
+    Ok(())
+}
+
+fn try_into_header(value: impl TryInto<HeaderValue>) -> Result<HeaderValue, StatusCode> {
+    value.try_into().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+// weak comparison of entity tags according to RFC 7232 section 2.3.2.
+fn if_none_match(headers: &HeaderMap, etag: &HeaderValue) -> bool {
+    let etag = opaque_tag(etag.as_bytes());
+    headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .flat_map(|v| v.as_bytes().split(|b| *b == b','))
+        .map(<[u8]>::trim_ascii)
+        .any(|tag| tag == b"*" || opaque_tag(tag) == etag)
+}
+
+fn opaque_tag(tag: &[u8]) -> &[u8] {
+    tag.strip_prefix(b"W/").unwrap_or(tag)
+}
+
+#[cfg(test)]
+mod test {
+    use core::time::Duration;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::http::header::HeaderName;
+
+    use super::*;
+
+    fn respond<F, R>(cond: Conditional<F>, method: Method, headers: &[(HeaderName, &str)]) -> WebResponse
+    where
+        F: FnOnce() -> R,
+        R: for<'r> Responder<WebContext<'r, ()>, Response = WebResponse, Error = Error>,
+    {
+        let mut ctx = WebContext::new_test(());
+        let mut ctx = ctx.as_web_ctx();
+        *ctx.req_mut().method_mut() = method;
+        for (name, value) in headers {
+            ctx.req_mut()
+                .headers_mut()
+                .append(name.clone(), HeaderValue::from_str(value).unwrap());
+        }
+        let mut fut = cond.respond(ctx);
+        fut.now_or_panic().unwrap()
+    }
+
+    #[test]
+    fn etag() {
+        let headers = [(IF_NONE_MATCH, "\"123\", W/\"996\"")];
+
+        let cond = Conditional::etag("\"996\"", || -> &'static str { panic!("body must not be produced") });
+        let res = respond(cond, Method::GET, &headers);
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers().get(ETAG).unwrap(), "\"996\"");
+
+        let res = respond(Conditional::etag("\"251\"", || "hello,world!"), Method::GET, &headers);
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get(ETAG).unwrap(), "\"251\"");
+
+        let res = respond(Conditional::etag("\"996\"", || "hello,world!"), Method::POST, &headers);
+        assert_eq!(res.status(), StatusCode::PRECONDITION_FAILED);
+
+        let res = respond(
+            Conditional::etag("\"251\"", || "hello,world!"),
+            Method::GET,
+            &[(IF_NONE_MATCH, "*")],
+        );
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+    }
+
+    #[test]
+    fn last_modified() {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let since = HttpDate::from(modified + Duration::from_secs(1)).to_string();
+        let headers = [(IF_MODIFIED_SINCE, since.as_str())];
+
+        let res = respond(
+            Conditional::last_modified(modified, || "hello,world!"),
+            Method::GET,
+            &headers,
+        );
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert!(res.headers().contains_key(LAST_MODIFIED));
+
+        let cond = Conditional::last_modified(modified + Duration::from_secs(2), || "hello,world!");
+        let res = respond(cond, Method::GET, &headers);
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // If-None-Match takes precedence over If-Modified-Since.
+        let headers = [(IF_MODIFIED_SINCE, since.as_str()), (IF_NONE_MATCH, "\"123\"")];
+        let cond = Conditional::last_modified(modified, || "hello,world!").with_etag("\"996\"");
+        let res = respond(cond, Method::GET, &headers);
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
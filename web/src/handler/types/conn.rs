@@ -0,0 +1,70 @@
+//! type extractor for connection information.
+
+use crate::{context::WebContext, error::Error, handler::FromRequest};
+
+pub use xitca_http::http::ConnInfo;
+
+/// Extract information of connection request is received from. Including negotiated http version,
+/// local and remote socket address, count of requests received from connection and if connection is
+/// keep-alive.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::{handler_service, conn::ConnInfo}, App};
+/// async fn handler(info: &ConnInfo) -> String {
+///     format!(
+///         "{:?} connection from {} served {} requests",
+///         info.version(),
+///         info.remote_addr(),
+///         info.request_count()
+///     )
+/// }
+///
+/// App::new()
+///     .at("/", handler_service(handler))
+///     # .at("/infer", handler_service(|_: &xitca_web::WebContext<'_>| async { "" }))
+///     .finish();
+/// ```
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for &'a ConnInfo {
+    type Type<'b> = &'b ConnInfo;
+    type Error = Error;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        Ok(ctx.req().body().conn_info())
+    }
+}
+
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for ConnInfo {
+    type Type<'b> = ConnInfo;
+    type Error = Error;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        Ok(*ctx.req().body().conn_info())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::http::{Request, RequestExt, Version};
+
+    use super::*;
+
+    #[test]
+    fn extract() {
+        let mut req = WebContext::new_test(&());
+        let mut req = req.as_web_ctx();
+
+        let mut ext = RequestExt::default();
+        *ext.conn_info_mut() = ext.conn_info().with_version(Version::HTTP_2).with_request_count(3);
+        *req.req_mut() = Request::new(ext);
+
+        let info = ConnInfo::from_request(&req).now_or_panic().unwrap();
+        assert_eq!(info.version(), Version::HTTP_2);
+        assert_eq!(info.request_count(), 3);
+        assert!(info.local_addr().is_none());
+    }
+}
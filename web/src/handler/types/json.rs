@@ -21,7 +21,7 @@ use crate::{
 };
 
 use super::{
-    body::Limit,
+    body::collect,
     header::{self, HeaderRef},
 };
 
@@ -30,7 +30,8 @@ pub const DEFAULT_LIMIT: usize = 1024 * 1024;
 /// Extract type for Json object. const generic param LIMIT is for max size of the object in bytes.
 /// Object larger than limit would be treated as error.
 ///
-/// Default limit is [DEFAULT_LIMIT] in bytes.
+/// Default limit is [DEFAULT_LIMIT] in bytes. When LIMIT is [DEFAULT_LIMIT] the limit can be
+/// configured by [JsonConfig].
 #[derive(Clone)]
 pub struct Json<T, const LIMIT: usize = DEFAULT_LIMIT>(pub T);
 
@@ -69,8 +70,7 @@ where
     type Error = Error;
 
     async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
-        HeaderRef::<'a, { header::CONTENT_TYPE }>::from_request(ctx).await?;
-        let (bytes, _) = <(BytesMut, Limit<LIMIT>)>::from_request(ctx).await?;
+        let bytes = from_ctx::<_, _, _, LIMIT>(ctx, BytesMut::new(), BytesMut::extend_from_slice).await?;
         serde_json::from_slice(&bytes).map(Json).map_err(Into::into)
     }
 }
@@ -107,6 +107,10 @@ where
 ///     .at("/post", handler_service(handler))
 ///     # .at("/", handler_service(|_: &WebContext<'_>| async { "used for infer type" }));
 /// ```
+///
+/// Limits of [JsonConfig] are checked when LazyJson is extracted. The check scans the collected bytes
+/// in place without deserializing or copying them, so zero copy deserialization in handler function
+/// is not affected and payload violating the limits is rejected before handler function is called.
 pub struct LazyJson<T, const LIMIT: usize = DEFAULT_LIMIT> {
     bytes: Vec<u8>,
     _json: PhantomData<T>,
@@ -130,8 +134,7 @@ where
     type Error = Error;
 
     async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
-        HeaderRef::<'a, { header::CONTENT_TYPE }>::from_request(ctx).await?;
-        let (bytes, _) = <(Vec<u8>, Limit<LIMIT>)>::from_request(ctx).await?;
+        let bytes = from_ctx::<_, _, _, LIMIT>(ctx, Vec::new(), Vec::extend_from_slice).await?;
         Ok(LazyJson {
             bytes,
             _json: PhantomData,
@@ -139,6 +142,180 @@ where
     }
 }
 
+/// configuration of limits for [Json] and [LazyJson] type extractors.
+///
+/// Extractors look up the configuration from request's [Extensions]. When it's absent the body size is
+/// limited by const generic LIMIT param of extractor and nesting depth is limited to 128 by
+/// `serde_json`'s recursion limit.
+///
+/// - body size limit is applied when extractor's LIMIT param is [DEFAULT_LIMIT]. Other LIMIT value
+///   takes priority over configuration. Exceeding it produces `413 Payload Too Large` response.
+/// - nesting depth and array/object length limits are checked by scanning the whole body before
+///   deserializing. Exceeding them produces [JsonLimitError] which would be converted to
+///   `400 Bad Request` response.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::{handler_service, json::{Json, JsonConfig}},
+/// #   middleware::Extension,
+/// #   route::post,
+/// #   App, WebContext,
+/// # };
+/// App::new()
+///     .at("/", post(handler_service(|Json(v): Json<Vec<Vec<u8>>>| async move { v.len().to_string() })))
+///     # .at("/infer", handler_service(|_: &WebContext<'_>| async { "" }))
+///     // limit body to 64KB, nesting depth to 2 and arrays to 16 elements.
+///     .enclosed(Extension::new(JsonConfig::new().limit(64 * 1024).max_depth(2).max_length(16)))
+///     .finish();
+/// ```
+///
+/// [Extensions]: crate::http::Extensions
+#[derive(Clone, Copy, Debug)]
+pub struct JsonConfig {
+    limit: usize,
+    max_depth: usize,
+    max_length: usize,
+}
+
+impl Default for JsonConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonConfig {
+    /// construct configuration with body size limit of [DEFAULT_LIMIT], nesting depth limit of 128 and
+    /// unlimited array/object length.
+    pub const fn new() -> Self {
+        Self {
+            limit: DEFAULT_LIMIT,
+            max_depth: 128,
+            max_length: 0,
+        }
+    }
+
+    /// set body size limit in bytes. 0 means unlimited.
+    pub const fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// set max nesting depth of arrays and objects. top level array or object has depth of 1.
+    pub const fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// set max number of elements of array and max number of members of object. 0 means unlimited.
+    pub const fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    // scan json text for nesting depth and container length without parsing. malformed json is left
+    // to deserializer for reporting error.
+    fn check(&self, bytes: &[u8]) -> Result<(), JsonLimitError> {
+        // separator count of each open container.
+        let mut stack = Vec::new();
+        let mut in_str = false;
+        let mut escape = false;
+
+        for b in bytes {
+            if in_str {
+                match (escape, b) {
+                    (true, _) => escape = false,
+                    (false, b'\\') => escape = true,
+                    (false, b'"') => in_str = false,
+                    _ => {}
+                }
+                continue;
+            }
+
+            match b {
+                b'"' => in_str = true,
+                b'[' | b'{' => {
+                    if stack.len() == self.max_depth {
+                        return Err(JsonLimitError::Depth(self.max_depth));
+                    }
+                    stack.push(0usize);
+                }
+                b']' | b'}' => {
+                    stack.pop();
+                }
+                b',' => {
+                    if let Some(separators) = stack.last_mut() {
+                        *separators += 1;
+                        // n separators means at least n + 1 elements.
+                        if self.max_length > 0 && *separators >= self.max_length {
+                            return Err(JsonLimitError::Length(self.max_length));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// error type for json payload exceeding limits of [JsonConfig].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum JsonLimitError {
+    /// nesting depth of arrays and objects exceeds given limit.
+    Depth(usize),
+    /// number of elements of array or members of object exceeds given limit.
+    Length(usize),
+}
+
+impl fmt::Display for JsonLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Depth(limit) => write!(f, "json nesting depth exceeds limit: {limit}"),
+            Self::Length(limit) => write!(f, "json array or object length exceeds limit: {limit}"),
+        }
+    }
+}
+
+impl std::error::Error for JsonLimitError {}
+
+error_from_service!(JsonLimitError);
+forward_blank_bad_request!(JsonLimitError);
+
+// collect request body with limits of JsonConfig applied.
+async fn from_ctx<C, B, T, const LIMIT: usize>(
+    ctx: &WebContext<'_, C, B>,
+    mut buf: T,
+    extend: fn(&mut T, &[u8]),
+) -> Result<T, Error>
+where
+    B: BodyStream + Default,
+    T: AsRef<[u8]>,
+{
+    HeaderRef::<'_, { header::CONTENT_TYPE }>::from_request(ctx).await?;
+
+    let config = ctx.req().extensions().get::<JsonConfig>().copied();
+
+    let limit = match config {
+        Some(config) if LIMIT == DEFAULT_LIMIT => config.limit,
+        _ => LIMIT,
+    };
+
+    collect(ctx, limit, |chunk| {
+        extend(&mut buf, chunk);
+        buf.as_ref().len()
+    })
+    .await?;
+
+    if let Some(config) = config {
+        config.check(buf.as_ref())?;
+    }
+
+    Ok(buf)
+}
+
 impl<'r, C, B, T> Responder<WebContext<'r, C, B>> for Json<T>
 where
     T: Serialize,
@@ -230,7 +407,7 @@ mod test {
 
     use crate::{
         handler::handler_service,
-        http::{header::CONTENT_LENGTH, WebRequest},
+        http::{header::CONTENT_LENGTH, StatusCode, WebRequest},
         test::collect_string_body,
         App,
     };
@@ -271,6 +448,40 @@ mod test {
         assert_eq!(res, "bankruptcy");
     }
 
+    #[test]
+    fn config() {
+        let config = JsonConfig::new().max_depth(2).max_length(3);
+        assert!(config.check(br#"[[1, 2, 3], {"a": 1, "b": "[[[,,,"}]"#).is_ok());
+        assert!(matches!(config.check(b"[[[1]]]"), Err(JsonLimitError::Depth(2))));
+        assert!(matches!(config.check(b"[1, 2, 3, 4]"), Err(JsonLimitError::Length(3))));
+        assert!(matches!(
+            config.check(br#"{"a": 1, "b": 2, "c": 3, "d": 4}"#),
+            Err(JsonLimitError::Length(3))
+        ));
+        // escaped quote does not end string.
+        assert!(config.check(br#"["\"[[[", 1]"#).is_ok());
+
+        let mut ctx = WebContext::new_test(());
+        let mut ctx = ctx.as_web_ctx();
+
+        let extract = |ctx: &mut WebContext<'_>, body: &'static str| {
+            ctx.req_mut().headers_mut().insert(CONTENT_TYPE, JSON);
+            *ctx.body_borrow_mut() = body.as_bytes().into();
+            Json::<Vec<u8>>::from_request(ctx).now_or_panic()
+        };
+
+        ctx.req_mut().extensions_mut().insert(config);
+        assert_eq!(extract(&mut ctx, "[1, 2, 3]").unwrap().0, [1, 2, 3]);
+        let err = extract(&mut ctx, "[1, 2, 3, 4]").err().unwrap();
+        let res = err.call(ctx.reborrow()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        ctx.req_mut().extensions_mut().insert(JsonConfig::new().limit(4));
+        let err = extract(&mut ctx, "[1, 2, 3]").err().unwrap();
+        let res = err.call(ctx.reborrow()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
     #[test]
     fn service() {
         let res = App::new()
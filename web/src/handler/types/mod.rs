@@ -1,13 +1,21 @@
 pub mod body;
+pub mod cancel;
+pub mod conditional;
+pub mod conn;
 pub mod extension;
 pub mod header;
 pub mod html;
+pub mod negotiate;
 pub mod path;
 pub mod redirect;
 pub mod state;
 pub mod text;
+pub mod tls;
 pub mod uri;
 
+#[cfg(feature = "__server")]
+pub mod upgrade;
+
 #[cfg(feature = "params")]
 pub mod params;
 
@@ -23,6 +31,9 @@ pub mod json;
 #[cfg(feature = "cookie")]
 pub mod cookie;
 
+#[cfg(feature = "jwt")]
+pub mod auth;
+
 #[cfg(feature = "multipart")]
 pub mod multipart;
 
@@ -1,3 +1,5 @@
+//! type extractor for multipart form data.
+
 use crate::{
     body::{BodyStream, RequestBody},
     context::WebContext,
@@ -5,8 +7,13 @@ use crate::{
     handler::FromRequest,
 };
 
+#[cfg(feature = "file-raw")]
+pub use self::save::{FieldExt, SaveLimit};
+
 pub type Multipart<B = RequestBody> = http_multipart::Multipart<B>;
 
+pub type Field<'a, B = RequestBody> = http_multipart::Field<'a, B>;
+
 impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for Multipart<B>
 where
     B: BodyStream + Default,
@@ -22,6 +29,175 @@ where
 
 forward_blank_bad_request!(http_multipart::MultipartError);
 
+#[cfg(feature = "file-raw")]
+mod save {
+    use core::future::Future;
+
+    use std::{borrow::Cow, error, path::Path};
+
+    use futures_core::stream::Stream;
+    use http_file::runtime::{AsyncFsWrite, ChunkWrite};
+
+    use crate::{
+        error::{BodyOverFlow, Error},
+        http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
+    };
+
+    use super::Field;
+
+    /// limits applied to [FieldExt::save_to].
+    ///
+    /// default to no size limit and allow all content types.
+    #[derive(Clone, Debug)]
+    pub struct SaveLimit {
+        size: usize,
+        content_types: Vec<Cow<'static, str>>,
+    }
+
+    impl Default for SaveLimit {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl SaveLimit {
+        pub const fn new() -> Self {
+            Self {
+                size: usize::MAX,
+                content_types: Vec::new(),
+            }
+        }
+
+        /// set max size of field in bytes. field exceeding the limit would produce [BodyOverFlow] error.
+        pub fn max_size(mut self, size: usize) -> Self {
+            self.size = size;
+            self
+        }
+
+        /// add an allowed content type. a field with content type not in the allowed set would produce
+        /// "415 Unsupported Media Type" error.
+        ///
+        /// content type is compared by its essence with parameters ignored. e.g: `image/png`.
+        /// sub type wildcard is supported. e.g: `image/*`.
+        pub fn allow_content_type(mut self, content_type: impl Into<Cow<'static, str>>) -> Self {
+            self.content_types.push(content_type.into());
+            self
+        }
+
+        fn check_content_type(&self, headers: &HeaderMap) -> Result<(), Error> {
+            if self.content_types.is_empty() {
+                return Ok(());
+            }
+
+            // RFC 7578 section 4.4: field without Content-Type header defaults to text/plain.
+            let essence = match headers.get(CONTENT_TYPE) {
+                Some(value) => value.to_str().map_err(|_| StatusCode::UNSUPPORTED_MEDIA_TYPE)?,
+                None => "text/plain",
+            };
+            let essence = essence.split(';').next().unwrap_or_default().trim();
+
+            self.content_types
+                .iter()
+                .any(|allowed| match allowed.strip_suffix("/*") {
+                    Some(typ) => essence
+                        .split_once('/')
+                        .is_some_and(|(t, _)| t.eq_ignore_ascii_case(typ)),
+                    None => essence.eq_ignore_ascii_case(allowed),
+                })
+                .then_some(())
+                .ok_or_else(|| StatusCode::UNSUPPORTED_MEDIA_TYPE.into())
+        }
+    }
+
+    /// extension trait for [Field] type.
+    pub trait FieldExt {
+        /// stream field to file at given path through file system `fs` with [SaveLimit] enforced. return number of
+        /// bytes written.
+        ///
+        /// file is created or truncated when it already exists. partial written file would be removed on error.
+        ///
+        /// # Security
+        /// [Field::file_name] is provided by client and must not be used as file path directly. A crafted name like
+        /// `../../etc/passwd` can escape the upload directory.
+        ///
+        /// # Example
+        /// ```rust
+        /// # use core::pin::pin;
+        /// # use http_file::runtime::AsyncFsWrite;
+        /// # use xitca_web::{error::Error, handler::multipart::{FieldExt, Multipart, SaveLimit}};
+        /// async fn upload(fs: &impl AsyncFsWrite, multipart: Multipart) -> Result<String, Error> {
+        ///     let mut multipart = pin!(multipart);
+        ///     let limit = SaveLimit::new().max_size(1024 * 1024).allow_content_type("image/*");
+        ///     let mut written = 0;
+        ///     let mut idx = 0;
+        ///     while let Some(mut field) = multipart.try_next().await.map_err(Error::from_service)? {
+        ///         // use server generated file name instead of client provided one.
+        ///         let path = std::env::temp_dir().join(format!("upload-{idx}"));
+        ///         idx += 1;
+        ///         written += field.save_to(fs, path, &limit).await?;
+        ///     }
+        ///     Ok(format!("{written} bytes uploaded"))
+        /// }
+        /// ```
+        fn save_to<F>(
+            &mut self,
+            fs: &F,
+            path: impl AsRef<Path>,
+            limit: &SaveLimit,
+        ) -> impl Future<Output = Result<u64, Error>>
+        where
+            F: AsyncFsWrite;
+    }
+
+    impl<B, T, E> FieldExt for Field<'_, B>
+    where
+        B: Stream<Item = Result<T, E>>,
+        T: AsRef<[u8]> + 'static,
+        E: Into<Box<dyn error::Error + Send + Sync>>,
+    {
+        async fn save_to<F>(&mut self, fs: &F, path: impl AsRef<Path>, limit: &SaveLimit) -> Result<u64, Error>
+        where
+            F: AsyncFsWrite,
+        {
+            limit.check_content_type(self.headers())?;
+
+            let path = path.as_ref();
+            let file = fs.create(path.to_path_buf()).await?;
+
+            let res = write_field(self, file, limit.size).await;
+
+            if res.is_err() {
+                let _ = fs.remove(path.to_path_buf()).await;
+            }
+
+            res
+        }
+    }
+
+    async fn write_field<B, T, E, W>(field: &mut Field<'_, B>, mut file: W, limit: usize) -> Result<u64, Error>
+    where
+        B: Stream<Item = Result<T, E>>,
+        T: AsRef<[u8]> + 'static,
+        E: Into<Box<dyn error::Error + Send + Sync>>,
+        W: ChunkWrite,
+    {
+        let mut written = 0;
+
+        // file is dropped on early return and it's closed by file system without flushing.
+        while let Some(bytes) = field.try_next().await.map_err(Error::from_service)? {
+            written += bytes.len();
+            if written > limit {
+                return Err(Error::from_service(BodyOverFlow { limit }));
+            }
+            file = file.write(bytes).await?;
+        }
+
+        file.close().await?;
+
+        Ok(written as u64)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use core::pin::pin;
@@ -109,4 +285,68 @@ mod test {
 
         assert_eq!(body, b"testtestdata");
     }
+
+    #[cfg(feature = "file")]
+    async fn save_handler(multipart: Multipart) -> Result<String, Error> {
+        let mut multipart = pin!(multipart);
+        let dir = std::env::temp_dir();
+        let fs = &http_file::runtime::TokioFs;
+
+        let mut field = multipart.try_next().await.map_err(Error::from_service)?.unwrap();
+        let path = dir.join("xitca_web_multipart_foo.txt");
+        let n = field.save_to(fs, &path, &SaveLimit::new().max_size(4)).await?;
+        assert_eq!(std::fs::read(&path).unwrap(), b"test");
+        std::fs::remove_file(&path).unwrap();
+        drop(field);
+
+        let mut field = multipart.try_next().await.map_err(Error::from_service)?.unwrap();
+        let path = dir.join("xitca_web_multipart_bar.txt");
+        let limit = SaveLimit::new().allow_content_type("image/*");
+        assert!(field.save_to(fs, &path, &limit).await.is_err());
+        assert!(!path.exists());
+
+        let limit = SaveLimit::new().max_size(4).allow_content_type("text/plain");
+        assert!(field.save_to(fs, &path, &limit).await.is_err());
+        assert!(!path.exists());
+
+        Ok(n.to_string())
+    }
+
+    #[cfg(feature = "file")]
+    #[tokio::test]
+    async fn save_to() {
+        let body: &'static [u8] = b"\
+            --abbc761f78ff4d7cb7573b5a23f96ef0\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"foo.txt\"\r\n\
+            Content-Type: text/plain; charset=utf-8\r\nContent-Length: 4\r\n\r\n\
+            test\r\n\
+            --abbc761f78ff4d7cb7573b5a23f96ef0\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"bar.txt\"\r\n\
+            Content-Type: text/plain\r\nContent-Length: 8\r\n\r\n\
+            testdata\r\n\
+            --abbc761f78ff4d7cb7573b5a23f96ef0--\r\n";
+
+        let req = request::Builder::default()
+            .method(Method::POST)
+            .header(
+                CONTENT_TYPE,
+                HeaderValue::from_static("multipart/mixed; boundary=abbc761f78ff4d7cb7573b5a23f96ef0"),
+            )
+            .body(RequestExt::default().map_body(|_: ()| body.into()))
+            .unwrap();
+
+        let res = App::new()
+            .at("/", post(handler_service(save_handler)))
+            .finish()
+            .call(())
+            .await
+            .unwrap()
+            .call(req)
+            .await
+            .unwrap();
+
+        let body = collect_body(res.into_body()).await.unwrap();
+
+        assert_eq!(body, b"4");
+    }
 }
@@ -0,0 +1,336 @@
+//! response generator with content negotiation based on `Accept` request header.
+
+use crate::{
+    body::ResponseBody,
+    context::WebContext,
+    error::Error,
+    handler::Responder,
+    http::{
+        const_header_value::{TEXT_HTML_UTF8, TEXT_UTF8},
+        header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_TYPE, VARY},
+        StatusCode, WebResponse,
+    },
+};
+
+type Render = Box<dyn FnOnce() -> Result<ResponseBody, Error>>;
+
+struct Variant {
+    content_type: HeaderValue,
+    render: Render,
+}
+
+/// response generator selecting one of registered variants based on `Accept` request header.
+///
+/// Variants are rendered lazily and only the selected one is rendered. Selection follows the
+/// quality values(`q` parameter) of `Accept` header where the most specific media range matching a
+/// variant decides it's quality. When multiple variants share the same quality the one registered
+/// first wins.
+///
+/// When request does not carry `Accept` header the fallback variant is selected and when no fallback
+/// is set the first registered variant is selected. When no variant is acceptable the fallback
+/// variant is selected and when no fallback is set `406 Not Acceptable` error is produced.
+///
+/// Selected response carries `Vary: Accept` header.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::{handler_service, negotiate::Negotiate}, route::get, App};
+/// async fn handler() -> Negotiate {
+///     let name = String::from("xitca");
+///     let render = format!("<h1>{name}</h1>");
+///     Negotiate::new()
+///         // text variant is selected when request does not accept any of registered variants.
+///         // with json feature enabled Negotiate::json can be used to register serializable type.
+///         .text(move || name)
+///         .fallback()
+///         .html(move || render)
+/// }
+///
+/// App::new().at("/", get(handler_service(handler))).finish();
+/// ```
+#[derive(Default)]
+pub struct Negotiate {
+    variants: Vec<Variant>,
+    fallback: Option<usize>,
+}
+
+impl Negotiate {
+    /// construct a new negotiate response generator with no variant registered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// register a variant with given `Content-Type` header value and render function.
+    pub fn variant<F, T>(mut self, content_type: HeaderValue, render: F) -> Self
+    where
+        F: FnOnce() -> T + 'static,
+        T: Into<ResponseBody>,
+    {
+        self.variants.push(Variant {
+            content_type,
+            render: Box::new(|| Ok(render().into())),
+        });
+        self
+    }
+
+    /// register a `text/html; charset=utf-8` variant.
+    pub fn html<F, T>(self, render: F) -> Self
+    where
+        F: FnOnce() -> T + 'static,
+        T: Into<ResponseBody>,
+    {
+        self.variant(TEXT_HTML_UTF8, render)
+    }
+
+    /// register a `text/plain; charset=utf-8` variant.
+    pub fn text<F, T>(self, render: F) -> Self
+    where
+        F: FnOnce() -> T + 'static,
+        T: Into<ResponseBody>,
+    {
+        self.variant(TEXT_UTF8, render)
+    }
+
+    /// register an `application/json` variant serialized from given value.
+    #[cfg(feature = "json")]
+    pub fn json<T>(mut self, value: T) -> Self
+    where
+        T: serde::Serialize + 'static,
+    {
+        self.variants.push(Variant {
+            content_type: crate::http::const_header_value::JSON,
+            render: Box::new(move || {
+                let mut bytes = crate::bytes::BytesMut::new();
+                serde_json::to_writer(crate::bytes::BufMutWriter(&mut bytes), &value)?;
+                Ok(bytes.freeze().into())
+            }),
+        });
+        self
+    }
+
+    /// mark the last registered variant as fallback.
+    ///
+    /// # Panics
+    /// When no variant is registered.
+    pub fn fallback(mut self) -> Self {
+        assert!(
+            !self.variants.is_empty(),
+            "fallback must be called after variant registered"
+        );
+        self.fallback = Some(self.variants.len() - 1);
+        self
+    }
+
+    fn select(mut self, headers: &HeaderMap) -> Result<Variant, Error> {
+        let mut accepts = headers
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .peekable();
+
+        let idx = if accepts.peek().is_none() {
+            self.fallback.or((!self.variants.is_empty()).then_some(0))
+        } else {
+            let ranges = accepts
+                .flat_map(|v| v.split(','))
+                .filter_map(MediaRange::parse)
+                .collect::<Vec<_>>();
+            let mut selected = None::<(usize, u16)>;
+            for (idx, variant) in self.variants.iter().enumerate() {
+                let q = quality(&ranges, &variant.content_type);
+                if q > 0 && selected.is_none_or(|(_, sq)| q > sq) {
+                    selected = Some((idx, q));
+                }
+            }
+            selected.map(|(idx, _)| idx).or(self.fallback)
+        };
+
+        match idx {
+            Some(idx) => Ok(self.variants.swap_remove(idx)),
+            None => Err(Error::from(StatusCode::NOT_ACCEPTABLE)),
+        }
+    }
+}
+
+impl Variant {
+    fn render(self, res: WebResponse) -> Result<WebResponse, Error> {
+        let body = (self.render)()?;
+        let mut res = res.map(|_| body);
+        let headers = res.headers_mut();
+        headers.insert(CONTENT_TYPE, self.content_type);
+        headers.append(VARY, HeaderValue::from_static("accept"));
+        Ok(res)
+    }
+}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for Negotiate {
+    type Response = WebResponse;
+    type Error = Error;
+
+    async fn respond(self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let variant = self.select(ctx.req().headers())?;
+        variant.render(ctx.into_response(ResponseBody::none()))
+    }
+
+    fn map(self, res: Self::Response) -> Result<Self::Response, Self::Error> {
+        // no request header can be observed. select variant as if no Accept header is present.
+        self.select(&HeaderMap::new())?.render(res)
+    }
+}
+
+struct MediaRange<'a> {
+    ty: &'a str,
+    sub_ty: &'a str,
+    // quality value in range of 0..=1000.
+    q: u16,
+}
+
+impl<'a> MediaRange<'a> {
+    fn parse(range: &'a str) -> Option<Self> {
+        let mut params = range.split(';');
+        let (ty, sub_ty) = params.next()?.trim().split_once('/')?;
+
+        let mut q = 1000;
+        for param in params {
+            if let Some((name, value)) = param.trim().split_once('=') {
+                if name.trim().eq_ignore_ascii_case("q") {
+                    q = parse_q(value.trim())?;
+                }
+            }
+        }
+
+        Some(Self {
+            ty: ty.trim(),
+            sub_ty: sub_ty.trim(),
+            q,
+        })
+    }
+
+    // specificity of range matching given media type. None when not matching.
+    fn matches(&self, ty: &str, sub_ty: &str) -> Option<u8> {
+        match (self.ty, self.sub_ty) {
+            ("*", "*") => Some(0),
+            (t, "*") if t.eq_ignore_ascii_case(ty) => Some(1),
+            (t, s) if t.eq_ignore_ascii_case(ty) && s.eq_ignore_ascii_case(sub_ty) => Some(2),
+            _ => None,
+        }
+    }
+}
+
+// parse quality value with at most 3 digits after decimal point into range of 0..=1000.
+fn parse_q(value: &str) -> Option<u16> {
+    let (int, frac) = value.split_once('.').unwrap_or((value, ""));
+    if frac.len() > 3 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let frac = frac
+        .bytes()
+        .chain([b'0'; 3])
+        .take(3)
+        .fold(0, |n, b| n * 10 + u16::from(b - b'0'));
+    match int {
+        "0" => Some(frac),
+        "1" if frac == 0 => Some(1000),
+        _ => None,
+    }
+}
+
+fn quality(ranges: &[MediaRange<'_>], content_type: &HeaderValue) -> u16 {
+    let Some((ty, sub_ty)) = content_type
+        .to_str()
+        .ok()
+        .and_then(|v| v.split(';').next())
+        .and_then(|v| v.trim().split_once('/'))
+    else {
+        return 0;
+    };
+
+    ranges
+        .iter()
+        .filter_map(|range| range.matches(ty, sub_ty).map(|s| (s, range.q)))
+        .max_by_key(|(s, _)| *s)
+        .map_or(0, |(_, q)| q)
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::RequestBody,
+        handler::handler_service,
+        http::{Request, RequestExt, Uri},
+        service::Service,
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn q_value() {
+        assert_eq!(parse_q("1"), Some(1000));
+        assert_eq!(parse_q("1.000"), Some(1000));
+        assert_eq!(parse_q("0.5"), Some(500));
+        assert_eq!(parse_q("0.05"), Some(50));
+        assert_eq!(parse_q("0"), Some(0));
+        assert_eq!(parse_q("1.5"), None);
+        assert_eq!(parse_q("0.0001"), None);
+    }
+
+    #[test]
+    fn negotiate() {
+        async fn handler() -> Negotiate {
+            Negotiate::new().text(|| "text").html(|| "html")
+        }
+
+        async fn handler_fallback() -> Negotiate {
+            Negotiate::new().text(|| "text").html(|| "html").fallback()
+        }
+
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .at("/fallback", handler_service(handler_fallback))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call = |path: &'static str, accept: Option<&'static str>| {
+            let mut req = Request::new(RequestExt::<RequestBody>::default());
+            *req.uri_mut() = Uri::from_static(path);
+            if let Some(accept) = accept {
+                req.headers_mut().insert(ACCEPT, HeaderValue::from_static(accept));
+            }
+            service.call(req).now_or_panic().unwrap()
+        };
+
+        let body = |res: WebResponse<_>| collect_string_body(res.into_body()).now_or_panic().unwrap();
+
+        let res = call("/", None);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), TEXT_UTF8);
+        assert_eq!(res.headers().get(VARY).unwrap(), "accept");
+        assert_eq!(body(res), "text");
+
+        let res = call("/fallback", None);
+        assert_eq!(body(res), "html");
+
+        let res = call("/", Some("text/plain;q=0.5, text/html"));
+        assert_eq!(body(res), "html");
+
+        let res = call("/", Some("text/*;q=0.9, text/plain;q=0.8"));
+        assert_eq!(body(res), "html");
+
+        let res = call("/", Some("*/*"));
+        assert_eq!(body(res), "text");
+
+        let res = call("/", Some("application/json"));
+        assert_eq!(res.status(), StatusCode::NOT_ACCEPTABLE);
+
+        let res = call("/", Some("text/html;q=0, text/plain;q=0"));
+        assert_eq!(res.status(), StatusCode::NOT_ACCEPTABLE);
+
+        let res = call("/fallback", Some("application/json"));
+        assert_eq!(body(res), "html");
+    }
+}
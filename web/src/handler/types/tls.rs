@@ -0,0 +1,83 @@
+//! type extractor for tls connection information.
+
+use crate::{
+    context::WebContext,
+    error::{Error, ExtensionNotFound},
+    handler::FromRequest,
+};
+
+/// Extract information of tls connection request is received from. Including negotiated ALPN protocol,
+/// tls protocol version, cipher suite and SNI server name.
+///
+/// Extracting from request of plain text connection produces [ExtensionNotFound] error. Use
+/// `Option<&TlsInfo>` when application accepts both tls and plain text connections.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::{handler_service, tls::TlsInfo}, App};
+/// async fn handler(info: Option<&TlsInfo>) -> String {
+///     match info.and_then(|info| info.alpn_protocol.as_deref()) {
+///         Some(b"h2") => "tls with h2".to_owned(),
+///         Some(_) => "tls with other protocol".to_owned(),
+///         None => "plain text or no alpn".to_owned(),
+///     }
+/// }
+///
+/// App::new()
+///     .at("/", handler_service(handler))
+///     # .at("/infer", handler_service(|_: &xitca_web::WebContext<'_>| async { "" }))
+///     .finish();
+/// ```
+pub use xitca_http::http::TlsInfo;
+
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for &'a TlsInfo {
+    type Type<'b> = &'b TlsInfo;
+    type Error = Error;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        ctx.req()
+            .body()
+            .tls_info()
+            .ok_or_else(|| Error::from(ExtensionNotFound::from_type::<TlsInfo>()))
+    }
+}
+
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for TlsInfo {
+    type Type<'b> = TlsInfo;
+    type Error = Error;
+
+    #[inline]
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        <&'a TlsInfo>::from_request(ctx).await.cloned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::http::{Request, RequestExt};
+
+    use super::*;
+
+    #[test]
+    fn extract() {
+        let mut req = WebContext::new_test(&());
+        let mut req = req.as_web_ctx();
+
+        assert!(<&TlsInfo>::from_request(&req).now_or_panic().is_err());
+        assert!(Option::<&TlsInfo>::from_request(&req).now_or_panic().unwrap().is_none());
+
+        let mut info = TlsInfo::default();
+        info.alpn_protocol = Some(Box::from(&b"h2"[..]));
+        let mut ext = RequestExt::default();
+        *ext.tls_info_mut() = Some(Arc::new(info));
+        *req.req_mut() = Request::new(ext);
+
+        let info = TlsInfo::from_request(&req).now_or_panic().unwrap();
+        assert_eq!(info.alpn_protocol.as_deref(), Some(&b"h2"[..]));
+    }
+}
@@ -0,0 +1,224 @@
+//! type extractor and responder for taking over connection with upgraded protocol.
+
+use core::{convert::Infallible, future::Future, pin::Pin};
+
+use crate::{
+    body::{BodyStream, RequestBody, ResponseBody},
+    context::WebContext,
+    error::{Error, HeaderNotFound},
+    handler::{FromRequest, Responder},
+    http::{
+        header::{HeaderValue, CONNECTION, UPGRADE},
+        StatusCode, Version, WebResponse,
+    },
+};
+
+pub use xitca_http::util::tunnel::Tunnel;
+
+type OnUpgradeCB<B> = Box<dyn FnOnce(Tunnel<B>) -> Pin<Box<dyn Future<Output = ()>>>>;
+
+/// extractor and responder for protocol upgrade request.
+///
+/// For Http/1 request must carry `Connection: upgrade` and `Upgrade` headers. Responding with [Upgrade]
+/// produces `101 Switching Protocols` response and from this point on the connection carries raw
+/// bytes of upgraded protocol. For Http/2 request must be extended `CONNECT` request with `:protocol`
+/// pseudo-header and `200 OK` response switches the stream into tunnel mode.
+///
+/// After response is sent the connection is handed to async function registered with
+/// [Upgrade::on_upgrade] as a [Tunnel] which implements `AsyncRead` and `AsyncWrite` traits.
+///
+/// # Threading
+/// The function is spawned as a task on the current thread with [tokio::task::spawn_local] and it's
+/// future must be `'static`. [Tunnel] is bound to the worker thread accepted the connection and it is
+/// not `Send` for default request body type. Moving it to other thread is not possible. Dropping or
+/// shutting down the tunnel closes the upgraded connection.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::{handler_service, upgrade::Upgrade}, route::get, App};
+/// use tokio::io::{split, copy};
+///
+/// async fn handler(upgrade: Upgrade) -> Upgrade {
+///     // only accept echo protocol.
+///     assert_eq!(upgrade.protocol(), "echo");
+///     upgrade.on_upgrade(|tunnel| async move {
+///         let (mut read, mut write) = split(tunnel);
+///         let _ = copy(&mut read, &mut write).await;
+///     })
+/// }
+///
+/// App::new()
+///     .at("/", get(handler_service(handler)))
+///     .finish();
+/// ```
+pub struct Upgrade<B = RequestBody> {
+    protocol: String,
+    version: Version,
+    body: B,
+    on_upgrade: Option<OnUpgradeCB<B>>,
+}
+
+impl<B> Upgrade<B> {
+    /// protocol requested by client. For Http/1 it's the first protocol listed in `Upgrade` header and
+    /// for Http/2 it's the value of `:protocol` pseudo-header.
+    pub fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    /// async function that would be called with upgraded connection after response is sent.
+    ///
+    /// When no function is registered the upgraded connection is closed immediately.
+    pub fn on_upgrade<F, Fut>(mut self, func: F) -> Self
+    where
+        F: FnOnce(Tunnel<B>) -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        self.on_upgrade = Some(Box::new(|tunnel| Box::pin(func(tunnel))));
+        self
+    }
+}
+
+impl<'a, 'r, C, B> FromRequest<'a, WebContext<'r, C, B>> for Upgrade<B>
+where
+    B: BodyStream + Default,
+{
+    type Type<'b> = Upgrade<B>;
+    type Error = Error;
+
+    async fn from_request(ctx: &'a WebContext<'r, C, B>) -> Result<Self, Self::Error> {
+        let req = ctx.req();
+
+        let protocol = match req.version() {
+            #[cfg(feature = "http2")]
+            Version::HTTP_2 => {
+                if req.method() != crate::http::Method::CONNECT {
+                    return Err(Error::from(StatusCode::METHOD_NOT_ALLOWED));
+                }
+                xitca_http::h2::tunnel::protocol(req)
+                    .ok_or(Error::from(StatusCode::BAD_REQUEST))?
+                    .to_owned()
+            }
+            _ => {
+                let headers = req.headers();
+
+                headers
+                    .get_all(CONNECTION)
+                    .iter()
+                    .filter_map(|v| v.to_str().ok())
+                    .flat_map(|v| v.split(','))
+                    .find(|v| v.trim().eq_ignore_ascii_case("upgrade"))
+                    .ok_or(HeaderNotFound(CONNECTION))?;
+
+                headers
+                    .get(UPGRADE)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.split(',').next())
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .ok_or(HeaderNotFound(UPGRADE))?
+                    .to_owned()
+            }
+        };
+
+        Ok(Upgrade {
+            protocol,
+            version: req.version(),
+            body: ctx.take_body_ref(),
+            on_upgrade: None,
+        })
+    }
+}
+
+impl<'r, C, B> Responder<WebContext<'r, C, B>> for Upgrade<B>
+where
+    B: 'static,
+{
+    type Response = WebResponse;
+    type Error = Infallible;
+
+    async fn respond(self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let (tunnel, body) = xitca_http::util::tunnel::tunnel(self.body);
+
+        // tunnel is dropped when no function is registered and response body ends right away.
+        if let Some(func) = self.on_upgrade {
+            tokio::task::spawn_local(func(tunnel));
+        }
+
+        let mut res = ctx.into_response(ResponseBody::box_stream(body));
+
+        if self.version != Version::HTTP_2 {
+            *res.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+            res.headers_mut()
+                .insert(CONNECTION, HeaderValue::from_static("upgrade"));
+            // upgrade header signals http/1 dispatcher to pass through response body as is.
+            let protocol = HeaderValue::try_from(self.protocol).expect("protocol is obtained from valid header value");
+            res.headers_mut().insert(UPGRADE, protocol);
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use crate::{
+        bytes::Bytes,
+        handler::handler_service,
+        http::{request, RequestExt},
+        route::get,
+        service::Service,
+        test::collect_body,
+        App,
+    };
+
+    use super::*;
+
+    async fn handler(upgrade: Upgrade) -> Upgrade {
+        assert_eq!(upgrade.protocol(), "echo");
+        upgrade.on_upgrade(|mut tunnel| async move {
+            let mut buf = [0; 5];
+            tunnel.read_exact(&mut buf).await.unwrap();
+            tunnel.write_all(&buf).await.unwrap();
+            tunnel.write_all(b", world").await.unwrap();
+            tunnel.shutdown().await.unwrap();
+        })
+    }
+
+    #[tokio::test]
+    async fn upgrade() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let service = App::new()
+                    .at("/", get(handler_service(handler)))
+                    .finish()
+                    .call(())
+                    .await
+                    .unwrap();
+
+                let req = request::Builder::default()
+                    .header(UPGRADE, HeaderValue::from_static("echo, foo"))
+                    .header(CONNECTION, HeaderValue::from_static("keep-alive, Upgrade"))
+                    .body(RequestExt::default().map_body(|_: ()| Bytes::from_static(b"hello").into()))
+                    .unwrap();
+
+                let res = service.call(req).await.unwrap();
+                assert_eq!(res.status(), StatusCode::SWITCHING_PROTOCOLS);
+                assert_eq!(res.headers().get(UPGRADE).unwrap(), "echo");
+                assert_eq!(res.headers().get(CONNECTION).unwrap(), "upgrade");
+
+                let body = collect_body(res.into_body()).await.unwrap();
+                assert_eq!(body, b"hello, world");
+
+                let req = request::Builder::default()
+                    .header(UPGRADE, HeaderValue::from_static("echo"))
+                    .body(Default::default())
+                    .unwrap();
+
+                let res = service.call(req).await.unwrap();
+                assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+            })
+            .await;
+    }
+}
@@ -1,17 +1,19 @@
 use core::{
+    cell::Cell,
     cmp::Ordering,
     convert::Infallible,
     future::{poll_fn, Future},
     pin::{pin, Pin},
+    task::{Context, Poll},
     time::Duration,
 };
 
-use std::io;
+use std::{io, rc::Rc};
 
 use futures_core::stream::Stream;
 use http_ws::{
     stream::{RequestStream, WsError},
-    HandshakeError, Item, Message as WsMessage, ProtocolError, WsOutput,
+    CloseCode, HandshakeError, Item, Message as WsMessage, ProtocolError, Registration, WsOutput,
 };
 use tokio::time::{sleep, Instant};
 use xitca_unsafe_collection::{
@@ -32,7 +34,7 @@ use crate::{
     service::Service,
 };
 
-pub use http_ws::{ResponseSender, ResponseWeakSender};
+pub use http_ws::{Broadcaster, ResponseSender, ResponseWeakSender, SendPolicy};
 
 /// simplified websocket message type.
 /// for more variant of message please reference [http_ws::Message] type.
@@ -47,10 +49,38 @@ type BoxFuture<'a> = Pin<Box<dyn Future<Output = ()> + 'a>>;
 
 type OnMsgCB = Box<dyn for<'a> FnMut(&'a mut ResponseSender, Message) -> BoxFuture<'a>>;
 
+type OnTextCB = Box<dyn for<'a> FnMut(&'a mut ResponseSender, BytesStr) -> BoxFuture<'a>>;
+
+type OnBinaryCB = Box<dyn for<'a> FnMut(&'a mut ResponseSender, Bytes) -> BoxFuture<'a>>;
+
+// return None when text message can not be deserialized.
+type OnJsonCB = Box<dyn for<'a> FnMut(&'a mut ResponseSender, &str) -> Option<BoxFuture<'a>>>;
+
 type OnErrCB<E> = Box<dyn FnMut(WsError<E>) -> BoxFuture<'static>>;
 
 type OnCloseCB<B> = Box<dyn for<'a> FnOnce(Pin<&'a mut RequestStream<B>>) -> BoxFuture<'a>>;
 
+/// byte counters of a websocket connection. obtained from [WebSocket::stats].
+///
+/// counters are shared between clones and updated while the connection is alive.
+#[derive(Clone, Debug, Default)]
+pub struct WebSocketStats {
+    received: Rc<Cell<u64>>,
+    sent: Rc<Cell<u64>>,
+}
+
+impl WebSocketStats {
+    /// cumulative payload bytes of text, binary and continuation messages received from client.
+    pub fn bytes_received(&self) -> u64 {
+        self.received.get()
+    }
+
+    /// cumulative bytes of encoded websocket frames sent to client.
+    pub fn bytes_sent(&self) -> u64 {
+        self.sent.get()
+    }
+}
+
 pub struct WebSocket<B = RequestBody>
 where
     B: BodyStream,
@@ -58,7 +88,10 @@ where
     ws: WsOutput<B>,
     ping_interval: Duration,
     max_unanswered_ping: u8,
-    on_msg: OnMsgCB,
+    limit: Limit,
+    stats: WebSocketStats,
+    registrations: Vec<Registration>,
+    on_msg: OnMsg,
     on_err: OnErrCB<B::Error>,
     on_close: OnCloseCB<B>,
 }
@@ -78,7 +111,18 @@ where
             ws,
             ping_interval: Duration::from_secs(15),
             max_unanswered_ping: 3,
-            on_msg: Box::new(|_, _| boxed_future()),
+            limit: Limit {
+                message_size: usize::MAX,
+                total_bytes: u64::MAX,
+            },
+            stats: WebSocketStats::default(),
+            registrations: Vec::new(),
+            on_msg: OnMsg {
+                msg: Box::new(|_, _| boxed_future()),
+                text: None,
+                binary: None,
+                json: None,
+            },
             on_err: Box::new(|_| boxed_future()),
             on_close: Box::new(|_| boxed_future()),
         }
@@ -101,6 +145,77 @@ where
         self
     }
 
+    /// Set max payload size of a single message received from client. For continuation messages the
+    /// size is the sum of all fragments.
+    ///
+    /// When exceeded connection would be closed with [CloseCode::Policy] and [ProtocolError::Overflow]
+    /// error would be passed to [WebSocket::on_err].
+    ///
+    /// Default to no limit.
+    pub fn set_max_message_size(&mut self, size: usize) -> &mut Self {
+        self.limit.message_size = size;
+        self
+    }
+
+    /// Set max cumulative payload bytes received from client in the lifetime of connection.
+    ///
+    /// When exceeded connection would be closed with [CloseCode::Policy] and [ProtocolError::Overflow]
+    /// error would be passed to [WebSocket::on_err].
+    ///
+    /// Default to no limit.
+    pub fn set_max_total_bytes(&mut self, size: u64) -> &mut Self {
+        self.limit.total_bytes = size;
+        self
+    }
+
+    /// Set policy of sending message when queue of outgoing messages is full. A slow client not
+    /// reading messages can make the queue full and by default sending would wait for free space.
+    ///
+    /// See [SendPolicy] for detail.
+    pub fn set_send_policy(&mut self, policy: SendPolicy) -> &mut Self {
+        self.ws.2.set_send_policy(policy);
+        self
+    }
+
+    /// Register connection to given [Broadcaster]. The connection is removed from it when connection
+    /// is closed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_web::{
+    /// #   handler::{handler_service, state::StateRef, websocket::{Broadcaster, Message, WebSocket}},
+    /// #   route::get,
+    /// #   App, WebContext,
+    /// # };
+    /// async fn handler(StateRef(broadcaster): StateRef<'_, Broadcaster>, mut ws: WebSocket) -> WebSocket {
+    ///     let broadcaster_2 = broadcaster.clone();
+    ///     ws.join(broadcaster).on_msg(move |_, msg| {
+    ///         let broadcaster = broadcaster_2.clone();
+    ///         Box::pin(async move {
+    ///             if let Message::Text(txt) = msg {
+    ///                 broadcaster.broadcast(http_ws::Message::Text(txt.into_inner())).await;
+    ///             }
+    ///         })
+    ///     });
+    ///     ws
+    /// }
+    ///
+    /// App::new()
+    ///     .with_state(Broadcaster::new())
+    ///     .at("/", get(handler_service(handler)))
+    ///     # .at("/nah", handler_service(|_: &WebContext<'_, Broadcaster>| async { "used for infer type" }))
+    ///     .finish();
+    /// ```
+    pub fn join(&mut self, broadcaster: &Broadcaster) -> &mut Self {
+        self.registrations.push(broadcaster.register(&self.ws.2));
+        self
+    }
+
+    /// Get byte counters of websocket connection.
+    pub fn stats(&self) -> WebSocketStats {
+        self.stats.clone()
+    }
+
     /// Get a reference of Websocket message sender.
     /// Can be used to send message to client.
     pub fn msg_sender(&self) -> &ResponseSender {
@@ -108,11 +223,72 @@ where
     }
 
     /// Async function that would be called when new message arrived from client.
+    ///
+    /// When typed handlers like [WebSocket::on_text] and [WebSocket::on_binary] are registered it
+    /// works as catch-all handler for messages not dispatched to them.
     pub fn on_msg<F>(&mut self, func: F) -> &mut Self
     where
         F: for<'a> FnMut(&'a mut ResponseSender, Message) -> BoxFuture<'a> + 'static,
     {
-        self.on_msg = Box::new(func);
+        self.on_msg.msg = Box::new(func);
+        self
+    }
+
+    /// Async function that would be called when new text message arrived from client.
+    ///
+    /// Continuation messages are not dispatched to it and would be passed to [WebSocket::on_msg].
+    pub fn on_text<F>(&mut self, func: F) -> &mut Self
+    where
+        F: for<'a> FnMut(&'a mut ResponseSender, BytesStr) -> BoxFuture<'a> + 'static,
+    {
+        self.on_msg.text = Some(Box::new(func));
+        self
+    }
+
+    /// Async function that would be called when new binary message arrived from client.
+    ///
+    /// Continuation messages are not dispatched to it and would be passed to [WebSocket::on_msg].
+    pub fn on_binary<F>(&mut self, func: F) -> &mut Self
+    where
+        F: for<'a> FnMut(&'a mut ResponseSender, Bytes) -> BoxFuture<'a> + 'static,
+    {
+        self.on_msg.binary = Some(Box::new(func));
+        self
+    }
+
+    /// Async function that would be called when new text message arrived from client and it can be
+    /// deserialized into type `T` as json.
+    ///
+    /// Text message failed to deserialize would be passed to [WebSocket::on_text] when registered
+    /// and [WebSocket::on_msg] otherwise.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use xitca_web::handler::websocket::{Message, WebSocket};
+    /// #[derive(serde::Deserialize)]
+    /// struct Ping {
+    ///     id: u32,
+    /// }
+    ///
+    /// async fn handler(mut ws: WebSocket) -> WebSocket {
+    ///     ws.on_json(|tx, ping: Ping| {
+    ///         Box::pin(async move {
+    ///             let _ = tx.text(format!("pong {}", ping.id)).await;
+    ///         })
+    ///     })
+    ///     .on_msg(|_, _: Message| Box::pin(async {}));
+    ///     ws
+    /// }
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn on_json<T, F>(&mut self, mut func: F) -> &mut Self
+    where
+        T: serde::de::DeserializeOwned,
+        F: for<'a> FnMut(&'a mut ResponseSender, T) -> BoxFuture<'a> + 'static,
+    {
+        self.on_msg.json = Some(Box::new(move |tx, txt| {
+            serde_json::from_str(txt).ok().map(|value| func(tx, value))
+        }));
         self
     }
 
@@ -182,6 +358,9 @@ where
             ws,
             ping_interval,
             max_unanswered_ping,
+            limit,
+            stats,
+            registrations,
             on_msg,
             on_err,
             on_close,
@@ -189,32 +368,100 @@ where
 
         let (decode, res, tx) = ws;
 
-        tokio::task::spawn_local(spawn_task(
-            ping_interval,
-            max_unanswered_ping,
-            decode,
-            tx,
-            on_msg,
-            on_err,
-            on_close,
-        ));
+        let sent = stats.sent.clone();
+
+        tokio::task::spawn_local(async move {
+            spawn_task(
+                ping_interval,
+                max_unanswered_ping,
+                limit,
+                stats.received,
+                decode,
+                tx,
+                on_msg,
+                on_err,
+                on_close,
+            )
+            .await;
+            drop(registrations);
+        });
+
+        Ok(res.map(|stream| ResponseBody::box_stream(CountStream { stream, sent })))
+    }
+}
+
+struct OnMsg {
+    msg: OnMsgCB,
+    text: Option<OnTextCB>,
+    binary: Option<OnBinaryCB>,
+    json: Option<OnJsonCB>,
+}
 
-        Ok(res.map(ResponseBody::box_stream))
+impl OnMsg {
+    async fn dispatch(&mut self, tx: &mut ResponseSender, msg: Message) {
+        match msg {
+            Message::Text(txt) => {
+                if let Some(ref mut json) = self.json {
+                    if let Some(fut) = json(tx, &txt) {
+                        return fut.await;
+                    }
+                }
+                match self.text {
+                    Some(ref mut text) => text(tx, txt).await,
+                    None => (self.msg)(tx, Message::Text(txt)).await,
+                }
+            }
+            Message::Binary(bin) => match self.binary {
+                Some(ref mut binary) => binary(tx, bin).await,
+                None => (self.msg)(tx, Message::Binary(bin)).await,
+            },
+            msg => (self.msg)(tx, msg).await,
+        }
     }
 }
 
+#[derive(Clone, Copy)]
+struct Limit {
+    message_size: usize,
+    total_bytes: u64,
+}
+
+// count bytes yielded by inner stream.
+struct CountStream<S> {
+    stream: S,
+    sent: Rc<Cell<u64>>,
+}
+
+impl<S> Stream for CountStream<S>
+where
+    S: Stream<Item = io::Result<Bytes>> + Unpin,
+{
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let res = Pin::new(&mut this.stream).poll_next(cx);
+        if let Poll::Ready(Some(Ok(ref bytes))) = res {
+            this.sent.set(this.sent.get() + bytes.len() as u64);
+        }
+        res
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn spawn_task<B>(
     ping_interval: Duration,
     max_unanswered_ping: u8,
+    limit: Limit,
+    received: Rc<Cell<u64>>,
     decode: RequestStream<B>,
     mut tx: ResponseSender,
-    mut on_msg: OnMsgCB,
+    mut on_msg: OnMsg,
     mut on_err: OnErrCB<B::Error>,
     on_close: OnCloseCB<B>,
 ) where
     B: BodyStream,
 {
-    let on_msg = &mut *on_msg;
     let on_err = &mut *on_err;
 
     let mut decode = pin!(decode);
@@ -224,6 +471,9 @@ async fn spawn_task<B>(
 
         let mut un_answered_ping = 0u8;
 
+        // payload size of current message. continuation fragments are summed.
+        let mut message_size = 0usize;
+
         loop {
             match poll_fn(|cx| decode.as_mut().poll_next(cx)).select(sleep.as_mut()).await {
                 SelectOutput::A(Some(Ok(msg))) => {
@@ -252,7 +502,31 @@ async fn spawn_task<B>(
                         }
                     };
 
-                    on_msg(&mut tx, msg).await
+                    let len = match msg {
+                        Message::Text(ref txt) => txt.len(),
+                        Message::Binary(ref bin) => bin.len(),
+                        Message::Continuation(Item::FirstText(ref b) | Item::FirstBinary(ref b)) => {
+                            message_size = 0;
+                            b.len()
+                        }
+                        Message::Continuation(Item::Continue(ref b) | Item::Last(ref b)) => b.len(),
+                    };
+
+                    message_size = if matches!(msg, Message::Continuation(_)) {
+                        message_size.saturating_add(len)
+                    } else {
+                        len
+                    };
+                    received.set(received.get() + len as u64);
+
+                    if message_size > limit.message_size || received.get() > limit.total_bytes {
+                        match tx.send(WsMessage::Close(Some(CloseCode::Policy.into()))).await {
+                            Ok(_) | Err(ProtocolError::Closed) => return Err(ProtocolError::Overflow.into()),
+                            Err(e) => return Err(e.into()),
+                        }
+                    }
+
+                    on_msg.dispatch(&mut tx, msg).await
                 }
                 SelectOutput::A(Some(Err(e))) => on_err(e).await,
                 SelectOutput::A(None) => return Ok(()),
@@ -294,3 +568,161 @@ async fn spawn_task<B>(
 
     on_close(decode).await;
 }
+
+#[cfg(test)]
+mod test {
+    use http_ws::{CloseReason, Codec};
+
+    use crate::{
+        handler::handler_service,
+        http::{
+            header::{HeaderValue, SEC_WEBSOCKET_KEY},
+            request, RequestExt,
+        },
+        route::get,
+        test::collect_body,
+        App,
+    };
+
+    use super::*;
+
+    fn ws_request(frames: Bytes) -> crate::http::Request<RequestExt<RequestBody>> {
+        request::Builder::default()
+            .header(UPGRADE, HeaderValue::from_static("websocket"))
+            .header(CONNECTION, HeaderValue::from_static("upgrade"))
+            .header(SEC_WEBSOCKET_VERSION, HeaderValue::from_static("13"))
+            .header(SEC_WEBSOCKET_KEY, HeaderValue::from_static("dGhlIHNhbXBsZSBub25jZQ=="))
+            .body(RequestExt::default().map_body(|_: ()| frames.into()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn max_message_size() {
+        async fn handler(mut ws: WebSocket) -> WebSocket {
+            ws.set_max_message_size(5);
+            ws
+        }
+
+        let mut frames = crate::bytes::BytesMut::new();
+        let mut codec = Codec::new().client_mode();
+        codec.encode(WsMessage::Text("hello".into()), &mut frames).unwrap();
+        codec
+            .encode(WsMessage::Text("hello,world!".into()), &mut frames)
+            .unwrap();
+        let frames = frames.freeze();
+
+        let req = ws_request(frames);
+
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let res = App::new()
+                    .at("/", get(handler_service(handler)))
+                    .finish()
+                    .call(())
+                    .await
+                    .unwrap()
+                    .call(req)
+                    .await
+                    .unwrap();
+
+                assert_eq!(res.status(), StatusCode::SWITCHING_PROTOCOLS);
+
+                let body = collect_body(res.into_body()).await.unwrap();
+                let mut body = crate::bytes::BytesMut::from(body.as_slice());
+                let msg = Codec::new().client_mode().decode(&mut body).unwrap().unwrap();
+                assert_eq!(msg, WsMessage::Close(Some(CloseReason::from(CloseCode::Policy))));
+            })
+            .await;
+    }
+
+    #[cfg(feature = "json")]
+    #[tokio::test]
+    async fn typed_handler() {
+        async fn handler(mut ws: WebSocket) -> WebSocket {
+            ws.on_json(|tx, num: u32| Box::pin(async move { tx.text(format!("json {num}")).await.unwrap() }))
+                .on_text(|tx, txt| Box::pin(async move { tx.text(format!("text {}", txt.as_ref())).await.unwrap() }))
+                .on_binary(|tx, bin| Box::pin(async move { tx.binary(bin).await.unwrap() }))
+                .on_msg(|tx, _| Box::pin(async move { tx.text("msg").await.unwrap() }));
+            ws
+        }
+
+        let mut frames = crate::bytes::BytesMut::new();
+        let mut codec = Codec::new().client_mode();
+        for msg in [
+            WsMessage::Text("996".into()),
+            WsMessage::Text("hello".into()),
+            WsMessage::Binary(Bytes::from_static(b"251")),
+            WsMessage::Continuation(Item::FirstText(Bytes::from_static(b"99"))),
+            WsMessage::Continuation(Item::Last(Bytes::from_static(b"6"))),
+            WsMessage::Close(None),
+        ] {
+            codec.encode(msg, &mut frames).unwrap();
+        }
+
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let res = App::new()
+                    .at("/", get(handler_service(handler)))
+                    .finish()
+                    .call(())
+                    .await
+                    .unwrap()
+                    .call(ws_request(frames.freeze()))
+                    .await
+                    .unwrap();
+
+                let body = collect_body(res.into_body()).await.unwrap();
+                let mut body = crate::bytes::BytesMut::from(body.as_slice());
+                let mut codec = Codec::new().client_mode();
+                let mut next = || codec.decode(&mut body).unwrap().unwrap();
+                assert_eq!(next(), WsMessage::Text("json 996".into()));
+                assert_eq!(next(), WsMessage::Text("text hello".into()));
+                assert_eq!(next(), WsMessage::Binary(Bytes::from_static(b"251")));
+                // continuation is passed to catch all handler.
+                assert_eq!(next(), WsMessage::Text("msg".into()));
+                assert_eq!(next(), WsMessage::Text("msg".into()));
+                assert_eq!(next(), WsMessage::Close(None));
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn join_broadcaster() {
+        use crate::handler::state::StateRef;
+
+        async fn handler(StateRef(broadcaster): StateRef<'_, Broadcaster>, mut ws: WebSocket) -> WebSocket {
+            ws.join(broadcaster);
+            ws
+        }
+
+        let mut frames = crate::bytes::BytesMut::new();
+        Codec::new()
+            .client_mode()
+            .encode(WsMessage::Close(None), &mut frames)
+            .unwrap();
+
+        let broadcaster = Broadcaster::new();
+
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let res = App::new()
+                    .with_state(broadcaster.clone())
+                    .at("/", get(handler_service(handler)))
+                    .finish()
+                    .call(())
+                    .await
+                    .unwrap()
+                    .call(ws_request(frames.freeze()))
+                    .await
+                    .unwrap();
+
+                assert_eq!(broadcaster.len(), 1);
+
+                // connection is removed from broadcaster after it's closed.
+                collect_body(res.into_body()).await.unwrap();
+                tokio::task::yield_now().await;
+                assert!(broadcaster.is_empty());
+            })
+            .await;
+    }
+}
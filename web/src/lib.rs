@@ -357,7 +357,7 @@ pub mod route {
     pub use xitca_http::util::service::route::{connect, delete, get, head, options, patch, post, put, trace, Route};
 }
 
-pub use app::{App, AppObject, NestApp};
+pub use app::{App, AppObject, InheritAppService, NestApp, Scope};
 pub use body::BodyStream;
 pub use context::WebContext;
 #[cfg(feature = "__server")]
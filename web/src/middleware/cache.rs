@@ -0,0 +1,889 @@
+//! http response cache middleware.
+//!
+//! Cacheable response produced by enclosed service is stored and served to following requests until
+//! it's expired. Concurrent requests missing the cache are coalesced so only one of them executes
+//! enclosed service.
+
+use core::{future::Future, time::Duration};
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use tokio::sync::watch;
+
+use crate::{
+    bytes::Bytes,
+    error::Error,
+    http::{
+        header::{HeaderMap, HeaderName, HeaderValue},
+        StatusCode,
+    },
+    service::Service,
+};
+
+/// builder for middleware caching response of enclosed service.
+///
+/// # Cacheable request
+/// Only `GET` and `HEAD` requests are served from and stored to cache. Request with `Authorization`
+/// header or `Cache-Control` header containing `no-store` or `no-cache` directive bypasses the cache.
+///
+/// # Cacheable response
+/// - status code is one of `200 OK`, `203 Non-Authoritative Information`, `301 Moved Permanently` and
+///   `404 Not Found`.
+/// - response has explicit freshness lifetime from `s-maxage` or `max-age` directive of
+///   `Cache-Control` header or `Expires` header. Freshness is not guessed from other headers.
+/// - `Cache-Control` header does not contain `no-store`, `no-cache` or `private` directive.
+/// - response does not have `Set-Cookie` header or `Vary: *` header.
+///
+/// Response and [Error] produced by enclosed service not meeting above conditions are passed through
+/// as is. The whole body of cacheable response is collected into memory before it's stored and
+/// returned. Response with body larger than [CacheStore::max_body_size] is passed through without
+/// being collected.
+///
+/// # Cache key
+/// Cache key is made of request method, uri authority (or `Host` header when uri has no authority) and uri
+/// path and query. Request headers listed by
+/// `Vary` header of response are stored along with it and cached response is only served to request
+/// with the same values of these headers.
+///
+/// # Coalescing
+/// When multiple requests miss the cache with the same key only one of them executes enclosed service
+/// and others wait for it. Waiting requests receive the newly cached response or execute enclosed
+/// service by themselves when it's not cacheable.
+///
+/// # Age and stale response
+/// Response served from cache has `Age` header of seconds since it's generated. Expired response with
+/// `stale-while-revalidate` directive can still be served for the given seconds after it's expired.
+/// In which case the first request observing it executes enclosed service to revalidate the cache and
+/// other requests during revalidation receive the stale response without waiting. `must-revalidate`
+/// directive disables serving stale response.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   body::ResponseBody,
+/// #   handler::handler_service,
+/// #   http::{header::{HeaderValue, CACHE_CONTROL}, WebResponse},
+/// #   middleware::cache::Cache,
+/// #   route::get,
+/// #   App,
+/// # };
+/// async fn expensive() -> WebResponse {
+///     let mut res = WebResponse::new(ResponseBody::from("report"));
+///     // response is fresh for 60 seconds and can be served stale for 30 more seconds during revalidation.
+///     res.headers_mut().insert(
+///         CACHE_CONTROL,
+///         HeaderValue::from_static("max-age=60, stale-while-revalidate=30"),
+///     );
+///     res
+/// }
+///
+/// App::new()
+///     .at("/report", get(handler_service(expensive)))
+///     .enclosed(Cache::new())
+///     .finish();
+/// ```
+#[derive(Clone)]
+pub struct Cache<St = MemoryStore> {
+    store: St,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cache {
+    /// construct a new middleware builder with default [MemoryStore].
+    ///
+    /// [MemoryStore] is shared by all clones of the builder and all services produced by it.
+    pub fn new() -> Self {
+        Self::with_store(MemoryStore::default())
+    }
+}
+
+impl<St> Cache<St> {
+    /// construct a new middleware builder with given store. See [CacheStore] for detail.
+    pub fn with_store(store: St) -> Self {
+        Self { store }
+    }
+}
+
+impl<St, S, E> Service<Result<S, E>> for Cache<St>
+where
+    St: Clone,
+{
+    type Response = service::CacheService<S, St>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::CacheService {
+            service,
+            store: self.store.clone(),
+        })
+    }
+}
+
+/// response stored by [CacheStore].
+#[derive(Clone, Debug)]
+pub struct CachedResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+    /// request headers listed by `Vary` header of response and their values.
+    pub vary: Vec<(HeaderName, Vec<HeaderValue>)>,
+    /// time when response is generated.
+    pub created: SystemTime,
+    /// freshness lifetime of response.
+    pub fresh: Duration,
+    /// duration response can be served after it's expired while it's being revalidated.
+    pub stale_while_revalidate: Duration,
+}
+
+impl CachedResponse {
+    /// duration since response is generated.
+    pub fn age(&self, now: SystemTime) -> Duration {
+        now.duration_since(self.created).unwrap_or_default()
+    }
+
+    /// check if response is fresh at given time.
+    pub fn is_fresh(&self, now: SystemTime) -> bool {
+        self.age(now) < self.fresh
+    }
+
+    /// check if response is fresh or can be served as stale response while it's being revalidated
+    /// at given time.
+    pub fn is_usable(&self, now: SystemTime) -> bool {
+        self.age(now) < self.usable()
+    }
+
+    // duration response can be served since it's generated.
+    fn usable(&self) -> Duration {
+        self.fresh.saturating_add(self.stale_while_revalidate)
+    }
+
+    /// check if response can be served to request with given headers according to it's `Vary` header.
+    pub fn is_match(&self, headers: &HeaderMap) -> bool {
+        self.vary
+            .iter()
+            .all(|(name, values)| headers.get_all(name).iter().eq(values.iter()))
+    }
+}
+
+/// outcome of [CacheStore::lookup].
+pub enum Lookup<P> {
+    /// response served to request. it's either fresh or stale while it's being revalidated by other
+    /// request.
+    Hit(Box<CachedResponse>),
+    /// request must execute enclosed service. response is passed to [CacheStore::store] with the
+    /// permit when it's cacheable. dropping the permit must wake up requests waiting on the same key.
+    Miss(P),
+    /// request executes enclosed service without storing response. it's used when request waited
+    /// for other request with the same key and there is still no response can be served.
+    Pass,
+}
+
+/// trait for storage of cached responses.
+///
+/// Implementation is responsible for coalescing requests with the same key:
+/// - fresh response matching request headers ([CachedResponse::is_fresh] and
+///   [CachedResponse::is_match]) is returned as [Lookup::Hit].
+/// - when no request is executing for the key [Lookup::Miss] is returned and the key is marked as
+///   in flight until permit is stored or dropped. response can be served as stale
+///   ([CachedResponse::is_usable]) should be revalidated this way.
+/// - when key is in flight usable stale response is returned as [Lookup::Hit]. Otherwise lookup
+///   waits for in flight request and returns it's response or [Lookup::Pass].
+pub trait CacheStore {
+    /// permit of executing request for missed key.
+    type Permit;
+
+    /// look up response of given key for request with given headers.
+    fn lookup(&self, key: &str, headers: &HeaderMap) -> impl Future<Output = Result<Lookup<Self::Permit>, Error>>;
+
+    /// store response of missed key.
+    fn store(&self, permit: Self::Permit, res: CachedResponse) -> impl Future<Output = Result<(), Error>>;
+
+    /// max size in bytes of response body can be stored. Response with larger body is passed through
+    /// without being collected. When the limit is set response body must have known size from it's
+    /// size hint to be cached. Default to no limit.
+    fn max_body_size(&self) -> usize {
+        usize::MAX
+    }
+}
+
+/// in memory [CacheStore]. One response is kept for each key and response of different `Vary` header
+/// values replaces each other. Expired responses are evicted when new response is stored.
+///
+/// Store keeps at most 1024 responses with body up to 1MiB by default. See [MemoryStore::capacity] and
+/// [MemoryStore::max_body_size] for changing the limits.
+#[derive(Clone)]
+pub struct MemoryStore {
+    map: Arc<Mutex<HashMap<String, Slot>>>,
+    capacity: usize,
+    max_body_size: usize,
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryStore {
+    /// construct a new store with default limits.
+    pub fn new() -> Self {
+        Self {
+            map: Arc::new(Mutex::new(HashMap::new())),
+            capacity: 1024,
+            max_body_size: 1024 * 1024,
+        }
+    }
+
+    /// set max number of responses kept in store. When store is full the response closest to it's
+    /// expiration is evicted to make room for new response.
+    ///
+    /// # Panics
+    /// When capacity is 0.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "cache capacity must be greater than 0");
+        self.capacity = capacity;
+        self
+    }
+
+    /// set max size in bytes of response body can be stored.
+    pub fn max_body_size(mut self, size: usize) -> Self {
+        self.max_body_size = size;
+        self
+    }
+
+    // evict response closest to expiration. slot of in flight key is kept for coalescing requests.
+    fn evict(map: &mut HashMap<String, Slot>, now: SystemTime) {
+        let key = map
+            .iter()
+            .filter_map(|(key, slot)| {
+                slot.res
+                    .as_ref()
+                    .map(|res| (key, res.usable().saturating_sub(res.age(now))))
+            })
+            .min_by_key(|(_, remain)| *remain)
+            .map(|(key, _)| key.clone());
+
+        if let Some(key) = key {
+            match map.get_mut(&key) {
+                Some(slot) if slot.in_flight.is_some() => slot.res = None,
+                _ => {
+                    map.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Slot {
+    res: Option<CachedResponse>,
+    in_flight: Option<watch::Receiver<()>>,
+}
+
+/// permit type of [MemoryStore].
+pub struct MemoryPermit {
+    store: MemoryStore,
+    key: String,
+    // dropping sender wakes up all requests waiting on in flight key.
+    _tx: watch::Sender<()>,
+}
+
+impl Drop for MemoryPermit {
+    fn drop(&mut self) {
+        let mut map = self.store.map.lock().unwrap();
+        if let Some(slot) = map.get_mut(&self.key) {
+            slot.in_flight = None;
+            if slot.res.is_none() {
+                map.remove(&self.key);
+            }
+        }
+    }
+}
+
+impl CacheStore for MemoryStore {
+    type Permit = MemoryPermit;
+
+    async fn lookup(&self, key: &str, headers: &HeaderMap) -> Result<Lookup<Self::Permit>, Error> {
+        let mut rx = {
+            let mut map = self.map.lock().unwrap();
+            let now = SystemTime::now();
+            let slot = map.entry(key.to_owned()).or_default();
+
+            let res = slot.res.as_ref().filter(|res| res.is_match(headers));
+
+            match (res, slot.in_flight.as_ref()) {
+                (Some(res), _) if res.is_fresh(now) => return Ok(Lookup::Hit(Box::new(res.clone()))),
+                (Some(res), Some(_)) if res.is_usable(now) => return Ok(Lookup::Hit(Box::new(res.clone()))),
+                (_, Some(rx)) => rx.clone(),
+                (_, None) => {
+                    let (tx, rx) = watch::channel(());
+                    slot.in_flight = Some(rx);
+                    return Ok(Lookup::Miss(MemoryPermit {
+                        store: self.clone(),
+                        key: key.to_owned(),
+                        _tx: tx,
+                    }));
+                }
+            }
+        };
+
+        // value is never sent and future resolves when permit is dropped.
+        let _ = rx.changed().await;
+
+        let map = self.map.lock().unwrap();
+        let now = SystemTime::now();
+        match map
+            .get(key)
+            .and_then(|slot| slot.res.as_ref())
+            .filter(|res| res.is_match(headers) && res.is_fresh(now))
+        {
+            Some(res) => Ok(Lookup::Hit(Box::new(res.clone()))),
+            None => Ok(Lookup::Pass),
+        }
+    }
+
+    async fn store(&self, permit: Self::Permit, res: CachedResponse) -> Result<(), Error> {
+        if res.body.len() > self.max_body_size {
+            return Ok(());
+        }
+
+        let now = SystemTime::now();
+        let mut map = self.map.lock().unwrap();
+        map.retain(|_, slot| slot.in_flight.is_some() || slot.res.as_ref().is_some_and(|res| res.is_usable(now)));
+
+        // replacing response of the same key does not take extra room.
+        if map.get(&permit.key).is_none_or(|slot| slot.res.is_none()) {
+            while map.values().filter(|slot| slot.res.is_some()).count() >= self.capacity {
+                Self::evict(&mut map, now);
+            }
+        }
+
+        map.entry(permit.key.clone()).or_default().res = Some(res);
+        // permit is dropped after lock is released and wakes up waiting requests.
+        drop(map);
+        drop(permit);
+        Ok(())
+    }
+
+    fn max_body_size(&self) -> usize {
+        self.max_body_size
+    }
+}
+
+mod service {
+    use core::{future::poll_fn, pin::pin};
+
+    use crate::{
+        body::{BodyStream, Either, ResponseBody},
+        bytes::BytesMut,
+        error::BodyError,
+        http::{
+            header::{AGE, AUTHORIZATION, CACHE_CONTROL, DATE, EXPIRES, HOST, SET_COOKIE, VARY},
+            Method, WebResponse,
+        },
+        service::ready::ReadyService,
+        WebContext,
+    };
+
+    use super::*;
+
+    pub struct CacheService<S, St> {
+        pub(super) service: S,
+        pub(super) store: St,
+    }
+
+    impl<'r, C, B, S, St, ResB> Service<WebContext<'r, C, B>> for CacheService<S, St>
+    where
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = Error>,
+        St: CacheStore,
+        ResB: BodyStream,
+    {
+        type Response = WebResponse<Either<ResB, ResponseBody>>;
+        type Error = Error;
+
+        async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let req = ctx.req();
+
+            if !matches!(*req.method(), Method::GET | Method::HEAD)
+                || req.headers().contains_key(AUTHORIZATION)
+                || Directives::from_headers(req.headers()).is_some_and(|d| d.no_store || d.no_cache)
+            {
+                return self.service.call(ctx).await.map(|res| res.map(Either::left));
+            }
+
+            // http/2 request carries host in :authority pseudo header and it may not have Host header.
+            let host = req
+                .uri()
+                .authority()
+                .map(|a| a.as_str().as_bytes())
+                .or_else(|| req.headers().get(HOST).map(|v| v.as_bytes()))
+                .unwrap_or_default();
+            let key = format!(
+                "{} {} {}",
+                req.method(),
+                String::from_utf8_lossy(host),
+                req.uri().path_and_query().map(|p| p.as_str()).unwrap_or("/")
+            );
+
+            let permit = match self.store.lookup(&key, req.headers()).await? {
+                Lookup::Miss(permit) => permit,
+                Lookup::Pass => return self.service.call(ctx).await.map(|res| res.map(Either::left)),
+                Lookup::Hit(cached) => {
+                    let age = cached.age(SystemTime::now()).as_secs();
+                    let mut res = ctx.into_response(ResponseBody::from(cached.body));
+                    *res.status_mut() = cached.status;
+                    *res.headers_mut() = cached.headers;
+                    res.headers_mut().insert(AGE, HeaderValue::from(age));
+                    return Ok(res.map(Either::right));
+                }
+            };
+
+            // vary header values of request are collected before it's consumed by enclosed service.
+            let req_headers = ctx.req().headers().clone();
+
+            // permit is dropped and waiting requests are woken up on error.
+            let res = self.service.call(ctx).await?;
+
+            let Some((fresh, stale_while_revalidate, age)) = freshness(res.status(), res.headers()) else {
+                return Ok(res.map(Either::left));
+            };
+
+            let Some(vary) = vary(res.headers(), &req_headers) else {
+                return Ok(res.map(Either::left));
+            };
+
+            // body of unknown size is only collected when store has no limit on body size.
+            let max_body_size = self.store.max_body_size();
+            if res
+                .body()
+                .size_hint()
+                .1
+                .map_or(max_body_size != usize::MAX, |size| size > max_body_size)
+            {
+                return Ok(res.map(Either::left));
+            }
+
+            // response with age larger than time since unix epoch can not be stored.
+            let Some(created) = SystemTime::now().checked_sub(age) else {
+                return Ok(res.map(Either::left));
+            };
+
+            let (parts, body) = res.into_parts();
+            let body = collect(body).await?;
+
+            let cached = CachedResponse {
+                status: parts.status,
+                headers: parts.headers.clone(),
+                body: body.clone(),
+                vary,
+                created,
+                fresh,
+                stale_while_revalidate,
+            };
+            self.store.store(permit, cached).await?;
+
+            Ok(WebResponse::from_parts(parts, Either::right(ResponseBody::from(body))))
+        }
+    }
+
+    // freshness lifetime, stale-while-revalidate duration and initial age of cacheable response.
+    fn freshness(status: StatusCode, headers: &HeaderMap) -> Option<(Duration, Duration, Duration)> {
+        if !matches!(
+            status,
+            StatusCode::OK
+                | StatusCode::NON_AUTHORITATIVE_INFORMATION
+                | StatusCode::MOVED_PERMANENTLY
+                | StatusCode::NOT_FOUND
+        ) || headers.contains_key(SET_COOKIE)
+        {
+            return None;
+        }
+
+        let directives = Directives::from_headers(headers).unwrap_or_default();
+        if directives.no_store || directives.no_cache || directives.private {
+            return None;
+        }
+
+        let fresh = match directives.s_maxage.or(directives.max_age) {
+            Some(secs) => Duration::from_secs(secs),
+            None => {
+                let expires = http_date(headers.get(EXPIRES)?)?;
+                let date = headers.get(DATE).and_then(http_date).unwrap_or_else(SystemTime::now);
+                expires.duration_since(date).ok()?
+            }
+        };
+
+        let stale_while_revalidate = match directives.must_revalidate {
+            true => Duration::ZERO,
+            false => Duration::from_secs(directives.stale_while_revalidate.unwrap_or(0)),
+        };
+
+        let age = headers
+            .get(AGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or_default();
+
+        (fresh > age || stale_while_revalidate > Duration::ZERO).then_some((fresh, stale_while_revalidate, age))
+    }
+
+    // request headers listed by vary header of response. None when response varies on everything.
+    fn vary(headers: &HeaderMap, req_headers: &HeaderMap) -> Option<Vec<(HeaderName, Vec<HeaderValue>)>> {
+        let mut vary = Vec::new();
+        for value in headers.get_all(VARY) {
+            for name in value.to_str().ok()?.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                if name == "*" {
+                    return None;
+                }
+                let name = HeaderName::from_bytes(name.as_bytes()).ok()?;
+                let values = req_headers.get_all(&name).iter().cloned().collect();
+                vary.push((name, values));
+            }
+        }
+        Some(vary)
+    }
+
+    fn http_date(value: &HeaderValue) -> Option<SystemTime> {
+        httpdate::parse_http_date(value.to_str().ok()?).ok()
+    }
+
+    // directives of Cache-Control header concerned by middleware.
+    #[derive(Default)]
+    struct Directives {
+        no_store: bool,
+        no_cache: bool,
+        private: bool,
+        must_revalidate: bool,
+        max_age: Option<u64>,
+        s_maxage: Option<u64>,
+        stale_while_revalidate: Option<u64>,
+    }
+
+    impl Directives {
+        fn from_headers(headers: &HeaderMap) -> Option<Self> {
+            let mut values = headers.get_all(CACHE_CONTROL).iter().peekable();
+            values.peek()?;
+
+            let mut this = Self::default();
+            for directive in values
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(','))
+                .map(str::trim)
+            {
+                let (name, value) = match directive.split_once('=') {
+                    Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                    None => (directive, None),
+                };
+                let secs = || value.and_then(|v| v.parse().ok());
+                match name.to_ascii_lowercase().as_str() {
+                    "no-store" => this.no_store = true,
+                    "no-cache" => this.no_cache = true,
+                    "private" => this.private = true,
+                    "must-revalidate" | "proxy-revalidate" => this.must_revalidate = true,
+                    "max-age" => this.max_age = secs(),
+                    "s-maxage" => this.s_maxage = secs(),
+                    "stale-while-revalidate" => this.stale_while_revalidate = secs(),
+                    _ => {}
+                }
+            }
+            Some(this)
+        }
+    }
+
+    async fn collect<B>(body: B) -> Result<Bytes, Error>
+    where
+        B: BodyStream,
+    {
+        let mut body = pin!(body);
+        let mut buf = BytesMut::new();
+        while let Some(chunk) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+            let chunk = chunk.map_err(|e| Error::from(Into::<BodyError>::into(e)))?;
+            buf.extend_from_slice(chunk.as_ref());
+        }
+        Ok(buf.freeze())
+    }
+
+    impl<S, St> ReadyService for CacheService<S, St>
+    where
+        S: ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{
+        body::ResponseBody,
+        handler::handler_service,
+        http::{
+            header::{ACCEPT_LANGUAGE, AGE, CACHE_CONTROL, HOST, VARY},
+            request, Method, RequestExt, WebResponse,
+        },
+        test::collect_body,
+        App, WebContext,
+    };
+
+    use super::*;
+
+    // respond with count of executions and use request path as cache-control header value.
+    async fn handler(ctx: &WebContext<'_, Arc<AtomicUsize>>) -> WebResponse {
+        let count = ctx.state().fetch_add(1, Ordering::SeqCst);
+        // yield to executor so concurrent request can observe in flight key.
+        tokio::task::yield_now().await;
+        let cache_control = ctx.req().uri().path().trim_start_matches('/').replace('_', ", ");
+        let mut res = WebResponse::new(ResponseBody::from(count.to_string()));
+        res.headers_mut()
+            .insert(CACHE_CONTROL, HeaderValue::try_from(cache_control).unwrap());
+        if let Some(vary) = ctx.req().uri().query() {
+            res.headers_mut().insert(VARY, HeaderValue::try_from(vary).unwrap());
+        }
+        res
+    }
+
+    fn req(method: Method, uri: &'static str, lang: Option<&'static str>) -> crate::http::WebRequest {
+        let mut req = request::Builder::default().method(method).uri(uri);
+        if let Some(lang) = lang {
+            req = req.header(ACCEPT_LANGUAGE, lang);
+        }
+        req.body(RequestExt::default()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn cache() {
+        let service = App::new()
+            .with_state(Arc::new(AtomicUsize::new(0)))
+            .at("/*path", handler_service(handler))
+            .enclosed(Cache::new())
+            .finish()
+            .call(())
+            .await
+            .unwrap();
+
+        let call = |req| async {
+            let res = service.call(req).await.unwrap();
+            let age = res.headers().contains_key(AGE);
+            let body = collect_body(res.into_body()).await.unwrap();
+            (String::from_utf8(body).unwrap().parse::<usize>().unwrap(), age)
+        };
+
+        // concurrent requests are coalesced.
+        let (first, second) = tokio::join!(
+            call(req(Method::GET, "/max-age=60", None)),
+            call(req(Method::GET, "/max-age=60", None))
+        );
+        assert_eq!(first, (0, false));
+        assert_eq!(second, (0, true));
+        assert_eq!(call(req(Method::GET, "/max-age=60", None)).await, (0, true));
+
+        // method is part of cache key and non cacheable method is not cached.
+        assert_eq!(call(req(Method::HEAD, "/max-age=60", None)).await, (1, false));
+        assert_eq!(call(req(Method::POST, "/max-age=60", None)).await, (2, false));
+        assert_eq!(call(req(Method::POST, "/max-age=60", None)).await, (3, false));
+
+        // uncacheable response.
+        for uri in ["/max-age=60_private", "/max-age=60_no-store", "/no-cache", "/public"] {
+            let (first, age) = call(req(Method::GET, uri, None)).await;
+            assert!(!age);
+            let (second, age) = call(req(Method::GET, uri, None)).await;
+            assert!(!age);
+            assert_eq!(second, first + 1);
+        }
+
+        // uncacheable response does not serialize concurrent requests.
+        let (first, second) = tokio::join!(
+            call(req(Method::GET, "/public", None)),
+            call(req(Method::GET, "/public", None))
+        );
+        assert!(!first.1 && !second.1);
+        assert_ne!(first.0, second.0);
+
+        // response varies on request header.
+        let (en, _) = call(req(Method::GET, "/max-age=60?accept-language", Some("en"))).await;
+        assert_eq!(
+            call(req(Method::GET, "/max-age=60?accept-language", Some("en"))).await,
+            (en, true)
+        );
+        let (fr, age) = call(req(Method::GET, "/max-age=60?accept-language", Some("fr"))).await;
+        assert!(!age);
+        assert_eq!(fr, en + 1);
+
+        // request bypasses cache.
+        let mut bypass = req(Method::GET, "/max-age=60", None);
+        bypass
+            .headers_mut()
+            .insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        assert_eq!(call(bypass).await, (fr + 1, false));
+    }
+
+    #[tokio::test]
+    async fn authority() {
+        let service = App::new()
+            .with_state(Arc::new(AtomicUsize::new(0)))
+            .at("/*path", handler_service(handler))
+            .enclosed(Cache::new())
+            .finish()
+            .call(())
+            .await
+            .unwrap();
+
+        let call = |req| async {
+            let res = service.call(req).await.unwrap();
+            let body = collect_body(res.into_body()).await.unwrap();
+            String::from_utf8(body).unwrap().parse::<usize>().unwrap()
+        };
+
+        // h2 style request with uri authority and without Host header.
+        assert_eq!(call(req(Method::GET, "http://a.example/max-age=60", None)).await, 0);
+        assert_eq!(call(req(Method::GET, "http://b.example/max-age=60", None)).await, 1);
+        assert_eq!(call(req(Method::GET, "http://a.example/max-age=60", None)).await, 0);
+        assert_eq!(call(req(Method::GET, "http://b.example/max-age=60", None)).await, 1);
+
+        // h1 style request falls back to Host header.
+        let host = |host| {
+            let mut req = req(Method::GET, "/max-age=60", None);
+            req.headers_mut().insert(HOST, HeaderValue::from_static(host));
+            req
+        };
+        assert_eq!(call(host("a.example")).await, 0);
+        assert_eq!(call(host("c.example")).await, 2);
+    }
+
+    #[tokio::test]
+    async fn stale_while_revalidate() {
+        let service = App::new()
+            .with_state(Arc::new(AtomicUsize::new(0)))
+            .at("/*path", handler_service(handler))
+            .enclosed(Cache::new())
+            .finish()
+            .call(())
+            .await
+            .unwrap();
+
+        let call = |req| async {
+            let res = service.call(req).await.unwrap();
+            let age = res.headers().contains_key(AGE);
+            let body = collect_body(res.into_body()).await.unwrap();
+            (String::from_utf8(body).unwrap().parse::<usize>().unwrap(), age)
+        };
+
+        let uri = "/max-age=0_stale-while-revalidate=60";
+
+        // response is stale right after it's stored.
+        let (first, _) = call(req(Method::GET, uri, None)).await;
+
+        // first request revalidates and the other one receives stale response.
+        let (revalidate, stale) = tokio::join!(call(req(Method::GET, uri, None)), call(req(Method::GET, uri, None)));
+        assert!(!revalidate.1);
+        assert!(revalidate.0 > first);
+        assert_eq!(stale, (first, true));
+
+        // must-revalidate disables stale response.
+        let uri = "/max-age=0_stale-while-revalidate=60_must-revalidate";
+        let (first, _) = call(req(Method::GET, uri, None)).await;
+        let (second, age) = call(req(Method::GET, uri, None)).await;
+        assert!(!age);
+        assert!(second > first);
+    }
+
+    fn cached(body: &'static str, fresh: u64, stale_while_revalidate: Duration) -> CachedResponse {
+        CachedResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(body.as_bytes()),
+            vary: Vec::new(),
+            created: SystemTime::now(),
+            fresh: Duration::from_secs(fresh),
+            stale_while_revalidate,
+        }
+    }
+
+    async fn store_res(store: &MemoryStore, key: &str, res: CachedResponse) {
+        match store.lookup(key, &HeaderMap::new()).await.unwrap() {
+            Lookup::Miss(permit) => store.store(permit, res).await.unwrap(),
+            _ => panic!("key must miss the cache"),
+        }
+    }
+
+    async fn is_hit(store: &MemoryStore, key: &str) -> bool {
+        matches!(store.lookup(key, &HeaderMap::new()).await.unwrap(), Lookup::Hit(_))
+    }
+
+    #[tokio::test]
+    async fn memory_store_limit() {
+        let store = MemoryStore::new().capacity(2).max_body_size(4);
+
+        // freshness lifetime plus stale duration saturates instead of overflow.
+        store_res(&store, "a", cached("a", 60, Duration::MAX)).await;
+        store_res(&store, "b", cached("b", 30, Duration::ZERO)).await;
+
+        // response closest to expiration is evicted when store is full.
+        store_res(&store, "c", cached("c", 90, Duration::ZERO)).await;
+        assert!(is_hit(&store, "a").await);
+        assert!(!is_hit(&store, "b").await);
+        assert!(is_hit(&store, "c").await);
+
+        // response with body larger than limit is not stored.
+        store_res(&store, "d", cached("large", 90, Duration::ZERO)).await;
+        assert!(!is_hit(&store, "d").await);
+        assert!(is_hit(&store, "a").await);
+    }
+
+    #[tokio::test]
+    async fn max_body_size() {
+        let service = App::new()
+            .with_state(Arc::new(AtomicUsize::new(0)))
+            .at("/*path", handler_service(handler))
+            .enclosed(Cache::with_store(MemoryStore::new().max_body_size(0)))
+            .finish()
+            .call(())
+            .await
+            .unwrap();
+
+        for _ in 0..2 {
+            let res = service.call(req(Method::GET, "/max-age=60", None)).await.unwrap();
+            assert!(!res.headers().contains_key(AGE));
+        }
+    }
+
+    #[tokio::test]
+    async fn large_age() {
+        async fn handler() -> WebResponse {
+            let mut res = WebResponse::new(ResponseBody::from("large age"));
+            res.headers_mut()
+                .insert(CACHE_CONTROL, HeaderValue::from_static("max-age=60"));
+            res.headers_mut()
+                .insert(AGE, HeaderValue::from_static("18446744073709551615"));
+            res
+        }
+
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(Cache::new())
+            .finish()
+            .call(())
+            .await
+            .unwrap();
+
+        let res = service.call(req(Method::GET, "/", None)).await.unwrap();
+        let body = collect_body(res.into_body()).await.unwrap();
+        assert_eq!(body, b"large age");
+    }
+}
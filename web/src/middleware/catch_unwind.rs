@@ -10,6 +10,21 @@ use crate::{
 
 /// middleware for catching panic inside [`Service::call`] and return a 500 error response.
 ///
+/// Without it a panic in handler would drop the connection it happens on without response. With it
+/// panic is converted to [`ThreadJoinError`] and can be observed by error handling middleware and
+/// rendered as 500 response. Connection is kept alive and can be used for following requests.
+///
+/// Panic message is still printed by panic hook of std. (or custom hook set by
+/// [`std::panic::set_hook`])
+///
+/// The middleware is opt-in as catching panic comes with tradeoffs. State shared between requests
+/// (for example a lock guarded value) may be left in inconsistent state by the panicked handler.
+/// There are also panics it can not catch:
+/// - process is built with `panic = "abort"` profile setting. Panic would abort the process.
+/// - panic inside tasks spawned by handler. Panic is contained in spawned task and surfaced from
+///   it's join handle.
+/// - panic while already panicking(double panic) always aborts the process.
+///
 /// # Examples:
 /// ```rust
 /// # use xitca_web::{handler::handler_service, middleware::CatchUnwind, service::ServiceExt, App, WebContext};
@@ -118,4 +133,34 @@ mod test {
 
         assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
     }
+
+    #[test]
+    fn catch_panic_keep_serving() {
+        async fn handler(req: &crate::http::WebRequest<()>) -> &'static str {
+            if req.uri().path() == "/panic" {
+                panic!("");
+            }
+            "996"
+        }
+
+        let service = App::new()
+            .at("/*", handler_service(handler))
+            .enclosed(CatchUnwind)
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        for (path, status) in [
+            ("/panic", StatusCode::INTERNAL_SERVER_ERROR),
+            ("/", StatusCode::OK),
+            ("/panic", StatusCode::INTERNAL_SERVER_ERROR),
+            ("/", StatusCode::OK),
+        ] {
+            let mut req = Request::default();
+            *req.uri_mut() = crate::http::Uri::from_static(path);
+            let res = service.call(req).now_or_panic().unwrap();
+            assert_eq!(res.status(), status);
+        }
+    }
 }
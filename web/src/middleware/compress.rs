@@ -1,5 +1,9 @@
 //! compression middleware
 
+use core::time::Duration;
+
+pub use http_encoding::{BrotliConfig, BrotliMode};
+
 use crate::service::Service;
 
 /// compress middleware.
@@ -13,22 +17,110 @@ use crate::service::Service;
 /// by it must be able to handle it's mutation or utilize [TypeEraser] to erase the mutation.
 /// For more explanation please reference [type mutation](crate::middleware#type-mutation).
 ///
+/// # Brotli configuration
+/// Brotli encoder uses [BrotliConfig::fast] preset by default. [Compress::brotli] can be used to
+/// tune compression ratio against CPU cost.
+///
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::handler_service,
+/// #   middleware::compress::{BrotliConfig, BrotliMode, Compress},
+/// #   App
+/// # };
+/// # async fn handler() -> &'static str { "" }
+/// App::new()
+///     .at("/", handler_service(handler))
+///     .enclosed(Compress.brotli(BrotliConfig::balanced().mode(BrotliMode::Text)))
+///     # .finish();
+/// ```
+///
+/// # Buffering
+/// By default every chunk of response body is compressed and flushed individually. For streaming
+/// response producing many small chunks (SSE, NDJSON etc) [Compress::buffer] can be used to
+/// accumulate body chunks before compressing them for better compression ratio.
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_web::{handler::handler_service, middleware::compress::Compress, App};
+/// # async fn handler() -> &'static str { "" }
+/// App::new()
+///     .at("/", handler_service(handler))
+///     // compress when 4KB of body is accumulated or body chunk has been held for 50ms.
+///     .enclosed(Compress.buffer(4096, Duration::from_millis(50)))
+///     # .finish();
+/// ```
+///
 /// [WebRequest]: crate::http::WebRequest
 /// [WebResponse]: crate::http::WebResponse
 /// [TypeEraser]: crate::middleware::eraser::TypeEraser
 #[derive(Clone)]
 pub struct Compress;
 
+impl Compress {
+    /// construct compress middleware with given [BrotliConfig] for brotli encoding.
+    pub const fn brotli(self, config: BrotliConfig) -> CompressWith {
+        CompressWith::new().brotli(config)
+    }
+
+    /// construct compress middleware buffering response body until given threshold in bytes
+    /// is reached or buffered body has been held longer than given interval.
+    pub const fn buffer(self, threshold: usize, interval: Duration) -> CompressWith {
+        CompressWith::new().buffer(threshold, interval)
+    }
+}
+
+/// compress middleware with custom encoder configuration. see [Compress::brotli] and
+/// [Compress::buffer] for detail.
+#[derive(Clone)]
+pub struct CompressWith {
+    brotli: BrotliConfig,
+    buffer: Option<(usize, Duration)>,
+}
+
+impl CompressWith {
+    const fn new() -> Self {
+        Self {
+            brotli: BrotliConfig::fast(),
+            buffer: None,
+        }
+    }
+
+    /// see [Compress::brotli] for detail.
+    pub const fn brotli(mut self, config: BrotliConfig) -> Self {
+        self.brotli = config;
+        self
+    }
+
+    /// see [Compress::buffer] for detail.
+    pub const fn buffer(mut self, threshold: usize, interval: Duration) -> Self {
+        self.buffer = Some((threshold, interval));
+        self
+    }
+}
+
 impl<S, E> Service<Result<S, E>> for Compress {
     type Response = service::CompressService<S>;
     type Error = E;
 
     async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
-        res.map(service::CompressService)
+        CompressWith::new().call(res).await
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for CompressWith {
+    type Response = service::CompressService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::CompressService {
+            service,
+            brotli: self.brotli,
+            buffer: self.buffer,
+        })
     }
 }
 mod service {
-    use http_encoding::{encoder, Coder, ContentEncoding};
+    use http_encoding::{encoder_with, Buffered, Coder, ContentEncoding};
 
     use crate::{
         body::{BodyStream, NONE_BODY_HINT},
@@ -36,7 +128,13 @@ mod service {
         service::{ready::ReadyService, Service},
     };
 
-    pub struct CompressService<S>(pub(super) S);
+    use super::{BrotliConfig, Duration};
+
+    pub struct CompressService<S> {
+        pub(super) service: S,
+        pub(super) brotli: BrotliConfig,
+        pub(super) buffer: Option<(usize, Duration)>,
+    }
 
     impl<S, Req, ResB> Service<Req> for CompressService<S>
     where
@@ -44,12 +142,12 @@ mod service {
         S: Service<Req, Response = WebResponse<ResB>>,
         ResB: BodyStream,
     {
-        type Response = WebResponse<Coder<ResB>>;
+        type Response = WebResponse<Coder<Buffered<ResB>>>;
         type Error = S::Error;
 
         async fn call(&self, req: Req) -> Result<Self::Response, Self::Error> {
             let mut encoding = ContentEncoding::from_headers(req.borrow());
-            let res = self.0.call(req).await?;
+            let res = self.service.call(req).await?;
 
             // TODO: expose encoding filter as public api.
             match res.body().size_hint() {
@@ -59,7 +157,14 @@ mod service {
                 _ => {}
             }
 
-            Ok(encoder(res, encoding))
+            let res = res.map(|body| match self.buffer {
+                Some((threshold, interval)) if encoding != ContentEncoding::NoOp => {
+                    Buffered::new(body).buffer(threshold, interval)
+                }
+                _ => Buffered::new(body),
+            });
+
+            Ok(encoder_with(res, encoding, self.brotli))
         }
     }
 
@@ -71,7 +176,7 @@ mod service {
 
         #[inline]
         async fn ready(&self) -> Self::Ready {
-            self.0.ready().await
+            self.service.ready().await
         }
     }
 }
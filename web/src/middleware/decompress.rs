@@ -8,22 +8,66 @@ use crate::service::Service;
 /// it according to enabled compress feature.
 /// `compress-x` feature must be enabled for this middleware to function correctly.
 ///
+/// When request body is decompressed `Content-Encoding` and `Content-Length` headers are removed from
+/// request so services enclosed by it observe the request as plain body with unknown length.
+///
+/// Size of decompressed body can be limited by [Decompress::set_decoded_body_max_size]. Decompressed
+/// body exceeding the limit produces [BodyOverFlow] error which would be converted to
+/// `413 Payload Too Large` response.
+///
 /// # Type mutation
-/// `Decompress` would mutate request body type from `B` to `Coder<B>`. Service enclosed
+/// `Decompress` would mutate request body type from `B` to `LimitBody<Coder<B>>`. Service enclosed
 /// by it must be able to handle it's mutation or utilize [TypeEraser] to erase the mutation.
 /// For more explanation please reference [type mutation](crate::middleware#type-mutation).
 ///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::handler_service, middleware::decompress::Decompress, App};
+/// App::new()
+///     .at("/", handler_service(|body: String| async move { body }))
+///     // limit decompressed request body to 1MB.
+///     .enclosed(Decompress::new().set_decoded_body_max_size(1024 * 1024))
+///     .finish();
+/// ```
+///
 /// [WebContext]: crate::WebContext
+/// [BodyOverFlow]: crate::error::BodyOverFlow
 /// [TypeEraser]: crate::middleware::eraser::TypeEraser
-#[derive(Clone)]
-pub struct Decompress;
+#[derive(Clone, Copy)]
+pub struct Decompress {
+    decoded_body_size: usize,
+}
+
+impl Default for Decompress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decompress {
+    /// construct decompress middleware with no limit on decompressed body size.
+    pub const fn new() -> Self {
+        Self {
+            decoded_body_size: usize::MAX,
+        }
+    }
+
+    /// Set max size in byte unit the decompressed request body can be.
+    pub fn set_decoded_body_max_size(mut self, size: usize) -> Self {
+        self.decoded_body_size = size;
+        self
+    }
+}
 
 impl<S, E> Service<Result<S, E>> for Decompress {
     type Response = service::DecompressService<S>;
     type Error = E;
 
     async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
-        res.map(service::DecompressService)
+        res.map(|service| service::DecompressService {
+            service,
+            decoded_body_size: self.decoded_body_size,
+        })
     }
 }
 
@@ -37,18 +81,26 @@ mod service {
         context::WebContext,
         error::error_from_service,
         error::Error,
-        http::{const_header_value::TEXT_UTF8, header::CONTENT_TYPE, Request, StatusCode, WebResponse},
+        http::{
+            const_header_value::TEXT_UTF8,
+            header::{CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE},
+            Request, StatusCode, WebResponse,
+        },
+        middleware::limit::LimitBody,
         service::ready::ReadyService,
     };
 
     use super::*;
 
-    pub struct DecompressService<S>(pub(super) S);
+    pub struct DecompressService<S> {
+        pub(super) service: S,
+        pub(super) decoded_body_size: usize,
+    }
 
     impl<'r, S, C, B, Res, Err> Service<WebContext<'r, C, B>> for DecompressService<S>
     where
         B: BodyStream + Default,
-        S: for<'rs> Service<WebContext<'rs, C, Coder<B>>, Response = Res, Error = Err>,
+        S: for<'rs> Service<WebContext<'rs, C, LimitBody<Coder<B>>>, Response = Res, Error = Err>,
         Err: Into<Error>,
     {
         type Response = Res;
@@ -58,18 +110,24 @@ mod service {
             let (parts, ext) = ctx.take_request().into_parts();
             let state = ctx.ctx;
             let (ext, body) = ext.replace_body(());
-            let req = Request::from_parts(parts, ());
+            let mut req = Request::from_parts(parts, ());
 
             let decoder = http_encoding::try_decoder(req.headers(), body)?;
-            let mut body = RefCell::new(decoder);
+
+            // length of encoded body does not apply to decoded one.
+            if req.headers_mut().remove(CONTENT_ENCODING).is_some() {
+                req.headers_mut().remove(CONTENT_LENGTH);
+            }
+
+            let mut body = RefCell::new(LimitBody::new(decoder, self.decoded_body_size));
             let mut req = req.map(|_| ext);
 
-            self.0
+            self.service
                 .call(WebContext::new(&mut req, &mut body, state))
                 .await
                 .map_err(|e| {
                     // restore original body as error path of other services may have use of it.
-                    let body = body.into_inner().into_inner();
+                    let body = body.into_inner().into_inner().into_inner();
                     *ctx.body_borrow_mut() = body;
                     e.into()
                 })
@@ -84,7 +142,7 @@ mod service {
 
         #[inline]
         async fn ready(&self) -> Self::Ready {
-            self.0.ready().await
+            self.service.ready().await
         }
     }
 
@@ -111,8 +169,10 @@ mod test {
     use crate::{
         body::ResponseBody,
         handler::handler_service,
-        http::header::CONTENT_ENCODING,
-        http::{WebRequest, WebResponse},
+        http::{
+            header::{CONTENT_ENCODING, CONTENT_LENGTH},
+            StatusCode, WebRequest, WebResponse,
+        },
         test::collect_body,
         App,
     };
@@ -135,7 +195,7 @@ mod test {
 
         App::new()
             .at("/", handler_service(noop))
-            .enclosed(Decompress)
+            .enclosed(Decompress::new())
             .finish()
             .call(())
             .now_or_panic()
@@ -151,7 +211,7 @@ mod test {
         let req = WebRequest::default().map(|ext| ext.map_body(|_: ()| Q.into()));
         App::new()
             .at("/", handler_service(handler))
-            .enclosed(Decompress)
+            .enclosed(Decompress::new())
             .finish()
             .call(())
             .now_or_panic()
@@ -190,25 +250,47 @@ mod test {
 
         let encoding = encoding();
 
-        let (mut parts, body) = encoder(res, encoding).into_parts();
+        let (parts, body) = encoder(res, encoding).into_parts();
 
         let body = collect_body(body).now_or_panic().unwrap();
 
-        let mut req = WebRequest::default().map(|ext| ext.map_body(|_: ()| body.into()));
+        let compressed_req = || {
+            let mut req = WebRequest::default().map(|ext| ext.map_body(|_: ()| body.clone().into()));
+            req.headers_mut()
+                .insert(CONTENT_ENCODING, parts.headers.get(CONTENT_ENCODING).unwrap().clone());
+            req.headers_mut().insert(CONTENT_LENGTH, body.len().into());
+            req
+        };
 
-        req.headers_mut()
-            .insert(CONTENT_ENCODING, parts.headers.remove(CONTENT_ENCODING).unwrap());
+        async fn handler_header(req: &WebRequest<()>, vec: Vec<u8>) -> &'static str {
+            assert!(!req.headers().contains_key(CONTENT_ENCODING));
+            assert!(!req.headers().contains_key(CONTENT_LENGTH));
+            handler(vec).await
+        }
 
         App::new()
-            .at("/", handler_service(handler))
-            .enclosed(Decompress)
+            .at("/", handler_service(handler_header))
+            .enclosed(Decompress::new())
             .finish()
             .call(())
             .now_or_panic()
             .unwrap()
-            .call(req)
+            .call(compressed_req())
             .now_or_panic()
             .ok()
             .unwrap();
+
+        // decoded body exceeding limit.
+        let res = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(Decompress::new().set_decoded_body_max_size(Q.len() - 1))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap()
+            .call(compressed_req())
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
     }
 }
@@ -0,0 +1,234 @@
+//! default response headers middleware.
+
+use crate::{
+    http::header::{
+        HeaderMap, HeaderName, HeaderValue, STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+    },
+    service::Service,
+};
+
+/// builder for middleware adding headers to every response.
+///
+/// Headers registered with [`DefaultHeaders::header`] are only added when response does not contain
+/// header with the same name already. Headers registered with [`DefaultHeaders::override_header`]
+/// replace all values of the same name from response.
+///
+/// Error produced by enclosed service is converted to response before headers are applied so error
+/// responses(like `404 Not Found` from router) carry default headers as well. Middleware enclosing
+/// [`DefaultHeaders`] would not observe error from inner services anymore and middlewares like
+/// [`MapError`] should be enclosed before it.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::handler_service,
+/// #   http::header::{HeaderValue, SERVER},
+/// #   middleware::default_headers::DefaultHeaders,
+/// #   App, WebContext,
+/// # };
+/// App::new()
+///     .at("/", handler_service(|_: &WebContext<'_>| async { "hello,world!" }))
+///     // security related headers with server header overriding the one produced by handlers.
+///     .enclosed(DefaultHeaders::security().override_header(SERVER, HeaderValue::from_static("xitca")))
+///     .finish();
+/// ```
+///
+/// [`MapError`]: crate::middleware::map_error::MapError
+#[derive(Clone, Default)]
+pub struct DefaultHeaders {
+    headers: HeaderMap,
+    overrides: HeaderMap,
+}
+
+impl DefaultHeaders {
+    /// construct a new middleware builder with no header registered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// construct a new middleware builder with preset of security related headers:
+    /// - `X-Content-Type-Options: nosniff`
+    /// - `X-Frame-Options: DENY`
+    /// - `Strict-Transport-Security: max-age=31536000; includeSubDomains`
+    ///
+    /// Preset headers are only added when response does not contain them.
+    pub fn security() -> Self {
+        Self::new()
+            .header(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"))
+            .header(X_FRAME_OPTIONS, HeaderValue::from_static("DENY"))
+            .header(
+                STRICT_TRANSPORT_SECURITY,
+                HeaderValue::from_static("max-age=31536000; includeSubDomains"),
+            )
+    }
+
+    /// register header that is added when response does not contain header with the same name.
+    ///
+    /// Registering the same name multiple times would add multiple values of it. Previous
+    /// registration of the same name with [`DefaultHeaders::override_header`] is removed.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.overrides.remove(&name);
+        self.headers.append(name, value);
+        self
+    }
+
+    /// register header that replaces all values of the same name from response.
+    ///
+    /// Registering the same name multiple times would add multiple values of it. Previous
+    /// registration of the same name with [`DefaultHeaders::header`] is removed.
+    pub fn override_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.remove(&name);
+        self.overrides.append(name, value);
+        self
+    }
+}
+
+impl From<HeaderMap> for DefaultHeaders {
+    fn from(headers: HeaderMap) -> Self {
+        Self {
+            headers,
+            overrides: HeaderMap::new(),
+        }
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for DefaultHeaders {
+    type Response = service::DefaultHeadersService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::DefaultHeadersService {
+            service,
+            headers: self.headers.clone(),
+            overrides: self.overrides.clone(),
+        })
+    }
+}
+
+mod service {
+    use core::convert::Infallible;
+
+    use crate::{
+        body::{Either, ResponseBody},
+        http::WebResponse,
+        WebContext,
+    };
+
+    use super::*;
+
+    pub struct DefaultHeadersService<S> {
+        pub(super) service: S,
+        pub(super) headers: HeaderMap,
+        pub(super) overrides: HeaderMap,
+    }
+
+    impl<'r, C, B, S, ResB, SE> Service<WebContext<'r, C, B>> for DefaultHeadersService<S>
+    where
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = SE>,
+        SE: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse, Error = Infallible>,
+    {
+        type Response = WebResponse<Either<ResB, ResponseBody>>;
+        type Error = Infallible;
+
+        async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let mut res = match self.service.call(ctx.reborrow()).await {
+                Ok(res) => res.map(Either::left),
+                Err(e) => e.call(ctx).await?.map(Either::right),
+            };
+
+            let headers = res.headers_mut();
+
+            for name in self.headers.keys() {
+                if !headers.contains_key(name) {
+                    for value in self.headers.get_all(name) {
+                        headers.append(name.clone(), value.clone());
+                    }
+                }
+            }
+
+            for name in self.overrides.keys() {
+                headers.remove(name);
+                for value in self.overrides.get_all(name) {
+                    headers.append(name.clone(), value.clone());
+                }
+            }
+
+            Ok(res)
+        }
+    }
+
+    impl<S> crate::service::ready::ReadyService for DefaultHeadersService<S>
+    where
+        S: crate::service::ready::ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::{RequestBody, ResponseBody},
+        handler::handler_service,
+        http::{
+            header::{CONTENT_TYPE, SERVER},
+            Request, RequestExt, StatusCode, Uri, WebResponse,
+        },
+        App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn default_headers() {
+        async fn handler() -> WebResponse {
+            let mut res = WebResponse::new(ResponseBody::from("996"));
+            res.headers_mut()
+                .insert(X_FRAME_OPTIONS, HeaderValue::from_static("SAMEORIGIN"));
+            res.headers_mut().insert(SERVER, HeaderValue::from_static("handler"));
+            res
+        }
+
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(
+                DefaultHeaders::security()
+                    .override_header(SERVER, HeaderValue::from_static("xitca"))
+                    .header(CONTENT_TYPE, HeaderValue::from_static("application/octet-stream")),
+            )
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service
+            .call(Request::new(RequestExt::<RequestBody>::default()))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let headers = res.headers();
+        // header produced by handler is kept.
+        assert_eq!(headers.get(X_FRAME_OPTIONS).unwrap(), "SAMEORIGIN");
+        // absent header is added.
+        assert_eq!(headers.get(CONTENT_TYPE).unwrap(), "application/octet-stream");
+        assert_eq!(headers.get(X_CONTENT_TYPE_OPTIONS).unwrap(), "nosniff");
+        assert!(headers.contains_key(STRICT_TRANSPORT_SECURITY));
+        // overridden header.
+        assert_eq!(headers.get_all(SERVER).iter().collect::<Vec<_>>(), ["xitca"]);
+
+        // error response carries default headers.
+        let mut req = Request::new(RequestExt::<RequestBody>::default());
+        *req.uri_mut() = Uri::from_static("/not_found");
+        let res = service.call(req).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(res.headers().get(X_FRAME_OPTIONS).unwrap(), "DENY");
+        assert_eq!(res.headers().get(SERVER).unwrap(), "xitca");
+    }
+}
@@ -0,0 +1,201 @@
+//! tls 1.3 0-RTT early data middleware.
+
+use core::fmt;
+
+use std::error;
+
+use crate::service::Service;
+
+/// builder for middleware rejecting request received as tls 1.3 0-RTT early data with
+/// `425 Too Early` response.
+///
+/// Early data can be replayed by attacker. By default only request with safe method(`GET`, `HEAD`,
+/// `OPTIONS` and `TRACE`) is allowed to be processed as early data. [`RejectEarlyData::all`] can be
+/// used to reject every request received as early data. Client receiving `425 Too Early` response is
+/// expected to retry request after tls handshake is finished.
+///
+/// A request is considered as early data when [`TlsInfo::early_data`] is true or it carries
+/// `Early-Data: 1` header added by intermediary according to
+/// [RFC 8470](https://www.rfc-editor.org/rfc/rfc8470).
+///
+/// Early data is disabled by default and rustls server must be configured with non zero
+/// `ServerConfig::max_early_data_size` to accept it.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::handler_service,
+/// #   middleware::early_data::RejectEarlyData,
+/// #   route::{get, post},
+/// #   App, WebContext,
+/// # };
+/// App::new()
+///     // get request is processed even it's received as early data.
+///     .at("/", get(handler_service(|| async { "index" })))
+///     // post request received as early data is rejected.
+///     .at("/order", post(handler_service(|| async { "ordered" })))
+///     # .at("/nah", handler_service(|_: &WebContext<'_>| async { "used for infer type" }))
+///     .enclosed(RejectEarlyData::new())
+///     .finish();
+/// ```
+///
+/// [`TlsInfo::early_data`]: crate::handler::tls::TlsInfo::early_data
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RejectEarlyData {
+    all: bool,
+}
+
+impl RejectEarlyData {
+    pub const fn new() -> Self {
+        Self { all: false }
+    }
+
+    /// reject all requests received as early data regardless of their method.
+    pub const fn all(mut self) -> Self {
+        self.all = true;
+        self
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for RejectEarlyData {
+    type Response = service::RejectEarlyDataService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::RejectEarlyDataService { service, all: self.all })
+    }
+}
+
+/// error type for request rejected for being received as tls early data. can be used by handler to
+/// produce `425 Too Early` response.
+#[derive(Debug)]
+pub struct TooEarly;
+
+impl fmt::Display for TooEarly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("request received as tls early data")
+    }
+}
+
+impl error::Error for TooEarly {}
+
+crate::error::error_from_service!(TooEarly);
+crate::error::blank_error_service!(TooEarly, crate::http::StatusCode::TOO_EARLY);
+
+mod service {
+    use crate::{
+        error::Error,
+        http::{HeaderName, Method, Request, RequestExt},
+        WebContext,
+    };
+
+    use super::*;
+
+    const EARLY_DATA: HeaderName = HeaderName::from_static("early-data");
+
+    pub struct RejectEarlyDataService<S> {
+        pub(super) service: S,
+        pub(super) all: bool,
+    }
+
+    impl<S> RejectEarlyDataService<S> {
+        fn is_rejected(&self, req: &Request<RequestExt<()>>) -> bool {
+            let is_early_data = req.body().tls_info().is_some_and(|info| info.early_data)
+                || req.headers().get(EARLY_DATA).is_some_and(|v| v == "1");
+
+            is_early_data
+                && (self.all
+                    || !matches!(
+                        *req.method(),
+                        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+                    ))
+        }
+    }
+
+    impl<'r, C, B, S, Res> Service<WebContext<'r, C, B>> for RejectEarlyDataService<S>
+    where
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = Error>,
+    {
+        type Response = Res;
+        type Error = Error;
+
+        async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            if self.is_rejected(ctx.req()) {
+                return Err(Error::from_service(TooEarly));
+            }
+            self.service.call(ctx).await
+        }
+    }
+
+    impl<S> crate::service::ready::ReadyService for RejectEarlyDataService<S>
+    where
+        S: crate::service::ready::ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        handler::{handler_service, tls::TlsInfo},
+        http::{header::HeaderValue, Method, Request, RequestExt, StatusCode},
+        route::get,
+        service::Service,
+        App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn reject_early_data() {
+        let call = |all: bool, method: Method, early_data: bool, header: bool| {
+            let builder = if all {
+                RejectEarlyData::new().all()
+            } else {
+                RejectEarlyData::new()
+            };
+
+            let service = App::new()
+                .at(
+                    "/",
+                    get(handler_service(|| async { "get" })).post(handler_service(|| async { "post" })),
+                )
+                .enclosed(builder)
+                .finish()
+                .call(())
+                .now_or_panic()
+                .unwrap();
+
+            let mut info = TlsInfo::default();
+            info.early_data = early_data;
+            let mut ext = RequestExt::default();
+            *ext.tls_info_mut() = Some(Arc::new(info));
+
+            let mut req = Request::new(ext);
+            *req.method_mut() = method;
+            if header {
+                req.headers_mut().insert("early-data", HeaderValue::from_static("1"));
+            }
+
+            let res = service.call(req).now_or_panic().unwrap();
+            res.status()
+        };
+
+        assert_eq!(call(false, Method::GET, false, false), StatusCode::OK);
+        assert_eq!(call(false, Method::POST, false, false), StatusCode::OK);
+        assert_eq!(call(false, Method::GET, true, false), StatusCode::OK);
+        assert_eq!(call(false, Method::POST, true, false), StatusCode::TOO_EARLY);
+        assert_eq!(call(false, Method::POST, false, true), StatusCode::TOO_EARLY);
+        assert_eq!(call(true, Method::GET, true, false), StatusCode::TOO_EARLY);
+    }
+}
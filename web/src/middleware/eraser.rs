@@ -136,7 +136,7 @@ mod service {
         type Error = Err;
 
         async fn call(&self, mut ctx: WebContext<'r, C, ReqB>) -> Result<Self::Response, Self::Error> {
-            let body = ctx.take_body_mut();
+            let body = ctx.take_body();
             let mut body = RefCell::new(RequestBody::Unknown(BoxBody::new(body)));
             let WebContext { req, ctx, .. } = ctx;
             let res = self.service.call(WebContext::new(req, &mut body, ctx)).await?;
@@ -0,0 +1,556 @@
+//! idempotency key middleware.
+//!
+//! Request carrying `Idempotency-Key` header is executed at most once for the same key and the
+//! response is cached. Retried request with the same key receives the cached response without
+//! executing enclosed service again.
+
+use core::{future::Future, time::Duration};
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use tokio::sync::watch;
+
+use crate::{
+    bytes::Bytes,
+    error::Error,
+    http::{
+        header::{HeaderMap, HeaderName},
+        StatusCode,
+    },
+    service::Service,
+};
+
+/// request header name carrying idempotency key.
+pub const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+
+/// response header name added to cached response when it's replayed for retried request.
+pub const IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// builder for middleware honoring `Idempotency-Key` request header.
+///
+/// # Key
+/// Cache key is made of request method, uri path and value of `Idempotency-Key` header. Request without
+/// the header is passed to enclosed service as is. Request with header value that is empty or not
+/// visible ASCII string is rejected with `400 Bad Request` response.
+///
+/// # In flight request
+/// When a request with the same key is already being executed the retried request waits for it to
+/// finish. It receives the cached response when the first one produced cacheable response or executes
+/// enclosed service by itself otherwise.
+///
+/// # Cacheable response
+/// - Response with status code other than `5xx` is cached. The whole response body is collected into
+///   memory before it's cached and returned. Streaming response (server sent events .etc) should not be
+///   enclosed by this middleware.
+/// - Response with body larger than [IdempotencyStore::max_body_size] is passed through without being
+///   collected or cached and the key is released.
+/// - Response with `5xx` status code and [Error] produced by enclosed service are not cached and the key
+///   is released for next retry to execute again. [Error] can be converted to cacheable response with
+///   [`MapError`] middleware enclosed before this middleware.
+///
+/// Request body is not part of cache key and retried request with the same key but different body still
+/// receives the cached response.
+///
+/// # TTL
+/// Cached response expires after 24 hours by default and can be changed with [`Idempotency::ttl`]. The
+/// key can be used to execute enclosed service again after it's expired.
+///
+/// # Examples
+/// ```rust
+/// # use std::time::Duration;
+/// # use xitca_web::{handler::handler_service, middleware::idempotency::Idempotency, route::post, App};
+/// App::new()
+///     .at("/payment", post(handler_service(|| async { "paid" })))
+///     .enclosed(Idempotency::new().ttl(Duration::from_secs(3600)))
+///     .finish();
+/// ```
+///
+/// [`MapError`]: crate::middleware::map_error::MapError
+#[derive(Clone)]
+pub struct Idempotency<St = MemoryStore> {
+    store: St,
+    ttl: Duration,
+}
+
+impl Default for Idempotency {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Idempotency {
+    /// construct a new middleware builder with default [MemoryStore].
+    ///
+    /// [MemoryStore] is shared by all clones of the builder and all services produced by it.
+    pub fn new() -> Self {
+        Self::with_store(MemoryStore::default())
+    }
+}
+
+impl<St> Idempotency<St> {
+    /// construct a new middleware builder with given store. See [IdempotencyStore] for detail.
+    pub fn with_store(store: St) -> Self {
+        Self {
+            store,
+            ttl: Duration::from_secs(60 * 60 * 24),
+        }
+    }
+
+    /// set the duration cached response lives in store.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+impl<St, S, E> Service<Result<S, E>> for Idempotency<St>
+where
+    St: Clone,
+{
+    type Response = service::IdempotencyService<S, St>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::IdempotencyService {
+            service,
+            store: self.store.clone(),
+            ttl: self.ttl,
+        })
+    }
+}
+
+/// response cached by [IdempotencyStore].
+#[derive(Clone, Debug)]
+pub struct CachedResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+/// outcome of [IdempotencyStore::acquire].
+pub enum Acquire<P> {
+    /// key is acquired and request is about to be executed. dropping the permit without passing it
+    /// to [IdempotencyStore::complete] must release the key.
+    Permit(P),
+    /// response cached from previous request with the same key.
+    Cached(CachedResponse),
+}
+
+/// trait for storage of idempotency keys and cached responses.
+///
+/// Implementing this trait for remote storage (Redis .etc) enables sharing keys across multiple
+/// application instances. A typical implementation would:
+/// - acquire key with atomic set if not exist operation where the value marks in flight state and
+///   expires after a timeout, so a key of crashed instance is not held forever.
+/// - poll the key with a back off when it's in flight until it's completed or released.
+/// - release the key in [Drop] implementation of permit type by spawning a delete task or leave
+///   it to the expiration of in flight marker.
+pub trait IdempotencyStore {
+    /// permit of executing request for acquired key.
+    type Permit;
+
+    /// acquire given key or obtain cached response of it.
+    ///
+    /// When the key is in flight the returned future must wait until it's completed or released.
+    fn acquire(&self, key: &str) -> impl Future<Output = Result<Acquire<Self::Permit>, Error>>;
+
+    /// complete acquired key with cacheable response that lives for given duration.
+    fn complete(
+        &self,
+        permit: Self::Permit,
+        res: CachedResponse,
+        ttl: Duration,
+    ) -> impl Future<Output = Result<(), Error>>;
+
+    /// max size in bytes of response body can be cached. Response with larger body is passed through
+    /// without being collected. When the limit is set response body must have known size from it's
+    /// size hint to be cached. Default to no limit.
+    fn max_body_size(&self) -> usize {
+        usize::MAX
+    }
+}
+
+/// in memory [IdempotencyStore]. Expired responses are evicted when new response is cached.
+///
+/// Store keeps at most 1024 responses with body up to 1MiB by default. See [MemoryStore::capacity] and
+/// [MemoryStore::max_body_size] for changing the limits.
+#[derive(Clone)]
+pub struct MemoryStore {
+    map: Arc<Mutex<HashMap<String, Slot>>>,
+    capacity: usize,
+    max_body_size: usize,
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryStore {
+    /// construct a new store with default limits.
+    pub fn new() -> Self {
+        Self {
+            map: Arc::new(Mutex::new(HashMap::new())),
+            capacity: 1024,
+            max_body_size: 1024 * 1024,
+        }
+    }
+
+    /// set max number of responses kept in store. When store is full the oldest response is evicted to
+    /// make room for new response.
+    ///
+    /// # Panics
+    /// When capacity is 0.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "idempotency store capacity must be greater than 0");
+        self.capacity = capacity;
+        self
+    }
+
+    /// set max size in bytes of response body can be cached.
+    pub fn max_body_size(mut self, size: usize) -> Self {
+        self.max_body_size = size;
+        self
+    }
+}
+
+enum Slot {
+    InFlight(watch::Receiver<()>),
+    Done(CachedResponse, Instant),
+}
+
+/// permit type of [MemoryStore].
+pub struct MemoryPermit {
+    store: MemoryStore,
+    key: String,
+    // dropping sender wakes up all requests waiting on in flight key.
+    _tx: watch::Sender<()>,
+    completed: bool,
+}
+
+impl Drop for MemoryPermit {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.store.map.lock().unwrap().remove(&self.key);
+        }
+    }
+}
+
+impl IdempotencyStore for MemoryStore {
+    type Permit = MemoryPermit;
+
+    async fn acquire(&self, key: &str) -> Result<Acquire<Self::Permit>, Error> {
+        loop {
+            let mut rx = {
+                let mut map = self.map.lock().unwrap();
+                match map.get(key) {
+                    Some(Slot::Done(res, expires)) if *expires > Instant::now() => {
+                        return Ok(Acquire::Cached(res.clone()));
+                    }
+                    Some(Slot::InFlight(rx)) => rx.clone(),
+                    _ => {
+                        let (tx, rx) = watch::channel(());
+                        map.insert(key.to_owned(), Slot::InFlight(rx));
+                        return Ok(Acquire::Permit(MemoryPermit {
+                            store: self.clone(),
+                            key: key.to_owned(),
+                            _tx: tx,
+                            completed: false,
+                        }));
+                    }
+                }
+            };
+            // value is never sent and future resolves when permit is dropped.
+            let _ = rx.changed().await;
+        }
+    }
+
+    async fn complete(&self, mut permit: Self::Permit, res: CachedResponse, ttl: Duration) -> Result<(), Error> {
+        // permit is dropped and key is released.
+        if res.body.len() > self.max_body_size {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let mut map = self.map.lock().unwrap();
+        map.retain(|_, slot| !matches!(slot, Slot::Done(_, expires) if *expires <= now));
+
+        // evict the response closest to expiration when store is full. in flight keys are kept.
+        while map.values().filter(|slot| matches!(slot, Slot::Done(..))).count() >= self.capacity {
+            let key = map
+                .iter()
+                .filter_map(|(key, slot)| match slot {
+                    Slot::Done(_, expires) => Some((key, expires)),
+                    Slot::InFlight(_) => None,
+                })
+                .min_by_key(|(_, expires)| **expires)
+                .map(|(key, _)| key.clone());
+            if let Some(key) = key {
+                map.remove(&key);
+            }
+        }
+
+        map.insert(permit.key.clone(), Slot::Done(res, now + ttl));
+        permit.completed = true;
+        Ok(())
+    }
+
+    fn max_body_size(&self) -> usize {
+        self.max_body_size
+    }
+}
+
+mod service {
+    use core::{future::poll_fn, pin::pin};
+
+    use crate::{
+        body::{BodyStream, Either, ResponseBody},
+        bytes::BytesMut,
+        error::{BodyError, InvalidHeaderValue},
+        http::{header::HeaderValue, WebResponse},
+        service::ready::ReadyService,
+        WebContext,
+    };
+
+    use super::*;
+
+    pub struct IdempotencyService<S, St> {
+        pub(super) service: S,
+        pub(super) store: St,
+        pub(super) ttl: Duration,
+    }
+
+    impl<'r, C, B, S, St, ResB> Service<WebContext<'r, C, B>> for IdempotencyService<S, St>
+    where
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = Error>,
+        St: IdempotencyStore,
+        ResB: BodyStream,
+    {
+        type Response = WebResponse<Either<ResB, ResponseBody>>;
+        type Error = Error;
+
+        async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let key = match ctx.req().headers().get(&IDEMPOTENCY_KEY) {
+                Some(value) => {
+                    let value = value
+                        .to_str()
+                        .ok()
+                        .filter(|v| !v.is_empty())
+                        .ok_or(InvalidHeaderValue(IDEMPOTENCY_KEY))?;
+                    format!("{} {} {}", ctx.req().method(), ctx.req().uri().path(), value)
+                }
+                None => return self.service.call(ctx).await.map(|res| res.map(Either::left)),
+            };
+
+            let permit = match self.store.acquire(&key).await? {
+                Acquire::Permit(permit) => permit,
+                Acquire::Cached(CachedResponse { status, headers, body }) => {
+                    let mut res = ctx.into_response(ResponseBody::from(body));
+                    *res.status_mut() = status;
+                    *res.headers_mut() = headers;
+                    res.headers_mut()
+                        .insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+                    return Ok(res.map(Either::right));
+                }
+            };
+
+            // permit is dropped and key is released on error.
+            let res = self.service.call(ctx).await?;
+
+            if res.status().is_server_error() {
+                return Ok(res.map(Either::left));
+            }
+
+            // body of unknown size is only collected when store has no limit on body size.
+            let max_body_size = self.store.max_body_size();
+            if res
+                .body()
+                .size_hint()
+                .1
+                .map_or(max_body_size != usize::MAX, |size| size > max_body_size)
+            {
+                return Ok(res.map(Either::left));
+            }
+
+            let (parts, body) = res.into_parts();
+            let body = collect(body).await?;
+
+            let cached = CachedResponse {
+                status: parts.status,
+                headers: parts.headers.clone(),
+                body: body.clone(),
+            };
+            self.store.complete(permit, cached, self.ttl).await?;
+
+            Ok(WebResponse::from_parts(parts, Either::right(ResponseBody::from(body))))
+        }
+    }
+
+    async fn collect<B>(body: B) -> Result<Bytes, Error>
+    where
+        B: BodyStream,
+    {
+        let mut body = pin!(body);
+        let mut buf = BytesMut::new();
+        while let Some(chunk) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+            let chunk = chunk.map_err(|e| Error::from(Into::<BodyError>::into(e)))?;
+            buf.extend_from_slice(chunk.as_ref());
+        }
+        Ok(buf.freeze())
+    }
+
+    impl<S, St> ReadyService for IdempotencyService<S, St>
+    where
+        S: ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{
+        handler::handler_service,
+        http::{request, Method, RequestExt},
+        route::post,
+        test::collect_body,
+        App,
+    };
+
+    use super::*;
+
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    async fn handler(body: String) -> Result<String, Error> {
+        let count = COUNT.fetch_add(1, Ordering::SeqCst);
+        // yield to executor so concurrent request can observe in flight key.
+        tokio::task::yield_now().await;
+        match body.as_str() {
+            "error" => Err(Error::from(StatusCode::INTERNAL_SERVER_ERROR)),
+            _ => Ok(format!("{body}-{count}")),
+        }
+    }
+
+    fn req(key: Option<&'static str>, path: &'static str, body: &'static str) -> crate::http::WebRequest {
+        let mut req = request::Builder::default().method(Method::POST).uri(path);
+        if let Some(key) = key {
+            req = req.header(IDEMPOTENCY_KEY, key);
+        }
+        req.body(RequestExt::default().map_body(|_: ()| Bytes::from_static(body.as_bytes()).into()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn idempotency() {
+        let service = App::new()
+            .at("/", post(handler_service(handler)))
+            .at("/other", post(handler_service(handler)))
+            .enclosed(Idempotency::new())
+            .finish()
+            .call(())
+            .await
+            .unwrap();
+
+        let call = |req| async {
+            let res = service.call(req).await.unwrap();
+            let replayed = res.headers().contains_key(IDEMPOTENT_REPLAYED);
+            let status = res.status();
+            let body = collect_body(res.into_body()).await.unwrap();
+            (status, String::from_utf8(body).unwrap(), replayed)
+        };
+
+        // concurrent retry waits for the first request.
+        let (first, second) = tokio::join!(call(req(Some("foo"), "/", "a")), call(req(Some("foo"), "/", "b")));
+        assert_eq!(first, (StatusCode::OK, "a-0".to_owned(), false));
+        assert_eq!(second, (StatusCode::OK, "a-0".to_owned(), true));
+
+        // different path is keyed separately.
+        let res = call(req(Some("foo"), "/other", "a")).await;
+        assert_eq!(res, (StatusCode::OK, "a-1".to_owned(), false));
+
+        // request without key is always executed.
+        let res = call(req(None, "/", "a")).await;
+        assert_eq!(res, (StatusCode::OK, "a-2".to_owned(), false));
+        let res = call(req(None, "/", "a")).await;
+        assert_eq!(res, (StatusCode::OK, "a-3".to_owned(), false));
+
+        // error is not cached.
+        let res = call(req(Some("bar"), "/", "error")).await;
+        assert_eq!(res.0, StatusCode::INTERNAL_SERVER_ERROR);
+        let res = call(req(Some("bar"), "/", "a")).await;
+        assert_eq!(res, (StatusCode::OK, "a-5".to_owned(), false));
+
+        let res = call(req(Some(""), "/", "a")).await;
+        assert_eq!(res.0, StatusCode::BAD_REQUEST);
+    }
+
+    fn cached(body: &'static str) -> CachedResponse {
+        CachedResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(body.as_bytes()),
+        }
+    }
+
+    async fn complete(store: &MemoryStore, key: &str, res: CachedResponse) {
+        match store.acquire(key).await.unwrap() {
+            Acquire::Permit(permit) => store.complete(permit, res, Duration::from_secs(60)).await.unwrap(),
+            Acquire::Cached(_) => panic!("key must not be cached"),
+        }
+    }
+
+    async fn is_cached(store: &MemoryStore, key: &str) -> bool {
+        matches!(store.acquire(key).await.unwrap(), Acquire::Cached(_))
+    }
+
+    #[tokio::test]
+    async fn memory_store_limit() {
+        let store = MemoryStore::new().capacity(2).max_body_size(4);
+
+        complete(&store, "a", cached("a")).await;
+        complete(&store, "b", cached("b")).await;
+
+        // oldest response is evicted when store is full.
+        complete(&store, "c", cached("c")).await;
+        assert!(!is_cached(&store, "a").await);
+        assert!(is_cached(&store, "b").await);
+        assert!(is_cached(&store, "c").await);
+
+        // response with body larger than limit is not cached and key is released.
+        complete(&store, "d", cached("large")).await;
+        assert!(!is_cached(&store, "d").await);
+        assert!(is_cached(&store, "b").await);
+        assert!(is_cached(&store, "c").await);
+    }
+
+    #[tokio::test]
+    async fn max_body_size() {
+        let service = App::new()
+            .at("/", post(handler_service(|| async { "large body" })))
+            .enclosed(Idempotency::with_store(MemoryStore::new().max_body_size(4)))
+            .finish()
+            .call(())
+            .await
+            .unwrap();
+
+        for _ in 0..2 {
+            let res = service.call(req(Some("foo"), "/", "a")).await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+            assert!(!res.headers().contains_key(IDEMPOTENT_REPLAYED));
+            let body = collect_body(res.into_body()).await.unwrap();
+            assert_eq!(body, b"large body");
+        }
+    }
+}
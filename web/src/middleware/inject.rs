@@ -0,0 +1,107 @@
+//! middleware for injecting request scoped value to request extensions.
+
+use crate::{
+    http::{BorrowReqMut, Extensions},
+    service::{ready::ReadyService, Service},
+};
+
+/// middleware for computing a value from request and insert it to request's [Extensions]. The value
+/// can be extracted by downstream handlers with [ExtensionRef] or [ExtensionOwn] extractors.
+///
+/// Unlike [Extension] middleware which clones the same value for every request, the value is
+/// produced by given function per request.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::{extension::ExtensionRef, handler_service},
+/// #   http::header::HOST,
+/// #   middleware::Inject,
+/// #   App, WebContext
+/// # };
+/// #[derive(Clone)]
+/// struct Tenant(String);
+///
+/// // tenant id is parsed from subdomain and injected by middleware.
+/// async fn handler(ExtensionRef(tenant): ExtensionRef<'_, Tenant>) -> String {
+///     format!("hello {}", tenant.0)
+/// }
+///
+/// App::new()
+///     .at("/", handler_service(handler))
+///     .enclosed(Inject::new(|ctx: &WebContext<'_>| {
+///         let host = ctx.req().headers().get(HOST).and_then(|v| v.to_str().ok()).unwrap_or("");
+///         let tenant = host.split('.').next().unwrap_or("").to_owned();
+///         Tenant(tenant)
+///     }))
+///     .finish();
+/// ```
+///
+/// For async computation of value [ServiceExt::enclosed_fn] can be used to mutate extensions
+/// through [WebContext::req_mut].
+///
+/// [ExtensionRef]: crate::handler::extension::ExtensionRef
+/// [ExtensionOwn]: crate::handler::extension::ExtensionOwn
+/// [Extension]: crate::middleware::Extension
+/// [ServiceExt::enclosed_fn]: crate::service::ServiceExt::enclosed_fn
+/// [WebContext::req_mut]: crate::WebContext::req_mut
+#[derive(Clone)]
+pub struct Inject<F> {
+    func: F,
+}
+
+impl<F> Inject<F> {
+    pub fn new(func: F) -> Self {
+        Self { func }
+    }
+}
+
+impl<S, E, F> Service<Result<S, E>> for Inject<F>
+where
+    F: Clone,
+{
+    type Response = InjectService<S, F>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| InjectService {
+            service,
+            func: self.func.clone(),
+        })
+    }
+}
+
+pub struct InjectService<S, F> {
+    service: S,
+    func: F,
+}
+
+impl<S, F, T, Req> Service<Req> for InjectService<S, F>
+where
+    S: Service<Req>,
+    F: Fn(&Req) -> T,
+    T: Clone + Send + Sync + 'static,
+    Req: BorrowReqMut<Extensions>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+
+    #[inline]
+    async fn call(&self, mut req: Req) -> Result<Self::Response, Self::Error> {
+        let value = (self.func)(&req);
+        req.borrow_mut().insert(value);
+        self.service.call(req).await
+    }
+}
+
+impl<S, F> ReadyService for InjectService<S, F>
+where
+    S: ReadyService,
+{
+    type Ready = S::Ready;
+
+    #[inline]
+    async fn ready(&self) -> Self::Ready {
+        self.service.ready().await
+    }
+}
@@ -0,0 +1,588 @@
+//! bearer json web token validation middleware.
+
+use core::{fmt, future::Future, marker::PhantomData, time::Duration};
+
+use std::{
+    collections::HashMap,
+    error,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use serde::de::DeserializeOwned;
+use tokio::sync::watch;
+
+use crate::{
+    error::Error,
+    http::{
+        header::{HeaderValue, AUTHORIZATION, WWW_AUTHENTICATE},
+        StatusCode, WebResponse,
+    },
+    service::{AsyncFn, Service},
+    WebContext,
+};
+
+pub use jsonwebtoken::{jwk::JwkSet, Algorithm, DecodingKey, Header, Validation};
+
+/// builder for middleware validating bearer json web token from `Authorization` header of request.
+///
+/// Token is validated with given [`Validation`] (signature algorithm, expiry, audience, issuer and
+/// etc) and key from [`KeyStore`]. Claims of valid token are deserialized to type `T` and inserted
+/// into request extensions as [`Claims<T>`] where it can be extracted by handler. Request with
+/// missing or invalid token is rejected with 401 status code.
+///
+/// # Examples
+/// ```rust
+/// # use serde::Deserialize;
+/// # use xitca_web::{
+/// #   handler::{auth::Claims, handler_service},
+/// #   middleware::jwt::{Algorithm, DecodingKey, Jwt, Validation},
+/// #   App,
+/// # };
+/// #[derive(Clone, Deserialize)]
+/// struct User {
+///     sub: String,
+/// }
+///
+/// async fn handler(Claims(user): Claims<User>) -> String {
+///     format!("hello, {}!", user.sub)
+/// }
+///
+/// let mut validation = Validation::new(Algorithm::HS256);
+/// validation.set_audience(&["my-app"]);
+///
+/// App::new()
+///     .at("/", handler_service(handler))
+///     .enclosed(Jwt::<User, _>::new(DecodingKey::from_secret(b"secret"), validation))
+///     .finish();
+/// ```
+///
+/// [`Claims<T>`]: crate::handler::auth::Claims
+pub struct Jwt<T, K> {
+    keys: K,
+    validation: Arc<Validation>,
+    _claims: PhantomData<fn() -> T>,
+}
+
+impl<T, K> Jwt<T, K>
+where
+    K: KeyStore,
+{
+    /// construct a new middleware builder with given key store and validation rules.
+    ///
+    /// [`DecodingKey`] can be used as key store of a single static key. [`Jwks`] can be used for
+    /// fetching keys from remote JWKS endpoint.
+    pub fn new(keys: K, validation: Validation) -> Self {
+        Self {
+            keys,
+            validation: Arc::new(validation),
+            _claims: PhantomData,
+        }
+    }
+}
+
+impl<T, K, S, E> Service<Result<S, E>> for Jwt<T, K>
+where
+    K: Clone,
+{
+    type Response = service::JwtService<T, K, S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::JwtService {
+            service,
+            keys: self.keys.clone(),
+            validation: self.validation.clone(),
+            _claims: PhantomData,
+        })
+    }
+}
+
+/// trait for source of keys used for validating token signature.
+pub trait KeyStore {
+    /// validate token with matching key and deserialize it's claims.
+    fn decode<T>(
+        &self,
+        token: &str,
+        header: &Header,
+        validation: &Validation,
+    ) -> impl Future<Output = Result<T, Error>>
+    where
+        T: DeserializeOwned;
+}
+
+impl KeyStore for DecodingKey {
+    async fn decode<T>(&self, token: &str, _: &Header, validation: &Validation) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        decode(token, self, validation)
+    }
+}
+
+/// key store fetching keys from JWKS (json web key set) and caching them in memory.
+///
+/// Key is looked up with `kid` field of token header. Keys are fetched again when cache is older than
+/// max age or when token refers to an unknown key. Fetching for unknown key happens at most once every
+/// 10 seconds so forged tokens can't be used to flood the JWKS endpoint. Only one fetch runs at a time and
+/// concurrent requests needing keys wait for it's result.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{error::Error, middleware::jwt::{Algorithm, JwkSet, Jwks, Jwt, Validation}};
+/// async fn fetch() -> Result<JwkSet, Error> {
+///     // fetch json web key set from remote endpoint with http client. an empty set is used here.
+///     Ok(JwkSet { keys: Vec::new() })
+/// }
+///
+/// # #[derive(Clone, serde::Deserialize)] struct User;
+/// let jwt = Jwt::<User, _>::new(Jwks::new(fetch), Validation::new(Algorithm::RS256));
+/// ```
+pub struct Jwks<F> {
+    inner: Arc<JwksInner<F>>,
+    max_age: Duration,
+}
+
+struct JwksInner<F> {
+    fetch: F,
+    state: Mutex<JwksState>,
+}
+
+struct JwksState {
+    keys: HashMap<String, DecodingKey>,
+    fetched_at: Option<Instant>,
+    // receiver of in flight fetch. it resolves when fetch is finished or cancelled.
+    fetching: Option<watch::Receiver<()>>,
+}
+
+enum Refetch<'a> {
+    // cached keys are used.
+    Skip,
+    // wait for in flight fetch and use it's result.
+    Wait(watch::Receiver<()>),
+    // fetch keys while other requests wait.
+    Fetch(FetchGuard<'a>),
+}
+
+// clear in flight state when fetch is finished or cancelled. sender is dropped afterwards and wakes up
+// waiting requests.
+struct FetchGuard<'a> {
+    state: &'a Mutex<JwksState>,
+    _tx: watch::Sender<()>,
+}
+
+impl Drop for FetchGuard<'_> {
+    fn drop(&mut self) {
+        self.state.lock().unwrap().fetching = None;
+    }
+}
+
+const JWKS_MAX_AGE: Duration = Duration::from_secs(300);
+const JWKS_MIN_REFETCH: Duration = Duration::from_secs(10);
+
+impl<F> Clone for Jwks<F> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            max_age: self.max_age,
+        }
+    }
+}
+
+impl<F> Jwks<F>
+where
+    F: AsyncFn<(), Output = Result<JwkSet, Error>>,
+{
+    /// construct a new JWKS key store with given async function fetching json web key set.
+    pub fn new(fetch: F) -> Self {
+        Self {
+            inner: Arc::new(JwksInner {
+                fetch,
+                state: Mutex::new(JwksState {
+                    keys: HashMap::new(),
+                    fetched_at: None,
+                    fetching: None,
+                }),
+            }),
+            max_age: JWKS_MAX_AGE,
+        }
+    }
+
+    /// max age of cached keys before they are fetched again. default to 5 minutes.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+}
+
+impl<F> KeyStore for Jwks<F>
+where
+    F: AsyncFn<(), Output = Result<JwkSet, Error>>,
+{
+    async fn decode<T>(&self, token: &str, header: &Header, validation: &Validation) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let kid = header.kid.as_deref().ok_or(JwtError::UnknownKey)?;
+
+        let refetch = {
+            let mut state = self.inner.state.lock().unwrap();
+            let refetch = match state.fetched_at {
+                Some(at) => {
+                    let elapsed = at.elapsed();
+                    elapsed >= self.max_age || (!state.keys.contains_key(kid) && elapsed >= JWKS_MIN_REFETCH)
+                }
+                None => true,
+            };
+
+            match state.fetching {
+                _ if !refetch => Refetch::Skip,
+                Some(ref rx) => Refetch::Wait(rx.clone()),
+                None => {
+                    let (tx, rx) = watch::channel(());
+                    state.fetching = Some(rx);
+                    Refetch::Fetch(FetchGuard {
+                        state: &self.inner.state,
+                        _tx: tx,
+                    })
+                }
+            }
+        };
+
+        match refetch {
+            Refetch::Skip => {}
+            // value is never sent and future resolves when fetch is finished or cancelled.
+            Refetch::Wait(mut rx) => {
+                let _ = rx.changed().await;
+            }
+            Refetch::Fetch(_guard) => self.fetch(kid).await?,
+        }
+
+        let state = self.inner.state.lock().unwrap();
+        let key = state.keys.get(kid).ok_or(JwtError::UnknownKey)?;
+        decode(token, key, validation)
+    }
+}
+
+impl<F> Jwks<F>
+where
+    F: AsyncFn<(), Output = Result<JwkSet, Error>>,
+{
+    async fn fetch(&self, kid: &str) -> Result<(), Error> {
+        let res = self.inner.fetch.call(()).await;
+
+        let mut state = self.inner.state.lock().unwrap();
+        // failed fetch is recorded as well so it's not retried by every request. previously
+        // cached keys stay in use until next fetch.
+        state.fetched_at = Some(Instant::now());
+
+        match res {
+            Ok(set) => {
+                state.keys = set
+                    .keys
+                    .iter()
+                    .filter_map(|jwk| {
+                        let kid = jwk.common.key_id.clone()?;
+                        DecodingKey::from_jwk(jwk).ok().map(|key| (kid, key))
+                    })
+                    .collect();
+                Ok(())
+            }
+            Err(e) if !state.keys.contains_key(kid) => Err(e),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+fn decode<T>(token: &str, key: &DecodingKey, validation: &Validation) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    jsonwebtoken::decode(token, key, validation)
+        .map(|data| data.claims)
+        .map_err(|e| Error::from(JwtError::Invalid(e)))
+}
+
+/// error type of json web token validation. produce 401 response with `WWW-Authenticate` header.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum JwtError {
+    /// request does not have bearer token in `Authorization` header.
+    Missing,
+    /// key referenced by token can't be found from [`KeyStore`].
+    UnknownKey,
+    /// token is malformed or failed validation.
+    Invalid(jsonwebtoken::errors::Error),
+}
+
+impl fmt::Display for JwtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => f.write_str("bearer token is missing"),
+            Self::UnknownKey => f.write_str("key of bearer token can't be found"),
+            Self::Invalid(e) => write!(f, "bearer token is invalid: {e}"),
+        }
+    }
+}
+
+impl error::Error for JwtError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Invalid(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<JwtError> for Error {
+    fn from(e: JwtError) -> Self {
+        Self::from_service(e)
+    }
+}
+
+impl<'r, C, B> Service<WebContext<'r, C, B>> for JwtError {
+    type Response = WebResponse;
+    type Error = core::convert::Infallible;
+
+    async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+        let mut res = StatusCode::UNAUTHORIZED.call(ctx).await?;
+        let challenge = match self {
+            Self::Missing => HeaderValue::from_static("Bearer"),
+            _ => HeaderValue::from_static("Bearer error=\"invalid_token\""),
+        };
+        res.headers_mut().insert(WWW_AUTHENTICATE, challenge);
+        Ok(res)
+    }
+}
+
+// extract bearer token from Authorization header. auth scheme is case insensitive.
+fn bearer_token(value: Option<&HeaderValue>) -> Option<&str> {
+    let value = value?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme.eq_ignore_ascii_case("bearer").then(|| token.trim())
+}
+
+mod service {
+    use crate::handler::auth::Claims;
+
+    use super::*;
+
+    pub struct JwtService<T, K, S> {
+        pub(super) service: S,
+        pub(super) keys: K,
+        pub(super) validation: Arc<Validation>,
+        pub(super) _claims: PhantomData<fn() -> T>,
+    }
+
+    impl<'r, C, B, T, K, S, Res> Service<WebContext<'r, C, B>> for JwtService<T, K, S>
+    where
+        T: DeserializeOwned + Clone + Send + Sync + 'static,
+        K: KeyStore,
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = Error>,
+    {
+        type Response = Res;
+        type Error = Error;
+
+        async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let token = bearer_token(ctx.req().headers().get(AUTHORIZATION)).ok_or(JwtError::Missing)?;
+            let header = jsonwebtoken::decode_header(token).map_err(JwtError::Invalid)?;
+            let claims = self.keys.decode::<T>(token, &header, &self.validation).await?;
+            ctx.req_mut().extensions_mut().insert(Claims(claims));
+            self.service.call(ctx).await
+        }
+    }
+
+    impl<T, K, S> crate::service::ready::ReadyService for JwtService<T, K, S>
+    where
+        S: crate::service::ready::ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use jsonwebtoken::{
+        jwk::{AlgorithmParameters, CommonParameters, Jwk, OctetKeyParameters},
+        EncodingKey,
+    };
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::RequestBody,
+        handler::{auth::Claims, handler_service},
+        http::{Request, RequestExt},
+        App,
+    };
+
+    use super::*;
+
+    #[derive(Clone, serde::Deserialize, serde::Serialize)]
+    struct User {
+        sub: String,
+        aud: String,
+        exp: u64,
+    }
+
+    // token signed with HS256 and "secret" key.
+    fn token(kid: Option<&str>, exp: u64) -> String {
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = kid.map(Into::into);
+        let user = User {
+            sub: "996".into(),
+            aud: "xitca".into(),
+            exp,
+        };
+        jsonwebtoken::encode(&header, &user, &EncodingKey::from_secret(b"secret")).unwrap()
+    }
+
+    fn validation(aud: &str) -> Validation {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_audience(&[aud]);
+        validation
+    }
+
+    fn request(auth: Option<&str>) -> Request<RequestExt<RequestBody>> {
+        let mut req = Request::new(RequestExt::<RequestBody>::default());
+        if let Some(auth) = auth {
+            req.headers_mut()
+                .insert(AUTHORIZATION, HeaderValue::from_str(auth).unwrap());
+        }
+        req
+    }
+
+    async fn handler(Claims(user): Claims<User>) -> String {
+        user.sub
+    }
+
+    #[test]
+    fn validate() {
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(Jwt::<User, _>::new(
+                DecodingKey::from_secret(b"secret"),
+                validation("xitca"),
+            ))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let valid = token(None, 4102444800);
+        let res = service
+            .call(request(Some(&format!("bearer {valid}"))))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let expired = token(None, 996);
+        for auth in [
+            None,
+            Some("Basic 996".to_owned()),
+            Some("Bearer abc".to_owned()),
+            Some(format!("Bearer {valid}x")),
+            Some(format!("Bearer {expired}")),
+        ] {
+            let res = service.call(request(auth.as_deref())).now_or_panic().unwrap();
+            assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+            assert!(res.headers().contains_key(WWW_AUTHENTICATE));
+        }
+
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(Jwt::<User, _>::new(
+                DecodingKey::from_secret(b"secret"),
+                validation("other"),
+            ))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service
+            .call(request(Some(&format!("Bearer {valid}"))))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn jwks() {
+        let set = JwkSet {
+            keys: vec![Jwk {
+                common: CommonParameters {
+                    key_id: Some("k1".into()),
+                    ..Default::default()
+                },
+                // base64 encoded "secret"
+                algorithm: AlgorithmParameters::OctetKey(OctetKeyParameters {
+                    key_type: Default::default(),
+                    value: "c2VjcmV0".into(),
+                }),
+            }],
+        };
+
+        let keys = Jwks::new(move || {
+            let set = set.clone();
+            async move { Ok(set) }
+        });
+
+        let token = token(Some("k1"), 4102444800);
+        let mut header = jsonwebtoken::decode_header(&token).unwrap();
+
+        let user = keys
+            .decode::<User>(&token, &header, &validation("xitca"))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(user.sub, "996");
+
+        header.kid = Some("k2".into());
+        let err = keys
+            .decode::<User>(&token, &header, &validation("xitca"))
+            .now_or_panic()
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err.upcast().downcast_ref::<JwtError>(),
+            Some(JwtError::UnknownKey)
+        ));
+    }
+
+    #[tokio::test]
+    async fn jwks_single_flight() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let keys = Jwks::new({
+            let count = count.clone();
+            move || {
+                let count = count.clone();
+                async move {
+                    count.fetch_add(1, Ordering::SeqCst);
+                    // yield so concurrent requests can observe in flight fetch.
+                    tokio::task::yield_now().await;
+                    Ok(JwkSet { keys: Vec::new() })
+                }
+            }
+        });
+
+        let token = token(Some("unknown"), 4102444800);
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        let validation = validation("xitca");
+
+        let res =
+            futures_util::future::join_all((0..8).map(|_| keys.decode::<User>(&token, &header, &validation))).await;
+        assert!(res.iter().all(Result::is_err));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        // unknown key is not fetched again within minimal refetch interval.
+        keys.decode::<User>(&token, &header, &validation).await.err().unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+}
@@ -121,11 +121,11 @@ impl<B: Default> Default for LimitBody<B> {
 }
 
 impl<B> LimitBody<B> {
-    const fn new(body: B, limit: usize) -> Self {
+    pub(crate) const fn new(body: B, limit: usize) -> Self {
         Self { limit, record: 0, body }
     }
 
-    fn into_inner(self) -> B {
+    pub(crate) fn into_inner(self) -> B {
         self.body
     }
 }
@@ -190,7 +190,7 @@ mod test {
 
         let mut ctx = WebContext::new_test(());
         let res = err.call(ctx.as_web_ctx()).await.unwrap();
-        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
 
         std::str::from_utf8(chunk.as_ref()).unwrap().to_string()
     }
@@ -0,0 +1,260 @@
+//! load shedding middleware.
+
+use core::{fmt, time::Duration};
+
+use std::error;
+
+use crate::service::Service;
+
+/// builder for middleware rejecting request with `503 Service Unavailable` response when the service
+/// it enclosed is not ready.
+///
+/// Readiness is observed through [`ReadyService::ready`] of enclosed service. A service under load
+/// (for example all connections of a database pool are in use) can stay pending in it's ready method
+/// and [`LoadShed`] would reject request with [`Overloaded`] error immediately instead of queueing it.
+/// The ready state is held until request is finished.
+///
+/// [`LoadShed`] is always ready itself. It should enclose service with meaningful readiness directly
+/// as route and router services are always ready and do not forward readiness of their inner services.
+///
+/// # Examples
+/// ```rust
+/// # use std::{sync::Arc, time::Duration};
+/// # use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+/// # use xitca_web::{
+/// #   error::Error,
+/// #   http::WebResponse,
+/// #   middleware::load_shed::LoadShed,
+/// #   service::{fn_build, ready::ReadyService, Service, ServiceExt},
+/// #   App, WebContext,
+/// # };
+/// // service handling limited number of requests concurrently.
+/// struct Limited(Arc<Semaphore>);
+///
+/// impl<'r, C, B> Service<WebContext<'r, C, B>> for Limited {
+///     type Response = WebResponse;
+///     type Error = Error;
+///
+///     async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+///         Ok(ctx.into_response("hello,world!"))
+///     }
+/// }
+///
+/// impl ReadyService for Limited {
+///     type Ready = OwnedSemaphorePermit;
+///
+///     // service is not ready when all permits are in use.
+///     async fn ready(&self) -> Self::Ready {
+///         self.0.clone().acquire_owned().await.unwrap()
+///     }
+/// }
+///
+/// App::new()
+///     .at(
+///         "/",
+///         fn_build(|_| async { Ok::<_, Error>(Limited(Arc::new(Semaphore::new(64)))) })
+///             // the 65th concurrent request would be rejected with 503 response.
+///             .enclosed(LoadShed::new().retry_after(Duration::from_secs(3))),
+///     )
+///     .finish();
+/// ```
+///
+/// [`ReadyService::ready`]: crate::service::ready::ReadyService::ready
+#[derive(Clone, Copy, Default)]
+pub struct LoadShed {
+    retry_after: Option<Duration>,
+}
+
+impl LoadShed {
+    pub const fn new() -> Self {
+        Self { retry_after: None }
+    }
+
+    /// Set `Retry-After` header value of rejected response. Duration is rounded down to seconds.
+    pub const fn retry_after(mut self, dur: Duration) -> Self {
+        self.retry_after = Some(dur);
+        self
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for LoadShed {
+    type Response = service::LoadShedService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::LoadShedService {
+            service,
+            retry_after: self.retry_after,
+        })
+    }
+}
+
+/// error type for request rejected by [`LoadShed`] middleware.
+#[derive(Debug)]
+pub struct Overloaded {
+    retry_after: Option<Duration>,
+}
+
+impl fmt::Display for Overloaded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("service is overloaded")
+    }
+}
+
+impl error::Error for Overloaded {}
+
+// rejection from xitca_service::middleware::ConcurrencyLimit with load shedding enabled.
+crate::error::error_from_service!(xitca_service::middleware::Overloaded);
+crate::error::blank_error_service!(
+    xitca_service::middleware::Overloaded,
+    crate::http::StatusCode::SERVICE_UNAVAILABLE
+);
+
+mod service {
+    use core::{
+        future::{poll_fn, Future},
+        pin::pin,
+        task::Poll,
+    };
+
+    use crate::{
+        body::ResponseBody,
+        error::Error,
+        http::{
+            header::{HeaderValue, RETRY_AFTER},
+            StatusCode, WebResponse,
+        },
+        service::ready::ReadyService,
+        WebContext,
+    };
+
+    use super::*;
+
+    pub struct LoadShedService<S> {
+        pub(super) service: S,
+        pub(super) retry_after: Option<Duration>,
+    }
+
+    impl<'r, C, B, S, ResB> Service<WebContext<'r, C, B>> for LoadShedService<S>
+    where
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = Error> + ReadyService,
+    {
+        type Response = WebResponse<ResB>;
+        type Error = Error;
+
+        async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let mut ready = pin!(self.service.ready());
+            match poll_fn(|cx| Poll::Ready(ready.as_mut().poll(cx))).await {
+                Poll::Ready(_ready) => self.service.call(ctx).await,
+                Poll::Pending => Err(Error::from_service(Overloaded {
+                    retry_after: self.retry_after,
+                })),
+            }
+        }
+    }
+
+    impl<S> ReadyService for LoadShedService<S> {
+        type Ready = ();
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {}
+    }
+
+    impl<'r, C, B> Service<WebContext<'r, C, B>> for Overloaded {
+        type Response = WebResponse;
+        type Error = core::convert::Infallible;
+
+        async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let mut res = ctx.into_response(ResponseBody::empty());
+            *res.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+            if let Some(dur) = self.retry_after {
+                res.headers_mut().insert(RETRY_AFTER, HeaderValue::from(dur.as_secs()));
+            }
+            Ok(res)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{cell::Cell, convert::Infallible, future::pending};
+
+    use std::rc::Rc;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use xitca_service::middleware::Overloaded as ConcurrencyOverloaded;
+
+    use crate::{
+        error::Error,
+        handler::handler_service,
+        http::{header::RETRY_AFTER, StatusCode, WebRequest, WebResponse},
+        service::ready::ReadyService,
+        App, WebContext,
+    };
+
+    use super::*;
+
+    // service being ready when inner boolean is true.
+    struct Gate(Rc<Cell<bool>>);
+
+    impl<'r, C, B> Service<WebContext<'r, C, B>> for Gate {
+        type Response = WebResponse;
+        type Error = Error;
+
+        async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            Ok(ctx.into_response("996"))
+        }
+    }
+
+    impl ReadyService for Gate {
+        type Ready = ();
+
+        async fn ready(&self) -> Self::Ready {
+            if !self.0.get() {
+                pending().await
+            }
+        }
+    }
+
+    #[test]
+    fn shed_on_not_ready() {
+        let open = Rc::new(Cell::new(true));
+
+        let service = LoadShed::new()
+            .retry_after(Duration::from_secs(3))
+            .call(Ok::<_, Infallible>(Gate(open.clone())))
+            .now_or_panic()
+            .unwrap();
+
+        let mut ctx = WebContext::new_test(());
+
+        let res = service.call(ctx.as_web_ctx()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        open.set(false);
+
+        let err = service.call(ctx.as_web_ctx()).now_or_panic().err().unwrap();
+        let res = err.call(ctx.as_web_ctx()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.headers().get(RETRY_AFTER).unwrap(), "3");
+
+        open.set(true);
+
+        let res = service.call(ctx.as_web_ctx()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn concurrency_limit_overloaded() {
+        let service = App::new()
+            .at("/", handler_service(|| async { Err::<&str, _>(ConcurrencyOverloaded) }))
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(WebRequest::default()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -5,6 +5,9 @@ use crate::service::Service;
 
 /// builder for tracing log middleware.
 ///
+/// By default response is logged when it's produced. [Logger::body_size] defers the logging until response
+/// body is fully sent and adds the count of body bytes to log record.
+///
 /// # Examples
 /// ```rust
 /// # use xitca_web::{handler::handler_service, middleware::Logger, route::get, App, WebContext};
@@ -13,9 +16,15 @@ use crate::service::Service;
 ///     # .at("/infer", handler_service(|_: &WebContext<'_>| async{ "infer type" }))
 ///     // log http request and error with default setting.
 ///     .enclosed(Logger::new());
+///
+/// App::new()
+///     .at("/", get(handler_service(|| async { "hello,world!" })))
+///     # .at("/infer", handler_service(|_: &WebContext<'_>| async{ "infer type" }))
+///     // log http request and response with count of body bytes sent.
+///     .enclosed(Logger::new().body_size());
 /// ```
-pub struct Logger {
-    logger: middleware::Logger,
+pub struct Logger<const BODY_SIZE: bool = false> {
+    logger: middleware::Logger<BODY_SIZE>,
 }
 
 impl Default for Logger {
@@ -43,16 +52,72 @@ impl Logger {
             logger: middleware::Logger::with_level(level),
         }
     }
+
+    /// defer logging of response until it's body is fully sent and log the count of body bytes with it.
+    ///
+    /// # Note:
+    /// response body type is changed to [LoggerBody](xitca_http::util::middleware::LoggerBody). when used
+    /// on a single route instead of [App](crate::App) the route's response type would be different from
+    /// other routes.
+    pub fn body_size(self) -> Logger<true> {
+        Logger {
+            logger: self.logger.body_size(),
+        }
+    }
 }
 
-impl<Arg> Service<Arg> for Logger
+impl<Arg, const BODY_SIZE: bool> Service<Arg> for Logger<BODY_SIZE>
 where
-    middleware::Logger: Service<Arg>,
+    middleware::Logger<BODY_SIZE>: Service<Arg>,
 {
-    type Response = <middleware::Logger as Service<Arg>>::Response;
-    type Error = <middleware::Logger as Service<Arg>>::Error;
+    type Response = <middleware::Logger<BODY_SIZE> as Service<Arg>>::Response;
+    type Error = <middleware::Logger<BODY_SIZE> as Service<Arg>>::Error;
 
     async fn call(&self, arg: Arg) -> Result<Self::Response, Self::Error> {
         self.logger.call(arg).await
     }
 }
+
+#[cfg(test)]
+mod test {
+    use core::{future::poll_fn, pin::pin};
+
+    use futures_core::stream::Stream;
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::RequestBody,
+        handler::handler_service,
+        http::{Request, RequestExt, StatusCode},
+        App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn body_size() {
+        let service = App::new()
+            .at("/", handler_service(|| async { "hello,world!" }))
+            .enclosed(Logger::new().body_size())
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let res = service
+            .call(Request::new(RequestExt::<RequestBody>::default()))
+            .now_or_panic()
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let mut body = pin!(res.into_body());
+        assert_eq!(body.size_hint(), (12, Some(12)));
+
+        let chunk = poll_fn(|cx| body.as_mut().poll_next(cx))
+            .now_or_panic()
+            .unwrap()
+            .unwrap();
+        assert_eq!(chunk, "hello,world!");
+        assert!(poll_fn(|cx| body.as_mut().poll_next(cx)).now_or_panic().is_none());
+    }
+}
@@ -0,0 +1,306 @@
+//! error to response mapping middleware.
+
+use core::{future::Future, marker::PhantomData};
+
+use std::error;
+
+use crate::{
+    error::Error,
+    service::{AsyncFn, Service},
+    WebContext,
+};
+
+/// builder for middleware mapping typed error to response.
+///
+/// Error type is registered with [`MapError::on`] method and an async function producing response
+/// from reference of the error. When enclosed service produces [`Error`] it's downcasted to each
+/// registered type and the function of matching type is called. When the same type is registered
+/// multiple times the last registration is used. Error with no matching type is passed to outer
+/// service untouched.
+///
+/// Registered types form a table resolved at compile time so adding more error types does not need a
+/// single error handler function matching on all of them. [`App::map_error`] is a shortcut for
+/// enclosing application with [`MapError`] with single error type registered.
+///
+/// # Examples
+/// ```rust
+/// # use std::{convert::Infallible, fmt};
+/// # use xitca_web::{
+/// #   error::Error,
+/// #   handler::handler_service,
+/// #   http::{StatusCode, WebResponse},
+/// #   middleware::map_error::MapError,
+/// #   service::Service,
+/// #   App, WebContext,
+/// # };
+/// // error types produced by handlers.
+/// #[derive(Debug)]
+/// struct NotFound;
+///
+/// #[derive(Debug)]
+/// struct Forbidden;
+///
+/// # impl fmt::Display for NotFound {
+/// #     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+/// #         f.write_str("not found")
+/// #     }
+/// # }
+/// # impl std::error::Error for NotFound {}
+/// # impl<'r, C> Service<WebContext<'r, C>> for NotFound {
+/// #     type Response = WebResponse;
+/// #     type Error = Infallible;
+/// #     async fn call(&self, ctx: WebContext<'r, C>) -> Result<Self::Response, Self::Error> {
+/// #         StatusCode::INTERNAL_SERVER_ERROR.call(ctx).await
+/// #     }
+/// # }
+/// # impl From<NotFound> for Error {
+/// #     fn from(e: NotFound) -> Self {
+/// #         Error::from_service(e)
+/// #     }
+/// # }
+/// # impl fmt::Display for Forbidden {
+/// #     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+/// #         f.write_str("forbidden")
+/// #     }
+/// # }
+/// # impl std::error::Error for Forbidden {}
+/// # impl<'r, C> Service<WebContext<'r, C>> for Forbidden {
+/// #     type Response = WebResponse;
+/// #     type Error = Infallible;
+/// #     async fn call(&self, ctx: WebContext<'r, C>) -> Result<Self::Response, Self::Error> {
+/// #         StatusCode::INTERNAL_SERVER_ERROR.call(ctx).await
+/// #     }
+/// # }
+/// # impl From<Forbidden> for Error {
+/// #     fn from(e: Forbidden) -> Self {
+/// #         Error::from_service(e)
+/// #     }
+/// # }
+/// async fn handler() -> Result<&'static str, NotFound> {
+///     Err(NotFound)
+/// }
+///
+/// // response mapping functions of each error type.
+/// async fn not_found(_: &NotFound, ctx: WebContext<'_>) -> WebResponse {
+///     ctx.into_response(StatusCode::NOT_FOUND.as_str())
+/// }
+///
+/// async fn forbidden(_: &Forbidden, ctx: WebContext<'_>) -> WebResponse {
+///     let mut res = ctx.into_response("forbidden");
+///     *res.status_mut() = StatusCode::FORBIDDEN;
+///     res
+/// }
+///
+/// App::new()
+///     .at("/", handler_service(handler))
+///     .enclosed(MapError::new().on::<NotFound, _>(not_found).on::<Forbidden, _>(forbidden))
+///     .finish();
+///
+/// // or register error type one by one on App.
+/// App::new()
+///     .at("/", handler_service(handler))
+///     .map_error::<NotFound, _>(not_found)
+///     .map_error::<Forbidden, _>(forbidden)
+///     .finish();
+/// ```
+///
+/// [`App::map_error`]: crate::App::map_error
+pub struct MapError<M> {
+    mappers: M,
+}
+
+impl MapError<()> {
+    /// construct a new middleware builder with no error type registered.
+    pub const fn new() -> Self {
+        Self { mappers: () }
+    }
+}
+
+impl Default for MapError<()> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M> MapError<M> {
+    /// register error type and async function producing response from it.
+    ///
+    /// Function receives reference of the error and [`WebContext`] of the request.
+    pub fn on<E, F>(self, func: F) -> MapError<(M, Mapper<E, F>)>
+    where
+        E: error::Error + 'static,
+    {
+        MapError {
+            mappers: (
+                self.mappers,
+                Mapper {
+                    func,
+                    _err: PhantomData,
+                },
+            ),
+        }
+    }
+}
+
+impl<M, S, E> Service<Result<S, E>> for MapError<M>
+where
+    M: Clone,
+{
+    type Response = service::MapErrorService<M, S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::MapErrorService {
+            service,
+            mappers: self.mappers.clone(),
+        })
+    }
+}
+
+// middleware with single error type registered.
+pub(crate) type MapErrorOne<E, F> = MapError<((), Mapper<E, F>)>;
+
+/// a registered error type and it's response mapping function.
+pub struct Mapper<E, F> {
+    func: F,
+    _err: PhantomData<fn(E)>,
+}
+
+impl<E, F> Clone for Mapper<E, F>
+where
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            func: self.func.clone(),
+            _err: PhantomData,
+        }
+    }
+}
+
+/// trait for table of registered error types. implemented by [`MapError`]'s type parameter.
+#[doc(hidden)]
+pub trait ErrorMap<C, B, Res> {
+    /// try to map error to response. return None when error type is not registered.
+    fn map<'r>(&'r self, err: &'r Error, ctx: WebContext<'r, C, B>) -> impl Future<Output = Option<Res>>;
+}
+
+impl<C, B, Res> ErrorMap<C, B, Res> for () {
+    #[inline]
+    fn map<'r>(&'r self, _: &'r Error, _: WebContext<'r, C, B>) -> impl Future<Output = Option<Res>> {
+        core::future::ready(None)
+    }
+}
+
+impl<M, E, F, C, B, Res> ErrorMap<C, B, Res> for (M, Mapper<E, F>)
+where
+    M: ErrorMap<C, B, Res>,
+    E: error::Error + 'static,
+    F: for<'r> AsyncFn<(&'r E, WebContext<'r, C, B>), Output = Res>,
+{
+    async fn map<'r>(&'r self, err: &'r Error, ctx: WebContext<'r, C, B>) -> Option<Res> {
+        match err.upcast().downcast_ref::<E>() {
+            Some(e) => Some(self.1.func.call((e, ctx)).await),
+            None => self.0.map(err, ctx).await,
+        }
+    }
+}
+
+mod service {
+    use super::*;
+
+    pub struct MapErrorService<M, S> {
+        pub(super) service: S,
+        pub(super) mappers: M,
+    }
+
+    impl<'r, C, B, M, S, Res> Service<WebContext<'r, C, B>> for MapErrorService<M, S>
+    where
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = Error>,
+        M: ErrorMap<C, B, Res>,
+    {
+        type Response = Res;
+        type Error = Error;
+
+        async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            match self.service.call(ctx.reborrow()).await {
+                Ok(res) => Ok(res),
+                Err(e) => match self.mappers.map(&e, ctx).await {
+                    Some(res) => Ok(res),
+                    None => Err(e),
+                },
+            }
+        }
+    }
+
+    impl<M, S> crate::service::ready::ReadyService for MapErrorService<M, S>
+    where
+        S: crate::service::ready::ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::http::{StatusCode, WebResponse};
+
+    use super::*;
+
+    // service always produce error. io error when inner boolean is true.
+    struct Fail(bool);
+
+    impl<'r, C, B> Service<WebContext<'r, C, B>> for Fail {
+        type Response = WebResponse;
+        type Error = Error;
+
+        async fn call(&self, _: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            if self.0 {
+                Err(Error::from(io::Error::from(io::ErrorKind::TimedOut)))
+            } else {
+                Err(Error::from(StatusCode::BAD_REQUEST))
+            }
+        }
+    }
+
+    async fn io_error(e: &io::Error, ctx: WebContext<'_>) -> WebResponse {
+        assert_eq!(e.kind(), io::ErrorKind::TimedOut);
+        let mut res = ctx.into_response("996");
+        *res.status_mut() = StatusCode::GATEWAY_TIMEOUT;
+        res
+    }
+
+    #[test]
+    fn map_registered_error() {
+        let mut ctx = WebContext::new_test(());
+
+        let service = MapError::new()
+            .on::<io::Error, _>(io_error)
+            .call(Ok::<_, Error>(Fail(true)))
+            .now_or_panic()
+            .unwrap();
+
+        let res = service.call(ctx.as_web_ctx()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::GATEWAY_TIMEOUT);
+
+        // error type not registered is passed through.
+        let service = MapError::new()
+            .on::<io::Error, _>(io_error)
+            .call(Ok::<_, Error>(Fail(false)))
+            .now_or_panic()
+            .unwrap();
+
+        let err = service.call(ctx.as_web_ctx()).now_or_panic().err().unwrap();
+        let res = err.call(ctx.as_web_ctx()).now_or_panic().unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
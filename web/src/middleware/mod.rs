@@ -297,15 +297,27 @@
 pub mod compress;
 #[cfg(any(feature = "compress-br", feature = "compress-gz", feature = "compress-de"))]
 pub mod decompress;
+#[cfg(feature = "jwt")]
+pub mod jwt;
 #[cfg(feature = "rate-limit")]
 pub mod rate_limit;
 #[cfg(not(target_family = "wasm"))]
 pub mod sync;
 #[cfg(feature = "tower-http-compat")]
 pub mod tower_http_compat;
+#[cfg(feature = "trace")]
+pub mod trace;
 
+pub mod cache;
+pub mod default_headers;
+pub mod early_data;
 pub mod eraser;
+pub mod idempotency;
 pub mod limit;
+pub mod load_shed;
+pub mod map_error;
+#[cfg(feature = "json")]
+pub mod problem;
 
 #[cfg(feature = "logger")]
 mod logger;
@@ -314,11 +326,16 @@ pub use logger::Logger;
 
 mod catch_unwind;
 mod context;
+mod inject;
 
 pub use catch_unwind::CatchUnwind;
 pub use context::WebContext;
+pub use inject::Inject;
 pub use xitca_http::util::middleware::Extension;
-pub use xitca_service::middleware::{AsyncFn, Group, UncheckedReady};
+pub use xitca_service::middleware::{
+    AsyncFn, Collector, ConcurrencyLimit, ConcurrencyLimitService, Group, Metered, MeteredService, Outcome,
+    UncheckedReady,
+};
 
 #[cfg(test)]
 mod test {
@@ -327,7 +344,7 @@ mod test {
 
     use crate::{
         handler::{extension::ExtensionRef, handler_service},
-        http::{Request, RequestExt},
+        http::{header::HOST, HeaderValue, Request, RequestExt},
         service::Service,
         test::collect_string_body,
         App,
@@ -357,4 +374,31 @@ mod test {
         let string = collect_string_body(body).now_or_panic().unwrap();
         assert_eq!(string, "hello");
     }
+
+    #[test]
+    fn inject() {
+        async fn root(ExtensionRef(ext): ExtensionRef<'_, String>) -> String {
+            ext.to_string()
+        }
+
+        let service = App::new()
+            .at("/", handler_service(root))
+            .enclosed(Inject::new(|ctx: &crate::WebContext<'_>| {
+                let host = ctx.req().headers().get(HOST).unwrap().to_str().unwrap();
+                host.split('.').next().unwrap().to_owned()
+            }))
+            .enclosed(UncheckedReady)
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        for (host, tenant) in [("foo.example.com", "foo"), ("bar.example.com", "bar")] {
+            let mut req = Request::new(RequestExt::<RequestBody>::default());
+            req.headers_mut().insert(HOST, HeaderValue::from_static(host));
+            let body = service.call(req).now_or_panic().unwrap().into_body();
+            let string = collect_string_body(body).now_or_panic().unwrap();
+            assert_eq!(string, tenant);
+        }
+    }
 }
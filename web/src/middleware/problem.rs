@@ -0,0 +1,256 @@
+//! problem details error middleware.
+
+use crate::service::Service;
+
+/// builder for middleware converting error produced by enclosed service to [`ProblemDetails`] and
+/// render it as `application/problem+json` response.
+///
+/// Status code of problem is the status code of response generated by the original error. For client
+/// error(`4xx`) the display message of original error is used as `detail` member. For other status
+/// codes `detail` member is omitted so internal error message is not exposed to client. Path of
+/// request uri is used as `instance` member. Headers of response generated by the original error (e.g.
+/// `Allow` of `405 Method Not Allowed`) are kept in problem response.
+///
+/// Converted error is still passed to outer service in `Result::Err` and original error is accessible
+/// through [`std::error::Error::source`] of [`ProblemDetails`]. Error already in the form of
+/// [`ProblemDetails`] is passed through untouched.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   error::Error,
+/// #   handler::handler_service,
+/// #   http::StatusCode,
+/// #   middleware::problem::ProblemJson,
+/// #   App, WebContext,
+/// # };
+/// async fn handler() -> Result<&'static str, Error> {
+///     // this error would be rendered as:
+///     // {"type":"about:blank","title":"Bad Request","status":400,"detail":"400 Bad Request","instance":"/"}
+///     Err(Error::from(StatusCode::BAD_REQUEST))
+/// }
+///
+/// App::new()
+///     .at("/", handler_service(handler))
+///     # .at("/nah", handler_service(|_: &WebContext<'_>| async { "used for infer type" }))
+///     .enclosed(ProblemJson)
+///     .finish();
+/// ```
+///
+/// [`ProblemDetails`]: crate::error::ProblemDetails
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ProblemJson;
+
+impl<S, E> Service<Result<S, E>> for ProblemJson {
+    type Response = service::ProblemJsonService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::ProblemJsonService { service })
+    }
+}
+
+mod service {
+    use core::{cell::RefCell, convert::Infallible};
+
+    use crate::{
+        body::RequestBody,
+        error::{Error, ProblemDetails},
+        http::header::{CONTENT_LENGTH, CONTENT_TYPE},
+        WebContext,
+    };
+
+    use super::*;
+
+    pub struct ProblemJsonService<S> {
+        pub(super) service: S,
+    }
+
+    impl<'r, C, B, S, Res> Service<WebContext<'r, C, B>> for ProblemJsonService<S>
+    where
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = Res, Error = Error>,
+    {
+        type Response = Res;
+        type Error = Error;
+
+        async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let err = match self.service.call(ctx.reborrow()).await {
+                Ok(res) => return Ok(res),
+                Err(e) => e,
+            };
+
+            if err.upcast().downcast_ref::<ProblemDetails>().is_some() {
+                return Err(err);
+            }
+
+            // response generating can take request head. collect instance path before it.
+            let instance = ctx.req().uri().path().to_owned();
+
+            // error is rendered with request head only. application state and request body are not
+            // needed for status code and headers of response.
+            let mut body = RefCell::new(RequestBody::None);
+            let res = err
+                .call(WebContext::new(ctx.req, &mut body, &()))
+                .await
+                .unwrap_or_else(|e: Infallible| match e {});
+            let status = res.status();
+
+            let mut problem = ProblemDetails::new(status).instance(instance);
+            if status.is_client_error() {
+                problem = problem.detail(err.to_string());
+            }
+
+            let (mut parts, _) = res.into_parts();
+            parts.headers.remove(CONTENT_TYPE);
+            parts.headers.remove(CONTENT_LENGTH);
+            *problem.headers_mut() = parts.headers;
+
+            Err(problem.source(err).into())
+        }
+    }
+
+    impl<S> crate::service::ready::ReadyService for ProblemJsonService<S>
+    where
+        S: crate::service::ready::ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error as _;
+
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::RequestBody,
+        error::{Error, ProblemDetails},
+        handler::handler_service,
+        http::{header::ALLOW, header::CONTENT_TYPE, Method, Request, RequestExt, StatusCode, Uri},
+        route::get,
+        service::{fn_service, ServiceExt},
+        test::collect_string_body,
+        App, WebContext,
+    };
+
+    use super::*;
+
+    #[test]
+    fn problem_json() {
+        async fn bad_request() -> Result<&'static str, Error> {
+            Err(Error::from(StatusCode::BAD_REQUEST))
+        }
+
+        async fn internal() -> Result<&'static str, Error> {
+            Err(Error::from(StatusCode::INTERNAL_SERVER_ERROR))
+        }
+
+        async fn problem() -> Result<&'static str, ProblemDetails> {
+            Err(ProblemDetails::new(StatusCode::CONFLICT).title("conflict"))
+        }
+
+        let service = App::new()
+            .at("/bad", handler_service(bad_request))
+            .at("/internal", handler_service(internal))
+            .at("/problem", handler_service(problem))
+            .at("/get", get(handler_service(bad_request)))
+            .enclosed(ProblemJson)
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let call_with = |method: Method, path: &'static str| {
+            let mut req = Request::new(RequestExt::<RequestBody>::default());
+            *req.method_mut() = method;
+            *req.uri_mut() = Uri::from_static(path);
+            let res = service.call(req).now_or_panic().unwrap();
+            let content_type = res.headers().get(CONTENT_TYPE).unwrap().clone();
+            assert_eq!(content_type, "application/problem+json");
+            let allow = res.headers().get(ALLOW).cloned();
+            let status = res.status();
+            (
+                status,
+                allow,
+                collect_string_body(res.into_body()).now_or_panic().unwrap(),
+            )
+        };
+
+        let call = |path: &'static str| {
+            let (status, _, body) = call_with(Method::GET, path);
+            (status, body)
+        };
+
+        let (status, body) = call("/bad");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body,
+            r#"{"type":"about:blank","title":"Bad Request","status":400,"detail":"400 Bad Request","instance":"/bad"}"#
+        );
+
+        let (status, body) = call("/internal");
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            body,
+            r#"{"type":"about:blank","title":"Internal Server Error","status":500,"instance":"/internal"}"#
+        );
+
+        let (status, body) = call("/problem");
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body, r#"{"type":"about:blank","title":"conflict","status":409}"#);
+
+        let (status, _) = call("/not_found");
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // headers of response rendered from original error are kept.
+        let (status, allow, _) = call_with(Method::POST, "/get");
+        assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(allow.unwrap(), "GET");
+    }
+
+    #[test]
+    fn custom_context() {
+        // non 'static application state and request body type other than default RequestBody.
+        async fn handler(_: WebContext<'_, &String, ()>) -> Result<&'static str, Error> {
+            Err(Error::from(StatusCode::BAD_REQUEST))
+        }
+
+        let service = fn_service(handler)
+            .enclosed(ProblemJson)
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let state = String::from("state");
+        let mut req = Default::default();
+        let mut body = core::cell::RefCell::new(());
+        let err = service
+            .call(WebContext::new(&mut req, &mut body, &&state))
+            .now_or_panic()
+            .err()
+            .unwrap();
+        let problem = err.upcast().downcast_ref::<ProblemDetails>().unwrap();
+        assert_eq!(problem.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn source() {
+        let service = handler_service(|| async { Err::<&str, _>(Error::from(StatusCode::BAD_REQUEST)) })
+            .enclosed(ProblemJson)
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let mut ctx = WebContext::new_test(());
+        let err = service.call(ctx.as_web_ctx()).now_or_panic().err().unwrap();
+        let problem = err.upcast().downcast_ref::<ProblemDetails>().unwrap();
+        assert_eq!(problem.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(problem.source().unwrap().to_string(), "400 Bad Request");
+    }
+}
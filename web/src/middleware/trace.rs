@@ -0,0 +1,202 @@
+//! distributed tracing middleware with [W3C trace context](https://www.w3.org/TR/trace-context/)
+//! propagation.
+
+use crate::service::Service;
+
+pub use xitca_http::util::trace_context::{TraceContext, TRACEPARENT, TRACESTATE};
+
+/// builder for middleware creating a [tracing] span for each request.
+///
+/// Trace context of request is parsed from it's `traceparent` and `tracestate` headers. Request
+/// without valid `traceparent` header starts a new trace. Span of request is a child of the parsed
+/// context and it records following fields:
+/// - `trace_id`, `span_id` and `parent_span_id` of trace context in lowercase hex.
+/// - `http.request.method`, `url.path` and `http.route` (when set by [`Trace::route`]) of request.
+/// - `http.response.status_code` of response or `error` message when enclosed service fails.
+///
+/// Context of the span is inserted into request's extensions and it's the current context
+/// ([`TraceContext::current`]) while enclosed service is running. Http client used by service can
+/// propagate it to outgoing requests. (xitca-client's `middleware::Trace` for example)
+///
+/// Collecting and exporting span is up to the `tracing` subscriber set by user.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{
+/// #   handler::{extension::ExtensionRef, handler_service},
+/// #   middleware::trace::{Trace, TraceContext},
+/// #   route::get,
+/// #   service::ServiceExt,
+/// #   App, WebContext,
+/// # };
+/// async fn handler(ExtensionRef(ctx): ExtensionRef<'_, TraceContext>) -> String {
+///     format!("{:032x}", ctx.trace_id())
+/// }
+///
+/// App::new()
+///     // route pattern can be recorded when middleware is applied to a route.
+///     .at("/users/:id", get(handler_service(handler).enclosed(Trace::new().route("/users/:id"))))
+///     # .at("/nah", handler_service(|_: &WebContext<'_>| async { "used for infer type" }))
+///     .finish();
+///
+/// App::new()
+///     .at("/", get(handler_service(handler)))
+///     # .at("/nah", handler_service(|_: &WebContext<'_>| async { "used for infer type" }))
+///     // apply middleware to all routes.
+///     .enclosed(Trace::new())
+///     .finish();
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Trace {
+    route: Option<&'static str>,
+}
+
+impl Trace {
+    pub const fn new() -> Self {
+        Self { route: None }
+    }
+
+    /// record given route pattern as `http.route` field of span. middleware applied to
+    /// [`App`](crate::App) does not know the route matched by request and it's only useful when
+    /// middleware is applied to a route.
+    pub const fn route(mut self, route: &'static str) -> Self {
+        self.route = Some(route);
+        self
+    }
+}
+
+impl<S, E> Service<Result<S, E>> for Trace {
+    type Response = service::TraceService<S>;
+    type Error = E;
+
+    async fn call(&self, res: Result<S, E>) -> Result<Self::Response, Self::Error> {
+        res.map(|service| service::TraceService {
+            service,
+            route: self.route,
+        })
+    }
+}
+
+mod service {
+    use tracing::{field::Empty, info_span, Instrument};
+
+    use crate::{error::Error, http::WebResponse, WebContext};
+
+    use super::*;
+
+    pub struct TraceService<S> {
+        pub(super) service: S,
+        pub(super) route: Option<&'static str>,
+    }
+
+    impl<'r, C, B, S, ResB> Service<WebContext<'r, C, B>> for TraceService<S>
+    where
+        S: for<'r2> Service<WebContext<'r2, C, B>, Response = WebResponse<ResB>, Error = Error>,
+    {
+        type Response = WebResponse<ResB>;
+        type Error = Error;
+
+        async fn call(&self, mut ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
+            let parent = TraceContext::from_headers(ctx.req().headers());
+            let trace = match parent {
+                Some(ref parent) => parent.child(),
+                None => TraceContext::new_root(),
+            };
+
+            let span = info_span!(
+                "request",
+                otel.kind = "server",
+                trace_id = %format_args!("{:032x}", trace.trace_id()),
+                span_id = %format_args!("{:016x}", trace.span_id()),
+                parent_span_id = Empty,
+                http.request.method = %ctx.req().method(),
+                url.path = ctx.req().uri().path(),
+                http.route = self.route,
+                http.response.status_code = Empty,
+                error = Empty,
+            );
+
+            if let Some(parent) = parent {
+                span.record("parent_span_id", format_args!("{:016x}", parent.span_id()));
+            }
+
+            ctx.req_mut().extensions_mut().insert(trace.clone());
+
+            let res = trace.scope(self.service.call(ctx)).instrument(span.clone()).await;
+
+            match res {
+                Ok(ref res) => span.record("http.response.status_code", res.status().as_u16()),
+                Err(ref e) => span.record("error", tracing::field::display(e)),
+            };
+
+            res
+        }
+    }
+
+    impl<S> crate::service::ready::ReadyService for TraceService<S>
+    where
+        S: crate::service::ready::ReadyService,
+    {
+        type Ready = S::Ready;
+
+        #[inline]
+        async fn ready(&self) -> Self::Ready {
+            self.service.ready().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use xitca_unsafe_collection::futures::NowOrPanic;
+
+    use crate::{
+        body::RequestBody,
+        handler::{extension::ExtensionRef, handler_service},
+        http::{header::HeaderValue, Request, RequestExt},
+        service::Service,
+        test::collect_string_body,
+        App,
+    };
+
+    use super::*;
+
+    #[test]
+    fn trace() {
+        async fn handler(ExtensionRef(ctx): ExtensionRef<'_, TraceContext>) -> String {
+            // context in extensions is the current context.
+            assert_eq!(TraceContext::current().as_ref(), Some(ctx));
+            format!("{:032x}-{:016x}", ctx.trace_id(), ctx.span_id())
+        }
+
+        let service = App::new()
+            .at("/", handler_service(handler))
+            .enclosed(Trace::new())
+            .finish()
+            .call(())
+            .now_or_panic()
+            .unwrap();
+
+        let mut req = Request::new(RequestExt::<RequestBody>::default());
+        req.headers_mut().insert(
+            TRACEPARENT,
+            HeaderValue::from_static("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"),
+        );
+        let res = service.call(req).now_or_panic().unwrap();
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        let (trace_id, span_id) = body.split_once('-').unwrap();
+        // span of request is a child of incoming context.
+        assert_eq!(trace_id, "0af7651916cd43dd8448eb211c80319c");
+        assert_ne!(span_id, "b7ad6b7169203331");
+
+        // request without trace context starts a new trace.
+        let res = service
+            .call(Request::new(RequestExt::<RequestBody>::default()))
+            .now_or_panic()
+            .unwrap();
+        let body = collect_string_body(res.into_body()).now_or_panic().unwrap();
+        assert!(!body.starts_with("0af7651916cd43dd8448eb211c80319c"));
+
+        assert!(TraceContext::current().is_none());
+    }
+}
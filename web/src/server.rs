@@ -6,7 +6,7 @@ use xitca_http::{
     config::{HttpServiceConfig, DEFAULT_HEADER_LIMIT, DEFAULT_READ_BUF_LIMIT, DEFAULT_WRITE_BUF_LIMIT},
     HttpServiceBuilder,
 };
-use xitca_server::{Builder, ServerFuture};
+use xitca_server::{Builder, ServerFuture, ShutdownController};
 use xitca_service::ServiceExt;
 
 use crate::{
@@ -84,11 +84,62 @@ where
         self
     }
 
+    /// Timeout for graceful shutdown of workers in seconds.
+    ///
+    /// See [Builder::shutdown_timeout](xitca_server::Builder::shutdown_timeout) for detail.
+    pub fn shutdown_timeout(mut self, secs: u64) -> Self {
+        self.builder = self.builder.shutdown_timeout(secs);
+        self
+    }
+
+    /// Register server with given [ShutdownController] so multiple servers in one process can be stopped
+    /// together.
+    ///
+    /// See [Builder::shutdown_controller](xitca_server::Builder::shutdown_controller) for detail.
+    pub fn shutdown_controller(mut self, controller: &ShutdownController) -> Self {
+        self.builder = self.builder.shutdown_controller(controller);
+        self
+    }
+
     pub fn backlog(mut self, num: u32) -> Self {
         self.builder = self.builder.backlog(num);
         self
     }
 
+    /// Set `TCP_NODELAY` option on every accepted Tcp connection.
+    ///
+    /// See [Builder::tcp_nodelay](xitca_server::Builder::tcp_nodelay) for detail.
+    pub fn tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.builder = self.builder.tcp_nodelay(nodelay);
+        self
+    }
+
+    /// Enable `SO_KEEPALIVE` option with given idle time on every accepted Tcp connection.
+    ///
+    /// See [Builder::tcp_keepalive](xitca_server::Builder::tcp_keepalive) for detail.
+    pub fn tcp_keepalive(mut self, time: Option<Duration>) -> Self {
+        self.builder = self.builder.tcp_keepalive(time);
+        self
+    }
+
+    /// Expect PROXY protocol (v1 or v2) header at the start of every accepted Tcp connection. Client address
+    /// carried by header would be observed by application instead of the address of proxy.
+    ///
+    /// See [Builder::proxy_protocol](xitca_server::Builder::proxy_protocol) for detail.
+    pub fn proxy_protocol(mut self, enable: bool) -> Self {
+        self.builder = self.builder.proxy_protocol(enable);
+        self
+    }
+
+    #[cfg(feature = "io-uring")]
+    /// Fallback to tokio runtime when io_uring is not available at runtime.
+    ///
+    /// See [Builder::io_uring_with_fallback](xitca_server::Builder::io_uring_with_fallback) for detail.
+    pub fn io_uring_with_fallback(mut self) -> Self {
+        self.builder = self.builder.io_uring_with_fallback();
+        self
+    }
+
     /// Disable vectored write even when IO is able to perform it.
     ///
     /// This is beneficial when dealing with small size of response body.
@@ -97,6 +148,28 @@ where
         self
     }
 
+    /// Write header names of Http/1 response in title case.
+    ///
+    /// See [HttpServiceConfig::title_case_headers] for detail.
+    pub fn title_case_headers(mut self) -> Self {
+        self.config = self.config.title_case_headers();
+        self
+    }
+
+    /// Serve Http/2 over plain Tcp connection with prior knowledge (h2c) along side Http/1 on the
+    /// same address.
+    ///
+    /// Protocol of connection is decided by peeking into it's first bytes for Http/2 connection
+    /// preface. It also applies to tls connection where `h2` is not negotiated by ALPN. It applies
+    /// to all bound addresses. Use [HttpServer::bind_h2c] for enabling it on a single address.
+    ///
+    /// See [HttpServiceConfig::peek_protocol] for detail.
+    #[cfg(feature = "http2")]
+    pub fn h2c(mut self) -> Self {
+        self.config = self.config.peek_protocol();
+        self
+    }
+
     /// Change keep alive duration for Http/1 connection.
     ///
     /// Connection kept idle for this duration would be closed.
@@ -115,6 +188,17 @@ where
         self
     }
 
+    /// Change request body timeout for Http/1 and Http/2 connection.
+    ///
+    /// Request with it's body idle between chunks for this duration before response is produced would
+    /// be aborted with `408 Request Timeout` response.
+    ///
+    /// See [HttpServiceConfig::request_body_timeout] for detail.
+    pub fn request_body_timeout(mut self, dur: Duration) -> Self {
+        self.config = self.config.request_body_timeout(dur);
+        self
+    }
+
     /// Change tls accept timeout for Http/1 and Http/2 connection.
     ///
     /// Connection can not finish tls handshake for this duration would be closed.
@@ -179,11 +263,51 @@ where
         BE: fmt::Debug + 'static,
     {
         let config = self.config;
-        let service = self.service.clone().enclosed(HttpServiceBuilder::with_config(config));
+        let service = self
+            .service
+            .clone()
+            .enclosed(HttpServiceBuilder::with_config(config).draining_state(self.builder.draining_state()));
         self.builder = self.builder.bind("xitca-web", addr, service)?;
         Ok(self)
     }
 
+    /// Bind to Tcp address and serve Http/2 over plain Tcp (h2c) along side Http/1.
+    ///
+    /// Connection starting with Http/2 connection preface is served as Http/2 with prior knowledge.
+    /// Http/1 connection can be upgraded to Http/2 with `Upgrade: h2c` header on it's first request
+    /// when the request has no body. Other connections are served as Http/1.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// # use xitca_web::{handler::handler_service, App, HttpServer};
+    /// # fn run() -> std::io::Result<()> {
+    /// HttpServer::serve(App::new().at("/", handler_service(|| async { "hello" })).finish())
+    ///     // internal traffic without tls.
+    ///     .bind_h2c("127.0.0.1:8080")?
+    ///     .run()
+    ///     .wait()
+    /// # }
+    /// ```
+    #[cfg(all(feature = "http2", not(target_family = "wasm")))]
+    pub fn bind_h2c<A, ResB, BE>(mut self, addr: A) -> std::io::Result<Self>
+    where
+        A: std::net::ToSocketAddrs,
+        S: Service + 'static,
+        S::Response: ReadyService + Service<Request<RequestExt<RequestBody>>, Response = Response<ResB>> + 'static,
+        S::Error: fmt::Debug,
+        <S::Response as Service<Request<RequestExt<RequestBody>>>>::Error: fmt::Debug,
+        ResB: Stream<Item = Result<Bytes, BE>> + 'static,
+        BE: fmt::Debug + 'static,
+    {
+        let config = self.config.peek_protocol();
+        let service = self
+            .service
+            .clone()
+            .enclosed(HttpServiceBuilder::with_config(config).draining_state(self.builder.draining_state()));
+        self.builder = self.builder.bind("xitca-web-h2c", addr, service)?;
+        Ok(self)
+    }
+
     pub fn listen<ResB, BE>(mut self, listener: std::net::TcpListener) -> std::io::Result<Self>
     where
         S: Service + 'static,
@@ -195,16 +319,24 @@ where
         BE: fmt::Debug + 'static,
     {
         let config = self.config;
-        let service = self.service.clone().enclosed(HttpServiceBuilder::with_config(config));
+        let service = self
+            .service
+            .clone()
+            .enclosed(HttpServiceBuilder::with_config(config).draining_state(self.builder.draining_state()));
         self.builder = self.builder.listen("xitca-web", listener, service);
         Ok(self)
     }
 
+    /// Bind to Tcp address and serve Http/1 and/or Http/2 over tls with given openssl acceptor
+    /// builder.
+    ///
+    /// ALPN protocols are set according to enabled http version features. `h2` is preferred over
+    /// `http/1.1` when `http2` feature is enabled.
     #[cfg(feature = "openssl")]
     pub fn bind_openssl<A: std::net::ToSocketAddrs, ResB, BE>(
         mut self,
         addr: A,
-        mut builder: xitca_tls::openssl::ssl::SslAcceptorBuilder,
+        builder: xitca_tls::openssl::ssl::SslAcceptorBuilder,
     ) -> std::io::Result<Self>
     where
         S: Service + 'static,
@@ -215,53 +347,73 @@ where
         ResB: Stream<Item = Result<Bytes, BE>> + 'static,
         BE: fmt::Debug + 'static,
     {
-        let config = self.config;
+        let acceptor = openssl_acceptor(builder)?;
 
-        const H11: &[u8] = b"\x08http/1.1";
-
-        const H2: &[u8] = b"\x02h2";
-
-        builder.set_alpn_select_callback(|_, protocols| {
-            if protocols.windows(3).any(|window| window == H2) {
-                #[cfg(feature = "http2")]
-                {
-                    Ok(b"h2")
-                }
-                #[cfg(not(feature = "http2"))]
-                Err(xitca_tls::openssl::ssl::AlpnError::ALERT_FATAL)
-            } else if protocols.windows(9).any(|window| window == H11) {
-                Ok(b"http/1.1")
-            } else {
-                Err(xitca_tls::openssl::ssl::AlpnError::NOACK)
-            }
-        });
+        let service = self.service.clone().enclosed(
+            HttpServiceBuilder::with_config(self.config)
+                .draining_state(self.builder.draining_state())
+                .openssl(acceptor),
+        );
 
-        #[cfg(not(feature = "http2"))]
-        let protos = H11.iter().cloned().collect::<Vec<_>>();
+        self.builder = self.builder.bind("xitca-web-openssl", addr, service)?;
 
-        #[cfg(feature = "http2")]
-        let protos = H11.iter().chain(H2).cloned().collect::<Vec<_>>();
+        Ok(self)
+    }
 
-        builder.set_alpn_protos(&protos)?;
+    /// Serve Http/1 and/or Http/2 over tls on given Tcp listener with given openssl acceptor builder.
+    ///
+    /// See [HttpServer::bind_openssl] for detail.
+    #[cfg(feature = "openssl")]
+    pub fn listen_openssl<ResB, BE>(
+        mut self,
+        listener: std::net::TcpListener,
+        builder: xitca_tls::openssl::ssl::SslAcceptorBuilder,
+    ) -> std::io::Result<Self>
+    where
+        S: Service + 'static,
+        S::Response: ReadyService + Service<Request<RequestExt<RequestBody>>, Response = Response<ResB>> + 'static,
+        S::Error: fmt::Debug,
+        <S::Response as Service<Request<RequestExt<RequestBody>>>>::Error: fmt::Debug,
 
-        let acceptor = builder.build();
+        ResB: Stream<Item = Result<Bytes, BE>> + 'static,
+        BE: fmt::Debug + 'static,
+    {
+        let acceptor = openssl_acceptor(builder)?;
 
-        let service = self
-            .service
-            .clone()
-            .enclosed(HttpServiceBuilder::with_config(config).openssl(acceptor));
+        let service = self.service.clone().enclosed(
+            HttpServiceBuilder::with_config(self.config)
+                .draining_state(self.builder.draining_state())
+                .openssl(acceptor),
+        );
 
-        self.builder = self.builder.bind("xitca-web-openssl", addr, service)?;
+        self.builder = self.builder.listen("xitca-web-openssl", listener, service);
 
         Ok(self)
     }
 
+    /// Bind to Tcp address and serve Http/1 and/or Http/2 over tls with given rustls server config.
+    ///
+    /// ALPN protocols are appended to config according to enabled `http1` and `http2` features. `h2`
+    /// is preferred over `http/1.1` when both are enabled. Protocols already present in config
+    /// are not duplicated. Every connection is dispatched to Http/1 or Http/2 according to it's
+    /// negotiated protocol so both are served on the same address.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// # use xitca_web::{handler::handler_service, App, HttpServer};
+    /// # fn run(config: xitca_tls::rustls::ServerConfig) -> std::io::Result<()> {
+    /// HttpServer::serve(App::new().at("/", handler_service(|| async { "hello" })).finish())
+    ///     // Http/2 for client negotiated h2 and Http/1 for others.
+    ///     .bind_rustls("0.0.0.0:443", config)?
+    ///     .run()
+    ///     .wait()
+    /// # }
+    /// ```
     #[cfg(feature = "rustls")]
     pub fn bind_rustls<A: std::net::ToSocketAddrs, ResB, BE>(
         mut self,
         addr: A,
-        #[cfg_attr(not(all(feature = "http1", feature = "http2")), allow(unused_mut))]
-        mut config: xitca_tls::rustls::ServerConfig,
+        config: xitca_tls::rustls::ServerConfig,
     ) -> std::io::Result<Self>
     where
         S: Service + 'static,
@@ -272,23 +424,104 @@ where
         ResB: Stream<Item = Result<Bytes, BE>> + 'static,
         BE: fmt::Debug + 'static,
     {
-        let service_config = self.config;
+        let service = self.service.clone().enclosed(
+            HttpServiceBuilder::with_config(self.config)
+                .draining_state(self.builder.draining_state())
+                .rustls(rustls_config(config)),
+        );
 
-        #[cfg(feature = "http2")]
-        config.alpn_protocols.push("h2".into());
+        self.builder = self.builder.bind("xitca-web-rustls", addr, service)?;
 
-        #[cfg(feature = "http1")]
-        config.alpn_protocols.push("http/1.1".into());
+        Ok(self)
+    }
+
+    /// Serve Http/1 and/or Http/2 over tls on given Tcp listener with given rustls server config.
+    ///
+    /// See [HttpServer::bind_rustls] for detail.
+    #[cfg(feature = "rustls")]
+    pub fn listen_rustls<ResB, BE>(
+        mut self,
+        listener: std::net::TcpListener,
+        config: xitca_tls::rustls::ServerConfig,
+    ) -> std::io::Result<Self>
+    where
+        S: Service + 'static,
+        S::Response: ReadyService + Service<Request<RequestExt<RequestBody>>, Response = Response<ResB>> + 'static,
+        S::Error: fmt::Debug,
+        <S::Response as Service<Request<RequestExt<RequestBody>>>>::Error: fmt::Debug,
 
-        let config = std::sync::Arc::new(config);
+        ResB: Stream<Item = Result<Bytes, BE>> + 'static,
+        BE: fmt::Debug + 'static,
+    {
+        let service = self.service.clone().enclosed(
+            HttpServiceBuilder::with_config(self.config)
+                .draining_state(self.builder.draining_state())
+                .rustls(rustls_config(config)),
+        );
+
+        self.builder = self.builder.listen("xitca-web-rustls", listener, service);
+
+        Ok(self)
+    }
 
+    /// Bind to Udp address and serve Http/3 over QUIC with given quinn server config.
+    ///
+    /// Config must carry tls certificate and have `h3` in it's alpn protocols. Browsers would not
+    /// use Http/3 service until they are told by `Alt-Svc` response header from Http/1 or Http/2
+    /// service. Typically the same address is bound with both `bind_rustls` (or `bind_openssl`) and
+    /// `bind_h3`:
+    ///
+    /// ```rust,no_run
+    /// # use xitca_web::{
+    /// #   handler::handler_service,
+    /// #   http::{header::{HeaderValue, ALT_SVC}, WebResponse},
+    /// #   service::Service,
+    /// #   App, HttpServer, WebContext,
+    /// # };
+    /// # fn run(quic: xitca_io::net::QuicConfig) -> std::io::Result<()> {
+    /// // advertise Http/3 service on port 443 to clients for 24 hours.
+    /// async fn alt_svc<S, C, B, Err>(next: &S, ctx: WebContext<'_, C, B>) -> Result<WebResponse, Err>
+    /// where
+    ///     S: for<'r> Service<WebContext<'r, C, B>, Response = WebResponse, Error = Err>,
+    /// {
+    ///     let mut res = next.call(ctx).await?;
+    ///     res.headers_mut().insert(ALT_SVC, HeaderValue::from_static("h3=\":443\"; ma=86400"));
+    ///     Ok(res)
+    /// }
+    ///
+    /// let app = App::new()
+    ///     .at("/", handler_service(|| async { "hello" }))
+    ///     .enclosed_fn(alt_svc);
+    ///
+    /// HttpServer::serve(app.finish())
+    ///     // Http/1 and Http/2 over tls. alt-svc header is sent from here.
+    ///     // .bind_rustls("0.0.0.0:443", tls_config)?
+    ///     .bind_h3("0.0.0.0:443", quic)?
+    ///     .run()
+    ///     .wait()
+    /// # }
+    /// ```
+    #[cfg(feature = "http3")]
+    pub fn bind_h3<A: std::net::ToSocketAddrs, ResB, BE>(
+        mut self,
+        addr: A,
+        config: xitca_io::net::QuicConfig,
+    ) -> std::io::Result<Self>
+    where
+        S: Service + 'static,
+        S::Response: ReadyService + Service<Request<RequestExt<RequestBody>>, Response = Response<ResB>> + 'static,
+        S::Error: fmt::Debug,
+        <S::Response as Service<Request<RequestExt<RequestBody>>>>::Error: fmt::Debug,
+
+        ResB: Stream<Item = Result<Bytes, BE>> + 'static,
+        BE: fmt::Debug + 'static,
+    {
+        let service_config = self.config;
         let service = self
             .service
             .clone()
-            .enclosed(HttpServiceBuilder::with_config(service_config).rustls(config));
-
-        self.builder = self.builder.bind("xitca-web-rustls", addr, service)?;
-
+            .enclosed(HttpServiceBuilder::with_config(service_config).draining_state(self.builder.draining_state()));
+        self.builder = self.builder.bind_h3("xitca-web-h3", addr, config, service)?;
         Ok(self)
     }
 
@@ -304,7 +537,10 @@ where
         BE: fmt::Debug + 'static,
     {
         let config = self.config;
-        let service = self.service.clone().enclosed(HttpServiceBuilder::with_config(config));
+        let service = self
+            .service
+            .clone()
+            .enclosed(HttpServiceBuilder::with_config(config).draining_state(self.builder.draining_state()));
         self.builder = self.builder.bind_unix("xitca-web", path, service)?;
         Ok(self)
     }
@@ -325,3 +561,55 @@ where
         }
     }
 }
+
+#[cfg(feature = "openssl")]
+fn openssl_acceptor(
+    mut builder: xitca_tls::openssl::ssl::SslAcceptorBuilder,
+) -> std::io::Result<xitca_tls::openssl::ssl::SslAcceptor> {
+    const H11: &[u8] = b"\x08http/1.1";
+
+    const H2: &[u8] = b"\x02h2";
+
+    builder.set_alpn_select_callback(|_, protocols| {
+        if protocols.windows(3).any(|window| window == H2) {
+            #[cfg(feature = "http2")]
+            {
+                Ok(b"h2")
+            }
+            #[cfg(not(feature = "http2"))]
+            Err(xitca_tls::openssl::ssl::AlpnError::ALERT_FATAL)
+        } else if protocols.windows(9).any(|window| window == H11) {
+            Ok(b"http/1.1")
+        } else {
+            Err(xitca_tls::openssl::ssl::AlpnError::NOACK)
+        }
+    });
+
+    #[cfg(not(feature = "http2"))]
+    let protos = H11.iter().cloned().collect::<Vec<_>>();
+
+    #[cfg(feature = "http2")]
+    let protos = H11.iter().chain(H2).cloned().collect::<Vec<_>>();
+
+    builder.set_alpn_protos(&protos)?;
+
+    Ok(builder.build())
+}
+
+#[cfg(feature = "rustls")]
+fn rustls_config(mut config: xitca_tls::rustls::ServerConfig) -> Arc<xitca_tls::rustls::ServerConfig> {
+    let protos = [
+        #[cfg(feature = "http2")]
+        &b"h2"[..],
+        #[cfg(feature = "http1")]
+        &b"http/1.1"[..],
+    ];
+
+    for proto in protos {
+        if !config.alpn_protocols.iter().any(|p| p == proto) {
+            config.alpn_protocols.push(proto.to_vec());
+        }
+    }
+
+    Arc::new(config)
+}
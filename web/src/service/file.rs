@@ -104,8 +104,8 @@ mod service {
     use crate::{
         body::ResponseBody,
         context::WebContext,
-        error::{Error, ErrorStatus, MatchError, MethodNotAllowed, RouterError},
-        http::{Method, StatusCode, WebResponse},
+        error::{Error, MatchError, MethodNotAllowed, RouterError},
+        http::{Method, WebResponse},
         service::Service,
     };
 
@@ -122,19 +122,21 @@ mod service {
         async fn call(&self, ctx: WebContext<'r, C, B>) -> Result<Self::Response, Self::Error> {
             match self.0.serve(ctx.req()).await {
                 Ok(res) => Ok(res.map(ResponseBody::box_stream)),
-                Err(ServeError::NotModified) => {
+                // not found and method not allowed errors are forwarded to router so they can be
+                // handled together with other routes.
+                Err(ServeError::NotFound) => Err(RouterError::Match(MatchError)),
+                Err(ServeError::MethodNotAllowed) => Err(RouterError::NotAllowed(MethodNotAllowed(Box::new(vec![
+                    Method::GET,
+                    Method::HEAD,
+                ])))),
+                Err(ServeError::Io(io)) => Err(RouterError::Service(Error::from(io))),
+                Err(e) => {
+                    let (parts, _) = e.into_response().into_parts();
                     let mut res = ctx.into_response(ResponseBody::none());
-                    *res.status_mut() = StatusCode::NOT_MODIFIED;
+                    *res.status_mut() = parts.status;
+                    res.headers_mut().extend(parts.headers);
                     Ok(res)
                 }
-                Err(e) => Err(match e {
-                    ServeError::NotFound => RouterError::Match(MatchError),
-                    ServeError::MethodNotAllowed => {
-                        RouterError::NotAllowed(MethodNotAllowed(Box::new(vec![Method::GET, Method::HEAD])))
-                    }
-                    ServeError::Io(io) => RouterError::Service(Error::from(io)),
-                    _ => RouterError::Service(Error::from(ErrorStatus::bad_request())),
-                }),
             }
         }
     }
@@ -1,10 +1,120 @@
 //! utilities for testing web application
 
-use core::{future::poll_fn, pin::pin};
+use core::{convert::Infallible, future::poll_fn, pin::pin};
 
 use futures_core::stream::Stream;
 
-use crate::service::pipeline::PipelineE;
+use crate::{
+    body::RequestBody,
+    bytes::Bytes,
+    http::{
+        header::{HeaderName, HeaderValue},
+        Method, Uri, WebRequest, WebResponse,
+    },
+    service::{pipeline::PipelineE, Service},
+};
+
+/// in process test client for calling application service directly without binding to socket.
+///
+/// # Examples
+/// ```rust
+/// # use xitca_web::{handler::handler_service, http::{header::CONTENT_TYPE, StatusCode}, test::{collect_string_body, TestClient}, App};
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let app = App::new().at("/", handler_service(|body: String| async move { body }));
+///
+/// // build application service once and reuse it for multiple requests.
+/// let client = TestClient::new(app.finish()).await.unwrap();
+///
+/// let res = client
+///     .post("/")
+///     .header(CONTENT_TYPE, "text/plain".parse().unwrap())
+///     .body("hello")
+///     .send()
+///     .await;
+///
+/// assert_eq!(res.status(), StatusCode::OK);
+/// assert_eq!(collect_string_body(res.into_body()).await.unwrap(), "hello");
+/// # }
+/// ```
+pub struct TestClient<S> {
+    service: S,
+}
+
+impl<S> TestClient<S> {
+    /// construct test client from an application service builder. (the output of [`App::finish`] for example)
+    ///
+    /// [`App::finish`]: crate::App::finish
+    pub async fn new<F>(builder: F) -> Result<Self, F::Error>
+    where
+        F: Service<Response = S>,
+    {
+        builder.call(()).await.map(|service| Self { service })
+    }
+
+    /// start a GET request to given path.
+    pub fn get(&self, path: &str) -> TestRequest<'_, S> {
+        self.request(Method::GET, path)
+    }
+
+    /// start a POST request to given path.
+    pub fn post(&self, path: &str) -> TestRequest<'_, S> {
+        self.request(Method::POST, path)
+    }
+
+    /// start a PUT request to given path.
+    pub fn put(&self, path: &str) -> TestRequest<'_, S> {
+        self.request(Method::PUT, path)
+    }
+
+    /// start a DELETE request to given path.
+    pub fn delete(&self, path: &str) -> TestRequest<'_, S> {
+        self.request(Method::DELETE, path)
+    }
+
+    /// start a request with given method and path.
+    ///
+    /// # Panics
+    /// when path is not a valid [`Uri`].
+    pub fn request(&self, method: Method, path: &str) -> TestRequest<'_, S> {
+        let mut req = WebRequest::default();
+        *req.method_mut() = method;
+        *req.uri_mut() = Uri::try_from(path).expect("path must be valid Uri");
+        TestRequest { client: self, req }
+    }
+}
+
+/// request builder produced by [`TestClient`].
+pub struct TestRequest<'a, S> {
+    client: &'a TestClient<S>,
+    req: WebRequest,
+}
+
+impl<S> TestRequest<'_, S> {
+    /// append a header to request.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.req.headers_mut().append(name, value);
+        self
+    }
+
+    /// set request body.
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        let body = RequestBody::from(body.into());
+        self.req = self.req.map(|ext| ext.replace_body(body).0);
+        self
+    }
+
+    /// send request to application service and wait for response.
+    pub async fn send<B>(self) -> WebResponse<B>
+    where
+        S: Service<WebRequest, Response = WebResponse<B>, Error = Infallible>,
+    {
+        match self.client.service.call(self.req).await {
+            Ok(res) => res,
+            Err(e) => match e {},
+        }
+    }
+}
 
 /// Collect request or response body to Vec.
 pub async fn collect_body<B, T, E>(body: B) -> Result<Vec<u8>, E>
@@ -34,3 +144,44 @@ where
     let body = collect_body(body).await.map_err(CollectStringError::Second)?;
     String::from_utf8(body).map_err(CollectStringError::First)
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        handler::{
+            handler_service,
+            header::{self, HeaderRef},
+        },
+        http::{header::HOST, StatusCode},
+        App,
+    };
+
+    use super::*;
+
+    async fn host(host: HeaderRef<'_, { header::HOST }>) -> String {
+        host.to_str().unwrap().to_owned()
+    }
+
+    #[tokio::test]
+    async fn test_client() {
+        let app = App::new()
+            .at("/", handler_service(host))
+            .at("/echo", handler_service(|body: String| async move { body }));
+
+        let client = TestClient::new(app.finish()).await.unwrap();
+
+        let res = client
+            .get("/")
+            .header(HOST, HeaderValue::from_static("localhost"))
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(collect_string_body(res.into_body()).await.unwrap(), "localhost");
+
+        let res = client.post("/echo").body("hello").send().await;
+        assert_eq!(collect_body(res.into_body()).await.unwrap(), b"hello");
+
+        let res = client.get("/nowhere").send().await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}